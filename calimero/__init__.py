@@ -13,24 +13,180 @@ __email__ = "team@calimero.network"
 from calimero_client_py import (
     create_connection,
     create_client,
+    connect,
+    load_config,
+    discover_nodes,
     ConnectionInfo,
     Client,
+    Context,
     JwtToken,
     ClientError,
+    ErrorCode,
+    CalimeroError,
+    AuthenticationError,
+    TokenExpiredError,
+    NodeUnreachableError,
+    RpcError,
+    StorageError,
+    InternalError,
+    ResponseTooLargeError,
+    ResultTypeError,
+    AbiArgumentError,
+    RequestTooLargeError,
+    DangerousOperationError,
     AuthMode,
+    LoginState,
+    Capability,
+    CallbackHandle,
     get_token_cache_path,
     get_token_cache_dir,
+    migrate_token_cache,
+    storage_doctor,
+    verify_cache,
+    hash_blob,
+    verify_blob,
+    verify_audit_log,
+    clear_auth_metadata_cache,
+    export_tokens,
+    import_tokens,
+    import_cli_credentials,
+    export_cli_credentials,
+    snapshot,
+    restore,
+    encode_invitation,
+    decode_invitation,
+    verify_membership_proof,
+    set_entropy_source,
+    set_log_level,
+    features,
+    convert_result,
+    FeatureNotBuiltError,
+    SqliteView,
+    CdcExporter,
+    BulkResult,
+    TemporaryContext,
+    TaskGroup,
+    DevDeployer,
+    ContextSession,
+    NodeAddress,
+    NodeHealth,
+    NodeInfo,
+    Peers,
+    ClientStorage,
+    MemoryStorage,
+    TokenCache,
+    EventSchemaRegistry,
+    ApplicationEvent,
+    Subscription,
+    ReplicaSet,
+    ClientPool,
+    Config,
+    RetryConfig,
+    ArgEncoding,
+    TimeoutScope,
+    TokenRefresher,
+    WebhookServer,
+    Signer,
+    Paginator,
+    AbiMethod,
+    Query,
+    CalimeroWarning,
+    InsecureConfigWarning,
+    TokenExpiryWarning,
+    CorruptTokenCacheWarning,
+    SlowConsumerWarning,
+    DeprecatedEndpointWarning,
 )
+from .blocking import BlockingClient
+from .selftest import self_test
 
 # Re-export main types
 __all__ = [
     "create_connection",
     "create_client",
+    "connect",
+    "load_config",
+    "discover_nodes",
     "ConnectionInfo",
     "Client",
+    "BlockingClient",
+    "Context",
     "JwtToken",
     "ClientError",
+    "ErrorCode",
+    "CalimeroError",
+    "AuthenticationError",
+    "TokenExpiredError",
+    "NodeUnreachableError",
+    "RpcError",
+    "StorageError",
+    "InternalError",
+    "ResponseTooLargeError",
+    "ResultTypeError",
+    "AbiArgumentError",
+    "RequestTooLargeError",
+    "DangerousOperationError",
     "AuthMode",
+    "LoginState",
+    "Capability",
+    "CallbackHandle",
     "get_token_cache_path",
     "get_token_cache_dir",
+    "migrate_token_cache",
+    "storage_doctor",
+    "verify_cache",
+    "hash_blob",
+    "verify_blob",
+    "verify_audit_log",
+    "clear_auth_metadata_cache",
+    "export_tokens",
+    "import_tokens",
+    "import_cli_credentials",
+    "export_cli_credentials",
+    "snapshot",
+    "restore",
+    "encode_invitation",
+    "decode_invitation",
+    "verify_membership_proof",
+    "set_entropy_source",
+    "set_log_level",
+    "features",
+    "convert_result",
+    "FeatureNotBuiltError",
+    "SqliteView",
+    "CdcExporter",
+    "BulkResult",
+    "TemporaryContext",
+    "TaskGroup",
+    "DevDeployer",
+    "ContextSession",
+    "NodeAddress",
+    "NodeHealth",
+    "NodeInfo",
+    "Peers",
+    "ClientStorage",
+    "MemoryStorage",
+    "TokenCache",
+    "EventSchemaRegistry",
+    "ApplicationEvent",
+    "Subscription",
+    "ReplicaSet",
+    "ClientPool",
+    "Config",
+    "RetryConfig",
+    "ArgEncoding",
+    "TimeoutScope",
+    "TokenRefresher",
+    "WebhookServer",
+    "Signer",
+    "Paginator",
+    "AbiMethod",
+    "Query",
+    "CalimeroWarning",
+    "InsecureConfigWarning",
+    "TokenExpiryWarning",
+    "CorruptTokenCacheWarning",
+    "SlowConsumerWarning",
+    "DeprecatedEndpointWarning",
+    "self_test",
 ]
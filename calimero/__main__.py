@@ -0,0 +1,41 @@
+"""
+``python -m calimero``: interactive REPL with a pre-configured client.
+"""
+
+import argparse
+
+from calimero_client_py import create_client, create_connection
+
+from calimero.repl import start
+
+
+def main() -> None:
+    parser = argparse.ArgumentParser(
+        prog="python -m calimero",
+        description="Interactive Calimero REPL",
+    )
+    parser.add_argument(
+        "--base-url",
+        default="http://localhost:2528",
+        help="Node API URL (default: http://localhost:2528)",
+    )
+    parser.add_argument(
+        "--node-name",
+        default=None,
+        help="Node name used to look up cached tokens",
+    )
+    parser.add_argument(
+        "--anonymous",
+        action="store_true",
+        help="Connect without tokens (nodes with auth disabled)",
+    )
+    args = parser.parse_args()
+
+    connection = create_connection(
+        api_url=args.base_url, node_name=args.node_name, anonymous=args.anonymous
+    )
+    start(create_client(connection), connection, url=args.base_url)
+
+
+if __name__ == "__main__":
+    main()
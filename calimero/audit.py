@@ -0,0 +1,180 @@
+"""
+Permission audits for contexts.
+
+:func:`audit_permissions` gathers everything the node reports about who can
+act on a context — the context's identities, the owning group's members with
+their roles and capabilities, and client keys — and flags anomalies worth a
+look in a periodic security review::
+
+    from calimero.audit import audit_permissions
+
+    report = audit_permissions(client, context_id)
+    for anomaly in report["anomalies"]:
+        print(anomaly["kind"], anomaly["subject"], anomaly["message"])
+
+The report is plain JSON-compatible data, so it can be diffed between runs
+or fed to CI. Lookups that fail are recorded under ``errors`` instead of
+aborting the audit.
+"""
+
+import time
+from typing import Any, Dict, Iterable, List, Optional
+
+_KEY_FIELDS = ("publicKey", "public_key", "identity", "memberId", "member_id", "id")
+
+
+def _unwrap(value: Any) -> Any:
+    while isinstance(value, dict) and set(value) == {"data"}:
+        value = value["data"]
+    return value
+
+
+def _records(value: Any, keys: Iterable[str]) -> List[Any]:
+    value = _unwrap(value)
+    if isinstance(value, list):
+        return value
+    if isinstance(value, dict):
+        for key in keys:
+            if isinstance(value.get(key), list):
+                return value[key]
+    return []
+
+
+def _field(record: Any, names: Iterable[str]) -> Any:
+    if isinstance(record, dict):
+        for name in names:
+            if record.get(name) is not None:
+                return record[name]
+    return None
+
+
+def _key_of(record: Any) -> Optional[str]:
+    if isinstance(record, str):
+        return record
+    key = _field(record, _KEY_FIELDS)
+    return str(key) if key is not None else None
+
+
+def _anomaly(kind: str, subject: Optional[str], message: str) -> Dict[str, Any]:
+    return {"kind": kind, "subject": subject, "message": message}
+
+
+def find_anomalies(
+    members: List[Dict[str, Any]],
+    context_identities: List[str],
+    client_keys: List[Dict[str, Any]],
+    has_group: bool,
+) -> List[Dict[str, Any]]:
+    """Flag suspicious combinations in an audit's collected data."""
+    anomalies = []
+    identities = set(context_identities)
+    member_keys = {m["publicKey"] for m in members}
+    admins = [m for m in members if (m.get("role") or "").lower() == "admin"]
+
+    if has_group and not admins:
+        anomalies.append(
+            _anomaly("no_admin", None, "The context's group has no admin member")
+        )
+    for admin in admins:
+        if admin["publicKey"] not in identities:
+            anomalies.append(
+                _anomaly(
+                    "orphaned_admin",
+                    admin["publicKey"],
+                    "Admin key has no identity in the context (left, or never joined)",
+                )
+            )
+    if has_group:
+        for identity in sorted(identities - member_keys):
+            anomalies.append(
+                _anomaly(
+                    "identity_outside_group",
+                    identity,
+                    "Context identity is not a member of the owning group",
+                )
+            )
+    for key in client_keys:
+        owner = _field(key, ("identity", "publicKey", "public_key"))
+        if owner is not None and str(owner) not in identities:
+            anomalies.append(
+                _anomaly(
+                    "orphaned_client_key",
+                    str(owner),
+                    "Client key is bound to an identity that is not in the context",
+                )
+            )
+    return anomalies
+
+
+def audit_permissions(
+    client: Any, context_id: str, group_id: Optional[str] = None
+) -> Dict[str, Any]:
+    """Structured permissions report for ``context_id``.
+
+    ``group_id`` defaults to the group the node reports for the context.
+    Nodes expose no API for listing outstanding invitations, so
+    ``pendingInvitations`` is always ``None``.
+    """
+    errors: List[Dict[str, str]] = []
+
+    def attempt(what: str, call, default):
+        try:
+            return call()
+        except Exception as e:
+            errors.append({"lookup": what, "error": str(e)})
+            return default
+
+    context = _unwrap(attempt("context", lambda: client.get_context(context_id), {}))
+    if group_id is None:
+        group_id = _field(context, ("groupId", "group_id"))
+
+    identities_raw = attempt(
+        "context_identities", lambda: client.get_context_identities(context_id), []
+    )
+    context_identities = sorted(
+        filter(None, map(_key_of, _records(identities_raw, ("identities",))))
+    )
+
+    keys_raw = attempt(
+        "client_keys", lambda: client.get_context_client_keys(context_id), []
+    )
+    client_keys = _records(keys_raw, ("clientKeys", "client_keys", "keys"))
+
+    members: List[Dict[str, Any]] = []
+    if group_id:
+        listed = attempt(
+            "group_members", lambda: client.list_group_members(group_id), []
+        )
+        for record in _records(listed, ("members",)):
+            key = _key_of(record)
+            if key is None:
+                continue
+            capabilities = _unwrap(
+                attempt(
+                    f"capabilities:{key}",
+                    lambda: client.get_member_capabilities(group_id, key),
+                    None,
+                )
+            )
+            members.append(
+                {
+                    "publicKey": key,
+                    "role": _field(record, ("role",)),
+                    "capabilities": capabilities,
+                    "inContext": key in context_identities,
+                }
+            )
+
+    return {
+        "contextId": context_id,
+        "groupId": group_id,
+        "generatedAt": int(time.time()),
+        "members": members,
+        "contextIdentities": context_identities,
+        "clientKeys": client_keys,
+        "pendingInvitations": None,
+        "anomalies": find_anomalies(
+            members, context_identities, client_keys, bool(group_id)
+        ),
+        "errors": errors,
+    }
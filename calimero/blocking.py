@@ -0,0 +1,96 @@
+"""
+Synchronous client for code without an event loop.
+
+:class:`BlockingClient` owns a :class:`Client` — and with it a dedicated
+tokio runtime — and exposes only its blocking surface: every call runs on
+that runtime and returns once it completes, so plain scripts, Jupyter cells
+and Django views call it directly::
+
+    from calimero import BlockingClient, create_connection
+
+    with BlockingClient(create_connection("http://localhost:2528")) as client:
+        result = client.execute_function(context_id, "get", '{"key": "a"}')
+        for event in client.subscribe(context_id):
+            ...
+
+The awaitable methods of :class:`Client` are left out; each has a blocking
+twin (``client.execute_async`` raises ``AttributeError`` naming it):
+
+- ``execute_async`` → ``execute_function``
+- ``session(...).call_async`` → ``session(...).call``
+- ``task_group().run_async`` → ``task_group().run``
+- ``async for`` over paginators and subscriptions → ``for``
+
+Calls made from a thread running an asyncio event loop raise
+``RuntimeError`` instead of stalling the loop; async code uses
+:class:`Client` and its awaitable methods.
+"""
+
+import asyncio
+import functools
+from typing import Any, Dict
+
+from calimero_client_py import create_client
+
+#: Blocking twin of each awaitable ``Client`` method.
+ASYNC_TWINS: Dict[str, str] = {"execute_async": "execute_function"}
+
+
+def _ensure_no_running_loop(name: str) -> None:
+    try:
+        asyncio.get_running_loop()
+    except RuntimeError:
+        return
+    raise RuntimeError(
+        f"BlockingClient.{name}() blocks and would stall the running event loop; "
+        "use Client and its awaitable methods from async code"
+    )
+
+
+class BlockingClient:
+    """A :class:`Client` restricted to calls that block until they complete."""
+
+    def __init__(self, connection: Any, **client_options: Any) -> None:
+        self._client = create_client(connection, **client_options)
+
+    @property
+    def client(self) -> Any:
+        """The underlying :class:`Client`."""
+        return self._client
+
+    def __getattr__(self, name: str) -> Any:
+        if name.startswith("_"):
+            raise AttributeError(name)
+        if name.endswith("_async"):
+            twin = ASYNC_TWINS.get(name, name[: -len("_async")])
+            raise AttributeError(
+                f"BlockingClient has no {name}(); call {twin}() instead"
+            )
+        attr = getattr(self._client, name)
+        if not callable(attr):
+            return attr
+
+        @functools.wraps(attr)
+        def call(*args: Any, **kwargs: Any) -> Any:
+            _ensure_no_running_loop(name)
+            return attr(*args, **kwargs)
+
+        return call
+
+    def __dir__(self):
+        names = set(super().__dir__()) | set(dir(self._client))
+        return sorted(name for name in names if not name.endswith("_async"))
+
+    def close(self) -> None:
+        """Close the underlying client and shut its runtime down."""
+        self._client.close()
+
+    def __enter__(self) -> "BlockingClient":
+        return self
+
+    def __exit__(self, *exc: Any) -> bool:
+        self.close()
+        return False
+
+    def __repr__(self) -> str:
+        return f"BlockingClient({self._client!r})"
@@ -4,16 +4,84 @@ Command Line Interface for Calimero Client Python Library.
 
 import argparse
 import asyncio
+import json
+import os
 import sys
-from typing import Optional
+from typing import Any, Optional
 
 from calimero import __version__
-from calimero_client_py import create_connection, create_client, AuthMode
+from calimero.completion import (
+    SHELLS,
+    cached_node_names,
+    context_candidates,
+    generate,
+)
+from calimero.output import FORMATS, render
+from calimero.script import ScriptError, ScriptRunner, load_script
+from calimero_client_py import (
+    create_connection,
+    create_client,
+    AuthMode,
+    export_tokens,
+    get_token_cache_dir,
+    import_tokens,
+)
 
+PROG = "calimero-client-py"
 
-def main():
-    """Main CLI entry point."""
+
+def _unwrap(result: Any) -> Any:
+    """Strip the node's ``{"data": ...}`` envelope."""
+    if isinstance(result, dict) and set(result) == {"data"}:
+        return result["data"]
+    return result
+
+
+def _listing(result: Any, key: str) -> dict:
+    """Normalize a list response to the stable ``{key: [...]}`` schema."""
+    data = _unwrap(result)
+    if isinstance(data, dict) and isinstance(data.get(key), list):
+        return {key: data[key]}
+    if isinstance(data, list):
+        return {key: data}
+    return {key: []}
+
+
+def cmd_list_contexts(client, args) -> Any:
+    return _listing(client.list_contexts(), "contexts")
+
+
+def _resolve_context(client, context: str) -> str:
+    """Accept a context id or a context alias."""
+    try:
+        client.get_context(context)
+        return context
+    except ValueError:
+        resolved = _unwrap(client.resolve_context_alias(context))
+        if isinstance(resolved, dict) and resolved.get("value"):
+            return resolved["value"]
+        raise
+
+
+def cmd_get_context(client, args) -> Any:
+    context_id = _resolve_context(client, args.context_id)
+    return {"context": _unwrap(client.get_context(context_id))}
+
+
+def cmd_list_applications(client, args) -> Any:
+    return _listing(client.list_applications(), "apps")
+
+
+COMMANDS = {
+    "list-contexts": cmd_list_contexts,
+    "get-context": cmd_get_context,
+    "list-applications": cmd_list_applications,
+}
+
+
+def build_parser() -> argparse.ArgumentParser:
     parser = argparse.ArgumentParser(
+        prog=PROG,
         description="Calimero Client Python Library CLI",
         formatter_class=argparse.RawDescriptionHelpFormatter,
         epilog="""
@@ -21,6 +89,9 @@ Examples:
   calimero-client-py --help
   calimero-client-py --version
   calimero-client-py --base-url https://test.merod.dev.p2p.aws.calimero.network list-contexts
+  calimero-client-py --output json list-contexts | jq '.contexts[].id'
+  BUNDLE_PASS=... calimero-client-py tokens export my-node --passphrase-env BUNDLE_PASS -f bundle.json
+  source <(calimero-client-py completion bash)
         """,
     )
 
@@ -43,33 +114,216 @@ Examples:
         help="Authentication mode (default: none)",
     )
 
+    parser.add_argument(
+        "--node-name",
+        default=None,
+        help="Node name used to look up cached tokens",
+    )
+
+    parser.add_argument(
+        "--output",
+        "-o",
+        choices=FORMATS,
+        default="table",
+        help="Output format (default: table); json and yaml are stable for scripting",
+    )
+
     subparsers = parser.add_subparsers(dest="command", help="Available commands")
 
     # List contexts command
-    list_parser = subparsers.add_parser("list-contexts", help="List all contexts")
+    subparsers.add_parser("list-contexts", help="List all contexts")
 
-    # Add more commands here as needed
+    # Get context command
+    get_context_parser = subparsers.add_parser("get-context", help="Show one context")
+    get_context_parser.add_argument("context_id", help="Context ID or alias")
 
-    args = parser.parse_args()
+    # List applications command
+    subparsers.add_parser("list-applications", help="List installed applications")
+
+    # Batch script command
+    run_parser = subparsers.add_parser(
+        "run", help="Run a declarative YAML/JSON script of steps"
+    )
+    run_parser.add_argument("script", help="Path to the script file")
+    run_parser.add_argument(
+        "--var",
+        action="append",
+        default=[],
+        metavar="NAME=VALUE",
+        help="Set a script variable (overrides the script's vars)",
+    )
+
+    # Token bundles
+    tokens_parser = subparsers.add_parser(
+        "tokens", help="Export or import cached tokens as a portable bundle"
+    )
+    tokens_commands = tokens_parser.add_subparsers(dest="tokens_command", required=True)
+    export_parser = tokens_commands.add_parser(
+        "export", help="Write a token bundle for the given nodes"
+    )
+    export_parser.add_argument(
+        "nodes",
+        nargs="*",
+        metavar="NODE",
+        help="Node name (default: every cached node)",
+    )
+    export_parser.add_argument(
+        "--include-refresh",
+        action="store_true",
+        help="Include refresh tokens (the bundle then grants lasting access)",
+    )
+    export_parser.add_argument(
+        "--file", "-f", default=None, help="Write to FILE (mode 0600) instead of stdout"
+    )
+    import_parser = tokens_commands.add_parser(
+        "import", help="Save the tokens of a bundle into the token cache"
+    )
+    import_parser.add_argument("file", help="Bundle file, or - for stdin")
+    for sub in (export_parser, import_parser):
+        sub.add_argument(
+            "--passphrase-env",
+            default=None,
+            metavar="VAR",
+            help="Encrypt/decrypt with the passphrase in environment variable VAR",
+        )
+
+    # Shell completion script
+    completion_parser = subparsers.add_parser(
+        "completion", help="Print a shell completion script"
+    )
+    completion_parser.add_argument("shell", choices=SHELLS)
+
+    # Dynamic completion values (used by the completion scripts)
+    complete_parser = subparsers.add_parser("__complete")
+    complete_parser.add_argument("kind", choices=["node-names", "contexts"])
+
+    return parser
+
+
+def completion_script(shell: str) -> str:
+    """Completion script for the CLI as currently defined."""
+    options = {
+        "--base-url": [],
+        "--node-name": [],
+        "--auth-mode": ["none", "required"],
+        "--output": list(FORMATS),
+    }
+    return generate(shell, PROG, [*COMMANDS, "run", "tokens", "completion"], options)
+
+
+def _complete(args) -> None:
+    """Print one completion candidate per line; never fails loudly."""
+    try:
+        if args.kind == "node-names":
+            candidates = cached_node_names(get_token_cache_dir())
+        else:
+            connection = create_connection(
+                api_url=args.base_url, node_name=args.node_name
+            )
+            client = create_client(connection)
+            candidates = context_candidates(
+                client.list_contexts(), client.list_context_aliases()
+            )
+    except Exception:
+        return
+    for candidate in candidates:
+        print(candidate)
+
+
+def run_script(args) -> None:
+    """Run a script file; the connection comes from its `login` step."""
+    variables = {}
+    for item in args.var:
+        name, sep, value = item.partition("=")
+        if not sep:
+            print(f"Invalid --var '{item}' (expected NAME=VALUE)", file=sys.stderr)
+            sys.exit(2)
+        variables[name] = value
+
+    try:
+        script = load_script(args.script)
+    except Exception as e:
+        print(f"Error loading {args.script}: {e}", file=sys.stderr)
+        sys.exit(2)
+
+    runner = ScriptRunner(variables=variables)
+    try:
+        results = runner.run(script)
+    except ScriptError as e:
+        print(f"Script failed at {e}", file=sys.stderr)
+        sys.exit(1)
+    print(render({"steps": results}, args.output))
+
+
+def tokens_command(args) -> None:
+    """Export or import a token bundle with the default token storage."""
+    passphrase = None
+    if args.passphrase_env:
+        passphrase = os.environ.get(args.passphrase_env)
+        if not passphrase:
+            print(f"Environment variable {args.passphrase_env} is not set", file=sys.stderr)
+            sys.exit(2)
+
+    try:
+        if args.tokens_command == "export":
+            bundle = export_tokens(
+                args.nodes or None,
+                include_refresh=args.include_refresh,
+                passphrase=passphrase,
+                path=args.file,
+            )
+            if args.file is None:
+                print(bundle)
+                return
+            exported = json.loads(bundle)["nodes"]
+            print(render({"exported": exported, "file": args.file}, args.output))
+            return
+
+        if args.file == "-":
+            imported = import_tokens(sys.stdin.read(), passphrase=passphrase)
+        else:
+            imported = import_tokens(path=args.file, passphrase=passphrase)
+    except Exception as e:
+        print(f"Error running tokens {args.tokens_command}: {e}", file=sys.stderr)
+        sys.exit(1)
+    print(render({"imported": imported}, args.output))
+
+
+def main(argv: Optional[list] = None):
+    """Main CLI entry point."""
+    parser = build_parser()
+    args = parser.parse_args(argv)
 
     if not args.command:
         parser.print_help()
         return
 
+    if args.command == "completion":
+        print(completion_script(args.shell), end="")
+        return
+    if args.command == "__complete":
+        _complete(args)
+        return
+    if args.command == "run":
+        run_script(args)
+        return
+    if args.command == "tokens":
+        tokens_command(args)
+        return
+
     # Create connection
-    connection = create_connection(api_url=args.base_url, node_name=None)
+    connection = create_connection(api_url=args.base_url, node_name=args.node_name)
 
     # Create client
     client = create_client(connection)
 
     # Execute command
-    if args.command == "list-contexts":
-        try:
-            contexts = client.list_contexts()
-            print(f"Found {contexts} contexts:")
-        except Exception as e:
-            print(f"Error listing contexts: {e}", file=sys.stderr)
-            sys.exit(1)
+    try:
+        result = COMMANDS[args.command](client, args)
+    except Exception as e:
+        print(f"Error running {args.command}: {e}", file=sys.stderr)
+        sys.exit(1)
+    print(render(result, args.output))
 
 
 def cli():
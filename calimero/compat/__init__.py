@@ -0,0 +1,6 @@
+"""
+Compatibility layers for code not yet moved to the typed API.
+
+- ``calimero.compat.http`` - ``requests``-style ``get``/``post`` against node
+  endpoints with the cached session's token attached
+"""
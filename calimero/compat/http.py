@@ -0,0 +1,208 @@
+"""
+``requests``-style access to node endpoints with the cached session's token.
+
+Hand-rolled scripts build URLs and ``Authorization`` headers themselves::
+
+    requests.get(f"{url}/admin-api/contexts", headers={"Authorization": token})
+
+Swapping the import is enough to have the token loaded from the token cache
+(and refreshed when it is about to expire) instead, so such scripts can move
+to the typed ``Client`` methods one call at a time::
+
+    from calimero.compat import http as requests
+
+    requests.get(f"{url}/admin-api/contexts", node_name="node1").json()
+
+Requests go through a ``Client`` of the URL's origin (one per origin and
+``node_name``, created on first use, or the ``client`` passed in), so they
+share its connection pool, hooks, metrics and request timeout. The token is
+only ever sent to that origin. Unlike ``requests``, an unreachable node
+raises the client's exceptions; error statuses are returned as responses.
+"""
+
+import json as _json
+import threading
+from typing import Any, Dict, Mapping, Optional, Tuple, Union
+from urllib.parse import urlencode, urlsplit
+
+from calimero_client_py import create_client, create_connection
+
+_clients: Dict[Tuple[str, Optional[str]], Any] = {}
+_lock = threading.Lock()
+
+
+class HTTPError(Exception):
+    """Raised by :meth:`Response.raise_for_status`; ``response`` is attached."""
+
+    def __init__(self, message: str, response: "Response"):
+        super().__init__(message)
+        self.response = response
+
+
+class _Headers(dict):
+    """Response headers, looked up without regard to case."""
+
+    def __init__(self, headers: Mapping[str, str]):
+        super().__init__((k.lower(), v) for k, v in headers.items())
+
+    def __getitem__(self, name: str) -> str:
+        return super().__getitem__(name.lower())
+
+    def __contains__(self, name: object) -> bool:
+        return isinstance(name, str) and super().__contains__(name.lower())
+
+    def get(self, name: str, default: Any = None) -> Any:
+        return super().get(name.lower(), default)
+
+
+class Response:
+    """The subset of ``requests.Response`` scripts rely on."""
+
+    def __init__(self, raw: Dict[str, Any]):
+        self.status_code: int = raw["status"]
+        self.url: str = raw["url"]
+        self.headers = _Headers(raw["headers"])
+        self.content: bytes = raw["body"]
+
+    @property
+    def ok(self) -> bool:
+        return self.status_code < 400
+
+    @property
+    def text(self) -> str:
+        return self.content.decode("utf-8", errors="replace")
+
+    def json(self) -> Any:
+        return _json.loads(self.content)
+
+    def raise_for_status(self) -> None:
+        if not self.ok:
+            kind = "Client" if self.status_code < 500 else "Server"
+            raise HTTPError(
+                f"{self.status_code} {kind} Error for url: {self.url}", self
+            )
+
+    def __repr__(self) -> str:
+        return f"<Response [{self.status_code}]>"
+
+
+def _origin(url: str) -> str:
+    parts = urlsplit(url)
+    if not parts.scheme or not parts.netloc:
+        raise ValueError(f"{url!r} is not an absolute URL; pass client= for paths")
+    return f"{parts.scheme}://{parts.netloc}"
+
+
+def _client_for(url: str, node_name: Optional[str]) -> Any:
+    key = (_origin(url), node_name)
+    with _lock:
+        if key not in _clients:
+            connection = create_connection(api_url=key[0], node_name=node_name)
+            _clients[key] = create_client(connection)
+        return _clients[key]
+
+
+def request(
+    method: str,
+    url: str,
+    *,
+    params: Optional[Mapping[str, Any]] = None,
+    data: Union[None, bytes, str, Mapping[str, Any]] = None,
+    json: Any = None,
+    headers: Optional[Mapping[str, str]] = None,
+    node_name: Optional[str] = None,
+    client: Any = None,
+    timeout: Optional[float] = None,
+) -> Response:
+    """Send ``method url`` like ``requests.request`` and return the response.
+
+    ``url`` may be a path (``"admin-api/contexts"``) when ``client`` is given.
+    ``node_name`` names the cached session to authenticate with. ``timeout``
+    is accepted for compatibility; the client's ``request_timeout`` applies.
+    """
+    del timeout
+    if client is None:
+        client = _client_for(url, node_name)
+    headers = dict(headers or {})
+    names = {name.lower() for name in headers}
+    if params:
+        url += ("&" if "?" in url else "?") + urlencode(params, doseq=True)
+    body: Optional[bytes] = None
+    if json is not None:
+        body = _json.dumps(json).encode()
+        if "content-type" not in names:
+            headers["Content-Type"] = "application/json"
+    elif isinstance(data, Mapping):
+        body = urlencode(data, doseq=True).encode()
+        if "content-type" not in names:
+            headers["Content-Type"] = "application/x-www-form-urlencoded"
+    elif isinstance(data, str):
+        body = data.encode()
+    elif data is not None:
+        body = bytes(data)
+    return Response(client.raw_request(method, url, body, headers))
+
+
+def get(
+    url: str, params: Optional[Mapping[str, Any]] = None, **kwargs: Any
+) -> Response:
+    """``GET url``; see :func:`request`."""
+    return request("GET", url, params=params, **kwargs)
+
+
+def post(url: str, data: Any = None, json: Any = None, **kwargs: Any) -> Response:
+    """``POST url``; see :func:`request`."""
+    return request("POST", url, data=data, json=json, **kwargs)
+
+
+def put(url: str, data: Any = None, **kwargs: Any) -> Response:
+    """``PUT url``; see :func:`request`."""
+    return request("PUT", url, data=data, **kwargs)
+
+
+def delete(url: str, **kwargs: Any) -> Response:
+    """``DELETE url``; see :func:`request`."""
+    return request("DELETE", url, **kwargs)
+
+
+class Session:
+    """Bound to one client, like a ``requests.Session`` with a base URL.
+
+    ``Session(client)`` reuses a client; ``Session(api_url, node_name)``
+    creates one. Relative URLs are resolved against the node.
+    """
+
+    def __init__(
+        self,
+        client_or_url: Any,
+        node_name: Optional[str] = None,
+    ):
+        if isinstance(client_or_url, str):
+            client_or_url = _client_for(client_or_url, node_name)
+        self.client = client_or_url
+        self.headers: Dict[str, str] = {}
+
+    def request(self, method: str, url: str, **kwargs: Any) -> Response:
+        headers = {**self.headers, **(kwargs.pop("headers", None) or {})}
+        return request(method, url, headers=headers, client=self.client, **kwargs)
+
+    def get(self, url: str, params: Optional[Mapping[str, Any]] = None, **kwargs):
+        return self.request("GET", url, params=params, **kwargs)
+
+    def post(self, url: str, data: Any = None, json: Any = None, **kwargs):
+        return self.request("POST", url, data=data, json=json, **kwargs)
+
+    def put(self, url: str, data: Any = None, **kwargs: Any) -> Response:
+        return self.request("PUT", url, data=data, **kwargs)
+
+    def delete(self, url: str, **kwargs: Any) -> Response:
+        return self.request("DELETE", url, **kwargs)
+
+    def close(self) -> None:
+        """Nothing to release; the client's pool outlives the session."""
+
+    def __enter__(self) -> "Session":
+        return self
+
+    def __exit__(self, *exc: Any) -> None:
+        self.close()
@@ -0,0 +1,186 @@
+"""
+Shell completion for the ``calimero-client-py`` CLI.
+
+``calimero-client-py completion bash|zsh|fish`` prints a script to source
+(or install) in the shell. The scripts complete subcommands and option
+values statically, and call back into the CLI's hidden ``__complete``
+command for dynamic values:
+
+- ``node-names`` — nodes with cached tokens in ``~/.merobox/auth_cache``
+- ``contexts`` — context ids and context aliases on the selected node
+
+Install, e.g.::
+
+    calimero-client-py completion bash > ~/.local/share/bash-completion/completions/calimero-client-py
+    calimero-client-py completion zsh > "${fpath[1]}/_calimero-client-py"
+    calimero-client-py completion fish > ~/.config/fish/completions/calimero-client-py.fish
+"""
+
+import os
+import re
+from typing import Any, Dict, Iterable, List, Sequence
+
+SHELLS = ("bash", "zsh", "fish")
+
+# `{slug}-{sha256[:12]}.json`, see `derive_token_filename` in src/cache.rs.
+_TOKEN_FILE = re.compile(r"^(?P<slug>.+)-[0-9a-f]{12}\.json$")
+
+
+def cached_node_names(cache_dir: str) -> List[str]:
+    """Node names recoverable from token cache filenames.
+
+    Filenames hold a sanitized slug of the node name, which equals the name
+    for the usual ``[A-Za-z0-9._-]`` names.
+    """
+    try:
+        entries = os.listdir(cache_dir)
+    except OSError:
+        return []
+    names = {m.group("slug") for m in map(_TOKEN_FILE.match, entries) if m}
+    return sorted(names)
+
+
+def _records(value: Any, key: str) -> List[Any]:
+    if isinstance(value, dict):
+        if isinstance(value.get(key), list):
+            return value[key]
+        if set(value) == {"data"}:
+            return _records(value["data"], key)
+    return value if isinstance(value, list) else []
+
+
+def context_candidates(contexts: Any, aliases: Any) -> List[str]:
+    """Context ids and aliases from ``list_contexts`` / ``list_context_aliases``."""
+    ids = [c["id"] for c in _records(contexts, "contexts") if isinstance(c, dict)]
+    data = aliases.get("data", aliases) if isinstance(aliases, dict) else aliases
+    if isinstance(data, dict):
+        alias_names = [str(k) for k in data]
+    else:
+        alias_names = [
+            a["alias"] for a in data or [] if isinstance(a, dict) and "alias" in a
+        ]
+    return sorted(set(ids) | set(alias_names))
+
+
+def _bash(prog: str, commands: Sequence[str], options: Dict[str, Sequence[str]]) -> str:
+    func = "_" + re.sub(r"\W", "_", prog)
+    value_cases = "\n".join(
+        f'        {opt}) COMPREPLY=($(compgen -W "{" ".join(values)}" -- "$cur")); return;;'
+        for opt, values in options.items()
+        if values
+    )
+    return f"""# bash completion for {prog}
+{func}() {{
+    local cur prev globals i
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    globals=()
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${{COMP_WORDS[i]}}" in
+            --base-url|--node-name) globals+=("${{COMP_WORDS[i]}}" "${{COMP_WORDS[i+1]}}");;
+        esac
+    done
+    case "$prev" in
+{value_cases}
+        --node-name) COMPREPLY=($(compgen -W "$({prog} __complete node-names 2>/dev/null)" -- "$cur")); return;;
+        get-context) COMPREPLY=($(compgen -W "$({prog} "${{globals[@]}}" __complete contexts 2>/dev/null)" -- "$cur")); return;;
+        completion) COMPREPLY=($(compgen -W "{" ".join(SHELLS)}" -- "$cur")); return;;
+    esac
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "{" ".join(options)} --help --version" -- "$cur"))
+    else
+        COMPREPLY=($(compgen -W "{" ".join(commands)}" -- "$cur"))
+    fi
+}}
+complete -F {func} {prog}
+"""
+
+
+def _zsh(prog: str, commands: Sequence[str], options: Dict[str, Sequence[str]]) -> str:
+    func = "_" + re.sub(r"\W", "_", prog)
+    specs = []
+    for opt, values in options.items():
+        if opt == "--node-name":
+            action = "_calimero_node_names"
+        elif values:
+            action = "(" + " ".join(values) + ")"
+        else:
+            action = "_default"
+        specs.append(f"        '{opt}=[{opt[2:]}]:value:{action}' \\")
+    return f"""#compdef {prog}
+_calimero_node_names() {{
+    local -a names
+    names=(${{(f)"$({prog} __complete node-names 2>/dev/null)"}})
+    compadd -a names
+}}
+_calimero_contexts() {{
+    local -a ids
+    ids=(${{(f)"$({prog} "${{_calimero_globals[@]}}" __complete contexts 2>/dev/null)"}})
+    compadd -a ids
+}}
+{func}() {{
+    local state i
+    local -a _calimero_globals
+    # Collect connection options before _arguments shifts $words.
+    for ((i = 2; i < CURRENT; i++)); do
+        case "${{words[i]}}" in
+            --base-url|--node-name) _calimero_globals+=("${{words[i]}}" "${{words[i+1]}}");;
+        esac
+    done
+    _arguments -C \\
+{chr(10).join(specs)}
+        '1:command:({" ".join(commands)})' \\
+        '*::arg:->args'
+    case "$state" in
+        args)
+            case "${{words[1]}}" in
+                get-context) _calimero_contexts;;
+                completion) compadd {" ".join(SHELLS)};;
+            esac;;
+    esac
+}}
+{func} "$@"
+"""
+
+
+def _fish(prog: str, commands: Sequence[str], options: Dict[str, Sequence[str]]) -> str:
+    lines = [f"# fish completion for {prog}", f"complete -c {prog} -f"]
+    no_cmd = f"not __fish_seen_subcommand_from {' '.join(commands)}"
+    for command in commands:
+        lines.append(f"complete -c {prog} -n '{no_cmd}' -a {command}")
+    for opt, values in options.items():
+        if opt == "--node-name":
+            args = f"-x -a '({prog} __complete node-names 2>/dev/null)'"
+        elif values:
+            args = f"-x -a '{' '.join(values)}'"
+        else:
+            args = "-r"
+        lines.append(f"complete -c {prog} -l {opt[2:]} {args}")
+    lines.append(
+        f"complete -c {prog} -n '__fish_seen_subcommand_from get-context' "
+        f"-a '({prog} __complete contexts 2>/dev/null)'"
+    )
+    lines.append(
+        f"complete -c {prog} -n '__fish_seen_subcommand_from completion' "
+        f"-a '{' '.join(SHELLS)}'"
+    )
+    return "\n".join(lines) + "\n"
+
+
+def generate(
+    shell: str,
+    prog: str,
+    commands: Iterable[str],
+    options: Dict[str, Sequence[str]],
+) -> str:
+    """Completion script for ``shell``.
+
+    ``options`` maps each global ``--option`` to its fixed choices (empty for
+    free-form values).
+    """
+    generators = {"bash": _bash, "zsh": _zsh, "fish": _fish}
+    if shell not in generators:
+        raise ValueError(
+            f"Unsupported shell '{shell}' (expected one of {', '.join(SHELLS)})"
+        )
+    return generators[shell](prog, list(commands), dict(options))
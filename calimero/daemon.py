@@ -0,0 +1,312 @@
+"""
+Local daemon that keeps warm clients for short-lived scripts.
+
+Every new Python process pays for importing the bindings, loading tokens,
+refreshing them when they are close to expiry and opening a TLS connection
+before its first call. Workflows made of dozens of small scripts pay that
+each time. The daemon holds one client per node for all of them; scripts
+talk to it over a Unix socket and get a proxy with the ``Client`` methods::
+
+    from calimero.daemon import connect
+
+    client = connect("http://localhost:2528", node_name="node1")
+    client.execute_function(context_id, "get", '{"key": "a"}')
+
+``connect`` starts the daemon (``python -m calimero.daemon``) when nothing
+answers on the socket, and it exits after ``idle_timeout`` seconds without
+requests. The socket lives in ``$CALIMERO_DAEMON_SOCKET``, or in the user's
+runtime directory, and is only accessible to its owner. Arguments and
+results travel as JSON, so only methods taking and returning JSON values are
+available; objects with a ``to_dict()`` come back as that dict. Exceptions
+raised in the daemon are re-raised as :class:`DaemonError` (or as the
+built-in exception type, for ``ValueError``, ``TypeError`` and friends).
+
+Unix only: there are no Unix sockets to listen on elsewhere.
+"""
+
+import argparse
+import builtins
+import json
+import os
+import socket
+import socketserver
+import subprocess
+import sys
+import tempfile
+import threading
+import time
+from typing import Any, Dict, Optional, Tuple
+
+DEFAULT_IDLE_TIMEOUT = 600.0
+SOCKET_ENV = "CALIMERO_DAEMON_SOCKET"
+
+# How long ``connect`` waits for a daemon it started to listen.
+_SPAWN_WAIT = 10.0
+_BUILTIN_ERRORS = {"ValueError", "TypeError", "KeyError", "AttributeError"}
+
+NodeKey = Tuple[str, Optional[str], bool]
+
+
+class DaemonError(RuntimeError):
+    """A call failed inside the daemon; ``error_type`` names the exception."""
+
+    def __init__(self, error_type: str, message: str):
+        super().__init__(f"{error_type}: {message}")
+        self.error_type = error_type
+
+
+def default_socket_path() -> str:
+    """``$CALIMERO_DAEMON_SOCKET``, else a per-user socket in the runtime dir."""
+    if os.environ.get(SOCKET_ENV):
+        return os.environ[SOCKET_ENV]
+    runtime_dir = os.environ.get("XDG_RUNTIME_DIR") or tempfile.gettempdir()
+    return os.path.join(runtime_dir, f"calimero-client-{os.getuid()}.sock")
+
+
+def _jsonable(value: Any) -> Any:
+    """``value`` as JSON: ``to_dict()`` of binding objects, lists for tuples."""
+    if value is None or isinstance(value, (bool, int, float, str)):
+        return value
+    if isinstance(value, dict):
+        return {str(k): _jsonable(v) for k, v in value.items()}
+    if isinstance(value, (list, tuple)):
+        return [_jsonable(v) for v in value]
+    if isinstance(value, bytes):
+        return list(value)
+    if hasattr(value, "to_dict"):
+        return _jsonable(value.to_dict())
+    raise TypeError(f"{type(value).__name__} results cannot leave the daemon")
+
+
+def _send(stream, message: Dict[str, Any]) -> None:
+    stream.write(json.dumps(message).encode() + b"\n")
+    stream.flush()
+
+
+class _Handler(socketserver.StreamRequestHandler):
+    def handle(self) -> None:
+        for line in self.rfile:
+            self.server.touch()
+            try:
+                request = json.loads(line)
+                result = self.server.dispatch(request)
+                reply = {"ok": True, "result": _jsonable(result)}
+            except Exception as e:  # reported to the caller
+                reply = {
+                    "ok": False,
+                    "error": {"type": type(e).__name__, "message": str(e)},
+                }
+            _send(self.wfile, reply)
+
+
+class _Server(socketserver.ThreadingMixIn, socketserver.UnixStreamServer):
+    daemon_threads = True
+
+    def __init__(self, path: str, idle_timeout: float):
+        self.clients: Dict[NodeKey, Any] = {}
+        self.lock = threading.Lock()
+        self.idle_timeout = idle_timeout
+        self.last_request = time.monotonic()
+        old_umask = os.umask(0o077)
+        try:
+            super().__init__(path, _Handler)
+        finally:
+            os.umask(old_umask)
+
+    def touch(self) -> None:
+        self.last_request = time.monotonic()
+
+    def client(self, node: Dict[str, Any]) -> Any:
+        from calimero_client_py import create_client, create_connection
+
+        key = (node["api_url"], node.get("node_name"), bool(node.get("anonymous")))
+        with self.lock:
+            if key not in self.clients:
+                connection = create_connection(
+                    api_url=key[0], node_name=key[1], anonymous=key[2]
+                )
+                self.clients[key] = create_client(connection)
+            return self.clients[key]
+
+    def dispatch(self, request: Dict[str, Any]) -> Any:
+        method = request.get("method", "")
+        if method == "__ping__":
+            return {"pid": os.getpid(), "clients": len(self.clients)}
+        if method == "__shutdown__":
+            threading.Thread(target=self.shutdown, daemon=True).start()
+            return True
+        if not method or method.startswith("_"):
+            raise AttributeError(f"no client method {method!r}")
+        target = getattr(self.client(request["node"]), method)
+        if not callable(target):
+            return target
+        return target(*request.get("args", []), **request.get("kwargs", {}))
+
+    def watch_idle(self) -> None:
+        while True:
+            time.sleep(min(self.idle_timeout, 5.0))
+            if time.monotonic() - self.last_request >= self.idle_timeout:
+                self.shutdown()
+                return
+
+
+def serve(
+    socket_path: Optional[str] = None, idle_timeout: float = DEFAULT_IDLE_TIMEOUT
+) -> None:
+    """Run the daemon in this process until it is idle for ``idle_timeout``."""
+    path = socket_path or default_socket_path()
+    if os.path.exists(path):
+        if _ping(path):
+            raise RuntimeError(f"a daemon is already listening on {path}")
+        os.unlink(path)
+    server = _Server(path, idle_timeout)
+    threading.Thread(target=server.watch_idle, daemon=True).start()
+    try:
+        server.serve_forever()
+    finally:
+        server.server_close()
+        if os.path.exists(path):
+            os.unlink(path)
+
+
+def _request(path: str, message: Dict[str, Any], timeout: Optional[float]) -> Any:
+    with socket.socket(socket.AF_UNIX, socket.SOCK_STREAM) as sock:
+        sock.settimeout(timeout)
+        sock.connect(path)
+        stream = sock.makefile("rwb")
+        _send(stream, message)
+        line = stream.readline()
+    if not line:
+        raise DaemonError("ConnectionError", "the daemon closed the connection")
+    reply = json.loads(line)
+    if reply["ok"]:
+        return reply["result"]
+    error = reply["error"]
+    if error["type"] in _BUILTIN_ERRORS:
+        raise getattr(builtins, error["type"])(error["message"])
+    raise DaemonError(error["type"], error["message"])
+
+
+def _ping(path: str) -> bool:
+    try:
+        _request(path, {"method": "__ping__"}, timeout=1.0)
+        return True
+    except (OSError, DaemonError):
+        return False
+
+
+def _spawn(path: str, idle_timeout: float) -> None:
+    subprocess.Popen(
+        [
+            sys.executable,
+            "-m",
+            "calimero.daemon",
+            "--socket",
+            path,
+            "--idle-timeout",
+            str(idle_timeout),
+        ],
+        stdin=subprocess.DEVNULL,
+        stdout=subprocess.DEVNULL,
+        stderr=subprocess.DEVNULL,
+        start_new_session=True,
+    )
+    deadline = time.monotonic() + _SPAWN_WAIT
+    while time.monotonic() < deadline:
+        if _ping(path):
+            return
+        time.sleep(0.05)
+    raise DaemonError("TimeoutError", f"the daemon did not start listening on {path}")
+
+
+class DaemonClient:
+    """Proxy forwarding ``Client`` method calls to the daemon."""
+
+    def __init__(
+        self,
+        api_url: str,
+        node_name: Optional[str] = None,
+        anonymous: bool = False,
+        socket_path: Optional[str] = None,
+        timeout: Optional[float] = None,
+    ):
+        self._node = {
+            "api_url": api_url,
+            "node_name": node_name,
+            "anonymous": anonymous,
+        }
+        self._path = socket_path or default_socket_path()
+        self._timeout = timeout
+
+    def __getattr__(self, name: str) -> Any:
+        if name.startswith("_"):
+            raise AttributeError(name)
+
+        def call(*args: Any, **kwargs: Any) -> Any:
+            message = {
+                "node": self._node,
+                "method": name,
+                "args": args,
+                "kwargs": kwargs,
+            }
+            return _request(self._path, message, self._timeout)
+
+        call.__name__ = name
+        return call
+
+    def __repr__(self) -> str:
+        return f"DaemonClient({self._node['api_url']!r}, socket={self._path!r})"
+
+
+def connect(
+    api_url: str,
+    node_name: Optional[str] = None,
+    anonymous: bool = False,
+    socket_path: Optional[str] = None,
+    spawn: bool = True,
+    idle_timeout: float = DEFAULT_IDLE_TIMEOUT,
+    timeout: Optional[float] = None,
+) -> DaemonClient:
+    """Proxy for the daemon's client of ``api_url``, starting the daemon if needed.
+
+    With ``spawn=False`` a missing daemon raises :class:`DaemonError`.
+    ``timeout`` bounds each call in seconds (default: none).
+    """
+    path = socket_path or default_socket_path()
+    if not _ping(path):
+        if not spawn:
+            raise DaemonError("ConnectionError", f"no daemon is listening on {path}")
+        _spawn(path, idle_timeout)
+    return DaemonClient(api_url, node_name, anonymous, path, timeout)
+
+
+def stop(socket_path: Optional[str] = None) -> bool:
+    """Ask the daemon to exit; ``False`` when none was running."""
+    path = socket_path or default_socket_path()
+    if not _ping(path):
+        return False
+    _request(path, {"method": "__shutdown__"}, timeout=5.0)
+    return True
+
+
+def main() -> None:
+    parser = argparse.ArgumentParser(
+        prog="python -m calimero.daemon",
+        description="Keep warm Calimero clients for short-lived scripts",
+    )
+    parser.add_argument("--socket", default=None, help="Unix socket path")
+    parser.add_argument(
+        "--idle-timeout",
+        type=float,
+        default=DEFAULT_IDLE_TIMEOUT,
+        help=f"Exit after this many idle seconds (default: {DEFAULT_IDLE_TIMEOUT:g})",
+    )
+    parser.add_argument("--stop", action="store_true", help="Stop a running daemon")
+    args = parser.parse_args()
+    if args.stop:
+        sys.exit(0 if stop(args.socket) else 1)
+    serve(args.socket, args.idle_timeout)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,142 @@
+"""
+Output formatting for the CLI: ``json``, ``table``, and ``yaml``.
+
+Every command produces plain JSON-compatible data; these renderers turn it
+into text. JSON and YAML are lossless and meant for pipelines (keys keep
+their order, so the schema is whatever the command returns). Tables are for
+humans: a list of records becomes one row per record, a single record
+becomes key/value rows.
+"""
+
+import json
+import re
+from typing import Any, Callable, Dict, List
+
+FORMATS = ("json", "table", "yaml")
+
+_PLAIN_SCALAR = re.compile(r"^[A-Za-z0-9_./-][A-Za-z0-9_./ :-]*$")
+_YAML_KEYWORDS = {"true", "false", "null", "yes", "no", "on", "off", "~"}
+
+
+def render_json(data: Any) -> str:
+    return json.dumps(data, indent=2)
+
+
+def _yaml_scalar(value: Any) -> str:
+    if value is None:
+        return "null"
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    if isinstance(value, (int, float)):
+        return json.dumps(value)
+    text = str(value)
+    if (
+        _PLAIN_SCALAR.match(text)
+        and not text.endswith(" ")
+        and text.lower() not in _YAML_KEYWORDS
+        and not _looks_numeric(text)
+        and ": " not in text
+    ):
+        return text
+    # JSON strings are valid YAML double-quoted scalars.
+    return json.dumps(text)
+
+
+def _looks_numeric(text: str) -> bool:
+    try:
+        float(text)
+    except ValueError:
+        return False
+    return True
+
+
+def _yaml_lines(value: Any, indent: int) -> List[str]:
+    pad = "  " * indent
+    if isinstance(value, dict):
+        if not value:
+            return [pad + "{}"]
+        lines = []
+        for key, item in value.items():
+            label = pad + _yaml_scalar(str(key)) + ":"
+            if isinstance(item, (dict, list)) and item:
+                lines.append(label)
+                lines.extend(_yaml_lines(item, indent + 1))
+            else:
+                lines.append(label + " " + _yaml_lines(item, 0)[0])
+        return lines
+    if isinstance(value, list):
+        if not value:
+            return [pad + "[]"]
+        lines = []
+        for item in value:
+            nested = _yaml_lines(item, indent + 1)
+            # Hoist the first nested line onto the "- " marker.
+            lines.append(pad + "- " + nested[0].lstrip())
+            lines.extend(nested[1:])
+        return lines
+    return [pad + _yaml_scalar(value)]
+
+
+def render_yaml(data: Any) -> str:
+    return "\n".join(_yaml_lines(data, 0))
+
+
+def _cell(value: Any) -> str:
+    if value is None:
+        return ""
+    if isinstance(value, (dict, list)):
+        return json.dumps(value, separators=(",", ":"))
+    return str(value)
+
+
+def _records(data: Any) -> Any:
+    """The list (or single record) a table should show."""
+    if isinstance(data, dict) and len(data) == 1:
+        (only,) = data.values()
+        if isinstance(only, (list, dict)):
+            return only
+    return data
+
+
+def render_table(data: Any) -> str:
+    data = _records(data)
+    if isinstance(data, dict):
+        rows = [[str(k), _cell(v)] for k, v in data.items()]
+        headers = ["KEY", "VALUE"]
+    elif isinstance(data, list) and all(isinstance(r, dict) for r in data):
+        columns: List[str] = []
+        for record in data:
+            columns.extend(k for k in record if k not in columns)
+        headers = [c.upper() for c in columns]
+        rows = [[_cell(r.get(c)) for c in columns] for r in data]
+    elif isinstance(data, list):
+        headers = ["VALUE"]
+        rows = [[_cell(v)] for v in data]
+    else:
+        return _cell(data)
+
+    if not rows:
+        return "(no results)"
+    widths = [max(len(h), *(len(r[i]) for r in rows)) for i, h in enumerate(headers)]
+    fmt = "  ".join("{:<%d}" % w for w in widths)
+    lines = [fmt.format(*headers), fmt.format(*("-" * w for w in widths))]
+    lines.extend(fmt.format(*r) for r in rows)
+    return "\n".join(line.rstrip() for line in lines)
+
+
+RENDERERS: Dict[str, Callable[[Any], str]] = {
+    "json": render_json,
+    "table": render_table,
+    "yaml": render_yaml,
+}
+
+
+def render(data: Any, fmt: str) -> str:
+    """Render ``data`` in one of :data:`FORMATS`."""
+    try:
+        renderer = RENDERERS[fmt]
+    except KeyError:
+        raise ValueError(
+            f"Unknown output format '{fmt}' (expected one of {', '.join(FORMATS)})"
+        ) from None
+    return renderer(data)
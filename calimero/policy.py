@@ -0,0 +1,193 @@
+"""
+Declarative permission policies for contexts.
+
+A policy names roles, the capabilities each role grants and the members
+holding it; :func:`apply_policy` compares that with the capabilities the
+context's group currently gives each member and sets only what differs::
+
+    from calimero.policy import apply_policy
+
+    policy = {
+        "capabilities": {"create_context": 1, "invite": 2, "manage": 4},
+        "roles": {
+            "admin": {"capabilities": ["create_context", "invite", "manage"],
+                      "members": ["<admin key>"]},
+            "writer": {"capabilities": ["create_context"],
+                       "members": ["<key a>", "<key b>"]},
+        },
+        "default": 0,
+    }
+    report = apply_policy(client, context_id, policy, dry_run=True)
+
+Capabilities are the group's capability bits: plain integers, or names
+defined in the policy's ``capabilities`` table. A member in several roles
+gets the union of their capabilities. With ``default`` set, group members
+the policy does not list are set to it (``0`` revokes everything); without
+it they are left alone. Policies may also be given as a YAML or JSON file
+path (YAML needs PyYAML).
+"""
+
+import json
+from typing import Any, Dict, List, Optional, Union
+
+from calimero.audit import _field, _key_of, _records, _unwrap
+
+
+class PolicyError(ValueError):
+    """The policy document is malformed."""
+
+
+def load_policy(source: Union[str, Dict[str, Any]]) -> Dict[str, Any]:
+    """Policy from a dict, or a JSON/YAML file path."""
+    if isinstance(source, dict):
+        return source
+    with open(source, "r", encoding="utf-8") as f:
+        text = f.read()
+    if source.endswith(".json"):
+        document = json.loads(text)
+    else:
+        try:
+            import yaml
+        except ImportError:
+            raise RuntimeError(
+                "YAML policies need PyYAML (pip install pyyaml); or use a .json policy"
+            ) from None
+        document = yaml.safe_load(text)
+    if not isinstance(document, dict):
+        raise PolicyError(f"{source}: a policy must be a mapping")
+    return document
+
+
+def _mask(value: Any, names: Dict[str, int], role: str) -> int:
+    if isinstance(value, bool):
+        raise PolicyError(f"role '{role}': capabilities must be names or integers")
+    if isinstance(value, int):
+        if value < 0:
+            raise PolicyError(f"role '{role}': capabilities cannot be negative")
+        return value
+    if isinstance(value, str):
+        if value not in names:
+            raise PolicyError(f"role '{role}': unknown capability '{value}'")
+        return names[value]
+    if isinstance(value, list):
+        mask = 0
+        for item in value:
+            mask |= _mask(item, names, role)
+        return mask
+    raise PolicyError(f"role '{role}': capabilities must be names or integers")
+
+
+def desired_capabilities(policy: Dict[str, Any]) -> Dict[str, int]:
+    """Capabilities the policy gives each listed member."""
+    names = policy.get("capabilities") or {}
+    if not isinstance(names, dict) or not all(
+        isinstance(bit, int) and not isinstance(bit, bool) for bit in names.values()
+    ):
+        raise PolicyError("'capabilities' must map names to integers")
+    roles = policy.get("roles")
+    if not isinstance(roles, dict) or not roles:
+        raise PolicyError("a policy needs a non-empty 'roles' mapping")
+
+    desired: Dict[str, int] = {}
+    for role, spec in roles.items():
+        if not isinstance(spec, dict):
+            raise PolicyError(f"role '{role}' must be a mapping")
+        mask = _mask(spec.get("capabilities", 0), names, role)
+        members = spec.get("members") or []
+        if not isinstance(members, list):
+            raise PolicyError(f"role '{role}': 'members' must be a list")
+        for member in members:
+            desired[str(member)] = desired.get(str(member), 0) | mask
+    return desired
+
+
+def plan(policy: Dict[str, Any], current: Dict[str, Optional[int]]) -> Dict[str, Any]:
+    """Changes that bring ``current`` (group member -> capabilities, ``None``
+    when unknown) in line with ``policy``."""
+    desired = desired_capabilities(policy)
+    default = policy.get("default")
+    if default is not None:
+        default = _mask(default, policy.get("capabilities") or {}, "default")
+
+    changes: List[Dict[str, Any]] = []
+    unchanged: List[str] = []
+    for member in sorted(current):
+        target = desired.get(member, default)
+        if target is None:
+            continue
+        have = current[member]
+        if have == target:
+            unchanged.append(member)
+            continue
+        changes.append(
+            {
+                "member": member,
+                "from": have,
+                "to": target,
+                "granted": target & ~(have or 0),
+                "revoked": (have or 0) & ~target,
+            }
+        )
+    return {
+        "changes": changes,
+        "unchanged": unchanged,
+        "notMembers": sorted(set(desired) - set(current)),
+    }
+
+
+def _capabilities_of(value: Any) -> Optional[int]:
+    value = _unwrap(value)
+    if isinstance(value, dict):
+        value = _field(value, ("capabilities",))
+    return value if isinstance(value, int) and not isinstance(value, bool) else None
+
+
+def apply_policy(
+    client: Any,
+    context_id: str,
+    policy: Union[str, Dict[str, Any]],
+    group_id: Optional[str] = None,
+    dry_run: bool = False,
+) -> Dict[str, Any]:
+    """Bring the capabilities of ``context_id``'s group members in line
+    with ``policy`` and report what changed.
+
+    ``group_id`` defaults to the group the node reports for the context.
+    Policy members that are not in the group are listed under
+    ``notMembers`` and skipped; add them to the group first. Changes that
+    fail are listed under ``errors``; the others are still applied. With
+    ``dry_run=True`` nothing is set.
+    """
+    policy = load_policy(policy)
+    desired_capabilities(policy)  # reject a malformed policy before any call
+    if group_id is None:
+        context = _unwrap(client.get_context(context_id))
+        group_id = _field(context, ("groupId", "group_id"))
+        if not group_id:
+            raise PolicyError(
+                f"context {context_id} has no group to apply a policy to"
+            )
+
+    current: Dict[str, Optional[int]] = {}
+    for record in _records(client.list_group_members(group_id), ("members",)):
+        key = _key_of(record)
+        if key is not None:
+            current[key] = _capabilities_of(
+                client.get_member_capabilities(group_id, key)
+            )
+
+    report = plan(policy, current)
+    errors: List[Dict[str, str]] = []
+    if not dry_run:
+        for change in report["changes"]:
+            try:
+                client.set_member_capabilities(group_id, change["member"], change["to"])
+            except Exception as e:
+                errors.append({"member": change["member"], "error": str(e)})
+    return {
+        "contextId": context_id,
+        "groupId": group_id,
+        "dryRun": dry_run,
+        **report,
+        "errors": errors,
+    }
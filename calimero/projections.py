@@ -0,0 +1,189 @@
+"""
+Event projections: fold context events into local state with a reducer.
+
+A :class:`Projection` owns a user-supplied reducer ``reducer(state, event) ->
+state`` and feeds it events from any source — a list of historical events,
+a live subscription, or both in sequence. Progress is checkpointed to
+``~/.merobox/projections/<name>.json`` so a restarted process resumes where
+it left off instead of re-applying (or missing) events.
+
+Example::
+
+    from calimero.projections import Projection
+
+    def count_by_kind(state, event):
+        kind = event.get("kind", "unknown")
+        state[kind] = state.get(kind, 0) + 1
+        return state
+
+    projection = Projection("votes", count_by_kind, initial_state={})
+    projection.replay(historical_events)   # already-applied prefix is skipped
+    for event in live_events:
+        projection.apply(event)
+    print(projection.state)
+"""
+
+import json
+import os
+import tempfile
+from typing import Any, AsyncIterable, Callable, Iterable, Optional
+
+from calimero_client_py import SqliteView, get_token_cache_dir
+
+Reducer = Callable[[Any, Any], Any]
+
+
+def default_checkpoint_dir() -> str:
+    """Directory holding projection checkpoints (``~/.merobox/projections``)."""
+    return os.path.join(os.path.dirname(get_token_cache_dir()), "projections")
+
+
+class Projection:
+    """A reducer-driven, checkpointed view over a stream of events.
+
+    ``position`` counts the events applied so far. Replaying a history from
+    its beginning skips the first ``position`` events, so sources that always
+    start at the oldest event can be fed repeatedly without double-applying.
+
+    State is checkpointed as JSON every ``checkpoint_every`` events (and on
+    :meth:`checkpoint`); pass ``encode``/``decode`` for states that are not
+    JSON-serializable. ``checkpoint_dir=None`` keeps the projection purely
+    in memory.
+    """
+
+    def __init__(
+        self,
+        name: str,
+        reducer: Reducer,
+        initial_state: Any = None,
+        checkpoint_dir: Optional[str] = "",
+        checkpoint_every: int = 100,
+        encode: Callable[[Any], Any] = lambda state: state,
+        decode: Callable[[Any], Any] = lambda data: data,
+    ):
+        if checkpoint_every < 1:
+            raise ValueError("checkpoint_every must be at least 1")
+        self.name = name
+        self.reducer = reducer
+        self.checkpoint_every = checkpoint_every
+        self._encode = encode
+        self._decode = decode
+        self._since_checkpoint = 0
+
+        if checkpoint_dir == "":
+            checkpoint_dir = default_checkpoint_dir()
+        self.checkpoint_path = (
+            os.path.join(checkpoint_dir, f"{name}.json") if checkpoint_dir else None
+        )
+
+        self.state = initial_state
+        self.position = 0
+        self._load()
+
+    def _load(self) -> None:
+        if not self.checkpoint_path or not os.path.exists(self.checkpoint_path):
+            return
+        with open(self.checkpoint_path, "r", encoding="utf-8") as f:
+            data = json.load(f)
+        self.state = self._decode(data["state"])
+        self.position = int(data["position"])
+
+    def checkpoint(self) -> None:
+        """Persist the current state and position atomically."""
+        self._since_checkpoint = 0
+        if not self.checkpoint_path:
+            return
+        directory = os.path.dirname(self.checkpoint_path)
+        os.makedirs(directory, mode=0o700, exist_ok=True)
+        payload = {"state": self._encode(self.state), "position": self.position}
+        fd, temp_path = tempfile.mkstemp(dir=directory, suffix=".tmp")
+        try:
+            with os.fdopen(fd, "w", encoding="utf-8") as f:
+                json.dump(payload, f)
+            os.replace(temp_path, self.checkpoint_path)
+        except BaseException:
+            if os.path.exists(temp_path):
+                os.remove(temp_path)
+            raise
+
+    def apply(self, event: Any) -> Any:
+        """Apply one new event and return the updated state."""
+        self.state = self.reducer(self.state, event)
+        self.position += 1
+        self._since_checkpoint += 1
+        if self._since_checkpoint >= self.checkpoint_every:
+            self.checkpoint()
+        return self.state
+
+    def replay(self, events: Iterable[Any]) -> Any:
+        """Apply a history that starts at the first event ever emitted.
+
+        Events before ``position`` have already been folded in and are
+        skipped. A checkpoint is written at the end.
+        """
+        for index, event in enumerate(events):
+            if index < self.position:
+                continue
+            self.apply(event)
+        self.checkpoint()
+        return self.state
+
+    def follow(self, events: Iterable[Any]) -> Any:
+        """Apply every event from a live source (no prefix skipping)."""
+        try:
+            for event in events:
+                self.apply(event)
+        finally:
+            self.checkpoint()
+        return self.state
+
+    async def follow_async(self, events: AsyncIterable[Any]) -> Any:
+        """Async counterpart of :meth:`follow` for async event sources."""
+        try:
+            async for event in events:
+                self.apply(event)
+        finally:
+            self.checkpoint()
+        return self.state
+
+    def reset(self, initial_state: Any = None) -> None:
+        """Drop all progress and the on-disk checkpoint."""
+        self.state = initial_state
+        self.position = 0
+        self._since_checkpoint = 0
+        if self.checkpoint_path and os.path.exists(self.checkpoint_path):
+            os.remove(self.checkpoint_path)
+
+    def __repr__(self) -> str:
+        return f"Projection(name={self.name!r}, position={self.position})"
+
+
+def sqlite_projection(
+    name: str,
+    db_path: str,
+    event_kinds: Optional[Iterable[str]] = None,
+    key_prefixes: Optional[Iterable[str]] = None,
+    checkpoint_dir: Optional[str] = "",
+) -> Projection:
+    """A projection that mirrors events into a SQLite database at ``db_path``.
+
+    The returned projection's ``view`` attribute is the underlying
+    :class:`SqliteView`; query it with ``projection.view.query(sql, params)``,
+    which only runs read-only statements. Its state is the number of events
+    the view accepted. Every event is checkpointed so a restart never
+    re-inserts rows already in the database.
+    """
+    view = SqliteView(
+        db_path,
+        event_kinds=list(event_kinds) if event_kinds is not None else None,
+        key_prefixes=list(key_prefixes) if key_prefixes is not None else None,
+    )
+
+    def mirror(count: int, event: Any) -> int:
+        return count + 1 if view.apply(event) else count
+
+    projection = Projection(
+        name, mirror, initial_state=0, checkpoint_dir=checkpoint_dir, checkpoint_every=1
+    )
+    projection.view = view
+    return projection
@@ -0,0 +1,199 @@
+"""
+Interactive REPL for poking at a node during development.
+
+Started with ``python -m calimero``. The session namespace holds:
+
+- ``client`` / ``connection`` — a configured client for the node
+- ``contexts`` — mapping of context id to :class:`ContextProxy`; keys
+  tab-complete in IPython, ``contexts.ids()`` lists them elsewhere
+- ``pp`` — pretty-printer used for results
+
+Context proxies expose the application's methods as attributes (completed
+from the ABI when the node provides one)::
+
+    >>> ctx = contexts["<context-id>"]
+    >>> ctx.get(key="greeting")
+"""
+
+import json
+import pprint
+import sys
+from typing import Any, Dict, Iterable, List, Optional
+
+BANNER = """Calimero REPL — connected to {url}
+  client, connection    configured client and connection
+  contexts[id].method() call a context method (tab-completes from the ABI)
+  contexts.ids()        list context ids
+  pp(obj)               pretty-print"""
+
+
+def pp(obj: Any) -> None:
+    """Pretty-print a result (JSON-ish values are indented)."""
+    pprint.pprint(obj, sort_dicts=False, width=100)
+
+
+def _displayhook(value: Any) -> None:
+    if value is None:
+        return
+    import builtins
+
+    builtins._ = value
+    pp(value)
+
+
+def _find_list(value: Any, key: str) -> Optional[list]:
+    """Depth-first search for a list stored under ``key``."""
+    if isinstance(value, dict):
+        if isinstance(value.get(key), list):
+            return value[key]
+        for child in value.values():
+            found = _find_list(child, key)
+            if found is not None:
+                return found
+    return None
+
+
+def _find_value(value: Any, keys: Iterable[str]) -> Any:
+    keys = tuple(keys)
+    if isinstance(value, dict):
+        for key in keys:
+            if key in value:
+                return value[key]
+        for child in value.values():
+            found = _find_value(child, keys)
+            if found is not None:
+                return found
+    return None
+
+
+def context_ids(listing: Any) -> List[str]:
+    """Extract context ids from a ``list_contexts()`` result."""
+    contexts = _find_list(listing, "contexts") or []
+    return [c["id"] for c in contexts if isinstance(c, dict) and "id" in c]
+
+
+def abi_methods(application: Any) -> List[str]:
+    """Method names from an application's ABI, if the node includes one.
+
+    Accepts the ``get_application()`` result; the ABI may be embedded as an
+    object or as a JSON string (e.g. in the package metadata).
+    """
+    abi = _find_value(application, ("abi",))
+    if isinstance(abi, str):
+        try:
+            abi = json.loads(abi)
+        except ValueError:
+            return []
+    methods = _find_list(abi, "methods") if isinstance(abi, dict) else None
+    return sorted(
+        m["name"] for m in methods or [] if isinstance(m, dict) and "name" in m
+    )
+
+
+class ContextProxy:
+    """Calls ``execute_function`` on one context via attribute access."""
+
+    def __init__(self, client: Any, context_id: str):
+        self._client = client
+        self._context_id = context_id
+        self._methods: Optional[List[str]] = None
+
+    def _abi_methods(self) -> List[str]:
+        if self._methods is None:
+            try:
+                if hasattr(self._client, "discover_methods"):
+                    self._methods = [
+                        m["name"] for m in self._client.discover_methods(self._context_id)
+                    ]
+                    return self._methods
+                context = self._client.get_context(self._context_id)
+                app_id = _find_value(context, ("applicationId", "application_id"))
+                self._methods = (
+                    abi_methods(self._client.get_application(app_id)) if app_id else []
+                )
+            except Exception:
+                self._methods = []
+        return self._methods
+
+    def __getattr__(self, method: str):
+        if method.startswith("_"):
+            raise AttributeError(method)
+
+        def call(**kwargs):
+            return self._client.execute_function(
+                self._context_id, method, json.dumps(kwargs)
+            )
+
+        call.__name__ = method
+        return call
+
+    def __dir__(self) -> List[str]:
+        return sorted(set(super().__dir__()) | set(self._abi_methods()))
+
+    def __repr__(self) -> str:
+        return f"ContextProxy({self._context_id!r})"
+
+
+class Contexts:
+    """Lazily-listed mapping of context id to :class:`ContextProxy`."""
+
+    def __init__(self, client: Any):
+        self._client = client
+
+    def ids(self) -> List[str]:
+        return context_ids(self._client.list_contexts())
+
+    def __getitem__(self, context_id: str) -> ContextProxy:
+        return ContextProxy(self._client, context_id)
+
+    def _ipython_key_completions_(self) -> List[str]:
+        try:
+            return self.ids()
+        except Exception:
+            return []
+
+    def __repr__(self) -> str:
+        return f"Contexts({len(self.ids())} contexts)"
+
+
+def build_namespace(client: Any, connection: Any = None) -> Dict[str, Any]:
+    """Names available in a REPL session."""
+    return {
+        "client": client,
+        "connection": connection,
+        "contexts": Contexts(client),
+        "pp": pp,
+    }
+
+
+def start(client: Any, connection: Any = None, url: str = "") -> None:
+    """Run an interactive session, preferring IPython when installed."""
+    namespace = build_namespace(client, connection)
+    banner = BANNER.format(url=url or client.get_api_url())
+    try:
+        from IPython import start_ipython
+    except ImportError:
+        start_ipython = None
+
+    if start_ipython is not None:
+        print(banner)
+        start_ipython(argv=[], user_ns=namespace)
+        return
+
+    import code
+
+    try:
+        import readline
+        import rlcompleter
+
+        readline.set_completer(rlcompleter.Completer(namespace).complete)
+        readline.parse_and_bind("tab: complete")
+    except ImportError:
+        pass
+
+    previous_hook = sys.displayhook
+    sys.displayhook = _displayhook
+    try:
+        code.interact(banner=banner, local=namespace, exitmsg="")
+    finally:
+        sys.displayhook = previous_hook
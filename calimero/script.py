@@ -0,0 +1,245 @@
+"""
+Declarative batch scripts for ``calimero-client-py run``.
+
+A script is a YAML (requires PyYAML) or JSON document::
+
+    vars:
+      app_id: 5VaSg...
+      group_id: 9pQ2...
+    steps:
+      - login:
+          url: http://localhost:2528
+          node_name: node1
+      - create_context:
+          application_id: ${app_id}
+          group_id: ${group_id}
+        save_as: created
+      - execute:
+          context_id: ${created.data.contextId}
+          method: set
+          args: {key: greeting, value: hello}
+      - execute:
+          context_id: ${created.data.contextId}
+          method: get
+          args: {key: greeting}
+        save_as: greeting
+      - assert:
+          that: ${greeting.result.output}
+          equals: hello
+
+Steps run in order and stop at the first failure. ``${name}`` (with
+optional ``.key`` / ``.0`` path segments) expands to a variable; a value that
+is exactly one reference keeps the variable's type, otherwise it is
+interpolated as text. ``save_as`` stores a step's result.
+
+Step kinds:
+
+- ``login`` — connect to ``url`` as ``node_name`` (``anonymous`` optional);
+  ``access_token``/``refresh_token`` seed the node's token cache first
+- ``create_context`` / ``execute`` — the common client calls
+- ``call`` — any client method: ``{method: list_contexts, args: {...}}``
+- ``set`` — assign variables
+- ``assert`` — ``that`` with ``equals`` / ``contains`` / ``exists``
+- ``echo`` — record a message
+"""
+
+import json
+import os
+import re
+from typing import Any, Callable, Dict, List, Optional
+
+_REF = re.compile(r"\$\{([^}]+)\}")
+
+
+class ScriptError(Exception):
+    """A step failed; ``step`` is its 1-based index."""
+
+    def __init__(self, step: int, kind: str, message: str):
+        super().__init__(f"step {step} ({kind}): {message}")
+        self.step = step
+        self.kind = kind
+
+
+def load_script(path: str) -> Dict[str, Any]:
+    """Parse a script file (JSON, or YAML when PyYAML is installed)."""
+    with open(path, "r", encoding="utf-8") as f:
+        text = f.read()
+    if path.endswith(".json"):
+        document = json.loads(text)
+    else:
+        try:
+            import yaml
+        except ImportError:
+            raise RuntimeError(
+                "YAML scripts need PyYAML (pip install pyyaml); or use a .json script"
+            ) from None
+        document = yaml.safe_load(text)
+    if not isinstance(document, dict) or not isinstance(document.get("steps"), list):
+        raise ValueError(f"{path}: a script needs a top-level 'steps' list")
+    return document
+
+
+def lookup(variables: Dict[str, Any], reference: str) -> Any:
+    """Resolve ``name.path.0.key`` against ``variables``."""
+    name, *path = reference.strip().split(".")
+    if name not in variables:
+        raise KeyError(f"undefined variable '{name}'")
+    value = variables[name]
+    for segment in path:
+        if isinstance(value, list) and segment.isdigit():
+            value = value[int(segment)]
+        elif isinstance(value, dict) and segment in value:
+            value = value[segment]
+        else:
+            raise KeyError(f"'{reference}' has no '{segment}'")
+    return value
+
+
+def substitute(value: Any, variables: Dict[str, Any]) -> Any:
+    """Expand ``${...}`` references inside strings, lists and dicts."""
+    if isinstance(value, str):
+        whole = _REF.fullmatch(value)
+        if whole:
+            return lookup(variables, whole.group(1))
+
+        def text(match):
+            found = lookup(variables, match.group(1))
+            return found if isinstance(found, str) else json.dumps(found)
+
+        return _REF.sub(text, value)
+    if isinstance(value, list):
+        return [substitute(v, variables) for v in value]
+    if isinstance(value, dict):
+        return {k: substitute(v, variables) for k, v in value.items()}
+    return value
+
+
+def _default_connect(spec: Dict[str, Any]) -> Any:
+    from calimero_client_py import create_client, create_connection, get_token_cache_path
+
+    node_name = spec.get("node_name")
+    if node_name and spec.get("access_token"):
+        path = get_token_cache_path(node_name)
+        os.makedirs(os.path.dirname(path), mode=0o700, exist_ok=True)
+        tokens = {
+            "access_token": spec["access_token"],
+            "refresh_token": spec.get("refresh_token"),
+            "expires_at": spec.get("expires_at"),
+        }
+        with open(path, "w", encoding="utf-8") as f:
+            json.dump(tokens, f)
+        os.chmod(path, 0o600)
+    connection = create_connection(
+        api_url=spec["url"],
+        node_name=node_name,
+        anonymous=bool(spec.get("anonymous", False)),
+    )
+    return create_client(connection)
+
+
+class ScriptRunner:
+    """Executes script steps against a client.
+
+    ``connect`` turns a ``login`` spec into a client (overridable for tests);
+    ``client`` may be passed to skip ``login`` entirely.
+    """
+
+    def __init__(
+        self,
+        client: Any = None,
+        variables: Optional[Dict[str, Any]] = None,
+        connect: Callable[[Dict[str, Any]], Any] = _default_connect,
+    ):
+        self.client = client
+        self.variables: Dict[str, Any] = dict(variables or {})
+        self._connect = connect
+
+    def _require_client(self) -> Any:
+        if self.client is None:
+            raise RuntimeError("no client; add a 'login' step first")
+        return self.client
+
+    def _login(self, spec):
+        self.client = self._connect(spec)
+        return {"url": spec["url"], "node_name": spec.get("node_name")}
+
+    def _create_context(self, spec):
+        params = spec.get("params")
+        if params is not None and not isinstance(params, str):
+            params = json.dumps(params)
+        return self._require_client().create_context(
+            spec["application_id"],
+            spec["group_id"],
+            params=params,
+            service_name=spec.get("service_name"),
+        )
+
+    def _execute(self, spec):
+        return self._require_client().execute_function(
+            spec["context_id"], spec["method"], json.dumps(spec.get("args", {}))
+        )
+
+    def _call(self, spec):
+        method = getattr(self._require_client(), spec["method"])
+        args = spec.get("args", {})
+        return method(*args) if isinstance(args, list) else method(**args)
+
+    def _set(self, spec):
+        self.variables.update(spec)
+        return spec
+
+    def _assert(self, spec):
+        if "that" not in spec:
+            raise ValueError("assert needs 'that'")
+        actual = spec["that"]
+        if "equals" in spec and actual != spec["equals"]:
+            raise AssertionError(f"expected {spec['equals']!r}, got {actual!r}")
+        if "contains" in spec and spec["contains"] not in actual:
+            raise AssertionError(f"{actual!r} does not contain {spec['contains']!r}")
+        if spec.get("exists") and actual is None:
+            raise AssertionError("value does not exist")
+        return True
+
+    def _echo(self, spec):
+        return spec
+
+    def run_step(self, index: int, step: Dict[str, Any]) -> Dict[str, Any]:
+        handlers = {
+            "login": self._login,
+            "create_context": self._create_context,
+            "execute": self._execute,
+            "call": self._call,
+            "set": self._set,
+            "assert": self._assert,
+            "echo": self._echo,
+        }
+        kinds = [k for k in step if k in handlers]
+        if len(kinds) != 1:
+            raise ScriptError(
+                index, "?", f"expected exactly one of {', '.join(handlers)}"
+            )
+        kind = kinds[0]
+        try:
+            spec = substitute(step[kind], self.variables)
+            result = handlers[kind](spec)
+        except ScriptError:
+            raise
+        except Exception as e:
+            raise ScriptError(index, kind, str(e)) from e
+        if step.get("save_as"):
+            self.variables[step["save_as"]] = result
+        return {
+            "step": index,
+            "kind": kind,
+            "name": step.get("name"),
+            "result": result,
+        }
+
+    def run(self, script: Dict[str, Any]) -> List[Dict[str, Any]]:
+        """Run every step; raises :class:`ScriptError` on the first failure."""
+        for name, value in (script.get("vars") or {}).items():
+            self.variables.setdefault(name, value)
+        return [
+            self.run_step(index, step)
+            for index, step in enumerate(script["steps"], start=1)
+        ]
@@ -0,0 +1,177 @@
+"""
+End-to-end smoke test of a node.
+
+:func:`self_test` walks the path a real workload takes — reach the node,
+authenticate, create a temporary context, execute a method on it, subscribe
+to its events, delete it — and reports each stage as passed, failed or
+skipped, so a new environment can be validated before anything real runs
+against it::
+
+    import calimero
+
+    report = calimero.self_test(
+        "http://localhost:2428",
+        application_id=app_id,
+        group_id=group_id,
+        method="get",
+        args='{"key": "probe"}',
+    )
+    for stage in report["stages"]:
+        print(stage["stage"], stage["status"], stage.get("error") or "")
+    assert report["ok"]
+
+Stages that need something the caller did not provide (an application and
+group for the context, a method to execute) and stages after a failure they
+depend on are ``skipped`` with the reason. The context is deleted even when
+a stage in between fails. The report is plain JSON-compatible data.
+"""
+
+import time
+from typing import Any, Callable, Dict, List, Optional
+
+STAGES = ("connect", "auth", "create_context", "execute", "subscribe", "cleanup")
+
+
+def _unwrap(value: Any) -> Any:
+    while isinstance(value, dict) and set(value) == {"data"}:
+        value = value["data"]
+    return value
+
+
+def _context_id(created: Any) -> Optional[str]:
+    data = created.get("data", created) if isinstance(created, dict) else created
+    if isinstance(data, dict):
+        return data.get("contextId") or data.get("id")
+    return None
+
+
+class _Report:
+    def __init__(self, node: Optional[str]) -> None:
+        self.node = node
+        self.stages: List[Dict[str, Any]] = []
+
+    def run(self, stage: str, call: Callable[[], Any]) -> Any:
+        """Run ``call`` as ``stage``; returns its result, or ``None`` if it raised."""
+        started = time.monotonic()
+        record: Dict[str, Any] = {"stage": stage}
+        try:
+            detail = call()
+        except Exception as e:
+            record.update(status="failed", error=f"{type(e).__name__}: {e}")
+            detail = None
+        else:
+            record.update(status="passed", detail=detail)
+        record["durationMs"] = round((time.monotonic() - started) * 1000, 1)
+        self.stages.append(record)
+        return detail if record["status"] == "passed" else None
+
+    def skip(self, stage: str, reason: str) -> None:
+        self.stages.append({"stage": stage, "status": "skipped", "reason": reason})
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "node": self.node,
+            "ok": all(s["status"] != "failed" for s in self.stages),
+            "stages": self.stages,
+        }
+
+
+def _wait_connected(client: Any, timeout: float) -> Dict[str, Any]:
+    deadline = time.monotonic() + timeout
+    while True:
+        stats = client.subscription_stats or {}
+        if stats.get("connected"):
+            return stats
+        if time.monotonic() >= deadline:
+            raise TimeoutError(f"event socket not connected after {timeout}s")
+        time.sleep(0.1)
+
+
+def self_test(
+    node_url: Optional[str] = None,
+    *,
+    node_name: Optional[str] = None,
+    client: Any = None,
+    application_id: Optional[str] = None,
+    group_id: Optional[str] = None,
+    method: Optional[str] = None,
+    args: str = "{}",
+    timeout: float = 30.0,
+) -> Dict[str, Any]:
+    """Smoke-test the node at ``node_url`` (or the one ``client`` talks to).
+
+    Returns ``{node, ok, stages: [{stage, status, detail | error | reason,
+    durationMs}]}`` with one record per stage in :data:`STAGES` order.
+    ``timeout`` bounds waiting for the node to become ready and for the
+    event socket to connect. The temporary context needs ``application_id``
+    and ``group_id``; ``execute`` needs ``method`` (called with ``args``).
+    """
+    if client is None and node_url is None:
+        raise ValueError("self_test needs a node_url or a client")
+    report = _Report(node_url)
+
+    def connect() -> Dict[str, Any]:
+        nonlocal client
+        if client is None:
+            from calimero_client_py import create_client, create_connection
+
+            client = create_client(create_connection(node_url, node_name=node_name))
+        report.node = client.get_api_url()
+        return client.wait_until_ready(timeout=timeout).to_dict()
+
+    if report.run("connect", connect) is None:
+        for stage in STAGES[1:]:
+            report.skip(stage, "node not reachable")
+        return report.to_dict()
+
+    def auth() -> Dict[str, Any]:
+        mode = client.auth_mode.value
+        contexts = _unwrap(client.list_contexts())
+        if isinstance(contexts, dict):
+            contexts = contexts.get("contexts", [])
+        return {"authMode": mode, "contexts": len(contexts or [])}
+
+    if report.run("auth", auth) is None:
+        for stage in STAGES[2:]:
+            report.skip(stage, "authentication failed")
+        return report.to_dict()
+
+    if not (application_id and group_id):
+        for stage in STAGES[2:]:
+            report.skip(stage, "no application_id and group_id given")
+        return report.to_dict()
+
+    created = report.run(
+        "create_context",
+        lambda: {
+            "contextId": _context_id(client.create_context(application_id, group_id))
+        },
+    )
+    context_id = created and created["contextId"]
+    if not context_id:
+        if created is not None:
+            report.stages[-1].update(status="failed", error="no context id in response")
+        for stage in STAGES[3:]:
+            report.skip(stage, "no context was created")
+        return report.to_dict()
+
+    try:
+        if method:
+            report.run(
+                "execute",
+                lambda: {"result": client.execute_function(context_id, method, args)},
+            )
+        else:
+            report.skip("execute", "no method given")
+
+        def subscribe() -> Dict[str, Any]:
+            subscription = client.subscribe(context_id)
+            try:
+                return _wait_connected(client, timeout)
+            finally:
+                subscription.close()
+
+        report.run("subscribe", subscribe)
+    finally:
+        report.run("cleanup", lambda: client.delete_context(context_id))
+    return report.to_dict()
@@ -0,0 +1,620 @@
+//! Method discovery from application ABIs behind `Client.discover_methods()`,
+//! and ABI-checked calls behind `Client.method()`.
+//!
+//! Applications built with an ABI carry it in their package metadata, which
+//! the node returns with `get_application` — as an object, as a JSON string,
+//! or inside metadata bytes holding JSON. [`find_abi`] digs it out and
+//! [`methods`] flattens its `methods` into
+//! `{name, params: [{name, type, optional}], returns}` records, with types
+//! rendered as short strings (`string`, `list<u64>`, `map<string, Entry>`,
+//! `Entry?`) so UIs and completion can show them as they are.
+//!
+//! [`validate_args`] checks call arguments against a method's parameters
+//! before anything is sent: unknown and missing names, and values of the
+//! wrong JSON kind (walking lists, maps, options and the ABI's record and
+//! variant types), fail with an [`AbiArgumentError`] naming the argument,
+//! instead of an execution error from inside the application. Types the
+//! checker does not know are let through for the node to judge.
+
+use std::sync::Arc;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::{json, Map, Value};
+
+use crate::client::PyClient;
+use crate::utils::python_to_json;
+
+create_exception!(
+    calimero_client_py,
+    AbiArgumentError,
+    PyValueError,
+    "Call arguments do not match the method's parameters in the application ABI."
+);
+
+/// Value of a JSON-holding string or byte array, if it parses as JSON.
+fn embedded_json(value: &Value) -> Option<Value> {
+    let text = match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) if !items.is_empty() => {
+            let bytes = items
+                .iter()
+                .map(|item| item.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+                .collect::<Option<Vec<u8>>>()?;
+            String::from_utf8(bytes).ok()?
+        }
+        _ => return None,
+    };
+    let text = text.trim();
+    if !text.starts_with('{') {
+        return None;
+    }
+    serde_json::from_str(text).ok()
+}
+
+/// The ABI in a `get_application` response: the first `abi` field found,
+/// searching JSON embedded in strings and metadata bytes too.
+pub fn find_abi(value: &Value) -> Option<Value> {
+    match value {
+        Value::Object(fields) => {
+            if let Some(abi) = fields.get("abi") {
+                match abi {
+                    Value::Object(_) => return Some(abi.clone()),
+                    other => {
+                        if let Some(abi) = embedded_json(other) {
+                            return Some(abi);
+                        }
+                    }
+                }
+            }
+            fields.values().find_map(find_abi)
+        }
+        Value::Array(items) => embedded_json(value)
+            .as_ref()
+            .and_then(find_abi)
+            .or_else(|| items.iter().find_map(find_abi)),
+        Value::String(_) => embedded_json(value).as_ref().and_then(find_abi),
+        _ => None,
+    }
+}
+
+/// Short rendering of an ABI type.
+pub fn type_name(ty: &Value) -> String {
+    if let Some(name) = ty.as_str() {
+        return name.to_string();
+    }
+    if let Some(reference) = ty.get("$ref").and_then(Value::as_str) {
+        return reference.to_string();
+    }
+    let kind = ty.get("kind").and_then(Value::as_str).unwrap_or("unknown");
+    let inner = |key: &str| ty.get(key).map(type_name);
+    match kind {
+        "list" | "vec" | "set" => match inner("items") {
+            Some(items) => format!("{}<{}>", kind, items),
+            None => kind.to_string(),
+        },
+        "map" => format!(
+            "map<{}, {}>",
+            inner("key").unwrap_or_else(|| "string".to_string()),
+            inner("value").unwrap_or_else(|| "unknown".to_string())
+        ),
+        "option" | "optional" => match inner("inner").or_else(|| inner("items")) {
+            Some(inner) => format!("{}?", inner),
+            None => "unknown?".to_string(),
+        },
+        _ => kind.to_string(),
+    }
+}
+
+fn nullable(param: &Value) -> bool {
+    param
+        .get("nullable")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+        || param
+            .get("type")
+            .and_then(|ty| ty.get("kind"))
+            .and_then(Value::as_str)
+            .is_some_and(|kind| kind == "option" || kind == "optional")
+}
+
+/// Methods of `abi`, sorted by name.
+pub fn methods(abi: &Value) -> Vec<Value> {
+    let mut methods: Vec<Value> = abi
+        .get("methods")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|method| {
+            let name = method.get("name")?.as_str()?;
+            let params: Vec<Value> = method
+                .get("params")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(|param| {
+                    json!({
+                        "name": param.get("name").cloned().unwrap_or(Value::Null),
+                        "type": param.get("type").map(type_name),
+                        "optional": nullable(param),
+                    })
+                })
+                .collect();
+            let mut record = json!({
+                "name": name,
+                "params": params,
+                "returns": method.get("returns").filter(|r| !r.is_null()).map(type_name),
+            });
+            for key in ["doc", "description"] {
+                if let Some(doc) = method.get(key).and_then(Value::as_str) {
+                    record["doc"] = doc.into();
+                }
+            }
+            Some(record)
+        })
+        .collect();
+    methods.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+    methods
+}
+
+/// Raw record of `method` in `abi`.
+pub fn find_method<'a>(abi: &'a Value, method: &str) -> Option<&'a Value> {
+    abi.get("methods")?
+        .as_array()?
+        .iter()
+        .find(|record| record.get("name").and_then(Value::as_str) == Some(method))
+}
+
+/// Definition of the named type `reference` (`Entry` or `#/types/Entry`).
+fn resolve<'a>(abi: &'a Value, reference: &str) -> Option<&'a Value> {
+    let name = reference.rsplit('/').next().unwrap_or(reference);
+    let types = abi.get("types")?;
+    match types {
+        Value::Object(types) => types.get(name),
+        Value::Array(types) => types
+            .iter()
+            .find(|ty| ty.get("name").and_then(Value::as_str) == Some(name)),
+        _ => None,
+    }
+}
+
+/// Inclusive bounds of an integer kind, `None` for kinds wider than 64 bits.
+fn int_bounds(kind: &str) -> Option<(i128, i128)> {
+    Some(match kind {
+        "u8" => (0, u8::MAX.into()),
+        "u16" => (0, u16::MAX.into()),
+        "u32" => (0, u32::MAX.into()),
+        "u64" | "usize" => (0, u64::MAX.into()),
+        "i8" => (i8::MIN.into(), i8::MAX.into()),
+        "i16" => (i16::MIN.into(), i16::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        "i64" | "isize" => (i64::MIN.into(), i64::MAX.into()),
+        _ => return None,
+    })
+}
+
+/// Check `value` against the ABI type `ty`; the error names `path`.
+fn check(abi: &Value, ty: &Value, value: &Value, path: &str, depth: usize) -> Result<(), String> {
+    let mismatch = || Err(format!("{} must be {}, got {}", path, type_name(ty), value));
+    // Recursive types are checked to a reasonable depth only.
+    if depth > 32 {
+        return Ok(());
+    }
+    let kind = match ty {
+        Value::String(kind) => kind.as_str(),
+        ty => match ty.get("$ref").and_then(Value::as_str) {
+            Some(reference) => {
+                return match resolve(abi, reference) {
+                    Some(definition) => check(abi, definition, value, path, depth + 1),
+                    None => Ok(()),
+                }
+            }
+            None => ty.get("kind").and_then(Value::as_str).unwrap_or("unknown"),
+        },
+    };
+    match kind {
+        "bool" => match value.is_boolean() {
+            true => Ok(()),
+            false => mismatch(),
+        },
+        "string" | "str" => match value.is_string() {
+            true => Ok(()),
+            false => mismatch(),
+        },
+        "f32" | "f64" | "float" => match value.is_number() {
+            true => Ok(()),
+            false => mismatch(),
+        },
+        "u128" | "i128" => match value {
+            Value::Number(number) if !number.is_f64() => Ok(()),
+            // 128-bit values travel as decimal strings.
+            Value::String(digits)
+                if digits
+                    .trim_start_matches('-')
+                    .chars()
+                    .all(|c| c.is_ascii_digit())
+                    && !digits.is_empty() =>
+            {
+                Ok(())
+            }
+            _ => mismatch(),
+        },
+        "bytes" => match value {
+            Value::String(_) => Ok(()),
+            Value::Array(items)
+                if items
+                    .iter()
+                    .all(|item| item.as_u64().is_some_and(|b| b <= 255)) =>
+            {
+                Ok(())
+            }
+            _ => mismatch(),
+        },
+        "option" | "optional" => match value {
+            Value::Null => Ok(()),
+            value => match ty.get("inner").or_else(|| ty.get("items")) {
+                Some(inner) => check(abi, inner, value, path, depth + 1),
+                None => Ok(()),
+            },
+        },
+        "list" | "vec" | "set" => {
+            let Value::Array(items) = value else {
+                return mismatch();
+            };
+            match ty.get("items") {
+                Some(item_type) => items.iter().enumerate().try_for_each(|(index, item)| {
+                    check(
+                        abi,
+                        item_type,
+                        item,
+                        &format!("{}[{}]", path, index),
+                        depth + 1,
+                    )
+                }),
+                None => Ok(()),
+            }
+        }
+        "map" => {
+            let Value::Object(entries) = value else {
+                return mismatch();
+            };
+            match ty.get("value") {
+                Some(value_type) => entries.iter().try_for_each(|(key, entry)| {
+                    check(
+                        abi,
+                        value_type,
+                        entry,
+                        &format!("{}.{}", path, key),
+                        depth + 1,
+                    )
+                }),
+                None => Ok(()),
+            }
+        }
+        "record" | "struct" => {
+            let Value::Object(entries) = value else {
+                return mismatch();
+            };
+            let fields = ty.get("fields").and_then(Value::as_array);
+            check_fields(
+                abi,
+                fields.map(Vec::as_slice).unwrap_or_default(),
+                entries,
+                path,
+                depth,
+            )
+        }
+        "variant" | "enum" => {
+            let names: Vec<&str> = ty
+                .get("variants")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|variant| variant.get("name").and_then(Value::as_str))
+                .collect();
+            let chosen = match value {
+                Value::String(name) => Some(name.as_str()),
+                Value::Object(entries) if entries.len() == 1 => {
+                    entries.keys().next().map(String::as_str)
+                }
+                _ => return mismatch(),
+            };
+            match chosen {
+                Some(name) if names.is_empty() || names.contains(&name) => Ok(()),
+                _ => Err(format!(
+                    "{} must be one of {}, got {}",
+                    path,
+                    names.join(", "),
+                    value
+                )),
+            }
+        }
+        kind => {
+            // Unknown kinds are left to the node.
+            let Some((min, max)) = int_bounds(kind) else {
+                return Ok(());
+            };
+            let number = value
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| value.as_u64().map(i128::from));
+            match number {
+                Some(number) if (min..=max).contains(&number) => Ok(()),
+                Some(number) => Err(format!(
+                    "{} must be a {} between {} and {}, got {}",
+                    path, kind, min, max, number
+                )),
+                None => mismatch(),
+            }
+        }
+    }
+}
+
+/// Check `args` against `params` (`[{name, type, nullable}]`): every
+/// argument must be a parameter and every non-optional parameter given.
+fn check_fields(
+    abi: &Value,
+    params: &[Value],
+    args: &Map<String, Value>,
+    path: &str,
+    depth: usize,
+) -> Result<(), String> {
+    let names: Vec<&str> = params
+        .iter()
+        .filter_map(|param| param.get("name").and_then(Value::as_str))
+        .collect();
+    let prefix = |name: &str| match path {
+        "" => name.to_string(),
+        path => format!("{}.{}", path, name),
+    };
+    if let Some(unknown) = args.keys().find(|key| !names.contains(&key.as_str())) {
+        return Err(format!(
+            "unknown argument '{}'; expected {}",
+            prefix(unknown),
+            match names.is_empty() {
+                true => "none".to_string(),
+                false => names.join(", "),
+            }
+        ));
+    }
+    for param in params {
+        let Some(name) = param.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        match (args.get(name), param.get("type")) {
+            (None, _) if nullable(param) => {}
+            (None, _) => return Err(format!("missing argument '{}'", prefix(name))),
+            (Some(Value::Null), _) if nullable(param) => {}
+            (Some(value), Some(ty)) => check(abi, ty, value, &prefix(name), depth + 1)?,
+            (Some(_), None) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Check a call of `method` with `args` against `abi`.
+pub fn validate_args(abi: &Value, method: &str, args: &Map<String, Value>) -> Result<(), String> {
+    let Some(record) = find_method(abi, method) else {
+        let known: Vec<Value> = methods(abi)
+            .into_iter()
+            .map(|m| m["name"].clone())
+            .collect();
+        let known: Vec<&str> = known.iter().filter_map(Value::as_str).collect();
+        return Err(format!(
+            "the application has no method '{}'; its methods are {}",
+            method,
+            known.join(", ")
+        ));
+    };
+    let params = record.get("params").and_then(Value::as_array);
+    check_fields(
+        abi,
+        params.map(Vec::as_slice).unwrap_or_default(),
+        args,
+        "",
+        0,
+    )
+    .map_err(|e| format!("{}(): {}", method, e))
+}
+
+/// A context method checked against the application's ABI before each call;
+/// returned by `Client.method(context_id, name)`.
+#[pyclass(name = "AbiMethod", frozen)]
+pub struct PyAbiMethod {
+    client: Py<PyClient>,
+    context_id: String,
+    name: String,
+    abi: Arc<Value>,
+}
+
+impl PyAbiMethod {
+    pub fn new(
+        client: Py<PyClient>,
+        context_id: String,
+        name: String,
+        abi: Value,
+    ) -> PyResult<Self> {
+        if find_method(&abi, &name).is_none() {
+            return Err(AbiArgumentError::new_err(
+                validate_args(&abi, &name, &Map::new()).unwrap_err(),
+            ));
+        }
+        Ok(Self {
+            client,
+            context_id,
+            name,
+            abi: Arc::new(abi),
+        })
+    }
+
+    fn args(&self, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Map<String, Value>> {
+        let args = match kwargs {
+            Some(kwargs) => python_to_json(kwargs.as_any())?,
+            None => Value::Object(Map::new()),
+        };
+        let Value::Object(args) = args else {
+            return Err(AbiArgumentError::new_err("arguments must be keywords"));
+        };
+        validate_args(&self.abi, &self.name, &args).map_err(AbiArgumentError::new_err)?;
+        Ok(args)
+    }
+}
+
+#[pymethods]
+impl PyAbiMethod {
+    #[getter]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[getter]
+    pub fn context_id(&self) -> &str {
+        &self.context_id
+    }
+
+    /// `{name, params: [{name, type, optional}], returns, doc}` from the ABI.
+    #[getter]
+    pub fn signature(&self, py: Python<'_>) -> PyObject {
+        let record = methods(&self.abi)
+            .into_iter()
+            .find(|record| record["name"] == self.name.as_str())
+            .unwrap_or_default();
+        crate::utils::json_to_python(py, &record)
+    }
+
+    /// Check keyword arguments against the ABI without calling the method;
+    /// raises `AbiArgumentError` when they do not fit.
+    #[pyo3(signature = (**kwargs))]
+    pub fn validate(&self, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        self.args(kwargs).map(drop)
+    }
+
+    /// Call the method with keyword arguments, after checking them against
+    /// the ABI; returns what `execute_function` returns.
+    #[pyo3(signature = (**kwargs))]
+    fn __call__(&self, py: Python<'_>, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<PyObject> {
+        let args = Value::Object(self.args(kwargs)?).to_string();
+        self.client.borrow(py).execute_function(
+            &self.context_id,
+            &self.name,
+            &args,
+            "",
+            false,
+            None,
+            None,
+            false,
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!("AbiMethod({}, context_id='{}')", self.name, self.context_id)
+    }
+}
+
+pub fn register(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("AbiArgumentError", py.get_type_bound::<AbiArgumentError>())?;
+    Ok(())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abi() -> Value {
+        json!({
+            "schema_version": "wasm-abi/1",
+            "methods": [
+                {
+                    "name": "set",
+                    "params": [
+                        {"name": "key", "type": {"kind": "string"}},
+                        {"name": "value", "type": {"kind": "option", "inner": {"$ref": "Entry"}}}
+                    ]
+                },
+                {
+                    "name": "entries",
+                    "params": [],
+                    "returns": {"kind": "map", "key": {"kind": "string"}, "value": {"kind": "list", "items": {"kind": "u64"}}}
+                }
+            ]
+        })
+    }
+
+    /// The ABI is found as an object, a JSON string or metadata bytes.
+    #[test]
+    fn test_find_abi() {
+        let abi = abi();
+        let text = abi.to_string();
+        let metadata = json!({"abi": abi.clone()}).to_string();
+        let bytes: Vec<Value> = metadata.bytes().map(Value::from).collect();
+        for response in [
+            json!({"data": {"application": {"abi": abi.clone()}}}),
+            json!({"data": {"application": {"metadata": {"abi": text.clone()}}}}),
+            json!({"data": {"application": {"metadata": bytes}}}),
+        ] {
+            assert_eq!(find_abi(&response), Some(abi.clone()), "{}", response);
+        }
+        assert_eq!(find_abi(&json!({"metadata": metadata})), Some(abi));
+        assert_eq!(find_abi(&json!({"data": {"application": {}}})), None);
+    }
+
+    /// Arguments are checked by name, kind and range, through named types.
+    #[test]
+    fn test_validate_args() {
+        let abi = json!({
+            "methods": [{
+                "name": "transfer",
+                "params": [
+                    {"name": "to", "type": {"kind": "string"}},
+                    {"name": "amount", "type": {"kind": "u32"}},
+                    {"name": "memo", "type": {"kind": "option", "inner": {"$ref": "Memo"}}}
+                ]
+            }],
+            "types": {
+                "Memo": {"kind": "record", "fields": [{"name": "text", "type": {"kind": "string"}}]}
+            }
+        });
+        let args = |value: Value| value.as_object().unwrap().clone();
+        assert!(validate_args(&abi, "transfer", &args(json!({"to": "bob", "amount": 5}))).is_ok());
+        assert!(validate_args(
+            &abi,
+            "transfer",
+            &args(json!({"to": "bob", "amount": 5, "memo": {"text": "hi"}}))
+        )
+        .is_ok());
+
+        let error = |value| validate_args(&abi, "transfer", &args(value)).unwrap_err();
+        assert!(error(json!({"to": "bob"})).contains("missing argument 'amount'"));
+        assert!(
+            error(json!({"to": "bob", "amount": 5, "fee": 1})).contains("unknown argument 'fee'")
+        );
+        assert!(error(json!({"to": 1, "amount": 5})).contains("to must be string"));
+        assert!(error(json!({"to": "bob", "amount": -1})).contains("between 0 and 4294967295"));
+        assert!(error(json!({"to": "b", "amount": 1, "memo": {"txt": ""}})).contains("memo.txt"));
+        assert!(validate_args(&abi, "send", &Map::new())
+            .unwrap_err()
+            .contains("methods are transfer"));
+    }
+
+    /// Methods flatten to names, rendered types and optional flags.
+    #[test]
+    fn test_methods() {
+        let methods = methods(&abi());
+        assert_eq!(methods[0]["name"], "entries");
+        assert_eq!(methods[0]["returns"], "map<string, list<u64>>");
+        assert_eq!(
+            methods[1]["params"],
+            json!([
+                {"name": "key", "type": "string", "optional": false},
+                {"name": "value", "type": "Entry?", "optional": true}
+            ])
+        );
+        assert!(methods[1]["returns"].is_null());
+    }
+}
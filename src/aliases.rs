@@ -0,0 +1,203 @@
+//! Alias management behind `Client.create_alias()` and friends, and
+//! transparent alias resolution.
+//!
+//! Nodes keep aliases per kind: context aliases and application aliases are
+//! node-wide, identity aliases live inside a context. Every client call
+//! taking a context id (or, where a context is known, an identity) also
+//! accepts an alias: a string that does not parse as an id is resolved on
+//! the node and the answer kept in an [`AliasCache`] for a short while, so
+//! calls in a loop resolve once. Deleting an alias through the client
+//! forgets it immediately; aliases re-pointed by other clients are picked
+//! up once the entry expires.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use calimero_primitives::alias::Alias;
+use calimero_primitives::context::ContextId;
+use serde_json::Value;
+
+/// How long a resolved alias is reused before asking the node again.
+pub const DEFAULT_ALIAS_TTL: Duration = Duration::from_secs(60);
+
+/// What an alias names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AliasKind {
+    Context,
+    Identity,
+    Application,
+}
+
+impl AliasKind {
+    pub fn parse(kind: &str) -> eyre::Result<Self> {
+        match kind {
+            "context" => Ok(AliasKind::Context),
+            "identity" => Ok(AliasKind::Identity),
+            "application" => Ok(AliasKind::Application),
+            other => eyre::bail!(
+                "Invalid alias kind '{}'. Expected one of: context, identity, application",
+                other
+            ),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AliasKind::Context => "context",
+            AliasKind::Identity => "identity",
+            AliasKind::Application => "application",
+        }
+    }
+}
+
+/// Whether `name` is acceptable to the node as an alias.
+pub fn is_alias_name(name: &str) -> bool {
+    !name.is_empty() && Alias::<ContextId>::from_str(name).is_ok()
+}
+
+/// Target in a `resolve_alias` response, `None` when the alias is unknown.
+pub fn resolved_value(response: &Value) -> Option<String> {
+    let data = response.get("data").unwrap_or(response);
+    data.get("value")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// `(name, target)` pairs of a `list_*_aliases` response, whose `data` is
+/// a map of names to targets or a list of `{alias, value}` records.
+pub fn listed(response: &Value) -> Vec<(String, String)> {
+    let data = response.get("data").unwrap_or(response);
+    let mut pairs: Vec<(String, String)> = match data {
+        Value::Object(fields) => fields
+            .iter()
+            .filter_map(|(name, target)| Some((name.clone(), target.as_str()?.to_string())))
+            .collect(),
+        Value::Array(records) => records
+            .iter()
+            .filter_map(|record| {
+                let name = record.get("alias").or_else(|| record.get(0))?.as_str()?;
+                let target = record.get("value").or_else(|| record.get(1))?.as_str()?;
+                Some((name.to_string(), target.to_string()))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    pairs.sort();
+    pairs
+}
+
+type CacheKey = (AliasKind, Option<String>, String);
+
+/// Recently resolved aliases, per kind and context scope.
+pub struct AliasCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<CacheKey, (String, Instant)>>,
+}
+
+impl AliasCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn key(kind: AliasKind, scope: Option<&ContextId>, name: &str) -> CacheKey {
+        (kind, scope.map(ToString::to_string), name.to_string())
+    }
+
+    pub fn get(&self, kind: AliasKind, scope: Option<&ContextId>, name: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries
+            .get(&Self::key(kind, scope, name))
+            .filter(|(_, at)| at.elapsed() < self.ttl)
+            .map(|(target, _)| target.clone())
+    }
+
+    pub fn insert(&self, kind: AliasKind, scope: Option<&ContextId>, name: &str, target: &str) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.retain(|_, (_, at)| at.elapsed() < self.ttl);
+        entries.insert(
+            Self::key(kind, scope, name),
+            (target.to_string(), Instant::now()),
+        );
+    }
+
+    /// Drop `name` in every kind and scope.
+    pub fn forget(&self, name: &str) {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|(_, _, cached), _| cached != name);
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Entries are scoped by kind and context, and forgotten by name.
+    #[test]
+    fn test_cache_scopes_and_forget() {
+        let cache = AliasCache::new(DEFAULT_ALIAS_TTL);
+        let scope = ContextId::from([1; 32]);
+        cache.insert(AliasKind::Context, None, "staging", "ctx");
+        cache.insert(AliasKind::Identity, Some(&scope), "alice", "key");
+
+        assert_eq!(
+            cache.get(AliasKind::Context, None, "staging").as_deref(),
+            Some("ctx")
+        );
+        assert_eq!(cache.get(AliasKind::Application, None, "staging"), None);
+        assert_eq!(cache.get(AliasKind::Identity, None, "alice"), None);
+        assert_eq!(
+            cache
+                .get(AliasKind::Identity, Some(&scope), "alice")
+                .as_deref(),
+            Some("key")
+        );
+
+        cache.forget("staging");
+        assert_eq!(cache.get(AliasKind::Context, None, "staging"), None);
+
+        let disabled = AliasCache::new(Duration::ZERO);
+        disabled.insert(AliasKind::Context, None, "staging", "ctx");
+        assert_eq!(disabled.get(AliasKind::Context, None, "staging"), None);
+    }
+
+    /// Resolve and list responses are read in their known shapes.
+    #[test]
+    fn test_responses() {
+        assert_eq!(
+            resolved_value(&json!({"data": {"value": "ctx"}})).as_deref(),
+            Some("ctx")
+        );
+        assert_eq!(resolved_value(&json!({"data": {"value": null}})), None);
+        assert_eq!(
+            listed(&json!({"data": {"b": "2", "a": "1"}})),
+            [("a".into(), "1".into()), ("b".into(), "2".into())]
+        );
+        assert_eq!(
+            listed(&json!({"data": [{"alias": "a", "value": "1"}]})),
+            [("a".to_string(), "1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_kinds_and_names() {
+        assert_eq!(AliasKind::parse("identity").unwrap(), AliasKind::Identity);
+        assert!(AliasKind::parse("group").is_err());
+        assert!(is_alias_name("staging"));
+        assert!(!is_alias_name(""));
+    }
+}
@@ -0,0 +1,188 @@
+//! Installing applications from local WASM bundles.
+//!
+//! `install_application` takes an `http(s)://` URL the node downloads the
+//! bundle from, or a local file (a path or `file://` URL). Local bundles are
+//! hashed here so the node verifies what it installs, and reach the node in
+//! one of two ways:
+//!
+//! - a node on this machine (loopback API URL) installs the file in place,
+//!   as `install_dev_application` does, without copying it;
+//! - a remote node gets the bundle uploaded as a blob and installs it from
+//!   its own blob endpoint. The upload goes in chunks and resumes where an
+//!   interrupted one stopped (see [`crate::blob::upload_resumable`]), with
+//!   `on_progress(bytes_sent, total)` told as chunks land; nodes without
+//!   resumable uploads get the file streamed in one request.
+
+use std::path::{Path, PathBuf};
+
+use eyre::WrapErr;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::blob::{BlobDigest, BlobHasher};
+
+/// Where an application bundle comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// Downloaded by the node.
+    Remote(Url),
+    /// A bundle on this machine.
+    Local(PathBuf),
+}
+
+impl Source {
+    /// `http(s)://` URLs are remote; `file://` URLs and anything else are
+    /// local paths, which must exist.
+    pub fn parse(url_or_path: &str) -> eyre::Result<Self> {
+        if let Ok(url) = Url::parse(url_or_path) {
+            match url.scheme() {
+                "http" | "https" => return Ok(Source::Remote(url)),
+                "file" => {
+                    let path = url
+                        .to_file_path()
+                        .map_err(|()| eyre::eyre!("Invalid file URL '{}'", url_or_path))?;
+                    return Self::local(&path);
+                }
+                // Windows drive letters parse as one-letter schemes
+                scheme if scheme.len() > 1 => {
+                    eyre::bail!(
+                        "Unsupported application URL scheme '{}' (use http(s):// or a local path)",
+                        scheme
+                    )
+                }
+                _ => {}
+            }
+        }
+        Self::local(Path::new(url_or_path))
+    }
+
+    fn local(path: &Path) -> eyre::Result<Self> {
+        let path = path
+            .canonicalize()
+            .wrap_err_with(|| format!("Application bundle {:?} not found", path))?;
+        if !path.is_file() {
+            eyre::bail!("Application bundle {:?} is not a file", path);
+        }
+        Ok(Source::Local(path))
+    }
+}
+
+/// SHA-256 of `data`, as the node's `Hash`.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Blob digest and SHA-256 of the bundle at `path`, read once in chunks.
+pub fn digest_bundle(path: &Path) -> eyre::Result<(BlobDigest, [u8; 32])> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)
+        .wrap_err_with(|| format!("Failed to read application bundle {:?}", path))?;
+    let mut blob = BlobHasher::new();
+    let mut content = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .wrap_err_with(|| format!("Failed to read application bundle {:?}", path))?;
+        if read == 0 {
+            return Ok((blob.finish(), content.finalize().into()));
+        }
+        blob.update(&buffer[..read]);
+        content.update(&buffer[..read]);
+    }
+}
+
+/// Check the bundle hash `actual` against the hex `expected`, when given.
+pub fn check_hash(actual: [u8; 32], expected: Option<&str>) -> eyre::Result<[u8; 32]> {
+    if let Some(expected) = expected {
+        if !expected.eq_ignore_ascii_case(&hex::encode(actual)) {
+            eyre::bail!(
+                "Application bundle hash mismatch: expected {}, got {}",
+                expected,
+                hex::encode(actual)
+            );
+        }
+    }
+    Ok(actual)
+}
+
+/// Whether the node at `api_url` runs on this machine.
+pub fn is_loopback(api_url: &Url) -> bool {
+    match api_url.host() {
+        Some(url::Host::Domain(domain)) => domain == "localhost",
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
+
+/// URL the node serves blob `blob_id` at.
+pub fn blob_url(api_url: &Url, blob_id: &str) -> eyre::Result<Url> {
+    api_url
+        .join(&format!("admin-api/blobs/{}", blob_id))
+        .wrap_err("Invalid blob URL")
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// URLs, file URLs and paths are told apart; missing files are errors.
+    #[test]
+    fn test_parse_source() {
+        assert!(matches!(
+            Source::parse("https://example.com/app.wasm").unwrap(),
+            Source::Remote(_)
+        ));
+        assert!(Source::parse("ftp://example.com/app.wasm").is_err());
+        assert!(Source::parse("/definitely/not/here.wasm").is_err());
+
+        let file = std::env::temp_dir().join(format!("applications-{}.wasm", std::process::id()));
+        std::fs::write(&file, b"\0asm").unwrap();
+        let local = Source::Local(file.canonicalize().unwrap());
+        assert_eq!(Source::parse(file.to_str().unwrap()).unwrap(), local);
+        let file_url = Url::from_file_path(&file).unwrap();
+        assert_eq!(Source::parse(file_url.as_str()).unwrap(), local);
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    /// Hashes are checked case-insensitively.
+    #[test]
+    fn test_check_hash() {
+        let hash = hex::encode(sha256(b"app"));
+        assert!(check_hash(sha256(b"app"), Some(&hash.to_uppercase())).is_ok());
+        assert!(check_hash(sha256(b"app"), None).is_ok());
+        assert!(check_hash(sha256(b"other"), Some(&hash)).is_err());
+    }
+
+    /// A bundle's digest matches hashing it whole.
+    #[test]
+    fn test_digest_bundle() {
+        let data: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        let file = std::env::temp_dir().join(format!("bundle-{}.wasm", std::process::id()));
+        std::fs::write(&file, &data).unwrap();
+        let (digest, hash) = digest_bundle(&file).unwrap();
+        assert_eq!(digest, BlobDigest::of(&data));
+        assert_eq!(hash, sha256(&data));
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn test_loopback_and_blob_url() {
+        let local = Url::parse("http://127.0.0.1:2428/").unwrap();
+        assert!(is_loopback(&local));
+        assert!(is_loopback(&Url::parse("http://localhost:2428").unwrap()));
+        assert!(!is_loopback(
+            &Url::parse("https://node.example.com").unwrap()
+        ));
+        assert_eq!(
+            blob_url(&local, "abc").unwrap().as_str(),
+            "http://127.0.0.1:2428/admin-api/blobs/abc"
+        );
+    }
+}
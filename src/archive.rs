@@ -0,0 +1,98 @@
+//! Client-side archival of contexts.
+//!
+//! Finished projects are frozen rather than deleted: their state stays on
+//! the node and can still be read, but the client refuses to execute on
+//! them until they are unfrozen. The node API has no freeze endpoint, so
+//! the frozen set lives in the client; `list_contexts(archived=...)` uses it
+//! to show or hide archived contexts.
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+/// Context IDs frozen on this client.
+#[derive(Debug, Default)]
+pub struct ArchiveSet {
+    frozen: Mutex<BTreeSet<String>>,
+}
+
+impl ArchiveSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Freeze `context_id`; `false` if it already was.
+    pub fn freeze(&self, context_id: &str) -> bool {
+        self.frozen
+            .lock()
+            .map(|mut frozen| frozen.insert(context_id.to_string()))
+            .unwrap_or(false)
+    }
+
+    /// Unfreeze `context_id`; `false` if it was not frozen.
+    pub fn unfreeze(&self, context_id: &str) -> bool {
+        self.frozen
+            .lock()
+            .map(|mut frozen| frozen.remove(context_id))
+            .unwrap_or(false)
+    }
+
+    pub fn is_frozen(&self, context_id: &str) -> bool {
+        self.frozen
+            .lock()
+            .map(|frozen| frozen.contains(context_id))
+            .unwrap_or(false)
+    }
+
+    /// Frozen context IDs, sorted.
+    pub fn frozen(&self) -> Vec<String> {
+        self.frozen
+            .lock()
+            .map(|frozen| frozen.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Keep only the context records of a `list_contexts` response whose
+    /// archived state is `archived`.
+    pub fn filter_contexts(&self, value: &mut serde_json::Value, archived: bool) {
+        let Some(contexts) = value
+            .pointer_mut("/data/contexts")
+            .and_then(serde_json::Value::as_array_mut)
+        else {
+            return;
+        };
+        contexts.retain(|context| {
+            let id = context.get("id").and_then(serde_json::Value::as_str);
+            id.is_some_and(|id| self.is_frozen(id)) == archived
+        });
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// The filter keeps archived or live contexts and leaves the envelope.
+    #[test]
+    fn test_filter_contexts() {
+        let archive = ArchiveSet::new();
+        assert!(archive.freeze("b"));
+        assert!(!archive.freeze("b"));
+        let response = json!({"data": {"contexts": [{"id": "a"}, {"id": "b"}]}});
+
+        let mut archived = response.clone();
+        archive.filter_contexts(&mut archived, true);
+        assert_eq!(archived, json!({"data": {"contexts": [{"id": "b"}]}}));
+
+        let mut live = response;
+        archive.filter_contexts(&mut live, false);
+        assert_eq!(live, json!({"data": {"contexts": [{"id": "a"}]}}));
+
+        assert!(archive.unfreeze("b"));
+        assert!(archive.frozen().is_empty());
+    }
+}
@@ -0,0 +1,669 @@
+//! Argument encodings of application calls (`ArgEncoding`, `encoding=` on
+//! `execute_function`, `Client.set_arg_encoding()`).
+//!
+//! Call arguments reach the node as JSON. Applications whose methods take
+//! Borsh-encoded or raw byte input used to need that serialization redone
+//! in Python; an encoding converts the arguments here instead:
+//!
+//! - `json` (the default) sends them as given;
+//! - `base64` takes a JSON string of base64 and sends the bytes it holds;
+//! - `borsh` serializes the arguments object with Borsh, following a
+//!   schema of its fields in order.
+//!
+//! Encoded bytes go out as a JSON array of byte values, the way the node's
+//! JSON API carries byte payloads. Schemas are a list of `(name, type)`
+//! pairs, types being `bool`, `u8`..`u128`, `i8`..`i128`, `f32`, `f64`,
+//! `string`, `bytes`, `option<T>` (or `T?`), `list<T>`, `map<K, V>` with
+//! string or integer keys, a nested list of pairs for a struct, or
+//! `{"enum": [(variant, type or None), ...]}`. They are checked when the
+//! encoding is created, so a misspelt type fails at configuration rather
+//! than on the first call; values are checked against them per call, and
+//! errors name the argument (`args.owner`, `args.amounts[2]`).
+//!
+//! Encodings are chosen per call, or registered per context (optionally
+//! per method) with `set_arg_encoding()`; a call's own `encoding=` wins
+//! over the method's, which wins over the context's.
+
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde_json::Value;
+
+use crate::utils::python_to_json;
+
+/// A type of a Borsh schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    F32,
+    F64,
+    String,
+    Bytes,
+    Option(Box<Type>),
+    List(Box<Type>),
+    Map(Box<Type>, Box<Type>),
+    Struct(Vec<(String, Type)>),
+    Enum(Vec<(String, Option<Type>)>),
+}
+
+impl Type {
+    /// Parse a schema type: a type name, a list of `(name, type)` pairs
+    /// or `{"enum": [...]}`.
+    pub fn parse(schema: &Value) -> Result<Self, String> {
+        match schema {
+            Value::String(name) => Self::parse_name(name.trim()),
+            Value::Array(_) => Self::parse_fields(schema).map(Type::Struct),
+            Value::Object(object) => match object.get("enum") {
+                Some(Value::Array(variants)) if object.len() == 1 => {
+                    let variants = variants
+                        .iter()
+                        .map(|variant| match variant.as_array().map(Vec::as_slice) {
+                            Some([Value::String(name), Value::Null]) => Ok((name.clone(), None)),
+                            Some([Value::String(name), payload]) => {
+                                Ok((name.clone(), Some(Self::parse(payload)?)))
+                            }
+                            _ => Err(format!(
+                                "Enum variants are (name, type or None) pairs, got {}",
+                                variant
+                            )),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if variants.is_empty() || variants.len() > 256 {
+                        return Err("An enum has 1 to 256 variants".to_string());
+                    }
+                    Ok(Type::Enum(variants))
+                }
+                _ => Err(format!("Unknown schema type {}", schema)),
+            },
+            _ => Err(format!("Unknown schema type {}", schema)),
+        }
+    }
+
+    /// Parse a list of `(name, type)` pairs.
+    pub fn parse_fields(schema: &Value) -> Result<Vec<(String, Type)>, String> {
+        let Value::Array(fields) = schema else {
+            return Err(format!(
+                "A schema is a list of (name, type) pairs, got {}",
+                schema
+            ));
+        };
+        let mut parsed: Vec<(String, Type)> = Vec::with_capacity(fields.len());
+        for field in fields {
+            let (name, ty) = match field.as_array().map(Vec::as_slice) {
+                Some([Value::String(name), ty]) => (name, ty),
+                _ => return Err(format!("Fields are (name, type) pairs, got {}", field)),
+            };
+            if parsed.iter().any(|(existing, _)| existing == name) {
+                return Err(format!("Field '{}' appears twice", name));
+            }
+            let ty = Self::parse(ty).map_err(|e| format!("{}: {}", name, e))?;
+            parsed.push((name.clone(), ty));
+        }
+        Ok(parsed)
+    }
+
+    fn parse_name(name: &str) -> Result<Self, String> {
+        if let Some(inner) = name.strip_suffix('?') {
+            return Ok(Type::Option(Box::new(Self::parse_name(inner.trim())?)));
+        }
+        if let Some((outer, inner)) = name.strip_suffix('>').and_then(|name| name.split_once('<')) {
+            let inner = inner.trim();
+            return match outer.trim() {
+                "option" => Ok(Type::Option(Box::new(Self::parse_name(inner)?))),
+                "list" | "vec" => Ok(Type::List(Box::new(Self::parse_name(inner)?))),
+                "map" => {
+                    let (key, value) = split_top_level(inner)
+                        .ok_or_else(|| format!("map takes two types, got '{}'", name))?;
+                    let key = Self::parse_name(key)?;
+                    if !key.is_map_key() {
+                        return Err(format!(
+                            "map keys must be a string or an integer type, got '{}'",
+                            name
+                        ));
+                    }
+                    Ok(Type::Map(Box::new(key), Box::new(Self::parse_name(value)?)))
+                }
+                other => Err(format!("Unknown generic type '{}'", other)),
+            };
+        }
+        Ok(match name {
+            "bool" => Type::Bool,
+            "u8" => Type::U8,
+            "u16" => Type::U16,
+            "u32" => Type::U32,
+            "u64" => Type::U64,
+            "u128" => Type::U128,
+            "i8" => Type::I8,
+            "i16" => Type::I16,
+            "i32" => Type::I32,
+            "i64" => Type::I64,
+            "i128" => Type::I128,
+            "f32" => Type::F32,
+            "f64" => Type::F64,
+            "string" => Type::String,
+            "bytes" => Type::Bytes,
+            _ => return Err(format!("Unknown type '{}'", name)),
+        })
+    }
+
+    fn is_map_key(&self) -> bool {
+        matches!(
+            self,
+            Type::String
+                | Type::U8
+                | Type::U16
+                | Type::U32
+                | Type::U64
+                | Type::U128
+                | Type::I8
+                | Type::I16
+                | Type::I32
+                | Type::I64
+                | Type::I128
+        )
+    }
+
+    /// Append the Borsh encoding of `value` to `out`; `path` names the
+    /// value in errors.
+    pub fn encode(&self, value: &Value, path: &str, out: &mut Vec<u8>) -> Result<(), String> {
+        let mismatch = |expected: &str| format!("{} must be {}, got {}", path, expected, value);
+        match self {
+            Type::Bool => out.push(value.as_bool().ok_or_else(|| mismatch("a bool"))? as u8),
+            Type::U8 => out.extend(unsigned::<u8>(value, path)?.to_le_bytes()),
+            Type::U16 => out.extend(unsigned::<u16>(value, path)?.to_le_bytes()),
+            Type::U32 => out.extend(unsigned::<u32>(value, path)?.to_le_bytes()),
+            Type::U64 => out.extend(unsigned::<u64>(value, path)?.to_le_bytes()),
+            Type::U128 => out.extend(unsigned::<u128>(value, path)?.to_le_bytes()),
+            Type::I8 => out.extend(signed::<i8>(value, path)?.to_le_bytes()),
+            Type::I16 => out.extend(signed::<i16>(value, path)?.to_le_bytes()),
+            Type::I32 => out.extend(signed::<i32>(value, path)?.to_le_bytes()),
+            Type::I64 => out.extend(signed::<i64>(value, path)?.to_le_bytes()),
+            Type::I128 => out.extend(signed::<i128>(value, path)?.to_le_bytes()),
+            Type::F32 | Type::F64 => {
+                let number = value.as_f64().ok_or_else(|| mismatch("a number"))?;
+                if number.is_nan() {
+                    return Err(format!("{} is NaN, which Borsh does not encode", path));
+                }
+                match self {
+                    Type::F32 => out.extend((number as f32).to_le_bytes()),
+                    _ => out.extend(number.to_le_bytes()),
+                }
+            }
+            Type::String => {
+                let text = value.as_str().ok_or_else(|| mismatch("a string"))?;
+                encode_bytes(text.as_bytes(), path, out)?;
+            }
+            Type::Bytes => {
+                let bytes = byte_values(value)
+                    .or_else(|| STANDARD.decode(value.as_str()?).ok())
+                    .ok_or_else(|| mismatch("a list of bytes or a base64 string"))?;
+                encode_bytes(&bytes, path, out)?;
+            }
+            Type::Option(inner) => match value {
+                Value::Null => out.push(0),
+                value => {
+                    out.push(1);
+                    inner.encode(value, path, out)?;
+                }
+            },
+            Type::List(item) => {
+                let items = value.as_array().ok_or_else(|| mismatch("a list"))?;
+                encode_len(items.len(), path, out)?;
+                for (index, value) in items.iter().enumerate() {
+                    item.encode(value, &format!("{}[{}]", path, index), out)?;
+                }
+            }
+            Type::Map(key, item) => {
+                let entries = value.as_object().ok_or_else(|| mismatch("an object"))?;
+                let mut encoded = entries
+                    .iter()
+                    .map(|(name, value)| {
+                        let key_value = match **key {
+                            Type::String => Value::String(name.clone()),
+                            _ => name
+                                .parse::<i128>()
+                                .map(|_| Value::String(name.clone()))
+                                .map_err(|_| format!("{} has non-integer key '{}'", path, name))?,
+                        };
+                        let sort_key = match **key {
+                            Type::String => SortKey::Text(name.clone()),
+                            _ => SortKey::Number(name.parse::<i128>().unwrap_or_default()),
+                        };
+                        let mut bytes = Vec::new();
+                        key.encode(&key_value, &format!("{} key '{}'", path, name), &mut bytes)?;
+                        item.encode(value, &format!("{}.{}", path, name), &mut bytes)?;
+                        Ok((sort_key, bytes))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                // Borsh writes maps in key order.
+                encoded.sort_by(|(a, _), (b, _)| a.cmp(b));
+                encode_len(encoded.len(), path, out)?;
+                for (_, bytes) in encoded {
+                    out.extend(bytes);
+                }
+            }
+            Type::Struct(fields) => {
+                let object = value.as_object().ok_or_else(|| mismatch("an object"))?;
+                if let Some(unknown) = object
+                    .keys()
+                    .find(|name| !fields.iter().any(|(field, _)| field == *name))
+                {
+                    return Err(format!("{} has no field '{}'", path, unknown));
+                }
+                for (name, ty) in fields {
+                    let path = format!("{}.{}", path, name);
+                    match (object.get(name), ty) {
+                        (Some(value), ty) => ty.encode(value, &path, out)?,
+                        (None, Type::Option(_)) => out.push(0),
+                        (None, _) => return Err(format!("{} is missing", path)),
+                    }
+                }
+            }
+            Type::Enum(variants) => {
+                let (name, payload) = match value {
+                    Value::String(name) => (name, None),
+                    Value::Object(object) if object.len() == 1 => match object.iter().next() {
+                        Some((name, payload)) => (name, Some(payload)),
+                        None => return Err(mismatch("a variant name or {variant: value}")),
+                    },
+                    _ => return Err(mismatch("a variant name or {variant: value}")),
+                };
+                let index = variants
+                    .iter()
+                    .position(|(variant, _)| variant == name)
+                    .ok_or_else(|| format!("{} has no variant '{}'", path, name))?;
+                out.push(index as u8);
+                match (&variants[index].1, payload) {
+                    (None, None | Some(Value::Null)) => {}
+                    (Some(ty), Some(payload)) => {
+                        ty.encode(payload, &format!("{}.{}", path, name), out)?
+                    }
+                    (Some(_), None) => {
+                        return Err(format!("{} variant '{}' needs a value", path, name))
+                    }
+                    (None, Some(_)) => {
+                        return Err(format!("{} variant '{}' takes no value", path, name))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortKey {
+    Text(String),
+    Number(i128),
+}
+
+/// `a, b` split at its first comma outside angle brackets.
+fn split_top_level(types: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+    for (index, c) in types.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                return Some((types[..index].trim(), types[index + 1..].trim()));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// An unsigned integer from a JSON number or a decimal string (for values
+/// past 2^53).
+fn unsigned<T: TryFrom<u128>>(value: &Value, path: &str) -> Result<T, String> {
+    let number = match value {
+        Value::Number(number) => number.as_u64().map(u128::from),
+        Value::String(text) => text.parse::<u128>().ok(),
+        _ => None,
+    };
+    number
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or_else(|| {
+            format!(
+                "{} must be an unsigned integer in range, got {}",
+                path, value
+            )
+        })
+}
+
+/// A signed integer from a JSON number or a decimal string.
+fn signed<T: TryFrom<i128>>(value: &Value, path: &str) -> Result<T, String> {
+    let number = match value {
+        Value::Number(number) => number.as_i64().map(i128::from),
+        Value::String(text) => text.parse::<i128>().ok(),
+        _ => None,
+    };
+    number
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or_else(|| format!("{} must be an integer in range, got {}", path, value))
+}
+
+fn encode_len(len: usize, path: &str, out: &mut Vec<u8>) -> Result<(), String> {
+    let len = u32::try_from(len).map_err(|_| format!("{} is too long for Borsh", path))?;
+    out.extend(len.to_le_bytes());
+    Ok(())
+}
+
+fn encode_bytes(bytes: &[u8], path: &str, out: &mut Vec<u8>) -> Result<(), String> {
+    encode_len(bytes.len(), path, out)?;
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// Bytes of a JSON array of byte values.
+fn byte_values(value: &Value) -> Option<Vec<u8>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+        .collect()
+}
+
+/// An encoding's kind, and the schema of `borsh`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Encoding {
+    Json,
+    Base64,
+    Borsh(Vec<(String, Type)>),
+}
+
+/// How a call's arguments are encoded before they are sent.
+#[pyclass(name = "ArgEncoding", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgEncoding(pub Encoding);
+
+impl ArgEncoding {
+    /// Encoding `kind`, checking `schema`, which only `borsh` takes (and
+    /// requires).
+    pub fn new(kind: &str, schema: Option<&Value>) -> PyResult<Self> {
+        let invalid = |message: String| PyErr::new::<pyo3::exceptions::PyValueError, _>(message);
+        match (kind.to_ascii_lowercase().as_str(), schema) {
+            ("json", None) => Ok(Self(Encoding::Json)),
+            ("base64", None) => Ok(Self(Encoding::Base64)),
+            ("borsh", Some(schema)) => Type::parse_fields(schema)
+                .map(|fields| Self(Encoding::Borsh(fields)))
+                .map_err(|e| invalid(format!("Invalid Borsh schema: {}", e))),
+            ("borsh", None) => Err(invalid("borsh encoding needs a schema".to_string())),
+            ("json" | "base64", Some(_)) => {
+                Err(invalid(format!("{} encoding takes no schema", kind)))
+            }
+            _ => Err(invalid(format!(
+                "Unknown encoding '{}' (expected 'json', 'base64' or 'borsh')",
+                kind
+            ))),
+        }
+    }
+
+    /// `encoding=` as given from Python: an `ArgEncoding`, or the name of
+    /// one that takes no schema.
+    pub fn from_py(encoding: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(encoding) = encoding.downcast::<Self>() {
+            return Ok(encoding.get().clone());
+        }
+        match encoding.extract::<String>() {
+            Ok(kind) => Self::new(&kind, None),
+            Err(_) => Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "encoding must be an ArgEncoding or 'json', 'base64' or 'borsh'",
+            )),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self.0 {
+            Encoding::Json => "json",
+            Encoding::Base64 => "base64",
+            Encoding::Borsh(_) => "borsh",
+        }
+    }
+
+    /// The bytes `args` encode to; `None` for `json`, which sends them as
+    /// they are.
+    pub fn encode_bytes(&self, args: &Value) -> Result<Option<Vec<u8>>, String> {
+        match &self.0 {
+            Encoding::Json => Ok(None),
+            Encoding::Base64 => {
+                let text = args.as_str().ok_or_else(|| {
+                    format!("base64 arguments must be a JSON string, got {}", args)
+                })?;
+                STANDARD
+                    .decode(text.trim())
+                    .map(Some)
+                    .map_err(|e| format!("Arguments are not valid base64: {}", e))
+            }
+            Encoding::Borsh(fields) => {
+                let mut out = Vec::new();
+                Type::Struct(fields.clone()).encode(args, "args", &mut out)?;
+                Ok(Some(out))
+            }
+        }
+    }
+
+    /// The JSON text of `args` as sent: unchanged for `json`, else the
+    /// encoded bytes as a JSON array.
+    pub fn encode(&self, args: &str) -> Result<String, String> {
+        if let Encoding::Json = self.0 {
+            return Ok(args.to_string());
+        }
+        let value: Value =
+            serde_json::from_str(args).map_err(|e| format!("Invalid JSON args: {}", e))?;
+        let bytes = self.encode_bytes(&value)?.unwrap_or_default();
+        Ok(Value::Array(bytes.into_iter().map(Value::from).collect()).to_string())
+    }
+}
+
+#[pymethods]
+impl ArgEncoding {
+    /// `ArgEncoding("json")`, `ArgEncoding("base64")` or
+    /// `ArgEncoding("borsh", schema=[("owner", "string"), ("amount", "u64")])`.
+    #[new]
+    #[pyo3(signature = (kind, schema=None))]
+    pub fn py_new(kind: &str, schema: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let schema = schema.map(python_to_json).transpose()?;
+        Self::new(kind, schema.as_ref())
+    }
+
+    /// `json`, `base64` or `borsh`.
+    #[getter(kind)]
+    fn py_kind(&self) -> &'static str {
+        self.kind()
+    }
+
+    /// The bytes `args` (a JSON string or a JSON-compatible value) encode
+    /// to, e.g. to compare with an application's own serialization.
+    /// `None` for `json`.
+    fn encode_args(
+        &self,
+        py: Python<'_>,
+        args: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<Py<PyBytes>>> {
+        let value = match args.extract::<String>() {
+            Ok(text) if self.0 != Encoding::Base64 => serde_json::from_str(&text).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid JSON args: {}", e))
+            })?,
+            _ => python_to_json(args)?,
+        };
+        let bytes = self
+            .encode_bytes(&value)
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        Ok(bytes.map(|bytes| PyBytes::new_bound(py, &bytes).unbind()))
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.0 {
+            Encoding::Borsh(fields) => format!("ArgEncoding('borsh', {} fields)", fields.len()),
+            _ => format!("ArgEncoding('{}')", self.kind()),
+        }
+    }
+}
+
+/// Encodings registered with `set_arg_encoding()`, by context and method
+/// (`None`: every method of the context).
+#[derive(Default)]
+pub struct ArgEncodings {
+    entries: RwLock<HashMap<(String, Option<String>), Arc<ArgEncoding>>>,
+}
+
+impl ArgEncodings {
+    pub fn set(&self, context_id: &str, method: Option<&str>, encoding: ArgEncoding) {
+        self.entries
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                (context_id.to_string(), method.map(str::to_string)),
+                Arc::new(encoding),
+            );
+    }
+
+    /// Remove the encoding of `method` (with `None`, every encoding of the
+    /// context); returns how many registrations went.
+    pub fn clear(&self, context_id: &str, method: Option<&str>) -> usize {
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        let before = entries.len();
+        entries.retain(|(context, name), _| {
+            context != context_id || method.is_some_and(|method| name.as_deref() != Some(method))
+        });
+        before - entries.len()
+    }
+
+    /// The encoding of `method` in `context_id`: the method's, else the
+    /// context's.
+    pub fn get(&self, context_id: &str, method: &str) -> Option<Arc<ArgEncoding>> {
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        if entries.is_empty() {
+            return None;
+        }
+        entries
+            .get(&(context_id.to_string(), Some(method.to_string())))
+            .or_else(|| entries.get(&(context_id.to_string(), None)))
+            .cloned()
+    }
+
+    /// `{contextId: {method or "*": kind}}`
+    pub fn to_json(&self) -> Value {
+        let mut contexts = serde_json::Map::new();
+        for ((context, method), encoding) in self
+            .entries
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+        {
+            let methods = contexts
+                .entry(context.clone())
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+            if let Value::Object(methods) = methods {
+                methods.insert(
+                    method.clone().unwrap_or_else(|| "*".to_string()),
+                    encoding.kind().into(),
+                );
+            }
+        }
+        Value::Object(contexts)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn borsh(schema: Value) -> ArgEncoding {
+        ArgEncoding(Encoding::Borsh(Type::parse_fields(&schema).unwrap()))
+    }
+
+    /// Fields are written in schema order with Borsh's layouts.
+    #[test]
+    fn test_borsh_layout() {
+        let encoding = borsh(json!([
+            ["owner", "string"],
+            ["amount", "u64"],
+            ["memo", "string?"],
+            ["tags", "list<u8>"],
+            ["limits", "map<string, u16>"],
+            ["kind", {"enum": [["Plain", null], ["Fee", "u32"]]}],
+        ]));
+        let args = json!({
+            "amount": "18446744073709551615",
+            "owner": "al",
+            "tags": [1, 2],
+            "limits": {"b": 2, "a": 1},
+            "kind": {"Fee": 7},
+        });
+        let bytes = encoding.encode_bytes(&args).unwrap().unwrap();
+        let mut expected = vec![2, 0, 0, 0, b'a', b'l'];
+        expected.extend([0xff; 8]);
+        expected.push(0);
+        expected.extend([2, 0, 0, 0, 1, 2]);
+        expected.extend([2, 0, 0, 0, 1, 0, 0, 0, b'a', 1, 0, 1, 0, 0, 0, b'b', 2, 0]);
+        expected.extend([1, 7, 0, 0, 0]);
+        assert_eq!(bytes, expected);
+    }
+
+    /// Bad values are reported by path; bad schemas when parsed.
+    #[test]
+    fn test_errors_name_the_argument() {
+        let encoding = borsh(json!([["amounts", "list<u8>"]]));
+        let error = encoding
+            .encode_bytes(&json!({"amounts": [1, 300]}))
+            .unwrap_err();
+        assert!(error.starts_with("args.amounts[1]"), "{}", error);
+        assert!(encoding
+            .encode_bytes(&json!({"amounts": [], "extra": 1}))
+            .unwrap_err()
+            .contains("no field 'extra'"));
+        assert!(Type::parse_fields(&json!([["a", "u65"]])).is_err());
+        assert!(Type::parse_fields(&json!([["a", "map<bool, u8>"]])).is_err());
+        assert!(Type::parse_fields(&json!([["a", "u8"], ["a", "u8"]])).is_err());
+    }
+
+    /// base64 arguments become their bytes; JSON passes through.
+    #[test]
+    fn test_base64_and_json() {
+        assert_eq!(
+            ArgEncoding(Encoding::Base64).encode("\"AAEC\"").unwrap(),
+            "[0,1,2]"
+        );
+        assert!(ArgEncoding(Encoding::Base64).encode("{}").is_err());
+        assert_eq!(
+            ArgEncoding(Encoding::Json).encode("{\"a\":1}").unwrap(),
+            "{\"a\":1}"
+        );
+    }
+
+    /// A method's encoding wins over its context's, and clearing the
+    /// context drops both.
+    #[test]
+    fn test_registry_lookup() {
+        let encodings = ArgEncodings::default();
+        encodings.set("ctx", None, ArgEncoding(Encoding::Base64));
+        encodings.set("ctx", Some("set"), borsh(json!([["a", "u8"]])));
+        assert_eq!(encodings.get("ctx", "set").unwrap().kind(), "borsh");
+        assert_eq!(encodings.get("ctx", "get").unwrap().kind(), "base64");
+        assert!(encodings.get("other", "set").is_none());
+        assert_eq!(encodings.clear("ctx", Some("set")), 1);
+        assert_eq!(encodings.clear("ctx", None), 1);
+        assert!(encodings.get("ctx", "get").is_none());
+    }
+}
@@ -0,0 +1,422 @@
+//! Audit trail of mutating calls (`audit_log=` on `create_client`).
+//!
+//! Operators running automation against production nodes need a record of
+//! what it did that cannot be quietly edited afterwards. A client created
+//! with `audit_log="audit.jsonl"` appends one JSON object per mutating call
+//! (context and application changes, group and namespace administration,
+//! aliases, executions, uploads) to that file once the call completes:
+//!
+//! ```json
+//! {"seq": 1, "timestamp": "2026-10-16T09:12:03.412Z", "node": "node1",
+//!  "contextId": "...", "operation": "execute_function", "method": "set",
+//!  "argsHash": "9f86d0...", "subject": "...", "outcome": "ok", "error": null,
+//!  "prev": "000...0", "hash": "5e1c2a..."}
+//! ```
+//!
+//! `argsHash` is the SHA-256 of the call's arguments, so a record proves
+//! what was sent without keeping the arguments themselves; `subject` is the
+//! `sub` claim of the access token the call used. Records are hash-chained:
+//! `hash` is the SHA-256 of `prev` followed by the record without `hash`,
+//! and `prev` is the previous record's `hash` (zeros for the first), so an
+//! edited, removed or reordered record breaks the chain from there on;
+//! `verify_audit_log()` reports where. Appending resumes the chain of an
+//! existing file; only one client at a time should append to a file.
+//!
+//! A plain SHA-256 chain only catches edits by someone who does not
+//! recompute it: whoever can write the file can rewrite every hash after
+//! the record they changed. Two things close that gap:
+//!
+//! - `audit_key=` makes `hash` an HMAC-SHA256 under that key instead, so
+//!   rewriting the chain takes the key as well. Keep the key somewhere the
+//!   log's writers cannot read it, and pass it to `verify_audit_log()`.
+//! - `Client.audit_head` is the `{seq, hash}` of the last record; copying
+//!   it to another store now and then anchors the chain up to that record,
+//!   keyed or not, and also catches records truncated from the end.
+//!
+//! `audit_log=` also takes a callable, passed each record as a dict (with
+//! the same chain) to ship to another store. It is called with the chain
+//! locked, so it sees records in chain order, and must not make mutating
+//! calls on the same client; its exceptions are printed, not raised. Reads
+//! are not recorded, and neither are calls the client refuses before
+//! sending anything.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, TryLockError};
+
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use ring::hmac;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::permissions;
+use crate::utils::json_to_python;
+
+/// `prev` of a chain's first record.
+pub const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// SHA-256 of `args` as compact JSON, hex-encoded.
+pub fn args_hash(args: &Value) -> String {
+    hex::encode(Sha256::digest(args.to_string().as_bytes()))
+}
+
+/// Chain hash of `record` (without its `hash`) following `prev`: SHA-256,
+/// or HMAC-SHA256 under `key` when there is one.
+pub fn chain_hash(prev: &str, record: &Map<String, Value>, key: Option<&hmac::Key>) -> String {
+    let record = Value::Object(record.clone()).to_string();
+    match key {
+        Some(key) => {
+            let mut context = hmac::Context::with_key(key);
+            context.update(prev.as_bytes());
+            context.update(record.as_bytes());
+            hex::encode(context.sign().as_ref())
+        }
+        None => {
+            let mut hasher = Sha256::new();
+            hasher.update(prev.as_bytes());
+            hasher.update(record.as_bytes());
+            hex::encode(hasher.finalize())
+        }
+    }
+}
+
+/// HMAC key for `audit_key=`.
+pub fn chain_key(secret: &str) -> hmac::Key {
+    hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes())
+}
+
+/// One mutating call, as the client knows it when the call completes.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub operation: &'static str,
+    pub context_id: Option<String>,
+    /// Application method of an execution.
+    pub method: Option<String>,
+    pub args: Value,
+    pub subject: Option<String>,
+    /// `None` when the call succeeded.
+    pub error: Option<String>,
+}
+
+struct Chain {
+    /// `None` for a Python sink.
+    file: Option<File>,
+    seq: u64,
+    prev: String,
+}
+
+/// Where a client's audit records go, and the end of their chain.
+pub struct AuditLog {
+    node: String,
+    path: Option<PathBuf>,
+    /// Called with the chain locked, so records arrive in chain order.
+    callback: Option<PyObject>,
+    key: Option<hmac::Key>,
+    chain: Mutex<Chain>,
+}
+
+impl AuditLog {
+    /// Append to the file at `path`, continuing the chain it holds.
+    pub fn open(path: &Path, node: &str, key: Option<hmac::Key>) -> eyre::Result<Self> {
+        let (seq, prev) = match File::open(path) {
+            Ok(file) => {
+                last_link(file).wrap_err_with(|| format!("Failed to read audit log {:?}", path))?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (0, GENESIS.to_string()),
+            Err(e) => {
+                return Err(e).wrap_err_with(|| format!("Failed to open audit log {:?}", path))
+            }
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .wrap_err_with(|| format!("Failed to open audit log {:?}", path))?;
+        permissions::restrict_to_owner(path, false)?;
+        Ok(Self {
+            node: node.to_string(),
+            path: Some(path.to_path_buf()),
+            callback: None,
+            key,
+            chain: Mutex::new(Chain {
+                file: Some(file),
+                seq,
+                prev,
+            }),
+        })
+    }
+
+    /// Pass records to `callback`, starting a new chain.
+    pub fn python(callback: PyObject, node: &str, key: Option<hmac::Key>) -> Self {
+        Self {
+            node: node.to_string(),
+            path: None,
+            callback: Some(callback),
+            key,
+            chain: Mutex::new(Chain {
+                file: None,
+                seq: 0,
+                prev: GENESIS.to_string(),
+            }),
+        }
+    }
+
+    /// File the records are appended to; `None` for a Python sink.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// `(seq, hash)` of the last record, `(0, GENESIS)` before the first.
+    pub fn head(&self) -> Option<(u64, String)> {
+        let chain = self.chain.lock().ok()?;
+        Some((chain.seq, chain.prev.clone()))
+    }
+
+    /// Chain and write `entry`. A record that cannot be written is logged
+    /// and leaves the chain where it was.
+    pub fn record(&self, entry: Entry) {
+        let Some(callback) = &self.callback else {
+            let Ok(mut chain) = self.chain.lock() else {
+                tracing::warn!("audit log lock is poisoned; dropping a record");
+                return;
+            };
+            self.link(&mut chain, entry);
+            return;
+        };
+        Python::with_gil(|py| {
+            // Wait for the lock without the GIL: a callback holding the
+            // lock may release the GIL and need it back to finish.
+            let mut chain = loop {
+                match self.chain.try_lock() {
+                    Ok(chain) => break chain,
+                    Err(TryLockError::WouldBlock) => {
+                        py.allow_threads(|| drop(self.chain.lock()));
+                    }
+                    Err(TryLockError::Poisoned(_)) => {
+                        tracing::warn!("audit log lock is poisoned; dropping a record");
+                        return;
+                    }
+                }
+            };
+            if let Some(record) = self.link(&mut chain, entry) {
+                if let Err(e) = callback.call1(py, (json_to_python(py, &record),)) {
+                    e.print(py);
+                }
+            }
+        });
+    }
+
+    /// Link `entry` into `chain`, appending it to the file if there is
+    /// one; `None` when it could not be written.
+    fn link(&self, chain: &mut Chain, entry: Entry) -> Option<Value> {
+        let seq = chain.seq + 1;
+        let mut record = Map::new();
+        record.insert("seq".into(), seq.into());
+        record.insert(
+            "timestamp".into(),
+            chrono::Utc::now()
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+                .into(),
+        );
+        record.insert("node".into(), self.node.clone().into());
+        record.insert("contextId".into(), entry.context_id.into());
+        record.insert("operation".into(), entry.operation.into());
+        record.insert("method".into(), entry.method.into());
+        record.insert("argsHash".into(), args_hash(&entry.args).into());
+        record.insert("subject".into(), entry.subject.into());
+        let outcome = if entry.error.is_some() { "error" } else { "ok" };
+        record.insert("outcome".into(), outcome.into());
+        record.insert("error".into(), entry.error.into());
+        record.insert("prev".into(), chain.prev.clone().into());
+        let hash = chain_hash(&chain.prev, &record, self.key.as_ref());
+        record.insert("hash".into(), hash.clone().into());
+        let record = Value::Object(record);
+
+        if let Some(file) = &mut chain.file {
+            if let Err(e) = writeln!(file, "{}", record).and_then(|()| file.flush()) {
+                tracing::warn!(error = %e, "failed to write an audit record");
+                return None;
+            }
+        }
+        chain.seq = seq;
+        chain.prev = hash;
+        Some(record)
+    }
+}
+
+/// `seq` and `hash` of the last record in `file`.
+fn last_link(file: File) -> eyre::Result<(u64, String)> {
+    let mut last = None;
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            last = Some(line);
+        }
+    }
+    let Some(line) = last else {
+        return Ok((0, GENESIS.to_string()));
+    };
+    let record: Value = serde_json::from_str(&line).wrap_err("Invalid last record")?;
+    let seq = record.get("seq").and_then(Value::as_u64);
+    let hash = record.get("hash").and_then(Value::as_str);
+    match (seq, hash) {
+        (Some(seq), Some(hash)) => Ok((seq, hash.to_string())),
+        _ => eyre::bail!("Last record has no seq or hash"),
+    }
+}
+
+/// Check the chain of the audit log at `path`, written with `key` if it
+/// was keyed: `{valid, records, head, firstInvalid, reason}`, `head` being
+/// the `hash` of the last valid record and `firstInvalid` the 1-based line
+/// of the first record that does not follow from the ones before it.
+pub fn verify(path: &Path, key: Option<&hmac::Key>) -> eyre::Result<Value> {
+    let file = File::open(path).wrap_err_with(|| format!("Failed to open audit log {:?}", path))?;
+    let mut prev = GENESIS.to_string();
+    let mut records = 0u64;
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.wrap_err_with(|| format!("Failed to read audit log {:?}", path))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let broken = |reason: &str| {
+            json!({
+                "valid": false,
+                "records": records,
+                "head": prev,
+                "firstInvalid": number + 1,
+                "reason": reason,
+            })
+        };
+        let Ok(Value::Object(mut record)) = serde_json::from_str::<Value>(&line) else {
+            return Ok(broken("not a JSON object"));
+        };
+        let Some(Value::String(hash)) = record.remove("hash") else {
+            return Ok(broken("no hash"));
+        };
+        if record.get("prev").and_then(Value::as_str) != Some(prev.as_str()) {
+            return Ok(broken("prev does not match the previous record"));
+        }
+        if record.get("seq").and_then(Value::as_u64) != Some(records + 1) {
+            return Ok(broken("seq is out of order"));
+        }
+        if chain_hash(&prev, &record, key) != hash {
+            return Ok(broken(if key.is_some() {
+                "hash does not match the record under this key"
+            } else {
+                "hash does not match the record (keyed log verified without its key?)"
+            }));
+        }
+        prev = hash;
+        records += 1;
+    }
+    Ok(json!({
+        "valid": true,
+        "records": records,
+        "head": prev,
+        "firstInvalid": null,
+        "reason": null,
+    }))
+}
+
+/// Check the hash chain of an audit log written with `audit_log=`, passing
+/// the client's `audit_key` if it had one: `{valid, records, head,
+/// firstInvalid, reason}`, `firstInvalid` being the line of the first
+/// record edited, removed or reordered. Compare `head` with an anchored
+/// `Client.audit_head` to catch records removed from the end.
+#[pyfunction]
+#[pyo3(signature = (path, key=None))]
+pub fn verify_audit_log(py: Python<'_>, path: PathBuf, key: Option<&str>) -> PyResult<PyObject> {
+    let key = key.map(chain_key);
+    let report = verify(&path, key.as_ref())
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e)))?;
+    Ok(json_to_python(py, &report))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(error: Option<&str>) -> Entry {
+        Entry {
+            operation: "execute_function",
+            context_id: Some("ctx".into()),
+            method: Some("set".into()),
+            args: json!({"key": "a"}),
+            subject: Some("alice".into()),
+            error: error.map(str::to_string),
+        }
+    }
+
+    /// Records chain across reopening, and any edit breaks the chain there.
+    #[test]
+    fn test_chain_survives_reopen_and_detects_edits() {
+        let dir = std::env::temp_dir().join(format!("audit-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.jsonl");
+        AuditLog::open(&path, "node1", None)
+            .unwrap()
+            .record(entry(None));
+        let log = AuditLog::open(&path, "node1", None).unwrap();
+        log.record(entry(Some("boom")));
+        log.record(entry(None));
+        assert_eq!(verify(&path, None).unwrap()["records"], 3);
+        assert_eq!(verify(&path, None).unwrap()["valid"], true);
+        assert_eq!(verify(&path, None).unwrap()["head"], log.head().unwrap().1);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[1].contains("\"outcome\":\"error\""));
+        let tampered = lines[1].replace("\"outcome\":\"error\"", "\"outcome\":\"ok\"");
+        std::fs::write(&path, [lines[0], &tampered, lines[2]].join("\n")).unwrap();
+        let report = verify(&path, None).unwrap();
+        assert_eq!(report["valid"], false);
+        assert_eq!(report["firstInvalid"], 2);
+
+        std::fs::write(&path, [lines[0], lines[2]].join("\n")).unwrap();
+        assert_eq!(verify(&path, None).unwrap()["firstInvalid"], 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// A keyed chain verifies only under its key, so recomputing the
+    /// hashes after an edit does not pass without it.
+    #[test]
+    fn test_keyed_chain_needs_the_key() {
+        let dir = std::env::temp_dir().join(format!("audit-key-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.jsonl");
+        let log = AuditLog::open(&path, "node1", Some(chain_key("s3cret"))).unwrap();
+        log.record(entry(Some("boom")));
+        log.record(entry(None));
+        let key = chain_key("s3cret");
+        assert_eq!(verify(&path, Some(&key)).unwrap()["valid"], true);
+        assert_eq!(verify(&path, None).unwrap()["firstInvalid"], 1);
+        let wrong = chain_key("guess");
+        assert_eq!(verify(&path, Some(&wrong)).unwrap()["firstInvalid"], 1);
+
+        // Rewrite the first record and recompute the unkeyed chain after it.
+        let text = std::fs::read_to_string(&path).unwrap();
+        let mut prev = GENESIS.to_string();
+        let mut forged = Vec::new();
+        for line in text.lines() {
+            let Value::Object(mut record) = serde_json::from_str(line).unwrap() else {
+                unreachable!()
+            };
+            record.remove("hash");
+            record.insert("outcome".into(), "ok".into());
+            record.insert("prev".into(), prev.clone().into());
+            prev = chain_hash(&prev, &record, None);
+            record.insert("hash".into(), prev.clone().into());
+            forged.push(Value::Object(record).to_string());
+        }
+        std::fs::write(&path, forged.join("\n")).unwrap();
+        assert_eq!(verify(&path, None).unwrap()["valid"], true);
+        assert_eq!(verify(&path, Some(&key)).unwrap()["firstInvalid"], 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,22 @@
-//! Python wrapper for AuthMode
+//! Python wrapper for AuthMode, plus a TTL cache of detected auth modes
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
 
 use calimero_client::connection::AuthMode;
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::get_cache_base_dir;
+use crate::entropy;
+
+/// How long a detected auth mode is trusted before re-probing (seconds).
+pub const DEFAULT_AUTH_MODE_TTL_SECS: u64 = 3600;
+
+/// File (under the token cache dir) holding detected auth modes per API URL.
+const AUTH_MODE_CACHE_FILE: &str = "auth_modes.json";
 
 /// Python wrapper for AuthMode
 #[pyclass(name = "AuthMode")]
@@ -28,10 +43,7 @@ impl PyAuthMode {
 
     #[getter]
     pub fn value(&self) -> &str {
-        match self.mode {
-            AuthMode::Required => "required",
-            AuthMode::None => "none",
-        }
+        auth_mode_str(self.mode)
     }
 
     fn __str__(&self) -> &str {
@@ -42,3 +54,248 @@ impl PyAuthMode {
         format!("AuthMode('{}')", self.value())
     }
 }
+
+fn auth_mode_str(mode: AuthMode) -> &'static str {
+    match mode {
+        AuthMode::Required => "required",
+        AuthMode::None => "none",
+    }
+}
+
+fn parse_auth_mode(mode: &str) -> Option<AuthMode> {
+    match mode {
+        "required" => Some(AuthMode::Required),
+        "none" => Some(AuthMode::None),
+        _ => None,
+    }
+}
+
+/// Reason phrases opening an error (or one of its causes) that report the
+/// node rejecting the credentials: `ClientError::Authentication` and 401s.
+const REJECTED_PHRASES: &[&str] = &[
+    "authentication failed",
+    "authentication error",
+    "authentication required",
+    "unauthorized",
+];
+
+/// Reason phrase opening an error (or cause) that reports a 403.
+const FORBIDDEN_PHRASE: &str = "forbidden";
+
+/// An authentication failure reported by a failed call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthFailure {
+    /// The node rejected the token (401, `ClientError::Authentication`).
+    Rejected,
+    /// The node refused the caller permission (403).
+    Forbidden,
+}
+
+/// Whether `segment` opens with `phrase` as whole words.
+fn opens_with(segment: &str, phrase: &str) -> bool {
+    segment.strip_prefix(phrase).is_some_and(|rest| {
+        !rest
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// The authentication failure an error message reports, if any.
+///
+/// `calimero-client` surfaces these as formatted errors, so the check is on
+/// the rendered cause chain: an HTTP status of 401 or 403 (`status 401`,
+/// `HTTP 403`, see [`crate::error::parse_status`]), else a cause that opens
+/// with the error's reason phrase. Digits or words elsewhere in a message
+/// (hashes, ids, field names) do not count.
+pub fn auth_failure(message: &str) -> Option<AuthFailure> {
+    match crate::error::parse_status(message) {
+        Some(401) => return Some(AuthFailure::Rejected),
+        Some(403) => return Some(AuthFailure::Forbidden),
+        Some(_) => return None,
+        None => {}
+    }
+    let message = message.to_ascii_lowercase();
+    message.split(": ").map(str::trim).find_map(|segment| {
+        if REJECTED_PHRASES
+            .iter()
+            .any(|phrase| opens_with(segment, phrase))
+        {
+            Some(AuthFailure::Rejected)
+        } else if opens_with(segment, FORBIDDEN_PHRASE) {
+            Some(AuthFailure::Forbidden)
+        } else {
+            None
+        }
+    })
+}
+
+/// Whether an error message reports an authentication failure.
+pub fn is_auth_error(message: &str) -> bool {
+    auth_failure(message).is_some()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedAuthMode {
+    mode: String,
+    detected_at: i64,
+}
+
+/// Detected auth mode for one node, trusted for `ttl_secs`.
+///
+/// Persisted detections are shared across processes via
+/// `auth_modes.json` in the token cache directory; anonymous connections keep the
+/// result in memory only.
+pub struct AuthModeCache {
+    api_url: String,
+    ttl_secs: u64,
+    persist: bool,
+    current: Mutex<Option<(AuthMode, i64)>>,
+}
+
+impl AuthModeCache {
+    pub fn new(api_url: &str, ttl_secs: u64, persist: bool) -> Self {
+        let current = if persist {
+            load_cached_modes()
+                .remove(api_url)
+                .and_then(|c| parse_auth_mode(&c.mode).map(|mode| (mode, c.detected_at)))
+        } else {
+            None
+        };
+        Self {
+            api_url: api_url.to_string(),
+            ttl_secs,
+            persist,
+            current: Mutex::new(current),
+        }
+    }
+
+    /// The cached mode, if one was detected within the TTL.
+    pub fn get(&self) -> Option<AuthMode> {
+        let now = chrono::Utc::now().timestamp();
+        let current = self.current.lock().ok()?;
+        (*current).and_then(|(mode, detected_at)| {
+            let age = now.saturating_sub(detected_at);
+            (age >= 0 && (age as u64) < self.ttl_secs).then_some(mode)
+        })
+    }
+
+    /// Record a fresh detection.
+    pub fn store(&self, mode: AuthMode) {
+        let now = chrono::Utc::now().timestamp();
+        if let Ok(mut current) = self.current.lock() {
+            *current = Some((mode, now));
+        }
+        if self.persist {
+            let mut modes = load_cached_modes();
+            let _ = modes.insert(
+                self.api_url.clone(),
+                CachedAuthMode {
+                    mode: auth_mode_str(mode).to_string(),
+                    detected_at: now,
+                },
+            );
+            // Best effort: a failed write only costs a re-probe later.
+            let _ = save_cached_modes(&modes);
+        }
+    }
+
+    /// Forget the detection so the next lookup re-probes the node.
+    pub fn invalidate(&self) {
+        if let Ok(mut current) = self.current.lock() {
+            *current = None;
+        }
+        if self.persist {
+            let mut modes = load_cached_modes();
+            if modes.remove(&self.api_url).is_some() {
+                let _ = save_cached_modes(&modes);
+            }
+        }
+    }
+}
+
+fn auth_mode_cache_path() -> PathBuf {
+    get_cache_base_dir().join(AUTH_MODE_CACHE_FILE)
+}
+
+fn load_cached_modes() -> BTreeMap<String, CachedAuthMode> {
+    fs::read_to_string(auth_mode_cache_path())
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_cached_modes(modes: &BTreeMap<String, CachedAuthMode>) -> std::io::Result<()> {
+    let path = auth_mode_cache_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let temp_path = path.with_extension(format!("json.{}.tmp", entropy::temp_suffix()));
+    fs::write(&temp_path, serde_json::to_vec_pretty(modes)?)?;
+    fs::rename(&temp_path, &path)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Auth failures are recognized from their rendered messages.
+    #[test]
+    fn test_is_auth_error() {
+        assert!(is_auth_error("Authentication failed: token expired"));
+        assert!(is_auth_error("HTTP 401 Unauthorized"));
+        assert!(is_auth_error("request failed with status 403 Forbidden"));
+        assert!(is_auth_error("execute failed: Unauthorized"));
+        assert!(!is_auth_error("Network error: connection refused"));
+        assert_eq!(
+            auth_failure("request failed with status 403 Forbidden"),
+            Some(AuthFailure::Forbidden)
+        );
+        assert_eq!(
+            auth_failure("HTTP 401 Unauthorized"),
+            Some(AuthFailure::Rejected)
+        );
+    }
+
+    /// Status-like digits and auth words inside other errors do not count.
+    #[test]
+    fn test_is_auth_error_ignores_incidental_matches() {
+        assert!(!is_auth_error(
+            "Failed to decode blob 0x4013ab9f2c: invalid length"
+        ));
+        assert!(!is_auth_error("Context 403a9c1e not found"));
+        assert!(!is_auth_error("status 4013 is not a valid status"));
+        assert!(!is_auth_error(
+            "Method failed: forbidden_keys is missing from the state"
+        ));
+        assert!(!is_auth_error(
+            "Execution error: user is not authenticated in the app's own ACL \
+             (unauthorized action logged)"
+        ));
+        assert!(!is_auth_error(
+            "HTTP 500: Authentication failed in the application"
+        ));
+    }
+
+    /// In-memory detections expire after the TTL and on invalidation.
+    #[test]
+    fn test_auth_mode_cache_ttl() {
+        let cache = AuthModeCache::new("http://localhost:2528/", 60, false);
+        assert!(cache.get().is_none());
+
+        cache.store(AuthMode::Required);
+        assert!(matches!(cache.get(), Some(AuthMode::Required)));
+
+        cache.invalidate();
+        assert!(cache.get().is_none());
+
+        let expired = AuthModeCache::new("http://localhost:2528/", 0, false);
+        expired.store(AuthMode::None);
+        assert!(expired.get().is_none());
+    }
+}
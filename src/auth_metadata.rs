@@ -0,0 +1,266 @@
+//! On-disk cache of the auth service's JWKS and OIDC discovery documents.
+//!
+//! Short-lived CLI invocations start a new process per command, so an
+//! in-memory cache never gets a hit and every login or token check would
+//! fetch the same documents again. They are kept as files under
+//! `auth_metadata/` in [`crate::cache::state_root`] and served from there for
+//! the response's `Cache-Control: max-age` (else
+//! [`DEFAULT_AUTH_METADATA_TTL_SECS`]). Past that they are revalidated with
+//! `If-None-Match`, so an unchanged document costs a `304` without a body,
+//! and a stale copy is still served when the server cannot be reached.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use reqwest::header::{HeaderMap, ACCEPT, CACHE_CONTROL, ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::cache::state_root;
+use crate::entropy;
+
+/// Directory of cached documents under the state root.
+pub const AUTH_METADATA_SUBDIR: &str = "auth_metadata";
+
+/// Time a document without `Cache-Control: max-age` is fresh (one hour).
+pub const DEFAULT_AUTH_METADATA_TTL_SECS: u64 = 3600;
+
+/// Auth-service endpoint publishing the keys its tokens are signed with.
+pub const JWKS_PATH: &str = "auth/.well-known/jwks.json";
+
+/// A document as stored on disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedDocument {
+    pub url: String,
+    pub etag: Option<String>,
+    /// Unix time (seconds) the document was last fetched or revalidated.
+    pub fetched_at: i64,
+    pub max_age: u64,
+    pub body: Value,
+}
+
+impl CachedDocument {
+    pub fn is_fresh(&self, now: i64) -> bool {
+        now.saturating_sub(self.fetched_at) < self.max_age as i64
+    }
+}
+
+/// Freshness lifetime from `Cache-Control`: `0` for `no-cache`/`no-store`,
+/// `max-age` when given, else `None`.
+pub fn max_age(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(CACHE_CONTROL)?.to_str().ok()?;
+    let mut max_age = None;
+    for directive in value.split(',').map(str::trim) {
+        let directive = directive.to_ascii_lowercase();
+        if directive == "no-cache" || directive == "no-store" {
+            return Some(0);
+        }
+        if let Some(seconds) = directive.strip_prefix("max-age=") {
+            max_age = seconds.trim_matches('"').parse().ok();
+        }
+    }
+    max_age
+}
+
+/// Documents cached in one directory.
+#[derive(Debug, Clone)]
+pub struct DocumentCache {
+    dir: PathBuf,
+}
+
+impl Default for DocumentCache {
+    fn default() -> Self {
+        Self::new(state_root().join(AUTH_METADATA_SUBDIR))
+    }
+}
+
+impl DocumentCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        let digest = hex::encode(Sha256::digest(url.as_bytes()));
+        self.dir.join(format!("{}.json", &digest[..32]))
+    }
+
+    /// The cached copy of `url`, fresh or not.
+    pub fn load(&self, url: &str) -> Option<CachedDocument> {
+        let data = fs::read(self.path(url)).ok()?;
+        let document: CachedDocument = serde_json::from_slice(&data).ok()?;
+        (document.url == url).then_some(document)
+    }
+
+    pub fn store(&self, document: &CachedDocument) -> eyre::Result<()> {
+        fs::create_dir_all(&self.dir)
+            .wrap_err_with(|| format!("Failed to create {:?}", self.dir))?;
+        let path = self.path(&document.url);
+        write_atomic(&path, &serde_json::to_vec(document)?)
+            .wrap_err_with(|| format!("Failed to write {:?}", path))
+    }
+
+    /// Remove every cached document, returning how many there were.
+    pub fn clear(&self) -> eyre::Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e).wrap_err_with(|| format!("Failed to read {:?}", self.dir)),
+        };
+        let mut removed = 0;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                fs::remove_file(&path).wrap_err_with(|| format!("Failed to remove {:?}", path))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// The JSON document at `url`, from the cache while fresh.
+    ///
+    /// `refresh` skips the freshness check (the server is asked even for a
+    /// fresh copy, still with `If-None-Match`) and disables the stale
+    /// fallback. Failing to write the cache does not fail the fetch.
+    pub async fn fetch(
+        &self,
+        client: &reqwest::Client,
+        url: &Url,
+        refresh: bool,
+    ) -> eyre::Result<Value> {
+        let now = chrono::Utc::now().timestamp();
+        let cached = self.load(url.as_str());
+        if let Some(cached) = cached.as_ref().filter(|c| !refresh && c.is_fresh(now)) {
+            return Ok(cached.body.clone());
+        }
+
+        let mut request = client.get(url.clone()).header(ACCEPT, "application/json");
+        if let Some(etag) = cached.as_ref().and_then(|c| c.etag.as_deref()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => match cached {
+                Some(stale) if !refresh => return Ok(stale.body),
+                _ => return Err(e).wrap_err_with(|| format!("Request to {} failed", url)),
+            },
+        };
+        let max_age = max_age(response.headers()).unwrap_or(DEFAULT_AUTH_METADATA_TTL_SECS);
+
+        let document = match (response.status(), cached) {
+            (StatusCode::NOT_MODIFIED, Some(cached)) => CachedDocument {
+                fetched_at: now,
+                max_age,
+                ..cached
+            },
+            _ => {
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                let body = response
+                    .error_for_status()
+                    .wrap_err_with(|| format!("Fetching {} failed", url))?
+                    .json()
+                    .await
+                    .wrap_err_with(|| format!("{} did not return JSON", url))?;
+                CachedDocument {
+                    url: url.to_string(),
+                    etag,
+                    fetched_at: now,
+                    max_age,
+                    body,
+                }
+            }
+        };
+        let _ = self.store(&document);
+        Ok(document.body)
+    }
+}
+
+fn write_atomic(path: &Path, data: &[u8]) -> eyre::Result<()> {
+    let temp_path = path.with_extension(format!("{}.tmp", entropy::temp_suffix()));
+    let written = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok::<_, eyre::Report>(())
+    })();
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written
+}
+
+/// The auth service's JWKS URL for a node at `api_url`.
+pub fn jwks_url(api_url: &Url) -> eyre::Result<Url> {
+    api_url.join(JWKS_PATH).wrap_err("Invalid node URL")
+}
+
+/// Drop every cached JWKS and OIDC discovery document, returning how many
+/// were removed; the next login or token check fetches them again.
+#[pyfunction]
+pub fn clear_auth_metadata_cache() -> PyResult<usize> {
+    DocumentCache::default()
+        .clear()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e)))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_max_age_from_cache_control() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(max_age(&headers), None);
+        headers.insert(
+            CACHE_CONTROL,
+            HeaderValue::from_static("public, max-age=600"),
+        );
+        assert_eq!(max_age(&headers), Some(600));
+        headers.insert(
+            CACHE_CONTROL,
+            HeaderValue::from_static("max-age=600, no-cache"),
+        );
+        assert_eq!(max_age(&headers), Some(0));
+    }
+
+    #[test]
+    fn test_store_load_and_clear() {
+        let dir = std::env::temp_dir().join(format!("auth-metadata-{}", std::process::id()));
+        let cache = DocumentCache::new(dir.clone());
+        let document = CachedDocument {
+            url: "https://auth.example/auth/.well-known/jwks.json".into(),
+            etag: Some("\"v1\"".into()),
+            fetched_at: 1_000,
+            max_age: 60,
+            body: serde_json::json!({"keys": []}),
+        };
+        cache.store(&document).unwrap();
+
+        let loaded = cache.load(&document.url).unwrap();
+        assert_eq!(loaded, document);
+        assert!(loaded.is_fresh(1_059));
+        assert!(!loaded.is_fresh(1_060));
+        assert!(cache.load("https://other.example/jwks.json").is_none());
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert!(cache.load(&document.url).is_none());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
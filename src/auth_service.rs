@@ -0,0 +1,317 @@
+//! Bindings for the node's auth service.
+//!
+//! Nodes that require authentication sit behind an auth service which issues
+//! and manages tokens under `/auth` and `/admin`. `calimero-client` only uses
+//! it to log in and refresh; the management endpoints are called through
+//! [`http::send_json`] with the session's own bearer token, so they are
+//! limited to whatever that session is allowed to do.
+
+use calimero_client::JwtToken;
+use calimero_primitives::context::ContextId;
+use calimero_primitives::identity::PublicKey;
+use reqwest::Method;
+use url::Url;
+
+use crate::blob::find_string_field;
+use crate::http;
+use crate::security::jwt_expiry;
+use crate::storage::MeroboxFileStorage;
+
+/// Endpoint minting tokens with an explicit permission set.
+pub const CLIENT_KEY_PATH: &str = "admin/client-key";
+
+/// Root keys (one per login session) of the current user.
+pub const KEYS_PATH: &str = "admin/keys";
+
+/// Default lifetime of a scoped token (one hour).
+pub const DEFAULT_SCOPED_TOKEN_TTL_SECS: u64 = 3600;
+
+/// Default lifetime of the token handed out by `blob_access` (five minutes).
+pub const DEFAULT_BLOB_ACCESS_TTL_SECS: u64 = 300;
+
+/// Parameters of a token limited to a single context.
+#[derive(Debug, Clone)]
+pub struct ScopedTokenRequest {
+    pub context_id: ContextId,
+    pub context_identity: Option<PublicKey>,
+    pub read_only: bool,
+    pub ttl_secs: u64,
+}
+
+impl ScopedTokenRequest {
+    /// Permission strings granted to the token.
+    ///
+    /// Read access to the context always; execution (as `context_identity`
+    /// when given) only without `read_only`.
+    pub fn permissions(&self) -> Vec<String> {
+        let mut permissions = vec![format!("context:read[{}]", self.context_id)];
+        if !self.read_only {
+            permissions.push(match &self.context_identity {
+                Some(identity) => format!("context:execute[{},{}]", self.context_id, identity),
+                None => format!("context:execute[{}]", self.context_id),
+            });
+        }
+        permissions
+    }
+
+    fn body(&self) -> serde_json::Value {
+        serde_json::json!({
+            "context_id": self.context_id.to_string(),
+            "context_identity": self.context_identity.as_ref().map(ToString::to_string),
+            "permissions": self.permissions(),
+            "expires_in": self.ttl_secs,
+        })
+    }
+}
+
+/// Whether an error means the auth service lacks the endpoint altogether.
+fn is_unsupported(message: &str) -> bool {
+    message.contains("status 404") || message.contains("status 405")
+}
+
+/// Pull the token pair out of an auth-service token response.
+///
+/// The expiry comes from the token's own `exp` claim, falling back to the
+/// requested lifetime (when there is one) for opaque tokens.
+pub(crate) fn parse_tokens(
+    value: &serde_json::Value,
+    ttl_secs: Option<u64>,
+    now: i64,
+) -> eyre::Result<JwtToken> {
+    let access_token = find_string_field(value, "access_token")
+        .or_else(|| find_string_field(value, "accessToken"))
+        .ok_or_else(|| eyre::eyre!("Auth service response did not include an access token"))?
+        .to_string();
+    let refresh_token = find_string_field(value, "refresh_token")
+        .or_else(|| find_string_field(value, "refreshToken"))
+        .map(str::to_string);
+    let expires_at = jwt_expiry(&access_token)
+        .or_else(|| ttl_secs.map(|ttl| now.saturating_add(i64::try_from(ttl).unwrap_or(i64::MAX))));
+    Ok(JwtToken {
+        access_token,
+        refresh_token,
+        expires_at,
+    })
+}
+
+/// Mint a token scoped by `request` from the current session.
+pub async fn mint_scoped_token(
+    client: &reqwest::Client,
+    api_url: &Url,
+    storage: &MeroboxFileStorage,
+    node_name: Option<&str>,
+    request: &ScopedTokenRequest,
+    limit: Option<usize>,
+) -> eyre::Result<JwtToken> {
+    let response = http::send_json(
+        client,
+        Method::POST,
+        api_url,
+        CLIENT_KEY_PATH,
+        storage,
+        node_name,
+        Some(&request.body()),
+        limit,
+    )
+    .await
+    .map_err(unsupported("minting scoped tokens"))?;
+    parse_tokens(
+        &response,
+        Some(request.ttl_secs),
+        chrono::Utc::now().timestamp(),
+    )
+}
+
+/// Records of a list response, wherever the envelope puts the array.
+fn records(value: &serde_json::Value) -> Vec<serde_json::Value> {
+    match value {
+        serde_json::Value::Array(items) => items.clone(),
+        serde_json::Value::Object(obj) => obj
+            .values()
+            .map(records)
+            .find(|found| !found.is_empty())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Identifier of a key or client record.
+fn record_id<'a>(record: &'a serde_json::Value, fields: &[&str]) -> Option<&'a str> {
+    fields
+        .iter()
+        .find_map(|field| record.get(*field).and_then(|v| v.as_str()))
+}
+
+/// Reject ids that would change the request path when interpolated.
+pub fn validate_id(kind: &str, id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+        && id != "."
+        && id != "..";
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid {} '{}'", kind, id))
+    }
+}
+
+fn unsupported(operation: &str) -> impl Fn(eyre::Report) -> eyre::Report + '_ {
+    move |e| {
+        if is_unsupported(&e.to_string()) {
+            eyre::eyre!("The node's auth service does not support {}", operation)
+        } else {
+            e
+        }
+    }
+}
+
+/// List the current user's sessions: every root key with the client tokens
+/// issued under it attached as `clients`.
+pub async fn list_sessions(
+    client: &reqwest::Client,
+    api_url: &Url,
+    storage: &MeroboxFileStorage,
+    node_name: Option<&str>,
+    limit: Option<usize>,
+) -> eyre::Result<Vec<serde_json::Value>> {
+    let keys = http::send_json(
+        client,
+        Method::GET,
+        api_url,
+        KEYS_PATH,
+        storage,
+        node_name,
+        None,
+        limit,
+    )
+    .await
+    .map_err(unsupported("listing sessions"))?;
+
+    let mut sessions = records(&keys);
+    for session in &mut sessions {
+        let Some(key_id) = record_id(session, &["key_id", "keyId", "id"]).map(str::to_string)
+        else {
+            continue;
+        };
+        if validate_id("key id", &key_id).is_err() {
+            continue;
+        }
+        let clients = http::send_json(
+            client,
+            Method::GET,
+            api_url,
+            &format!("{KEYS_PATH}/{key_id}/clients"),
+            storage,
+            node_name,
+            None,
+            limit,
+        )
+        .await?;
+        if let serde_json::Value::Object(obj) = session {
+            obj.insert(
+                "clients".to_string(),
+                serde_json::Value::Array(records(&clients)),
+            );
+        }
+    }
+    Ok(sessions)
+}
+
+/// Revoke a whole session (`key_id`) or one client token issued under it.
+pub async fn revoke_session(
+    client: &reqwest::Client,
+    api_url: &Url,
+    storage: &MeroboxFileStorage,
+    node_name: Option<&str>,
+    key_id: &str,
+    client_id: Option<&str>,
+    limit: Option<usize>,
+) -> eyre::Result<serde_json::Value> {
+    let path = match client_id {
+        Some(client_id) => format!("{KEYS_PATH}/{key_id}/clients/{client_id}"),
+        None => format!("{KEYS_PATH}/{key_id}"),
+    };
+    http::send_json(
+        client,
+        Method::DELETE,
+        api_url,
+        &path,
+        storage,
+        node_name,
+        None,
+        limit,
+    )
+    .await
+    .map_err(unsupported("revoking sessions"))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(read_only: bool) -> ScopedTokenRequest {
+        ScopedTokenRequest {
+            context_id: ContextId::from([3u8; 32]),
+            context_identity: None,
+            read_only,
+            ttl_secs: 60,
+        }
+    }
+
+    /// Read-only tokens get no execute permission.
+    #[test]
+    fn test_permissions() {
+        let context_id = ContextId::from([3u8; 32]);
+        assert_eq!(
+            request(true).permissions(),
+            vec![format!("context:read[{}]", context_id)]
+        );
+        assert_eq!(
+            request(false).permissions()[1],
+            format!("context:execute[{}]", context_id)
+        );
+    }
+
+    /// Tokens are found inside a `data` envelope; opaque tokens expire
+    /// after the requested lifetime.
+    #[test]
+    fn test_parse_tokens() {
+        let value = serde_json::json!({
+            "data": {"access_token": "opaque", "refresh_token": "r"}
+        });
+        let token = parse_tokens(&value, Some(60), 1_000).unwrap();
+        assert_eq!(token.access_token, "opaque");
+        assert_eq!(token.refresh_token.as_deref(), Some("r"));
+        assert_eq!(token.expires_at, Some(1_060));
+
+        assert!(parse_tokens(&serde_json::json!({"data": {}}), None, 0).is_err());
+    }
+
+    /// List records are found in flat and nested envelopes.
+    #[test]
+    fn test_records() {
+        let flat = serde_json::json!({"data": [{"key_id": "a"}]});
+        let nested = serde_json::json!({"data": {"keys": [{"id": "b"}]}});
+        assert_eq!(record_id(&records(&flat)[0], &["key_id", "id"]), Some("a"));
+        assert_eq!(
+            record_id(&records(&nested)[0], &["key_id", "id"]),
+            Some("b")
+        );
+        assert!(records(&serde_json::json!({"data": null})).is_empty());
+    }
+
+    /// Ids that could escape their path segment are rejected.
+    #[test]
+    fn test_validate_id() {
+        assert!(validate_id("key id", "k3y_01-a.b").is_ok());
+        for bad in ["", "..", "a/b", "a?b", "a b"] {
+            assert!(validate_id("key id", bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,1189 @@
+//! Blob transfer helpers shared by the `Client` blob bindings.
+//!
+//! The pyo3 methods in `client.rs` stay thin; anything that needs more than a
+//! single round trip (fan-out, writing to disk, verification) lives here.
+//!
+//! Downloads are checked against their blob id before they are returned:
+//! the node derives the id from the content (see [`BlobHasher`]), so a
+//! corrupted or truncated transfer cannot pass for the blob it was asked
+//! for. `hash_blob()` and `verify_blob()` apply the same derivation to local
+//! files, so upload pipelines can know an id before uploading.
+//!
+//! Large local files (application bundles) are uploaded in chunks through
+//! the node's resumable upload endpoint, keyed by that same id, so a
+//! transfer cut off halfway continues from the last byte the node holds —
+//! on the next attempt or in a later process (see [`upload_resumable`]).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use calimero_client::client::Client;
+use calimero_client::connection::ConnectionInfo;
+use calimero_client::CliAuthenticator;
+use calimero_primitives::blobs::BlobId;
+use calimero_primitives::hash::Hash;
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+
+use crate::cache::state_root;
+use crate::concurrency::AdaptiveLimiter;
+use crate::entropy;
+use crate::http;
+use crate::retry::{self, RetryConfig};
+use crate::retry_budget::RetryBudget;
+use crate::storage::MeroboxFileStorage;
+use crate::utils::json_to_python;
+
+/// Subdirectory of the state root holding blob metadata sidecars.
+pub const BLOB_METADATA_SUBDIR: &str = "blob_meta";
+
+/// Default number of blobs fetched in parallel by `download_blobs`.
+pub const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 4;
+
+/// Size of the slices read per step when hashing local files.
+const VERIFY_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Size of the chunks the node's blob store splits content into.
+pub const BLOB_CHUNK_SIZE: usize = 1 << 20;
+
+/// Outcome of a single blob within a bulk download.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobDownloadOutcome {
+    pub blob_id: String,
+    pub path: Option<String>,
+    pub size: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl BlobDownloadOutcome {
+    pub fn succeeded(blob_id: &BlobId, path: &Path, size: u64) -> Self {
+        Self {
+            blob_id: blob_id.to_string(),
+            path: Some(path.to_string_lossy().into_owned()),
+            size: Some(size),
+            error: None,
+        }
+    }
+
+    pub fn failed(blob_id: impl ToString, error: impl ToString) -> Self {
+        Self {
+            blob_id: blob_id.to_string(),
+            path: None,
+            size: None,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// Path a blob is written to inside `dest_dir`: the blob id itself.
+pub fn blob_dest_path(dest_dir: &Path, blob_id: &BlobId) -> PathBuf {
+    dest_dir.join(blob_id.to_string())
+}
+
+/// Download one blob and stream it to `dest_dir/<blob_id>`.
+///
+/// The bytes land in a `.part` file first and are renamed into place only
+/// once fully written, so a failed or interrupted download never leaves a
+/// truncated file under the final name. Each chunk goes straight to disk
+/// (see [`stream_to_file`]), and the blob id, with `verify` also the content
+/// hash the node reports, is checked as it is written. `limiter` applies the
+/// client's adaptive limit, and a failed download counts against it; the
+/// caller bounds how many downloads run at once. Callers must not start two
+/// downloads of the same id into one directory, as they would share the
+/// `.part` file.
+#[allow(clippy::too_many_arguments)]
+pub async fn download_blob_to_dir(
+    client: Arc<Client<CliAuthenticator, MeroboxFileStorage>>,
+    connection: Arc<ConnectionInfo<CliAuthenticator, MeroboxFileStorage>>,
+    http_client: reqwest::Client,
+    storage: MeroboxFileStorage,
+    limiter: Arc<AdaptiveLimiter>,
+    blob_id: BlobId,
+    dest_dir: PathBuf,
+    verify: bool,
+) -> BlobDownloadOutcome {
+    let slot = limiter.acquire().await;
+
+    let result: eyre::Result<(PathBuf, u64)> = async {
+        let expected = if verify {
+            Some(fetch_expected_hash(&client, &blob_id).await?)
+        } else {
+            None
+        };
+        let response = http::get_from(
+            &http_client,
+            &connection.api_url,
+            &format!("admin-api/blobs/{blob_id}"),
+            &storage,
+            connection.node_name.as_deref(),
+            0,
+        )
+        .await?
+        .map(|(response, _)| response);
+        let final_path = blob_dest_path(&dest_dir, &blob_id);
+        let size = stream_to_file(
+            response,
+            0,
+            &partial_path(&final_path),
+            &final_path,
+            Some(&blob_id),
+            expected.as_ref(),
+            &mut Progress::new(None, None),
+        )
+        .await?;
+
+        Ok((final_path, size))
+    }
+    .await;
+
+    match result {
+        Ok((path, size)) => BlobDownloadOutcome::succeeded(&blob_id, &path, size),
+        Err(e) => {
+            slot.failed();
+            BlobDownloadOutcome::failed(blob_id, e)
+        }
+    }
+}
+
+/// Suffix of the file a download to `dest_path` is written to until it
+/// completes; an interrupted download resumes from it.
+pub const PARTIAL_SUFFIX: &str = "part";
+
+/// `dest_path` with [`PARTIAL_SUFFIX`] appended (`blob.bin.part`).
+pub fn partial_path(dest_path: &Path) -> PathBuf {
+    let mut name = dest_path.as_os_str().to_owned();
+    name.push(".");
+    name.push(PARTIAL_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Result of a download to a file.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDownload {
+    pub blob_id: String,
+    pub path: String,
+    pub size: u64,
+    /// Bytes kept from an earlier, interrupted download.
+    pub resumed_from: u64,
+}
+
+/// Hash of the first `length` bytes already in `file`, for verifying a
+/// resumed download.
+async fn hash_prefix(file: &mut tokio::fs::File, length: u64) -> eyre::Result<BlobHasher> {
+    use tokio::io::AsyncReadExt;
+
+    let mut hasher = BlobHasher::new();
+    let mut remaining = length;
+    let mut buffer = vec![0; VERIFY_CHUNK_SIZE];
+    while remaining > 0 {
+        let want = buffer
+            .len()
+            .min(usize::try_from(remaining).unwrap_or(usize::MAX));
+        let read = file
+            .read(&mut buffer[..want])
+            .await
+            .wrap_err("Failed to read partial download")?;
+        if read == 0 {
+            eyre::bail!("Partial download is shorter than expected");
+        }
+        hasher.update(&buffer[..read]);
+        remaining -= read as u64;
+    }
+    Ok(hasher)
+}
+
+/// Stream `response`, whose body starts at byte `start` of the blob, into
+/// `part_path` and rename it to `dest_path` once complete.
+///
+/// Bytes before `start` are kept from an earlier attempt (a `start` of `0`
+/// truncates the file). Each chunk goes straight to disk, so memory use
+/// does not grow with the blob. With `blob_id` or `expected`, the whole
+/// file is hashed and a mismatch deletes it, since resuming from corrupt
+/// bytes would only reproduce them. `response` of `None` means the partial
+/// file already holds the whole blob.
+pub async fn stream_to_file(
+    response: Option<reqwest::Response>,
+    start: u64,
+    part_path: &Path,
+    dest_path: &Path,
+    blob_id: Option<&BlobId>,
+    expected: Option<&Hash>,
+    progress: &mut Progress,
+) -> eyre::Result<u64> {
+    let result: eyre::Result<u64> = async {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .truncate(start == 0)
+            .read(true)
+            .write(true)
+            .open(part_path)
+            .await
+            .wrap_err_with(|| format!("Failed to open {:?}", part_path))?;
+        let mut hasher = match blob_id.is_some() || expected.is_some() {
+            true => Some(hash_prefix(&mut file, start).await?),
+            false => None,
+        };
+        file.set_len(start)
+            .await
+            .wrap_err_with(|| format!("Failed to truncate {:?}", part_path))?;
+        tokio::io::AsyncSeekExt::seek(&mut file, std::io::SeekFrom::Start(start))
+            .await
+            .wrap_err_with(|| format!("Failed to seek {:?}", part_path))?;
+
+        let mut written = start;
+        if let Some(mut response) = response {
+            if progress.total.is_none() {
+                progress.set_total(response.content_length().map(|length| start + length));
+            }
+            progress.report(written);
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .wrap_err("Failed to read blob download")?
+            {
+                if let Some(hasher) = hasher.as_mut() {
+                    hasher.update(&chunk);
+                }
+                file.write_all(&chunk)
+                    .await
+                    .wrap_err_with(|| format!("Failed to write blob to {:?}", part_path))?;
+                written += chunk.len() as u64;
+                progress.report(written);
+            }
+        }
+        file.sync_all()
+            .await
+            .wrap_err_with(|| format!("Failed to sync {:?}", part_path))?;
+
+        if let Some(hasher) = hasher {
+            if let Err(e) = hasher.finish().check(blob_id, expected) {
+                let _ = tokio::fs::remove_file(part_path).await;
+                return Err(e);
+            }
+        }
+        Ok(written)
+    }
+    .await;
+
+    let written = result?;
+    tokio::fs::rename(part_path, dest_path)
+        .await
+        .wrap_err_with(|| format!("Failed to rename {:?} to {:?}", part_path, dest_path))?;
+    Ok(written)
+}
+
+// ============================================================================
+// Content hash verification
+// ============================================================================
+
+/// SHA-256 content hash of `data`, in the node's `Hash` representation.
+pub fn content_hash(data: &[u8]) -> Hash {
+    let digest: [u8; 32] = Sha256::digest(data).into();
+    Hash::from(digest)
+}
+
+/// Blob id and content hash computed incrementally, the way the node's blob
+/// store does: content is cut into [`BLOB_CHUNK_SIZE`] chunks, and the id is
+/// the SHA-256 of the chunks' SHA-256 digests in order. The content hash is
+/// the SHA-256 of the whole content.
+#[derive(Clone, Default)]
+pub struct BlobHasher {
+    content: Sha256,
+    chunks: Sha256,
+    chunk: Sha256,
+    chunk_len: usize,
+    size: u64,
+}
+
+/// Blob id, content hash and size of some content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobDigest {
+    pub blob_id: Hash,
+    pub hash: Hash,
+    pub size: u64,
+}
+
+impl BlobHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let take = (BLOB_CHUNK_SIZE - self.chunk_len).min(data.len());
+            self.chunk.update(&data[..take]);
+            self.content.update(&data[..take]);
+            self.chunk_len += take;
+            self.size += take as u64;
+            data = &data[take..];
+            if self.chunk_len == BLOB_CHUNK_SIZE {
+                self.close_chunk();
+            }
+        }
+    }
+
+    fn close_chunk(&mut self) {
+        let digest = std::mem::take(&mut self.chunk).finalize();
+        self.chunks.update(digest);
+        self.chunk_len = 0;
+    }
+
+    pub fn finish(mut self) -> BlobDigest {
+        if self.chunk_len > 0 {
+            self.close_chunk();
+        }
+        let blob_id: [u8; 32] = self.chunks.finalize().into();
+        let hash: [u8; 32] = self.content.finalize().into();
+        BlobDigest {
+            blob_id: Hash::from(blob_id),
+            hash: Hash::from(hash),
+            size: self.size,
+        }
+    }
+}
+
+impl BlobDigest {
+    pub fn of(data: &[u8]) -> Self {
+        let mut hasher = BlobHasher::new();
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    /// Digest of the file at `path`, read in chunks.
+    pub fn of_file(path: &Path) -> eyre::Result<Self> {
+        use std::io::Read;
+
+        let mut file =
+            fs::File::open(path).wrap_err_with(|| format!("Failed to open {:?}", path))?;
+        let mut hasher = BlobHasher::new();
+        let mut buffer = vec![0; VERIFY_CHUNK_SIZE];
+        loop {
+            let read = file
+                .read(&mut buffer)
+                .wrap_err_with(|| format!("Failed to read {:?}", path))?;
+            if read == 0 {
+                return Ok(hasher.finish());
+            }
+            hasher.update(&buffer[..read]);
+        }
+    }
+
+    /// Whether the content is the blob `blob_id`.
+    pub fn matches(&self, blob_id: &BlobId) -> bool {
+        self.blob_id.to_string() == blob_id.to_string()
+    }
+
+    /// Fail unless the content is the blob `blob_id` and hashes to
+    /// `expected`, when given.
+    pub fn check(&self, blob_id: Option<&BlobId>, expected: Option<&Hash>) -> eyre::Result<()> {
+        if let Some(blob_id) = blob_id.filter(|blob_id| !self.matches(blob_id)) {
+            eyre::bail!(
+                "Blob id mismatch: expected {}, the content is {}",
+                blob_id,
+                self.blob_id
+            );
+        }
+        if let Some(expected) = expected.filter(|expected| **expected != self.hash) {
+            eyre::bail!(
+                "Blob hash mismatch: expected {}, got {}",
+                expected,
+                self.hash
+            );
+        }
+        Ok(())
+    }
+
+    /// `{blobId, hash, size}`
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "blobId": self.blob_id.to_string(),
+            "hash": self.hash.to_string(),
+            "size": self.size,
+        })
+    }
+}
+
+/// `{blobId, hash, size}` of the file at `path`: the id the node gives the
+/// file's content when it is uploaded, and its SHA-256.
+#[pyfunction]
+pub fn hash_blob(py: Python<'_>, path: PathBuf) -> PyResult<PyObject> {
+    let digest = py
+        .allow_threads(|| BlobDigest::of_file(&path))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e)))?;
+    Ok(json_to_python(py, &digest.to_json()))
+}
+
+/// Whether the file at `path` holds the content of blob `blob_id`.
+#[pyfunction]
+pub fn verify_blob(py: Python<'_>, path: PathBuf, blob_id: &str) -> PyResult<bool> {
+    let blob_id = blob_id.parse::<BlobId>().map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid blob ID '{}': {}",
+            blob_id, e
+        ))
+    })?;
+    let digest = py
+        .allow_threads(|| BlobDigest::of_file(&path))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e)))?;
+    Ok(digest.matches(&blob_id))
+}
+
+/// Extract the content hash from a serialized `get_blob_info` response.
+///
+/// Accepts the hash either as its string form or as a raw 32-byte array.
+pub fn expected_hash_from_info(info: &serde_json::Value) -> eyre::Result<Hash> {
+    match find_field(info, "hash") {
+        Some(serde_json::Value::String(raw)) => raw
+            .parse::<Hash>()
+            .map_err(|e| eyre::eyre!("Invalid content hash '{}' reported by node: {}", raw, e)),
+        Some(serde_json::Value::Array(items)) => {
+            let bytes: Vec<u8> = items
+                .iter()
+                .map(|v| v.as_u64().and_then(|b| u8::try_from(b).ok()))
+                .collect::<Option<_>>()
+                .ok_or_else(|| eyre::eyre!("Invalid content hash reported by node"))?;
+            let bytes: [u8; 32] = bytes
+                .try_into()
+                .map_err(|_| eyre::eyre!("Content hash reported by node must be 32 bytes"))?;
+            Ok(Hash::from(bytes))
+        }
+        _ => eyre::bail!("Node did not report a content hash for this blob"),
+    }
+}
+
+/// Ask the node for the content hash of `blob_id`.
+pub async fn fetch_expected_hash(
+    client: &Client<CliAuthenticator, MeroboxFileStorage>,
+    blob_id: &BlobId,
+) -> eyre::Result<Hash> {
+    let info = client.get_blob_info(blob_id).await?;
+    let info = serde_json::to_value(info).wrap_err("Failed to serialize blob info")?;
+    expected_hash_from_info(&info)
+}
+
+/// Read `response` into memory, hashing each chunk as it arrives.
+///
+/// Fails unless the content is the blob `blob_id` and, when given, hashes
+/// to `expected`, so the bytes are only handed back once verified without
+/// a second pass over them.
+pub async fn read_verified(
+    mut response: reqwest::Response,
+    blob_id: &BlobId,
+    expected: Option<&Hash>,
+) -> eyre::Result<Vec<u8>> {
+    let mut data = Vec::with_capacity(
+        response
+            .content_length()
+            .and_then(|length| usize::try_from(length).ok())
+            .unwrap_or(0)
+            .min(BLOB_CHUNK_SIZE),
+    );
+    let mut hasher = BlobHasher::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .wrap_err("Failed to read blob download")?
+    {
+        hasher.update(&chunk);
+        data.extend_from_slice(&chunk);
+    }
+    hasher.finish().check(Some(blob_id), expected)?;
+    Ok(data)
+}
+
+// ============================================================================
+// Streaming upload sources
+// ============================================================================
+
+/// Default size of the chunks read from Python upload sources.
+pub const DEFAULT_UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// A Python object blob bytes can be pulled from chunk by chunk.
+pub enum UploadSource {
+    /// Anything with a `read(size)` method (files, `io.BytesIO`, sockets).
+    Reader(PyObject),
+    /// A synchronous iterator yielding bytes-like chunks.
+    Iter(PyObject),
+    /// An asynchronous iterator yielding bytes-like chunks. Each `__anext__`
+    /// is driven on a private event loop owned by the reader thread, so the
+    /// iterator must not depend on resources bound to another running loop.
+    AsyncIter {
+        iter: PyObject,
+        event_loop: PyObject,
+    },
+}
+
+impl UploadSource {
+    /// Classify `source`, preferring `read()` over iteration so file objects
+    /// are read in `chunk_size` pieces rather than line by line. Paths
+    /// (`str` or `os.PathLike`) are opened for reading and bytes-like
+    /// objects read through `io.BytesIO`.
+    pub fn from_py(source: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let py = source.py();
+        if let Some(path) = source_path(source)? {
+            let file = py
+                .import_bound("builtins")?
+                .call_method1("open", (path, "rb"))?;
+            return Ok(Self::Reader(file.unbind()));
+        }
+        if is_bytes_like(source) {
+            let reader = py.import_bound("io")?.call_method1("BytesIO", (source,))?;
+            return Ok(Self::Reader(reader.unbind()));
+        }
+        if source.hasattr("read")? {
+            return Ok(Self::Reader(source.clone().unbind()));
+        }
+        if source.hasattr("__aiter__")? {
+            let iter = source.call_method0("__aiter__")?.unbind();
+            let event_loop = py
+                .import_bound("asyncio")?
+                .call_method0("new_event_loop")?
+                .unbind();
+            return Ok(Self::AsyncIter { iter, event_loop });
+        }
+        match source.iter() {
+            Ok(iter) => Ok(Self::Iter(iter.into_any().unbind())),
+            Err(_) => Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "Upload source must be a file-like object, an iterable of bytes, \
+                 or an async iterable of bytes",
+            )),
+        }
+    }
+
+    /// Pull the next chunk, or `None` once the source is exhausted.
+    pub fn next_chunk(&self, py: Python<'_>, chunk_size: usize) -> PyResult<Option<Vec<u8>>> {
+        let chunk = match self {
+            Self::Reader(reader) => reader.call_method1(py, "read", (chunk_size,))?,
+            Self::Iter(iter) => match iter.bind(py).call_method0("__next__") {
+                Ok(chunk) => chunk.unbind(),
+                Err(e) if e.is_instance_of::<pyo3::exceptions::PyStopIteration>(py) => {
+                    return Ok(None)
+                }
+                Err(e) => return Err(e),
+            },
+            Self::AsyncIter { iter, event_loop } => {
+                let awaitable = iter.call_method0(py, "__anext__")?;
+                match event_loop.call_method1(py, "run_until_complete", (awaitable,)) {
+                    Ok(chunk) => chunk,
+                    Err(e) if e.is_instance_of::<pyo3::exceptions::PyStopAsyncIteration>(py) => {
+                        return Ok(None)
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        };
+
+        let chunk = chunk.bind(py);
+        let bytes = match chunk.downcast::<PyBytes>() {
+            Ok(bytes) => bytes.as_bytes().to_vec(),
+            // bytearray / memoryview and friends
+            Err(_) => py
+                .get_type_bound::<PyBytes>()
+                .call1((chunk,))?
+                .downcast_into::<PyBytes>()?
+                .as_bytes()
+                .to_vec(),
+        };
+        // A reader signals EOF with an empty read; iterators may legitimately
+        // yield empty chunks, which are simply skipped.
+        if bytes.is_empty() && matches!(self, Self::Reader(_)) {
+            return Ok(None);
+        }
+        Ok(Some(bytes))
+    }
+
+    /// Release resources held by the source (the private event loop).
+    pub fn close(&self, py: Python<'_>) {
+        if let Self::AsyncIter { event_loop, .. } = self {
+            let _ = event_loop.call_method0(py, "close");
+        }
+    }
+}
+
+fn is_bytes_like(source: &Bound<'_, PyAny>) -> bool {
+    source.is_instance_of::<PyBytes>()
+        || source.is_instance_of::<pyo3::types::PyByteArray>()
+        || source.is_instance_of::<pyo3::types::PyMemoryView>()
+}
+
+/// The filesystem path `source` names, if it is a `str` or `os.PathLike`.
+fn source_path(source: &Bound<'_, PyAny>) -> PyResult<Option<PathBuf>> {
+    if source.is_instance_of::<pyo3::types::PyString>() || source.hasattr("__fspath__")? {
+        return Ok(Some(source.extract::<PathBuf>()?));
+    }
+    Ok(None)
+}
+
+/// Total size of an upload source when it can be known up front: the
+/// length of bytes and of files named by path.
+pub fn source_size(source: &Bound<'_, PyAny>) -> PyResult<Option<u64>> {
+    if let Some(path) = source_path(source)? {
+        return Ok(fs::metadata(&path).ok().map(|meta| meta.len()));
+    }
+    if is_bytes_like(source) {
+        return Ok(Some(source.len()? as u64));
+    }
+    Ok(None)
+}
+
+/// Progress callback of a transfer: `on_progress(bytes_done, total)`, with
+/// `total` `None` when unknown. Exceptions it raises are printed and
+/// otherwise ignored, so a broken progress bar never fails a transfer.
+pub struct Progress {
+    callback: Option<PyObject>,
+    total: Option<u64>,
+}
+
+impl Progress {
+    pub fn new(callback: Option<PyObject>, total: Option<u64>) -> Self {
+        Self { callback, total }
+    }
+
+    pub fn set_total(&mut self, total: Option<u64>) {
+        self.total = total;
+    }
+
+    pub fn report(&self, done: u64) {
+        if let Some(callback) = &self.callback {
+            Python::with_gil(|py| {
+                if let Err(e) = callback.call1(py, (done, self.total)) {
+                    e.print(py);
+                }
+            });
+        }
+    }
+}
+
+/// Turn an [`UploadSource`] into a request body.
+///
+/// A blocking task pulls chunks from Python (taking the GIL per chunk) and
+/// feeds them through a bounded channel, so at most a couple of chunks are
+/// buffered regardless of the total size. `progress` is told the bytes
+/// handed to the request so far. The caller must not hold the GIL while
+/// the body is being sent.
+pub fn upload_body(source: UploadSource, chunk_size: usize, progress: Progress) -> reqwest::Body {
+    let (tx, rx) = tokio::sync::mpsc::channel::<std::io::Result<Vec<u8>>>(2);
+
+    tokio::task::spawn_blocking(move || {
+        let mut sent: u64 = 0;
+        loop {
+            let next = Python::with_gil(|py| source.next_chunk(py, chunk_size));
+            let item = match next {
+                Ok(Some(chunk)) if chunk.is_empty() => continue,
+                Ok(Some(chunk)) => {
+                    sent += chunk.len() as u64;
+                    Ok(chunk)
+                }
+                Ok(None) => break,
+                Err(e) => Err(std::io::Error::other(format!(
+                    "Failed to read upload source: {}",
+                    e
+                ))),
+            };
+            let failed = item.is_err();
+            if tx.blocking_send(item).is_err() || failed {
+                break;
+            }
+            progress.report(sent);
+        }
+        Python::with_gil(|py| source.close(py));
+    });
+
+    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    });
+    reqwest::Body::wrap_stream(stream)
+}
+
+// ============================================================================
+// Resumable uploads
+// ============================================================================
+
+/// Default size of the chunks a resumable upload sends per request.
+pub const DEFAULT_RESUMABLE_CHUNK_SIZE: usize = 4 * BLOB_CHUNK_SIZE;
+
+/// Where a resumable upload stands, as the node reports it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UploadState {
+    /// The node holds the first `offset` bytes.
+    Partial(u64),
+    /// The node holds the whole blob; its upload response.
+    Complete(serde_json::Value),
+}
+
+impl UploadState {
+    /// State in a reply of the upload endpoint: the blob once it is
+    /// complete, else `{offset}`. A `409` (a chunk that does not start
+    /// where the node's bytes end) carries the offset to continue from.
+    pub fn from_reply(status: reqwest::StatusCode, body: &[u8]) -> eyre::Result<Self> {
+        if !status.is_success() && status != reqwest::StatusCode::CONFLICT {
+            eyre::bail!(
+                "Upload request failed with status {}: {}",
+                status,
+                String::from_utf8_lossy(body)
+            );
+        }
+        let reply: serde_json::Value =
+            serde_json::from_slice(body).wrap_err("Failed to decode upload response")?;
+        if find_string_field(&reply, "blobId").is_some() {
+            return Ok(Self::Complete(reply));
+        }
+        find_field(&reply, "offset")
+            .and_then(serde_json::Value::as_u64)
+            .map(Self::Partial)
+            .ok_or_else(|| eyre::eyre!("Upload response has neither a blobId nor an offset"))
+    }
+}
+
+/// Upload the file at `path`, whose digest is `digest`, in `chunk_size`
+/// pieces through `admin-api/blobs/uploads/<blob id>`.
+///
+/// The node is first asked how much of the upload it already holds and
+/// the file is sent from there, one `PUT ?offset=&total=` per chunk; each
+/// reply says where the next chunk starts. Chunks are addressed by offset,
+/// so a failed one is retried per `retry` like any idempotent call, and an
+/// upload abandoned halfway resumes from the node's offset the next time.
+/// `progress` sees the bytes the node holds. `Ok(None)` means the node has
+/// no resumable upload endpoint (`404`/`405`) and nothing was sent.
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_resumable(
+    client: &reqwest::Client,
+    api_url: &url::Url,
+    storage: &MeroboxFileStorage,
+    node_name: Option<&str>,
+    path: &Path,
+    digest: &BlobDigest,
+    chunk_size: usize,
+    retry: (&RetryConfig, &RetryBudget),
+    progress: &mut Progress,
+) -> eyre::Result<Option<serde_json::Value>> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let session = format!("admin-api/blobs/uploads/{}", digest.blob_id);
+    let reply = http::send_raw(
+        client,
+        reqwest::Method::GET,
+        api_url,
+        &session,
+        storage,
+        node_name,
+        reqwest::header::HeaderMap::new(),
+        None,
+        None,
+    )
+    .await?;
+    if matches!(reply.status.as_u16(), 404 | 405) {
+        return Ok(None);
+    }
+    let mut state = UploadState::from_reply(reply.status, &reply.body)?;
+
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .wrap_err_with(|| format!("Failed to open {:?}", path))?;
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        reqwest::header::CONTENT_TYPE,
+        reqwest::header::HeaderValue::from_static("application/octet-stream"),
+    );
+    let mut buffer = vec![0; chunk_size];
+    progress.set_total(Some(digest.size));
+    loop {
+        let offset = match state {
+            UploadState::Complete(blob) => {
+                progress.report(digest.size);
+                return Ok(Some(blob));
+            }
+            UploadState::Partial(offset) => offset,
+        };
+        if offset >= digest.size && digest.size > 0 {
+            eyre::bail!(
+                "Node holds {} of {} bytes but did not complete the upload",
+                offset,
+                digest.size
+            );
+        }
+        progress.report(offset);
+        let length = usize::try_from(digest.size - offset)
+            .unwrap_or(usize::MAX)
+            .min(chunk_size);
+        file.seek(std::io::SeekFrom::Start(offset))
+            .await
+            .wrap_err_with(|| format!("Failed to seek {:?}", path))?;
+        file.read_exact(&mut buffer[..length])
+            .await
+            .wrap_err_with(|| format!("Failed to read {:?}; did it change?", path))?;
+        let chunk = &buffer[..length];
+        let request = format!("{}?offset={}&total={}", session, offset, digest.size);
+        state = retry::with_retries(retry.0, retry.1, true, || async {
+            let reply = http::send_raw(
+                client,
+                reqwest::Method::PUT,
+                api_url,
+                &request,
+                storage,
+                node_name,
+                headers.clone(),
+                Some(chunk.to_vec()),
+                None,
+            )
+            .await?;
+            UploadState::from_reply(reply.status, &reply.body)
+        })
+        .await?;
+    }
+}
+
+/// A request body streaming the file at `path` in `chunk_size` pieces, for
+/// nodes without resumable uploads, and a channel of the bytes read so far.
+/// The body is polled by the connection task, which cannot take the GIL
+/// the caller holds, so progress goes back to the caller over the channel.
+pub async fn file_body(
+    path: &Path,
+    chunk_size: usize,
+) -> eyre::Result<(reqwest::Body, tokio::sync::mpsc::UnboundedReceiver<u64>)> {
+    use tokio::io::AsyncReadExt;
+
+    let file = tokio::fs::File::open(path)
+        .await
+        .wrap_err_with(|| format!("Failed to open {:?}", path))?;
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let stream = futures_util::stream::try_unfold((file, 0u64), move |(mut file, read_so_far)| {
+        let tx = tx.clone();
+        async move {
+            let mut chunk = vec![0; chunk_size];
+            let read = file.read(&mut chunk).await?;
+            if read == 0 {
+                return Ok::<_, std::io::Error>(None);
+            }
+            chunk.truncate(read);
+            let read_so_far = read_so_far + read as u64;
+            let _ = tx.send(read_so_far);
+            Ok(Some((chunk, (file, read_so_far))))
+        }
+    });
+    Ok((reqwest::Body::wrap_stream(stream), rx))
+}
+
+// ============================================================================
+// Blob metadata sidecars
+// ============================================================================
+
+/// Content type and user metadata attached to a blob at upload time.
+///
+/// The node stores blob bytes only, so this travels in a JSON sidecar under
+/// `<state root>/blob_meta/<blob_id>.json` and is merged back into
+/// `get_blob_info` / `download_blob(..., with_metadata=True)` results. The
+/// sidecar is local to this machine: other clients of the same node never
+/// see it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// Envelope algorithm when the blob was encrypted client-side.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<String>,
+}
+
+impl BlobMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.content_type.is_none() && self.metadata.is_empty() && self.encryption.is_none()
+    }
+}
+
+/// Directory holding blob metadata sidecars: `<state root>/blob_meta/`.
+pub fn blob_metadata_dir() -> PathBuf {
+    state_root().join(BLOB_METADATA_SUBDIR)
+}
+
+/// Sidecar path for `blob_id`, which is parsed first so that an id
+/// reported by the node can never name a file outside the directory.
+fn blob_metadata_path(blob_id: &str) -> eyre::Result<PathBuf> {
+    let blob_id = blob_id
+        .parse::<BlobId>()
+        .map_err(|e| eyre::eyre!("Invalid blob ID '{}': {}", blob_id, e))?;
+    Ok(blob_metadata_dir().join(format!("{}.json", blob_id)))
+}
+
+/// Persist the sidecar for `blob_id`, replacing any previous one.
+pub fn save_blob_metadata(blob_id: &str, metadata: &BlobMetadata) -> eyre::Result<()> {
+    let dir = blob_metadata_dir();
+    fs::create_dir_all(&dir)
+        .wrap_err_with(|| format!("Failed to create blob metadata directory: {:?}", dir))?;
+
+    let path = blob_metadata_path(blob_id)?;
+    let temp_path = path.with_extension(format!("json.{}.tmp", entropy::temp_suffix()));
+    let json = serde_json::to_string_pretty(metadata)
+        .wrap_err("Failed to serialize blob metadata to JSON")?;
+    fs::write(&temp_path, json)
+        .wrap_err_with(|| format!("Failed to write temp file: {:?}", temp_path))?;
+    fs::rename(&temp_path, &path)
+        .wrap_err_with(|| format!("Failed to rename {:?} to {:?}", temp_path, path))
+}
+
+/// Load the sidecar for `blob_id`, or `Ok(None)` if none was recorded.
+pub fn load_blob_metadata(blob_id: &str) -> eyre::Result<Option<BlobMetadata>> {
+    let path = blob_metadata_path(blob_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = fs::read_to_string(&path)
+        .wrap_err_with(|| format!("Failed to read blob metadata: {:?}", path))?;
+    let metadata = serde_json::from_str(&json)
+        .wrap_err_with(|| format!("Failed to parse blob metadata: {:?}", path))?;
+    Ok(Some(metadata))
+}
+
+/// Remove the sidecar for `blob_id` if one exists.
+pub fn remove_blob_metadata(blob_id: &str) -> eyre::Result<()> {
+    let path = blob_metadata_path(blob_id)?;
+    if path.exists() {
+        fs::remove_file(&path)
+            .wrap_err_with(|| format!("Failed to remove blob metadata: {:?}", path))?;
+    }
+    Ok(())
+}
+
+/// Find the first value stored under `key` anywhere in `value`.
+pub fn find_field<'a>(value: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+    match value {
+        serde_json::Value::Object(obj) => obj
+            .get(key)
+            .or_else(|| obj.values().find_map(|v| find_field(v, key))),
+        serde_json::Value::Array(items) => items.iter().find_map(|v| find_field(v, key)),
+        _ => None,
+    }
+}
+
+/// Find the first string value stored under `key` anywhere in `value`.
+///
+/// Used to pull the `blobId` out of upload responses without depending on
+/// the exact envelope shape.
+pub fn find_string_field<'a>(value: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    find_field(value, key).and_then(|v| v.as_str())
+}
+
+/// Merge a sidecar into a response's `data` object (or the root object when
+/// there is no `data` envelope).
+pub fn merge_blob_metadata(value: &mut serde_json::Value, metadata: &BlobMetadata) {
+    let target = match value {
+        serde_json::Value::Object(obj) if obj.get("data").is_some_and(|d| d.is_object()) => {
+            obj.get_mut("data")
+        }
+        other => Some(other),
+    };
+    if let Some(serde_json::Value::Object(obj)) = target {
+        if let Some(content_type) = &metadata.content_type {
+            obj.insert(
+                "contentType".to_string(),
+                serde_json::Value::String(content_type.clone()),
+            );
+        }
+        if !metadata.metadata.is_empty() {
+            obj.insert(
+                "metadata".to_string(),
+                serde_json::to_value(&metadata.metadata).unwrap_or_default(),
+            );
+        }
+        if let Some(encryption) = &metadata.encryption {
+            obj.insert(
+                "encryption".to_string(),
+                serde_json::Value::String(encryption.clone()),
+            );
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Upload replies are the blob once complete, else the offset held;
+    /// a conflict reports the offset and other failures carry the status.
+    #[test]
+    fn test_upload_state_from_reply() {
+        use reqwest::StatusCode;
+
+        let ok = |body: &str| UploadState::from_reply(StatusCode::OK, body.as_bytes());
+        assert_eq!(
+            ok(r#"{"offset": 4096}"#).unwrap(),
+            UploadState::Partial(4096)
+        );
+        assert!(matches!(
+            ok(r#"{"data": {"blobId": "abc", "size": 3}}"#).unwrap(),
+            UploadState::Complete(_)
+        ));
+        assert!(ok("{}").is_err());
+        let conflict = UploadState::from_reply(StatusCode::CONFLICT, br#"{"offset": 10}"#);
+        assert_eq!(conflict.unwrap(), UploadState::Partial(10));
+        let failed = UploadState::from_reply(StatusCode::SERVICE_UNAVAILABLE, b"busy");
+        assert!(failed.unwrap_err().to_string().contains("status 503"));
+    }
+
+    /// The blob id is found regardless of envelope depth.
+    #[test]
+    fn test_find_string_field_nested() {
+        let value = json!({"data": {"blobId": "abc", "size": 3}});
+        assert_eq!(find_string_field(&value, "blobId"), Some("abc"));
+        assert_eq!(find_string_field(&value, "missing"), None);
+    }
+
+    /// Sidecar paths are built from parsed ids, so traversal is rejected.
+    #[test]
+    fn test_blob_metadata_path_rejects_traversal() {
+        let id = "11111111111111111111111111111111";
+        assert_eq!(
+            blob_metadata_path(id).unwrap(),
+            blob_metadata_dir().join(format!("{}.json", id))
+        );
+        assert!(blob_metadata_path("../../tokens/node").is_err());
+        assert!(blob_metadata_path("a/b").is_err());
+    }
+
+    /// Sidecar fields are merged into the `data` envelope.
+    #[test]
+    fn test_merge_blob_metadata_into_data() {
+        let mut value = json!({"data": {"blobId": "abc"}});
+        let metadata = BlobMetadata {
+            content_type: Some("image/png".to_string()),
+            metadata: BTreeMap::from([("name".to_string(), "logo.png".to_string())]),
+            encryption: None,
+        };
+
+        merge_blob_metadata(&mut value, &metadata);
+
+        assert_eq!(
+            value,
+            json!({"data": {
+                "blobId": "abc",
+                "contentType": "image/png",
+                "metadata": {"name": "logo.png"}
+            }})
+        );
+    }
+
+    /// A download is returned only when it is the blob it was asked for.
+    #[tokio::test]
+    async fn test_read_verified_checks_chunks() {
+        let data = vec![7u8; VERIFY_CHUNK_SIZE * 2 + 1];
+        let digest = BlobDigest::of(&data);
+        let blob_id = digest.blob_id.to_string().parse::<BlobId>().unwrap();
+        let response = |data: &[u8]| reqwest::Response::from(::http::Response::new(data.to_vec()));
+
+        let read = read_verified(response(&data), &blob_id, Some(&digest.hash))
+            .await
+            .unwrap();
+        assert_eq!(read, data);
+
+        let err = read_verified(response(&data), &blob_id, Some(&content_hash(b"other")))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("hash mismatch"));
+
+        let err = read_verified(response(b"other"), &blob_id, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("id mismatch"));
+    }
+
+    /// A complete partial file is verified as a whole and moved into
+    /// place; a corrupt one is deleted rather than resumed from.
+    #[tokio::test]
+    async fn test_stream_to_file_finishes_partial_download() {
+        let dir = std::env::temp_dir().join(format!("blob-resume-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dest = dir.join("blob.bin");
+        let part = partial_path(&dest);
+        assert_eq!(part, dir.join("blob.bin.part"));
+        let data = vec![3u8; VERIFY_CHUNK_SIZE + 5];
+        let mut progress = Progress::new(None, None);
+
+        std::fs::write(&part, &data).unwrap();
+        let size = stream_to_file(
+            None,
+            data.len() as u64,
+            &part,
+            &dest,
+            None,
+            Some(&content_hash(&data)),
+            &mut progress,
+        )
+        .await
+        .unwrap();
+        assert_eq!(size, data.len() as u64);
+        assert_eq!(std::fs::read(&dest).unwrap(), data);
+        assert!(!part.exists());
+
+        std::fs::write(&part, &data).unwrap();
+        let err = stream_to_file(
+            None,
+            data.len() as u64,
+            &part,
+            &dir.join("other.bin"),
+            None,
+            Some(&content_hash(b"other")),
+            &mut progress,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("hash mismatch"));
+        assert!(!part.exists(), "corrupt partial download should be removed");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Ids cover every chunk in order; hashing in pieces changes nothing.
+    #[test]
+    fn test_blob_digest_chunks() {
+        let data: Vec<u8> = (0..BLOB_CHUNK_SIZE * 2 + 7).map(|i| i as u8).collect();
+        let digest = BlobDigest::of(&data);
+        assert_eq!(digest.size, data.len() as u64);
+        assert_eq!(digest.hash, content_hash(&data));
+
+        let mut chunks = Sha256::new();
+        for chunk in data.chunks(BLOB_CHUNK_SIZE) {
+            chunks.update(Sha256::digest(chunk));
+        }
+        let blob_id: [u8; 32] = chunks.finalize().into();
+        assert_eq!(digest.blob_id, Hash::from(blob_id));
+
+        let mut hasher = BlobHasher::new();
+        for piece in data.chunks(4093) {
+            hasher.update(piece);
+        }
+        assert_eq!(hasher.finish(), digest);
+
+        let blob_id: BlobId = digest.blob_id.to_string().parse().unwrap();
+        assert!(digest.check(Some(&blob_id), Some(&digest.hash)).is_ok());
+        let other = BlobDigest::of(b"other");
+        let err = other.check(Some(&blob_id), None).unwrap_err();
+        assert!(err.to_string().contains("Blob id mismatch"));
+    }
+
+    /// Empty metadata serializes to an empty object and round trips.
+    #[test]
+    fn test_blob_metadata_roundtrip() {
+        let metadata = BlobMetadata::default();
+        assert!(metadata.is_empty());
+
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert_eq!(json, "{}");
+        let parsed: BlobMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, metadata);
+    }
+}
@@ -0,0 +1,212 @@
+//! Per-item results for bulk operations.
+//!
+//! Bulk calls never fail as a whole because one item did: each input gets a
+//! [`BulkItem`] recording its result or error, and the call returns a
+//! `BulkResult`. It still behaves like the list of per-item records bulk
+//! calls used to return, and adds `failed_inputs` plus `merge()` so only the
+//! failed subset has to be retried:
+//!
+//! ```python
+//! result = client.download_blobs(ids, dest)
+//! while not result.ok:
+//!     result = result.merge(client.download_blobs(result.failed_inputs, dest))
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use crate::utils::json_to_python;
+
+/// Result of one input of a bulk call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkItem {
+    /// The input as the caller passed it (blob id, method, ...).
+    pub input: String,
+    /// Per-item record returned to Python.
+    pub value: serde_json::Value,
+    pub error: Option<String>,
+}
+
+impl BulkItem {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Replace the failed items of `items` with retried ones for the same input,
+/// matched in order so duplicate inputs are paired up one to one. Items the
+/// retry did not cover keep their original failure.
+pub fn merge_retried(items: &[BulkItem], retried: &[BulkItem]) -> Vec<BulkItem> {
+    let mut by_input: HashMap<&str, VecDeque<&BulkItem>> = HashMap::new();
+    for item in retried {
+        by_input.entry(&item.input).or_default().push_back(item);
+    }
+    items
+        .iter()
+        .map(|item| {
+            if item.is_ok() {
+                return item.clone();
+            }
+            by_input
+                .get_mut(item.input.as_str())
+                .and_then(VecDeque::pop_front)
+                .unwrap_or(item)
+                .clone()
+        })
+        .collect()
+}
+
+/// Per-item outcome of a bulk call; iterates like a list of records.
+#[pyclass(name = "BulkResult")]
+#[derive(Debug, Clone)]
+pub struct PyBulkResult {
+    operation: String,
+    items: Vec<BulkItem>,
+}
+
+impl PyBulkResult {
+    pub fn new(operation: &str, items: Vec<BulkItem>) -> Self {
+        Self {
+            operation: operation.to_string(),
+            items,
+        }
+    }
+}
+
+/// Python list of the records of `items`.
+fn records<'a>(py: Python<'_>, items: impl Iterator<Item = &'a BulkItem>) -> PyObject {
+    let values: Vec<PyObject> = items.map(|item| json_to_python(py, &item.value)).collect();
+    PyList::new_bound(py, values).into_any().unbind()
+}
+
+#[pymethods]
+impl PyBulkResult {
+    /// Name of the bulk call that produced this result
+    #[getter]
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    /// Whether every item succeeded
+    #[getter]
+    pub fn ok(&self) -> bool {
+        self.items.iter().all(BulkItem::is_ok)
+    }
+
+    /// Records of the items that succeeded
+    #[getter]
+    pub fn succeeded(&self, py: Python<'_>) -> PyObject {
+        records(py, self.items.iter().filter(|item| item.is_ok()))
+    }
+
+    /// Records of the items that failed
+    #[getter]
+    pub fn failed(&self, py: Python<'_>) -> PyObject {
+        records(py, self.items.iter().filter(|item| !item.is_ok()))
+    }
+
+    /// Inputs of the failed items, in order — pass these to the same call
+    /// to retry just them
+    #[getter]
+    pub fn failed_inputs(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .filter(|item| !item.is_ok())
+            .map(|item| item.input.clone())
+            .collect()
+    }
+
+    /// `{input: error}` for every failed item
+    #[getter]
+    pub fn errors(&self) -> HashMap<String, String> {
+        self.items
+            .iter()
+            .filter_map(|item| Some((item.input.clone(), item.error.clone()?)))
+            .collect()
+    }
+
+    /// This result with its failed items replaced by those of `retried`
+    /// (the result of retrying `failed_inputs`)
+    pub fn merge(&self, retried: &PyBulkResult) -> Self {
+        Self::new(&self.operation, merge_retried(&self.items, &retried.items))
+    }
+
+    fn __len__(&self) -> usize {
+        self.items.len()
+    }
+
+    fn __getitem__(&self, py: Python<'_>, index: isize) -> PyResult<PyObject> {
+        let len = self.items.len() as isize;
+        let position = if index < 0 { index + len } else { index };
+        if !(0..len).contains(&position) {
+            return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(
+                "BulkResult index out of range",
+            ));
+        }
+        Ok(json_to_python(py, &self.items[position as usize].value))
+    }
+
+    fn __iter__(&self, py: Python<'_>) -> PyResult<PyObject> {
+        Ok(records(py, self.items.iter())
+            .bind(py)
+            .iter()?
+            .into_any()
+            .unbind())
+    }
+
+    /// Equal to another `BulkResult` with the same items, or to the list of
+    /// records it iterates over
+    fn __eq__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<bool> {
+        if let Ok(other) = other.extract::<PyRef<'_, PyBulkResult>>() {
+            return Ok(self.items == other.items);
+        }
+        records(py, self.items.iter()).bind(py).eq(other)
+    }
+
+    fn __repr__(&self) -> String {
+        let failed = self.items.iter().filter(|item| !item.is_ok()).count();
+        format!(
+            "BulkResult(operation='{}', succeeded={}, failed={})",
+            self.operation,
+            self.items.len() - failed,
+            failed
+        )
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(input: &str, error: Option<&str>) -> BulkItem {
+        BulkItem {
+            input: input.to_string(),
+            value: serde_json::json!({ "input": input, "error": error }),
+            error: error.map(str::to_string),
+        }
+    }
+
+    /// Failed slots take the retried item for their input, in order.
+    #[test]
+    fn test_merge_replaces_failed_items() {
+        let items = vec![
+            item("a", None),
+            item("b", Some("timeout")),
+            item("b", Some("timeout")),
+            item("c", Some("gone")),
+        ];
+        let retried = vec![item("b", None), item("b", Some("still down"))];
+
+        let merged = merge_retried(&items, &retried);
+        assert_eq!(merged[0], items[0]);
+        assert!(merged[1].is_ok());
+        assert_eq!(merged[2].error.as_deref(), Some("still down"));
+        assert_eq!(merged[3], items[3]);
+    }
+}
@@ -1,9 +1,41 @@
 //! Token cache path utilities
 //!
 //! Provides functions for deriving stable, collision-resistant cache file paths
-//! for JWT tokens stored in ~/.merobox/auth_cache/
+//! for JWT tokens stored in `<state root>/auth_cache/`.
+//!
+//! The token directory of a connection is the first of:
+//!
+//! 1. the connection's `cache_dir`
+//! 2. `$MEROBOX_CACHE_DIR`, naming the token directory itself
+//! 3. `auth_cache/` under the state root
+//!
+//! and the state root (also holding `blob_meta/` and the layout version)
+//! the first of:
+//!
+//! 1. `$CALIMERO_CACHE_DIR`
+//! 2. `$XDG_CACHE_HOME/calimero`, or when it is unset `~/.cache/calimero`
+//!    (`%LOCALAPPDATA%\calimero` on Windows)
+//! 3. `~/.merobox/`, only while its state cannot be copied to 2
+//!
+//! So with both variables set, tokens go to `$MEROBOX_CACHE_DIR` and the
+//! rest to `$CALIMERO_CACHE_DIR`. [`migrate_token_cache`] moves existing
+//! token files to the token directory.
+//!
+//! Clients created with a `namespace` (a tenant of a service acting for many
+//! end users) keep their token files in `namespaces/<namespace>/` under the
+//! token cache directory, so no tenant's file name can collide with
+//! another's and a tenant is purged by clearing its directory.
+//!
+//! Installs from before used `~/.merobox/`. The first lookup in a process
+//! copies that state to the XDG location, leaving the originals for tools
+//! that still read them (they no longer see logins made since). The copy is
+//! adopted all at once: `auth_cache/` is copied under a temporary name and
+//! renamed into place last, and if anything fails the legacy directory
+//! keeps being used.
 
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use pyo3::prelude::*;
 use sha2::{Digest, Sha256};
@@ -12,21 +44,198 @@ use sha2::{Digest, Sha256};
 // Constants
 // ============================================================================
 
-/// Default base directory for token cache: ~/.merobox/auth_cache/
+/// Subdirectory of the state root holding token files
 pub const AUTH_CACHE_SUBDIR: &str = "auth_cache";
+/// Legacy state root under the home directory
 pub const MEROBOX_DIR: &str = ".merobox";
+/// Directory name under the XDG base directories
+pub const APP_DIR: &str = "calimero";
+
+/// Subdirectory of the token cache directory holding one per namespace
+pub const NAMESPACES_SUBDIR: &str = "namespaces";
+/// Longest namespace name
+pub const MAX_NAMESPACE_LEN: usize = 64;
+
+/// Environment variable overriding the state root
+pub const CACHE_DIR_ENV: &str = "CALIMERO_CACHE_DIR";
+/// Environment variable overriding the token cache directory itself
+pub const TOKEN_CACHE_DIR_ENV: &str = "MEROBOX_CACHE_DIR";
+
+/// Entries of a state root, moved together from the legacy location.
+const STATE_ENTRIES: &[&str] = &[AUTH_CACHE_SUBDIR, "blob_meta", "layout_version.json"];
 
 // ============================================================================
 // Internal Functions
 // ============================================================================
 
+fn home_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Non-empty directory named by the environment variable `name`.
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// `$<xdg_var>/calimero`, or `<fallback>/calimero` when it is unset. Relative
+/// values are ignored, as the spec requires.
+fn xdg_dir(xdg_var: &str, fallback: PathBuf) -> PathBuf {
+    env_dir(xdg_var)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or(fallback)
+        .join(APP_DIR)
+}
+
+/// Cache base without `$XDG_CACHE_HOME`: `%LOCALAPPDATA%` on Windows,
+/// `~/.cache` elsewhere.
+fn default_cache_home() -> PathBuf {
+    #[cfg(windows)]
+    if let Some(dir) = dirs::data_local_dir() {
+        return dir;
+    }
+    home_dir().join(".cache")
+}
+
+/// Legacy state root: `~/.merobox/`
+pub fn legacy_root() -> PathBuf {
+    home_dir().join(MEROBOX_DIR)
+}
+
+/// Root of the client's persisted state (`auth_cache/`, `blob_meta/`, the
+/// layout version): `$CALIMERO_CACHE_DIR`, else `$XDG_CACHE_HOME/calimero`,
+/// with legacy `~/.merobox/` state moved there on first use.
+pub fn state_root() -> PathBuf {
+    if let Some(dir) = env_dir(CACHE_DIR_ENV) {
+        return dir;
+    }
+    static ROOT: OnceLock<PathBuf> = OnceLock::new();
+    ROOT.get_or_init(|| {
+        adopt_legacy_root(
+            &legacy_root(),
+            &xdg_dir("XDG_CACHE_HOME", default_cache_home()),
+        )
+    })
+    .clone()
+}
+
+/// Copy the state under `legacy` to `target` unless `target` already holds
+/// state, returning the root to use: `target`, or `legacy` when the copy
+/// failed. `target` counts as holding state once it has an `auth_cache/`,
+/// which is renamed into place after everything else was copied.
+pub fn adopt_legacy_root(legacy: &Path, target: &Path) -> PathBuf {
+    let pending: Vec<&str> = STATE_ENTRIES
+        .iter()
+        .copied()
+        .filter(|entry| {
+            fs::symlink_metadata(legacy.join(entry)).is_ok_and(|meta| !meta.is_symlink())
+        })
+        .collect();
+    if pending.is_empty() || target.join(AUTH_CACHE_SUBDIR).exists() {
+        return target.to_path_buf();
+    }
+    let staged = target.join(format!(
+        "{}.adopting-{}",
+        AUTH_CACHE_SUBDIR,
+        std::process::id()
+    ));
+    let adopted = (|| -> eyre::Result<()> {
+        fs::create_dir_all(target)?;
+        for entry in &pending {
+            let to = match *entry {
+                AUTH_CACHE_SUBDIR => staged.clone(),
+                _ => target.join(entry),
+            };
+            copy_state(&legacy.join(entry), &to)?;
+        }
+        fs::create_dir_all(&staged)?;
+        fs::rename(&staged, target.join(AUTH_CACHE_SUBDIR))?;
+        Ok(())
+    })();
+    if adopted.is_err() {
+        let _ = fs::remove_dir_all(&staged);
+        // Another process may have adopted it meanwhile
+        if !target.join(AUTH_CACHE_SUBDIR).exists() {
+            return legacy.to_path_buf();
+        }
+    }
+    target.to_path_buf()
+}
+
+/// Copy the file or directory tree `from` to `to`, owner-only, leaving out
+/// symlinks and in-flight temp and lock files.
+fn copy_state(from: &Path, to: &Path) -> eyre::Result<()> {
+    let meta = fs::symlink_metadata(from)?;
+    if meta.is_file() {
+        fs::copy(from, to)?;
+        return crate::permissions::restrict_to_owner(to, false);
+    }
+    if !meta.is_dir() {
+        return Ok(());
+    }
+    fs::create_dir_all(to)?;
+    crate::permissions::restrict_to_owner(to, true)?;
+    for entry in fs::read_dir(from)? {
+        let name = entry?.file_name();
+        let path = from.join(&name);
+        if path
+            .extension()
+            .is_some_and(|ext| ext == "tmp" || ext == "lock")
+        {
+            continue;
+        }
+        copy_state(&path, &to.join(&name))?;
+    }
+    Ok(())
+}
+
 /// Get the base directory for token cache files.
-/// Returns `~/.merobox/auth_cache/`
+/// Returns `$MEROBOX_CACHE_DIR`, else `<state root>/auth_cache/`
 pub fn get_cache_base_dir() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join(MEROBOX_DIR)
-        .join(AUTH_CACHE_SUBDIR)
+    env_dir(TOKEN_CACHE_DIR_ENV).unwrap_or_else(|| state_root().join(AUTH_CACHE_SUBDIR))
+}
+
+/// Move the files in `source` to `destination`, skipping names already
+/// present there and in-flight temp and lock files. Returns the names
+/// moved; nothing moves when both name the same directory.
+pub fn move_token_files(source: &Path, destination: &Path) -> eyre::Result<Vec<String>> {
+    use eyre::WrapErr;
+
+    let same_dir = match (fs::canonicalize(source), fs::canonicalize(destination)) {
+        (Ok(source), Ok(destination)) => source == destination,
+        _ => false,
+    };
+    if same_dir || !source.is_dir() {
+        return Ok(Vec::new());
+    }
+    fs::create_dir_all(destination)
+        .wrap_err_with(|| format!("Failed to create cache directory: {:?}", destination))?;
+    crate::permissions::restrict_to_owner(destination, true)?;
+
+    let mut entries: Vec<_> = fs::read_dir(source)
+        .wrap_err_with(|| format!("Failed to read {:?}", source))?
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+        .map(|entry| entry.file_name())
+        .collect();
+    entries.sort();
+
+    let mut moved = Vec::new();
+    for name in entries {
+        let path = source.join(&name);
+        if path
+            .extension()
+            .is_some_and(|ext| ext == "tmp" || ext == "lock")
+            || destination.join(&name).exists()
+        {
+            continue;
+        }
+        fs::rename(&path, destination.join(&name))
+            .wrap_err_with(|| format!("Failed to move {:?} to {:?}", path, destination))?;
+        moved.push(name.to_string_lossy().into_owned());
+    }
+    Ok(moved)
 }
 
 /// Sanitize a node name to create a safe filesystem slug.
@@ -67,20 +276,91 @@ pub fn derive_token_filename(node_name: &str) -> String {
 }
 
 /// Get the full path to the token cache file for a given node name.
-/// Returns the path as `~/.merobox/auth_cache/{slug}-{hash}.json`
+/// Returns the path as `<state root>/auth_cache/{slug}-{hash}.json`
 pub fn get_token_cache_path_internal(node_name: &str) -> PathBuf {
     get_cache_base_dir().join(derive_token_filename(node_name))
 }
 
+/// Derive the token cache filename for `user` on `node_name`.
+/// Format: `{node_slug[:48]}@{user_slug[:32]}-{sha256_hash[:12]}.json`
+///
+/// The hash covers both parts length-prefixed, so no (node, user) pair can
+/// collide with another pair or with a plain node-only filename.
+pub fn derive_user_token_filename(node_name: &str, user: &str) -> String {
+    let node_slug: String = sanitize_node_name(node_name).chars().take(48).collect();
+    let user_slug: String = sanitize_node_name(user).chars().take(32).collect();
+
+    let mut hasher = Sha256::new();
+    hasher.update((node_name.len() as u64).to_le_bytes());
+    hasher.update(node_name.as_bytes());
+    hasher.update(user.as_bytes());
+    let hash_hex = hex::encode(hasher.finalize());
+
+    format!("{}@{}-{}.json", node_slug, user_slug, &hash_hex[..12])
+}
+
+/// Token cache filename for `node_name`, scoped to `user` when given.
+pub fn user_token_filename(node_name: &str, user: Option<&str>) -> String {
+    match user {
+        Some(user) => derive_user_token_filename(node_name, user),
+        None => derive_token_filename(node_name),
+    }
+}
+
+/// Token cache path for `node_name`, scoped to `user` when given.
+pub fn get_user_token_cache_path(node_name: &str, user: Option<&str>) -> PathBuf {
+    get_cache_base_dir().join(user_token_filename(node_name, user))
+}
+
+/// Check that `namespace` can name a directory as is: 1 to
+/// [`MAX_NAMESPACE_LEN`] characters of `[A-Za-z0-9._-]`, not starting with
+/// a dot.
+pub fn validate_namespace(namespace: &str) -> Result<(), String> {
+    if namespace.is_empty() || namespace.len() > MAX_NAMESPACE_LEN {
+        return Err(format!(
+            "namespace must be 1 to {} characters long",
+            MAX_NAMESPACE_LEN
+        ));
+    }
+    if namespace.starts_with('.') || sanitize_node_name(namespace) != namespace {
+        return Err(format!(
+            "Invalid namespace '{}': use letters, digits, '.', '_' and '-', not starting with '.'",
+            namespace
+        ));
+    }
+    Ok(())
+}
+
+/// Directory of `namespace`'s token files under the token cache directory
+/// `cache_dir`.
+pub fn namespace_dir(cache_dir: &Path, namespace: &str) -> PathBuf {
+    cache_dir.join(NAMESPACES_SUBDIR).join(namespace)
+}
+
 // ============================================================================
 // Python-exposed Functions
 // ============================================================================
 
 /// Python-exposed function to get the token cache path for a given node name.
 /// This allows Python code (e.g., merobox) to write initial tokens to the correct location.
+/// With `user`, returns the path of that user's tokens for the node; with
+/// `namespace`, the path inside that namespace's directory.
 #[pyfunction]
-pub fn get_token_cache_path(node_name: &str) -> PyResult<String> {
-    let path = get_token_cache_path_internal(node_name);
+#[pyo3(signature = (node_name, user=None, namespace=None))]
+pub fn get_token_cache_path(
+    node_name: &str,
+    user: Option<&str>,
+    namespace: Option<&str>,
+) -> PyResult<String> {
+    let path = match namespace {
+        Some(namespace) => {
+            validate_namespace(namespace)
+                .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+            namespace_dir(&get_cache_base_dir(), namespace)
+                .join(user_token_filename(node_name, user))
+        }
+        None => get_user_token_cache_path(node_name, user),
+    };
     path.to_str().map(|s| s.to_string()).ok_or_else(|| {
         PyErr::new::<pyo3::exceptions::PyValueError, _>(
             "Token cache path contains invalid UTF-8 characters",
@@ -89,7 +369,7 @@ pub fn get_token_cache_path(node_name: &str) -> PyResult<String> {
 }
 
 /// Python-exposed function to get the base directory for token cache.
-/// Returns `~/.merobox/auth_cache/`
+/// Returns `<state root>/auth_cache/` (see the module docs)
 #[pyfunction]
 pub fn get_token_cache_dir() -> PyResult<String> {
     let path = get_cache_base_dir();
@@ -100,6 +380,29 @@ pub fn get_token_cache_dir() -> PyResult<String> {
     })
 }
 
+/// Move existing token files into the token cache directory.
+///
+/// `source` defaults to the legacy `~/.merobox/auth_cache/`, `destination`
+/// to `get_token_cache_dir()` (so set `MEROBOX_CACHE_DIR` first, or pass
+/// the `cache_dir` given to `create_connection`). Files already present in
+/// the destination are left where they are. Returns the moved file names;
+/// running it again moves nothing.
+#[pyfunction]
+#[pyo3(signature = (source=None, destination=None))]
+pub fn migrate_token_cache(
+    source: Option<PathBuf>,
+    destination: Option<PathBuf>,
+) -> PyResult<Vec<String>> {
+    let source = source.unwrap_or_else(|| legacy_root().join(AUTH_CACHE_SUBDIR));
+    let destination = destination.unwrap_or_else(get_cache_base_dir);
+    move_token_files(&source, &destination).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyOSError, _>(format!(
+            "Failed to migrate token cache: {:#}",
+            e
+        ))
+    })
+}
+
 // ============================================================================
 // Unit Tests
 // ============================================================================
@@ -237,19 +540,76 @@ mod tests {
     #[test]
     fn test_cache_base_dir() {
         let cache_dir = get_cache_base_dir();
-        let path_str = cache_dir.to_str().unwrap();
 
-        // Should contain .merobox and auth_cache
+        // Should be auth_cache under the state root
         assert!(
-            path_str.contains(".merobox"),
-            "Cache dir should contain .merobox"
+            cache_dir.ends_with(AUTH_CACHE_SUBDIR),
+            "Cache dir should end with auth_cache"
         );
         assert!(
-            path_str.contains("auth_cache"),
-            "Cache dir should contain auth_cache"
+            cache_dir.starts_with(state_root()),
+            "Cache dir should be under the state root"
         );
     }
 
+    /// Token files move once; temp files and existing names stay put.
+    #[test]
+    fn test_move_token_files() {
+        let base = std::env::temp_dir().join(format!("token-move-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        let source = base.join("old");
+        let destination = base.join("new");
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir_all(&destination).unwrap();
+        fs::write(source.join("a.json"), b"{}").unwrap();
+        fs::write(source.join("b.json"), b"old").unwrap();
+        fs::write(destination.join("b.json"), b"new").unwrap();
+        fs::write(source.join("c.json.1.tmp"), b"").unwrap();
+
+        assert_eq!(move_token_files(&source, &destination).unwrap(), ["a.json"]);
+        assert!(destination.join("a.json").exists());
+        assert_eq!(fs::read(destination.join("b.json")).unwrap(), b"new");
+        assert!(source.join("b.json").exists());
+        assert!(move_token_files(&source, &destination).unwrap().is_empty());
+        assert!(move_token_files(&destination, &destination)
+            .unwrap()
+            .is_empty());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    /// Legacy state is copied to the XDG root once, originals left in
+    /// place; a root with state is kept.
+    #[test]
+    fn test_adopt_legacy_root() {
+        let base = std::env::temp_dir().join(format!("xdg-adopt-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        let legacy = base.join(".merobox");
+        let target = base.join("cache").join(APP_DIR);
+        fs::create_dir_all(legacy.join(AUTH_CACHE_SUBDIR)).unwrap();
+        fs::write(legacy.join(AUTH_CACHE_SUBDIR).join("node.json"), b"{}").unwrap();
+        fs::write(legacy.join(AUTH_CACHE_SUBDIR).join("node.json.tmp"), b"{").unwrap();
+        fs::create_dir_all(legacy.join("blob_meta")).unwrap();
+        fs::write(legacy.join("blob_meta").join("b.json"), b"{}").unwrap();
+
+        assert_eq!(adopt_legacy_root(&legacy, &target), target);
+        assert!(target.join(AUTH_CACHE_SUBDIR).join("node.json").exists());
+        assert!(!target
+            .join(AUTH_CACHE_SUBDIR)
+            .join("node.json.tmp")
+            .exists());
+        assert!(target.join("blob_meta").join("b.json").exists());
+        assert!(legacy.join(AUTH_CACHE_SUBDIR).join("node.json").exists());
+        assert!(legacy.join("blob_meta").join("b.json").exists());
+        assert_eq!(fs::read_dir(&target).unwrap().count(), 2);
+
+        fs::remove_file(target.join(AUTH_CACHE_SUBDIR).join("node.json")).unwrap();
+        assert_eq!(adopt_legacy_root(&legacy, &target), target);
+        assert!(!target.join(AUTH_CACHE_SUBDIR).join("node.json").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
     /// Test that empty node name works (edge case).
     #[test]
     fn test_empty_node_name() {
@@ -288,4 +648,47 @@ mod tests {
             }
         }
     }
+
+    /// Per-user filenames differ per user and from the node-only file.
+    #[test]
+    fn test_user_token_filenames() {
+        let alice = derive_user_token_filename("node", "alice");
+        let bob = derive_user_token_filename("node", "bob");
+        assert_ne!(alice, bob);
+        assert_ne!(alice, derive_token_filename("node"));
+        assert!(alice.starts_with("node@alice-"));
+
+        // Moving the split point between node and user changes the hash.
+        assert_ne!(
+            derive_user_token_filename("ab", "c"),
+            derive_user_token_filename("a", "bc")
+        );
+        assert_eq!(
+            get_user_token_cache_path("node", None),
+            get_token_cache_path_internal("node")
+        );
+    }
+
+    /// Namespaces are plain directory names, never paths.
+    #[test]
+    fn test_validate_namespace() {
+        assert!(validate_namespace("tenant-42").is_ok());
+        assert!(validate_namespace("acme.prod_1").is_ok());
+        let long = "x".repeat(65);
+        for invalid in [
+            "",
+            "..",
+            ".hidden",
+            "a/b",
+            "a\\b",
+            "t\u{e9}nant",
+            long.as_str(),
+        ] {
+            assert!(validate_namespace(invalid).is_err(), "{:?}", invalid);
+        }
+        assert_eq!(
+            namespace_dir(Path::new("/cache"), "tenant-42"),
+            Path::new("/cache/namespaces/tenant-42")
+        );
+    }
 }
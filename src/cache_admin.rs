@@ -0,0 +1,462 @@
+//! Inspection and cleanup of the on-disk token cache (`TokenCache`).
+//!
+//! Token files are named `{node slug}-{hash}.json` (or
+//! `{node slug}@{user slug}-{hash}.json` for per-user sessions), so node
+//! names are recovered from the slug, which equals the name for the usual
+//! `[A-Za-z0-9._-]` names. Expiry is read from plaintext files (the stored
+//! `expires_at` or the access token's `exp` claim); encrypted files are
+//! listed but their expiry is unknown without the key, so they are never
+//! purged as expired.
+//!
+//! Deletions take the token file's cross-process lock (see
+//! [`crate::storage`]) so they never race a concurrent save.
+//!
+//! Token files of namespaced clients live in per-namespace directories (see
+//! [`crate::cache`]); listing and clearing the cache directory leaves them
+//! alone, and `purge_namespace()` deletes one namespace's files.
+//!
+//! A plaintext token file that no longer parses (a disk filling up, a
+//! hand edit gone wrong) is damaged. `load_tokens` moves such a file aside
+//! as `<name>.corrupt-<timestamp>` and carries on as if nothing was cached,
+//! so the client logs in again instead of failing on every call;
+//! `verify_cache()` finds (and optionally quarantines) them all at once.
+//! Encrypted files cannot be checked without their key and never count as
+//! damaged.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use calimero_client::JwtToken;
+use chrono::{DateTime, Utc};
+use eyre::WrapErr;
+use pyo3::prelude::*;
+
+use crate::cache::{
+    get_cache_base_dir, namespace_dir, user_token_filename, validate_namespace, NAMESPACES_SUBDIR,
+};
+use crate::crypto;
+use crate::security::jwt_expiry;
+use crate::storage::{token_lock, WipeOnDrop};
+use crate::token_lifecycle::token_expiry;
+use crate::utils::json_to_python;
+
+/// One token file in the cache directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub file: String,
+    pub node: String,
+    pub user: Option<String>,
+    pub encrypted: bool,
+    /// Access token expiry, when readable.
+    pub expires_at: Option<i64>,
+    /// Whether the tokens can no longer be used or refreshed at `now`.
+    pub expired: bool,
+    /// Why the file does not parse, for damaged plaintext files.
+    pub damaged: Option<String>,
+}
+
+/// `(node slug, user slug)` of a token filename, if it is one.
+pub fn parse_filename(file: &str) -> Option<(String, Option<String>)> {
+    let stem = file.strip_suffix(".json")?;
+    let (slug, hash) = stem.rsplit_once('-')?;
+    if slug.is_empty() || hash.len() != 12 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(match slug.split_once('@') {
+        Some((node, user)) => (node.to_string(), Some(user.to_string())),
+        None => (slug.to_string(), None),
+    })
+}
+
+/// Whether `tokens` are past use at `now`: the access token has expired and
+/// there is no refresh token, or the refresh token is an expired JWT.
+pub fn is_dead(tokens: &JwtToken, now: i64) -> bool {
+    let access_expired = token_expiry(tokens).is_some_and(|expiry| expiry <= now);
+    let refresh_usable = tokens
+        .refresh_token
+        .as_deref()
+        .is_some_and(|refresh| !jwt_expiry(refresh).is_some_and(|expiry| expiry <= now));
+    access_expired && !refresh_usable
+}
+
+/// Token files in `dir`, sorted by filename.
+pub fn scan(dir: &Path, now: i64) -> Vec<CacheEntry> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<CacheEntry> = read_dir
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+        .filter_map(|entry| {
+            let file = entry.file_name().to_str()?.to_string();
+            let (node, user) = parse_filename(&file)?;
+            let contents = zeroize::Zeroizing::new(fs::read(entry.path()).ok()?);
+            let encrypted = crypto::is_envelope(&contents);
+            let (tokens, damaged) = match encrypted {
+                true => (None, None),
+                false => match serde_json::from_slice::<JwtToken>(&contents) {
+                    Ok(tokens) => (Some(WipeOnDrop::new(tokens)), None),
+                    Err(e) => (None, Some(e.to_string())),
+                },
+            };
+            Some(CacheEntry {
+                file,
+                node,
+                user,
+                encrypted,
+                expires_at: tokens.as_deref().and_then(token_expiry),
+                expired: tokens.as_deref().is_some_and(|tokens| is_dead(tokens, now)),
+                damaged,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.file.cmp(&b.file));
+    entries
+}
+
+/// Delete the token file at `path` under its lock. Returns whether it
+/// existed.
+pub fn remove(path: &Path) -> eyre::Result<bool> {
+    remove_if(path, |_| true)
+}
+
+/// Delete the token file at `path` if, read under its lock, its contents
+/// satisfy `condition` (so a token refreshed since a scan is kept).
+/// Returns whether it was deleted.
+pub fn remove_if(path: &Path, condition: impl FnOnce(&[u8]) -> bool) -> eyre::Result<bool> {
+    let mut lock = token_lock(path)?;
+    let _held = lock
+        .write()
+        .wrap_err_with(|| format!("Failed to lock token file: {:?}", path))?;
+    let contents = match fs::read(path) {
+        Ok(contents) => zeroize::Zeroizing::new(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e).wrap_err_with(|| format!("Failed to read token file: {:?}", path)),
+    };
+    if !condition(&contents) {
+        return Ok(false);
+    }
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e).wrap_err_with(|| format!("Failed to remove token file: {:?}", path)),
+    }
+}
+
+/// Namespaces with a token directory under `cache_dir`, sorted.
+pub fn namespaces(cache_dir: &Path) -> Vec<String> {
+    let Ok(read_dir) = fs::read_dir(cache_dir.join(NAMESPACES_SUBDIR)) else {
+        return Vec::new();
+    };
+    let mut namespaces: Vec<String> = read_dir
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|namespace| validate_namespace(namespace).is_ok())
+        .collect();
+    namespaces.sort();
+    namespaces
+}
+
+/// Delete every token file of `namespace` under `cache_dir`. Returns how
+/// many were deleted.
+pub fn purge_namespace(cache_dir: &Path, namespace: &str) -> eyre::Result<usize> {
+    validate_namespace(namespace).map_err(|e| eyre::eyre!(e))?;
+    let dir = namespace_dir(cache_dir, namespace);
+    let mut removed = 0;
+    for entry in scan(&dir, Utc::now().timestamp()) {
+        if remove(&dir.join(&entry.file))? {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Where a damaged `path` is moved at `now`: `<name>.corrupt-<timestamp>`.
+pub fn corrupt_path(path: &Path, now: DateTime<Utc>) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".corrupt-{}", now.format("%Y%m%dT%H%M%S%.3fZ")));
+    path.with_file_name(name)
+}
+
+/// Move the token file at `path` aside (see [`corrupt_path`]) if, read
+/// under its lock, its contents satisfy `condition` (so a file repaired by
+/// a concurrent save is kept). Returns where it was moved.
+pub fn quarantine_if(
+    path: &Path,
+    condition: impl FnOnce(&[u8]) -> bool,
+) -> eyre::Result<Option<PathBuf>> {
+    let mut lock = token_lock(path)?;
+    let _held = lock
+        .write()
+        .wrap_err_with(|| format!("Failed to lock token file: {:?}", path))?;
+    let contents = match fs::read(path) {
+        Ok(contents) => zeroize::Zeroizing::new(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).wrap_err_with(|| format!("Failed to read token file: {:?}", path)),
+    };
+    if !condition(&contents) {
+        return Ok(None);
+    }
+    let target = corrupt_path(path, Utc::now());
+    fs::rename(path, &target)
+        .wrap_err_with(|| format!("Failed to quarantine token file {:?}", path))?;
+    Ok(Some(target))
+}
+
+/// Whether plaintext token file `contents` fail to parse.
+fn is_damaged(contents: &[u8]) -> bool {
+    !crypto::is_envelope(contents) && serde_json::from_slice::<JwtToken>(contents).is_err()
+}
+
+/// Damaged token files in `dir` as `{file, node, user, error,
+/// quarantinedTo}`, moved aside when `quarantine` is set.
+fn verify(dir: &Path, quarantine: bool) -> eyre::Result<Vec<serde_json::Value>> {
+    let mut damaged = Vec::new();
+    for entry in scan(dir, Utc::now().timestamp()) {
+        let Some(error) = entry.damaged else {
+            continue;
+        };
+        let quarantined_to = match quarantine {
+            true => quarantine_if(&dir.join(&entry.file), is_damaged)?
+                .and_then(|path| path.file_name().map(|n| n.to_string_lossy().into_owned())),
+            false => None,
+        };
+        damaged.push(serde_json::json!({
+            "file": entry.file,
+            "node": entry.node,
+            "user": entry.user,
+            "error": error,
+            "quarantinedTo": quarantined_to,
+        }));
+    }
+    Ok(damaged)
+}
+
+fn os_error(e: eyre::Report) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e))
+}
+
+/// The token cache directory, for listing and cleaning up cached sessions.
+///
+/// `TokenCache()` looks at `get_token_cache_dir()`; pass `cache_dir` for a
+/// directory given to `create_connection(..., cache_dir=...)`.
+#[pyclass(name = "TokenCache")]
+pub struct PyTokenCache {
+    dir: PathBuf,
+}
+
+#[pymethods]
+impl PyTokenCache {
+    #[new]
+    #[pyo3(signature = (cache_dir=None))]
+    pub fn new(cache_dir: Option<PathBuf>) -> Self {
+        Self {
+            dir: cache_dir.unwrap_or_else(get_cache_base_dir),
+        }
+    }
+
+    #[getter]
+    pub fn cache_dir(&self) -> String {
+        self.dir.to_string_lossy().into_owned()
+    }
+
+    /// Names of the nodes with cached tokens, sorted and without duplicates
+    /// (per-user sessions of a node are listed once).
+    pub fn list_nodes(&self) -> Vec<String> {
+        let mut nodes: Vec<String> = scan(&self.dir, Utc::now().timestamp())
+            .into_iter()
+            .map(|entry| entry.node)
+            .collect();
+        nodes.sort();
+        nodes.dedup();
+        nodes
+    }
+
+    /// Access token expiry of `node` (for `user`, when given) as a UTC
+    /// `datetime`; `None` when nothing is cached or the expiry is unknown
+    /// (opaque tokens without `expires_at`, encrypted files).
+    #[pyo3(signature = (node, user=None))]
+    pub fn get_expiry(&self, node: &str, user: Option<&str>) -> Option<DateTime<Utc>> {
+        let contents =
+            zeroize::Zeroizing::new(fs::read(self.dir.join(user_token_filename(node, user))).ok()?);
+        let tokens = WipeOnDrop::new(serde_json::from_slice::<JwtToken>(&contents).ok()?);
+        DateTime::from_timestamp(token_expiry(&tokens)?, 0)
+    }
+
+    /// Delete token files that can no longer be used: the access token has
+    /// expired and there is no refresh token, or it has expired too.
+    /// Returns the deleted filenames.
+    pub fn purge_expired(&self) -> PyResult<Vec<String>> {
+        let now = Utc::now().timestamp();
+        let mut purged = Vec::new();
+        for entry in scan(&self.dir, now)
+            .into_iter()
+            .filter(|entry| entry.expired)
+        {
+            let still_dead = |contents: &[u8]| {
+                serde_json::from_slice::<JwtToken>(contents)
+                    .map(WipeOnDrop::new)
+                    .is_ok_and(|tokens| is_dead(&tokens, now))
+            };
+            if remove_if(&self.dir.join(&entry.file), still_dead).map_err(os_error)? {
+                purged.push(entry.file);
+            }
+        }
+        Ok(purged)
+    }
+
+    /// Delete every token file, logging out all nodes. Returns how many
+    /// were deleted; encryption keys are kept.
+    pub fn clear_all(&self) -> PyResult<usize> {
+        let mut removed = 0;
+        for entry in scan(&self.dir, Utc::now().timestamp()) {
+            if remove(&self.dir.join(&entry.file)).map_err(os_error)? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Damaged token files as `[{file, node, user, error, quarantinedTo}]`;
+    /// with `quarantine=True` each is moved aside as
+    /// `<file>.corrupt-<timestamp>`, named by `quarantinedTo`.
+    #[pyo3(signature = (quarantine=false))]
+    pub fn verify(&self, py: Python<'_>, quarantine: bool) -> PyResult<Vec<PyObject>> {
+        let damaged = verify(&self.dir, quarantine).map_err(os_error)?;
+        Ok(damaged
+            .iter()
+            .map(|entry| json_to_python(py, entry))
+            .collect())
+    }
+
+    /// Namespaces holding token files of their own, sorted. Their files are
+    /// not counted, listed or cleared by the methods above.
+    pub fn list_namespaces(&self) -> Vec<String> {
+        namespaces(&self.dir)
+    }
+
+    /// Delete every token file of `namespace`, logging its sessions out on
+    /// all nodes. Returns how many were deleted.
+    pub fn purge_namespace(&self, namespace: &str) -> PyResult<usize> {
+        validate_namespace(namespace).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        purge_namespace(&self.dir, namespace).map_err(os_error)
+    }
+
+    fn __len__(&self) -> usize {
+        scan(&self.dir, Utc::now().timestamp()).len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("TokenCache({:?})", self.dir)
+    }
+}
+
+/// Check every token file in `cache_dir` (default: the token cache) and
+/// report the damaged ones; `TokenCache(cache_dir).verify(quarantine)`.
+#[pyfunction]
+#[pyo3(signature = (cache_dir=None, quarantine=false))]
+pub fn verify_cache(
+    py: Python<'_>,
+    cache_dir: Option<PathBuf>,
+    quarantine: bool,
+) -> PyResult<Vec<PyObject>> {
+    PyTokenCache::new(cache_dir).verify(py, quarantine)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, file: &str, tokens: serde_json::Value) {
+        fs::write(dir.join(file), tokens.to_string()).unwrap();
+    }
+
+    /// Only `{slug}-{hash}.json` names are token files.
+    #[test]
+    fn test_parse_filename() {
+        assert_eq!(
+            parse_filename("node-1-0123456789ab.json"),
+            Some(("node-1".to_string(), None))
+        );
+        assert_eq!(
+            parse_filename("node@alice-0123456789ab.json"),
+            Some(("node".to_string(), Some("alice".to_string())))
+        );
+        assert_eq!(parse_filename("auth_modes.json"), None);
+        assert_eq!(parse_filename("node-0123456789ab.json.lock"), None);
+    }
+
+    /// Entries past refresh are expired; refreshable and unknown ones not.
+    #[test]
+    fn test_scan_and_remove() {
+        let dir = std::env::temp_dir().join(format!("cache-admin-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        write(
+            &dir,
+            &user_token_filename("dead", None),
+            serde_json::json!({"access_token": "a", "refresh_token": null, "expires_at": 10}),
+        );
+        write(
+            &dir,
+            &user_token_filename("refreshable", Some("alice")),
+            serde_json::json!({"access_token": "a", "refresh_token": "r", "expires_at": 10}),
+        );
+        write(
+            &dir,
+            &user_token_filename("opaque", None),
+            serde_json::json!({"access_token": "a", "refresh_token": null, "expires_at": null}),
+        );
+        fs::write(dir.join("auth_modes.json"), "{}").unwrap();
+
+        let entries = scan(&dir, 100);
+        assert_eq!(entries.len(), 3);
+        let dead: Vec<_> = entries.iter().filter(|e| e.expired).collect();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].node, "dead");
+        assert_eq!(dead[0].expires_at, Some(10));
+
+        let path = dir.join(&dead[0].file);
+        assert!(!remove_if(&path, |_| false).unwrap());
+        assert!(remove(&path).unwrap());
+        assert!(!remove(&path).unwrap());
+        assert_eq!(scan(&dir, 100).len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Damaged files are reported, then moved aside under a timestamp.
+    #[test]
+    fn test_verify_quarantines_damaged_files() {
+        let dir = std::env::temp_dir().join(format!("cache-verify-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let broken = dir.join(user_token_filename("broken", None));
+        fs::write(&broken, b"{\"access_token\": ").unwrap();
+        write(
+            &dir,
+            &user_token_filename("fine", None),
+            serde_json::json!({"access_token": "a", "refresh_token": null, "expires_at": null}),
+        );
+
+        let report = verify(&dir, false).unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0]["node"], "broken");
+        assert!(report[0]["quarantinedTo"].is_null());
+        assert!(broken.exists());
+
+        let report = verify(&dir, true).unwrap();
+        let moved = report[0]["quarantinedTo"].as_str().unwrap();
+        assert!(moved.contains(".json.corrupt-"));
+        assert!(!broken.exists() && dir.join(moved).exists());
+        assert!(verify(&dir, false).unwrap().is_empty());
+        assert_eq!(scan(&dir, 100).len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
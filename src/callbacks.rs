@@ -0,0 +1,184 @@
+//! Python callbacks registered on client hooks (`on_login_state_change`,
+//! `on_maintenance`, callback subscriptions).
+//!
+//! A strong reference from the client to a callback keeps the callback's
+//! owner alive for as long as the client lives: a bound method of a UI
+//! widget or test fixture registered once leaks the whole object, and keeps
+//! being called after its owner was torn down. So bound methods are held
+//! through `weakref.WeakMethod` by default: once their object is collected
+//! the callback is dropped the next time the hook fires. Plain functions
+//! and lambdas are held strongly (a weak reference would drop a lambda at
+//! once); `weak=True` or `weak=False` overrides either default.
+//!
+//! Registration returns a [`PyCallbackHandle`] whose `unsubscribe()` removes
+//! exactly that callback, and which does the same at the end of a `with`
+//! block. Handles hold the hook weakly, so they never keep a client alive.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+
+/// A registered callable, held strongly or through a weak reference.
+pub enum Callback {
+    Strong(PyObject),
+    /// `weakref.ref` or `weakref.WeakMethod` of the callable.
+    Weak(PyObject),
+}
+
+impl Callback {
+    /// Hold `callback`; `weak=None` holds bound methods weakly and any
+    /// other callable strongly.
+    pub fn new(py: Python<'_>, callback: PyObject, weak: Option<bool>) -> PyResult<Self> {
+        let bound = callback.bind(py);
+        if !bound.is_callable() {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "callback must be callable",
+            ));
+        }
+        let is_method = bound.hasattr("__self__")? && bound.hasattr("__func__")?;
+        if !weak.unwrap_or(is_method) {
+            return Ok(Callback::Strong(callback));
+        }
+        let weakref = py.import_bound("weakref")?;
+        let reference = match is_method {
+            true => weakref.getattr("WeakMethod")?.call1((bound,))?,
+            false => weakref.getattr("ref")?.call1((bound,))?,
+        };
+        Ok(Callback::Weak(reference.unbind()))
+    }
+
+    /// The callable, or `None` once its owner was collected.
+    pub fn upgrade(&self, py: Python<'_>) -> Option<PyObject> {
+        match self {
+            Callback::Strong(callback) => Some(callback.clone_ref(py)),
+            Callback::Weak(reference) => reference
+                .call0(py)
+                .ok()
+                .filter(|callback| !callback.is_none(py)),
+        }
+    }
+}
+
+/// Callbacks of one hook, in registration order.
+#[derive(Default)]
+pub struct CallbackList {
+    next_id: AtomicU64,
+    entries: Mutex<Vec<(u64, Callback)>>,
+}
+
+impl CallbackList {
+    /// Register `callback` on `hook`; the handle unsubscribes it.
+    pub fn subscribe(self: &Arc<Self>, hook: &'static str, callback: Callback) -> PyCallbackHandle {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.push((id, callback));
+        }
+        PyCallbackHandle {
+            hook,
+            id,
+            list: Arc::downgrade(self),
+        }
+    }
+
+    pub fn remove(&self, id: u64) -> bool {
+        let Ok(mut entries) = self.entries.lock() else {
+            return false;
+        };
+        let before = entries.len();
+        entries.retain(|(entry, _)| *entry != id);
+        entries.len() != before
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.entries
+            .lock()
+            .is_ok_and(|entries| entries.iter().any(|(entry, _)| *entry == id))
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+
+    /// Whether any callback is registered; weakly held ones count until
+    /// the hook next fires.
+    pub fn is_empty(&self) -> bool {
+        self.entries
+            .lock()
+            .map(|entries| entries.is_empty())
+            .unwrap_or(true)
+    }
+
+    /// Callables to call now. Callbacks whose owner was collected are
+    /// dropped from the list.
+    pub fn live(&self, py: Python<'_>) -> Vec<PyObject> {
+        let Ok(mut entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let mut live = Vec::with_capacity(entries.len());
+        entries.retain(|(_, callback)| match callback.upgrade(py) {
+            Some(callback) => {
+                live.push(callback);
+                true
+            }
+            None => false,
+        });
+        live
+    }
+}
+
+/// Registration of a callback on a client hook, from `on_*` methods.
+///
+/// `unsubscribe()` removes the callback; used as a context manager, the
+/// callback is registered for the `with` block only.
+#[pyclass(name = "CallbackHandle")]
+pub struct PyCallbackHandle {
+    hook: &'static str,
+    id: u64,
+    list: Weak<CallbackList>,
+}
+
+#[pymethods]
+impl PyCallbackHandle {
+    /// Remove the callback; returns whether it was still registered.
+    pub fn unsubscribe(&self) -> bool {
+        self.list.upgrade().is_some_and(|list| list.remove(self.id))
+    }
+
+    /// Whether the callback is still registered (not unsubscribed, cleared,
+    /// dropped with its owner, or its client gone)
+    #[getter]
+    pub fn active(&self, py: Python<'_>) -> bool {
+        self.list.upgrade().is_some_and(|list| {
+            list.live(py);
+            list.contains(self.id)
+        })
+    }
+
+    /// Hook the callback is registered on, e.g. `"on_maintenance"`
+    #[getter]
+    pub fn hook(&self) -> &'static str {
+        self.hook
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, _args: &Bound<'_, PyTuple>) -> bool {
+        self.unsubscribe();
+        false
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> String {
+        format!(
+            "CallbackHandle(hook='{}', active={})",
+            self.hook,
+            if self.active(py) { "True" } else { "False" }
+        )
+    }
+}
@@ -0,0 +1,215 @@
+//! Typed capabilities of context members, behind `Client.grant()`,
+//! `Client.revoke()` and `Client.list_capabilities()`.
+//!
+//! A context's members draw their permissions from the group owning the
+//! context, where each member holds a bitmask of capabilities. Setting up a
+//! permissioned application meant looking the group up and juggling those
+//! bits through `set_member_capabilities()` on every node; the calls here
+//! take a [`Capability`] (or its name, or a raw bit), resolve the context's
+//! group and change only the bits asked for:
+//!
+//! ```python
+//! client.grant(ctx, alice, Capability.INVITE_MEMBERS)
+//! client.revoke(ctx, bob, "create_context")
+//! client.list_capabilities(ctx)
+//! ```
+//!
+//! Bits the binding has no name for are kept as they are and still show in
+//! each member's `mask`.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyString};
+use serde_json::Value;
+
+/// A capability a group member holds over the group's contexts.
+#[pyclass(name = "Capability", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    #[pyo3(name = "CREATE_CONTEXT")]
+    CreateContext = 1,
+    #[pyo3(name = "INVITE_MEMBERS")]
+    InviteMembers = 1 << 1,
+    #[pyo3(name = "JOIN_OPEN_CONTEXTS")]
+    JoinOpenContexts = 1 << 2,
+}
+
+/// Every named capability, lowest bit first.
+pub const ALL: [Capability; 3] = [
+    Capability::CreateContext,
+    Capability::InviteMembers,
+    Capability::JoinOpenContexts,
+];
+
+impl Capability {
+    /// The capability called `name`, as `"invite_members"` or
+    /// `"INVITE_MEMBERS"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        ALL.into_iter()
+            .find(|capability| capability.value() == name)
+    }
+
+    /// The named capabilities set in `mask`.
+    pub fn from_mask(mask: u32) -> Vec<Self> {
+        ALL.into_iter()
+            .filter(|capability| mask & capability.bit() != 0)
+            .collect()
+    }
+}
+
+#[pymethods]
+impl Capability {
+    #[getter]
+    pub fn value(&self) -> &'static str {
+        match self {
+            Self::CreateContext => "create_context",
+            Self::InviteMembers => "invite_members",
+            Self::JoinOpenContexts => "join_open_contexts",
+        }
+    }
+
+    /// The capability's bit in a member's mask.
+    #[getter]
+    pub fn bit(&self) -> u32 {
+        *self as u32
+    }
+
+    fn __str__(&self) -> &'static str {
+        self.value()
+    }
+}
+
+/// Bits of `capability`: a `Capability`, its name, or a non-zero mask.
+pub fn mask_of(capability: &Bound<'_, PyAny>) -> PyResult<u32> {
+    if let Ok(capability) = capability.extract::<Capability>() {
+        return Ok(capability.bit());
+    }
+    if let Ok(name) = capability.downcast::<PyString>() {
+        let name = name.to_str()?;
+        return Capability::from_name(name).map(|c| c.bit()).ok_or_else(|| {
+            let known: Vec<_> = ALL.iter().map(|c| c.value()).collect();
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown capability '{}'; expected one of {}",
+                name,
+                known.join(", ")
+            ))
+        });
+    }
+    let mask = if capability.is_instance_of::<PyBool>() {
+        None
+    } else {
+        capability.extract::<u32>().ok()
+    };
+    match mask {
+        Some(0) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "A capability mask must set at least one bit",
+        )),
+        Some(mask) => Ok(mask),
+        None => Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+            "capability must be a Capability, its name or an int mask",
+        )),
+    }
+}
+
+fn unwrap_data(response: &Value) -> &Value {
+    match response.get("data") {
+        Some(data) if !data.is_null() => unwrap_data(data),
+        _ => response,
+    }
+}
+
+/// Mask reported by `get_member_capabilities`, bare or as `capabilities`.
+pub fn mask_from_response(response: &Value) -> Option<u32> {
+    let data = unwrap_data(response);
+    data.get("capabilities")
+        .unwrap_or(data)
+        .as_u64()
+        .and_then(|mask| u32::try_from(mask).ok())
+}
+
+/// Public keys of the members listed by `list_group_members`.
+pub fn member_keys(response: &Value) -> Vec<String> {
+    const KEY_FIELDS: [&str; 6] = [
+        "publicKey",
+        "public_key",
+        "identity",
+        "memberId",
+        "member_id",
+        "id",
+    ];
+    let data = unwrap_data(response);
+    let records = data
+        .as_array()
+        .or_else(|| data.get("members").and_then(Value::as_array));
+    records
+        .into_iter()
+        .flatten()
+        .filter_map(|record| match record {
+            Value::String(key) => Some(key.clone()),
+            _ => KEY_FIELDS
+                .iter()
+                .find_map(|field| record.get(*field).filter(|v| !v.is_null()))
+                .map(|key| key.as_str().map_or_else(|| key.to_string(), str::to_string)),
+        })
+        .collect()
+}
+
+/// `{member, capabilities, mask}` for `member` holding `mask`.
+pub fn member_record<'py>(
+    py: Python<'py>,
+    member: &str,
+    mask: u32,
+) -> PyResult<Bound<'py, PyDict>> {
+    let record = PyDict::new_bound(py);
+    record.set_item("member", member)?;
+    record.set_item("capabilities", Capability::from_mask(mask))?;
+    record.set_item("mask", mask)?;
+    Ok(record)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Names round-trip in either case and masks split into named bits.
+    #[test]
+    fn test_capability_names_and_masks() {
+        assert_eq!(
+            Capability::from_name("INVITE_MEMBERS"),
+            Some(Capability::InviteMembers)
+        );
+        assert_eq!(
+            Capability::from_name(" join_open_contexts"),
+            Some(Capability::JoinOpenContexts)
+        );
+        assert_eq!(Capability::from_name("admin"), None);
+        assert_eq!(
+            Capability::from_mask(0b1101),
+            vec![Capability::CreateContext, Capability::JoinOpenContexts]
+        );
+    }
+
+    /// Responses are read whether wrapped in `data` or not.
+    #[test]
+    fn test_response_shapes() {
+        assert_eq!(
+            mask_from_response(&json!({"data": {"capabilities": 6}})),
+            Some(6)
+        );
+        assert_eq!(mask_from_response(&json!({"data": 3})), Some(3));
+        assert_eq!(mask_from_response(&json!({"data": {}})), None);
+        assert_eq!(
+            member_keys(&json!({"data": {"members": [
+                {"identity": "a", "role": "Admin"},
+                {"publicKey": "b"},
+                "c",
+            ]}})),
+            vec!["a", "b", "c"]
+        );
+    }
+}
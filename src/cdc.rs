@@ -0,0 +1,430 @@
+//! Change-data-capture export of context events to Kafka or NATS.
+//!
+//! `CdcExporter` forwards events (dicts, as yielded by event sources and
+//! projections) to a message broker selected by URL scheme:
+//!
+//! - `kafka://broker1:9092,broker2:9092` — requires the `kafka` feature
+//! - `nats://host:4222` — JetStream publish, requires the `nats` feature
+//!
+//! Delivery is at-least-once: `publish` returns only after the broker has
+//! acknowledged the message, retrying with exponential backoff until
+//! `max_retries` is exhausted. Consumers should de-duplicate on the event
+//! payload if exactly-once processing matters.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use pyo3::prelude::*;
+use tokio::runtime::Runtime;
+
+use crate::error;
+use crate::features;
+use crate::timeouts;
+use crate::utils::{python_to_json, str_field};
+
+/// Topic used for events whose kind has no entry in the topic map.
+pub const DEFAULT_TOPIC: &str = "calimero.events";
+
+/// Initial delay between delivery attempts; doubled after every failure.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Upper bound on the delay between delivery attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// A broker connection that can deliver one message and wait for its ack.
+#[async_trait]
+pub trait CdcSink: Send + Sync {
+    async fn send(&self, topic: &str, key: Option<&str>, payload: &[u8]) -> eyre::Result<()>;
+}
+
+/// Broker kinds understood by [`parse_broker_url`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrokerUrl {
+    Kafka { brokers: String },
+    Nats { server: String },
+}
+
+/// Parse `kafka://...` / `nats://...` into a [`BrokerUrl`].
+pub fn parse_broker_url(url: &str) -> Result<BrokerUrl, String> {
+    let (scheme, rest) = url
+        .split_once("://")
+        .ok_or_else(|| format!("Invalid broker URL '{}': missing scheme", url))?;
+    if rest.is_empty() {
+        return Err(format!("Invalid broker URL '{}': missing host", url));
+    }
+    match scheme {
+        "kafka" => Ok(BrokerUrl::Kafka {
+            brokers: rest.trim_end_matches('/').to_string(),
+        }),
+        "nats" | "tls" => Ok(BrokerUrl::Nats {
+            server: url.to_string(),
+        }),
+        other => Err(format!(
+            "Unsupported broker scheme '{}' (expected 'kafka' or 'nats')",
+            other
+        )),
+    }
+}
+
+/// Maps event kinds to topics (Kafka) or subjects (NATS).
+///
+/// Templates may reference `{kind}` and `{context_id}`; unknown kinds fall
+/// back to `default_topic`.
+#[derive(Debug, Clone)]
+pub struct TopicMap {
+    pub topics: HashMap<String, String>,
+    pub default_topic: String,
+}
+
+impl TopicMap {
+    pub fn resolve(&self, kind: Option<&str>, context_id: Option<&str>) -> String {
+        let template = kind
+            .and_then(|k| self.topics.get(k))
+            .unwrap_or(&self.default_topic);
+        template
+            .replace("{kind}", kind.unwrap_or("unknown"))
+            .replace("{context_id}", context_id.unwrap_or("unknown"))
+    }
+}
+
+/// Deliver one message, retrying until acknowledged or out of attempts.
+pub async fn send_with_retry(
+    sink: &dyn CdcSink,
+    topic: &str,
+    key: Option<&str>,
+    payload: &[u8],
+    max_retries: u32,
+) -> eyre::Result<()> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        match sink.send(topic, key, payload).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt >= max_retries => {
+                return Err(e.wrap_err(format!(
+                    "delivery to '{}' failed after {} attempts",
+                    topic,
+                    attempt + 1
+                )))
+            }
+            Err(_) => {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use super::*;
+    use rdkafka::config::ClientConfig;
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+    use rdkafka::util::Timeout;
+
+    pub struct KafkaSink {
+        producer: FutureProducer,
+        timeout: Duration,
+    }
+
+    impl KafkaSink {
+        pub fn connect(brokers: &str, timeout: Duration) -> eyre::Result<Self> {
+            let producer = ClientConfig::new()
+                .set("bootstrap.servers", brokers)
+                .set("acks", "all")
+                .set("enable.idempotence", "true")
+                .set("message.timeout.ms", timeout.as_millis().to_string())
+                .create()?;
+            Ok(Self { producer, timeout })
+        }
+    }
+
+    #[async_trait]
+    impl CdcSink for KafkaSink {
+        async fn send(&self, topic: &str, key: Option<&str>, payload: &[u8]) -> eyre::Result<()> {
+            let mut record = FutureRecord::<str, [u8]>::to(topic).payload(payload);
+            if let Some(key) = key {
+                record = record.key(key);
+            }
+            self.producer
+                .send(record, Timeout::After(self.timeout))
+                .await
+                .map(|_| ())
+                .map_err(|(e, _)| eyre::eyre!(e))
+        }
+    }
+}
+
+#[cfg(feature = "nats")]
+mod nats {
+    use super::*;
+
+    pub struct NatsSink {
+        jetstream: async_nats::jetstream::Context,
+    }
+
+    impl NatsSink {
+        pub async fn connect(server: &str) -> eyre::Result<Self> {
+            let client = async_nats::connect(server).await?;
+            Ok(Self {
+                jetstream: async_nats::jetstream::new(client),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl CdcSink for NatsSink {
+        async fn send(&self, topic: &str, _key: Option<&str>, payload: &[u8]) -> eyre::Result<()> {
+            self.jetstream
+                .publish(topic.to_string(), payload.to_vec().into())
+                .await?
+                .await?;
+            Ok(())
+        }
+    }
+}
+
+fn connect_sink(
+    runtime: &Runtime,
+    broker: BrokerUrl,
+    timeout: Duration,
+) -> PyResult<Arc<dyn CdcSink>> {
+    let _ = (runtime, timeout);
+    match broker {
+        #[cfg(feature = "kafka")]
+        BrokerUrl::Kafka { brokers } => kafka::KafkaSink::connect(&brokers, timeout)
+            .map(|sink| Arc::new(sink) as Arc<dyn CdcSink>)
+            .map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to create Kafka producer: {}",
+                    e
+                ))
+            }),
+        #[cfg(feature = "nats")]
+        BrokerUrl::Nats { server } => runtime
+            .block_on(nats::NatsSink::connect(&server))
+            .map(|sink| Arc::new(sink) as Arc<dyn CdcSink>)
+            .map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to connect to NATS: {}",
+                    e
+                ))
+            }),
+        #[allow(unreachable_patterns)]
+        other => {
+            let feature = match other {
+                BrokerUrl::Kafka { .. } => "kafka",
+                BrokerUrl::Nats { .. } => "nats",
+            };
+            Err(features::not_built(feature))
+        }
+    }
+}
+
+/// Python-facing CDC exporter.
+#[pyclass(name = "CdcExporter")]
+pub struct PyCdcExporter {
+    sink: Arc<dyn CdcSink>,
+    topics: TopicMap,
+    max_retries: u32,
+    runtime: Arc<Runtime>,
+}
+
+impl PyCdcExporter {
+    fn publish_value(&self, py: Python<'_>, event: serde_json::Value) -> PyResult<()> {
+        let topic = self.topics.resolve(
+            str_field(&event, &["kind", "type"]),
+            str_field(&event, &["contextId", "context_id"]),
+        );
+        let key = str_field(&event, &["contextId", "context_id"]).map(str::to_owned);
+        let payload = serde_json::to_vec(&event).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Failed to serialize event: {}",
+                e
+            ))
+        })?;
+        let sink = Arc::clone(&self.sink);
+        let max_retries = self.max_retries;
+
+        let context = serde_json::json!({"operation": "cdc_publish", "topic": topic});
+        py.allow_threads(|| {
+            error::catch_panic(
+                move || context,
+                || {
+                    timeouts::block_on(&self.runtime, async move {
+                        send_with_retry(
+                            sink.as_ref(),
+                            &topic,
+                            key.as_deref(),
+                            &payload,
+                            max_retries,
+                        )
+                        .await
+                    })
+                },
+            )
+            .and_then(|outcome| outcome)
+        })?
+        .map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "CDC publish failed: {:#}",
+                e
+            ))
+        })
+    }
+}
+
+#[pymethods]
+impl PyCdcExporter {
+    /// Connect to the broker at `url`.
+    ///
+    /// `topics` maps event kinds to topic/subject templates; everything else
+    /// goes to `default_topic`.
+    #[new]
+    #[pyo3(signature = (url, topics=None, default_topic=DEFAULT_TOPIC.to_string(), max_retries=5, timeout_secs=30))]
+    pub fn new(
+        url: &str,
+        topics: Option<HashMap<String, String>>,
+        default_topic: String,
+        max_retries: u32,
+        timeout_secs: u64,
+    ) -> PyResult<Self> {
+        let broker =
+            parse_broker_url(url).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        let runtime = Arc::new(
+            Runtime::new()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        );
+        let sink = connect_sink(&runtime, broker, Duration::from_secs(timeout_secs))?;
+        Ok(Self {
+            sink,
+            topics: TopicMap {
+                topics: topics.unwrap_or_default(),
+                default_topic,
+            },
+            max_retries,
+            runtime,
+        })
+    }
+
+    /// Publish one event, returning once the broker has acknowledged it.
+    pub fn publish(&self, py: Python<'_>, event: &Bound<'_, PyAny>) -> PyResult<()> {
+        let event = python_to_json(event)?;
+        self.publish_value(py, event)
+    }
+
+    /// Publish every event from an iterable in order; returns the count.
+    ///
+    /// Stops at the first event that cannot be delivered, so everything
+    /// before it is known to be acknowledged.
+    pub fn publish_many(&self, py: Python<'_>, events: &Bound<'_, PyAny>) -> PyResult<usize> {
+        let mut count = 0;
+        for event in events.iter()? {
+            let event = python_to_json(&event?)?;
+            self.publish_value(py, event)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Topic/subject an event would be published to.
+    pub fn topic_for(&self, event: &Bound<'_, PyAny>) -> PyResult<String> {
+        let event = python_to_json(event)?;
+        Ok(self.topics.resolve(
+            str_field(&event, &["kind", "type"]),
+            str_field(&event, &["contextId", "context_id"]),
+        ))
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Broker URLs are dispatched by scheme.
+    #[test]
+    fn test_parse_broker_url() {
+        assert_eq!(
+            parse_broker_url("kafka://a:9092,b:9092/").unwrap(),
+            BrokerUrl::Kafka {
+                brokers: "a:9092,b:9092".to_string()
+            }
+        );
+        assert_eq!(
+            parse_broker_url("nats://localhost:4222").unwrap(),
+            BrokerUrl::Nats {
+                server: "nats://localhost:4222".to_string()
+            }
+        );
+        assert!(parse_broker_url("amqp://localhost").is_err());
+        assert!(parse_broker_url("localhost:9092").is_err());
+        assert!(parse_broker_url("kafka://").is_err());
+    }
+
+    /// Mapped kinds use their template; others fall back to the default.
+    #[test]
+    fn test_topic_map_resolve() {
+        let map = TopicMap {
+            topics: HashMap::from([(
+                "StateMutation".to_string(),
+                "ctx.{context_id}.state".to_string(),
+            )]),
+            default_topic: "events.{kind}".to_string(),
+        };
+        assert_eq!(
+            map.resolve(Some("StateMutation"), Some("abc")),
+            "ctx.abc.state"
+        );
+        assert_eq!(
+            map.resolve(Some("ExecutionEvent"), Some("abc")),
+            "events.ExecutionEvent"
+        );
+        assert_eq!(map.resolve(None, None), "events.unknown");
+    }
+
+    struct FlakySink {
+        failures: u32,
+        calls: AtomicU32,
+    }
+
+    #[async_trait]
+    impl CdcSink for FlakySink {
+        async fn send(&self, _: &str, _: Option<&str>, _: &[u8]) -> eyre::Result<()> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                eyre::bail!("broker unavailable")
+            }
+            Ok(())
+        }
+    }
+
+    /// Transient failures are retried until the broker acks.
+    #[tokio::test(start_paused = true)]
+    async fn test_send_with_retry_recovers() {
+        let sink = FlakySink {
+            failures: 2,
+            calls: AtomicU32::new(0),
+        };
+        send_with_retry(&sink, "t", None, b"{}", 5).await.unwrap();
+        assert_eq!(sink.calls.load(Ordering::SeqCst), 3);
+    }
+
+    /// Delivery gives up after `max_retries` retries.
+    #[tokio::test(start_paused = true)]
+    async fn test_send_with_retry_gives_up() {
+        let sink = FlakySink {
+            failures: u32::MAX,
+            calls: AtomicU32::new(0),
+        };
+        assert!(send_with_retry(&sink, "t", None, b"{}", 2).await.is_err());
+        assert_eq!(sink.calls.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,379 @@
+//! Tokens cached by the Calimero CLI tools, behind `import_cli_credentials()`
+//! and `export_cli_credentials()`.
+//!
+//! Someone who logged in to a node with `meroctl` or `merobox` already holds
+//! tokens for it; making them log in again from Python is friction, and a
+//! second session the node has to track. `import_cli_credentials(node)`
+//! copies those tokens into the client's token storage instead, looking in:
+//!
+//! - the meroctl config file: `source` when given, else
+//!   `$CALIMERO_CLI_CONFIG`, else the first of [`meroctl_config_candidates`]
+//!   that exists. Its `nodes.<name>` entry is searched for a token record
+//!   (`access_token` plus optional `refresh_token` / `expires_at`, snake or
+//!   camel case), however the entry nests it;
+//! - the token file merobox keeps for the node in the legacy
+//!   `~/.merobox/auth_cache/`, which holds the same JSON this client writes.
+//!
+//! `export_cli_credentials(node)` writes the client's tokens back into the
+//! node's existing token record in the meroctl config, keeping the record's
+//! key style and every other setting, so the CLI picks up a session
+//! refreshed from Python. Comments in a TOML config are not preserved.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use calimero_client::traits::ClientStorage;
+use calimero_client::JwtToken;
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use serde_json::{json, Map, Value};
+
+use crate::cache;
+use crate::connection::PyConnectionInfo;
+use crate::entropy;
+use crate::permissions::restrict_to_owner;
+use crate::storage::WipeOnDrop;
+use crate::token_bundle::{block_on, target_storage};
+use crate::utils::json_to_python;
+
+/// Environment variable naming the meroctl config file to use
+pub const CLI_CONFIG_ENV: &str = "CALIMERO_CLI_CONFIG";
+
+/// Where meroctl configs are looked for without `source` or
+/// `$CALIMERO_CLI_CONFIG`, in order.
+pub fn meroctl_config_candidates() -> Vec<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(|| home.join(".config"));
+    vec![
+        config_home.join("meroctl").join("config.toml"),
+        config_home
+            .join("calimero")
+            .join("meroctl")
+            .join("config.toml"),
+        home.join(".calimero").join("meroctl").join("config.toml"),
+    ]
+}
+
+/// The meroctl config to read or write: `source`, `$CALIMERO_CLI_CONFIG`,
+/// or the first existing candidate.
+fn meroctl_config(source: Option<PathBuf>) -> Option<PathBuf> {
+    source
+        .or_else(|| {
+            std::env::var_os(CLI_CONFIG_ENV)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        })
+        .or_else(|| {
+            meroctl_config_candidates()
+                .into_iter()
+                .find(|path| path.is_file())
+        })
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "toml")
+}
+
+/// A config file as JSON, whether it is TOML or JSON.
+fn read_config(path: &Path) -> eyre::Result<Value> {
+    let text = fs::read_to_string(path).wrap_err_with(|| format!("Failed to read {:?}", path))?;
+    match is_toml(path) {
+        true => {
+            let value: toml::Value =
+                toml::from_str(&text).wrap_err_with(|| format!("Invalid TOML in {:?}", path))?;
+            Ok(serde_json::to_value(value)?)
+        }
+        false => {
+            serde_json::from_str(&text).wrap_err_with(|| format!("Invalid JSON in {:?}", path))
+        }
+    }
+}
+
+/// Write `config` to `path` in the file's format, owner-only, through a
+/// temp file.
+fn write_config(path: &Path, config: &Value) -> eyre::Result<()> {
+    let text = match is_toml(path) {
+        true => toml::to_string_pretty(&serde_json::from_value::<toml::Value>(config.clone())?)?,
+        false => serde_json::to_string_pretty(config)?,
+    };
+    let temp_path = path.with_extension(format!("{}.tmp", entropy::temp_suffix()));
+    let written = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        restrict_to_owner(&temp_path, false)?;
+        file.write_all(text.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok::<_, eyre::Report>(())
+    })();
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written.wrap_err_with(|| format!("Failed to write {:?}", path))
+}
+
+fn field<'a>(record: &'a Map<String, Value>, snake: &str, camel: &str) -> Option<&'a Value> {
+    record.get(snake).or_else(|| record.get(camel))
+}
+
+/// The first token record in `value`, depth first.
+pub fn find_token_record(value: &mut Value) -> Option<&mut Map<String, Value>> {
+    match value {
+        Value::Object(record) if field(record, "access_token", "accessToken").is_some() => {
+            Some(record)
+        }
+        Value::Object(record) => record.values_mut().find_map(find_token_record),
+        Value::Array(items) => items.iter_mut().find_map(find_token_record),
+        _ => None,
+    }
+}
+
+/// Tokens of a token record.
+pub fn token_from_record(record: &Map<String, Value>) -> Option<JwtToken> {
+    let text = |snake, camel| {
+        field(record, snake, camel)
+            .and_then(Value::as_str)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    };
+    Some(JwtToken {
+        access_token: text("access_token", "accessToken")?,
+        refresh_token: text("refresh_token", "refreshToken"),
+        expires_at: field(record, "expires_at", "expiresAt").and_then(Value::as_i64),
+    })
+}
+
+/// Overwrite `record` with `token`, in the key style it already uses.
+pub fn update_record(record: &mut Map<String, Value>, token: &JwtToken) {
+    let camel = record.contains_key("accessToken");
+    let key = |snake: &'static str, camel_key: &'static str| match camel {
+        true => camel_key,
+        false => snake,
+    };
+    record.insert(
+        key("access_token", "accessToken").to_string(),
+        Value::String(token.access_token.clone()),
+    );
+    match &token.refresh_token {
+        Some(refresh) => record.insert(
+            key("refresh_token", "refreshToken").to_string(),
+            Value::String(refresh.clone()),
+        ),
+        None => record.remove(key("refresh_token", "refreshToken")),
+    };
+    // Only kept up to date where the CLI already records it.
+    let expires = key("expires_at", "expiresAt");
+    if record.contains_key(expires) {
+        match token.expires_at {
+            Some(at) => record.insert(expires.to_string(), Value::from(at)),
+            None => record.remove(expires),
+        };
+    }
+}
+
+/// Tokens of `node_name` in a meroctl `config`.
+pub fn meroctl_tokens(config: &Value, node_name: &str) -> Option<JwtToken> {
+    let mut entry = config.get("nodes")?.get(node_name)?.clone();
+    token_from_record(find_token_record(&mut entry)?)
+}
+
+/// Tokens merobox cached for `node_name` in the legacy cache directory.
+fn merobox_tokens(node_name: &str) -> Option<(PathBuf, JwtToken)> {
+    let path = cache::legacy_root()
+        .join(cache::AUTH_CACHE_SUBDIR)
+        .join(cache::derive_token_filename(node_name));
+    let token = serde_json::from_slice(&fs::read(&path).ok()?).ok()?;
+    Some((path, token))
+}
+
+fn credentials_error(e: eyre::Report) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e))
+}
+
+/// Copy the tokens the Calimero CLI tools hold for `node_name` into token
+/// storage, so no new login is needed.
+///
+/// Reads the meroctl config (`source`, `$CALIMERO_CLI_CONFIG` or the
+/// default locations), then merobox's legacy token cache. Tokens are saved
+/// to `connection`'s storage when given, else to the token cache directory;
+/// tokens already stored there are kept unless `overwrite=True`. Returns
+/// `{node, source, imported, refreshToken}`; raises `ValueError` when no CLI
+/// tool holds tokens for the node.
+#[pyfunction]
+#[pyo3(signature = (node_name, source=None, connection=None, overwrite=false))]
+pub fn import_cli_credentials(
+    py: Python<'_>,
+    node_name: &str,
+    source: Option<PathBuf>,
+    connection: Option<PyRef<'_, PyConnectionInfo>>,
+    overwrite: bool,
+) -> PyResult<PyObject> {
+    let mut searched = Vec::new();
+    let mut found = None;
+    if let Some(path) = meroctl_config(source) {
+        let config = read_config(&path).map_err(credentials_error)?;
+        found = meroctl_tokens(&config, node_name).map(|token| (path.clone(), token));
+        searched.push(path);
+    }
+    if found.is_none() {
+        found = merobox_tokens(node_name);
+        searched.push(cache::legacy_root().join(cache::AUTH_CACHE_SUBDIR));
+    }
+    let Some((path, token)) = found else {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "No CLI credentials for node '{}' (searched {:?})",
+            node_name, searched
+        )));
+    };
+    let token = WipeOnDrop::new(token);
+
+    let storage = target_storage(connection.as_deref());
+    let stored = block_on(storage.load_tokens(node_name))?
+        .map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to load tokens for node '{}': {:#}",
+                node_name, e
+            ))
+        })?
+        .map(WipeOnDrop::new);
+    let imported = overwrite || stored.is_none();
+    if imported {
+        block_on(storage.save_tokens(node_name, &token))?.map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to save tokens for node '{}': {:#}",
+                node_name, e
+            ))
+        })?;
+    }
+    Ok(json_to_python(
+        py,
+        &json!({
+            "node": node_name,
+            "source": path.to_string_lossy(),
+            "imported": imported,
+            "refreshToken": token.refresh_token.is_some(),
+        }),
+    ))
+}
+
+/// Write the stored tokens of `node_name` back into its token record in
+/// the meroctl config (`destination`, `$CALIMERO_CLI_CONFIG` or the default
+/// locations), e.g. after the client refreshed them. Returns the path
+/// written. Raises `ValueError` when the config has no token record for
+/// the node (log in once with meroctl first) or no tokens are stored.
+#[pyfunction]
+#[pyo3(signature = (node_name, destination=None, connection=None))]
+pub fn export_cli_credentials(
+    node_name: &str,
+    destination: Option<PathBuf>,
+    connection: Option<PyRef<'_, PyConnectionInfo>>,
+) -> PyResult<PathBuf> {
+    let path = meroctl_config(destination).ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "No meroctl config found (searched {:?}); pass destination",
+            meroctl_config_candidates()
+        ))
+    })?;
+    let storage = target_storage(connection.as_deref());
+    let token = block_on(storage.load_tokens(node_name))?
+        .map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to load tokens for node '{}': {:#}",
+                node_name, e
+            ))
+        })?
+        .map(WipeOnDrop::new)
+        .ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "No stored tokens for node '{}'",
+                node_name
+            ))
+        })?;
+
+    let mut config = read_config(&path).map_err(credentials_error)?;
+    let record = config
+        .get_mut("nodes")
+        .and_then(|nodes| nodes.get_mut(node_name))
+        .and_then(find_token_record)
+        .ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "{:?} has no token record for node '{}'",
+                path, node_name
+            ))
+        })?;
+    update_record(record, &token);
+    write_config(&path, &config)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e)))?;
+    Ok(path)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEROCTL: &str = r#"
+active_node = "node1"
+
+[nodes.node1.Remote]
+url = "http://localhost:2428"
+
+[nodes.node1.Remote.jwt_tokens]
+access_token = "access-1"
+refresh_token = "refresh-1"
+
+[nodes.bare.Remote]
+url = "http://localhost:2429"
+"#;
+
+    fn config() -> Value {
+        serde_json::to_value(toml::from_str::<toml::Value>(MEROCTL).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_reads_nested_token_records() {
+        let token = meroctl_tokens(&config(), "node1").unwrap();
+        assert_eq!(token.access_token, "access-1");
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh-1"));
+        assert!(meroctl_tokens(&config(), "bare").is_none());
+        assert!(meroctl_tokens(&config(), "missing").is_none());
+
+        let camel = json!({"nodes": {"n": {"auth": {"accessToken": "a", "expiresAt": 5}}}});
+        let token = meroctl_tokens(&camel, "n").unwrap();
+        assert_eq!(
+            (token.access_token.as_str(), token.expires_at),
+            ("a", Some(5))
+        );
+    }
+
+    /// Write-back keeps the record's key style and the rest of the entry.
+    #[test]
+    fn test_update_record_in_place() {
+        let mut config = config();
+        let token = JwtToken {
+            access_token: "access-2".into(),
+            refresh_token: None,
+            expires_at: Some(10),
+        };
+        update_record(
+            find_token_record(&mut config["nodes"]["node1"]).unwrap(),
+            &token,
+        );
+        let record = &config["nodes"]["node1"]["Remote"]["jwt_tokens"];
+        assert_eq!(record, &json!({"access_token": "access-2"}));
+        assert_eq!(
+            config["nodes"]["node1"]["Remote"]["url"],
+            "http://localhost:2428"
+        );
+
+        let mut camel = json!({"accessToken": "a", "refreshToken": "r", "expiresAt": 1});
+        update_record(camel.as_object_mut().unwrap(), &token);
+        assert_eq!(camel, json!({"accessToken": "access-2", "expiresAt": 10}));
+    }
+}
@@ -1,10 +1,11 @@
 //! Python wrapper for Client
 
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 
 use calimero_client::client::Client;
-use calimero_client::connection::ConnectionInfo;
+use calimero_client::connection::{AuthMode, ConnectionInfo};
+use calimero_client::traits::ClientStorage;
 use calimero_client::CliAuthenticator;
 use calimero_primitives::alias::Alias;
 use calimero_primitives::application::ApplicationId;
@@ -14,20 +15,303 @@ use calimero_primitives::hash::Hash;
 use calimero_primitives::identity;
 use calimero_primitives::identity::PublicKey;
 use calimero_server_primitives::admin;
-use calimero_server_primitives::jsonrpc;
+use futures_util::StreamExt;
 use pyo3::prelude::*;
+use pyo3::types::{PyList, PyString, PyTuple};
 use tokio::runtime::Runtime;
-
+use tracing::Instrument;
+use zeroize::Zeroizing;
+
+use crate::abi;
+use crate::aliases::{self, AliasCache, AliasKind};
+use crate::applications::{self, Source};
+use crate::archive::ArchiveSet;
+use crate::arg_encoding::{ArgEncoding, ArgEncodings};
+use crate::audit::{self, AuditLog};
+use crate::auth::{self, AuthModeCache, PyAuthMode};
+use crate::auth_metadata;
+use crate::auth_service::{self, ScopedTokenRequest};
+use crate::blob;
+use crate::bulk::{BulkItem, PyBulkResult};
+use crate::cache;
+use crate::callbacks::{Callback, PyCallbackHandle};
+use crate::capabilities;
+use crate::concurrency::{self, AdaptiveLimiter};
 use crate::connection::PyConnectionInfo;
-use crate::storage::MeroboxFileStorage;
-use crate::utils::json_to_python;
+use crate::context::PyContext;
+use crate::context_guard::ContextGuards;
+use crate::crypto;
+use crate::dedup::DedupWindow;
+use crate::deprecation;
+use crate::dev_deploy::{self, PyDevDeployer};
+use crate::entropy;
+use crate::error::{self, ErrorContext};
+use crate::event_filter::EventFilter;
+use crate::http;
+use crate::in_flight::InFlight;
+use crate::invitation::{self, Invitation};
+use crate::jsonrpc_batch::{self, BatchCall, BatchSupport};
+use crate::lifecycle::{self, ClientRuntime};
+use crate::limits::{self, OversizedArgs};
+use crate::log_bridge;
+use crate::login::{self, LoginState, LoginTracker};
+use crate::maintenance::{self, MaintenanceGate};
+use crate::membership::{self, MembershipProof, DEFAULT_MEMBERSHIP_PROOF_TTL_SECS};
+use crate::metadata_cache::{self, MetadataCache, MetadataKey, MetadataKind};
+use crate::metadata_store;
+use crate::method_defaults::MethodDefaults;
+use crate::metrics::{self, Metrics};
+use crate::middleware::{self, Middleware};
+use crate::node_address::NodeAddress;
+use crate::node_status::{self, PyNodeHealth, PyNodeInfo, PyPeers};
+use crate::outbox::{self, DrainOutcome, Outbox, OutboxEntry};
+use crate::pool::{self, PoolConfig, PoolLease};
+use crate::probe;
+use crate::production::{self, Dangerous};
+use crate::query::{self, PyQuery};
+use crate::quota;
+use crate::rate_limit::Throttle;
+use crate::read_cache::{self, ReadCache, ReadKey};
+use crate::reauth::{self, Reauth};
+use crate::request_cache::{self, RequestCache};
+use crate::retention;
+use crate::retry::{self, RetryConfig};
+use crate::retry_budget::{self, RetryBudget};
+use crate::security;
+use crate::session::PyContextSession;
+use crate::signing::{PySigner, ReplayGuard, SignedExecution};
+use crate::sso;
+use crate::storage::{MeroboxFileStorage, WipeOnDrop};
+use crate::subscriptions::{self, Hub, PySubscription};
+use crate::task_group::{self, GroupCall, GroupFuture, PyTaskGroup};
+use crate::temporary_context::PyTemporaryContext;
+use crate::timeouts::{self, PyTimeoutScope};
+use crate::token::PyJwtToken;
+use crate::token_lifecycle::{self, TokenLifecycle};
+use crate::token_refresh::{self, PyTokenRefresher, RefreshTask};
+use crate::transcript::{self, Transcript};
+use crate::typed_result;
+use crate::usage::{self, Execution, UsageLog};
+use crate::utils::{json_to_python, project_fields, python_to_json};
+use crate::wallet_login::{self, SigningKey};
+use crate::warnings::{self, WarningKind};
+use crate::wire::{self, WireMode, WireNegotiation};
 
 /// Python wrapper for Client
 #[pyclass(name = "Client")]
 pub struct PyClient {
     inner: Arc<Client<CliAuthenticator, MeroboxFileStorage>>,
     connection: Arc<ConnectionInfo<CliAuthenticator, MeroboxFileStorage>>,
-    runtime: Arc<Runtime>,
+    storage: MeroboxFileStorage,
+    /// HTTP client for the requests `crate::http` issues directly.
+    http: reqwest::Client,
+    http3: bool,
+    wire: Arc<WireNegotiation>,
+    /// Detected auth mode, trusted for a TTL and dropped on auth errors.
+    auth_mode: AuthModeCache,
+    /// Cap on response size; `None` disables the guard.
+    max_response_bytes: Option<usize>,
+    /// Cap on `execute_function` request size; `None` disables the guard.
+    max_request_bytes: Option<usize>,
+    /// What happens to arguments over `max_request_bytes`.
+    oversized_args: OversizedArgs,
+    /// Prepared `execute_function` requests, reused for repeated calls.
+    request_cache: Arc<RequestCache>,
+    /// Application and context metadata, served until its TTL runs out.
+    metadata_cache: Arc<MetadataCache>,
+    /// `view()` results, served until their TTL runs out.
+    read_cache: Arc<ReadCache>,
+    /// Recently resolved aliases, see [`aliases`].
+    aliases: Arc<AliasCache>,
+    /// Default arguments merged into `execute_function` calls.
+    method_defaults: Arc<MethodDefaults>,
+    /// Argument encodings registered per context and method.
+    arg_encodings: Arc<ArgEncodings>,
+    /// Adaptive limit on concurrent requests to the node.
+    limiter: Arc<AdaptiveLimiter>,
+    /// Share of requests that may be retries.
+    retry_budget: Arc<RetryBudget>,
+    /// Which failed calls are retried, and how long to back off.
+    retry: Arc<RetryConfig>,
+    /// Limit on a whole call unless a `timeout()` block overrides it.
+    request_timeout: Option<std::time::Duration>,
+    /// Dangerous operations need `allow_dangerous=True` (`production=True`).
+    production: bool,
+    /// `execute` calls queued while offline, flushed by `flush_outbox`.
+    outbox: Arc<Outbox>,
+    /// Contexts frozen by `freeze_context`; execution on them is refused.
+    archive: Arc<ArchiveSet>,
+    /// FIFO locks serializing mutating calls on opted-in contexts.
+    context_guards: Arc<ContextGuards>,
+    /// Identical `execute_function` calls folded within `dedup_window_ms`.
+    dedup: Arc<DedupWindow>,
+    /// Whether the node takes JSON-RPC batches (`execute_batch`).
+    batch_support: Arc<BatchSupport>,
+    /// Executions sent by this client, for `usage()` reports.
+    usage: Arc<UsageLog>,
+    /// Executions started and not completed, for `pending_executions()`.
+    in_flight: Arc<InFlight>,
+    /// Signed executions already submitted, for `submit_signed()`.
+    signed_nonces: Arc<ReplayGuard>,
+    /// `add_request_hook`/`add_response_hook` callbacks.
+    middleware: Arc<Middleware>,
+    /// Transcript requests are recorded to or replayed from.
+    transcript: Option<Arc<Transcript>>,
+    /// Audit trail of mutating calls (`audit_log=`).
+    audit: Option<Arc<AuditLog>>,
+    /// Request counters and latencies, for `metrics()`.
+    metrics: Arc<Metrics>,
+    /// Settings of the shared connection pool `http` comes from.
+    pool_config: PoolConfig,
+    /// Proactive token refresh; `None` when disabled or token-less.
+    token_lifecycle: Option<Arc<TokenLifecycle>>,
+    /// Background refresher from `start_token_refresh()`, if any.
+    token_refresh: Mutex<Option<Arc<RefreshTask>>>,
+    /// Recovery of a session the node rejects; `None` when token-less.
+    reauth: Option<Arc<Reauth>>,
+    /// `Client` arguments, connection first, for pickling.
+    pickle_args: Py<PyTuple>,
+    /// Planned-maintenance window announced by the node, and its callbacks.
+    maintenance: Arc<MaintenanceGate>,
+    /// Inferred login state and its change callbacks.
+    login: Arc<LoginTracker>,
+    /// Shared WebSocket for `subscribe`, opened on first use.
+    subscriptions: OnceLock<Arc<Hub>>,
+    /// Runtime the calls run on, shut down by `close()`.
+    runtime: ClientRuntime,
+    /// This client's share of the connection pool `http` comes from;
+    /// `None` for HTTP/3 clients, which are not pooled.
+    pool_lease: Option<PoolLease>,
+}
+
+/// JSON result of a call started by [`PyClient::spawn_call`], with the
+/// client that made it.
+struct CallOutput {
+    client: Py<PyClient>,
+    value: serde_json::Value,
+}
+
+/// Node name keying the tokens of ephemeral clients created without one.
+const EPHEMERAL_NODE_NAME: &str = "ephemeral";
+
+#[cfg(feature = "http3")]
+fn build_http3_client() -> PyResult<reqwest::Client> {
+    http::http3_client().map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to create client: {}", e))
+    })
+}
+
+#[cfg(not(feature = "http3"))]
+fn build_http3_client() -> PyResult<reqwest::Client> {
+    Err(crate::features::not_built("http3"))
+}
+
+/// Current root hash of `context_id`, read from the node (not the cache).
+async fn fetch_root_hash(
+    inner: &Client<CliAuthenticator, MeroboxFileStorage>,
+    context_id: &ContextId,
+) -> eyre::Result<String> {
+    let context = serde_json::to_value(inner.get_context(context_id).await?)?;
+    context
+        .pointer("/data/rootHash")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| eyre::eyre!("Context {} response has no root hash", context_id))
+}
+
+/// A call of `execute_batch`: a `(context_id, method, args)` tuple, a
+/// dict with those keys, or a `failed_inputs` entry.
+fn extract_batch_call(call: &Bound<'_, PyAny>) -> PyResult<BatchCall> {
+    if let Ok(input) = call.extract::<String>() {
+        return serde_json::from_str(&input).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid batch call '{}': {}",
+                input, e
+            ))
+        });
+    }
+    if let Ok(dict) = call.downcast::<pyo3::types::PyDict>() {
+        let field = |name: &str| -> PyResult<String> {
+            dict.get_item(name)?
+                .ok_or_else(|| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Batch call is missing '{}'",
+                        name
+                    ))
+                })?
+                .extract()
+        };
+        return Ok(BatchCall {
+            context_id: field("context_id")?,
+            method: field("method")?,
+            args: field("args")?,
+        });
+    }
+    let (context_id, method, args) = call.extract::<(String, String, String)>()?;
+    Ok(BatchCall {
+        context_id,
+        method,
+        args,
+    })
+}
+
+/// A call of `execute_many` against `context_id`: a `(method, args)`
+/// tuple, a `{method, args}` dict (`args` a JSON string or a value to
+/// serialize, `{}` when missing), or an entry of a previous result's
+/// `failed_inputs`.
+fn extract_fan_out_call(context_id: &str, call: &Bound<'_, PyAny>) -> PyResult<BatchCall> {
+    let args_text = |args: &Bound<'_, PyAny>| -> PyResult<String> {
+        match args.extract::<String>() {
+            Ok(text) => Ok(text),
+            Err(_) => Ok(python_to_json(args)?.to_string()),
+        }
+    };
+    let (method, args) = if call.is_instance_of::<PyString>() {
+        let call = extract_batch_call(call)?;
+        if call.context_id != context_id {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Call '{}' is for context {}, not {}",
+                call.method, call.context_id, context_id
+            )));
+        }
+        return Ok(call);
+    } else if let Ok(dict) = call.downcast::<pyo3::types::PyDict>() {
+        let method = dict
+            .get_item("method")?
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>("Call is missing 'method'")
+            })?
+            .extract()?;
+        let args = match dict.get_item("args")? {
+            Some(args) => args_text(&args)?,
+            None => "{}".to_string(),
+        };
+        (method, args)
+    } else {
+        let (method, args) = call.extract::<(String, Bound<'_, PyAny>)>()?;
+        (method, args_text(&args)?)
+    };
+    Ok(BatchCall {
+        context_id: context_id.to_string(),
+        method,
+        args,
+    })
+}
+
+/// Alias kind of `kind`, checking identity aliases come with a context.
+fn parse_alias_kind(kind: &str, context_id: Option<&str>) -> PyResult<AliasKind> {
+    let kind = AliasKind::parse(kind)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    if kind == AliasKind::Identity && context_id.is_none() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "identity aliases live in a context; pass context_id",
+        ));
+    }
+    Ok(kind)
+}
+
+fn parse_alias<T>(name: &str) -> eyre::Result<Alias<T>> {
+    Alias::<T>::from_str(name).map_err(|e| eyre::eyre!("Invalid alias '{}': {}", name, e))
 }
 
 fn parse_upgrade_policy(policy: &str) -> PyResult<UpgradePolicy> {
@@ -57,95 +341,3408 @@ fn parse_group_member_role(role: &str) -> PyResult<GroupMemberRole> {
     }
 }
 
-#[pymethods]
-impl PyClient {
-    #[new]
-    pub fn new(connection: &PyConnectionInfo) -> PyResult<Self> {
-        let runtime = Arc::new(
-            Runtime::new()
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+/// `fut` inside a `call` span naming `operation`, closed by a DEBUG event
+/// with its duration, for the `calimero_client` logger.
+async fn traced<F: std::future::Future>(operation: &'static str, fut: F) -> F::Output {
+    let span = tracing::debug_span!("call", operation);
+    async move {
+        let started = std::time::Instant::now();
+        let output = fut.await;
+        tracing::debug!(
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "call finished"
         );
+        output
+    }
+    .instrument(span)
+    .await
+}
 
-        // Extract the inner connection from the Arc
-        let connection_inner = connection.inner.as_ref().clone();
-        let client = Client::new(connection_inner.clone()).map_err(|e| {
+impl PyClient {
+    /// `get_context_identities` response of `context_id`.
+    pub(crate) fn identities_response(
+        &self,
+        context_id: &ContextId,
+    ) -> PyResult<serde_json::Value> {
+        let inner = self.inner.clone();
+        let call_context = Some(context_id.to_string());
+        let data = self
+            .run_retrying("get_context_identities", call_context, true, || async {
+                inner.get_context_identities(context_id, false).await
+            })?
+            .map_err(|e| self.client_error(e))?;
+        serde_json::to_value(data).map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                "Failed to create client: {}",
+                "Failed to serialize response: {}",
                 e
             ))
+        })
+    }
+
+    /// Full list response of `resource`, through the metadata cache
+    /// (blobs excepted).
+    pub(crate) fn list_response(
+        &self,
+        resource: query::Resource,
+        refresh: bool,
+    ) -> PyResult<serde_json::Value> {
+        let inner = self.inner.clone();
+        match resource {
+            query::Resource::Contexts => self.read_metadata(
+                "list_contexts",
+                (MetadataKind::Context, None),
+                refresh,
+                || async { inner.list_contexts().await },
+            ),
+            query::Resource::Applications => self.read_metadata(
+                "list_applications",
+                (MetadataKind::Application, None),
+                refresh,
+                || async { inner.list_applications().await },
+            ),
+            // Blob lists are not cached: uploads change them all the time.
+            query::Resource::Blobs => {
+                let data = self
+                    .run_retrying("list_blobs", None, true, || async {
+                        inner.list_blobs().await
+                    })?
+                    .map_err(|e| self.client_error(e))?;
+                serde_json::to_value(data).map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                        "Failed to serialize response: {}",
+                        e
+                    ))
+                })
+            }
+        }
+    }
+
+    /// Refuse execution on a context frozen by `freeze_context`.
+    fn ensure_not_frozen(&self, context_id: &ContextId) -> PyResult<()> {
+        let context_id = context_id.to_string();
+        if self.archive.is_frozen(&context_id) {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Context {} is frozen; call unfreeze_context() to execute on it",
+                context_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Context id of `raw`, a context id or a context alias.
+    pub(crate) fn context_id(&self, raw: &str) -> PyResult<ContextId> {
+        match raw.parse::<ContextId>() {
+            Ok(context_id) => Ok(context_id),
+            Err(e) => {
+                let target = self.alias_fallback(AliasKind::Context, raw, None, &e)?;
+                target.parse::<ContextId>().map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Context alias '{}' names an invalid context ID '{}': {}",
+                        raw, target, e
+                    ))
+                })
+            }
+        }
+    }
+
+    /// Public key of `raw`, a key or an identity alias in `context_id`;
+    /// `what` names the argument in errors.
+    fn identity(&self, raw: &str, context_id: &ContextId, what: &str) -> PyResult<PublicKey> {
+        match raw.parse::<PublicKey>() {
+            Ok(key) => Ok(key),
+            Err(e) => {
+                let target = self
+                    .alias_fallback(AliasKind::Identity, raw, Some(context_id), &e)
+                    .map_err(|_| {
+                        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                            "Invalid {} '{}': {}",
+                            what, raw, e
+                        ))
+                    })?;
+                target.parse::<PublicKey>().map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Identity alias '{}' names an invalid {} '{}': {}",
+                        raw, what, target, e
+                    ))
+                })
+            }
+        }
+    }
+
+    /// Group owning `context_id`, where its members' capabilities live.
+    fn capability_group(&self, context_id: &ContextId) -> PyResult<String> {
+        let inner = self.inner.clone();
+        let key = (MetadataKind::Context, Some(context_id.to_string()));
+        let response = self.read_metadata("get_context", key, false, || async {
+            inner.get_context(context_id).await
         })?;
+        PyContext::from_response(&response)
+            .group_id()
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Context {} has no group holding member capabilities",
+                    context_id
+                ))
+            })
+    }
 
-        Ok(Self {
-            inner: Arc::new(client),
-            connection: Arc::new(connection_inner),
-            runtime,
+    /// Read `member`'s capability mask in `context_id`'s group and write
+    /// back `change(mask)` when it differs.
+    fn change_capabilities(
+        &self,
+        operation: &'static str,
+        context_id: &str,
+        member: &str,
+        change: impl FnOnce(u32) -> u32,
+    ) -> PyResult<PyObject> {
+        let context_id = self.context_id(context_id)?;
+        let member = self.identity(member, &context_id, "member")?.to_string();
+        let group_id = self.capability_group(&context_id)?;
+        let inner = self.inner.clone();
+        let (group, key) = (group_id.clone(), member.clone());
+        let result = self.run_in_context(operation, Some(context_id.to_string()), async move {
+            let response = inner.get_member_capabilities(&group, &key).await?;
+            let current = capabilities::mask_from_response(&serde_json::to_value(response)?)
+                .unwrap_or_default();
+            let mask = change(current);
+            if mask != current {
+                let request = admin::SetMemberCapabilitiesApiRequest {
+                    capabilities: mask,
+                    requester: None,
+                };
+                inner.set_member_capabilities(&group, &key, request).await?;
+            }
+            Ok::<_, eyre::Report>((mask, mask != current))
+        })?;
+        let (mask, changed) = result.map_err(|e| self.client_error(e))?;
+        Python::with_gil(|py| {
+            let record = capabilities::member_record(py, &member, mask)?;
+            record.set_item("contextId", context_id.to_string())?;
+            record.set_item("groupId", group_id)?;
+            record.set_item("changed", changed)?;
+            Ok(record.into_any().unbind())
         })
     }
 
-    /// Get API URL
-    pub fn get_api_url(&self) -> String {
-        self.inner.api_url().to_string()
+    /// Target of `raw` taken as an alias, for a value that did not parse
+    /// as an id (`parse_error`); the error names both when it is neither.
+    fn alias_fallback(
+        &self,
+        kind: AliasKind,
+        raw: &str,
+        scope: Option<&ContextId>,
+        parse_error: &dyn std::fmt::Display,
+    ) -> PyResult<String> {
+        let invalid = |reason: String| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid {} ID '{}': {}{}",
+                kind.as_str(),
+                raw,
+                parse_error,
+                reason
+            ))
+        };
+        if !aliases::is_alias_name(raw) {
+            return Err(invalid(String::new()));
+        }
+        match self.resolve_alias_target(kind, raw, scope, false) {
+            Ok(Some(target)) => Ok(target),
+            Ok(None) => Err(invalid(format!(
+                " (and no {} alias of that name exists)",
+                kind.as_str()
+            ))),
+            Err(e) => Err(invalid(format!(
+                " (resolving it as an alias failed: {})",
+                e
+            ))),
+        }
+    }
+
+    /// Target of alias `name`, from the alias cache unless `refresh`;
+    /// `None` when the node has no such alias.
+    fn resolve_alias_target(
+        &self,
+        kind: AliasKind,
+        name: &str,
+        scope: Option<&ContextId>,
+        refresh: bool,
+    ) -> PyResult<Option<String>> {
+        if !refresh {
+            if let Some(target) = self.aliases.get(kind, scope, name) {
+                return Ok(Some(target));
+            }
+        }
+        let inner = self.inner.clone();
+        let scope_id = scope.copied();
+        let call_context = scope.map(ToString::to_string);
+        let result = self.run_retrying("resolve_alias", call_context, true, || async {
+            let response = match kind {
+                AliasKind::Context => serde_json::to_value(
+                    inner
+                        .resolve_alias(parse_alias::<ContextId>(name)?, None)
+                        .await?,
+                )?,
+                AliasKind::Application => serde_json::to_value(
+                    inner
+                        .resolve_alias(parse_alias::<ApplicationId>(name)?, None)
+                        .await?,
+                )?,
+                AliasKind::Identity => serde_json::to_value(
+                    inner
+                        .resolve_alias(parse_alias::<PublicKey>(name)?, scope_id)
+                        .await?,
+                )?,
+            };
+            Ok::<_, eyre::Report>(response)
+        })?;
+        let response = result.map_err(|e| self.client_error(e))?;
+        let target = aliases::resolved_value(&response);
+        if let Some(target) = &target {
+            self.aliases.insert(kind, scope, name, target);
+        }
+        Ok(target)
+    }
+
+    /// The client's runtime, or the error of a call on a closed client.
+    fn runtime(&self) -> PyResult<Arc<Runtime>> {
+        self.runtime.get().ok_or_else(|| self.closed_error())
+    }
+
+    fn closed_error(&self) -> PyErr {
+        self.client_error(lifecycle::CLOSED_MESSAGE)
+    }
+
+    /// Convert a failed call into the Python exception, dropping the cached
+    /// auth mode when the node rejected our credentials.
+    ///
+    /// The exception carries the full cause chain in its message and the
+    /// call's [`ErrorContext`] as attributes.
+    pub(crate) fn client_error(&self, e: impl std::fmt::Display) -> PyErr {
+        self.metrics.record_error();
+        let message = error::render_chain(e);
+        if maintenance::is_maintenance(&message) {
+            let window = maintenance::announced_window(&message);
+            if self.maintenance.enter(window) {
+                Python::with_gil(|py| self.maintenance.notify(py, true, window));
+            }
+        }
+        if auth::is_auth_error(&message) {
+            self.auth_mode.invalidate();
+            if self.login.is_observed() {
+                let state = match self.settled_login_state() {
+                    LoginState::LoggedOut => LoginState::LoggedOut,
+                    _ => LoginState::Expired,
+                };
+                Python::with_gil(|py| self.login.transition(py, state));
+            }
+        }
+        let context = ErrorContext::new(
+            error::take_call(),
+            self.connection.node_name.clone(),
+            &message,
+        );
+        if limits::is_too_large(&message) {
+            let err = limits::too_large_error(message);
+            context.attach(&err);
+            return err;
+        }
+        error::call_error(&context, &message)
+    }
+
+    /// Convert a response to Python, enforcing `max_response_bytes` first.
+    fn to_python(&self, py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObject> {
+        self.check_size(value)?;
+        Ok(json_to_python(py, value))
+    }
+
+    /// Reject `value` if it exceeds `max_response_bytes`.
+    fn check_size(&self, value: &serde_json::Value) -> PyResult<()> {
+        limits::check_json(value, self.max_response_bytes).map_err(limits::too_large_error)
+    }
+
+    /// Drive `fut` to completion on the client runtime; a panic surfaces as
+    /// `InternalError` instead of unwinding into the interpreter.
+    fn run<F: std::future::Future>(&self, operation: &'static str, fut: F) -> PyResult<F::Output> {
+        error::begin_call(operation, None);
+        self.run_call(operation, fut)
+    }
+
+    /// [`Self::run`] for a call about a context, so failures report it.
+    fn run_in_context<F: std::future::Future>(
+        &self,
+        operation: &'static str,
+        context_id: Option<String>,
+        fut: F,
+    ) -> PyResult<F::Output> {
+        error::begin_call(operation, context_id);
+        self.run_call(operation, fut)
+    }
+
+    /// [`Self::run_in_context`] for a call `make` can build again, retried
+    /// per the client's [`RetryConfig`]; `idempotent` says whether the call
+    /// may be repeated after failures that could have reached the node.
+    fn run_retrying<T, E, F, Fut>(
+        &self,
+        operation: &'static str,
+        context_id: Option<String>,
+        idempotent: bool,
+        make: F,
+    ) -> PyResult<Result<T, E>>
+    where
+        E: std::fmt::Display,
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+    {
+        let config = self.retry.clone();
+        let budget = self.retry_budget.clone();
+        self.run_in_context(operation, context_id, async move {
+            retry::with_retries(&config, &budget, idempotent, make).await
+        })
+    }
+
+    fn run_call<F: std::future::Future>(
+        &self,
+        operation: &'static str,
+        fut: F,
+    ) -> PyResult<F::Output> {
+        self.await_maintenance()?;
+        self.retry_budget.record_request();
+        let limiter = self.limiter.clone();
+        let refresh = self.refresh_tokens();
+        self.track_login(|| {
+            error::catch_panic(
+                || self.panic_context(operation),
+                || {
+                    self.block_on_call(operation, async move {
+                        refresh.await;
+                        let _slot = limiter.acquire().await;
+                        fut.await
+                    })
+                },
+            )
+            .and_then(|outcome| outcome)
+        })
+    }
+
+    /// Like [`Self::run`], for operations that take a limiter slot per
+    /// request themselves (holding one for the whole batch could deadlock
+    /// at a limit of one).
+    fn run_batch<F: std::future::Future>(
+        &self,
+        operation: &'static str,
+        fut: F,
+    ) -> PyResult<F::Output> {
+        error::begin_call(operation, None);
+        self.await_maintenance()?;
+        self.retry_budget.record_request();
+        let refresh = self.refresh_tokens();
+        self.track_login(|| {
+            error::catch_panic(
+                || self.panic_context(operation),
+                || {
+                    self.block_on_call(operation, async move {
+                        refresh.await;
+                        fut.await
+                    })
+                },
+            )
+            .and_then(|outcome| outcome)
+        })
+    }
+
+    /// Like [`Self::run_batch`], with the GIL released while `fut` runs on
+    /// the calling thread; anything in `fut` that calls into Python (a
+    /// Python token storage, progress callbacks) takes the GIL itself. The
+    /// request timeout does not bound the whole of `fut`, which bounds each
+    /// of its requests itself.
+    fn run_released<F>(
+        &self,
+        py: Python<'_>,
+        operation: &'static str,
+        context_id: Option<String>,
+        fut: F,
+    ) -> PyResult<F::Output>
+    where
+        F: std::future::Future + Send + 'static,
+        F::Output: Send,
+    {
+        error::begin_call(operation, context_id);
+        self.await_maintenance()?;
+        self.retry_budget.record_request();
+        let refresh = self.refresh_tokens();
+        let call = middleware::scope(
+            self.middleware.clone(),
+            traced(operation, async move {
+                refresh.await;
+                fut.await
+            }),
+        );
+        let call = transcript::scope(self.transcript.clone(), call);
+        let call = reauth::scope(self.reauth.clone(), call);
+        let panic_context = self.panic_context(operation);
+        let runtime = self.runtime()?;
+        let started = std::time::Instant::now();
+        let outcome = self.track_login(|| {
+            py.allow_threads(move || {
+                error::catch_panic(move || panic_context, || timeouts::block_on(&runtime, call))
+                    .and_then(|outcome| outcome)
+            })
+        });
+        self.metrics.record_request(started.elapsed());
+        log_bridge::flush(py);
+        outcome
+    }
+
+    /// Block on `fut` for at most the request timeout, giving up early
+    /// when a signal handler raises (Ctrl-C). Either way the in-flight
+    /// request is dropped.
+    fn block_on_call<F: std::future::Future>(
+        &self,
+        operation: &'static str,
+        fut: F,
+    ) -> PyResult<F::Output> {
+        let timeout = timeouts::effective(self.request_timeout);
+        let call = middleware::scope(self.middleware.clone(), traced(operation, fut));
+        let call = transcript::scope(self.transcript.clone(), call);
+        let call = reauth::scope(self.reauth.clone(), call);
+        let started = std::time::Instant::now();
+        let outcome = timeouts::block_on(&self.runtime()?, timeouts::bounded(timeout, call));
+        self.metrics.record_request(started.elapsed());
+        Python::with_gil(log_bridge::flush);
+        outcome?
+            .map_err(|timeout| self.client_error(timeouts::timed_out_message(operation, timeout)))
+    }
+
+    /// Asyncio counterpart of [`Self::run_in_context`]: returns an
+    /// awaitable resolving to `fut`'s converted result. `fut` runs on the
+    /// client runtime with the same maintenance wait, token refresh and
+    /// limiter slot as a blocking call, without holding the event loop.
+    /// Cancelling the awaiting task aborts the request. `hold_slot` is
+    /// false for futures that take a slot per request themselves.
+    fn run_async<'py, F>(
+        slf: &Bound<'py, Self>,
+        operation: &'static str,
+        context_id: Option<String>,
+        hold_slot: bool,
+        fut: F,
+    ) -> PyResult<Bound<'py, PyAny>>
+    where
+        F: std::future::Future<Output = eyre::Result<serde_json::Value>> + Send + 'static,
+    {
+        let py = slf.py();
+        let call = Self::spawn_call(slf, operation, context_id, hold_slot, fut);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let output = call.await?;
+            Python::with_gil(|py| output.client.borrow(py).to_python(py, &output.value))
+        })
+    }
+
+    /// Start `fut` on the client runtime as [`Self::run_async`] does and
+    /// return a future of its JSON result, settling the call's metrics,
+    /// maintenance and login state once it completes.
+    fn spawn_call<F>(
+        slf: &Bound<'_, Self>,
+        operation: &'static str,
+        context_id: Option<String>,
+        hold_slot: bool,
+        fut: F,
+    ) -> impl std::future::Future<Output = PyResult<CallOutput>> + Send + 'static
+    where
+        F: std::future::Future<Output = eyre::Result<serde_json::Value>> + Send + 'static,
+    {
+        let py = slf.py();
+        let this = slf.borrow();
+        this.retry_budget.record_request();
+        if this.login.is_observed() {
+            if let Some(pending) = login::pending_state(this.settled_login_state()) {
+                this.login.transition(py, pending);
+            }
+        }
+        let delay = this.maintenance.delay();
+        let limiter = this.limiter.clone();
+        let refresh = this.refresh_tokens();
+        let timeout = timeouts::effective(this.request_timeout);
+        let middleware = this.middleware.clone();
+        let transcript = this.transcript.clone();
+        let reauth = this.reauth.clone();
+        let started = std::time::Instant::now();
+        let closed = this.runtime.is_closed();
+        let task = timeouts::AbortOnDrop(this.runtime.handle().spawn(async move {
+            if let Some(delay) = delay {
+                tokio::time::sleep(delay).await;
+            }
+            let call = async move {
+                refresh.await;
+                let _slot = match hold_slot {
+                    true => Some(limiter.acquire().await),
+                    false => None,
+                };
+                fut.await
+            };
+            let call = middleware::scope(middleware, traced(operation, call));
+            let call = transcript::scope(transcript, call);
+            let call = reauth::scope(reauth, call);
+            match timeouts::bounded(timeout, call).await {
+                Ok(outcome) => outcome,
+                Err(timeout) => Err(eyre::eyre!(timeouts::timed_out_message(operation, timeout))),
+            }
+        }));
+        drop(this);
+
+        let client = slf.clone().unbind();
+        async move {
+            if closed {
+                return Python::with_gil(|py| Err(client.borrow(py).closed_error()));
+            }
+            let outcome = task.await;
+            Python::with_gil(|py| {
+                log_bridge::flush(py);
+                let this = client.borrow(py);
+                this.metrics.record_request(started.elapsed());
+                if this.maintenance.resume() {
+                    this.maintenance.notify(py, false, None);
+                }
+                if this.login.is_observed() {
+                    this.login.transition(py, this.settled_login_state());
+                }
+                // The call record is per thread; open it where the result
+                // is converted so errors still name the operation.
+                error::begin_call(operation, context_id);
+                let value = match outcome {
+                    Ok(Ok(value)) => value,
+                    Ok(Err(e)) => return Err(this.client_error(e)),
+                    // Only a runtime shut down under the call cancels it.
+                    Err(e) if e.is_cancelled() => return Err(this.closed_error()),
+                    Err(e) => {
+                        let message = match e.try_into_panic() {
+                            Ok(payload) => error::panic_message(payload.as_ref()),
+                            Err(e) => e.to_string(),
+                        };
+                        return Err(error::internal_error(
+                            &message,
+                            &this.panic_context(operation),
+                        ));
+                    }
+                };
+                drop(this);
+                Ok(CallOutput { client, value })
+            })
+        }
+    }
+
+    /// Awaitable counterpart of [`Self::list_response`] for async
+    /// iteration: resolves to the full list response of `resource`,
+    /// fetched on the client runtime unless the metadata cache has it.
+    pub(crate) fn list_response_async(
+        slf: &Bound<'_, Self>,
+        resource: query::Resource,
+    ) -> impl std::future::Future<Output = PyResult<serde_json::Value>> + Send + 'static {
+        let this = slf.borrow();
+        let key = match resource {
+            query::Resource::Contexts => Some((MetadataKind::Context, None)),
+            query::Resource::Applications => Some((MetadataKind::Application, None)),
+            query::Resource::Blobs => None,
+        };
+        let cached = key.as_ref().and_then(|key| this.metadata_cache.get(key));
+        let inner = this.inner.clone();
+        drop(this);
+        let call = match cached {
+            Some(_) => None,
+            None => Some(Self::spawn_call(
+                slf,
+                resource.operation(),
+                None,
+                true,
+                async move {
+                    let value = match resource {
+                        query::Resource::Contexts => {
+                            serde_json::to_value(inner.list_contexts().await?)
+                        }
+                        query::Resource::Applications => {
+                            serde_json::to_value(inner.list_applications().await?)
+                        }
+                        query::Resource::Blobs => serde_json::to_value(inner.list_blobs().await?),
+                    };
+                    Ok(value?)
+                },
+            )),
+        };
+        async move {
+            let Some(call) = call else {
+                return Ok(cached.unwrap_or_default());
+            };
+            let output = call.await?;
+            if let Some(key) = key {
+                Python::with_gil(|py| {
+                    output
+                        .client
+                        .borrow(py)
+                        .metadata_cache
+                        .insert(key, output.value.clone())
+                });
+            }
+            Ok(output.value)
+        }
+    }
+
+    /// Wait out an announced maintenance window (up to `maintenance_wait`)
+    /// before sending a call, reporting the resume once it is over. Calls
+    /// held back this way do not count against the retry budget; Ctrl-C
+    /// ends the wait.
+    fn await_maintenance(&self) -> PyResult<()> {
+        if let Some(delay) = self.maintenance.delay() {
+            timeouts::block_on(&self.runtime()?, tokio::time::sleep(delay))?;
+        }
+        if self.maintenance.resume() {
+            Python::with_gil(|py| self.maintenance.notify(py, false, None));
+        }
+        Ok(())
+    }
+
+    /// Refresh the session's tokens if they are about to expire. Failures
+    /// are ignored: the call then goes out with the old token and surfaces
+    /// any auth error itself.
+    fn refresh_tokens(&self) -> impl std::future::Future<Output = ()> + 'static {
+        let lifecycle = self.token_lifecycle.clone();
+        let storage = self.storage.clone();
+        let http = self.http.clone();
+        let api_url = self.connection.api_url.clone();
+        let node_name = self.connection.node_name.clone();
+        let metrics = self.metrics.clone();
+        async move {
+            if let (Some(lifecycle), Some(node_name)) = (lifecycle, node_name) {
+                match lifecycle
+                    .ensure_fresh(&http, &api_url, &storage, &node_name)
+                    .await
+                {
+                    Ok(true) => metrics.record_token_refresh(true),
+                    Ok(false) => {}
+                    Err(_) => metrics.record_token_refresh(false),
+                }
+            }
+        }
+    }
+
+    /// Report login transitions around a call, when anyone is listening:
+    /// logging in or refreshing before it, the settled state after it.
+    fn track_login<R>(&self, call: impl FnOnce() -> R) -> R {
+        if !self.login.is_observed() {
+            return call();
+        }
+        if let Some(pending) = login::pending_state(self.settled_login_state()) {
+            Python::with_gil(|py| self.login.transition(py, pending));
+        }
+        let output = call();
+        let settled = self.settled_login_state();
+        Python::with_gil(|py| self.login.transition(py, settled));
+        output
+    }
+
+    /// Node name tokens from an explicit login are stored under.
+    fn login_node_name(&self) -> PyResult<String> {
+        self.connection.node_name.clone().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Logging in needs a connection with a node_name to store the tokens under",
+            )
+        })
+    }
+
+    /// Run a challenge-signing login: report `LOGGING_IN`, cache the tokens
+    /// `login` obtains under the node name, then settle the login state.
+    fn finish_login<F>(
+        &self,
+        py: Python<'_>,
+        operation: &'static str,
+        login: F,
+    ) -> PyResult<PyJwtToken>
+    where
+        F: std::future::Future<Output = eyre::Result<calimero_client::JwtToken>>,
+    {
+        let node_name = self.login_node_name()?;
+        self.login.transition(py, LoginState::LoggingIn);
+        let storage = self.storage.clone();
+        let result = self.run_batch(operation, async move {
+            let tokens = login.await?;
+            storage.save_tokens(&node_name, &tokens).await?;
+            Ok::<_, eyre::Report>(tokens)
+        })?;
+        let result = result
+            .map(PyJwtToken::from)
+            .map_err(|e| self.client_error(e));
+        self.login.transition(py, self.settled_login_state());
+        result
+    }
+
+    /// Login state implied by the token cache right now.
+    fn settled_login_state(&self) -> LoginState {
+        let auth_required = !matches!(self.auth_mode.get(), Some(AuthMode::None));
+        let tokens = self.connection.node_name.clone().and_then(|node_name| {
+            let storage = self.storage.clone();
+            self.runtime
+                .get()?
+                .block_on(async move { storage.load_tokens(&node_name).await })
+                .ok()
+                .flatten()
+                .map(WipeOnDrop::new)
+        });
+        login::settled_state(
+            tokens.as_deref(),
+            auth_required,
+            chrono::Utc::now().timestamp(),
+        )
+    }
+
+    /// Serve `key` from the metadata cache unless `refresh` is set or the
+    /// entry is stale; otherwise run the request `fetch` builds (retried
+    /// like any read) and cache its response.
+    fn read_metadata<T, E, F, Fut>(
+        &self,
+        operation: &'static str,
+        key: MetadataKey,
+        refresh: bool,
+        fetch: F,
+    ) -> PyResult<serde_json::Value>
+    where
+        T: serde::Serialize,
+        E: std::fmt::Display,
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+    {
+        if !refresh {
+            if let Some(hit) = self.metadata_cache.get(&key) {
+                return Ok(hit);
+            }
+        }
+        let call_context = match &key {
+            (MetadataKind::Context, id) => id.clone(),
+            _ => None,
+        };
+        let data = self
+            .run_retrying(operation, call_context, true, fetch)?
+            .map_err(|e| self.client_error(e))?;
+        // Convert to JSON first, then to Python
+        let json_data = serde_json::to_value(data).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to serialize response: {}",
+                e
+            ))
+        })?;
+        self.metadata_cache.insert(key, json_data.clone());
+        Ok(json_data)
     }
 
-    /// Get application information
-    pub fn get_application(&self, app_id: &str) -> PyResult<PyObject> {
+    /// Id of the application `context_id` runs, read through the
+    /// metadata cache.
+    /// ABI of the application `context_id` runs, `None` when it has none.
+    fn application_abi(
+        &self,
+        context_id: &ContextId,
+        refresh: bool,
+    ) -> PyResult<Option<serde_json::Value>> {
         let inner = self.inner.clone();
+        let app_id = self.application_of(context_id, refresh)?;
+        let key = (MetadataKind::Application, Some(app_id.clone()));
         let app_id = app_id.parse::<ApplicationId>().map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
                 "Invalid application ID '{}': {}",
                 app_id, e
             ))
         })?;
+        let application = self.read_metadata("get_application", key, refresh, || async {
+            inner.get_application(&app_id).await
+        })?;
+        Ok(abi::find_abi(&application))
+    }
 
-        Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_application(&app_id).await });
+    fn application_of(&self, context_id: &ContextId, refresh: bool) -> PyResult<String> {
+        let inner = self.inner.clone();
+        let key = (MetadataKind::Context, Some(context_id.to_string()));
+        let context = self.read_metadata("get_context", key, refresh, || async {
+            inner.get_context(context_id).await
+        })?;
+        let context = context.get("data").unwrap_or(&context);
+        ["applicationId", "application_id"]
+            .iter()
+            .find_map(|name| context.get(*name).and_then(serde_json::Value::as_str))
+            .map(str::to_string)
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Context {} reports no application",
+                    context_id
+                ))
+            })
+    }
 
-            match result {
-                Ok(data) => {
-                    // Convert to JSON first, then to Python
-                    let json_data = serde_json::to_value(data).map_err(|e| {
-                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                            "Failed to serialize response: {}",
-                            e
-                        ))
+    /// `args` with the defaults registered for `method` merged in; see
+    /// [`crate::method_defaults`].
+    fn with_defaults(&self, context_id: &ContextId, method: &str, args: &str) -> PyResult<String> {
+        if self.method_defaults.is_empty() {
+            return Ok(args.to_string());
+        }
+        let application_id = match self.method_defaults.is_application_scoped(method) {
+            true => Some(self.application_of(context_id, false)?),
+            false => None,
+        };
+        match self
+            .method_defaults
+            .apply(application_id.as_deref(), method, args)
+        {
+            Ok(applied) => Ok(applied.unwrap_or_else(|| args.to_string())),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                e.to_string(),
+            )),
+        }
+    }
+
+    /// `args` as sent for `method`: with its defaults merged in, then
+    /// encoded with `encoding`, else the one registered for the method or
+    /// context; see [`crate::arg_encoding`].
+    fn prepare_args(
+        &self,
+        context_id: &ContextId,
+        method: &str,
+        args: &str,
+        encoding: Option<&ArgEncoding>,
+    ) -> PyResult<String> {
+        let args = self.with_defaults(context_id, method, args)?;
+        let registered = match encoding {
+            Some(_) => None,
+            None => self.arg_encodings.get(&context_id.to_string(), method),
+        };
+        match encoding.or(registered.as_deref()) {
+            Some(encoding) => encoding
+                .encode(&args)
+                .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>),
+            None => Ok(args),
+        }
+    }
+
+    /// The `execute_function` request as a standalone future, shared by
+    /// the blocking and asyncio entry points.
+    fn execute_call(
+        &self,
+        context_id: ContextId,
+        method: String,
+        args: String,
+        track_root_hash: bool,
+        expected_root_hash: Option<String>,
+    ) -> impl std::future::Future<Output = eyre::Result<serde_json::Value>> + Send + 'static {
+        let inner = self.inner.clone();
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+        let max_request_bytes = self.max_request_bytes;
+        let oversized_args = self.oversized_args;
+        let wire = self.wire.clone();
+        let request_cache = self.request_cache.clone();
+        let retry_budget = self.retry_budget.clone();
+        let retry_config = self.retry.clone();
+        let context_guards = self.context_guards.clone();
+        let metadata_cache = self.metadata_cache.clone();
+        let track_root_hash = track_root_hash || expected_root_hash.is_some();
+        let key = DedupWindow::key(&[
+            &context_id.to_string(),
+            &method,
+            &args,
+            if track_root_hash { "tracked" } else { "" },
+            expected_root_hash.as_deref().unwrap_or_default(),
+        ]);
+
+        self.dedup.run(key, async move {
+            let started_at = chrono::Utc::now().timestamp();
+            let result = async {
+                let _turn = context_guards.acquire(&context_id.to_string()).await;
+                let old_root_hash = match track_root_hash {
+                    true => Some(fetch_root_hash(&inner, &context_id).await?),
+                    false => None,
+                };
+                if let (Some(expected), Some(current)) = (&expected_root_hash, &old_root_hash) {
+                    if expected != current {
+                        eyre::bail!(
+                            "Root hash conflict: expected {}, context is at {}",
+                            expected,
+                            current
+                        );
+                    }
+                }
+                let mut prepared = request_cache.get_or_build(context_id, &method, &args)?;
+                let overflow = max_request_bytes.and_then(|limit| {
+                    Some((limits::json_overflow(&prepared.request, limit)?, limit))
+                });
+                if let Some((size, limit)) = overflow {
+                    if oversized_args == OversizedArgs::Error {
+                        return Err(limits::request_too_large(&method, size, limit));
+                    }
+                    let upload = inner
+                        .upload_blob(args.clone().into_bytes(), Some(&context_id))
+                        .await?;
+                    let upload = serde_json::to_value(upload)?;
+                    let blob_id = blob::find_string_field(&upload, "blobId").ok_or_else(|| {
+                        eyre::eyre!("Upload of oversized arguments returned no blobId")
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    let reference = limits::blob_reference(blob_id, args.len()).to_string();
+                    prepared = Arc::new(request_cache::PreparedRequest::build(
+                        context_id, &method, &reference,
+                    )?);
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                let mut response = None;
+
+                if wire.use_msgpack() {
+                    let body = prepared.msgpack()?;
+                    let reply = retry::with_retries(&retry_config, &retry_budget, false, || {
+                        http::post_msgpack(
+                            &client,
+                            &connection.api_url,
+                            "jsonrpc",
+                            &storage,
+                            connection.node_name.as_deref(),
+                            body.clone(),
+                            max_response_bytes,
+                        )
+                    })
+                    .await?;
+                    match reply {
+                        Some(body) => {
+                            wire.record(true);
+                            response = Some(wire::decode(&body)?);
+                        }
+                        None if wire.mode() == WireMode::Msgpack => {
+                            eyre::bail!("Node does not support the msgpack wire format")
+                        }
+                        None => {
+                            wire.record(false);
+                            if !retry_budget.try_retry() {
+                                eyre::bail!(
+                                    "Node rejected the msgpack wire format and the retry budget \
+                                     is spent; not retrying as JSON"
+                                );
+                            }
+                            error::record_retry("node rejected msgpack; retried as JSON");
+                        }
+                    }
+                }
+
+                let mut response = match response {
+                    Some(response) => response,
+                    // Hooks and transcripts only see requests sent through `http`.
+                    None if middleware::active().is_some() || transcript::active().is_some() => {
+                        let body = serde_json::to_value(&prepared.request)?;
+                        retry::with_retries(&retry_config, &retry_budget, false, || {
+                            http::send_json(
+                                &client,
+                                reqwest::Method::POST,
+                                &connection.api_url,
+                                "jsonrpc",
+                                &storage,
+                                connection.node_name.as_deref(),
+                                Some(&body),
+                                max_response_bytes,
+                            )
+                        })
+                        .await?
+                    }
+                    None => serde_json::to_value(
+                        retry::with_retries(&retry_config, &retry_budget, false, || {
+                            inner.execute_jsonrpc(prepared.request.clone())
+                        })
+                        .await?,
+                    )?,
+                };
+                if let Some(error) = response.get("error").filter(|error| !error.is_null()) {
+                    return Err(error::rpc_error(error));
+                }
+                if let (Some(old_root_hash), Some(fields)) =
+                    (old_root_hash, response.as_object_mut())
+                {
+                    let new_root_hash = fetch_root_hash(&inner, &context_id).await?;
+                    fields.insert("old_root_hash".to_string(), old_root_hash.into());
+                    fields.insert("new_root_hash".to_string(), new_root_hash.into());
+                }
+                let quota = quota::latest(&connection.api_url)
+                    .filter(|quota| quota.observed_at >= started_at);
+                if let (Some(quota), Some(fields)) = (quota, response.as_object_mut()) {
+                    let now = chrono::Utc::now().timestamp();
+                    fields.insert("quota".to_string(), quota.to_json(now));
+                }
+                Ok::<_, eyre::Report>(response)
             }
+            .await;
+            if track_root_hash {
+                metadata_cache.invalidate(MetadataKind::Context);
+            }
+            result
         })
     }
 
-    /// List applications
-    pub fn list_applications(&self) -> PyResult<PyObject> {
-        let inner = self.inner.clone();
-
-        Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_applications().await });
-
-            match result {
-                Ok(data) => {
-                    // Convert to JSON first, then to Python
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+    /// `call`, listed by `pending_executions()` while it runs and recorded
+    /// in the usage log once it completes.
+    fn record_usage<F>(
+        &self,
+        context_id: &ContextId,
+        method: &str,
+        executor_public_key: &str,
+        call: F,
+    ) -> impl std::future::Future<Output = eyre::Result<serde_json::Value>> + Send + 'static
+    where
+        F: std::future::Future<Output = eyre::Result<serde_json::Value>> + Send + 'static,
+    {
+        let usage = self.usage.clone();
+        let context_id = context_id.to_string();
+        let method = method.to_string();
+        let identity = Some(executor_public_key.to_string()).filter(|key| !key.is_empty());
+        let call = self
+            .in_flight
+            .track(&context_id, &method, identity.clone(), call);
+        async move {
+            let result = call.await;
+            usage.record(Execution {
+                context_id,
+                method,
+                identity,
+                at: chrono::Utc::now().timestamp_millis(),
+                ok: result.is_ok(),
+            });
+            result
+        }
+    }
+
+    /// `sub` claim of the access token calls currently go out with.
+    fn token_subject(&self) -> impl std::future::Future<Output = Option<String>> + Send + 'static {
+        let storage = self.storage.clone();
+        let node_name = self.connection.node_name.clone();
+        async move {
+            let tokens = storage
+                .load_tokens(node_name.as_deref()?)
+                .await
+                .ok()
+                .flatten();
+            let tokens = WipeOnDrop::new(tokens?);
+            let claims = security::jwt_claims(&tokens.access_token)?;
+            claims.get("sub")?.as_str().map(str::to_string)
+        }
+    }
+
+    /// `fut`, recorded in the audit log once it completes when the client
+    /// has one (see [`audit`]). `args` are hashed, never stored.
+    fn audited<F, T, E>(
+        &self,
+        operation: &'static str,
+        context_id: Option<String>,
+        method: Option<&str>,
+        args: serde_json::Value,
+        fut: F,
+    ) -> impl std::future::Future<Output = Result<T, E>>
+    where
+        F: std::future::Future<Output = Result<T, E>>,
+        E: std::fmt::Display,
+    {
+        let log = self.audit.clone();
+        let subject = self.token_subject();
+        let method = method.map(str::to_string);
+        async move {
+            let result = fut.await;
+            if let Some(log) = log {
+                let subject = subject.await;
+                log.record(audit::Entry {
+                    operation,
+                    context_id,
+                    method,
+                    args,
+                    subject,
+                    error: result.as_ref().err().map(error::render_chain),
+                });
+            }
+            result
+        }
+    }
+
+    /// Record the calls of a batch that were sent, as `(context_id,
+    /// method, args, error)`, in the audit log when the client has one.
+    fn audit_batch<'a>(
+        &self,
+        operation: &'static str,
+        sent: impl Iterator<Item = (String, &'a str, &'a str, Option<&'a str>)>,
+    ) {
+        let Some(log) = &self.audit else {
+            return;
+        };
+        let subject = self
+            .runtime()
+            .ok()
+            .and_then(|runtime| runtime.block_on(self.token_subject()));
+        for (context_id, method, args, error) in sent {
+            log.record(audit::Entry {
+                operation,
+                context_id: Some(context_id),
+                method: Some(method.to_string()),
+                args: serde_json::json!({ "args": args }),
+                subject: subject.clone(),
+                error: error.map(str::to_string),
+            });
+        }
+    }
+
+    /// The `execute_function` request for `dry_run=True`, built but not
+    /// sent.
+    fn preview_execute(
+        &self,
+        context_id: ContextId,
+        method: &str,
+        args: &str,
+    ) -> PyResult<PyObject> {
+        let prepared = request_cache::PreparedRequest::build(context_id, method, args)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let overflow = self
+            .max_request_bytes
+            .and_then(|limit| Some((limits::json_overflow(&prepared.request, limit)?, limit)));
+        if let (Some((size, limit)), OversizedArgs::Error) = (overflow, self.oversized_args) {
+            return Err(self.client_error(limits::request_too_large(method, size, limit)));
+        }
+        let body = serde_json::to_value(&prepared.request).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to serialize request: {}",
+                e
+            ))
+        })?;
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let msgpack = self.wire.use_msgpack();
+
+        Python::with_gil(|py| {
+            let call_context = Some(context_id.to_string());
+            let preview = self
+                .run_in_context("execute_function", call_context, async move {
+                    http::preview_post(
+                        &connection.api_url,
+                        "jsonrpc",
+                        &storage,
+                        connection.node_name.as_deref(),
+                        body,
+                        msgpack,
+                    )
+                    .await
+                })?
+                .map_err(|e| self.client_error(e))?;
+            let json_data = serde_json::to_value(preview).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to serialize response: {}",
+                    e
+                ))
+            })?;
+            self.to_python(py, &json_data)
+        })
+    }
+
+    /// A task group call as a future holding its own limiter slot.
+    pub(crate) fn group_call(&self, call: &GroupCall) -> PyResult<GroupFuture> {
+        self.ensure_not_frozen(&call.context_id)?;
+        let args = self.prepare_args(&call.context_id, &call.method, &call.args, None)?;
+        let limiter = self.limiter.clone();
+        let audit_args = serde_json::json!({ "args": call.args });
+        let request = self.execute_call(call.context_id, call.method.clone(), args, false, None);
+        let request = self.record_usage(
+            &call.context_id,
+            &call.method,
+            &call.executor_public_key,
+            request,
+        );
+        let request = self.audited(
+            "execute_function",
+            Some(call.context_id.to_string()),
+            Some(&call.method),
+            audit_args,
+            request,
+        );
+        Ok(Box::pin(async move {
+            let _slot = limiter.acquire().await;
+            request.await
+        }))
+    }
+
+    /// The calls of a task group joined with all-or-cancel semantics; the
+    /// index of a call that failed is stored in `failed`.
+    fn group_future(
+        &self,
+        calls: &[GroupCall],
+        failed: Arc<OnceLock<usize>>,
+    ) -> PyResult<
+        impl std::future::Future<Output = eyre::Result<Vec<serde_json::Value>>> + Send + 'static,
+    > {
+        let methods: Vec<String> = calls.iter().map(|call| call.method.clone()).collect();
+        let futures = calls
+            .iter()
+            .map(|call| self.group_call(call))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(async move {
+            task_group::join_all_or_cancel(futures)
+                .await
+                .map_err(|(index, e)| {
+                    let _ = failed.set(index);
+                    e.wrap_err(format!(
+                        "Task group call {} ({}) failed; the other calls were cancelled",
+                        index, methods[index]
+                    ))
+                })
+        })
+    }
+
+    /// Run a task group's calls, blocking until they all complete or one
+    /// fails.
+    pub(crate) fn run_group(
+        &self,
+        calls: &[GroupCall],
+        failed: Arc<OnceLock<usize>>,
+    ) -> PyResult<Vec<serde_json::Value>> {
+        let group = self.group_future(calls, failed)?;
+        match self.run_batch("task_group", group)? {
+            Ok(results) => {
+                results
+                    .iter()
+                    .try_for_each(|result| self.check_size(result))?;
+                Ok(results)
+            }
+            Err(e) => Err(self.client_error(e)),
+        }
+    }
+
+    /// Asyncio variant of [`Self::run_group`].
+    pub(crate) fn run_group_async<'py>(
+        slf: &Bound<'py, Self>,
+        calls: &[GroupCall],
+        failed: Arc<OnceLock<usize>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let group = slf.borrow().group_future(calls, failed)?;
+        Self::run_async(slf, "task_group", None, false, async move {
+            group.await.map(serde_json::Value::Array)
+        })
+    }
+
+    /// `download_blob(..., dest_path=...)`: stream a blob to a file,
+    /// resuming from its partial download when `resume` is set.
+    fn download_blob_to_file(
+        &self,
+        blob_id: blobs::BlobId,
+        context_id: Option<&str>,
+        dest_path: &std::path::Path,
+        verify: bool,
+        resume: bool,
+        mut progress: blob::Progress,
+    ) -> PyResult<PyObject> {
+        let path = match context_id {
+            Some(ctx_id) => {
+                let context_id = self.context_id(ctx_id)?;
+                format!("admin-api/blobs/{blob_id}?context_id={context_id}")
+            }
+            None => format!("admin-api/blobs/{blob_id}"),
+        };
+        let part_path = blob::partial_path(dest_path);
+        let offset = match resume {
+            true => std::fs::metadata(&part_path).map_or(0, |meta| meta.len()),
+            false => 0,
+        };
+        let inner = self.inner.clone();
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+
+        Python::with_gil(|py| {
+            let call_context = context_id.map(str::to_string);
+            let result = self.run_in_context("download_blob", call_context, async {
+                let expected = match verify {
+                    true => Some(blob::fetch_expected_hash(&inner, &blob_id).await?),
+                    false => None,
+                };
+                let (response, start) = match http::get_from(
+                    &client,
+                    &connection.api_url,
+                    &path,
+                    &storage,
+                    connection.node_name.as_deref(),
+                    offset,
+                )
+                .await?
+                {
+                    Some((response, start)) => (Some(response), start),
+                    None => (None, offset),
+                };
+                let size = blob::stream_to_file(
+                    response,
+                    start,
+                    &part_path,
+                    dest_path,
+                    Some(&blob_id),
+                    expected.as_ref(),
+                    &mut progress,
+                )
+                .await?;
+                Ok::<_, eyre::Report>(blob::FileDownload {
+                    blob_id: blob_id.to_string(),
+                    path: dest_path.to_string_lossy().into_owned(),
+                    size,
+                    resumed_from: start,
+                })
+            })?;
+
+            match result {
+                Ok(download) => {
+                    let value = serde_json::to_value(download).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &value)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
+                Err(e) => Err(self.client_error(e)),
+            }
+        })
+    }
+
+    /// The client's subscription WebSocket, started on first use.
+    fn subscription_hub(&self) -> PyResult<Arc<Hub>> {
+        let runtime = self.runtime()?;
+        if let Some(hub) = self.subscriptions.get() {
+            return Ok(hub.clone());
+        }
+        let url = subscriptions::ws_url(&self.connection.api_url)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e)))?;
+        let hub = self.subscriptions.get_or_init(|| {
+            Arc::new(Hub::start(
+                runtime.handle(),
+                url,
+                self.storage.clone(),
+                self.connection.node_name.clone(),
+            ))
+        });
+        Ok(hub.clone())
+    }
+
+    /// Write the snapshot `delete_context(export_to=...)` takes before
+    /// deleting; returns the file written.
+    fn export_context(
+        &self,
+        context_id: &ContextId,
+        export_to: &std::path::Path,
+    ) -> PyResult<std::path::PathBuf> {
+        let inner = self.inner.clone();
+        let context_id = *context_id;
+        let record = self
+            .run_retrying(
+                "export_context",
+                Some(context_id.to_string()),
+                true,
+                || async {
+                    Ok::<_, eyre::Report>(retention::ContextRecord {
+                        context: serde_json::to_value(inner.get_context(&context_id).await?)?,
+                        storage: serde_json::to_value(
+                            inner.get_context_storage(&context_id).await?,
+                        )?,
+                        identities: serde_json::to_value(
+                            inner.get_context_identities(&context_id, false).await?,
+                        )?,
+                        identity_aliases: serde_json::to_value(
+                            inner
+                                .list_aliases::<identity::PublicKey>(Some(context_id))
+                                .await?,
+                        )?,
+                    })
+                },
+            )?
+            .map_err(|e| {
+                self.client_error(e.wrap_err("Export before deletion failed; context not deleted"))
+            })?;
+
+        let context_id = context_id.to_string();
+        let events = self
+            .subscriptions
+            .get()
+            .map(|hub| hub.history(&context_id))
+            .unwrap_or_default();
+        let path = retention::target_path(export_to, &context_id);
+        retention::write(&path, &retention::snapshot(&context_id, record, events)).map_err(
+            |e| {
+                PyErr::new::<pyo3::exceptions::PyOSError, _>(format!(
+                    "{:#}; context {} not deleted",
+                    e, context_id
+                ))
+            },
+        )?;
+        Ok(path)
+    }
+
+    /// `metrics()` snapshot, for `ClientPool.metrics()`.
+    pub(crate) fn metrics_snapshot(&self) -> serde_json::Value {
+        let retries = self.retry_budget.stats()["retries"]
+            .as_u64()
+            .unwrap_or_default();
+        self.metrics.snapshot(retries)
+    }
+
+    /// HTTP client and API URL the node's health can be probed with.
+    pub(crate) fn probe_target(&self) -> (reqwest::Client, url::Url) {
+        (self.http.clone(), self.connection.api_url.clone())
+    }
+
+    /// Entries queued in the offline outbox, for `calimero.snapshot()`.
+    pub(crate) fn outbox_entries(&self) -> Vec<OutboxEntry> {
+        self.outbox.entries()
+    }
+
+    /// Queue the snapshotted outbox `entries` (`OutboxEntry::to_json`) again,
+    /// keeping their original queue times.
+    pub(crate) fn restore_outbox(&self, entries: &[serde_json::Value]) -> eyre::Result<()> {
+        for entry in entries {
+            let context_id = entry["contextId"]
+                .as_str()
+                .unwrap_or_default()
+                .parse::<ContextId>()
+                .map_err(|e| eyre::eyre!("Invalid outbox entry {}: {}", entry, e))?;
+            let (Some(method), Some(args)) = (entry["method"].as_str(), entry["args"].as_str())
+            else {
+                eyre::bail!("Invalid outbox entry {}", entry);
+            };
+            let queued_at = entry["queuedAt"].as_i64().unwrap_or_default();
+            self.outbox.push(context_id, method, args, queued_at);
+        }
+        Ok(())
+    }
+
+    fn pool_stats(&self) -> serde_json::Value {
+        let mut stats = self.pool_config.to_json();
+        stats["pooledClients"] = pool::pooled_clients().into();
+        stats
+    }
+
+    /// Request context attached to `InternalError` (no credentials).
+    fn panic_context(&self, operation: &str) -> serde_json::Value {
+        serde_json::json!({
+            "operation": operation,
+            "api_url": self.connection.api_url.to_string(),
+            "node_name": self.connection.node_name,
+            "wire_format": self.wire.active(),
+            "http_version": self.http_version(),
+            "client_version": env!("CARGO_PKG_VERSION"),
+        })
+    }
+}
+
+#[pymethods]
+impl PyClient {
+    /// `http3=True` (experimental, `http3` feature) sends the requests this
+    /// binding issues directly — ranged reads and streaming uploads — over
+    /// HTTP/3. Typed calls still go through `calimero-client`'s transport.
+    ///
+    /// `wire` selects the `execute_function` encoding: `"auto"` tries
+    /// gzip+msgpack and falls back to JSON for nodes that don't support it,
+    /// `"json"` and `"msgpack"` force one format.
+    ///
+    /// `auth_mode_ttl` is how long (seconds) a detected auth mode is reused
+    /// before the node is probed again.
+    ///
+    /// `max_response_bytes` (default 64 MiB) rejects larger responses with
+    /// `ResponseTooLargeError` before they are materialized as Python
+    /// objects; `None` disables the guard.
+    ///
+    /// `request_cache_size` bounds how many prepared `execute_function`
+    /// requests are kept for reuse by repeated calls; `0` disables it.
+    ///
+    /// `max_concurrency` caps the adaptive concurrency limit: requests to the
+    /// node are gated by a limit that grows while latency stays flat and
+    /// shrinks when it climbs or requests fail (see `concurrency_stats`).
+    ///
+    /// `user` keys cached tokens by (node, user) instead of the node alone,
+    /// so one process can act as several users of the same node without
+    /// their sessions overwriting each other. `profile` is the same setting
+    /// under the name used for identities such as `"admin"` and `"member"`;
+    /// pass either. Clients without one keep using the node's existing
+    /// cache file.
+    ///
+    /// `metadata_ttl` is how long (seconds) `get_application`,
+    /// `list_applications`, `get_context` and `list_contexts` responses are
+    /// served from a local cache; pass `refresh=True` to those calls to skip
+    /// it, or `0` here to disable caching.
+    ///
+    /// `retry_budget` is the share of requests (0–1, default 0.2) that may be
+    /// retries, so retries cannot pile onto a struggling node; a retry over
+    /// budget raises the original error instead. See `stats()`.
+    ///
+    /// `retry` (a `RetryConfig`) retries calls that fail transiently, with
+    /// exponential backoff and jitter. Reads are retried on every listed
+    /// failure; `execute_function` and other mutations only when the node
+    /// cannot have applied them. Without it nothing is retried.
+    ///
+    /// `refresh_margin` (seconds, default 60) refreshes cached tokens that
+    /// are this close to expiry before the next call, instead of waiting
+    /// for the node to reject them; `None` disables proactive refresh.
+    ///
+    /// `maintenance_wait` (seconds, default 300) is how long calls wait for
+    /// a maintenance window the node announced (a 503 with
+    /// `X-Calimero-Maintenance`) to end before going out; `None` sends them
+    /// right away. See `on_maintenance`.
+    ///
+    /// `dedup_window_ms` folds accidental double submits: an
+    /// `execute_function` identical to one started less than this many
+    /// milliseconds earlier is not sent again and returns that call's
+    /// result (or error). Off by default.
+    ///
+    /// Direct HTTP requests go through a connection pool shared by every
+    /// client of the same node (and TLS settings) in the process:
+    /// `pool_max_idle` keep-alive connections are kept per host for
+    /// `pool_idle_timeout` seconds. `max_connections_per_host` caps
+    /// concurrent requests to the node across all those clients.
+    ///
+    /// `production=True` makes the client refuse bulk deletes and
+    /// development application installs, raising `DangerousOperationError`,
+    /// unless the call passes `allow_dangerous=True`.
+    ///
+    /// `connect_timeout` (seconds) limits establishing a connection for
+    /// direct requests; `request_timeout` (seconds) limits a whole call,
+    /// retries included, which then raises with `ErrorCode.TIMEOUT`.
+    /// Both are off by default; `timeout()` overrides the request timeout
+    /// for a block of calls.
+    ///
+    /// `max_request_bytes` (default 2 MiB) is checked before an
+    /// `execute_function` request goes out. Larger arguments raise
+    /// `RequestTooLargeError` with `oversized_args="error"`; with
+    /// `oversized_args="blob"` they are uploaded as a blob and the method
+    /// gets `{"$blob": {"blobId", "size"}}` instead. `None` disables it.
+    ///
+    /// `read_cache_size` keeps up to that many `view()` results, each
+    /// served from memory for `read_cache_ttl` seconds (default 1); off
+    /// (`0`) by default. See `invalidate()`.
+    ///
+    /// `rate_limit` caps requests to the node at that many per second, in
+    /// bursts of up to `rate_burst` (default: one second's worth), shared
+    /// with every client of the node using the same settings. Requests over
+    /// the rate wait for their turn instead of being sent. Whether or not it
+    /// is set, a `429` with `Retry-After` holds further requests to the node
+    /// until then. See `rate_limit_stats`.
+    ///
+    /// `record` writes every request the client sends itself, with its
+    /// response, to that transcript file (JSON lines, credentials in headers
+    /// redacted); `replay` answers those requests from such a file without
+    /// network access, in recorded order. See `transcript_stats`.
+    ///
+    /// `namespace` confines the client's tokens to one tenant of a service
+    /// acting for many end users: token files go to the namespace's own
+    /// directory and other storages see the namespace in their keys (a
+    /// Python `ClientStorage` gets it as a leading argument). Combine it
+    /// with `user` for per-user sessions inside a tenant, and drop a
+    /// tenant with `ConnectionInfo.purge_namespace()`.
+    ///
+    /// `audit_log` records every mutating call (node, context, operation,
+    /// a hash of its arguments, the token subject and the outcome) in a
+    /// hash-chained JSON lines file at that path, or passes each record to
+    /// it when it is a callable. See `verify_audit_log()`. `audit_key`
+    /// makes the chain an HMAC under that key, so it cannot be recomputed
+    /// after an edit without the key; keep it away from the log.
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (connection, http3=false, wire="auto", auth_mode_ttl=auth::DEFAULT_AUTH_MODE_TTL_SECS, max_response_bytes=Some(limits::DEFAULT_MAX_RESPONSE_BYTES), request_cache_size=request_cache::DEFAULT_REQUEST_CACHE_SIZE, max_concurrency=concurrency::DEFAULT_MAX_CONCURRENCY, user=None, metadata_ttl=metadata_cache::DEFAULT_METADATA_TTL_SECS, retry_budget=retry_budget::DEFAULT_RETRY_BUDGET_RATIO, refresh_margin=Some(token_lifecycle::DEFAULT_REFRESH_MARGIN_SECS), maintenance_wait=Some(maintenance::DEFAULT_MAINTENANCE_WAIT_SECS), profile=None, dedup_window_ms=None, pool_max_idle=pool::DEFAULT_POOL_MAX_IDLE, pool_idle_timeout=pool::DEFAULT_POOL_IDLE_TIMEOUT_SECS, max_connections_per_host=None, retry=None, connect_timeout=None, request_timeout=None, production=false, max_request_bytes=Some(limits::DEFAULT_MAX_REQUEST_BYTES), oversized_args="error", read_cache_size=0, read_cache_ttl=read_cache::DEFAULT_READ_CACHE_TTL_SECS, rate_limit=None, rate_burst=None, record=None, replay=None, namespace=None, audit_log=None, audit_key=None))]
+    pub fn new(
+        py: Python<'_>,
+        connection: &Bound<'_, PyConnectionInfo>,
+        http3: bool,
+        wire: &str,
+        auth_mode_ttl: u64,
+        max_response_bytes: Option<usize>,
+        request_cache_size: usize,
+        max_concurrency: usize,
+        user: Option<&str>,
+        metadata_ttl: u64,
+        retry_budget: f64,
+        refresh_margin: Option<u64>,
+        maintenance_wait: Option<u64>,
+        profile: Option<&str>,
+        dedup_window_ms: Option<u64>,
+        pool_max_idle: usize,
+        pool_idle_timeout: u64,
+        max_connections_per_host: Option<usize>,
+        retry: Option<RetryConfig>,
+        connect_timeout: Option<f64>,
+        request_timeout: Option<f64>,
+        production: bool,
+        max_request_bytes: Option<usize>,
+        oversized_args: &str,
+        read_cache_size: usize,
+        read_cache_ttl: f64,
+        rate_limit: Option<f64>,
+        rate_burst: Option<u32>,
+        record: Option<std::path::PathBuf>,
+        replay: Option<std::path::PathBuf>,
+        namespace: Option<&str>,
+        audit_log: Option<Bound<'_, PyAny>>,
+        audit_key: Option<&str>,
+    ) -> PyResult<Self> {
+        let pickle_args = PyTuple::new_bound(
+            py,
+            [
+                connection.clone().into_any().unbind(),
+                http3.into_py(py),
+                wire.into_py(py),
+                auth_mode_ttl.into_py(py),
+                max_response_bytes.into_py(py),
+                request_cache_size.into_py(py),
+                max_concurrency.into_py(py),
+                user.into_py(py),
+                metadata_ttl.into_py(py),
+                retry_budget.into_py(py),
+                refresh_margin.into_py(py),
+                maintenance_wait.into_py(py),
+                profile.into_py(py),
+                dedup_window_ms.into_py(py),
+                pool_max_idle.into_py(py),
+                pool_idle_timeout.into_py(py),
+                max_connections_per_host.into_py(py),
+                retry.clone().into_py(py),
+                connect_timeout.into_py(py),
+                request_timeout.into_py(py),
+                production.into_py(py),
+                max_request_bytes.into_py(py),
+                oversized_args.into_py(py),
+                read_cache_size.into_py(py),
+                read_cache_ttl.into_py(py),
+                rate_limit.into_py(py),
+                rate_burst.into_py(py),
+                record.clone().into_py(py),
+                replay.clone().into_py(py),
+                namespace.into_py(py),
+                audit_log.clone().into_py(py),
+                audit_key.into_py(py),
+            ],
+        )
+        .unbind();
+        let connection = connection.borrow();
+        let user = match (user, profile) {
+            (Some(user), Some(profile)) if user != profile => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "user and profile name the same setting; pass one of them",
+                ));
+            }
+            (user, profile) => user.or(profile),
+        };
+        if !(0.0..=1.0).contains(&retry_budget) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "retry_budget must be between 0 and 1",
+            ));
+        }
+        let wire =
+            WireMode::parse(wire).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        let oversized_args = OversizedArgs::parse(oversized_args)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let read_cache_ttl =
+            timeouts::seconds("read_cache_ttl", Some(read_cache_ttl))?.unwrap_or_default();
+        let runtime = Arc::new(
+            Runtime::new()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        );
+
+        if let Some(namespace) = namespace {
+            cache::validate_namespace(namespace)
+                .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        }
+
+        // Extract the inner connection from the Arc, rebinding it to the
+        // user's (or namespace's) slice of the token cache when given
+        let unscoped = user.is_none() && namespace.is_none();
+        let (connection_inner, storage) = match (user, namespace) {
+            _ if unscoped || connection.storage.is_anonymous() => (
+                connection.inner.as_ref().clone(),
+                connection.storage.clone(),
+            ),
+            (user, namespace) => {
+                if user.is_some_and(str::is_empty) {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "user must be a non-empty string",
+                    ));
+                }
+                let mut storage = connection.storage.clone();
+                if let Some(user) = user {
+                    storage = storage.with_user(user);
+                }
+                if let Some(namespace) = namespace {
+                    storage = storage.with_namespace(namespace);
+                }
+                let inner = ConnectionInfo::new(
+                    connection.inner.api_url.clone(),
+                    connection.inner.node_name.clone(),
+                    CliAuthenticator::new(),
+                    storage.clone(),
+                );
+                (inner, storage)
+            }
+        };
+        let client = Client::new(connection_inner.clone()).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to create client: {}",
+                e
+            ))
+        })?;
+
+        let connect_timeout = timeouts::seconds("connect_timeout", connect_timeout)?;
+        let request_timeout = timeouts::seconds("request_timeout", request_timeout)?;
+        if max_connections_per_host == Some(0) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "max_connections_per_host must be at least 1",
+            ));
+        }
+        let pool_config = PoolConfig {
+            max_idle_per_host: pool_max_idle,
+            idle_timeout_secs: pool_idle_timeout,
+            max_connections_per_host,
+            connect_timeout,
+        };
+        let (http, pool_lease) = if http3 {
+            (build_http3_client()?, None)
+        } else {
+            let (http, lease) =
+                pool::lease(&connection_inner.api_url, &pool_config).map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                        "Failed to create client: {}",
+                        e
+                    ))
+                })?;
+            (http, Some(lease))
+        };
+        let host_limit = max_connections_per_host
+            .map(|limit| pool::host_limit(&connection_inner.api_url, limit));
+        let rate = match (rate_limit, rate_burst) {
+            (Some(rate), _) if !rate.is_finite() || rate <= 0.0 => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "rate_limit must be a positive number of requests per second",
+                ));
+            }
+            (_, Some(0)) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "rate_burst must be at least 1",
+                ));
+            }
+            (None, Some(_)) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "rate_burst needs rate_limit",
+                ));
+            }
+            (Some(rate), burst) => Some((rate, burst.unwrap_or(rate.ceil() as u32).max(1))),
+            (None, None) => None,
+        };
+        let throttle = Throttle::new(&connection_inner.api_url, rate);
+        let transcript = match (record, replay) {
+            (Some(_), Some(_)) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "record and replay are exclusive; pass one of them",
+                ));
+            }
+            (Some(path), None) => Some(Transcript::record(&path)),
+            (None, Some(path)) => Some(Transcript::replay(&path)),
+            (None, None) => None,
+        }
+        .transpose()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e)))?
+        .map(Arc::new);
+        let audit_node = connection_inner
+            .node_name
+            .clone()
+            .unwrap_or_else(|| connection_inner.api_url.to_string());
+        let audit_key = match audit_key {
+            Some("") => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "audit_key must not be empty",
+                ))
+            }
+            Some(_) if audit_log.is_none() => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "audit_key needs audit_log",
+                ))
+            }
+            key => key.map(audit::chain_key),
+        };
+        let audit = match audit_log {
+            None => None,
+            Some(sink) if sink.is_callable() => {
+                Some(AuditLog::python(sink.unbind(), &audit_node, audit_key))
+            }
+            Some(path) => Some(
+                AuditLog::open(
+                    &path.extract::<std::path::PathBuf>()?,
+                    &audit_node,
+                    audit_key,
+                )
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e)))?,
+            ),
+        }
+        .map(Arc::new);
+
+        for finding in security::check_connection(&connection_inner.api_url) {
+            warnings::warn(py, WarningKind::InsecureConfig, &finding.message)?;
+        }
+
+        if let Some(node_name) = connection_inner.node_name.as_deref() {
+            let storage = storage.clone();
+            let tokens = runtime.block_on(async { storage.load_tokens(node_name).await });
+            if let Ok(Some(tokens)) = tokens {
+                let tokens = WipeOnDrop::new(tokens);
+                let now = chrono::Utc::now().timestamp();
+                if let Some(message) = tokens
+                    .expires_at
+                    .and_then(|at| warnings::token_expiry_message(node_name, at, now))
+                {
+                    warnings::warn(py, WarningKind::TokenExpiry, &message)?;
+                }
+            }
+        }
+
+        if storage.persists_to_disk() {
+            metadata_store::ensure_migrated();
+        }
+
+        let token_lifecycle = refresh_margin
+            .filter(|_| !storage.is_anonymous())
+            .map(|margin| Arc::new(TokenLifecycle::new(margin)));
+
+        let metrics = Metrics::new(
+            connection_inner
+                .node_name
+                .clone()
+                .unwrap_or_else(|| pool::origin(&connection_inner.api_url)),
+        );
+
+        let metrics = Arc::new(metrics);
+        let reauth = connection_inner
+            .node_name
+            .clone()
+            .filter(|_| !storage.is_anonymous())
+            .map(|node_name| {
+                Arc::new(Reauth::new(
+                    http.clone(),
+                    connection_inner.api_url.clone(),
+                    storage.clone(),
+                    node_name,
+                    max_response_bytes,
+                    metrics.clone(),
+                ))
+            });
+
+        let auth_mode = AuthModeCache::new(
+            connection_inner.api_url.as_str(),
+            auth_mode_ttl,
+            storage.persists_to_disk(),
+        );
+
+        Ok(Self {
+            inner: Arc::new(client),
+            connection: Arc::new(connection_inner),
+            storage,
+            http,
+            http3,
+            wire: Arc::new(WireNegotiation::new(wire)),
+            auth_mode,
+            max_response_bytes,
+            max_request_bytes,
+            oversized_args,
+            request_cache: Arc::new(RequestCache::new(request_cache_size)),
+            metadata_cache: Arc::new(MetadataCache::new(metadata_ttl)),
+            read_cache: Arc::new(ReadCache::new(read_cache_size, read_cache_ttl)),
+            aliases: Arc::new(AliasCache::new(aliases::DEFAULT_ALIAS_TTL)),
+            method_defaults: Arc::new(MethodDefaults::default()),
+            arg_encodings: Arc::new(ArgEncodings::default()),
+            limiter: Arc::new(
+                AdaptiveLimiter::new(max_concurrency)
+                    .with_host_limit(host_limit)
+                    .with_throttle(throttle),
+            ),
+            pool_config,
+            retry_budget: Arc::new(RetryBudget::new(retry_budget)),
+            retry: Arc::new(retry.unwrap_or_else(RetryConfig::disabled)),
+            request_timeout,
+            production,
+            outbox: Arc::new(Outbox::new()),
+            archive: Arc::new(ArchiveSet::new()),
+            context_guards: Arc::new(ContextGuards::new()),
+            dedup: Arc::new(DedupWindow::new(dedup_window_ms)),
+            batch_support: Arc::new(BatchSupport::new()),
+            usage: Arc::new(UsageLog::new()),
+            in_flight: Arc::new(InFlight::new()),
+            signed_nonces: Arc::new(ReplayGuard::default()),
+            middleware: Arc::new(Middleware::new()),
+            transcript,
+            audit,
+            metrics,
+            token_lifecycle,
+            token_refresh: Mutex::new(None),
+            reauth,
+            pickle_args,
+            maintenance: Arc::new(MaintenanceGate::new(
+                maintenance_wait.map(std::time::Duration::from_secs),
+            )),
+            login: Arc::new(LoginTracker::new(LoginState::LoggedOut)),
+            subscriptions: OnceLock::new(),
+            runtime: ClientRuntime::new(runtime),
+            pool_lease,
+        })
+    }
+
+    /// Whether the node requires authentication.
+    ///
+    /// Probed once and cached (across processes) for `auth_mode_ttl`
+    /// seconds; an authentication error from any call forces a re-probe.
+    #[getter]
+    pub fn auth_mode(&self) -> PyResult<PyAuthMode> {
+        if let Some(mode) = self.auth_mode.get() {
+            return Ok(PyAuthMode { mode });
+        }
+        self.refresh_auth_mode()
+    }
+
+    /// `{seq, hash}` of the last audit record this client's chain ends
+    /// at, `None` without `audit_log=`. Copy it to a store the log's
+    /// writers cannot change to anchor the chain up to that record.
+    #[getter]
+    pub fn audit_head(&self, py: Python<'_>) -> Option<PyObject> {
+        let (seq, hash) = self.audit.as_ref()?.head()?;
+        Some(json_to_python(
+            py,
+            &serde_json::json!({ "seq": seq, "hash": hash }),
+        ))
+    }
+
+    /// Probe the node's auth mode now, bypassing the cache
+    pub fn refresh_auth_mode(&self) -> PyResult<PyAuthMode> {
+        let connection = self.connection.clone();
+        let mode = self
+            .run("refresh_auth_mode", async move {
+                connection.detect_auth_mode().await
+            })?
+            .map_err(|e| self.client_error(e))?;
+        self.auth_mode.store(mode);
+        Ok(PyAuthMode { mode })
+    }
+
+    /// The auth service's JSON Web Key Set, `{keys: [...]}`, for checking
+    /// token signatures locally.
+    ///
+    /// Cached on disk across processes for the response's `max-age` (one
+    /// hour by default), then revalidated with its `ETag`; a stale copy is
+    /// served when the node is unreachable. `refresh=True` always asks the
+    /// node.
+    #[pyo3(signature = (refresh=false))]
+    pub fn auth_jwks(&self, py: Python<'_>, refresh: bool) -> PyResult<PyObject> {
+        let client = self.http.clone();
+        let url =
+            auth_metadata::jwks_url(&self.connection.api_url).map_err(|e| self.client_error(e))?;
+        let jwks = self
+            .run("auth_jwks", async move {
+                auth_metadata::DocumentCache::default()
+                    .fetch(&client, &url, refresh)
+                    .await
+            })?
+            .map_err(|e| self.client_error(e))?;
+        Ok(json_to_python(py, &jwks))
+    }
+
+    /// Pre-establish connections so the first real call skips setup cost.
+    ///
+    /// Resolves the node's host, opens a TLS connection on the client used
+    /// for direct requests and on the typed client's pool (probing the auth
+    /// mode, which is cached as a side effect). Idle connections stay pooled
+    /// for 90 seconds and TLS sessions are resumed on reconnect. Returns
+    /// `{addresses, dnsMs, directMs, typedMs, status}`.
+    pub fn warm_up(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let connection = self.connection.clone();
+        let client = self.http.clone();
+        let (report, mode) = self
+            .run("warm_up", async move {
+                let url = &connection.api_url;
+                let host = url
+                    .host_str()
+                    .ok_or_else(|| eyre::eyre!("Node URL has no host"))?
+                    .to_string();
+                let port = url.port_or_known_default().unwrap_or(80);
+
+                let started = std::time::Instant::now();
+                let addresses: Vec<String> = tokio::net::lookup_host((host.as_str(), port))
+                    .await
+                    .map_err(|e| eyre::eyre!("Failed to resolve {}: {}", host, e))?
+                    .map(|addr| addr.to_string())
+                    .collect();
+                let dns_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+                let started = std::time::Instant::now();
+                let status = http::warm_up(&client, url).await?;
+                let direct_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+                let started = std::time::Instant::now();
+                let mode = connection
+                    .detect_auth_mode()
+                    .await
+                    .map_err(|e| eyre::eyre!("{}", e))?;
+                let typed_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+                Ok::<_, eyre::Report>((
+                    serde_json::json!({
+                        "addresses": addresses,
+                        "dnsMs": dns_ms,
+                        "directMs": direct_ms,
+                        "typedMs": typed_ms,
+                        "status": status.as_u16(),
+                    }),
+                    mode,
+                ))
+            })?
+            .map_err(|e| self.client_error(e))?;
+        self.auth_mode.store(mode);
+        Ok(json_to_python(py, &report))
+    }
+
+    /// Measure the network path and clock of the node (or of the node at
+    /// `node`, a URL or `NodeAddress`) over `samples` samples, to tell
+    /// network problems from node slowness.
+    ///
+    /// Each sample times a fresh TCP connect and TLS handshake plus an
+    /// unauthenticated health request. Returns `{node, samples, failed,
+    /// connectMs, tlsHandshakeMs, rttMs, serverMs, clockOffsetMs, status,
+    /// errors}`: timings are `{min, median, max, mean}` in milliseconds,
+    /// `serverMs` estimates the node's share of the request (median round
+    /// trip minus median connect), and `clockOffsetMs` is the node's clock
+    /// minus ours, from its `Date` header (one-second resolution).
+    #[pyo3(signature = (node=None, samples=probe::DEFAULT_PROBE_SAMPLES))]
+    pub fn probe(
+        &self,
+        py: Python<'_>,
+        node: Option<NodeAddress>,
+        samples: usize,
+    ) -> PyResult<PyObject> {
+        if samples == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "samples must be at least 1",
+            ));
+        }
+        let url = match node {
+            Some(node) => node.into_url(),
+            None => self.connection.api_url.clone(),
+        };
+        let client = self.http.clone();
+        let report = self.run("probe", async move {
+            let mut taken = Vec::with_capacity(samples);
+            for _ in 0..samples {
+                taken.push(probe::sample(&client, &url).await);
+            }
+            probe::report(&url, &taken)
+        })?;
+        Ok(json_to_python(py, &report))
+    }
+
+    /// Health of the node from its unauthenticated health endpoint, as a
+    /// `NodeHealth` (`healthy`, `status`, `http_status`, `latency_ms`,
+    /// `version`, `error`). An unreachable node is reported, not raised;
+    /// `bool(client.health())` tells whether it is up.
+    pub fn health(&self) -> PyResult<PyNodeHealth> {
+        let client = self.http.clone();
+        let url = self.connection.api_url.clone();
+        self.run(
+            "health",
+            async move { node_status::check(&client, &url).await },
+        )
+    }
+
+    /// What is known about the node, as a `NodeInfo`: its health and
+    /// advertised version, auth mode, peer count and the capabilities
+    /// negotiated so far (`capabilities`: wire format, JSON-RPC batches).
+    /// Auth mode and peers are only asked of a healthy node, and are
+    /// `None` when it does not answer them.
+    pub fn node_info(&self) -> PyResult<PyNodeInfo> {
+        let health = self.health()?;
+        let (auth_mode, peer_count) = match health.healthy() {
+            true => (
+                self.auth_mode().ok().map(|mode| mode.value().to_string()),
+                self.peers().ok().map(|peers| peers.count()),
+            ),
+            false => (None, None),
+        };
+        Ok(PyNodeInfo {
+            api_url: self.connection.api_url.to_string(),
+            node_name: self.connection.node_name.clone(),
+            health,
+            auth_mode,
+            peer_count,
+            wire_format: self.wire.active(),
+            batch_mode: self.batch_support.mode(),
+        })
+    }
+
+    /// Block until the node reports healthy, polling its health endpoint
+    /// with backoff (0.1s doubling up to 2s between attempts); returns the
+    /// healthy `NodeHealth`. Raises a timeout `ClientError` with the last
+    /// failure when the node is not ready within `timeout` seconds. Not
+    /// bounded by `request_timeout`; Ctrl-C interrupts it.
+    #[pyo3(signature = (timeout=30.0))]
+    pub fn wait_until_ready(&self, timeout: f64) -> PyResult<PyNodeHealth> {
+        let timeout = timeouts::seconds("timeout", Some(timeout))?.unwrap_or_default();
+        let client = self.http.clone();
+        let url = self.connection.api_url.clone();
+        let outcome = timeouts::block_on(
+            &self.runtime()?,
+            node_status::wait_until_ready(&client, &url, timeout),
+        )?;
+        outcome.map_err(|health| {
+            self.client_error(format!(
+                "wait_until_ready timed out after {}s: {}",
+                timeout.as_secs_f64(),
+                health.error().as_deref().unwrap_or("node not ready")
+            ))
+        })
+    }
+
+    /// Current login state (`LoginState`).
+    ///
+    /// While callbacks are registered this is the tracked state, which also
+    /// reflects authentication failures; otherwise it is read from the token
+    /// cache.
+    #[getter]
+    pub fn login_state(&self) -> LoginState {
+        if self.login.is_observed() {
+            self.login.get()
+        } else {
+            self.settled_login_state()
+        }
+    }
+
+    /// Call `callback(old, new)` with `LoginState`s whenever the login state
+    /// changes: `LOGGING_IN`/`REFRESHING` before a call that has to log in
+    /// or refresh, `LOGGED_IN`, `EXPIRED` or `LOGGED_OUT` once it settles.
+    /// Callbacks run on the calling thread; their exceptions are printed
+    /// rather than raised.
+    ///
+    /// Returns a `CallbackHandle` whose `unsubscribe()` removes the
+    /// callback. Bound methods are held weakly unless `weak=False`, so
+    /// registering one does not keep its object alive; see `CallbackHandle`.
+    #[pyo3(signature = (callback, weak=None))]
+    pub fn on_login_state_change(
+        &self,
+        py: Python<'_>,
+        callback: PyObject,
+        weak: Option<bool>,
+    ) -> PyResult<PyCallbackHandle> {
+        let callback = Callback::new(py, callback, weak)?;
+        if !self.login.is_observed() {
+            // Start from the current state without reporting a transition.
+            self.login.transition(py, self.settled_login_state());
+        }
+        Ok(self.login.subscribe(callback))
+    }
+
+    /// Remove every `on_login_state_change` callback
+    pub fn clear_login_state_callbacks(&self) {
+        self.login.clear();
+    }
+
+    /// Call `callback(in_maintenance, retry_after)` when the node announces
+    /// a maintenance window (`True` and the window in seconds, or `None`
+    /// when it gave none) and when calls resume after it (`False, None`).
+    /// Callbacks run on the calling thread; their exceptions are printed
+    /// rather than raised. Returns a `CallbackHandle`, as
+    /// `on_login_state_change` does.
+    #[pyo3(signature = (callback, weak=None))]
+    pub fn on_maintenance(
+        &self,
+        py: Python<'_>,
+        callback: PyObject,
+        weak: Option<bool>,
+    ) -> PyResult<PyCallbackHandle> {
+        let callback = Callback::new(py, callback, weak)?;
+        Ok(self.maintenance.subscribe(callback))
+    }
+
+    /// Remove every `on_maintenance` callback
+    pub fn clear_maintenance_callbacks(&self) {
+        self.maintenance.clear();
+    }
+
+    /// Refresh cached tokens in the background: every `interval` seconds,
+    /// on the client's runtime, the tokens of its node (and of the nodes
+    /// in `nodes`, `{node_name: api_url}` whose tokens share this client's
+    /// storage) that expire within `window` seconds are exchanged for new
+    /// ones, so requests never wait on a refresh. `on_refresh(node,
+    /// expires_at)` and `on_error(node, message)` are called from a
+    /// runtime thread; their exceptions are printed rather than raised,
+    /// and bound methods are held weakly unless `weak=False`.
+    ///
+    /// Returns a `TokenRefresher`; it runs until its `stop()` (or the end
+    /// of its `with` block), until another refresher is started, or until
+    /// the client is closed.
+    #[pyo3(signature = (interval=token_refresh::DEFAULT_REFRESH_INTERVAL_SECS, window=token_refresh::DEFAULT_REFRESH_WINDOW_SECS, on_refresh=None, on_error=None, nodes=None, weak=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn start_token_refresh(
+        &self,
+        py: Python<'_>,
+        interval: f64,
+        window: f64,
+        on_refresh: Option<PyObject>,
+        on_error: Option<PyObject>,
+        nodes: Option<std::collections::BTreeMap<String, NodeAddress>>,
+        weak: Option<bool>,
+    ) -> PyResult<PyTokenRefresher> {
+        if !(interval.is_finite() && interval > 0.0) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "interval must be a positive number of seconds",
+            ));
+        }
+        if !(window.is_finite() && window >= 0.0) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "window must be a non-negative number of seconds",
+            ));
+        }
+        if self.storage.is_anonymous() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "An anonymous client has no tokens to refresh",
+            ));
+        }
+        let mut targets: Vec<(String, url::Url)> = self
+            .connection
+            .node_name
+            .iter()
+            .map(|node_name| (node_name.clone(), self.connection.api_url.clone()))
+            .collect();
+        for (node_name, api_url) in nodes.unwrap_or_default() {
+            if !targets.iter().any(|(name, _)| *name == node_name) {
+                targets.push((node_name, api_url.into_url()));
+            }
+        }
+        if targets.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Background token refresh needs a connection with a node_name or `nodes`",
+            ));
+        }
+        let refresher = token_refresh::Refresher {
+            lifecycle: self
+                .token_lifecycle
+                .clone()
+                .unwrap_or_else(|| Arc::new(TokenLifecycle::new(0))),
+            storage: self.storage.clone(),
+            http: self.http.clone(),
+            metrics: self.metrics.clone(),
+            nodes: targets,
+            interval: std::time::Duration::from_secs_f64(interval),
+            window: window as u64,
+            on_refresh: on_refresh
+                .map(|callback| Callback::new(py, callback, weak))
+                .transpose()?,
+            on_error: on_error
+                .map(|callback| Callback::new(py, callback, weak))
+                .transpose()?,
+        };
+        let task = refresher.spawn(self.runtime()?.handle());
+        if let Ok(mut current) = self.token_refresh.lock() {
+            if let Some(previous) = current.replace(task.clone()) {
+                previous.stop();
+            }
+        }
+        Ok(PyTokenRefresher::new(task))
+    }
+
+    /// Stop the refresher started by `start_token_refresh()`; returns
+    /// whether one was running
+    pub fn stop_token_refresh(&self) -> bool {
+        self.token_refresh
+            .lock()
+            .ok()
+            .and_then(|mut current| current.take())
+            .is_some_and(|task| task.stop())
+    }
+
+    /// Call `hook(request)` before each request the client sends itself,
+    /// with a dict of its `method`, `url` and `headers`; changes to
+    /// `request["headers"]` are sent, e.g. a correlation ID or proxy auth
+    /// header. An exception aborts the request with a `ClientError`.
+    ///
+    /// Hooks run on the calling thread (the event loop's for `*_async`
+    /// methods) and see JSON-RPC executions, blob streams and auth-service
+    /// calls; admin calls made by the typed client and WebSocket
+    /// subscriptions bypass them. While any hook is registered, executions
+    /// are sent over the binding's HTTP client rather than the typed one.
+    /// Returns a `CallbackHandle`, as `on_maintenance` does.
+    #[pyo3(signature = (hook, weak=None))]
+    pub fn add_request_hook(
+        &self,
+        py: Python<'_>,
+        hook: PyObject,
+        weak: Option<bool>,
+    ) -> PyResult<PyCallbackHandle> {
+        let callback = Callback::new(py, hook, weak)?;
+        Ok(self.middleware.subscribe_request(callback))
+    }
+
+    /// Call `hook(response)` once the headers of a response to a request
+    /// seen by `add_request_hook` hooks arrive, with a dict of its
+    /// `method`, `url`, `status`, `headers` and `elapsed_ms`. Exceptions
+    /// are printed rather than raised. Returns a `CallbackHandle`.
+    #[pyo3(signature = (hook, weak=None))]
+    pub fn add_response_hook(
+        &self,
+        py: Python<'_>,
+        hook: PyObject,
+        weak: Option<bool>,
+    ) -> PyResult<PyCallbackHandle> {
+        let callback = Callback::new(py, hook, weak)?;
+        Ok(self.middleware.subscribe_response(callback))
+    }
+
+    /// Remove every request and response hook
+    pub fn clear_hooks(&self) {
+        self.middleware.clear();
+    }
+
+    /// Whether the node announced a maintenance window that is not over
+    #[getter]
+    pub fn in_maintenance(&self) -> bool {
+        self.maintenance.in_maintenance()
+    }
+
+    /// Log in through an upstream identity provider (SSO) in the browser.
+    ///
+    /// `provider` is `"oidc"` (with `issuer`, whose discovery document
+    /// supplies the endpoints), `"github"` or `"google"`; `client_id` is the
+    /// OAuth client registered with that provider for a loopback redirect
+    /// (`http://127.0.0.1:<port>/callback`). The authorization-code flow
+    /// uses PKCE, so no client secret is needed. The browser is opened on
+    /// the provider's login page (the URL is also printed to stderr) and
+    /// the resulting identity is exchanged for node tokens, which are cached
+    /// like any other login. Returns the new `JwtToken`.
+    ///
+    /// The node's auth service must have the same provider enabled.
+    #[pyo3(signature = (client_id, provider="oidc", issuer=None, scopes=None, open_browser=true, timeout=sso::DEFAULT_LOGIN_TIMEOUT_SECS))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn login_sso(
+        &self,
+        py: Python<'_>,
+        client_id: &str,
+        provider: &str,
+        issuer: Option<&str>,
+        scopes: Option<Vec<String>>,
+        open_browser: bool,
+        timeout: u64,
+    ) -> PyResult<PyJwtToken> {
+        let node_name = self.login_node_name()?;
+        let issuer = issuer
+            .map(|issuer| {
+                url::Url::parse(issuer).map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Invalid issuer URL: {}",
+                        e
+                    ))
+                })
+            })
+            .transpose()?;
+        let preset = sso::Provider::preset(provider);
+        if preset.is_none() && (provider != "oidc" || issuer.is_none()) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown provider '{}': use 'github', 'google', or 'oidc' with issuer=",
+                provider
+            )));
+        }
+
+        self.login.transition(py, LoginState::LoggingIn);
+        let client = self.http.clone();
+        let prepared = self.run_batch("login_sso", async move {
+            let mut provider = match (preset, issuer) {
+                (Some(preset), _) => preset,
+                (None, Some(issuer)) => sso::Provider::discover(&client, &issuer).await?,
+                (None, None) => unreachable!("validated above"),
+            };
+            if let Some(scopes) = scopes {
+                provider.scopes = scopes;
+            }
+            let (listener, redirect_uri) = sso::bind_callback().await?;
+            Ok::<_, eyre::Report>((provider, listener, redirect_uri))
+        })?;
+        let (provider, listener, redirect_uri) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                self.login.transition(py, self.settled_login_state());
+                return Err(self.client_error(e));
+            }
+        };
+        let pkce = sso::Pkce::generate().map_err(|e| self.client_error(e))?;
+        let login_url = sso::authorization_url(&provider, client_id, &redirect_uri, &pkce);
+
+        py.import_bound("sys")?
+            .getattr("stderr")?
+            .call_method1("write", (format!("Log in to continue: {}\n", login_url),))?;
+        if open_browser {
+            // A headless machine has no browser; the printed URL still works.
+            let _ = py
+                .import_bound("webbrowser")
+                .and_then(|webbrowser| webbrowser.call_method1("open", (login_url.as_str(),)));
+        }
+
+        let client = self.http.clone();
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client_id = client_id.to_string();
+        let max_response_bytes = self.max_response_bytes;
+        let runtime = self.runtime()?;
+        error::begin_call("login_sso", None);
+        let context = self.panic_context("login_sso");
+        // Waiting on the browser can take minutes; let other threads run.
+        let result = py.allow_threads(move || {
+            error::catch_panic(
+                move || context,
+                || {
+                    timeouts::block_on(&runtime, async move {
+                        let code = sso::wait_for_code(
+                            &listener,
+                            &pkce.state,
+                            std::time::Duration::from_secs(timeout),
+                        )
+                        .await?;
+                        let upstream = sso::exchange_code(
+                            &client,
+                            &provider,
+                            &client_id,
+                            &code,
+                            &redirect_uri,
+                            &pkce,
+                        )
+                        .await?;
+                        let tokens = sso::exchange_for_node_tokens(
+                            &client,
+                            &connection.api_url,
+                            &storage,
+                            &provider,
+                            &upstream,
+                            max_response_bytes,
+                        )
+                        .await?;
+                        storage.save_tokens(&node_name, &tokens).await?;
+                        Ok::<_, eyre::Report>(tokens)
+                    })
+                },
+            )
+            .and_then(|outcome| outcome)
+        })?;
+
+        let result = result
+            .map(PyJwtToken::from)
+            .map_err(|e| self.client_error(e));
+        self.login.transition(py, self.settled_login_state());
+        result
+    }
+
+    /// Log in by proving control of a NEAR account.
+    ///
+    /// Fetches a challenge from the node's auth service, signs it as a
+    /// NEP-413 message with `private_key` (`ed25519:<base58>`, a key of
+    /// `account_id`) for `recipient`, and exchanges the signature for node
+    /// tokens, which are cached like any other login. The key never leaves
+    /// the process. Returns the new `JwtToken`.
+    #[pyo3(signature = (account_id, private_key, recipient=wallet_login::DEFAULT_NEAR_RECIPIENT))]
+    pub fn login_near(
+        &self,
+        py: Python<'_>,
+        account_id: &str,
+        private_key: &str,
+        recipient: &str,
+    ) -> PyResult<PyJwtToken> {
+        let key = SigningKey::from_near(private_key)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let account_id = account_id.to_string();
+        let recipient = recipient.to_string();
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+
+        self.finish_login(py, "login_near", async move {
+            let challenge = wallet_login::fetch_challenge(
+                &client,
+                &connection.api_url,
+                &storage,
+                max_response_bytes,
+            )
+            .await?;
+            let body = wallet_login::near_login_body(
+                &account_id,
+                &key,
+                &challenge,
+                &recipient,
+                chrono::Utc::now().timestamp(),
+            );
+            wallet_login::exchange(
+                &client,
+                &connection.api_url,
+                &storage,
+                &body,
+                max_response_bytes,
+            )
+            .await
+        })
+    }
+
+    /// Log in with an Internet Identity delegation.
+    ///
+    /// `delegation` is the delegation chain Internet Identity issued to a
+    /// dapp session (e.g. `DelegationChain.toJSON()` from `@dfinity/auth-client`)
+    /// and `session_key` the 32-byte ed25519 seed of the key it delegates
+    /// to. The session key signs the auth service's challenge and the proof
+    /// is exchanged for node tokens. Returns the new `JwtToken`.
+    pub fn login_internet_identity(
+        &self,
+        py: Python<'_>,
+        delegation: &Bound<'_, PyAny>,
+        session_key: &[u8],
+    ) -> PyResult<PyJwtToken> {
+        let delegation = match delegation.downcast::<pyo3::types::PyString>() {
+            Ok(raw) => serde_json::from_str(raw.to_str()?).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid delegation JSON: {}",
                     e
-                ))),
+                ))
+            })?,
+            Err(_) => crate::utils::python_to_json(delegation)?,
+        };
+        let key = SigningKey::from_bytes(session_key)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+
+        self.finish_login(py, "login_internet_identity", async move {
+            let challenge = wallet_login::fetch_challenge(
+                &client,
+                &connection.api_url,
+                &storage,
+                max_response_bytes,
+            )
+            .await?;
+            let body = wallet_login::internet_identity_body(
+                &delegation,
+                &key,
+                &challenge,
+                chrono::Utc::now().timestamp(),
+            );
+            wallet_login::exchange(
+                &client,
+                &connection.api_url,
+                &storage,
+                &body,
+                max_response_bytes,
+            )
+            .await
+        })
+    }
+
+    /// Log in to the node if it needs it, saving the tokens through the
+    /// connection's token storage.
+    ///
+    /// The node's auth mode is probed first: a node without auth needs no
+    /// login and `None` is returned, as are the stored tokens while they
+    /// are valid unless `force` is set. The credentials pick the flow:
+    /// `username` and `password` for the auth service's username/password
+    /// provider, `account_id` and `private_key` for a NEAR key (as
+    /// `login_near`). Whatever the call leaves out is read from
+    /// `CALIMERO_USERNAME`/`CALIMERO_PASSWORD` or
+    /// `CALIMERO_NEAR_ACCOUNT`/`CALIMERO_NEAR_PRIVATE_KEY`.
+    ///
+    /// `headless` (by default, when stdin is not a terminal) never prompts,
+    /// raising `ValueError` for missing credentials so CI fails instead of
+    /// hanging; otherwise a missing username or password is asked for on
+    /// the terminal. Returns the `JwtToken` in use.
+    #[pyo3(signature = (username=None, password=None, account_id=None, private_key=None, headless=None, force=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn login(
+        &self,
+        py: Python<'_>,
+        username: Option<String>,
+        password: Option<String>,
+        account_id: Option<String>,
+        private_key: Option<String>,
+        headless: Option<bool>,
+        force: bool,
+    ) -> PyResult<Option<PyJwtToken>> {
+        let node_name = self.login_node_name()?;
+        if matches!(self.auth_mode()?.mode, AuthMode::None) {
+            self.login.transition(py, self.settled_login_state());
+            return Ok(None);
+        }
+        if !force && self.settled_login_state() == LoginState::LoggedIn {
+            let storage = self.storage.clone();
+            let stored = self
+                .runtime()?
+                .block_on(async move { storage.load_tokens(&node_name).await })
+                .map_err(|e| self.client_error(e))?;
+            if let Some(tokens) = stored {
+                return Ok(Some(PyJwtToken::from(tokens)));
+            }
+        }
+
+        let credentials = login::LoginCredentials {
+            username,
+            password: password.map(Zeroizing::new),
+            account_id,
+            private_key: private_key.map(Zeroizing::new),
+        }
+        .with_env(|name| std::env::var(name).ok().filter(|value| !value.is_empty()));
+        if credentials.is_near() {
+            let (Some(account_id), Some(private_key)) =
+                (&credentials.account_id, &credentials.private_key)
+            else {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "A NEAR login needs both account_id and private_key",
+                ));
+            };
+            return self
+                .login_near(
+                    py,
+                    account_id,
+                    private_key,
+                    wallet_login::DEFAULT_NEAR_RECIPIENT,
+                )
+                .map(Some);
+        }
+
+        let headless = match headless {
+            Some(headless) => headless,
+            None => {
+                let stdin = py.import_bound("sys")?.getattr("stdin")?;
+                stdin.is_none() || !stdin.call_method0("isatty")?.extract::<bool>()?
+            }
+        };
+        let (username, password) = match (credentials.username, credentials.password) {
+            (Some(username), Some(password)) => (username, password),
+            _ if headless => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Headless login needs credentials: pass username and password \
+                     (or account_id and private_key), or set {} and {}",
+                    login::USERNAME_ENV,
+                    login::PASSWORD_ENV
+                )))
+            }
+            (username, password) => {
+                let username = match username {
+                    Some(username) => username,
+                    None => py
+                        .import_bound("builtins")?
+                        .call_method1("input", ("Username: ",))?
+                        .extract()?,
+                };
+                let password = match password {
+                    Some(password) => password,
+                    None => Zeroizing::new(
+                        py.import_bound("getpass")?
+                            .call_method1("getpass", ("Password: ",))?
+                            .extract()?,
+                    ),
+                };
+                (username, password)
+            }
+        };
+
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+        self.finish_login(py, "login", async move {
+            let body = wallet_login::password_login_body(
+                &username,
+                &password,
+                chrono::Utc::now().timestamp(),
+            );
+            wallet_login::exchange(
+                &client,
+                &connection.api_url,
+                &storage,
+                &body,
+                max_response_bytes,
+            )
+            .await
+        })
+        .map(Some)
+    }
+
+    /// Log out of the node: remove its tokens from token storage and report
+    /// `LOGGED_OUT`. Returns whether any tokens were stored.
+    ///
+    /// The session itself stays valid on the auth service until its tokens
+    /// expire; `revoke_session()` ends it everywhere.
+    pub fn logout(&self, py: Python<'_>) -> PyResult<bool> {
+        let node_name = self.login_node_name()?;
+        let storage = self.storage.clone();
+        let removed = self
+            .run("logout", async move {
+                let stored = storage.load_tokens(&node_name).await?.map(WipeOnDrop::new);
+                storage.remove_tokens(&node_name).await?;
+                Ok::<_, eyre::Report>(stored.is_some())
+            })?
+            .map_err(|e| self.client_error(e))?;
+        self.login.transition(py, self.settled_login_state());
+        Ok(removed)
+    }
+
+    /// Mint a token limited to one context, for less-trusted downstream jobs.
+    ///
+    /// The token can read `context_id` and, unless `read_only`, execute
+    /// methods in it (as `context_identity` when given). It is issued by the
+    /// node's auth service from this client's session and expires after
+    /// `ttl` seconds. Returns a `JwtToken`; use `reveal()` to hand it on.
+    /// Raises `RuntimeError` when the auth service cannot mint scoped tokens.
+    #[pyo3(signature = (context_id, context_identity=None, read_only=true, ttl=auth_service::DEFAULT_SCOPED_TOKEN_TTL_SECS))]
+    pub fn mint_scoped_token(
+        &self,
+        context_id: &str,
+        context_identity: Option<&str>,
+        read_only: bool,
+        ttl: u64,
+    ) -> PyResult<PyJwtToken> {
+        let audit_args = serde_json::json!({
+            "contextId": context_id,
+            "contextIdentity": context_identity,
+            "readOnly": read_only,
+            "ttl": ttl,
+        });
+        let context_id = self.context_id(context_id)?;
+        let context_identity = context_identity
+            .map(|identity| self.identity(identity, &context_id, "context identity"))
+            .transpose()?;
+        if ttl == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "ttl must be at least 1 second",
+            ));
+        }
+        let request = ScopedTokenRequest {
+            context_id,
+            context_identity,
+            read_only,
+            ttl_secs: ttl,
+        };
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+
+        let call_context = Some(request.context_id.to_string());
+        let result = self.run_in_context(
+            "mint_scoped_token",
+            call_context.clone(),
+            self.audited(
+                "mint_scoped_token",
+                call_context,
+                None,
+                audit_args,
+                async move {
+                    auth_service::mint_scoped_token(
+                        &client,
+                        &connection.api_url,
+                        &storage,
+                        connection.node_name.as_deref(),
+                        &request,
+                        max_response_bytes,
+                    )
+                    .await
+                },
+            ),
+        )?;
+        result
+            .map(PyJwtToken::from)
+            .map_err(|e| self.client_error(e))
+    }
+
+    /// List the current user's sessions on the node's auth service.
+    ///
+    /// Returns one dict per root key (login session) as reported by the auth
+    /// service, with the client tokens issued under it in `clients`.
+    pub fn list_sessions(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+
+        let result = self.run_retrying("list_sessions", None, true, || async {
+            auth_service::list_sessions(
+                &client,
+                &connection.api_url,
+                &storage,
+                connection.node_name.as_deref(),
+                max_response_bytes,
+            )
+            .await
+        })?;
+        match result {
+            Ok(sessions) => self.to_python(py, &serde_json::Value::Array(sessions)),
+            Err(e) => Err(self.client_error(e)),
+        }
+    }
+
+    /// Revoke a session, or just one client token issued under it.
+    ///
+    /// Revoking `key_id` invalidates every token of that session, e.g. the
+    /// ones left on a lost laptop; with `client_id` only that token is
+    /// revoked. Revoking this client's own session logs it out.
+    #[pyo3(signature = (key_id, client_id=None))]
+    pub fn revoke_session(
+        &self,
+        py: Python<'_>,
+        key_id: &str,
+        client_id: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "keyId": key_id, "clientId": client_id });
+        auth_service::validate_id("key id", key_id)
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        if let Some(client_id) = client_id {
+            auth_service::validate_id("client id", client_id)
+                .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        }
+        let key_id = key_id.to_string();
+        let client_id = client_id.map(str::to_string);
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+
+        let result = self.run(
+            "revoke_session",
+            self.audited("revoke_session", None, None, audit_args, async move {
+                auth_service::revoke_session(
+                    &client,
+                    &connection.api_url,
+                    &storage,
+                    connection.node_name.as_deref(),
+                    &key_id,
+                    client_id.as_deref(),
+                    max_response_bytes,
+                )
+                .await
+            }),
+        )?;
+        match result {
+            Ok(json_data) => self.to_python(py, &json_data),
+            Err(e) => Err(self.client_error(e)),
+        }
+    }
+
+    /// Flag risky client settings: token cache permissions, plaintext
+    /// transport, long-lived tokens, and plaintext token storage.
+    ///
+    /// Returns a list of `{id, severity, message, remediation}` dicts with
+    /// severities `low`/`medium`/`high`, most severe first.
+    pub fn security_check(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let mut findings = security::check_connection(&self.connection.api_url);
+
+        if let (true, Some(node_name)) = (
+            self.storage.persists_to_disk(),
+            self.connection.node_name.as_deref(),
+        ) {
+            let cache_dir = self.storage.cache_dir();
+            let token_path = self.storage.cache_path(node_name);
+            findings.extend(security::check_permissions(&cache_dir, true));
+            if token_path.exists() {
+                findings.extend(security::check_permissions(&token_path, false));
+                if !self.storage.is_encrypted() {
+                    findings.push(security::unencrypted_storage(&token_path));
+                }
+            }
+
+            let storage = self.storage.clone();
+            let tokens = self.run("security_check", async move {
+                storage.load_tokens(node_name).await
+            })?;
+            if let Ok(Some(tokens)) = tokens {
+                let tokens = WipeOnDrop::new(tokens);
+                findings.extend(security::check_tokens(
+                    &tokens,
+                    chrono::Utc::now().timestamp(),
+                ));
+            }
+        }
+
+        findings.sort_by(|a, b| b.severity.cmp(&a.severity));
+        let json_data = serde_json::to_value(&findings).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to serialize response: {}",
+                e
+            ))
+        })?;
+        self.to_python(py, &json_data)
+    }
+
+    /// Create a client whose tokens live only in memory.
+    ///
+    /// Nothing is read from or written to the token cache. Tokens passed
+    /// here (or obtained by refresh) are zeroized on `close()`, when the
+    /// client is garbage-collected, or once `ttl` seconds have elapsed —
+    /// after which calls run unauthenticated. Intended for short-lived
+    /// serverless functions; use as a context manager to wipe on exit.
+    #[staticmethod]
+    #[pyo3(signature = (api_url, node_name=None, access_token=None, refresh_token=None, expires_at=None, ttl=None))]
+    pub fn ephemeral(
+        py: Python<'_>,
+        api_url: &str,
+        node_name: Option<&str>,
+        access_token: Option<String>,
+        refresh_token: Option<String>,
+        expires_at: Option<i64>,
+        ttl: Option<f64>,
+    ) -> PyResult<Self> {
+        let ttl = ttl
+            .map(|secs| {
+                std::time::Duration::try_from_secs_f64(secs).map_err(|_| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "ttl must be a non-negative number of seconds",
+                    )
+                })
+            })
+            .transpose()?;
+        let node_name = node_name.or(access_token.as_ref().map(|_| EPHEMERAL_NODE_NAME));
+        let storage = MeroboxFileStorage::ephemeral(ttl);
+
+        if let (Some(node_name), Some(access_token)) = (node_name, access_token) {
+            let tokens = WipeOnDrop::new(calimero_client::JwtToken {
+                access_token,
+                refresh_token,
+                expires_at,
+            });
+            let store = storage.clone();
+            let runtime = Runtime::new()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+            runtime
+                .block_on(async move { store.save_tokens(node_name, &tokens).await })
+                .map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                        "Failed to create client: {}",
+                        e
+                    ))
+                })?;
+        }
+
+        let connection = Bound::new(
+            py,
+            PyConnectionInfo::with_storage(api_url, node_name, storage)?,
+        )?;
+        Self::new(
+            py,
+            &connection,
+            false,
+            "auto",
+            auth::DEFAULT_AUTH_MODE_TTL_SECS,
+            Some(limits::DEFAULT_MAX_RESPONSE_BYTES),
+            request_cache::DEFAULT_REQUEST_CACHE_SIZE,
+            concurrency::DEFAULT_MAX_CONCURRENCY,
+            None,
+            metadata_cache::DEFAULT_METADATA_TTL_SECS,
+            retry_budget::DEFAULT_RETRY_BUDGET_RATIO,
+            Some(token_lifecycle::DEFAULT_REFRESH_MARGIN_SECS),
+            Some(maintenance::DEFAULT_MAINTENANCE_WAIT_SECS),
+            None,
+            None,
+            pool::DEFAULT_POOL_MAX_IDLE,
+            pool::DEFAULT_POOL_IDLE_TIMEOUT_SECS,
+            None,
+            None,
+            None,
+            None,
+            false,
+            false,
+            Some(limits::DEFAULT_MAX_REQUEST_BYTES),
+            "error",
+            0,
+            read_cache::DEFAULT_READ_CACHE_TTL_SECS,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// Close the client: end its subscriptions, give back its share of the
+    /// connection pool and shut its runtime down, joining the background
+    /// threads and closing their sockets. In-memory credentials (ephemeral
+    /// clients) are wiped and the `on_login_state_change` /
+    /// `on_maintenance` callbacks, request and response hooks and the
+    /// background token refresher dropped, so none is called afterwards.
+    /// Later calls raise `ClientError`; closing again does nothing
+    pub fn close(&self, py: Python<'_>) {
+        if let Some(hub) = self.subscriptions.get() {
+            hub.shutdown();
+        }
+        if let Some(lease) = &self.pool_lease {
+            lease.release();
+        }
+        self.stop_token_refresh();
+        // Callback subscriptions may be waiting for the GIL to deliver.
+        py.allow_threads(|| self.runtime.shutdown(lifecycle::SHUTDOWN_GRACE));
+        self.storage.wipe();
+        self.login.clear();
+        self.maintenance.clear();
+        self.middleware.clear();
+    }
+
+    /// Whether `close()` was called
+    #[getter]
+    pub fn closed(&self) -> bool {
+        self.runtime.is_closed()
+    }
+
+    /// Pickle as the configuration that created the client (its
+    /// connection's included), not its live state: a worker process gets a
+    /// client of its own, which opens connections and reads tokens on first
+    /// use. Hooks, callbacks, method defaults, caches and the outbox stay
+    /// behind.
+    pub fn __reduce__(slf: &Bound<'_, Self>) -> (PyObject, PyObject) {
+        let args = slf.borrow().pickle_args.clone_ref(slf.py());
+        (slf.get_type().into_any().unbind(), args.into_any())
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, py: Python<'_>, _args: &Bound<'_, pyo3::types::PyTuple>) -> bool {
+        self.close(py);
+        false
+    }
+
+    fn __aenter__<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        let client = slf.clone().unbind();
+        pyo3_async_runtimes::tokio::future_into_py(slf.py(), async move { Ok(client) })
+    }
+
+    /// Closes the client before returning the awaitable: the runtime
+    /// cannot be shut down from a task running on a runtime.
+    #[pyo3(signature = (*_args))]
+    fn __aexit__<'py>(
+        &self,
+        py: Python<'py>,
+        _args: &Bound<'py, pyo3::types::PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.close(py);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move { Ok(false) })
+    }
+
+    /// Whether the client keeps tokens in memory only
+    #[getter]
+    pub fn ephemeral_tokens(&self) -> bool {
+        self.storage.is_ephemeral()
+    }
+
+    /// Whether the client skips the token cache entirely
+    #[getter]
+    pub fn anonymous(&self) -> bool {
+        self.storage.is_anonymous()
+    }
+
+    /// User the client's cached tokens are keyed by, if any
+    #[getter]
+    pub fn user(&self) -> Option<String> {
+        self.storage.user().map(str::to_string)
+    }
+
+    /// Namespace (tenant) the client's tokens are confined to, if any
+    #[getter]
+    pub fn namespace(&self) -> Option<String> {
+        self.storage.namespace().map(str::to_string)
+    }
+
+    /// Profile the client's cached tokens are keyed by (same as `user`)
+    #[getter]
+    pub fn profile(&self) -> Option<String> {
+        self.user()
+    }
+
+    /// Wire format used by `execute_function`: `"json"`, `"msgpack"`, or
+    /// `None` in auto mode before the first call
+    #[getter]
+    pub fn wire_format(&self) -> Option<&'static str> {
+        self.wire.active()
+    }
+
+    /// Adaptive concurrency limiter state:
+    /// `{limit, maxLimit, inFlight, baselineMs, lastRttMs, decreases}`.
+    #[getter]
+    pub fn concurrency_stats(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.limiter.stats())
+    }
+
+    /// Client-side rate limit and `Retry-After` pauses of the node:
+    /// `{rate, burst, available, throttled, waitedSecs, pausedSecs,
+    /// pauses}`; `rate`, `burst` and `available` are `None` without
+    /// `rate_limit`.
+    #[getter]
+    pub fn rate_limit_stats(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.limiter.throttle_stats())
+    }
+
+    /// Transcript of `record=` or `replay=`: `{path, mode, exchanges,
+    /// remaining}` (`remaining` only when replaying); `None` without one.
+    #[getter]
+    pub fn transcript_stats(&self, py: Python<'_>) -> Option<PyObject> {
+        let transcript = self.transcript.as_ref()?;
+        Some(json_to_python(py, &transcript.stats()))
+    }
+
+    /// Client counters: `{retryBudget: {ratio, balance, requests, retries,
+    /// denied}, concurrency: {...}, maintenance: {active, remainingSecs,
+    /// windows}, dedup: {windowMs, suppressed}, pool: {maxIdlePerHost,
+    /// idleTimeoutSecs, maxConnectionsPerHost, pooledClients}, retry:
+    /// {maxAttempts, backoffBase, maxBackoff, jitter, retryOn,
+    /// retryMutations}, rateLimit: {...}}` (`concurrency` as in
+    /// `concurrency_stats`, `rateLimit` as in `rate_limit_stats`).
+    pub fn stats(&self, py: Python<'_>) -> PyObject {
+        let stats = serde_json::json!({
+            "retryBudget": self.retry_budget.stats(),
+            "concurrency": self.limiter.stats(),
+            "maintenance": self.maintenance.stats(),
+            "dedup": self.dedup.stats(),
+            "pool": self.pool_stats(),
+            "retry": self.retry.to_json(),
+            "rateLimit": self.limiter.throttle_stats(),
+        });
+        json_to_python(py, &stats)
+    }
+
+    /// Request metrics of this client's node: `{node, requests, errors,
+    /// retries, tokenRefreshes, tokenRefreshFailures, latencyMs: {count,
+    /// totalMs, mean, p50, p90, p95, p99, max}, uptimeSecs}`. Percentiles
+    /// cover the last 1024 calls. With `prometheus=True`, the same metrics
+    /// as Prometheus text exposition format, for a `/metrics` endpoint.
+    #[pyo3(signature = (prometheus=false))]
+    pub fn metrics(&self, py: Python<'_>, prometheus: bool) -> PyObject {
+        let snapshot = self.metrics_snapshot();
+        match prometheus {
+            true => metrics::prometheus(&[snapshot]).into_py(py),
+            false => json_to_python(py, &snapshot),
+        }
+    }
+
+    /// Drop prepared `execute_function` requests kept for reuse
+    pub fn clear_request_cache(&self) {
+        self.request_cache.clear();
+    }
+
+    /// Drop cached application and context metadata
+    pub fn clear_metadata_cache(&self) {
+        self.metadata_cache.clear();
+    }
+
+    /// Response size cap in bytes, or `None` when disabled
+    #[getter]
+    pub fn max_response_bytes(&self) -> Option<usize> {
+        self.max_response_bytes
+    }
+
+    /// `execute_function` request size cap in bytes, or `None` when disabled
+    #[getter]
+    pub fn max_request_bytes(&self) -> Option<usize> {
+        self.max_request_bytes
+    }
+
+    /// Limit on a whole call in seconds, or `None` when disabled
+    #[getter]
+    pub fn request_timeout(&self) -> Option<f64> {
+        self.request_timeout.map(|timeout| timeout.as_secs_f64())
+    }
+
+    /// Whether the client refuses dangerous operations (`production=True`)
+    #[getter]
+    pub fn production(&self) -> bool {
+        self.production
+    }
+
+    /// Limit on establishing a connection in seconds, or `None`
+    #[getter]
+    pub fn connect_timeout(&self) -> Option<f64> {
+        self.pool_config
+            .connect_timeout
+            .map(|timeout| timeout.as_secs_f64())
+    }
+
+    /// Request timeout for the calls made in a `with` block.
+    ///
+    /// `with client.timeout(2.0):` makes calls this thread sends inside
+    /// the block give up after 2 seconds, whatever the client's
+    /// `request_timeout`; `timeout(None)` lifts the limit. Blocks nest.
+    pub fn timeout(&self, seconds: Option<f64>) -> PyResult<PyTimeoutScope> {
+        Ok(PyTimeoutScope::new(timeouts::seconds("timeout", seconds)?))
+    }
+
+    /// HTTP version used for direct requests: `"http3"` or `"auto"`
+    #[getter]
+    pub fn http_version(&self) -> &'static str {
+        if self.http3 {
+            "http3"
+        } else {
+            "auto"
+        }
+    }
+
+    /// Forward deprecated method names listed in the deprecation table,
+    /// emitting a `DeprecatedEndpointWarning` with the replacement call.
+    fn __getattr__(slf: &Bound<'_, Self>, name: &str) -> PyResult<PyObject> {
+        let py = slf.py();
+        match deprecation::lookup(deprecation::CLIENT_DEPRECATIONS, name) {
+            Some(row) => {
+                warnings::warn(py, WarningKind::DeprecatedEndpoint, &row.message("Client"))?;
+                Ok(slf.getattr(row.new)?.unbind())
+            }
+            None => Err(PyErr::new::<pyo3::exceptions::PyAttributeError, _>(
+                format!("'Client' object has no attribute '{}'", name),
+            )),
+        }
+    }
+
+    /// Get API URL
+    pub fn get_api_url(&self) -> String {
+        self.inner.api_url().to_string()
+    }
+
+    /// Send `method path` with the session's bearer token and return the
+    /// response whatever its status, as `{status, url, headers, body}` with
+    /// `body` in bytes. Backs `calimero.compat.http` for scripts not yet
+    /// moved to the typed methods; `headers` are sent on top of the token
+    /// and win over it. `path` may be a full URL on the node's origin only.
+    #[pyo3(signature = (method, path, body=None, headers=None))]
+    pub fn raw_request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<Vec<u8>>,
+        headers: Option<&Bound<'_, pyo3::types::PyDict>>,
+    ) -> PyResult<PyObject> {
+        let method =
+            reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes()).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid HTTP method '{}': {}",
+                    method, e
+                ))
+            })?;
+        let headers = match headers {
+            Some(headers) => middleware::headers_from_dict(headers.as_any())?,
+            None => reqwest::header::HeaderMap::new(),
+        };
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+        let path = path.to_string();
+
+        Python::with_gil(|py| {
+            let result = self.run("raw_request", async move {
+                http::send_raw(
+                    &client,
+                    method,
+                    &connection.api_url,
+                    &path,
+                    &storage,
+                    connection.node_name.as_deref(),
+                    headers,
+                    body,
+                    max_response_bytes,
+                )
+                .await
+            })?;
+
+            let response = result.map_err(|e| self.client_error(e))?;
+            let dict = pyo3::types::PyDict::new_bound(py);
+            dict.set_item("status", response.status.as_u16())?;
+            dict.set_item("url", response.url.as_str())?;
+            dict.set_item(
+                "headers",
+                middleware::headers_to_dict(py, &response.headers)?,
+            )?;
+            dict.set_item("body", pyo3::types::PyBytes::new_bound(py, &response.body))?;
+            Ok(dict.into_py(py))
+        })
+    }
+
+    /// Get application information (cached for `metadata_ttl`; `refresh=True`
+    /// skips the cache)
+    #[pyo3(signature = (app_id, refresh=false))]
+    pub fn get_application(&self, app_id: &str, refresh: bool) -> PyResult<PyObject> {
+        let inner = self.inner.clone();
+        let key = (MetadataKind::Application, Some(app_id.to_string()));
+        let app_id = app_id.parse::<ApplicationId>().map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid application ID '{}': {}",
+                app_id, e
+            ))
+        })?;
+
+        Python::with_gil(|py| {
+            let json_data = self.read_metadata("get_application", key, refresh, || async {
+                inner.get_application(&app_id).await
+            })?;
+            self.to_python(py, &json_data)
+        })
+    }
+
+    /// Methods of the application `context_id` runs, from its ABI:
+    /// `[{name, params: [{name, type, optional}], returns, doc}]` sorted by
+    /// name. Empty when the application was built without an ABI. Uses the
+    /// metadata cache like `get_context` / `get_application`.
+    #[pyo3(signature = (context_id, refresh=false))]
+    pub fn discover_methods(&self, context_id: &str, refresh: bool) -> PyResult<PyObject> {
+        let context_id = self.context_id(context_id)?;
+
+        Python::with_gil(|py| {
+            let methods = self
+                .application_abi(&context_id, refresh)?
+                .map(|abi| abi::methods(&abi))
+                .unwrap_or_default();
+            self.to_python(py, &serde_json::Value::Array(methods))
+        })
+    }
+
+    /// Method `name` of the application `context_id` runs, as a callable
+    /// that checks its keyword arguments against the application's ABI
+    /// before executing: `client.method(ctx, "transfer")(to="bob", amount=5)`.
+    /// Unknown or missing arguments and values of the wrong type raise
+    /// `AbiArgumentError` without a request being sent. The ABI is read
+    /// through the metadata cache like `discover_methods`.
+    #[pyo3(signature = (context_id, name, refresh=false))]
+    pub fn method(
+        slf: &Bound<'_, Self>,
+        context_id: &str,
+        name: &str,
+        refresh: bool,
+    ) -> PyResult<abi::PyAbiMethod> {
+        let this = slf.borrow();
+        let parsed = this.context_id(context_id)?;
+        let abi = this.application_abi(&parsed, refresh)?.ok_or_else(|| {
+            abi::AbiArgumentError::new_err(format!(
+                "The application of context {} was built without an ABI",
+                parsed
+            ))
+        })?;
+        abi::PyAbiMethod::new(
+            slf.clone().unbind(),
+            parsed.to_string(),
+            name.to_string(),
+            abi,
+        )
+    }
+
+    /// List applications (cached for `metadata_ttl`; `refresh=True` skips the
+    /// cache)
+    #[pyo3(signature = (fields=None, refresh=false))]
+    pub fn list_applications(
+        &self,
+        fields: Option<Vec<String>>,
+        refresh: bool,
+    ) -> PyResult<PyObject> {
+        let inner = self.inner.clone();
+
+        Python::with_gil(|py| {
+            let key = (MetadataKind::Application, None);
+            let mut json_data =
+                self.read_metadata("list_applications", key, refresh, || async {
+                    inner.list_applications().await
+                })?;
+            if let Some(fields) = &fields {
+                project_fields(&mut json_data, fields);
             }
+            self.to_python(py, &json_data)
         })
     }
 
@@ -155,7 +3752,12 @@ impl PyClient {
     /// install plus any older blobs still referenced by groups or context
     /// activation markers. The `blobId` doubles as the `app_key` accepted by
     /// `create_namespace`. Wraps `GET admin-api/applications/{id}/versions`.
-    pub fn list_application_versions(&self, application_id: &str) -> PyResult<PyObject> {
+    #[pyo3(signature = (application_id, fields=None))]
+    pub fn list_application_versions(
+        &self,
+        application_id: &str,
+        fields: Option<Vec<String>>,
+    ) -> PyResult<PyObject> {
         let inner = self.inner.clone();
         let application_id = application_id.parse::<ApplicationId>().map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -165,123 +3767,255 @@ impl PyClient {
         })?;
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_application_versions(&application_id).await });
+            let result = self.run_retrying("list_application_versions", None, true, || async {
+                inner.list_application_versions(&application_id).await
+            })?;
 
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if let Some(fields) = &fields {
+                        project_fields(&mut json_data, fields);
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    /// Get context
-    pub fn get_context(&self, context_id: &str) -> PyResult<PyObject> {
+    /// Get context (cached for `metadata_ttl`; `refresh=True` skips the cache).
+    /// `typed=True` returns a `Context` instead of the response dict.
+    #[pyo3(signature = (context_id, refresh=false, typed=false))]
+    pub fn get_context(&self, context_id: &str, refresh: bool, typed: bool) -> PyResult<PyObject> {
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
+        let key = (MetadataKind::Context, Some(context_id.to_string()));
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_context(&context_id).await });
-
-            match result {
-                Ok(data) => {
-                    // Convert to JSON first, then to Python
-                    let json_data = serde_json::to_value(data).map_err(|e| {
-                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                            "Failed to serialize response: {}",
-                            e
-                        ))
-                    })?;
-                    Ok(json_to_python(py, &json_data))
-                }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+            let json_data = self.read_metadata("get_context", key, refresh, || async {
+                inner.get_context(&context_id).await
+            })?;
+            if typed {
+                self.check_size(&json_data)?;
+                return Ok(Py::new(py, PyContext::from_response(&json_data))?.into_any());
             }
+            self.to_python(py, &json_data)
         })
     }
 
-    /// List contexts
-    pub fn list_contexts(&self) -> PyResult<PyObject> {
+    /// Query over the node's contexts, e.g.
+    /// `client.contexts().filter(application=app_id).sort("id").limit(50)`.
+    ///
+    /// Fields: `id`, `application`, `root_hash`, `group` (`application_id`
+    /// and `group_id` also work); unknown ones raise `ValueError`. Nothing
+    /// is fetched until `fetch()`, `first()`, `count()` or iteration.
+    pub fn contexts(slf: &Bound<'_, Self>) -> PyQuery {
+        PyQuery::new(slf.clone().unbind(), query::Resource::Contexts)
+    }
+
+    /// Query over the installed applications, by `id`, `size` or `source`;
+    /// see `contexts()`.
+    pub fn applications(slf: &Bound<'_, Self>) -> PyQuery {
+        PyQuery::new(slf.clone().unbind(), query::Resource::Applications)
+    }
+
+    /// Query over the node's blobs, by `id` or `size`; see `contexts()`.
+    /// Blob lists skip the metadata cache.
+    pub fn blobs(slf: &Bound<'_, Self>) -> PyQuery {
+        PyQuery::new(slf.clone().unbind(), query::Resource::Blobs)
+    }
+
+    /// List contexts (cached for `metadata_ttl`; `refresh=True` skips the
+    /// cache). `archived=True` lists only contexts frozen with
+    /// `freeze_context`, `archived=False` only the others. `typed=True`
+    /// returns a list of `Context` instead of the response dict.
+    #[pyo3(signature = (fields=None, refresh=false, archived=None, typed=false))]
+    pub fn list_contexts(
+        &self,
+        fields: Option<Vec<String>>,
+        refresh: bool,
+        archived: Option<bool>,
+        typed: bool,
+    ) -> PyResult<PyObject> {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_contexts().await });
-
-            match result {
-                Ok(data) => {
-                    // Convert to JSON first, then to Python
-                    let json_data = serde_json::to_value(data).map_err(|e| {
-                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                            "Failed to serialize response: {}",
-                            e
-                        ))
-                    })?;
-                    Ok(json_to_python(py, &json_data))
-                }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+            let key = (MetadataKind::Context, None);
+            let mut json_data = self.read_metadata("list_contexts", key, refresh, || async {
+                inner.list_contexts().await
+            })?;
+            if let Some(archived) = archived {
+                self.archive.filter_contexts(&mut json_data, archived);
             }
+            if let Some(fields) = &fields {
+                project_fields(&mut json_data, fields);
+            }
+            if typed {
+                self.check_size(&json_data)?;
+                let contexts = PyContext::list_from_response(&json_data)
+                    .into_iter()
+                    .map(|context| Py::new(py, context))
+                    .collect::<PyResult<Vec<_>>>()?;
+                return Ok(PyList::new_bound(py, contexts).into_any().unbind());
+            }
+            self.to_python(py, &json_data)
         })
     }
 
-    /// Install application from URL
-    #[pyo3(signature = (url, hash=None, metadata=None))]
+    /// Install application from a URL or a local WASM bundle
+    ///
+    /// `url` is an `http(s)://` URL the node downloads the bundle from, or a
+    /// local file (path or `file://` URL). Local bundles are hashed, and
+    /// checked against `hash` when given. A node on this machine installs
+    /// the file in place; a remote node (or any node of a `production`
+    /// client) gets it uploaded as a blob and installs it from there.
+    ///
+    /// Uploads go in `chunk_size` pieces, each retried on its own, and an
+    /// upload cut off halfway resumes from the node's last byte when the
+    /// same bundle is installed again. `on_progress(bytes_sent, total)` is
+    /// called as chunks land. Nodes without resumable uploads get the
+    /// bundle streamed in one request, reported as it is read.
+    #[pyo3(signature = (url, hash=None, metadata=None, on_progress=None, chunk_size=blob::DEFAULT_RESUMABLE_CHUNK_SIZE))]
     pub fn install_application(
         &self,
         url: &str,
         hash: Option<&str>,
         metadata: Option<&[u8]>,
+        on_progress: Option<PyObject>,
+        chunk_size: usize,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "url": url,
+            "hash": hash,
+            "metadataSize": metadata.map(<[u8]>::len),
+        });
         let inner = self.inner.clone();
-        let url = url.to_string();
+        let source = Source::parse(url)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e)))?;
         let hash = hash.map(|h| h.to_string());
+        if chunk_size == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "chunk_size must be at least 1",
+            ));
+        }
         let metadata = metadata.unwrap_or(b"{}").to_vec();
+        let api_url = self.connection.api_url.clone();
+        let in_place = !self.production && applications::is_loopback(&api_url);
+        let client = self.http.clone();
+        let storage = self.storage.clone();
+        let node_name = self.connection.node_name.clone();
+        let retry_config = self.retry.clone();
+        let retry_budget = self.retry_budget.clone();
+        let max_response_bytes = self.max_response_bytes;
+        let mut progress = blob::Progress::new(on_progress, None);
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let url = url::Url::parse(&url).map_err(|e| eyre::eyre!("Invalid URL: {}", e))?;
-
-                let hash = if let Some(hash_str) = hash {
-                    let hash_bytes =
-                        hex::decode(hash_str).map_err(|e| eyre::eyre!("Invalid hash: {}", e))?;
-                    let hash_array: [u8; 32] = hash_bytes
-                        .try_into()
-                        .map_err(|_| eyre::eyre!("Hash must be 32 bytes"))?;
-                    Some(Hash::from(hash_array))
-                } else {
-                    None
-                };
-
-                let request =
-                    admin::InstallApplicationRequest::new(url, hash, metadata, None, None);
-
-                inner.install_application(request).await
-            });
+            let result = self.run(
+                "install_application",
+                self.audited("install_application", None, None, audit_args, async move {
+                    let path = match source {
+                        Source::Remote(url) => {
+                            let hash = if let Some(hash_str) = hash {
+                                let hash_bytes = hex::decode(hash_str)
+                                    .map_err(|e| eyre::eyre!("Invalid hash: {}", e))?;
+                                let hash_array: [u8; 32] = hash_bytes
+                                    .try_into()
+                                    .map_err(|_| eyre::eyre!("Hash must be 32 bytes"))?;
+                                Some(Hash::from(hash_array))
+                            } else {
+                                None
+                            };
+                            let request = admin::InstallApplicationRequest::new(
+                                url, hash, metadata, None, None,
+                            );
+                            let installed = inner.install_application(request).await?;
+                            return Ok(serde_json::to_value(installed)?);
+                        }
+                        Source::Local(path) => path,
+                    };
+
+                    let (digest, bundle_hash) = {
+                        let path = path.clone();
+                        tokio::task::spawn_blocking(move || applications::digest_bundle(&path))
+                            .await??
+                    };
+                    let hash = Hash::from(applications::check_hash(bundle_hash, hash.as_deref())?);
+                    if in_place {
+                        let path = camino::Utf8PathBuf::from_path_buf(path).map_err(|path| {
+                            eyre::eyre!("Application bundle path {:?} is not UTF-8", path)
+                        })?;
+                        let request =
+                            admin::InstallDevApplicationRequest::new(path, metadata, None, None);
+                        let installed = inner.install_dev_application(request).await?;
+                        return Ok(serde_json::to_value(installed)?);
+                    }
+                    let resumed = blob::upload_resumable(
+                        &client,
+                        &api_url,
+                        &storage,
+                        node_name.as_deref(),
+                        &path,
+                        &digest,
+                        chunk_size,
+                        (&*retry_config, &*retry_budget),
+                        &mut progress,
+                    )
+                    .await?;
+                    let uploaded = match resumed {
+                        Some(uploaded) => uploaded,
+                        None => {
+                            let (body, mut read) =
+                                blob::file_body(&path, blob::DEFAULT_UPLOAD_CHUNK_SIZE).await?;
+                            let upload = http::put_stream(
+                                &client,
+                                &api_url,
+                                "admin-api/blobs",
+                                &storage,
+                                node_name.as_deref(),
+                                body,
+                                max_response_bytes,
+                            );
+                            tokio::pin!(upload);
+                            progress.set_total(Some(digest.size));
+                            // Reported here, on the calling thread, which
+                            // holds the GIL the callback needs.
+                            let mut reported = 0;
+                            let uploaded = loop {
+                                tokio::select! {
+                                    uploaded = &mut upload => break uploaded?,
+                                    Some(done) = read.recv() => {
+                                        reported = done;
+                                        progress.report(done);
+                                    }
+                                }
+                            };
+                            if reported < digest.size {
+                                progress.report(digest.size);
+                            }
+                            uploaded
+                        }
+                    };
+                    let blob_id = blob::find_string_field(&uploaded, "blobId")
+                        .ok_or_else(|| eyre::eyre!("Upload response did not include a blobId"))?;
+                    let url = applications::blob_url(&api_url, blob_id)?;
+                    let request = admin::InstallApplicationRequest::new(
+                        url,
+                        Some(hash),
+                        metadata,
+                        None,
+                        None,
+                    );
+                    let installed = inner.install_application(request).await?;
+                    Ok::<_, eyre::Report>(serde_json::to_value(installed)?)
+                }),
+            )?;
+            self.metadata_cache.invalidate(MetadataKind::Application);
 
             match result {
                 Ok(data) => {
@@ -292,36 +4026,57 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    /// Install development application from local path
-    #[pyo3(signature = (path, metadata=None))]
+    /// Install development application from local path (refused by
+    /// production clients unless `allow_dangerous=True`)
+    #[pyo3(signature = (path, metadata=None, allow_dangerous=false))]
     pub fn install_dev_application(
         &self,
         path: &str,
         metadata: Option<&[u8]>,
+        allow_dangerous: bool,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "path": path,
+            "metadataSize": metadata.map(<[u8]>::len),
+            "allowDangerous": allow_dangerous,
+        });
+        production::guard(
+            self.production,
+            "install_dev_application",
+            Dangerous::DevInstall,
+            allow_dangerous,
+        )?;
         let inner = self.inner.clone();
         let path = path.to_string();
         let metadata = metadata.unwrap_or(b"{}").to_vec();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let path = camino::Utf8PathBuf::from(path);
-                let metadata = metadata;
-
-                let request = admin::InstallDevApplicationRequest::new(path, metadata, None, None);
-
-                inner.install_dev_application(request).await
-            });
+            let result = self.run(
+                "install_dev_application",
+                self.audited(
+                    "install_dev_application",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        let path = camino::Utf8PathBuf::from(path);
+                        let metadata = metadata;
+
+                        let request =
+                            admin::InstallDevApplicationRequest::new(path, metadata, None, None);
+
+                        inner.install_dev_application(request).await
+                    },
+                ),
+            )?;
+            self.metadata_cache.invalidate(MetadataKind::Application);
 
             match result {
                 Ok(data) => {
@@ -332,18 +4087,74 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
+    /// Reinstall `wasm_path` with `install_dev_application` whenever it
+    /// changes, for edit-build-try loops against a local node.
+    ///
+    /// Returns a running `DevDeployer`; `stop()` it or use it as a context
+    /// manager. A deploy starts once writes to the file have settled for
+    /// `debounce` seconds (and once at start unless `deploy_on_start=False`);
+    /// with `executor_public_key` the context is then moved to the new
+    /// application with `update_context_application`. Every attempt is
+    /// passed to `on_deploy(record)` as `{contextId, hash, applicationId,
+    /// migrated, error, at}`; failures are reported there and the watch
+    /// goes on.
+    #[pyo3(signature = (wasm_path, context_id, executor_public_key=None, debounce=dev_deploy::DEFAULT_DEBOUNCE_SECS, on_deploy=None, deploy_on_start=true, allow_dangerous=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn watch_and_deploy(
+        slf: &Bound<'_, Self>,
+        wasm_path: std::path::PathBuf,
+        context_id: &str,
+        executor_public_key: Option<&str>,
+        debounce: f64,
+        on_deploy: Option<PyObject>,
+        deploy_on_start: bool,
+        allow_dangerous: bool,
+    ) -> PyResult<PyDevDeployer> {
+        let py = slf.py();
+        let client = slf.borrow();
+        production::guard(
+            client.production,
+            "watch_and_deploy",
+            Dangerous::DevInstall,
+            allow_dangerous,
+        )?;
+        let context_id = client.context_id(context_id)?;
+        let executor_public_key = executor_public_key
+            .map(|raw| {
+                client
+                    .identity(raw, &context_id, "executor public key")
+                    .map(|key| key.to_string())
+            })
+            .transpose()?;
+        let debounce = timeouts::seconds("debounce", Some(debounce))?.unwrap_or_default();
+        let on_deploy = on_deploy
+            .map(|callback| Callback::new(py, callback, None))
+            .transpose()?;
+        drop(client);
+        PyDevDeployer::start(
+            slf.clone().unbind(),
+            dev_deploy::Target {
+                path: wasm_path,
+                context_id: context_id.to_string(),
+                executor_public_key,
+                allow_dangerous,
+            },
+            debounce,
+            on_deploy,
+            deploy_on_start,
+        )
+    }
+
     /// Uninstall application
     pub fn uninstall_application(&self, app_id: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "appId": app_id });
         let inner = self.inner.clone();
         let app_id = app_id.parse::<ApplicationId>().map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -353,9 +4164,17 @@ impl PyClient {
         })?;
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.uninstall_application(&app_id).await });
+            let result = self.run(
+                "uninstall_application",
+                self.audited(
+                    "uninstall_application",
+                    None,
+                    None,
+                    audit_args,
+                    async move { inner.uninstall_application(&app_id).await },
+                ),
+            )?;
+            self.metadata_cache.invalidate(MetadataKind::Application);
 
             match result {
                 Ok(data) => {
@@ -365,61 +4184,239 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     /// Upload blob
-    #[pyo3(signature = (data, context_id=None))]
-    pub fn upload_blob(&self, data: &[u8], context_id: Option<&str>) -> PyResult<PyObject> {
+    ///
+    /// `content_type` and `metadata` (a `str -> str` dict) are recorded in a
+    /// local sidecar keyed by the new blob id and echoed back in the response
+    /// as `contentType` / `metadata`; `get_blob_info` and
+    /// `download_blob(..., with_metadata=True)` return them again. Nodes
+    /// store blob bytes only, so this metadata is local to this machine and
+    /// is not visible to other clients of the node.
+    ///
+    /// With `encryption_key` (32 raw bytes or 64 hex characters) the payload
+    /// is sealed client-side before upload (see `crypto`), so the node only
+    /// ever stores ciphertext. Pass the same key to `download_blob`.
+    #[pyo3(signature = (data, context_id=None, content_type=None, metadata=None, encryption_key=None))]
+    pub fn upload_blob(
+        &self,
+        data: &[u8],
+        context_id: Option<&str>,
+        content_type: Option<&str>,
+        metadata: Option<std::collections::BTreeMap<String, String>>,
+        encryption_key: Option<&[u8]>,
+    ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "dataSize": data.len(),
+            "contextId": context_id,
+            "contentType": content_type,
+            "metadata": metadata,
+        });
         let inner = self.inner.clone();
-        let data_vec = data.to_vec();
-        let context_id_opt = context_id.map(|s| s.to_string());
+        let data_vec = match encryption_key {
+            Some(key) => crypto::decode_key(key)
+                .and_then(|key| crypto::seal(&key, data))
+                .map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Failed to encrypt blob: {}",
+                        e
+                    ))
+                })?,
+            None => data.to_vec(),
+        };
+        let context_id_parsed = context_id.map(|id| self.context_id(id)).transpose()?;
+        let blob_metadata = blob::BlobMetadata {
+            content_type: content_type.map(str::to_owned),
+            metadata: metadata.unwrap_or_default(),
+            encryption: encryption_key.map(|_| crypto::ENVELOPE_ALGORITHM.to_string()),
+        };
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let context_id_parsed = if let Some(ctx_id) = context_id_opt {
-                    Some(
-                        ctx_id
-                            .parse::<ContextId>()
-                            .map_err(|e| eyre::eyre!("Invalid context ID '{}': {}", ctx_id, e))?,
-                    )
-                } else {
-                    None
-                };
-
-                inner
-                    .upload_blob(data_vec, context_id_parsed.as_ref())
-                    .await
-            });
+            let call_context = context_id.map(str::to_string);
+            let result = self.run_in_context(
+                "upload_blob",
+                call_context.clone(),
+                self.audited("upload_blob", call_context, None, audit_args, async move {
+                    inner
+                        .upload_blob(data_vec, context_id_parsed.as_ref())
+                        .await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if !blob_metadata.is_empty() {
+                        let blob_id = blob::find_string_field(&json_data, "blobId")
+                            .map(str::to_owned)
+                            .ok_or_else(|| {
+                                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                                    "Upload response did not include a blobId",
+                                )
+                            })?;
+                        blob::save_blob_metadata(&blob_id, &blob_metadata).map_err(|e| {
+                            PyErr::new::<pyo3::exceptions::PyOSError, _>(format!(
+                                "Failed to save blob metadata: {}",
+                                e
+                            ))
+                        })?;
+                        blob::merge_blob_metadata(&mut json_data, &blob_metadata);
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
+    /// Upload a blob from a streaming source.
+    ///
+    /// `source` may be a path (`str` or `os.PathLike`), bytes, a file-like
+    /// object (anything with `read(size)`), an iterable of bytes-like
+    /// chunks (e.g. a generator), or an async iterable of chunks. Chunks
+    /// are pulled lazily across the FFI boundary while the GIL is
+    /// released, so large payloads never need a temp file or a full
+    /// in-memory copy. `content_type` / `metadata` behave as in
+    /// `upload_blob`.
+    ///
+    /// `on_progress(bytes_sent, total)` is called after each chunk; `total`
+    /// is known for paths and bytes and `None` otherwise.
+    #[pyo3(signature = (source, context_id=None, content_type=None, metadata=None, chunk_size=blob::DEFAULT_UPLOAD_CHUNK_SIZE, on_progress=None))]
+    pub fn upload_blob_stream(
+        &self,
+        py: Python<'_>,
+        source: &Bound<'_, PyAny>,
+        context_id: Option<&str>,
+        content_type: Option<&str>,
+        metadata: Option<std::collections::BTreeMap<String, String>>,
+        chunk_size: usize,
+        on_progress: Option<PyObject>,
+    ) -> PyResult<PyObject> {
+        if chunk_size == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "chunk_size must be at least 1",
+            ));
+        }
+        let path = match context_id {
+            Some(ctx_id) => {
+                let context_id = self.context_id(ctx_id)?;
+                format!("admin-api/blobs?context_id={context_id}")
+            }
+            None => "admin-api/blobs".to_string(),
+        };
+        let progress = blob::Progress::new(on_progress, blob::source_size(source)?);
+        let source = blob::UploadSource::from_py(source)?;
+        let blob_metadata = blob::BlobMetadata {
+            content_type: content_type.map(str::to_owned),
+            metadata: metadata.unwrap_or_default(),
+            encryption: None,
+        };
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+        let runtime = self.runtime()?;
+        let middleware = self.middleware.clone();
+        let transcript = self.transcript.clone();
+
+        // The reader task needs the GIL to pull each chunk.
+        error::begin_call("upload_blob_stream", None);
+        let context = self.panic_context("upload_blob_stream");
+        let result = py.allow_threads(move || {
+            error::catch_panic(
+                move || context,
+                || {
+                    timeouts::block_on(
+                        &runtime,
+                        middleware::scope(
+                            middleware,
+                            transcript::scope(transcript, async move {
+                                let body = blob::upload_body(source, chunk_size, progress);
+                                http::put_stream(
+                                    &client,
+                                    &connection.api_url,
+                                    &path,
+                                    &storage,
+                                    connection.node_name.as_deref(),
+                                    body,
+                                    max_response_bytes,
+                                )
+                                .await
+                            }),
+                        ),
+                    )
+                },
+            )
+            .and_then(|outcome| outcome)
+        })?;
+
+        match result {
+            Ok(mut json_data) => {
+                if !blob_metadata.is_empty() {
+                    if let Some(blob_id) = blob::find_string_field(&json_data, "blobId") {
+                        blob::save_blob_metadata(blob_id, &blob_metadata).map_err(|e| {
+                            PyErr::new::<pyo3::exceptions::PyOSError, _>(format!(
+                                "Failed to save blob metadata: {}",
+                                e
+                            ))
+                        })?;
+                    }
+                    blob::merge_blob_metadata(&mut json_data, &blob_metadata);
+                }
+                self.to_python(py, &json_data)
+            }
+            Err(e) => Err(self.client_error(e)),
+        }
+    }
+
     /// Download blob
-    #[pyo3(signature = (blob_id, context_id=None))]
-    pub fn download_blob(&self, blob_id: &str, context_id: Option<&str>) -> PyResult<PyObject> {
+    ///
+    /// With `with_metadata=True` returns `(bytes, metadata)` where `metadata`
+    /// is the `{contentType, metadata}` recorded at upload time, or `None`.
+    /// The payload is always checked against `blob_id`, which the node
+    /// derives from the content (see `hash_blob()`), and a mismatch raises
+    /// instead of returning the bytes. With `verify=True` its SHA-256 is also
+    /// checked against the hash the node reports for the blob, at the cost
+    /// of a round trip. Each chunk is hashed as it arrives, but the returned
+    /// bytes hold the whole blob in memory; use `dest_path` for large blobs.
+    /// Verification covers the bytes as stored, i.e. the ciphertext for
+    /// encrypted blobs.
+    ///
+    /// `encryption_key` decrypts a blob uploaded with the same key.
+    ///
+    /// With `dest_path` the blob is streamed to that file instead, chunk by
+    /// chunk, so it never sits in memory whole, and `{blobId, path, size,
+    /// resumedFrom}` is returned. Bytes land in `<dest_path>.part` until
+    /// the download completes; with `resume=True` (the default) a later
+    /// call continues an interrupted download from there using a range
+    /// request. `on_progress(bytes_written, total)` is called as chunks
+    /// arrive. Each chunk is hashed as it is written and verification covers
+    /// the whole file, resumed bytes included; on a mismatch the partial
+    /// file is deleted and the call raises.
+    #[pyo3(signature = (blob_id, context_id=None, with_metadata=false, verify=false, encryption_key=None, dest_path=None, on_progress=None, resume=true))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn download_blob(
+        &self,
+        blob_id: &str,
+        context_id: Option<&str>,
+        with_metadata: bool,
+        verify: bool,
+        encryption_key: Option<&[u8]>,
+        dest_path: Option<std::path::PathBuf>,
+        on_progress: Option<PyObject>,
+        resume: bool,
+    ) -> PyResult<PyObject> {
         let inner = self.inner.clone();
         let blob_id = blob_id.parse::<blobs::BlobId>().map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -427,66 +4424,374 @@ impl PyClient {
                 blob_id, e
             ))
         })?;
-        let context_id_opt = context_id.map(|s| s.to_string());
+        if let Some(dest_path) = dest_path {
+            if with_metadata || encryption_key.is_some() {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "dest_path downloads take neither with_metadata nor encryption_key",
+                ));
+            }
+            let progress = blob::Progress::new(on_progress, None);
+            return self
+                .download_blob_to_file(blob_id, context_id, &dest_path, verify, resume, progress);
+        }
+        let blob_id_str = blob_id.to_string();
+        let path = match context_id {
+            Some(ctx_id) => {
+                let context_id = self.context_id(ctx_id)?;
+                format!("admin-api/blobs/{blob_id}?context_id={context_id}")
+            }
+            None => format!("admin-api/blobs/{blob_id}"),
+        };
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let encryption_key = encryption_key
+            .map(crypto::decode_key)
+            .transpose()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let context_id_parsed = if let Some(ctx_id) = context_id_opt {
-                    Some(
-                        ctx_id
-                            .parse::<ContextId>()
-                            .map_err(|e| eyre::eyre!("Invalid context ID '{}': {}", ctx_id, e))?,
-                    )
+            let call_context = context_id.map(str::to_string);
+            let result = self.run_in_context("download_blob", call_context, async move {
+                let expected = if verify {
+                    Some(blob::fetch_expected_hash(&inner, &blob_id).await?)
                 } else {
                     None
                 };
 
-                inner
-                    .download_blob(&blob_id, context_id_parsed.as_ref())
-                    .await
-            });
+                let (response, _) = http::get_from(
+                    &client,
+                    &connection.api_url,
+                    &path,
+                    &storage,
+                    connection.node_name.as_deref(),
+                    0,
+                )
+                .await?
+                .ok_or_else(|| eyre::eyre!("Node returned no content for blob {}", blob_id))?;
+                let data = blob::read_verified(response, &blob_id, expected.as_ref()).await?;
+                match encryption_key {
+                    Some(key) => crypto::open(&key, &data),
+                    None => Ok(data),
+                }
+            })?;
 
             match result {
                 Ok(data) => {
                     // Return bytes directly as Python bytes object
-                    Ok(pyo3::types::PyBytes::new_bound(py, &data).into_py(py))
+                    let bytes = pyo3::types::PyBytes::new_bound(py, &data).into_py(py);
+                    if !with_metadata {
+                        return Ok(bytes);
+                    }
+                    let metadata = blob::load_blob_metadata(&blob_id_str)
+                        .map_err(|e| {
+                            PyErr::new::<pyo3::exceptions::PyOSError, _>(format!(
+                                "Failed to load blob metadata: {}",
+                                e
+                            ))
+                        })?
+                        .map(|m| serde_json::to_value(m).unwrap_or_default());
+                    let metadata = match metadata {
+                        Some(value) => json_to_python(py, &value),
+                        None => py.None(),
+                    };
+                    Ok((bytes, metadata).into_py(py))
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
+            }
+        })
+    }
+
+    /// Read `length` bytes of a blob starting at `offset`.
+    ///
+    /// Sends an HTTP range request so only the requested slice crosses the
+    /// wire when the node supports it; otherwise the node's full response is
+    /// sliced locally. `length=None` reads to the end of the blob. Reading
+    /// past the end returns the bytes that exist (possibly empty), and
+    /// `length=0` returns empty bytes without contacting the node. A range
+    /// reaching past the largest offset raises `ValueError`.
+    #[pyo3(signature = (blob_id, offset=0, length=None))]
+    pub fn read_blob(&self, blob_id: &str, offset: u64, length: Option<u64>) -> PyResult<PyObject> {
+        http::range_header(offset, length).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid range: {}", e))
+        })?;
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+        let blob_id = blob_id.parse::<blobs::BlobId>().map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid blob ID '{}': {}",
+                blob_id, e
+            ))
+        })?;
+
+        Python::with_gil(|py| {
+            let result = self.run("read_blob", async move {
+                http::get_range(
+                    &client,
+                    &connection.api_url,
+                    &format!("admin-api/blobs/{blob_id}"),
+                    &storage,
+                    connection.node_name.as_deref(),
+                    offset,
+                    length,
+                    max_response_bytes,
+                )
+                .await
+            })?;
+
+            match result {
+                Ok(data) => Ok(pyo3::types::PyBytes::new_bound(py, &data).into_py(py)),
+                Err(e) => Err(self.client_error(e)),
+            }
+        })
+    }
+
+    /// Describe direct, pre-authorized access to a blob.
+    ///
+    /// Returns `{url, headers, expiresAt}` that a web frontend can use to
+    /// download the blob straight from the node instead of proxying bytes
+    /// through the Python service. Nodes have no pre-signed URL support, so
+    /// `headers` carries a read-only token scoped to `context_id` and minted
+    /// for `ttl` seconds (see `mint_scoped_token`), never the client's own
+    /// session token. Raises `ClientError` when the node's auth service
+    /// cannot mint one. Nodes without authentication get no headers.
+    #[pyo3(signature = (blob_id, context_id, ttl=auth_service::DEFAULT_BLOB_ACCESS_TTL_SECS))]
+    pub fn blob_access(&self, blob_id: &str, context_id: &str, ttl: u64) -> PyResult<PyObject> {
+        let blob_id = blob_id.parse::<blobs::BlobId>().map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid blob ID '{}': {}",
+                blob_id, e
+            ))
+        })?;
+        let audit_args = serde_json::json!({
+            "blobId": blob_id.to_string(),
+            "contextId": context_id,
+            "ttl": ttl,
+        });
+        let context_id = self.context_id(context_id)?;
+        if ttl == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "ttl must be at least 1 second",
+            ));
+        }
+        let path = format!("admin-api/blobs/{blob_id}?context_id={context_id}");
+        let request = match self.auth_mode()?.mode {
+            AuthMode::None => None,
+            _ => Some(ScopedTokenRequest {
+                context_id,
+                context_identity: None,
+                read_only: true,
+                ttl_secs: ttl,
+            }),
+        };
+        let connection = self.connection.clone();
+        let storage = self.storage.clone();
+        let client = self.http.clone();
+        let max_response_bytes = self.max_response_bytes;
+
+        Python::with_gil(|py| {
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context(
+                "blob_access",
+                call_context.clone(),
+                self.audited("blob_access", call_context, None, audit_args, async move {
+                    let token = match &request {
+                        Some(request) => Some(
+                            auth_service::mint_scoped_token(
+                                &client,
+                                &connection.api_url,
+                                &storage,
+                                connection.node_name.as_deref(),
+                                request,
+                                max_response_bytes,
+                            )
+                            .await
+                            .map(WipeOnDrop::new)?,
+                        ),
+                        None => None,
+                    };
+                    http::authorize_get(&connection.api_url, &path, token.as_deref())
+                }),
+            )?;
+
+            match result {
+                Ok(data) => {
+                    let json_data = serde_json::to_value(data).map_err(|e| {
+                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                            "Failed to serialize response: {}",
+                            e
+                        ))
+                    })?;
+                    self.to_python(py, &json_data)
+                }
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     /// List blobs
-    pub fn list_blobs(&self) -> PyResult<PyObject> {
+    #[pyo3(signature = (fields=None))]
+    pub fn list_blobs(&self, fields: Option<Vec<String>>) -> PyResult<PyObject> {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_blobs().await });
+            let result = self.run_retrying("list_blobs", None, true, || async {
+                inner.list_blobs().await
+            })?;
 
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if let Some(fields) = &fields {
+                        project_fields(&mut json_data, fields);
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
+    /// Download many blobs into `dest_dir` in parallel.
+    ///
+    /// At most `concurrency` downloads are in flight at once, fewer while the
+    /// client's adaptive limit is lower (it grows as the node keeps up, so
+    /// `concurrency` can be set generously). Each blob is
+    /// written to `dest_dir/<blob_id>`; failures are reported per blob rather
+    /// than aborting the batch. Returns a `BulkResult` of
+    /// `{blobId, path, size, error}` records in the order the ids were given;
+    /// retry just the failures with `result.failed_inputs` and
+    /// `result.merge(...)`.
+    /// `on_progress(completed, total, bytes)` is invoked on the calling
+    /// thread after each blob finishes. Each blob is streamed to disk chunk
+    /// by chunk and checked against its id as it is written (with
+    /// `verify=True`, also against the hash the node reports); a mismatch
+    /// fails that blob (the partial file is removed). An id given more than
+    /// once is downloaded once and reported at each of its positions.
+    #[pyo3(signature = (blob_ids, dest_dir, concurrency=blob::DEFAULT_DOWNLOAD_CONCURRENCY, on_progress=None, verify=false))]
+    pub fn download_blobs(
+        &self,
+        py: Python<'_>,
+        blob_ids: Vec<String>,
+        dest_dir: &str,
+        concurrency: usize,
+        on_progress: Option<PyObject>,
+        verify: bool,
+    ) -> PyResult<PyBulkResult> {
+        if concurrency == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "concurrency must be at least 1",
+            ));
+        }
+        let dest_dir = std::path::PathBuf::from(dest_dir);
+        std::fs::create_dir_all(&dest_dir).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyOSError, _>(format!(
+                "Failed to create destination directory {:?}: {}",
+                dest_dir, e
+            ))
+        })?;
+
+        let total = blob_ids.len();
+        let mut outcomes: Vec<Option<blob::BlobDownloadOutcome>> = vec![None; total];
+        // Positions of each distinct id; repeats share one download.
+        let mut positions = std::collections::BTreeMap::new();
+        for (index, raw_id) in blob_ids.iter().enumerate() {
+            match raw_id.parse::<blobs::BlobId>() {
+                Ok(blob_id) => positions
+                    .entry(blob_id.to_string())
+                    .or_insert_with(|| (blob_id, Vec::new()))
+                    .1
+                    .push(index),
+                Err(e) => {
+                    outcomes[index] = Some(blob::BlobDownloadOutcome::failed(
+                        raw_id,
+                        format!("Invalid blob ID: {}", e),
+                    ));
+                }
+            }
+        }
+
+        let inner = self.inner.clone();
+        let connection = self.connection.clone();
+        let client = self.http.clone();
+        let storage = self.storage.clone();
+        let limiter = self.limiter.clone();
+        // Downloads run on the calling thread's task, inside the call's
+        // middleware, transcript and reauth scopes, with the GIL released
+        // so Python token storage and `on_progress` can take it.
+        let fan_out = async move {
+            let mut downloads = futures_util::stream::iter(positions.into_values())
+                .map(|(blob_id, indices)| {
+                    let download = blob::download_blob_to_dir(
+                        inner.clone(),
+                        connection.clone(),
+                        client.clone(),
+                        storage.clone(),
+                        limiter.clone(),
+                        blob_id,
+                        dest_dir.clone(),
+                        verify,
+                    );
+                    async move { (indices, download.await) }
+                })
+                .buffer_unordered(concurrency);
+
+            let mut completed = outcomes.iter().filter(|o| o.is_some()).count();
+            let mut bytes: u64 = 0;
+            while let Some((indices, outcome)) = downloads.next().await {
+                completed += indices.len();
+                bytes += outcome.size.unwrap_or(0);
+                for index in indices {
+                    outcomes[index] = Some(outcome.clone());
+                }
+                if let Some(callback) = &on_progress {
+                    Python::with_gil(|py| {
+                        if let Err(e) = callback.call1(py, (completed, total, bytes)) {
+                            e.print(py);
+                        }
+                    });
+                }
+            }
+            outcomes
+        };
+        let outcomes = self.run_released(py, "download_blobs", None, fan_out)?;
+
+        let items = outcomes
+            .into_iter()
+            .zip(blob_ids)
+            .map(|(outcome, raw_id)| {
+                let outcome = outcome.unwrap_or_else(|| {
+                    blob::BlobDownloadOutcome::failed(&raw_id, "Download did not complete")
+                });
+                let value = serde_json::to_value(&outcome).map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                        "Failed to serialize response: {}",
+                        e
+                    ))
+                })?;
+                limits::check_json(&value, self.max_response_bytes)
+                    .map_err(limits::too_large_error)?;
+                Ok(BulkItem {
+                    input: raw_id,
+                    value,
+                    error: outcome.error,
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(PyBulkResult::new("download_blobs", items))
+    }
+
     /// Get blob info
+    ///
+    /// Includes `contentType` / `metadata` when they were recorded by an
+    /// upload from this machine (see `upload_blob`).
     pub fn get_blob_info(&self, blob_id: &str) -> PyResult<PyObject> {
         let inner = self.inner.clone();
         let blob_id = blob_id.parse::<blobs::BlobId>().map_err(|e| {
@@ -495,26 +4800,27 @@ impl PyClient {
                 blob_id, e
             ))
         })?;
+        let blob_id_str = blob_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_blob_info(&blob_id).await });
+            let result = self.run_retrying("get_blob_info", None, true, || async {
+                inner.get_blob_info(&blob_id).await
+            })?;
 
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if let Ok(Some(metadata)) = blob::load_blob_metadata(&blob_id_str) {
+                        blob::merge_blob_metadata(&mut json_data, &metadata);
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -528,11 +4834,13 @@ impl PyClient {
                 blob_id, e
             ))
         })?;
+        let blob_id_str = blob_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.delete_blob(&blob_id).await });
+            let result = self.run(
+                "delete_blob",
+                async move { inner.delete_blob(&blob_id).await },
+            )?;
 
             match result {
                 Ok(data) => {
@@ -542,24 +4850,31 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    // Best effort: a stale sidecar is harmless, the blob is gone.
+                    let _ = blob::remove_blob_metadata(&blob_id_str);
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     /// Generate context identity
     pub fn generate_context_identity(&self) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({});
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.generate_context_identity().await });
+            let result = self.run(
+                "generate_context_identity",
+                self.audited(
+                    "generate_context_identity",
+                    None,
+                    None,
+                    audit_args,
+                    async move { inner.generate_context_identity().await },
+                ),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -569,12 +4884,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -584,9 +4896,10 @@ impl PyClient {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_peers_count().await });
+            let result = self.run(
+                "get_peers_count",
+                async move { inner.get_peers_count().await },
+            )?;
 
             match result {
                 Ok(data) => {
@@ -596,26 +4909,48 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    /// Create context
-    #[pyo3(signature = (application_id, group_id, params=None, service_name=None))]
+    /// Connected peers as a `Peers` object (`count`, `to_dict()`)
+    pub fn peers(&self) -> PyResult<PyPeers> {
+        let inner = self.inner.clone();
+        let result = self.run("peers", async move { inner.get_peers_count().await })?;
+        let data = result.map_err(|e| self.client_error(e))?;
+        let json_data = serde_json::to_value(data).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to serialize response: {}",
+                e
+            ))
+        })?;
+        Ok(PyPeers::from_response(&json_data))
+    }
+
+    /// Create context. `typed=True` returns a `Context` (with the new
+    /// context's id and this node's `member_public_key`) instead of the
+    /// response dict.
+    #[pyo3(signature = (application_id, group_id, params=None, service_name=None, typed=false))]
     pub fn create_context(
         &self,
         application_id: &str,
         group_id: &str,
         params: Option<&str>,
         service_name: Option<&str>,
+        typed: bool,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "applicationId": application_id,
+            "groupId": group_id,
+            "params": params,
+            "serviceName": service_name,
+            "typed": typed,
+        });
         let inner = self.inner.clone();
+        let application_id_str = application_id.to_string();
         let application_id = application_id.parse::<ApplicationId>().map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
                 "Invalid application ID '{}': {}",
@@ -625,50 +4960,226 @@ impl PyClient {
 
         let params = params.map(|p| p.as_bytes().to_vec()).unwrap_or_default();
         let group_id = group_id.to_string();
+        let group_id_str = group_id.clone();
         let service_name = service_name.map(|s| s.to_string());
 
-        Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
+        Python::with_gil(|py| {
+            let result = self.run(
+                "create_context",
+                self.audited("create_context", None, None, audit_args, async move {
+                    let request = admin::CreateContextRequest {
+                        application_id,
+                        service_name,
+                        context_seed: None,
+                        initialization_params: params,
+                        group_id,
+                        identity_secret: None,
+                        name: None,
+                    };
+                    inner.create_context(request).await
+                }),
+            )?;
+            self.metadata_cache.invalidate(MetadataKind::Context);
+
+            match result {
+                Ok(data) => {
+                    let json_data = serde_json::to_value(data).map_err(|e| {
+                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                            "Failed to serialize response: {}",
+                            e
+                        ))
+                    })?;
+                    if typed {
+                        let context =
+                            PyContext::created(&json_data, &application_id_str, &group_id_str);
+                        return Ok(Py::new(py, context)?.into_any());
+                    }
+                    self.to_python(py, &json_data)
+                }
+                Err(e) => Err(self.client_error(e)),
+            }
+        })
+    }
+
+    /// Context that exists for the duration of a `with` block.
+    ///
+    /// `with client.temporary_context(application_id, group_id) as ctx:`
+    /// creates the context on entry (same arguments as `create_context`),
+    /// exposes it as `ctx.context_id`, and deletes it on exit even when the
+    /// block raises.
+    #[pyo3(signature = (application_id, group_id, params=None, service_name=None))]
+    pub fn temporary_context(
+        slf: Py<Self>,
+        application_id: &str,
+        group_id: &str,
+        params: Option<&str>,
+        service_name: Option<&str>,
+    ) -> PyTemporaryContext {
+        PyTemporaryContext::new(slf, application_id, group_id, params, service_name)
+    }
+
+    /// Fork a context into a new one running the same application, e.g. to
+    /// try a risky change on production-like data in a sandbox.
+    ///
+    /// The node cannot copy a context's storage, so the application takes
+    /// the snapshot itself: `export_method` is executed on the source
+    /// context and its output is passed as the new context's
+    /// initialization params, which the application's init must accept.
+    /// The clone is created in `group_id` (default: the source context's
+    /// group) and `new_members` (public keys) are added to that group as
+    /// members. The result is the `create_context` response with
+    /// `clonedFrom`, `groupId` and `newMembers` added.
+    #[pyo3(signature = (context_id, new_members=None, group_id=None, export_method="export_state", service_name=None))]
+    pub fn clone_context(
+        &self,
+        context_id: &str,
+        new_members: Option<Vec<String>>,
+        group_id: Option<&str>,
+        export_method: &str,
+        service_name: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let members = new_members
+            .unwrap_or_default()
+            .iter()
+            .map(|key| {
+                key.parse::<PublicKey>().map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Invalid member public key '{}': {}",
+                        key, e
+                    ))
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let source = self.context_id(context_id)?;
+        self.ensure_not_frozen(&source)?;
+
+        let inner = self.inner.clone();
+        let key = (MetadataKind::Context, Some(source.to_string()));
+        let record = self.read_metadata("get_context", key, true, || async {
+            inner.get_context(&source).await
+        })?;
+        let group_id = match group_id {
+            Some(group_id) => group_id.to_string(),
+            None => invitation::group_of(&record).ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Context {} has no group to clone into; pass group_id",
+                    source
+                ))
+            })?,
+        };
+        let application_id = self.application_of(&source, false)?;
+        let application_id = application_id.parse::<ApplicationId>().map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Context {} reports an invalid application ID '{}': {}",
+                source, application_id, e
+            ))
+        })?;
+
+        let call = self.execute_call(
+            source,
+            export_method.to_string(),
+            "{}".to_string(),
+            false,
+            None,
+        );
+        let call = self.record_usage(&source, export_method, "", call);
+        let snapshot = self
+            .run_in_context("clone_context", Some(source.to_string()), call)?
+            .map_err(|e| {
+                self.client_error(e.wrap_err(format!(
+                    "Snapshot of context {} with {} failed; nothing was created",
+                    source, export_method
+                )))
+            })?;
+        let state = snapshot
+            .get("result")
+            .and_then(|result| result.get("output"))
+            .cloned()
+            .unwrap_or_default();
+        let params = serde_json::to_vec(&state).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to serialize snapshot: {}",
+                e
+            ))
+        })?;
+
+        let inner = self.inner.clone();
+        let group = group_id.clone();
+        let service_name = service_name.map(str::to_string);
+        let created = self
+            .run("clone_context", async move {
                 let request = admin::CreateContextRequest {
                     application_id,
                     service_name,
                     context_seed: None,
                     initialization_params: params,
-                    group_id,
+                    group_id: group,
                     identity_secret: None,
                     name: None,
                 };
                 inner.create_context(request).await
-            });
+            })?
+            .map_err(|e| self.client_error(e))?;
+        self.metadata_cache.invalidate(MetadataKind::Context);
+        let mut json_data = serde_json::to_value(created).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to serialize response: {}",
+                e
+            ))
+        })?;
 
-            match result {
-                Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
-                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                            "Failed to serialize response: {}",
-                            e
-                        ))
-                    })?;
-                    Ok(json_to_python(py, &json_data))
-                }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
-            }
-        })
+        let added: Vec<String> = members.iter().map(ToString::to_string).collect();
+        if !members.is_empty() {
+            let inner = self.inner.clone();
+            let group = group_id.clone();
+            self.run("clone_context", async move {
+                let request = admin::AddGroupMembersApiRequest {
+                    members: members
+                        .into_iter()
+                        .map(|identity| admin::GroupMemberApiInput {
+                            identity,
+                            role: GroupMemberRole::Member,
+                        })
+                        .collect(),
+                    requester: None,
+                };
+                inner.add_group_members(&group, request).await.map(|_| ())
+            })?
+            .map_err(|e| {
+                self.client_error(e.wrap_err("Context cloned but adding members failed"))
+            })?;
+        }
+
+        if let Some(fields) = json_data.as_object_mut() {
+            fields.insert("clonedFrom".to_string(), source.to_string().into());
+            fields.insert("groupId".to_string(), group_id.into());
+            fields.insert("newMembers".to_string(), added.into());
+        }
+        Python::with_gil(|py| self.to_python(py, &json_data))
     }
 
     /// Delete context
-    #[pyo3(signature = (context_id, requester=None))]
-    pub fn delete_context(&self, context_id: &str, requester: Option<&str>) -> PyResult<PyObject> {
+    ///
+    /// With `export_to` (a file path, or an existing directory to write
+    /// `context-<id>.json` into), a snapshot of the context — its record,
+    /// storage info, identities and their aliases, and the events received
+    /// over `subscribe` — is written there first, and the context is only
+    /// deleted once the file is on disk. If the export fails it raises and
+    /// nothing is deleted. The result then names the file as `exportedTo`.
+    #[pyo3(signature = (context_id, requester=None, export_to=None))]
+    pub fn delete_context(
+        &self,
+        context_id: &str,
+        requester: Option<&str>,
+        export_to: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "contextId": context_id,
+            "requester": requester,
+            "exportTo": export_to,
+        });
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
         let requester = match requester {
             Some(r) => Some(r.parse::<PublicKey>().map_err(|e| {
                 PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -680,24 +5191,40 @@ impl PyClient {
         };
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.delete_context(&context_id, requester).await });
+            let exported = match export_to {
+                Some(export_to) => {
+                    Some(self.export_context(&context_id, std::path::Path::new(export_to))?)
+                }
+                None => None,
+            };
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context(
+                "delete_context",
+                call_context.clone(),
+                self.audited(
+                    "delete_context",
+                    call_context,
+                    None,
+                    audit_args,
+                    async move { inner.delete_context(&context_id, requester).await },
+                ),
+            )?;
+            self.metadata_cache.invalidate(MetadataKind::Context);
 
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if let (Some(path), Some(fields)) = (exported, json_data.as_object_mut()) {
+                        fields.insert("exportedTo".to_string(), path.display().to_string().into());
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -705,17 +5232,14 @@ impl PyClient {
     /// Get context storage
     pub fn get_context_storage(&self, context_id: &str) -> PyResult<PyObject> {
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_context_storage(&context_id).await });
+            let call_context = Some(context_id.to_string());
+            let result =
+                self.run_retrying("get_context_storage", call_context, true, || async {
+                    inner.get_context_storage(&context_id).await
+                })?;
 
             match result {
                 Ok(data) => {
@@ -725,30 +5249,78 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     /// Get context identities
     pub fn get_context_identities(&self, context_id: &str) -> PyResult<PyObject> {
+        let context_id = self.context_id(context_id)?;
+        let json_data = self.identities_response(&context_id)?;
+        Python::with_gil(|py| self.to_python(py, &json_data))
+    }
+
+    /// Prove that `signer`'s key is an identity of `context_id`, for
+    /// systems outside the network.
+    ///
+    /// The key must be among the context's identities on this node. Returns
+    /// `{version, contextId, publicKey, rootHash, audience, nonce, issuedAt,
+    /// expiresAt, signature}`, signed by the key and valid for `ttl`
+    /// seconds; check it with `verify_membership_proof()`. `audience` names
+    /// who the proof is for, so it cannot be replayed to someone else.
+    #[pyo3(signature = (context_id, signer, audience=None, ttl=DEFAULT_MEMBERSHIP_PROOF_TTL_SECS))]
+    pub fn export_membership_proof(
+        &self,
+        py: Python<'_>,
+        context_id: &str,
+        signer: PyRef<'_, PySigner>,
+        audience: Option<String>,
+        ttl: u64,
+    ) -> PyResult<PyObject> {
+        let context_id = self.context_id(context_id)?;
+        let public_key = signer.public_key();
+        let identities = self.identities_response(&context_id)?;
+        if !membership::is_member(&identities, &public_key) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "{} is not an identity of context {}",
+                public_key, context_id
+            )));
+        }
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let root_hash = self
+            .run_in_context("get_context", Some(context_id.to_string()), async {
+                fetch_root_hash(&inner, &context_id).await
+            })?
+            .map_err(|e| self.client_error(e))?;
+        let nonce = entropy::random_bytes::<16>().map_err(|e| self.client_error(e))?;
+        let issued_at = chrono::Utc::now().timestamp();
+        let proof = MembershipProof::sign(
+            signer.signing_key(),
+            &context_id,
+            &root_hash,
+            audience,
+            nonce,
+            issued_at,
+            issued_at + ttl as i64,
+        );
+        let value = serde_json::to_value(&proof).map_err(|e| self.client_error(e))?;
+        Ok(json_to_python(py, &value))
+    }
+
+    /// Get context client keys
+    pub fn get_context_client_keys(&self, context_id: &str) -> PyResult<PyObject> {
+        let inner = self.inner.clone();
+        let context_id = self.context_id(context_id)?;
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_context_identities(&context_id, false).await });
+            let call_context = Some(context_id.to_string());
+            let result =
+                self.run_retrying("get_context_client_keys", call_context, true, || async {
+                    inner.get_context_client_keys(&context_id).await
+                })?;
 
             match result {
                 Ok(data) => {
@@ -758,30 +5330,23 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    /// Get context client keys
-    pub fn get_context_client_keys(&self, context_id: &str) -> PyResult<PyObject> {
+    /// Sync context
+    pub fn sync_context(&self, context_id: &str) -> PyResult<PyObject> {
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_context_client_keys(&context_id).await });
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context("sync_context", call_context, async move {
+                inner.sync_context(&context_id).await
+            })?;
 
             match result {
                 Ok(data) => {
@@ -791,108 +5356,1265 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    /// Sync context
-    pub fn sync_context(&self, context_id: &str) -> PyResult<PyObject> {
-        let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
+    /// Execute function call via JSON-RPC
+    ///
+    /// The executor_public_key parameter is accepted for backward compatibility
+    /// but ignored — the node auto-resolves the owned identity for the context.
+    ///
+    /// Repeated calls with the same `(context_id, method, args)` reuse the
+    /// request prepared by the first one (see `request_cache_size`).
+    ///
+    /// With `track_root_hash=True` the context's root hash is read before
+    /// and after the call and added to the result as `old_root_hash` and
+    /// `new_root_hash`. Passing `expected_root_hash` (implies tracking)
+    /// makes the call optimistic: it raises a conflict error without
+    /// executing if the context is no longer at that hash.
+    ///
+    /// With `result_type` (a dataclass, a pydantic model, `list[T]`, ...)
+    /// only the method's output is returned, converted to that type;
+    /// output that does not fit raises `ResultTypeError`.
+    ///
+    /// With `dry_run=True` nothing is sent: the call is checked and built
+    /// as it would be (aliases resolved, method defaults merged, size
+    /// limit applied) and returned as `{method, url, headers, body, wire,
+    /// expiresAt}`, `headers` carrying the bearer token the request would
+    /// use — treat the preview as a secret. Arguments over
+    /// `max_request_bytes` raise as the call would with
+    /// `oversized_args="error"`; with `"blob"` they are shown inline, the
+    /// upload only happens when sending.
+    ///
+    /// `encoding` (an `ArgEncoding`, or `"json"` / `"base64"`) converts
+    /// `args` before sending, over any encoding registered with
+    /// `set_arg_encoding()`.
+    #[pyo3(signature = (context_id, method, args, executor_public_key="", track_root_hash=false, expected_root_hash=None, result_type=None, dry_run=false, encoding=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_function(
+        &self,
+        context_id: &str,
+        method: &str,
+        args: &str,
+        executor_public_key: &str,
+        track_root_hash: bool,
+        expected_root_hash: Option<String>,
+        result_type: Option<&Bound<'_, PyAny>>,
+        dry_run: bool,
+        encoding: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<PyObject> {
+        let context_id = self.context_id(context_id)?;
+        // Not sent — the node resolves the executor; only used to
+        // attribute the call in `usage()` reports.
+        self.ensure_not_frozen(&context_id)?;
+        let encoding = encoding.map(ArgEncoding::from_py).transpose()?;
+        let audit_args = serde_json::json!({ "args": args });
+        let args = self.prepare_args(&context_id, method, args, encoding.as_ref())?;
+        if dry_run {
+            return self.preview_execute(context_id, method, &args);
+        }
+        let call = self.execute_call(
+            context_id,
+            method.to_string(),
+            args,
+            track_root_hash,
+            expected_root_hash,
+        );
+        let call = self.record_usage(&context_id, method, executor_public_key, call);
+        let call = self.audited(
+            "execute_function",
+            Some(context_id.to_string()),
+            Some(method),
+            audit_args,
+            call,
+        );
+
+        Python::with_gil(|py| {
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context("execute_function", call_context, call)?;
+            if result.is_ok() {
+                self.read_cache
+                    .invalidate(Some(&context_id.to_string()), None);
+            }
+
+            match (result, result_type) {
+                (Ok(json_data), Some(result_type)) => {
+                    self.check_size(&json_data)?;
+                    let output = typed_result::output(&json_data);
+                    typed_result::convert(py, output, result_type, "output")
+                }
+                (Ok(json_data), None) => self.to_python(py, &json_data),
+                (Err(e), _) => Err(self.client_error(e)),
+            }
+        })
+    }
+
+    /// Call a read-only method: `execute_function` whose result may be
+    /// served from the client's read cache (see `read_cache_size`).
+    ///
+    /// Results are cached per (context, method, args), the args compared
+    /// as JSON, for `read_cache_ttl` seconds; `refresh=True` skips the
+    /// cached result and stores the new one. Only use it for methods that
+    /// do not change state: a cached call is not sent at all.
+    #[pyo3(signature = (context_id, method, args="{}", refresh=false, result_type=None))]
+    pub fn view(
+        &self,
+        py: Python<'_>,
+        context_id: &str,
+        method: &str,
+        args: &str,
+        refresh: bool,
+        result_type: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<PyObject> {
+        let context_id = self.context_id(context_id)?;
+        self.ensure_not_frozen(&context_id)?;
+        let args = self.prepare_args(&context_id, method, args, None)?;
+        let key = ReadKey::new(&context_id.to_string(), method, &args);
+        let cached = match refresh {
+            true => None,
+            false => self.read_cache.get(&key),
+        };
+        let json_data = match cached {
+            Some(json_data) => json_data,
+            None => {
+                let call = self.execute_call(context_id, method.to_string(), args, false, None);
+                let call = self.record_usage(&context_id, method, "", call);
+                let json_data = self
+                    .run_in_context("view", Some(context_id.to_string()), call)?
+                    .map_err(|e| self.client_error(e))?;
+                self.read_cache.insert(key, json_data.clone());
+                json_data
+            }
+        };
+        match result_type {
+            Some(result_type) => {
+                self.check_size(&json_data)?;
+                let output = typed_result::output(&json_data);
+                typed_result::convert(py, output, result_type, "output")
+            }
+            None => self.to_python(py, &json_data),
+        }
+    }
+
+    /// Call read-only `method` with the same `args` on every context of
+    /// `context_ids` at once, e.g. to aggregate state across contexts for
+    /// a dashboard, with the whole fan-out on the client runtime and the
+    /// GIL released.
+    ///
+    /// Up to `concurrency` calls are in flight (within the client's
+    /// concurrency limit), each retried per the client's `RetryConfig` and
+    /// bounded by the request timeout. With `deadline` (seconds) the
+    /// fan-out as a whole stops then: calls still running are cancelled
+    /// and reported as timed out. Results are served from and stored in
+    /// the read cache like `view()`'s, unless `refresh=True`.
+    ///
+    /// Returns `{context_id: {result, error}}` in the order given, `error`
+    /// being `None` for contexts that answered: a failing or invalid
+    /// context only fails its own entry.
+    #[pyo3(signature = (context_ids, method, args="{}", concurrency=jsonrpc_batch::DEFAULT_FAN_OUT, deadline=None, refresh=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn query_contexts(
+        &self,
+        py: Python<'_>,
+        context_ids: Vec<String>,
+        method: &str,
+        args: &str,
+        concurrency: usize,
+        deadline: Option<f64>,
+        refresh: bool,
+    ) -> PyResult<PyObject> {
+        if concurrency == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "concurrency must be at least 1",
+            ));
+        }
+        let deadline = deadline
+            .map(|secs| {
+                std::time::Duration::try_from_secs_f64(secs)
+                    .ok()
+                    .filter(|deadline| !deadline.is_zero())
+                    .ok_or_else(|| {
+                        PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                            "deadline must be a positive number of seconds",
+                        )
+                    })
+            })
+            .transpose()?;
+        serde_json::from_str::<serde_json::Value>(args).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid JSON args: {}", e))
+        })?;
+
+        let mut context_ids = context_ids;
+        let mut seen = std::collections::HashSet::new();
+        context_ids.retain(|raw| seen.insert(raw.clone()));
+        let mut outcomes: Vec<Option<Result<serde_json::Value, String>>> =
+            vec![None; context_ids.len()];
+        let mut pending = Vec::new();
+        for (index, raw) in context_ids.iter().enumerate() {
+            let prepared = self.context_id(raw).and_then(|context_id| {
+                self.ensure_not_frozen(&context_id)?;
+                let args = self.prepare_args(&context_id, method, args, None)?;
+                Ok((context_id, args))
+            });
+            let (context_id, args) = match prepared {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    outcomes[index] = Some(Err(e.to_string()));
+                    continue;
+                }
+            };
+            let key = ReadKey::new(&context_id.to_string(), method, &args);
+            let cached = match refresh {
+                true => None,
+                false => self.read_cache.get(&key),
+            };
+            if let Some(cached) = cached {
+                outcomes[index] = Some(Ok(cached));
+                continue;
+            }
+            let call = self.execute_call(context_id, method.to_string(), args, false, None);
+            let call = self.record_usage(&context_id, method, "", call);
+            pending.push((index, key, call));
+        }
+
+        let limiter = self.limiter.clone();
+        let timeout = timeouts::effective(self.request_timeout);
+        let fan_out = async move {
+            let calls = futures_util::stream::iter(pending)
+                .map(|(index, key, call)| {
+                    let limiter = limiter.clone();
+                    async move {
+                        let _slot = limiter.acquire().await;
+                        let outcome = match timeouts::bounded(timeout, call).await {
+                            Ok(Ok(json_data)) => Ok(json_data),
+                            Ok(Err(e)) => Err(error::render_chain(e)),
+                            Err(timeout) => {
+                                Err(timeouts::timed_out_message("query_contexts", timeout))
+                            }
+                        };
+                        (index, key, outcome)
+                    }
+                })
+                .buffer_unordered(concurrency);
+            match deadline {
+                Some(deadline) => {
+                    calls
+                        .take_until(tokio::time::sleep(deadline))
+                        .collect::<Vec<_>>()
+                        .await
+                }
+                None => calls.collect::<Vec<_>>().await,
+            }
+        };
+
+        let finished = self.run_released(py, "query_contexts", None, fan_out)?;
+
+        for (index, key, outcome) in finished {
+            if let Ok(json_data) = &outcome {
+                self.read_cache.insert(key, json_data.clone());
+            }
+            outcomes[index] = Some(outcome);
+        }
+        let results = pyo3::types::PyDict::new_bound(py);
+        for (raw, outcome) in context_ids.iter().zip(outcomes) {
+            let outcome = outcome.unwrap_or_else(|| {
+                Err(format!(
+                    "query_contexts timed out after {}s (deadline)",
+                    deadline.unwrap_or_default().as_secs_f64()
+                ))
+            });
+            let (result, error) = match outcome {
+                Ok(result) => (result, None),
+                Err(e) => (serde_json::Value::Null, Some(e)),
+            };
+            let entry = serde_json::json!({ "result": result, "error": error });
+            limits::check_json(&entry, self.max_response_bytes).map_err(limits::too_large_error)?;
+            results.set_item(raw, json_to_python(py, &entry))?;
+        }
+        Ok(results.into_any().unbind())
+    }
+
+    /// Drop cached `view()` results of `context_id` (all contexts when
+    /// `None`) for `method` (all methods when `None`), so the next call
+    /// goes to the node. Returns how many were dropped.
+    #[pyo3(signature = (context_id=None, method=None))]
+    pub fn invalidate(&self, context_id: Option<&str>, method: Option<&str>) -> PyResult<usize> {
+        let context_id = context_id
+            .map(|raw| self.context_id(raw).map(|id| id.to_string()))
+            .transpose()?;
+        Ok(self.read_cache.invalidate(context_id.as_deref(), method))
+    }
+
+    /// Read cache counters: `{entries, capacity, ttl, hits, misses}`
+    #[getter]
+    pub fn read_cache_stats(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.read_cache.stats())
+    }
+
+    /// Execute a call signed offline with `Signer.sign_execution()`.
+    ///
+    /// `request` is the envelope, as a dict or its JSON. The signature and
+    /// expiry are checked first and each envelope is accepted once per
+    /// client, so a copied envelope cannot be replayed through it; the call
+    /// then runs like `execute_function` and is attributed to the signer's
+    /// key in `usage()`. Raises `ValueError` for invalid envelopes.
+    pub fn submit_signed(&self, py: Python<'_>, request: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        let value = match request.downcast::<pyo3::types::PyString>() {
+            Ok(raw) => serde_json::from_str(raw.to_str()?).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid signed execution JSON: {}",
+                    e
+                ))
+            })?,
+            Err(_) => crate::utils::python_to_json(request)?,
+        };
+        let signed: SignedExecution = serde_json::from_value(value).map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
+                "Invalid signed execution: {}",
+                e
             ))
         })?;
+        let now = chrono::Utc::now().timestamp();
+        let context_id = signed
+            .verify(now)
+            .and_then(|context_id| self.signed_nonces.admit(&signed, now).map(|()| context_id))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e)))?;
+        self.ensure_not_frozen(&context_id)?;
+        let call = self.execute_call(
+            context_id,
+            signed.method.clone(),
+            signed.args_json.clone(),
+            false,
+            None,
+        );
+        let call = self.record_usage(&context_id, &signed.method, &signed.public_key, call);
+        let call = self.audited(
+            "submit_signed",
+            Some(context_id.to_string()),
+            Some(&signed.method),
+            serde_json::json!({ "args": signed.args_json }),
+            call,
+        );
+        let result = self.run_in_context("submit_signed", Some(context_id.to_string()), call)?;
+        match result {
+            Ok(json_data) => self.to_python(py, &json_data),
+            Err(e) => Err(self.client_error(e)),
+        }
+    }
 
-        Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.sync_context(&context_id).await });
+    /// Asyncio variant of `execute_function`: returns an awaitable that
+    /// resolves to the same result, e.g.
+    /// `await client.execute_async(context_id, "get", "{}")`.
+    ///
+    /// The request runs on the client's runtime rather than a Python
+    /// thread, so an event loop can keep many calls in flight (still
+    /// bounded by the client's concurrency limit).
+    #[pyo3(signature = (context_id, method, args, executor_public_key="", track_root_hash=false, expected_root_hash=None, encoding=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_async<'py>(
+        slf: &Bound<'py, Self>,
+        context_id: &str,
+        method: &str,
+        args: &str,
+        executor_public_key: &str,
+        track_root_hash: bool,
+        expected_root_hash: Option<String>,
+        encoding: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = slf.borrow();
+        let context_id = client.context_id(context_id)?;
+        // Not sent — the node resolves the executor; only used to
+        // attribute the call in `usage()` reports.
+        client.ensure_not_frozen(&context_id)?;
+        let encoding = encoding.map(ArgEncoding::from_py).transpose()?;
+        let audit_args = serde_json::json!({ "args": args });
+        let args = client.prepare_args(&context_id, method, args, encoding.as_ref())?;
+        let call = client.execute_call(
+            context_id,
+            method.to_string(),
+            args,
+            track_root_hash,
+            expected_root_hash,
+        );
+        let call = client.record_usage(&context_id, method, executor_public_key, call);
+        let call = client.audited(
+            "execute_function",
+            Some(context_id.to_string()),
+            Some(method),
+            audit_args,
+            call,
+        );
+        drop(client);
+        Self::run_async(
+            slf,
+            "execute_function",
+            Some(context_id.to_string()),
+            true,
+            call,
+        )
+    }
 
-            match result {
-                Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
-                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                            "Failed to serialize response: {}",
-                            e
-                        ))
-                    })?;
-                    Ok(json_to_python(py, &json_data))
-                }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
+    /// `ContextSession` bound to `context_id` and `executor_public_key`
+    /// (ids or aliases, resolved once here), whose `call()` and `view()`
+    /// take just the method and its arguments.
+    #[pyo3(signature = (context_id, executor_public_key=None))]
+    pub fn session(
+        slf: &Bound<'_, Self>,
+        context_id: &str,
+        executor_public_key: Option<&str>,
+    ) -> PyResult<PyContextSession> {
+        let client = slf.borrow();
+        let context_id = client.context_id(context_id)?;
+        let executor_public_key = executor_public_key
+            .map(|raw| {
+                client
+                    .identity(raw, &context_id, "executor public key")
+                    .map(|key| key.to_string())
+            })
+            .transpose()?;
+        drop(client);
+        Ok(PyContextSession::new(
+            slf.clone().unbind(),
+            context_id.to_string(),
+            executor_public_key,
+        ))
+    }
+
+    /// Group of `execute_function` calls run together, all or nothing.
+    ///
+    /// `with client.task_group() as group:` collects `group.execute(...)`
+    /// calls and runs them concurrently when the block exits; the first
+    /// failure cancels the calls still in flight and is raised, with
+    /// `group.failed_index` naming it. Results land in `group.results` in
+    /// call order. `await group.run_async()` does the same from asyncio.
+    pub fn task_group(slf: Py<Self>) -> PyTaskGroup {
+        PyTaskGroup::new(slf)
+    }
+
+    /// Register default arguments for `method`, merged into the JSON
+    /// object arguments of every `execute_function` call of it (also
+    /// `execute_async`, `execute_batch`, task groups and the outbox).
+    ///
+    /// `defaults` is a dict or a JSON object string. Arguments given in a
+    /// call win key by key, nested objects merging the same way. With
+    /// `application_id` the defaults only apply to contexts running that
+    /// application, on top of any registered for all applications.
+    /// Registering again replaces the previous defaults.
+    #[pyo3(signature = (method, defaults, application_id=None))]
+    pub fn set_method_defaults(
+        &self,
+        method: &str,
+        defaults: &Bound<'_, PyAny>,
+        application_id: Option<&str>,
+    ) -> PyResult<()> {
+        let defaults = match defaults.downcast::<pyo3::types::PyString>() {
+            Ok(raw) => serde_json::from_str(raw.to_str()?).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid defaults JSON: {}",
                     e
-                ))),
+                ))
+            })?,
+            Err(_) => crate::utils::python_to_json(defaults)?,
+        };
+        let serde_json::Value::Object(defaults) = defaults else {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "defaults must be a JSON object",
+            ));
+        };
+        if let Some(application_id) = application_id {
+            application_id.parse::<ApplicationId>().map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid application ID '{}': {}",
+                    application_id, e
+                ))
+            })?;
+        }
+        self.method_defaults.set(application_id, method, defaults);
+        Ok(())
+    }
+
+    /// Remove the defaults of `method`, or of every method when omitted,
+    /// registered for `application_id` (for all applications when
+    /// omitted). Returns how many registrations were removed.
+    #[pyo3(signature = (method=None, application_id=None))]
+    pub fn clear_method_defaults(
+        &self,
+        method: Option<&str>,
+        application_id: Option<&str>,
+    ) -> usize {
+        self.method_defaults.clear(application_id, method)
+    }
+
+    /// Registered method defaults: `[{applicationId, method, defaults}]`,
+    /// `applicationId` being `None` for defaults of all applications
+    #[getter]
+    pub fn method_defaults(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.method_defaults.to_json())
+    }
+
+    /// Encode the arguments of every call in `context_id` (only of
+    /// `method` when given) with `encoding`: an `ArgEncoding`, or
+    /// `"json"` / `"base64"`. Applies wherever `execute_function` does,
+    /// unless a call passes its own `encoding`; a method's encoding wins
+    /// over its context's. Registering again replaces the previous one.
+    #[pyo3(signature = (context_id, encoding, method=None))]
+    pub fn set_arg_encoding(
+        &self,
+        context_id: &str,
+        encoding: &Bound<'_, PyAny>,
+        method: Option<&str>,
+    ) -> PyResult<()> {
+        let context_id = self.context_id(context_id)?;
+        let encoding = ArgEncoding::from_py(encoding)?;
+        self.arg_encodings
+            .set(&context_id.to_string(), method, encoding);
+        Ok(())
+    }
+
+    /// Remove the encoding of `method` in `context_id`, or every encoding
+    /// of the context when omitted. Returns how many registrations were
+    /// removed.
+    #[pyo3(signature = (context_id, method=None))]
+    pub fn clear_arg_encoding(&self, context_id: &str, method: Option<&str>) -> PyResult<usize> {
+        let context_id = self.context_id(context_id)?;
+        Ok(self.arg_encodings.clear(&context_id.to_string(), method))
+    }
+
+    /// Registered argument encodings: `{contextId: {method: kind}}`,
+    /// `"*"` standing for every method of the context
+    #[getter]
+    pub fn arg_encodings(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.arg_encodings.to_json())
+    }
+
+    /// Execute many calls in JSON-RPC batch requests.
+    ///
+    /// `calls` is a list of `(context_id, method, args)` tuples, dicts with
+    /// those keys, or entries of a previous result's `failed_inputs`. Up to
+    /// `max_batch_size` calls share one HTTP request (several requests go
+    /// out concurrently, within the client's concurrency limit); nodes that
+    /// refuse batches get the calls one by one. Returns a `BulkResult` of
+    /// `{contextId, method, result, error}` records in call order: a call
+    /// that fails, or is invalid, only fails its own record. Transport and
+    /// auth failures raise for the whole call.
+    #[pyo3(signature = (calls, max_batch_size=jsonrpc_batch::DEFAULT_MAX_BATCH_SIZE))]
+    pub fn execute_batch(
+        &self,
+        calls: Vec<Bound<'_, PyAny>>,
+        max_batch_size: usize,
+    ) -> PyResult<PyBulkResult> {
+        if max_batch_size == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "max_batch_size must be at least 1",
+            ));
+        }
+        let calls = calls
+            .iter()
+            .map(extract_batch_call)
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let mut outcomes: Vec<Option<Result<serde_json::Value, String>>> = vec![None; calls.len()];
+        let mut prepared = Vec::new();
+        for (index, call) in calls.iter().enumerate() {
+            let request = call
+                .context_id
+                .parse::<ContextId>()
+                .map_err(|e| format!("Invalid context ID '{}': {}", call.context_id, e))
+                .and_then(|context_id| {
+                    if self.archive.is_frozen(&call.context_id) {
+                        return Err(format!("Context {} is frozen", call.context_id));
+                    }
+                    let args = self
+                        .prepare_args(&context_id, &call.method, &call.args, None)
+                        .map_err(|e| e.to_string())?;
+                    self.request_cache
+                        .get_or_build(context_id, &call.method, &args)
+                        .map_err(error::render_chain)
+                });
+            match request {
+                Ok(request) => prepared.push((index, request)),
+                Err(e) => outcomes[index] = Some(Err(e)),
+            }
+        }
+
+        let mut serialized: Vec<String> = calls
+            .iter()
+            .map(|call| call.context_id.clone())
+            .filter(|context_id| self.context_guards.is_serialized(context_id))
+            .collect();
+        serialized.sort();
+        serialized.dedup();
+
+        let inner = self.inner.clone();
+        let limiter = self.limiter.clone();
+        let batch_support = self.batch_support.clone();
+        let context_guards = self.context_guards.clone();
+        let send_chunk = |chunk: &[(usize, Arc<request_cache::PreparedRequest>)]| {
+            let inner = inner.clone();
+            let limiter = limiter.clone();
+            let batch_support = batch_support.clone();
+            let retry_config = self.retry.clone();
+            let retry_budget = self.retry_budget.clone();
+            async move {
+                let _slot = limiter.acquire().await;
+                if batch_support.worth_trying() {
+                    let body = jsonrpc_batch::body(
+                        chunk
+                            .iter()
+                            .map(|(index, prepared)| (*index, &prepared.request)),
+                    )?;
+                    let reply = retry::with_retries(&retry_config, &retry_budget, false, || {
+                        http::send_json(
+                            &self.http,
+                            reqwest::Method::POST,
+                            &self.connection.api_url,
+                            "jsonrpc",
+                            &self.storage,
+                            self.connection.node_name.as_deref(),
+                            Some(&body),
+                            self.max_response_bytes,
+                        )
+                    })
+                    .await;
+                    let responses = match reply {
+                        Ok(reply) => jsonrpc_batch::split(reply),
+                        Err(e) if jsonrpc_batch::rejected_as_batch(&error::render_chain(&e)) => {
+                            None
+                        }
+                        Err(e) => return Err(e),
+                    };
+                    batch_support.record(responses.is_some());
+                    if let Some(mut responses) = responses {
+                        return Ok(chunk
+                            .iter()
+                            .map(|(index, _)| {
+                                let outcome = match responses.remove(index) {
+                                    Some(response) => {
+                                        match jsonrpc_batch::response_error(&response) {
+                                            Some(e) => Err(e),
+                                            None => Ok(response),
+                                        }
+                                    }
+                                    None => Err("Node sent no response for this call".to_string()),
+                                };
+                                (*index, outcome)
+                            })
+                            .collect::<Vec<_>>());
+                    }
+                }
+                Ok::<_, eyre::Report>(
+                    futures_util::future::join_all(chunk.iter().map(|(index, prepared)| {
+                        let inner = inner.clone();
+                        let retry_config = retry_config.clone();
+                        let retry_budget = retry_budget.clone();
+                        async move {
+                            let send = || inner.execute_jsonrpc(prepared.request.clone());
+                            let outcome = match retry::with_retries(
+                                &retry_config,
+                                &retry_budget,
+                                false,
+                                send,
+                            )
+                            .await
+                            {
+                                Ok(response) => {
+                                    serde_json::to_value(response).map_err(|e| e.to_string())
+                                }
+                                Err(e) => Err(error::render_chain(e)),
+                            };
+                            (*index, outcome)
+                        }
+                    }))
+                    .await,
+                )
+            }
+        };
+
+        let sent = self.run_batch("execute_batch", async {
+            let mut turns = Vec::with_capacity(serialized.len());
+            for context_id in &serialized {
+                turns.push(context_guards.acquire(context_id).await);
+            }
+            futures_util::future::try_join_all(prepared.chunks(max_batch_size).map(send_chunk))
+                .await
+        })?;
+        let sent = match sent {
+            Ok(sent) => sent,
+            Err(e) => return Err(self.client_error(e)),
+        };
+        let sent: Vec<_> = sent.into_iter().flatten().collect();
+        self.audit_batch(
+            "execute_batch",
+            sent.iter().map(|(index, outcome)| {
+                let call = &calls[*index];
+                let error = outcome.as_ref().err().map(String::as_str);
+                (
+                    call.context_id.clone(),
+                    call.method.as_str(),
+                    call.args.as_str(),
+                    error,
+                )
+            }),
+        );
+        let at = chrono::Utc::now().timestamp_millis();
+        for (index, outcome) in sent {
+            self.usage.record(Execution {
+                context_id: calls[index].context_id.clone(),
+                method: calls[index].method.clone(),
+                identity: None,
+                at,
+                ok: outcome.is_ok(),
+            });
+            outcomes[index] = Some(outcome);
+        }
+
+        let items = calls
+            .into_iter()
+            .zip(outcomes)
+            .map(|(call, outcome)| {
+                let outcome = outcome.unwrap_or_else(|| Err("Call was not sent".to_string()));
+                let (result, error) = match outcome {
+                    Ok(result) => (result, None),
+                    Err(e) => (serde_json::Value::Null, Some(e)),
+                };
+                let value = serde_json::json!({
+                    "contextId": call.context_id,
+                    "method": call.method,
+                    "result": result,
+                    "error": error,
+                });
+                limits::check_json(&value, self.max_response_bytes)
+                    .map_err(limits::too_large_error)?;
+                Ok(BulkItem {
+                    input: call.input(),
+                    value,
+                    error,
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(PyBulkResult::new("execute_batch", items))
+    }
+
+    /// Execute many calls against one context, with the GIL released.
+    ///
+    /// `calls` is a list of `(method, args)` tuples, `{method, args}` dicts
+    /// (`args` a JSON string or a dict to serialize) or entries of a
+    /// previous result's `failed_inputs`. Arguments are serialized first;
+    /// then the whole fan-out runs on the client runtime without the GIL:
+    /// up to `concurrency` requests in flight (within the client's
+    /// concurrency limit), each retried per the client's `RetryConfig` and
+    /// bounded by the request timeout. Returns a `BulkResult` of
+    /// `{contextId, method, result, error}` records in call order once all
+    /// calls finished; a call that fails only fails its own record.
+    #[pyo3(signature = (context_id, calls, concurrency=jsonrpc_batch::DEFAULT_FAN_OUT))]
+    pub fn execute_many(
+        &self,
+        py: Python<'_>,
+        context_id: &str,
+        calls: Vec<Bound<'_, PyAny>>,
+        concurrency: usize,
+    ) -> PyResult<PyBulkResult> {
+        if concurrency == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "concurrency must be at least 1",
+            ));
+        }
+        let context = self.context_id(context_id)?;
+        self.ensure_not_frozen(&context)?;
+        let context_key = context.to_string();
+        let calls = calls
+            .iter()
+            .map(|call| extract_fan_out_call(&context_key, call))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let mut outcomes: Vec<Option<Result<serde_json::Value, String>>> = vec![None; calls.len()];
+        let mut prepared = Vec::new();
+        for (index, call) in calls.iter().enumerate() {
+            let request = self
+                .prepare_args(&context, &call.method, &call.args, None)
+                .map_err(|e| e.to_string())
+                .and_then(|args| {
+                    self.request_cache
+                        .get_or_build(context, &call.method, &args)
+                        .map_err(error::render_chain)
+                });
+            match request {
+                Ok(request) => prepared.push((index, request)),
+                Err(e) => outcomes[index] = Some(Err(e)),
+            }
+        }
+
+        let inner = self.inner.clone();
+        let limiter = self.limiter.clone();
+        let retry_config = self.retry.clone();
+        let retry_budget = self.retry_budget.clone();
+        let context_guards = self.context_guards.clone();
+        let serialized = self.context_guards.is_serialized(&context_key);
+        let timeout = timeouts::effective(self.request_timeout);
+        let guard_key = context_key.clone();
+        let fan_out = async move {
+            let _turn = match serialized {
+                true => Some(context_guards.acquire(&guard_key).await),
+                false => None,
+            };
+            futures_util::stream::iter(prepared)
+                .map(|(index, prepared)| {
+                    let inner = inner.clone();
+                    let limiter = limiter.clone();
+                    let retry_config = retry_config.clone();
+                    let retry_budget = retry_budget.clone();
+                    async move {
+                        let _slot = limiter.acquire().await;
+                        let send = || inner.execute_jsonrpc(prepared.request.clone());
+                        let call = retry::with_retries(&retry_config, &retry_budget, false, send);
+                        let outcome = match timeouts::bounded(timeout, call).await {
+                            Ok(Ok(response)) => {
+                                serde_json::to_value(response).map_err(|e| e.to_string())
+                            }
+                            Ok(Err(e)) => Err(error::render_chain(e)),
+                            Err(timeout) => {
+                                Err(timeouts::timed_out_message("execute_many", timeout))
+                            }
+                        };
+                        (index, outcome)
+                    }
+                })
+                .buffer_unordered(concurrency)
+                .collect::<Vec<_>>()
+                .await
+        };
+
+        let sent = self.run_released(py, "execute_many", Some(context_key.clone()), fan_out)?;
+
+        self.audit_batch(
+            "execute_many",
+            sent.iter().map(|(index, outcome)| {
+                let call = &calls[*index];
+                let error = outcome.as_ref().err().map(String::as_str);
+                (
+                    call.context_id.clone(),
+                    call.method.as_str(),
+                    call.args.as_str(),
+                    error,
+                )
+            }),
+        );
+        let at = chrono::Utc::now().timestamp_millis();
+        for (index, outcome) in sent {
+            self.usage.record(Execution {
+                context_id: context_key.clone(),
+                method: calls[index].method.clone(),
+                identity: None,
+                at,
+                ok: outcome.is_ok(),
+            });
+            outcomes[index] = Some(outcome);
+        }
+
+        let items = calls
+            .into_iter()
+            .zip(outcomes)
+            .map(|(call, outcome)| {
+                let outcome = outcome.unwrap_or_else(|| Err("Call was not sent".to_string()));
+                let (result, error) = match outcome {
+                    Ok(result) => (result, None),
+                    Err(e) => (serde_json::Value::Null, Some(e)),
+                };
+                let value = serde_json::json!({
+                    "contextId": call.context_id,
+                    "method": call.method,
+                    "result": result,
+                    "error": error,
+                });
+                limits::check_json(&value, self.max_response_bytes)
+                    .map_err(limits::too_large_error)?;
+                Ok(BulkItem {
+                    input: call.input(),
+                    value,
+                    error,
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(PyBulkResult::new("execute_many", items))
+    }
+
+    /// Subscribe to `context_id`'s events over the node's WebSocket.
+    ///
+    /// Without `callback`, returns a `Subscription` to consume with
+    /// `async for event in client.subscribe(context_id)`. With one, event
+    /// batches (lists) are passed to `callback(events)` from a background
+    /// thread until `subscription.close()`, or until the callback's object
+    /// is garbage-collected when it is a bound method held weakly (the
+    /// default; `weak` as for `on_login_state_change`). All subscriptions
+    /// of a client share one socket, which reconnects and resubscribes
+    /// after network drops. Up to `capacity` events are buffered per
+    /// subscription; later ones are dropped while it is full (see
+    /// `subscription_stats`).
+    ///
+    /// With `replay=True`, delivery is at-least-once across reconnects and
+    /// restarts: the offset of the last processed event is kept per node
+    /// and context, and the node is asked to replay the events after it.
+    /// An event counts as processed once the next one is requested, the
+    /// callback returned, or `subscription.ack()` was called.
+    ///
+    /// `filter` keeps only the events matching it, evaluated in Rust as
+    /// events arrive so rejected ones never reach Python, e.g.
+    /// `"type:StateMutation method:transfer key:balances/ -key:balances/tmp"`
+    /// (clauses must all hold, comma-separated values are alternatives,
+    /// `key:` matches by prefix, `-` negates).
+    #[pyo3(signature = (context_id, callback=None, capacity=subscriptions::DEFAULT_SUBSCRIPTION_CAPACITY, weak=None, replay=false, filter=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn subscribe(
+        &self,
+        py: Python<'_>,
+        context_id: &str,
+        callback: Option<PyObject>,
+        capacity: usize,
+        weak: Option<bool>,
+        replay: bool,
+        filter: Option<&str>,
+    ) -> PyResult<PySubscription> {
+        let context_id = self.context_id(context_id)?.to_string();
+        let filter = filter
+            .map(EventFilter::parse)
+            .transpose()
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?
+            .map(Arc::new);
+        let callback = callback
+            .map(|callback| Callback::new(py, callback, weak))
+            .transpose()?;
+        let hub = self.subscription_hub()?;
+        Ok(match callback {
+            Some(callback) => {
+                PySubscription::with_callback(&hub, &context_id, capacity, replay, filter, callback)
             }
+            None => PySubscription::stream(&hub, &context_id, capacity, replay, filter),
         })
     }
 
-    /// Execute function call via JSON-RPC
+    /// Subscription socket state: `{connected, connects, drops,
+    /// droppedEvents, filteredEvents, replayedDuplicates, contexts}`;
+    /// `None` before the first `subscribe`.
+    #[getter]
+    pub fn subscription_stats(&self, py: Python<'_>) -> Option<PyObject> {
+        let hub = self.subscriptions.get()?;
+        Some(json_to_python(py, &hub.stats()))
+    }
+
+    /// Usage of `context_id` over the last `window` seconds, for capacity
+    /// reviews.
     ///
-    /// The executor_public_key parameter is accepted for backward compatibility
-    /// but ignored — the node auto-resolves the owned identity for the context.
-    #[pyo3(signature = (context_id, method, args, executor_public_key=""))]
-    pub fn execute_function(
+    /// Reports the executions this client sent to the context (`total`,
+    /// `failed`, `perMinute` and `topMethods`), the events it received for
+    /// it over `subscribe` (`total`, `byType`, `perMinute`) and the
+    /// `activeIdentities` behind both: executors passed to
+    /// `execute_function` and identities named in events. Both sources are
+    /// kept in memory, bounded and per client; `coverage` gives the oldest
+    /// record of each still kept. With `as_dataframe=True`, returns a
+    /// pandas DataFrame of the top methods with the whole report in
+    /// `df.attrs["usage"]`.
+    #[pyo3(signature = (context_id, window=usage::DEFAULT_USAGE_WINDOW_SECS, as_dataframe=false))]
+    pub fn usage(
         &self,
+        py: Python<'_>,
         context_id: &str,
-        method: &str,
-        args: &str,
-        executor_public_key: &str,
+        window: f64,
+        as_dataframe: bool,
     ) -> PyResult<PyObject> {
-        let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
+        let context_id = self.context_id(context_id)?.to_string();
+        if !(window.is_finite() && window > 0.0) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "window must be a positive number of seconds",
+            ));
+        }
+        let to = chrono::Utc::now().timestamp_millis();
+        let from = to.saturating_sub((window * 1000.0) as i64);
+        let (executions, executions_since) = self.usage.for_context(&context_id);
+        let events = self
+            .subscriptions
+            .get()
+            .map(|hub| hub.timed_history(&context_id))
+            .unwrap_or_default();
+        let events_since = events.first().map(|(at, _)| *at);
+        let report = usage::report(
+            &context_id,
+            from,
+            to,
+            &executions,
+            executions_since,
+            &events,
+            events_since,
+        );
+        if !as_dataframe {
+            return Ok(json_to_python(py, &report));
+        }
+        let pandas = py.import_bound("pandas").map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyImportError, _>(
+                "usage(as_dataframe=True) needs pandas (pip install pandas)",
+            )
         })?;
-        // Ignored — node auto-resolves executor identity.
-        let _ = executor_public_key;
+        let frame =
+            pandas.call_method1("DataFrame", (json_to_python(py, &report["topMethods"]),))?;
+        frame
+            .getattr("attrs")?
+            .set_item("usage", json_to_python(py, &report))?;
+        Ok(frame.unbind())
+    }
 
-        Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                // Parse args as JSON
-                let args_value: serde_json::Value = serde_json::from_str(args)
-                    .map_err(|e| eyre::eyre!("Invalid JSON args: {}", e))?;
+    /// Queue an `execute_function` call in the client's offline outbox
+    /// instead of sending it; returns the entry id. Use while the node is
+    /// unreachable and call `flush_outbox()` once it is back.
+    pub fn enqueue_execute(&self, context_id: &str, method: &str, args: &str) -> PyResult<u64> {
+        let context_id = self.context_id(context_id)?;
+        serde_json::from_str::<serde_json::Value>(args).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid JSON args: {}", e))
+        })?;
+        self.ensure_not_frozen(&context_id)?;
+        let args = self.prepare_args(&context_id, method, args, None)?;
+        Ok(self
+            .outbox
+            .push(context_id, method, &args, chrono::Utc::now().timestamp()))
+    }
 
-                let execution_request = jsonrpc::ExecutionRequest::new(
-                    context_id,
-                    method.to_string(),
-                    args_value,
-                    vec![], // substitute aliases
-                );
+    /// Queued outbox entries: `[{id, contextId, method, args, queuedAt}]`
+    #[getter]
+    pub fn outbox(&self, py: Python<'_>) -> PyObject {
+        let entries: Vec<serde_json::Value> = self
+            .outbox
+            .entries()
+            .iter()
+            .map(OutboxEntry::to_json)
+            .collect();
+        json_to_python(py, &serde_json::Value::Array(entries))
+    }
 
-                let request = jsonrpc::Request::new(
-                    jsonrpc::Version::TwoPointZero,
-                    jsonrpc::RequestId::String("1".to_string()),
-                    jsonrpc::RequestPayload::Execute(execution_request),
-                );
-                inner.execute_jsonrpc(request).await
-            });
+    /// Latest quota the node reported in rate-limit headers (`RateLimit-*`,
+    /// `RateLimit` or `X-RateLimit-*`), or `None` if it never did.
+    ///
+    /// Returns `{limit, remaining, resetAt, resetIn, remainingFraction,
+    /// observedAt, current}`; `current` is false once the reported window
+    /// has reset. Readings come from the responses the client reads itself
+    /// (msgpack executions, blobs, the auth service) and are shared by the
+    /// clients of a node; execution results carry the reading taken during
+    /// the call as `quota`.
+    pub fn quota_status(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let now = chrono::Utc::now().timestamp();
+        Ok(match quota::latest(&self.connection.api_url) {
+            Some(quota) => json_to_python(py, &quota.to_json(now)),
+            None => py.None(),
+        })
+    }
 
-            match result {
-                Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
-                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                            "Failed to serialize response: {}",
-                            e
-                        ))
-                    })?;
-                    Ok(json_to_python(py, &json_data))
+    /// Executions of `context_id` (every context when omitted) this client
+    /// has not finished, oldest first: calls started and not completed
+    /// (`state` `"in_flight"`: waiting for a concurrency slot or a
+    /// serialized context's turn, or awaiting the node) and outbox entries
+    /// (`"queued"`). Records are `{id, contextId, method, executor, state,
+    /// startedAt (Unix ms), ageMs, serialized}`.
+    ///
+    /// The node does not expose its own execution queue; calls submitted by
+    /// other clients are not listed.
+    #[pyo3(signature = (context_id=None))]
+    pub fn pending_executions(
+        &self,
+        py: Python<'_>,
+        context_id: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let context_id = context_id
+            .map(|raw| self.context_id(raw).map(|id| id.to_string()))
+            .transpose()?;
+        let now = std::time::Instant::now();
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut pending: Vec<serde_json::Value> = self
+            .in_flight
+            .calls(context_id.as_deref())
+            .iter()
+            .map(|call| call.to_json(now))
+            .collect();
+        pending.extend(
+            self.outbox
+                .entries()
+                .iter()
+                .filter(|entry| {
+                    context_id
+                        .as_deref()
+                        .map_or(true, |id| entry.context_id.to_string() == id)
+                })
+                .map(|entry| {
+                    let started_at = entry.queued_at.saturating_mul(1000);
+                    serde_json::json!({
+                        "id": entry.id,
+                        "contextId": entry.context_id.to_string(),
+                        "method": entry.method,
+                        "executor": null,
+                        "state": "queued",
+                        "startedAt": started_at,
+                        "ageMs": now_ms.saturating_sub(started_at).max(0),
+                    })
+                }),
+        );
+        for record in pending.iter_mut() {
+            let serialized = record["contextId"]
+                .as_str()
+                .is_some_and(|id| self.context_guards.is_serialized(id));
+            record["serialized"] = serialized.into();
+        }
+        pending.sort_by_key(|record| record["startedAt"].as_i64().unwrap_or_default());
+        Ok(json_to_python(py, &serde_json::Value::Array(pending)))
+    }
+
+    /// Send the queued outbox entries.
+    ///
+    /// Entries for the same context go out one at a time in queue order;
+    /// different contexts drain in parallel. A context's lane stops at the
+    /// first transient failure, which stays queued with everything behind
+    /// it. Entries the node refuses are dropped from the queue and passed
+    /// to `on_conflict(entry, error)` (HTTP 409) or `on_rejected(entry,
+    /// error)` (unknown context, invalid arguments, execution errors, ...).
+    /// Returns `{delivered, conflicts, rejected, pending}`: entry records
+    /// (with `result` or `error`) and the number still queued.
+    #[pyo3(signature = (on_conflict=None, on_rejected=None))]
+    pub fn flush_outbox(
+        &self,
+        py: Python<'_>,
+        on_conflict: Option<PyObject>,
+        on_rejected: Option<PyObject>,
+    ) -> PyResult<PyObject> {
+        let lanes = outbox::lanes(self.outbox.take_all());
+        let inner = self.inner.clone();
+        let limiter = self.limiter.clone();
+        let request_cache = self.request_cache.clone();
+        let archive = self.archive.clone();
+        let context_guards = self.context_guards.clone();
+        let send = move |entry: OutboxEntry| {
+            let inner = inner.clone();
+            let limiter = limiter.clone();
+            let request_cache = request_cache.clone();
+            let context_guards = context_guards.clone();
+            let frozen = archive.is_frozen(&entry.context_id.to_string());
+            async move {
+                if frozen {
+                    eyre::bail!("Context {} is frozen", entry.context_id);
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                let _turn = context_guards.acquire(&entry.context_id.to_string()).await;
+                let prepared =
+                    request_cache.get_or_build(entry.context_id, &entry.method, &entry.args)?;
+                let _slot = limiter.acquire().await;
+                let response = inner.execute_jsonrpc(prepared.request.clone()).await?;
+                Ok(serde_json::to_value(response)?)
             }
-        })
+        };
+
+        let drained = self.run_batch("flush_outbox", async {
+            futures_util::future::join_all(
+                lanes
+                    .into_iter()
+                    .map(|lane| outbox::drain_lane(lane, &send)),
+            )
+            .await
+        })?;
+
+        self.audit_batch(
+            "flush_outbox",
+            drained
+                .iter()
+                .flat_map(|(outcomes, _)| outcomes)
+                .map(|(entry, outcome)| {
+                    let error = match outcome {
+                        DrainOutcome::Delivered(_) => None,
+                        DrainOutcome::Conflict(error)
+                        | DrainOutcome::Rejected(error)
+                        | DrainOutcome::Deferred(error) => Some(error.as_str()),
+                    };
+                    (
+                        entry.context_id.to_string(),
+                        entry.method.as_str(),
+                        entry.args.as_str(),
+                        error,
+                    )
+                }),
+        );
+        let mut delivered = Vec::new();
+        let mut conflicts = Vec::new();
+        let mut rejected = Vec::new();
+        let mut requeue = Vec::new();
+        for (outcomes, left) in drained {
+            requeue.extend(left);
+            for (entry, outcome) in outcomes {
+                let mut record = entry.to_json();
+                let (bucket, callback, error) = match outcome {
+                    DrainOutcome::Delivered(result) => {
+                        record["result"] = result;
+                        delivered.push(record);
+                        continue;
+                    }
+                    DrainOutcome::Deferred(_) => continue,
+                    DrainOutcome::Conflict(error) => (&mut conflicts, &on_conflict, error),
+                    DrainOutcome::Rejected(error) => (&mut rejected, &on_rejected, error),
+                };
+                if let Some(callback) = callback {
+                    let entry = json_to_python(py, &record);
+                    if let Err(e) = callback.call1(py, (entry, error.as_str())) {
+                        e.print(py);
+                    }
+                }
+                record["error"] = serde_json::Value::String(error);
+                bucket.push(record);
+            }
+        }
+        requeue.sort_by_key(|entry| entry.id);
+        self.outbox.requeue(requeue);
+
+        let summary = serde_json::json!({
+            "delivered": delivered,
+            "conflicts": conflicts,
+            "rejected": rejected,
+            "pending": self.outbox.len(),
+        });
+        self.to_python(py, &summary)
+    }
+
+    /// Archive a finished context: its state stays readable but this
+    /// client refuses `execute_function` and `enqueue_execute` on it (queued
+    /// outbox entries wait) until `unfreeze_context`. Returns `False` if it
+    /// was already frozen.
+    pub fn freeze_context(&self, context_id: &str) -> PyResult<bool> {
+        let context_id = self.context_id(context_id)?;
+        Ok(self.archive.freeze(&context_id.to_string()))
+    }
+
+    /// Re-enable execution on a frozen context. Returns `False` if it was
+    /// not frozen.
+    pub fn unfreeze_context(&self, context_id: &str) -> PyResult<bool> {
+        let context_id = self.context_id(context_id)?;
+        Ok(self.archive.unfreeze(&context_id.to_string()))
+    }
+
+    /// Serialize mutating calls on `context_id` client-side: with
+    /// `enabled=True`, `execute_function`, outbox flushes and application
+    /// updates on the context run one at a time in arrival order, avoiding
+    /// node-side conflicts between concurrent writers. Reads are unaffected.
+    #[pyo3(signature = (context_id, enabled=true))]
+    pub fn serialize_context(&self, context_id: &str, enabled: bool) -> PyResult<()> {
+        let context_id = self.context_id(context_id)?;
+        self.context_guards.set(&context_id.to_string(), enabled);
+        Ok(())
+    }
+
+    /// IDs of the contexts whose mutating calls are serialized
+    #[getter]
+    pub fn serialized_contexts(&self) -> Vec<String> {
+        self.context_guards.serialized()
+    }
+
+    /// IDs of the contexts frozen on this client
+    #[getter]
+    pub fn frozen_contexts(&self) -> Vec<String> {
+        self.archive.frozen()
     }
 
     /// Update context application
@@ -902,13 +6624,13 @@ impl PyClient {
         application_id: &str,
         executor_public_key: &str,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "contextId": context_id,
+            "applicationId": application_id,
+            "executorPublicKey": executor_public_key,
+        });
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
         let application_id = application_id.parse::<ApplicationId>().map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
                 "Invalid application ID '{}': {}",
@@ -916,23 +6638,30 @@ impl PyClient {
             ))
         })?;
         let executor_public_key =
-            executor_public_key
-                .parse::<identity::PublicKey>()
-                .map_err(|e| {
-                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                        "Invalid executor public key '{}': {}",
-                        executor_public_key, e
-                    ))
-                })?;
+            self.identity(executor_public_key, &context_id, "executor public key")?;
+        let context_guards = self.context_guards.clone();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let request = admin::UpdateContextApplicationRequest::new(
-                    application_id,
-                    executor_public_key,
-                );
-                inner.update_context_application(&context_id, request).await
-            });
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context(
+                "update_context_application",
+                call_context.clone(),
+                self.audited(
+                    "update_context_application",
+                    call_context,
+                    None,
+                    audit_args,
+                    async move {
+                        let _turn = context_guards.acquire(&context_id.to_string()).await;
+                        let request = admin::UpdateContextApplicationRequest::new(
+                            application_id,
+                            executor_public_key,
+                        );
+                        inner.update_context_application(&context_id, request).await
+                    },
+                ),
+            )?;
+            self.metadata_cache.invalidate(MetadataKind::Context);
 
             match result {
                 Ok(data) => {
@@ -942,12 +6671,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -957,9 +6683,9 @@ impl PyClient {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.sync_all_contexts().await });
+            let result = self.run("sync_all_contexts", async move {
+                inner.sync_all_contexts().await
+            })?;
 
             match result {
                 Ok(data) => {
@@ -969,12 +6695,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -993,11 +6716,12 @@ impl PyClient {
         let context_id = context_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context("resync_context", call_context, async move {
                 inner
                     .resync_context(&context_id, admin::ResyncContextApiRequest { force })
                     .await
-            });
+            })?;
 
             match result {
                 Ok(data) => {
@@ -1007,12 +6731,123 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
+            }
+        })
+    }
+
+    /// Sync state of `context_id` on this node, read fresh:
+    /// `{contextId, rootHash, dagHeads, peerCount, syncable}`. The node
+    /// reports no sync progress of its own, so `syncable` only says whether
+    /// it has peers to sync with; compare `rootHash` across nodes (or after
+    /// `sync_context`) to tell whether they converged.
+    pub fn get_sync_status(&self, context_id: &str) -> PyResult<PyObject> {
+        let inner = self.inner.clone();
+        let context_id = self.context_id(context_id)?;
+        let (context, peers) = self
+            .run_retrying(
+                "get_sync_status",
+                Some(context_id.to_string()),
+                true,
+                || async {
+                    let (context, peers) =
+                        tokio::try_join!(inner.get_context(&context_id), inner.get_peers_count())?;
+                    Ok::<_, eyre::Report>((
+                        serde_json::to_value(context)?,
+                        serde_json::to_value(peers)?,
+                    ))
+                },
+            )?
+            .map_err(|e| self.client_error(e))?;
+        let status = node_status::sync_status(&context_id.to_string(), &context, &peers);
+        Python::with_gil(|py| self.to_python(py, &status))
+    }
+
+    /// Proposals of `context_id` awaiting approval, `limit` from `offset`.
+    /// Wraps `POST admin-api/contexts/{context_id}/proposals`.
+    #[pyo3(signature = (context_id, offset=0, limit=100))]
+    pub fn list_proposals(
+        &self,
+        context_id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> PyResult<PyObject> {
+        let connection = self.connection.clone();
+        let context_id = self.context_id(context_id)?;
+        let path = format!("admin-api/contexts/{context_id}/proposals");
+        let body = serde_json::json!({ "offset": offset, "limit": limit });
+
+        Python::with_gil(|py| {
+            let result = self.run_retrying(
+                "list_proposals",
+                Some(context_id.to_string()),
+                true,
+                || async {
+                    connection
+                        .post::<_, serde_json::Value>(&path, body.clone())
+                        .await
+                },
+            )?;
+
+            match result {
+                Ok(json_data) => self.to_python(py, &json_data),
+                Err(e) => Err(self.client_error(e)),
+            }
+        })
+    }
+
+    /// Approve proposal `proposal_id` of `context_id` as `signer_id` (a
+    /// context identity held by this node, or its alias). Wraps
+    /// `POST admin-api/contexts/{context_id}/proposals/approve`.
+    pub fn approve_proposal(
+        &self,
+        context_id: &str,
+        proposal_id: &str,
+        signer_id: &str,
+    ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "contextId": context_id,
+            "proposalId": proposal_id,
+            "signerId": signer_id,
+        });
+        let connection = self.connection.clone();
+        let context_id = self.context_id(context_id)?;
+        let signer_id = self.identity(signer_id, &context_id, "signer ID")?;
+        let valid_id = bs58::decode(proposal_id)
+            .into_vec()
+            .is_ok_and(|bytes| bytes.len() == 32);
+        if !valid_id {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid proposal ID '{}': expected 32 base58-encoded bytes",
+                proposal_id
+            )));
+        }
+        self.ensure_not_frozen(&context_id)?;
+        let path = format!("admin-api/contexts/{context_id}/proposals/approve");
+        let body = serde_json::json!({
+            "signerId": signer_id.to_string(),
+            "proposalId": proposal_id,
+        });
+
+        Python::with_gil(|py| {
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context(
+                "approve_proposal",
+                call_context.clone(),
+                self.audited(
+                    "approve_proposal",
+                    call_context,
+                    None,
+                    audit_args,
+                    async move { connection.post::<_, serde_json::Value>(&path, body).await },
+                ),
+            )?;
+
+            match result {
+                Ok(json_data) => self.to_python(py, &json_data),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1024,34 +6859,40 @@ impl PyClient {
         alias: &str,
         public_key: &str,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "contextId": context_id,
+            "alias": alias,
+            "publicKey": public_key,
+        });
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
-        let public_key = public_key.parse::<identity::PublicKey>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid public key '{}': {}",
-                public_key, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
+        let public_key = self.identity(public_key, &context_id, "public key")?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let alias_obj = Alias::<identity::PublicKey>::from_str(alias)
-                    .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
-                let request = admin::CreateAliasRequest {
-                    alias: alias_obj,
-                    value: admin::CreateContextIdentityAlias {
-                        identity: public_key,
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context(
+                "create_context_identity_alias",
+                call_context.clone(),
+                self.audited(
+                    "create_context_identity_alias",
+                    call_context,
+                    None,
+                    audit_args,
+                    async move {
+                        let alias_obj = Alias::<identity::PublicKey>::from_str(alias)
+                            .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
+                        let request = admin::CreateAliasRequest {
+                            alias: alias_obj,
+                            value: admin::CreateContextIdentityAlias {
+                                identity: public_key,
+                            },
+                        };
+                        inner
+                            .create_context_identity_alias(&context_id, request)
+                            .await
                     },
-                };
-                inner
-                    .create_context_identity_alias(&context_id, request)
-                    .await
-            });
+                ),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1061,33 +6902,37 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     /// Create context alias
     pub fn create_context_alias(&self, alias: &str, context_id: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "alias": alias, "contextId": context_id });
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let alias_obj = Alias::<ContextId>::from_str(alias)
-                    .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
-
-                inner.create_alias(alias_obj, context_id, None).await
-            });
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context(
+                "create_context_alias",
+                call_context.clone(),
+                self.audited(
+                    "create_context_alias",
+                    call_context,
+                    None,
+                    audit_args,
+                    async move {
+                        let alias_obj = Alias::<ContextId>::from_str(alias)
+                            .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
+
+                        inner.create_alias(alias_obj, context_id, None).await
+                    },
+                ),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1097,12 +6942,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1113,6 +6955,7 @@ impl PyClient {
         alias: &str,
         application_id: &str,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "alias": alias, "applicationId": application_id });
         let inner = self.inner.clone();
         let application_id = application_id.parse::<ApplicationId>().map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -1122,12 +6965,21 @@ impl PyClient {
         })?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let alias_obj = Alias::<ApplicationId>::from_str(alias)
-                    .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
-
-                inner.create_alias(alias_obj, application_id, None).await
-            });
+            let result = self.run(
+                "create_application_alias",
+                self.audited(
+                    "create_application_alias",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        let alias_obj = Alias::<ApplicationId>::from_str(alias)
+                            .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
+
+                        inner.create_alias(alias_obj, application_id, None).await
+                    },
+                ),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1137,27 +6989,29 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     /// Delete context alias
     pub fn delete_context_alias(&self, alias: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "alias": alias });
+        self.aliases.forget(alias);
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let alias_obj = Alias::<ContextId>::from_str(alias)
-                    .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
+            let result = self.run(
+                "delete_context_alias",
+                self.audited("delete_context_alias", None, None, audit_args, async move {
+                    let alias_obj = Alias::<ContextId>::from_str(alias)
+                        .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
 
-                inner.delete_alias(alias_obj, None).await
-            });
+                    inner.delete_alias(alias_obj, None).await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1167,12 +7021,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1183,21 +7034,29 @@ impl PyClient {
         alias: &str,
         context_id: &str,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "alias": alias, "contextId": context_id });
+        self.aliases.forget(alias);
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let alias_obj = Alias::<identity::PublicKey>::from_str(alias)
-                    .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
-
-                inner.delete_alias(alias_obj, Some(context_id)).await
-            });
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context(
+                "delete_context_identity_alias",
+                call_context.clone(),
+                self.audited(
+                    "delete_context_identity_alias",
+                    call_context,
+                    None,
+                    audit_args,
+                    async move {
+                        let alias_obj = Alias::<identity::PublicKey>::from_str(alias)
+                            .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
+
+                        inner.delete_alias(alias_obj, Some(context_id)).await
+                    },
+                ),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1207,27 +7066,35 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     /// Delete application alias
     pub fn delete_application_alias(&self, alias: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "alias": alias });
+        self.aliases.forget(alias);
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let alias_obj = Alias::<ApplicationId>::from_str(alias)
-                    .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
-
-                inner.delete_alias(alias_obj, None).await
-            });
+            let result = self.run(
+                "delete_application_alias",
+                self.audited(
+                    "delete_application_alias",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        let alias_obj = Alias::<ApplicationId>::from_str(alias)
+                            .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
+
+                        inner.delete_alias(alias_obj, None).await
+                    },
+                ),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1237,12 +7104,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1252,9 +7116,9 @@ impl PyClient {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_aliases::<ContextId>(None).await });
+            let result = self.run_retrying("list_context_aliases", None, true, || async {
+                inner.list_aliases::<ContextId>(None).await
+            })?;
 
             match result {
                 Ok(data) => {
@@ -1264,12 +7128,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1277,19 +7138,20 @@ impl PyClient {
     /// List context identity aliases
     pub fn list_context_identity_aliases(&self, context_id: &str) -> PyResult<PyObject> {
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .list_aliases::<identity::PublicKey>(Some(context_id))
-                    .await
-            });
+            let call_context = Some(context_id.to_string());
+            let result = self.run_retrying(
+                "list_context_identity_aliases",
+                call_context,
+                true,
+                || async {
+                    inner
+                        .list_aliases::<identity::PublicKey>(Some(context_id))
+                        .await
+                },
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1299,12 +7161,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1314,9 +7173,9 @@ impl PyClient {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_aliases::<ApplicationId>(None).await });
+            let result = self.run_retrying("list_application_aliases", None, true, || async {
+                inner.list_aliases::<ApplicationId>(None).await
+            })?;
 
             match result {
                 Ok(data) => {
@@ -1326,12 +7185,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1341,12 +7197,12 @@ impl PyClient {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
+            let result = self.run_retrying("lookup_context_alias", None, true, || async {
                 let alias_obj = Alias::<ContextId>::from_str(alias)
                     .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
 
                 inner.lookup_alias(alias_obj, None).await
-            });
+            })?;
 
             match result {
                 Ok(data) => {
@@ -1356,12 +7212,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1373,20 +7226,21 @@ impl PyClient {
         context_id: &str,
     ) -> PyResult<PyObject> {
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let alias_obj = Alias::<identity::PublicKey>::from_str(alias)
-                    .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
-
-                inner.lookup_alias(alias_obj, Some(context_id)).await
-            });
+            let call_context = Some(context_id.to_string());
+            let result = self.run_retrying(
+                "lookup_context_identity_alias",
+                call_context,
+                true,
+                || async {
+                    let alias_obj = Alias::<identity::PublicKey>::from_str(alias)
+                        .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
+
+                    inner.lookup_alias(alias_obj, Some(context_id)).await
+                },
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1396,12 +7250,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1411,12 +7262,12 @@ impl PyClient {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
+            let result = self.run_retrying("lookup_application_alias", None, true, || async {
                 let alias_obj = Alias::<ApplicationId>::from_str(alias)
                     .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
 
                 inner.lookup_alias(alias_obj, None).await
-            });
+            })?;
 
             match result {
                 Ok(data) => {
@@ -1426,12 +7277,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1441,12 +7289,12 @@ impl PyClient {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
+            let result = self.run_retrying("resolve_context_alias", None, true, || async {
                 let alias_obj = Alias::<ContextId>::from_str(alias)
                     .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
 
                 inner.resolve_alias(alias_obj, None).await
-            });
+            })?;
 
             match result {
                 Ok(data) => {
@@ -1456,12 +7304,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1473,20 +7318,21 @@ impl PyClient {
         context_id: &str,
     ) -> PyResult<PyObject> {
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let alias_obj = Alias::<identity::PublicKey>::from_str(alias)
-                    .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
-
-                inner.resolve_alias(alias_obj, Some(context_id)).await
-            });
+            let call_context = Some(context_id.to_string());
+            let result = self.run_retrying(
+                "resolve_context_identity_alias",
+                call_context,
+                true,
+                || async {
+                    let alias_obj = Alias::<identity::PublicKey>::from_str(alias)
+                        .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
+
+                    inner.resolve_alias(alias_obj, Some(context_id)).await
+                },
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1496,12 +7342,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1511,12 +7354,12 @@ impl PyClient {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
+            let result = self.run_retrying("resolve_application_alias", None, true, || async {
                 let alias_obj = Alias::<ApplicationId>::from_str(alias)
                     .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
 
                 inner.resolve_alias(alias_obj, None).await
-            });
+            })?;
 
             match result {
                 Ok(data) => {
@@ -1526,12 +7369,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1544,26 +7384,34 @@ impl PyClient {
         value: &str,
         scope: Option<&str>,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "alias": alias,
+            "value": value,
+            "scope": scope,
+        });
         let inner = self.inner.clone();
         let alias_str = alias.to_string();
         let value_str = value.to_string();
         let _scope_str = scope.map(|s| s.to_string());
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                // This is a simplified wrapper - in practice, you'd need to know the type T
-                // For now, we'll use ContextId as a default type
-                let alias_obj = Alias::<ContextId>::from_str(&alias_str)
-                    .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
-
-                // Parse the value as ContextId
-                let value_obj = value_str
-                    .parse::<ContextId>()
-                    .map_err(|e| eyre::eyre!("Invalid value: {}", e))?;
-
-                // Create the alias
-                inner.create_alias(alias_obj, value_obj, None).await
-            });
+            let result = self.run(
+                "create_alias_generic",
+                self.audited("create_alias_generic", None, None, audit_args, async move {
+                    // This is a simplified wrapper - in practice, you'd need to know the type T
+                    // For now, we'll use ContextId as a default type
+                    let alias_obj = Alias::<ContextId>::from_str(&alias_str)
+                        .map_err(|e| eyre::eyre!("Invalid alias: {}", e))?;
+
+                    // Parse the value as ContextId
+                    let value_obj = value_str
+                        .parse::<ContextId>()
+                        .map_err(|e| eyre::eyre!("Invalid value: {}", e))?;
+
+                    // Create the alias
+                    inner.create_alias(alias_obj, value_obj, None).await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1573,15 +7421,125 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
+    /// Create alias `name` for `target`.
+    ///
+    /// `kind` is `"context"` (the default), `"application"` or
+    /// `"identity"`; identity aliases live inside a context and need
+    /// `context_id`. Once created, the alias is accepted wherever a context
+    /// id (or, given its context, an identity) is.
+    #[pyo3(signature = (name, target, kind="context", context_id=None))]
+    pub fn create_alias(
+        &self,
+        name: &str,
+        target: &str,
+        kind: &str,
+        context_id: Option<&str>,
+    ) -> PyResult<PyObject> {
+        match parse_alias_kind(kind, context_id)? {
+            AliasKind::Context => self.create_context_alias(name, target),
+            AliasKind::Application => self.create_application_alias(name, target),
+            AliasKind::Identity => {
+                self.create_context_identity_alias(context_id.unwrap_or_default(), name, target)
+            }
+        }
+    }
+
+    /// Target of alias `name` as a string, or `None` if the node has no
+    /// such alias. Answers are cached for a minute; `refresh=True` asks the
+    /// node again.
+    #[pyo3(signature = (name, kind="context", context_id=None, refresh=false))]
+    pub fn resolve_alias(
+        &self,
+        name: &str,
+        kind: &str,
+        context_id: Option<&str>,
+        refresh: bool,
+    ) -> PyResult<Option<String>> {
+        let kind = parse_alias_kind(kind, context_id)?;
+        let scope = context_id.map(|id| self.context_id(id)).transpose()?;
+        if !aliases::is_alias_name(name) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid alias '{}'",
+                name
+            )));
+        }
+        self.resolve_alias_target(kind, name, scope.as_ref(), refresh)
+    }
+
+    /// Delete alias `name`; `kind` and `context_id` as in `create_alias`.
+    #[pyo3(signature = (name, kind="context", context_id=None))]
+    pub fn delete_alias(
+        &self,
+        name: &str,
+        kind: &str,
+        context_id: Option<&str>,
+    ) -> PyResult<PyObject> {
+        match parse_alias_kind(kind, context_id)? {
+            AliasKind::Context => self.delete_context_alias(name),
+            AliasKind::Application => self.delete_application_alias(name),
+            AliasKind::Identity => {
+                self.delete_context_identity_alias(name, context_id.unwrap_or_default())
+            }
+        }
+    }
+
+    /// Aliases as `[{name, kind, target, contextId}]` records sorted by
+    /// name within each kind. Without `kind`, lists context and application
+    /// aliases, plus identity aliases of `context_id` when given. Listed
+    /// aliases are cached as if resolved.
+    #[pyo3(signature = (kind=None, context_id=None))]
+    pub fn list_aliases(&self, kind: Option<&str>, context_id: Option<&str>) -> PyResult<PyObject> {
+        let kinds = match kind {
+            Some(kind) => vec![parse_alias_kind(kind, context_id)?],
+            None => {
+                let mut kinds = vec![AliasKind::Context, AliasKind::Application];
+                if context_id.is_some() {
+                    kinds.push(AliasKind::Identity);
+                }
+                kinds
+            }
+        };
+        let scope = context_id.map(|id| self.context_id(id)).transpose()?;
+        let inner = self.inner.clone();
+
+        let mut records = Vec::new();
+        for kind in kinds {
+            let scope = scope.filter(|_| kind == AliasKind::Identity);
+            let call_context = scope.map(|id| id.to_string());
+            let result = self.run_retrying("list_aliases", call_context, true, || async {
+                let response = match kind {
+                    AliasKind::Context => {
+                        serde_json::to_value(inner.list_aliases::<ContextId>(None).await?)?
+                    }
+                    AliasKind::Application => {
+                        serde_json::to_value(inner.list_aliases::<ApplicationId>(None).await?)?
+                    }
+                    AliasKind::Identity => {
+                        serde_json::to_value(inner.list_aliases::<PublicKey>(scope).await?)?
+                    }
+                };
+                Ok::<_, eyre::Report>(response)
+            })?;
+            let response = result.map_err(|e| self.client_error(e))?;
+            for (name, target) in aliases::listed(&response) {
+                self.aliases.insert(kind, scope.as_ref(), &name, &target);
+                records.push(serde_json::json!({
+                    "name": name,
+                    "kind": kind.as_str(),
+                    "target": target,
+                    "contextId": scope.map(|id| id.to_string()),
+                }));
+            }
+        }
+        Python::with_gil(|py| self.to_python(py, &serde_json::Value::Array(records)))
+    }
+
     // ---- Namespace and Group Management ----
 
     #[pyo3(signature = (application_id, upgrade_policy=None, name=None, app_key=None))]
@@ -1592,6 +7550,12 @@ impl PyClient {
         name: Option<&str>,
         app_key: Option<&str>,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "applicationId": application_id,
+            "upgradePolicy": upgrade_policy,
+            "name": name,
+            "appKey": app_key,
+        });
         let inner = self.inner.clone();
         let application_id = application_id.parse::<ApplicationId>().map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -1610,16 +7574,19 @@ impl PyClient {
         let app_key = app_key.map(str::to_owned);
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .create_namespace(admin::CreateNamespaceApiRequest {
-                        application_id,
-                        upgrade_policy,
-                        name,
-                        app_key,
-                    })
-                    .await
-            });
+            let result = self.run(
+                "create_namespace",
+                self.audited("create_namespace", None, None, audit_args, async move {
+                    inner
+                        .create_namespace(admin::CreateNamespaceApiRequest {
+                            application_id,
+                            upgrade_policy,
+                            name,
+                            app_key,
+                        })
+                        .await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1629,12 +7596,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1644,9 +7608,9 @@ impl PyClient {
         let namespace_id = namespace_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_group_info(&namespace_id).await });
+            let result = self.run_retrying("get_namespace", None, true, || async {
+                inner.get_group_info(&namespace_id).await
+            })?;
 
             match result {
                 Ok(data) => {
@@ -1656,22 +7620,33 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    #[pyo3(signature = (namespace_id, requester=None))]
+    /// Delete a namespace with its groups and contexts (refused by
+    /// production clients unless `allow_dangerous=True`)
+    #[pyo3(signature = (namespace_id, requester=None, allow_dangerous=false))]
     pub fn delete_namespace(
         &self,
         namespace_id: &str,
         requester: Option<&str>,
+        allow_dangerous: bool,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "namespaceId": namespace_id,
+            "requester": requester,
+            "allowDangerous": allow_dangerous,
+        });
+        production::guard(
+            self.production,
+            "delete_namespace",
+            Dangerous::BulkDelete,
+            allow_dangerous,
+        )?;
         let inner = self.inner.clone();
         let namespace_id = namespace_id.to_string();
         let requester = match requester {
@@ -1685,14 +7660,17 @@ impl PyClient {
         };
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .delete_namespace(
-                        &namespace_id,
-                        admin::DeleteNamespaceApiRequest { requester },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "delete_namespace",
+                self.audited("delete_namespace", None, None, audit_args, async move {
+                    inner
+                        .delete_namespace(
+                            &namespace_id,
+                            admin::DeleteNamespaceApiRequest { requester },
+                        )
+                        .await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -1702,38 +7680,37 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    pub fn list_namespaces(&self) -> PyResult<PyObject> {
+    #[pyo3(signature = (fields=None))]
+    pub fn list_namespaces(&self, fields: Option<Vec<String>>) -> PyResult<PyObject> {
         let inner = self.inner.clone();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_namespaces().await });
+            let result = self.run(
+                "list_namespaces",
+                async move { inner.list_namespaces().await },
+            )?;
 
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if let Some(fields) = &fields {
+                        project_fields(&mut json_data, fields);
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1743,9 +7720,9 @@ impl PyClient {
         let namespace_id = namespace_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_namespace_identity(&namespace_id).await });
+            let result = self.run_retrying("get_namespace_identity", None, true, || async {
+                inner.get_namespace_identity(&namespace_id).await
+            })?;
 
             match result {
                 Ok(data) => {
@@ -1755,17 +7732,19 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    pub fn list_namespaces_for_application(&self, application_id: &str) -> PyResult<PyObject> {
+    #[pyo3(signature = (application_id, fields=None))]
+    pub fn list_namespaces_for_application(
+        &self,
+        application_id: &str,
+        fields: Option<Vec<String>>,
+    ) -> PyResult<PyObject> {
         let inner = self.inner.clone();
         let application_id = application_id.parse::<ApplicationId>().map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -1776,24 +7755,25 @@ impl PyClient {
         let application_id = application_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner.list_namespaces_for_application(&application_id).await
-            });
+            let result =
+                self.run_retrying("list_namespaces_for_application", None, true, || async {
+                    inner.list_namespaces_for_application(&application_id).await
+                })?;
 
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if let Some(fields) = &fields {
+                        project_fields(&mut json_data, fields);
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1805,33 +7785,48 @@ impl PyClient {
         recursive: Option<bool>,
         expiration_timestamp: Option<u64>,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "namespaceId": namespace_id,
+            "recursive": recursive,
+            "expirationTimestamp": expiration_timestamp,
+        });
         let inner = self.inner.clone();
         let namespace_id = namespace_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .create_namespace_invitation(
-                        &namespace_id,
-                        admin::CreateGroupInvitationApiRequest {
-                            requester: None,
-                            expiration_timestamp,
-                            recursive,
-                        },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "create_namespace_invitation",
+                self.audited(
+                    "create_namespace_invitation",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        inner
+                            .create_namespace_invitation(
+                                &namespace_id,
+                                admin::CreateGroupInvitationApiRequest {
+                                    requester: None,
+                                    expiration_timestamp,
+                                    recursive,
+                                },
+                            )
+                            .await
+                    },
+                ),
+            )?;
             match result {
-                Ok(data) => Ok(json_to_python(py, &data)),
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Ok(data) => self.to_python(py, &data),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     pub fn join_namespace(&self, namespace_id: &str, invitation_json: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "namespaceId": namespace_id,
+            "invitationJson": invitation_json,
+        });
         let inner = self.inner.clone();
         let namespace_id = namespace_id.to_string();
         let invitation: calimero_context_config::types::SignedGroupOpenInvitation =
@@ -1843,17 +7838,20 @@ impl PyClient {
             })?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .join_namespace(
-                        &namespace_id,
-                        admin::JoinGroupApiRequest {
-                            invitation,
-                            group_name: None,
-                        },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "join_namespace",
+                self.audited("join_namespace", None, None, audit_args, async move {
+                    inner
+                        .join_namespace(
+                            &namespace_id,
+                            admin::JoinGroupApiRequest {
+                                invitation,
+                                group_name: None,
+                            },
+                        )
+                        .await
+                }),
+            )?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -1862,38 +7860,40 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    pub fn list_namespace_groups(&self, namespace_id: &str) -> PyResult<PyObject> {
+    #[pyo3(signature = (namespace_id, fields=None))]
+    pub fn list_namespace_groups(
+        &self,
+        namespace_id: &str,
+        fields: Option<Vec<String>>,
+    ) -> PyResult<PyObject> {
         let inner = self.inner.clone();
         let namespace_id = namespace_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_namespace_groups(&namespace_id).await });
+            let result = self.run_retrying("list_namespace_groups", None, true, || async {
+                inner.list_namespace_groups(&namespace_id).await
+            })?;
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if let Some(fields) = &fields {
+                        project_fields(&mut json_data, fields);
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1904,22 +7904,32 @@ impl PyClient {
         namespace_id: &str,
         group_name: Option<&str>,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "namespaceId": namespace_id,
+            "groupName": group_name,
+        });
         let inner = self.inner.clone();
         let namespace_id = namespace_id.to_string();
         let group_name = group_name.map(|s| s.to_string());
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .create_group_in_namespace(&namespace_id, group_name)
-                    .await
-            });
+            let result = self.run(
+                "create_group_in_namespace",
+                self.audited(
+                    "create_group_in_namespace",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        inner
+                            .create_group_in_namespace(&namespace_id, group_name)
+                            .await
+                    },
+                ),
+            )?;
             match result {
-                Ok(data) => Ok(json_to_python(py, &data)),
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Ok(data) => self.to_python(py, &data),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1929,22 +7939,26 @@ impl PyClient {
     /// Returns `{ "reparented": bool }` (false on idempotent no-op when
     /// `group_id` already had `new_parent_id` as its parent).
     pub fn reparent_group(&self, group_id: &str, new_parent_id: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "groupId": group_id, "newParentId": new_parent_id });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let new_parent_id = new_parent_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .reparent_group(
-                        &group_id,
-                        admin::ReparentGroupApiRequest {
-                            new_parent_id,
-                            requester: None,
-                        },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "reparent_group",
+                self.audited("reparent_group", None, None, audit_args, async move {
+                    inner
+                        .reparent_group(
+                            &group_id,
+                            admin::ReparentGroupApiRequest {
+                                new_parent_id,
+                                requester: None,
+                            },
+                        )
+                        .await
+                }),
+            )?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -1953,38 +7967,40 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    pub fn list_subgroups(&self, group_id: &str) -> PyResult<PyObject> {
+    #[pyo3(signature = (group_id, fields=None))]
+    pub fn list_subgroups(
+        &self,
+        group_id: &str,
+        fields: Option<Vec<String>>,
+    ) -> PyResult<PyObject> {
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_subgroups(&group_id).await });
+            let result = self.run_retrying("list_subgroups", None, true, || async {
+                inner.list_subgroups(&group_id).await
+            })?;
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if let Some(fields) = &fields {
+                        project_fields(&mut json_data, fields);
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -1994,9 +8010,9 @@ impl PyClient {
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_group_info(&group_id).await });
+            let result = self.run_retrying("get_group_info", None, true, || async {
+                inner.get_group_info(&group_id).await
+            })?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2005,19 +8021,33 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    /// Delete a group
-    #[pyo3(signature = (group_id, requester=None))]
-    pub fn delete_group(&self, group_id: &str, requester: Option<&str>) -> PyResult<PyObject> {
+    /// Delete a group (refused by production clients unless
+    /// `allow_dangerous=True`)
+    #[pyo3(signature = (group_id, requester=None, allow_dangerous=false))]
+    pub fn delete_group(
+        &self,
+        group_id: &str,
+        requester: Option<&str>,
+        allow_dangerous: bool,
+    ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "groupId": group_id,
+            "requester": requester,
+            "allowDangerous": allow_dangerous,
+        });
+        production::guard(
+            self.production,
+            "delete_group",
+            Dangerous::BulkDelete,
+            allow_dangerous,
+        )?;
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let requester = match requester {
@@ -2030,10 +8060,13 @@ impl PyClient {
             None => None,
         };
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let request = admin::DeleteGroupApiRequest { requester };
-                inner.delete_group(&group_id, request).await
-            });
+            let result = self.run(
+                "delete_group",
+                self.audited("delete_group", None, None, audit_args, async move {
+                    let request = admin::DeleteGroupApiRequest { requester };
+                    inner.delete_group(&group_id, request).await
+                }),
+            )?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2042,30 +8075,148 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    /// Join a context (via group membership, context_id in path)
+    /// Invite to a context; returns the invitation payload string for
+    /// `join_context` on the invitee's node.
+    ///
+    /// With `invitee_id` that identity is added to the context's group as a
+    /// member; without it the node signs an open invitation to the group
+    /// (valid until `expiration_timestamp`, Unix seconds) that the payload
+    /// carries. `inviter_id` is the member identity making the change, the
+    /// node's own when omitted. `group_id` defaults to the group the node
+    /// reports for the context; pass the namespace id when the context sits
+    /// in a subgroup and the invitee is not a member of its namespace yet.
+    #[pyo3(signature = (context_id, inviter_id=None, invitee_id=None, group_id=None, expiration_timestamp=None))]
+    pub fn invite(
+        &self,
+        context_id: &str,
+        inviter_id: Option<&str>,
+        invitee_id: Option<&str>,
+        group_id: Option<&str>,
+        expiration_timestamp: Option<u64>,
+    ) -> PyResult<String> {
+        let inner = self.inner.clone();
+        let context_id = self.context_id(context_id)?;
+        let public_key = |role: &str, key: Option<&str>| -> PyResult<Option<PublicKey>> {
+            key.map(|key| {
+                key.parse::<PublicKey>().map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Invalid {} public key '{}': {}",
+                        role, key, e
+                    ))
+                })
+            })
+            .transpose()
+        };
+        let inviter = public_key("inviter", inviter_id)?;
+        let invitee = public_key("invitee", invitee_id)?;
+
+        let group_id = match group_id {
+            Some(group_id) => group_id.to_string(),
+            None => {
+                let key = (MetadataKind::Context, Some(context_id.to_string()));
+                let record = self.read_metadata("get_context", key, false, || async {
+                    inner.get_context(&context_id).await
+                })?;
+                invitation::group_of(&record).ok_or_else(|| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Context {} has no group to invite to; pass group_id",
+                        context_id
+                    ))
+                })?
+            }
+        };
+
+        let call_context = Some(context_id.to_string());
+        let signed = match invitee {
+            Some(invitee) => {
+                let group = group_id.clone();
+                self.run_in_context("invite", call_context, async move {
+                    let request = admin::AddGroupMembersApiRequest {
+                        members: vec![admin::GroupMemberApiInput {
+                            identity: invitee,
+                            role: GroupMemberRole::Member,
+                        }],
+                        requester: inviter,
+                    };
+                    inner.add_group_members(&group, request).await.map(|_| ())
+                })?
+                .map_err(|e| self.client_error(e))?;
+                None
+            }
+            None => {
+                let group = group_id.clone();
+                let response = self
+                    .run_in_context("invite", call_context, async move {
+                        inner
+                            .create_namespace_invitation(
+                                &group,
+                                admin::CreateGroupInvitationApiRequest {
+                                    requester: inviter,
+                                    expiration_timestamp,
+                                    recursive: Some(true),
+                                },
+                            )
+                            .await
+                    })?
+                    .map_err(|e| self.client_error(e))?;
+                Some(invitation::signed_invitation(&response))
+            }
+        };
+
+        Invitation {
+            version: invitation::INVITATION_VERSION,
+            context_id: context_id.to_string(),
+            group_id,
+            inviter: inviter_id.map(str::to_string),
+            invitee: invitee_id.map(str::to_string),
+            invitation: signed,
+        }
+        .encode()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{:#}", e)))
+    }
+
+    /// Join a context (via group membership, context_id in path).
+    ///
+    /// Also takes an invitation payload from `invite()`: its group is
+    /// joined first when it carries a signed invitation, then the context.
     pub fn join_context(&self, context_id: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "contextId": context_id });
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = if Invitation::is_payload(context_id) {
+            let invitation = Invitation::decode(context_id)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e)))?;
+            if let Some(signed) = &invitation.invitation {
+                let signed = serde_json::to_string(signed).map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                        "Failed to serialize invitation: {}",
+                        e
+                    ))
+                })?;
+                self.join_namespace(&invitation.group_id, &signed)?;
+            }
+            invitation.context_id
+        } else {
+            context_id.to_string()
+        };
+        let context_id = self.context_id(context_id)?;
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let cid_str = context_id.to_string();
-                inner.join_context(&cid_str).await
-            });
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context(
+                "join_context",
+                call_context.clone(),
+                self.audited("join_context", call_context, None, audit_args, async move {
+                    let cid_str = context_id.to_string();
+                    inner.join_context(&cid_str).await
+                }),
+            )?;
+            self.metadata_cache.invalidate(MetadataKind::Context);
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2074,12 +8225,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2096,12 +8244,20 @@ impl PyClient {
     /// if the caller has no inheritance path, 404 if the subgroup is
     /// not visible to this node.
     pub fn join_subgroup_inheritance(&self, group_id: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "groupId": group_id });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.join_subgroup_inheritance(&group_id).await });
+            let result = self.run(
+                "join_subgroup_inheritance",
+                self.audited(
+                    "join_subgroup_inheritance",
+                    None,
+                    None,
+                    audit_args,
+                    async move { inner.join_subgroup_inheritance(&group_id).await },
+                ),
+            )?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2110,12 +8266,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2124,18 +8277,26 @@ impl PyClient {
     /// Stops sync, disarms auto-follow. Reversible by calling
     /// `join_context` again.
     pub fn leave_context(&self, context_id: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "contextId": context_id });
         let inner = self.inner.clone();
-        let context_id = context_id.parse::<ContextId>().map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                "Invalid context ID '{}': {}",
-                context_id, e
-            ))
-        })?;
+        let context_id = self.context_id(context_id)?;
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let cid_str = context_id.to_string();
-                inner.leave_context(&cid_str).await
-            });
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context(
+                "leave_context",
+                call_context.clone(),
+                self.audited(
+                    "leave_context",
+                    call_context,
+                    None,
+                    audit_args,
+                    async move {
+                        let cid_str = context_id.to_string();
+                        inner.leave_context(&cid_str).await
+                    },
+                ),
+            )?;
+            self.metadata_cache.invalidate(MetadataKind::Context);
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2144,12 +8305,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2161,9 +8319,10 @@ impl PyClient {
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.leave_group(&group_id).await });
+            let result = self.run(
+                "leave_group",
+                async move { inner.leave_group(&group_id).await },
+            )?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2172,12 +8331,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2187,12 +8343,16 @@ impl PyClient {
     /// with `MustTransferOwnership` if the leaver owns any group in
     /// the subtree.
     pub fn leave_namespace(&self, namespace_id: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "namespaceId": namespace_id });
         let inner = self.inner.clone();
         let namespace_id = namespace_id.to_string();
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.leave_namespace(&namespace_id).await });
+            let result = self.run(
+                "leave_namespace",
+                self.audited("leave_namespace", None, None, audit_args, async move {
+                    inner.leave_namespace(&namespace_id).await
+                }),
+            )?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2201,70 +8361,78 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     /// List members of a group
-    pub fn list_group_members(&self, group_id: &str) -> PyResult<PyObject> {
+    #[pyo3(signature = (group_id, fields=None))]
+    pub fn list_group_members(
+        &self,
+        group_id: &str,
+        fields: Option<Vec<String>>,
+    ) -> PyResult<PyObject> {
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_group_members(&group_id).await });
+            let result = self.run_retrying("list_group_members", None, true, || async {
+                inner.list_group_members(&group_id).await
+            })?;
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if let Some(fields) = &fields {
+                        project_fields(&mut json_data, fields);
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     /// List contexts in a group
-    pub fn list_group_contexts(&self, group_id: &str) -> PyResult<PyObject> {
+    #[pyo3(signature = (group_id, fields=None))]
+    pub fn list_group_contexts(
+        &self,
+        group_id: &str,
+        fields: Option<Vec<String>>,
+    ) -> PyResult<PyObject> {
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.list_group_contexts(&group_id).await });
+            let result = self.run_retrying("list_group_contexts", None, true, || async {
+                inner.list_group_contexts(&group_id).await
+            })?;
             match result {
                 Ok(data) => {
-                    let json_data = serde_json::to_value(data).map_err(|e| {
+                    let mut json_data = serde_json::to_value(data).map_err(|e| {
                         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                             "Failed to serialize response: {}",
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    if let Some(fields) = &fields {
+                        project_fields(&mut json_data, fields);
+                    }
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     /// Add members to a group
     pub fn add_group_members(&self, group_id: &str, members_json: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "groupId": group_id, "membersJson": members_json });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let members: Vec<serde_json::Value> = serde_json::from_str(members_json).map_err(|e| {
@@ -2275,25 +8443,31 @@ impl PyClient {
             .map(|m| {
                 let identity_str = m.get("identity").and_then(|v| v.as_str()).unwrap_or("");
                 let role_str = m.get("role").and_then(|v| v.as_str()).unwrap_or("Member");
-                let identity = identity_str
-                    .parse::<identity::PublicKey>()
-                    .expect("invalid identity");
+                let identity = identity_str.parse::<identity::PublicKey>().map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Invalid member identity '{}': {}",
+                        identity_str, e
+                    ))
+                })?;
                 let role = match role_str {
                     "Admin" => GroupMemberRole::Admin,
                     "ReadOnly" => GroupMemberRole::ReadOnly,
                     _ => GroupMemberRole::Member,
                 };
-                admin::GroupMemberApiInput { identity, role }
+                Ok(admin::GroupMemberApiInput { identity, role })
             })
-            .collect();
+            .collect::<PyResult<_>>()?;
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let request = admin::AddGroupMembersApiRequest {
-                    members: api_members,
-                    requester: None,
-                };
-                inner.add_group_members(&group_id, request).await
-            });
+            let result = self.run(
+                "add_group_members",
+                self.audited("add_group_members", None, None, audit_args, async move {
+                    let request = admin::AddGroupMembersApiRequest {
+                        members: api_members,
+                        requester: None,
+                    };
+                    inner.add_group_members(&group_id, request).await
+                }),
+            )?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2302,18 +8476,33 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    /// Remove members from a group
-    pub fn remove_group_members(&self, group_id: &str, members_json: &str) -> PyResult<PyObject> {
+    /// Remove members from a group (refused by production clients unless
+    /// `allow_dangerous=True`)
+    #[pyo3(signature = (group_id, members_json, allow_dangerous=false))]
+    pub fn remove_group_members(
+        &self,
+        group_id: &str,
+        members_json: &str,
+        allow_dangerous: bool,
+    ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "groupId": group_id,
+            "membersJson": members_json,
+            "allowDangerous": allow_dangerous,
+        });
+        production::guard(
+            self.production,
+            "remove_group_members",
+            Dangerous::BulkDelete,
+            allow_dangerous,
+        )?;
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let member_strs: Vec<String> = serde_json::from_str(members_json).map_err(|e| {
@@ -2322,18 +8511,25 @@ impl PyClient {
         let members: Vec<identity::PublicKey> = member_strs
             .iter()
             .map(|s| {
-                s.parse::<identity::PublicKey>()
-                    .expect("invalid public key")
+                s.parse::<identity::PublicKey>().map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Invalid member public key '{}': {}",
+                        s, e
+                    ))
+                })
             })
-            .collect();
+            .collect::<PyResult<_>>()?;
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let request = admin::RemoveGroupMembersApiRequest {
-                    members,
-                    requester: None,
-                };
-                inner.remove_group_members(&group_id, request).await
-            });
+            let result = self.run(
+                "remove_group_members",
+                self.audited("remove_group_members", None, None, audit_args, async move {
+                    let request = admin::RemoveGroupMembersApiRequest {
+                        members,
+                        requester: None,
+                    };
+                    inner.remove_group_members(&group_id, request).await
+                }),
+            )?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2342,12 +8538,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2359,19 +8552,33 @@ impl PyClient {
         member_id: &str,
         capabilities: u32,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "groupId": group_id,
+            "memberId": member_id,
+            "capabilities": capabilities,
+        });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let member_id = member_id.to_string();
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let request = admin::SetMemberCapabilitiesApiRequest {
-                    capabilities,
-                    requester: None,
-                };
-                inner
-                    .set_member_capabilities(&group_id, &member_id, request)
-                    .await
-            });
+            let result = self.run(
+                "set_member_capabilities",
+                self.audited(
+                    "set_member_capabilities",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        let request = admin::SetMemberCapabilitiesApiRequest {
+                            capabilities,
+                            requester: None,
+                        };
+                        inner
+                            .set_member_capabilities(&group_id, &member_id, request)
+                            .await
+                    },
+                ),
+            )?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2380,12 +8587,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2405,6 +8609,13 @@ impl PyClient {
         auto_follow_subgroups: bool,
         requester: Option<&str>,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "groupId": group_id,
+            "memberId": member_id,
+            "autoFollowContexts": auto_follow_contexts,
+            "autoFollowSubgroups": auto_follow_subgroups,
+            "requester": requester,
+        });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let member_id = member_id.to_string();
@@ -2418,16 +8629,25 @@ impl PyClient {
             None => None,
         };
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                let request = admin::SetMemberAutoFollowApiRequest {
-                    auto_follow_contexts,
-                    auto_follow_subgroups,
-                    requester,
-                };
-                inner
-                    .set_member_auto_follow(&group_id, &member_id, request)
-                    .await
-            });
+            let result = self.run(
+                "set_member_auto_follow",
+                self.audited(
+                    "set_member_auto_follow",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        let request = admin::SetMemberAutoFollowApiRequest {
+                            auto_follow_contexts,
+                            auto_follow_subgroups,
+                            requester,
+                        };
+                        inner
+                            .set_member_auto_follow(&group_id, &member_id, request)
+                            .await
+                    },
+                ),
+            )?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2436,12 +8656,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2452,9 +8669,9 @@ impl PyClient {
         let group_id = group_id.to_string();
         let member_id = member_id.to_string();
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
+            let result = self.run_retrying("get_member_capabilities", None, true, || async {
                 inner.get_member_capabilities(&group_id, &member_id).await
-            });
+            })?;
             match result {
                 Ok(data) => {
                     let json_data = serde_json::to_value(data).map_err(|e| {
@@ -2463,37 +8680,104 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
+    /// Grant `capability` (a `Capability`, its name or a mask of bits) to
+    /// `member` in the group owning `context_id`, keeping the member's
+    /// other capabilities. Returns `{contextId, groupId, member,
+    /// capabilities, mask, changed}` with the capabilities held afterwards.
+    pub fn grant(
+        &self,
+        context_id: &str,
+        member: &str,
+        capability: &Bound<'_, PyAny>,
+    ) -> PyResult<PyObject> {
+        let bits = capabilities::mask_of(capability)?;
+        self.change_capabilities("grant", context_id, member, |mask| mask | bits)
+    }
+
+    /// Revoke `capability` from `member` in the group owning `context_id`;
+    /// see `grant()`.
+    pub fn revoke(
+        &self,
+        context_id: &str,
+        member: &str,
+        capability: &Bound<'_, PyAny>,
+    ) -> PyResult<PyObject> {
+        let bits = capabilities::mask_of(capability)?;
+        self.change_capabilities("revoke", context_id, member, |mask| mask & !bits)
+    }
+
+    /// Capabilities of every member of the group owning `context_id`, as
+    /// `{member, capabilities, mask}` records sorted by member.
+    pub fn list_capabilities(&self, context_id: &str) -> PyResult<PyObject> {
+        let context_id = self.context_id(context_id)?;
+        let group_id = self.capability_group(&context_id)?;
+        let inner = self.inner.clone();
+        let result = self.run_in_context(
+            "list_capabilities",
+            Some(context_id.to_string()),
+            async move {
+                let members = serde_json::to_value(inner.list_group_members(&group_id).await?)?;
+                let mut masks = Vec::new();
+                for member in capabilities::member_keys(&members) {
+                    let response = inner.get_member_capabilities(&group_id, &member).await?;
+                    let mask = capabilities::mask_from_response(&serde_json::to_value(response)?);
+                    masks.push((member, mask.unwrap_or_default()));
+                }
+                masks.sort();
+                Ok::<_, eyre::Report>(masks)
+            },
+        )?;
+        let masks = result.map_err(|e| self.client_error(e))?;
+        Python::with_gil(|py| {
+            let records = masks
+                .iter()
+                .map(|(member, mask)| capabilities::member_record(py, member, *mask))
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(PyList::new_bound(py, records).into_any().unbind())
+        })
+    }
+
     pub fn update_group_settings(
         &self,
         group_id: &str,
         upgrade_policy: &str,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "groupId": group_id,
+            "upgradePolicy": upgrade_policy,
+        });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let upgrade_policy = parse_upgrade_policy(upgrade_policy)?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .update_group_settings(
-                        &group_id,
-                        admin::UpdateGroupSettingsApiRequest {
-                            requester: None,
-                            upgrade_policy,
-                        },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "update_group_settings",
+                self.audited(
+                    "update_group_settings",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        inner
+                            .update_group_settings(
+                                &group_id,
+                                admin::UpdateGroupSettingsApiRequest {
+                                    requester: None,
+                                    upgrade_policy,
+                                },
+                            )
+                            .await
+                    },
+                ),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -2503,17 +8787,15 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     pub fn set_group_metadata(&self, group_id: &str, body_json: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "groupId": group_id, "bodyJson": body_json });
         let connection = self.connection.clone();
         let group_id = group_id.to_string();
         let req: admin::SetMetadataApiRequest = serde_json::from_str(body_json).map_err(|e| {
@@ -2521,14 +8803,17 @@ impl PyClient {
         })?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                connection
-                    .put_json::<_, admin::SetMetadataApiResponse>(
-                        &format!("admin-api/groups/{group_id}/metadata"),
-                        req,
-                    )
-                    .await
-            });
+            let result = self.run(
+                "set_group_metadata",
+                self.audited("set_group_metadata", None, None, audit_args, async move {
+                    connection
+                        .put_json::<_, admin::SetMetadataApiResponse>(
+                            &format!("admin-api/groups/{group_id}/metadata"),
+                            req,
+                        )
+                        .await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -2538,12 +8823,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2554,6 +8836,11 @@ impl PyClient {
         member_id: &str,
         body_json: &str,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "groupId": group_id,
+            "memberId": member_id,
+            "bodyJson": body_json,
+        });
         let connection = self.connection.clone();
         let group_id = group_id.to_string();
         let member_id = member_id.to_string();
@@ -2562,14 +8849,17 @@ impl PyClient {
         })?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                connection
-                    .put_json::<_, admin::SetMetadataApiResponse>(
-                        &format!("admin-api/groups/{group_id}/members/{member_id}/metadata"),
-                        req,
-                    )
-                    .await
-            });
+            let result = self.run(
+                "set_member_metadata",
+                self.audited("set_member_metadata", None, None, audit_args, async move {
+                    connection
+                        .put_json::<_, admin::SetMetadataApiResponse>(
+                            &format!("admin-api/groups/{group_id}/members/{member_id}/metadata"),
+                            req,
+                        )
+                        .await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -2579,12 +8869,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2595,6 +8882,11 @@ impl PyClient {
         context_id: &str,
         body_json: &str,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "groupId": group_id,
+            "contextId": context_id,
+            "bodyJson": body_json,
+        });
         let connection = self.connection.clone();
         let group_id = group_id.to_string();
         let context_id = context_id.to_string();
@@ -2603,14 +8895,26 @@ impl PyClient {
         })?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                connection
+            let call_context = Some(context_id.to_string());
+            let result =
+                self.run_in_context(
+                    "set_context_metadata",
+                    call_context.clone(),
+                    self.audited(
+                        "set_context_metadata",
+                        call_context,
+                        None,
+                        audit_args,
+                        async move {
+                            connection
                     .put_json::<_, admin::SetMetadataApiResponse>(
                         &format!("admin-api/groups/{group_id}/contexts/{context_id}/metadata"),
                         req,
                     )
                     .await
-            });
+                        },
+                    ),
+                )?;
 
             match result {
                 Ok(data) => {
@@ -2620,12 +8924,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2635,13 +8936,13 @@ impl PyClient {
         let group_id = group_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
+            let result = self.run_retrying("get_group_metadata", None, true, || async {
                 connection
                     .get::<admin::GetMetadataApiResponse>(&format!(
                         "admin-api/groups/{group_id}/metadata"
                     ))
                     .await
-            });
+            })?;
 
             match result {
                 Ok(data) => {
@@ -2651,12 +8952,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2667,13 +8965,13 @@ impl PyClient {
         let member_id = member_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
+            let result = self.run_retrying("get_member_metadata", None, true, || async {
                 connection
                     .get::<admin::GetMetadataApiResponse>(&format!(
                         "admin-api/groups/{group_id}/members/{member_id}/metadata"
                     ))
                     .await
-            });
+            })?;
 
             match result {
                 Ok(data) => {
@@ -2683,12 +8981,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2699,13 +8994,15 @@ impl PyClient {
         let context_id = context_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                connection
-                    .get::<admin::GetMetadataApiResponse>(&format!(
-                        "admin-api/groups/{group_id}/contexts/{context_id}/metadata"
-                    ))
-                    .await
-            });
+            let call_context = Some(context_id.to_string());
+            let result =
+                self.run_retrying("get_context_metadata", call_context, true, || async {
+                    connection
+                        .get::<admin::GetMetadataApiResponse>(&format!(
+                            "admin-api/groups/{group_id}/contexts/{context_id}/metadata"
+                        ))
+                        .await
+                })?;
 
             match result {
                 Ok(data) => {
@@ -2715,12 +9012,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2731,24 +9025,32 @@ impl PyClient {
         member_id: &str,
         role: &str,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "groupId": group_id,
+            "memberId": member_id,
+            "role": role,
+        });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let member_id = member_id.to_string();
         let role = parse_group_member_role(role)?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .update_member_role(
-                        &group_id,
-                        &member_id,
-                        admin::UpdateMemberRoleApiRequest {
-                            role,
-                            requester: None,
-                        },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "update_member_role",
+                self.audited("update_member_role", None, None, audit_args, async move {
+                    inner
+                        .update_member_role(
+                            &group_id,
+                            &member_id,
+                            admin::UpdateMemberRoleApiRequest {
+                                role,
+                                requester: None,
+                            },
+                        )
+                        .await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -2758,12 +9060,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2773,21 +9072,31 @@ impl PyClient {
         group_id: &str,
         capabilities: u32,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "groupId": group_id, "capabilities": capabilities });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .set_default_capabilities(
-                        &group_id,
-                        admin::SetDefaultCapabilitiesApiRequest {
-                            default_capabilities: capabilities,
-                            requester: None,
-                        },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "set_default_capabilities",
+                self.audited(
+                    "set_default_capabilities",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        inner
+                            .set_default_capabilities(
+                                &group_id,
+                                admin::SetDefaultCapabilitiesApiRequest {
+                                    default_capabilities: capabilities,
+                                    requester: None,
+                                },
+                            )
+                            .await
+                    },
+                ),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -2797,33 +9106,40 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     pub fn set_subgroup_visibility(&self, group_id: &str, visibility: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "groupId": group_id, "visibility": visibility });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let visibility = visibility.to_ascii_lowercase();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .set_subgroup_visibility(
-                        &group_id,
-                        admin::SetSubgroupVisibilityApiRequest {
-                            subgroup_visibility: visibility,
-                            requester: None,
-                        },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "set_subgroup_visibility",
+                self.audited(
+                    "set_subgroup_visibility",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        inner
+                            .set_subgroup_visibility(
+                                &group_id,
+                                admin::SetSubgroupVisibilityApiRequest {
+                                    subgroup_visibility: visibility,
+                                    requester: None,
+                                },
+                            )
+                            .await
+                    },
+                ),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -2833,35 +9149,23 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
-    /// Deprecated alias for [`Self::set_subgroup_visibility`]. Kept so older
-    /// merobox releases that still emit `set_default_visibility` keep working
-    /// while the wider ecosystem rolls forward to the renamed surface (issue
-    /// calimero-network/core#2256). New callers should use
-    /// `set_subgroup_visibility` directly.
-    pub fn set_default_visibility(&self, group_id: &str, visibility: &str) -> PyResult<PyObject> {
-        self.set_subgroup_visibility(group_id, visibility)
-    }
-
     pub fn sync_group(&self, group_id: &str) -> PyResult<PyObject> {
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
+            let result = self.run("sync_group", async move {
                 inner
                     .sync_group(&group_id, admin::SyncGroupApiRequest { requester: None })
                     .await
-            });
+            })?;
 
             match result {
                 Ok(data) => {
@@ -2871,12 +9175,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2886,19 +9187,29 @@ impl PyClient {
         group_id: &str,
         signing_key: &str,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "groupId": group_id });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let signing_key = signing_key.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .register_group_signing_key(
-                        &group_id,
-                        admin::RegisterGroupSigningKeyApiRequest { signing_key },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "register_group_signing_key",
+                self.audited(
+                    "register_group_signing_key",
+                    None,
+                    None,
+                    audit_args,
+                    async move {
+                        inner
+                            .register_group_signing_key(
+                                &group_id,
+                                admin::RegisterGroupSigningKeyApiRequest { signing_key },
+                            )
+                            .await
+                    },
+                ),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -2908,12 +9219,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2936,6 +9244,11 @@ impl PyClient {
         target_application_id: &str,
         cascade: bool,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({
+            "groupId": group_id,
+            "targetApplicationId": target_application_id,
+            "cascade": cascade,
+        });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let target_application_id =
@@ -2949,18 +9262,21 @@ impl PyClient {
                 })?;
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .upgrade_group(
-                        &group_id,
-                        admin::UpgradeGroupApiRequest {
-                            target_application_id,
-                            requester: None,
-                            cascade,
-                        },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "upgrade_group",
+                self.audited("upgrade_group", None, None, audit_args, async move {
+                    inner
+                        .upgrade_group(
+                            &group_id,
+                            admin::UpgradeGroupApiRequest {
+                                target_application_id,
+                                requester: None,
+                                cascade,
+                            },
+                        )
+                        .await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -2970,12 +9286,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -2985,9 +9298,9 @@ impl PyClient {
         let group_id = group_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_group_upgrade_status(&group_id).await });
+            let result = self.run_retrying("get_group_upgrade_status", None, true, || async {
+                inner.get_group_upgrade_status(&group_id).await
+            })?;
 
             match result {
                 Ok(data) => {
@@ -2997,12 +9310,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -3017,9 +9327,9 @@ impl PyClient {
         let namespace_id = namespace_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_cascade_status(&namespace_id).await });
+            let result = self.run_retrying("get_cascade_status", None, true, || async {
+                inner.get_cascade_status(&namespace_id).await
+            })?;
 
             match result {
                 Ok(data) => {
@@ -3029,12 +9339,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -3054,9 +9361,9 @@ impl PyClient {
         let namespace_id = namespace_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get_migration_status(&namespace_id).await });
+            let result = self.run_retrying("get_migration_status", None, true, || async {
+                inner.get_migration_status(&namespace_id).await
+            })?;
 
             match result {
                 Ok(data) => {
@@ -3066,12 +9373,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -3084,13 +9388,17 @@ impl PyClient {
     /// pending is a no-op). Returns `{namespaceId, aborted}`. Wraps
     /// `POST admin-api/groups/{namespace_id}/migration/abort`.
     pub fn abort_migration(&self, namespace_id: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "namespaceId": namespace_id });
         let inner = self.inner.clone();
         let namespace_id = namespace_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.abort_migration(&namespace_id).await });
+            let result = self.run(
+                "abort_migration",
+                self.audited("abort_migration", None, None, audit_args, async move {
+                    inner.abort_migration(&namespace_id).await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -3100,29 +9408,30 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
 
     pub fn retry_group_upgrade(&self, group_id: &str) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "groupId": group_id });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .retry_group_upgrade(
-                        &group_id,
-                        admin::RetryGroupUpgradeApiRequest { requester: None },
-                    )
-                    .await
-            });
+            let result = self.run(
+                "retry_group_upgrade",
+                self.audited("retry_group_upgrade", None, None, audit_args, async move {
+                    inner
+                        .retry_group_upgrade(
+                            &group_id,
+                            admin::RetryGroupUpgradeApiRequest { requester: None },
+                        )
+                        .await
+                }),
+            )?;
 
             match result {
                 Ok(data) => {
@@ -3132,12 +9441,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -3147,20 +9453,33 @@ impl PyClient {
         group_id: &str,
         context_id: &str,
     ) -> PyResult<PyObject> {
+        let audit_args = serde_json::json!({ "groupId": group_id, "contextId": context_id });
         let inner = self.inner.clone();
         let group_id = group_id.to_string();
         let context_id = context_id.to_string();
 
         Python::with_gil(|py| {
-            let result = self.runtime.block_on(async move {
-                inner
-                    .detach_context_from_group(
-                        &group_id,
-                        &context_id,
-                        admin::DetachContextFromGroupApiRequest { requester: None },
-                    )
-                    .await
-            });
+            let call_context = Some(context_id.to_string());
+            let result = self.run_in_context(
+                "detach_context_from_group",
+                call_context.clone(),
+                self.audited(
+                    "detach_context_from_group",
+                    call_context,
+                    None,
+                    audit_args,
+                    async move {
+                        inner
+                            .detach_context_from_group(
+                                &group_id,
+                                &context_id,
+                                admin::DetachContextFromGroupApiRequest { requester: None },
+                            )
+                            .await
+                    },
+                ),
+            )?;
+            self.metadata_cache.invalidate(MetadataKind::Context);
 
             match result {
                 Ok(data) => {
@@ -3170,12 +9489,9 @@ impl PyClient {
                             e
                         ))
                     })?;
-                    Ok(json_to_python(py, &json_data))
+                    self.to_python(py, &json_data)
                 }
-                Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Client error: {}",
-                    e
-                ))),
+                Err(e) => Err(self.client_error(e)),
             }
         })
     }
@@ -3183,6 +9499,76 @@ impl PyClient {
 
 /// Create a new client
 #[pyfunction]
-pub fn create_client(connection: &PyConnectionInfo) -> PyResult<PyClient> {
-    PyClient::new(connection)
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (connection, http3=false, wire="auto", auth_mode_ttl=auth::DEFAULT_AUTH_MODE_TTL_SECS, max_response_bytes=Some(limits::DEFAULT_MAX_RESPONSE_BYTES), request_cache_size=request_cache::DEFAULT_REQUEST_CACHE_SIZE, max_concurrency=concurrency::DEFAULT_MAX_CONCURRENCY, user=None, metadata_ttl=metadata_cache::DEFAULT_METADATA_TTL_SECS, retry_budget=retry_budget::DEFAULT_RETRY_BUDGET_RATIO, refresh_margin=Some(token_lifecycle::DEFAULT_REFRESH_MARGIN_SECS), maintenance_wait=Some(maintenance::DEFAULT_MAINTENANCE_WAIT_SECS), profile=None, dedup_window_ms=None, pool_max_idle=pool::DEFAULT_POOL_MAX_IDLE, pool_idle_timeout=pool::DEFAULT_POOL_IDLE_TIMEOUT_SECS, max_connections_per_host=None, retry=None, connect_timeout=None, request_timeout=None, production=false, max_request_bytes=Some(limits::DEFAULT_MAX_REQUEST_BYTES), oversized_args="error", read_cache_size=0, read_cache_ttl=read_cache::DEFAULT_READ_CACHE_TTL_SECS, rate_limit=None, rate_burst=None, record=None, replay=None, namespace=None, audit_log=None, audit_key=None))]
+pub fn create_client(
+    py: Python<'_>,
+    connection: &Bound<'_, PyConnectionInfo>,
+    http3: bool,
+    wire: &str,
+    auth_mode_ttl: u64,
+    max_response_bytes: Option<usize>,
+    request_cache_size: usize,
+    max_concurrency: usize,
+    user: Option<&str>,
+    metadata_ttl: u64,
+    retry_budget: f64,
+    refresh_margin: Option<u64>,
+    maintenance_wait: Option<u64>,
+    profile: Option<&str>,
+    dedup_window_ms: Option<u64>,
+    pool_max_idle: usize,
+    pool_idle_timeout: u64,
+    max_connections_per_host: Option<usize>,
+    retry: Option<RetryConfig>,
+    connect_timeout: Option<f64>,
+    request_timeout: Option<f64>,
+    production: bool,
+    max_request_bytes: Option<usize>,
+    oversized_args: &str,
+    read_cache_size: usize,
+    read_cache_ttl: f64,
+    rate_limit: Option<f64>,
+    rate_burst: Option<u32>,
+    record: Option<std::path::PathBuf>,
+    replay: Option<std::path::PathBuf>,
+    namespace: Option<&str>,
+    audit_log: Option<Bound<'_, PyAny>>,
+    audit_key: Option<&str>,
+) -> PyResult<PyClient> {
+    PyClient::new(
+        py,
+        connection,
+        http3,
+        wire,
+        auth_mode_ttl,
+        max_response_bytes,
+        request_cache_size,
+        max_concurrency,
+        user,
+        metadata_ttl,
+        retry_budget,
+        refresh_margin,
+        maintenance_wait,
+        profile,
+        dedup_window_ms,
+        pool_max_idle,
+        pool_idle_timeout,
+        max_connections_per_host,
+        retry,
+        connect_timeout,
+        request_timeout,
+        production,
+        max_request_bytes,
+        oversized_args,
+        read_cache_size,
+        read_cache_ttl,
+        rate_limit,
+        rate_burst,
+        record,
+        replay,
+        namespace,
+        audit_log,
+        audit_key,
+    )
 }
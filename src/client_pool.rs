@@ -0,0 +1,383 @@
+//! `ClientPool`: clients for a fleet of nodes, with fan-out helpers.
+//!
+//! Multi-node workflows (merobox-style local networks, staging fleets)
+//! talk to many nodes at once. A [`PyClientPool`] builds one `Client` per
+//! entry of a `{name: api_url}` map, each connection named after its node
+//! and all sharing one token storage, so logging in to a node once serves
+//! every later pool over the same storage.
+//!
+//! ```python
+//! with ClientPool({"node1": "http://localhost:2428",
+//!                  "node2": "http://localhost:2429"}) as pool:
+//!     result = pool.execute_on_all(ctx, "get", '{"key": "a"}')
+//!     peers = pool.map(lambda name, client: client.peers().count)
+//! ```
+//!
+//! `execute_on_all` runs inside Rust, at most `max_concurrency` nodes at a
+//! time, and never fails as a whole because one node did: it returns a
+//! `BulkResult` with a record per node. `map` calls a Python function per
+//! node from up to `max_concurrency` threads.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures_util::stream::{self, StreamExt};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use serde_json::json;
+use tokio::runtime::Runtime;
+
+use crate::bulk::{BulkItem, PyBulkResult};
+use crate::client::{create_client, PyClient};
+use crate::connection::PyConnectionInfo;
+use crate::error;
+use crate::metrics;
+use crate::node_address::NodeAddress;
+use crate::task_group::GroupCall;
+use crate::timeouts;
+use crate::token_refresh::{self, PyTokenRefresher};
+use crate::utils::json_to_python;
+
+/// Default number of nodes a fan-out call works on at once.
+pub const DEFAULT_POOL_CONCURRENCY: usize = 8;
+
+/// Clients of named nodes sharing one token storage.
+#[pyclass(name = "ClientPool")]
+pub struct PyClientPool {
+    /// In the order the nodes were given.
+    clients: Vec<(String, Py<PyClient>)>,
+    max_concurrency: usize,
+    runtime: Arc<Runtime>,
+}
+
+impl PyClientPool {
+    /// `(name, client)` of `nodes` (all nodes when `None`), in pool order
+    /// unless names are given.
+    fn select(
+        &self,
+        py: Python<'_>,
+        nodes: Option<Vec<String>>,
+    ) -> PyResult<Vec<(String, Py<PyClient>)>> {
+        let Some(nodes) = nodes else {
+            return Ok(self
+                .clients
+                .iter()
+                .map(|(name, client)| (name.clone(), client.clone_ref(py)))
+                .collect());
+        };
+        nodes
+            .into_iter()
+            .map(|name| {
+                let client = self.client(py, &name)?;
+                Ok((name, client))
+            })
+            .collect()
+    }
+}
+
+#[pymethods]
+impl PyClientPool {
+    /// Clients for `nodes` (`{name: api_url}`), sharing `storage` (as for
+    /// `create_connection`; the default token cache when omitted).
+    /// Further keyword arguments are passed to `create_client` for every
+    /// node.
+    #[new]
+    #[pyo3(signature = (nodes, storage=None, cache_dir=None, passphrase=None, max_concurrency=DEFAULT_POOL_CONCURRENCY, **client_options))]
+    pub fn new(
+        py: Python<'_>,
+        nodes: &Bound<'_, PyDict>,
+        storage: Option<Bound<'_, PyAny>>,
+        cache_dir: Option<std::path::PathBuf>,
+        passphrase: Option<&str>,
+        max_concurrency: usize,
+        client_options: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        if max_concurrency == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "max_concurrency must be at least 1",
+            ));
+        }
+        let create = wrap_pyfunction!(create_client, py)?;
+        let mut clients = Vec::with_capacity(nodes.len());
+        for (name, api_url) in nodes.iter() {
+            let name: String = name.extract()?;
+            let api_url: NodeAddress = api_url.extract()?;
+            let connection = PyConnectionInfo::new(
+                py,
+                api_url,
+                Some(&name),
+                false,
+                storage.clone(),
+                passphrase,
+                cache_dir.clone(),
+            )?;
+            let client = create
+                .call((connection,), client_options)?
+                .downcast_into::<PyClient>()?
+                .unbind();
+            clients.push((name, client));
+        }
+        let runtime = Arc::new(
+            Runtime::new()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        );
+        Ok(Self {
+            clients,
+            max_concurrency,
+            runtime,
+        })
+    }
+
+    /// Names of the nodes, in pool order
+    #[getter]
+    pub fn nodes(&self) -> Vec<String> {
+        self.clients.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    #[getter]
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// Client of node `name`; `KeyError` for names not in the pool
+    pub fn client(&self, py: Python<'_>, name: &str) -> PyResult<Py<PyClient>> {
+        self.clients
+            .iter()
+            .find(|(node, _)| node == name)
+            .map(|(_, client)| client.clone_ref(py))
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>(name.to_string()))
+    }
+
+    /// Refresh the tokens of every node in the background, as
+    /// `Client.start_token_refresh()` does: one refresher, running on the
+    /// first node's client, scans the storage the pool shares. Returns its
+    /// `TokenRefresher`.
+    #[pyo3(signature = (interval=token_refresh::DEFAULT_REFRESH_INTERVAL_SECS, window=token_refresh::DEFAULT_REFRESH_WINDOW_SECS, on_refresh=None, on_error=None, weak=None))]
+    pub fn start_token_refresh(
+        &self,
+        py: Python<'_>,
+        interval: f64,
+        window: f64,
+        on_refresh: Option<PyObject>,
+        on_error: Option<PyObject>,
+        weak: Option<bool>,
+    ) -> PyResult<PyTokenRefresher> {
+        let Some((_, first)) = self.clients.first() else {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "The pool has no nodes",
+            ));
+        };
+        let nodes = self
+            .clients
+            .iter()
+            .map(|(name, client)| {
+                let (_, api_url) = client.borrow(py).probe_target();
+                let api_url = NodeAddress::parse(api_url.as_str())
+                    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+                Ok((name.clone(), api_url))
+            })
+            .collect::<PyResult<_>>()?;
+        first.borrow(py).start_token_refresh(
+            py,
+            interval,
+            window,
+            on_refresh,
+            on_error,
+            Some(nodes),
+            weak,
+        )
+    }
+
+    /// Call `execute_function` on every node (or those in `nodes`), at most
+    /// `max_concurrency` at a time. Returns a `BulkResult` of `{node,
+    /// result, error}` records in node order; its `failed_inputs` are node
+    /// names, to pass back as `nodes` for a retry.
+    #[pyo3(signature = (context_id, method, args, nodes=None))]
+    pub fn execute_on_all(
+        &self,
+        py: Python<'_>,
+        context_id: &str,
+        method: &str,
+        args: &str,
+        nodes: Option<Vec<String>>,
+    ) -> PyResult<PyBulkResult> {
+        let selected = self.select(py, nodes)?;
+        // Calls that cannot be built (unknown alias, frozen context) fail
+        // their own record; the others are sent.
+        let mut outcomes: Vec<Result<serde_json::Value, String>> = Vec::new();
+        let mut pending = Vec::new();
+        let mut futures = Vec::new();
+        for (index, (_, client)) in selected.iter().enumerate() {
+            let client = client.borrow(py);
+            let call = client.context_id(context_id).and_then(|context_id| {
+                client.group_call(&GroupCall {
+                    context_id,
+                    method: method.to_string(),
+                    args: args.to_string(),
+                    executor_public_key: String::new(),
+                })
+            });
+            match call {
+                Ok(call) => {
+                    pending.push(index);
+                    futures.push(call);
+                    outcomes.push(Ok(serde_json::Value::Null));
+                }
+                Err(e) => outcomes.push(Err(e.to_string())),
+            }
+        }
+
+        let max_concurrency = self.max_concurrency;
+        let sent = timeouts::block_on(
+            &self.runtime,
+            stream::iter(futures)
+                .buffered(max_concurrency)
+                .collect::<Vec<_>>(),
+        )?;
+        for (index, outcome) in pending.into_iter().zip(sent) {
+            outcomes[index] = outcome.map_err(error::render_chain);
+        }
+
+        let items = selected
+            .into_iter()
+            .zip(outcomes)
+            .map(|((node, _), outcome)| {
+                let (result, error) = match outcome {
+                    Ok(result) => (result, None),
+                    Err(e) => (serde_json::Value::Null, Some(e)),
+                };
+                BulkItem {
+                    value: json!({"node": node, "result": result, "error": error}),
+                    input: node,
+                    error,
+                }
+            })
+            .collect();
+        Ok(PyBulkResult::new("execute_on_all", items))
+    }
+
+    /// Call `fn(name, client)` for every node (or those in `nodes`) from up
+    /// to `max_concurrency` threads; returns `{name: result}` in node
+    /// order. Once every call finished, the first exception (in node
+    /// order) is raised, unless `return_exceptions=True` puts exceptions
+    /// in the result in place of values.
+    #[pyo3(signature = (func, nodes=None, return_exceptions=false))]
+    pub fn map(
+        &self,
+        py: Python<'_>,
+        func: PyObject,
+        nodes: Option<Vec<String>>,
+        return_exceptions: bool,
+    ) -> PyResult<PyObject> {
+        if !func.bind(py).is_callable() {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "func must be callable",
+            ));
+        }
+        let selected = self.select(py, nodes)?;
+        let outcomes: Vec<Mutex<Option<PyResult<PyObject>>>> =
+            selected.iter().map(|_| Mutex::new(None)).collect();
+        let next = AtomicUsize::new(0);
+        let workers = self.max_concurrency.min(selected.len());
+        py.allow_threads(|| {
+            std::thread::scope(|scope| {
+                for _ in 0..workers {
+                    scope.spawn(|| loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some((name, client)) = selected.get(index) else {
+                            break;
+                        };
+                        let outcome = Python::with_gil(|py| {
+                            func.call1(py, (name.as_str(), client.clone_ref(py)))
+                        });
+                        if let Ok(mut slot) = outcomes[index].lock() {
+                            *slot = Some(outcome);
+                        }
+                    });
+                }
+            })
+        });
+
+        let results = PyDict::new_bound(py);
+        for ((name, _), outcome) in selected.iter().zip(outcomes) {
+            let outcome = outcome.into_inner().ok().flatten().ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "map call for node {} did not complete",
+                    name
+                ))
+            });
+            match outcome.and_then(|outcome| outcome) {
+                Ok(value) => results.set_item(name, value)?,
+                Err(e) if return_exceptions => results.set_item(name, e.into_value(py))?,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(results.into_any().unbind())
+    }
+
+    /// `health()` of every node: `{name: NodeHealth}`
+    pub fn health(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let results = PyDict::new_bound(py);
+        for (name, client) in &self.clients {
+            results.set_item(name, client.borrow(py).health()?)?;
+        }
+        Ok(results.into_any().unbind())
+    }
+
+    /// `metrics()` of every node: `{name: snapshot}`, or with
+    /// `prometheus=True` one Prometheus text exposition with a series per
+    /// node.
+    #[pyo3(signature = (prometheus=false))]
+    pub fn metrics(&self, py: Python<'_>, prometheus: bool) -> PyResult<PyObject> {
+        let snapshots: Vec<(&String, serde_json::Value)> = self
+            .clients
+            .iter()
+            .map(|(name, client)| (name, client.borrow(py).metrics_snapshot()))
+            .collect();
+        if prometheus {
+            let snapshots: Vec<serde_json::Value> = snapshots
+                .into_iter()
+                .map(|(_, snapshot)| snapshot)
+                .collect();
+            return Ok(metrics::prometheus(&snapshots).into_py(py));
+        }
+        let results = PyDict::new_bound(py);
+        for (name, snapshot) in snapshots {
+            results.set_item(name, json_to_python(py, &snapshot))?;
+        }
+        Ok(results.into_any().unbind())
+    }
+
+    /// `close()` every client.
+    pub fn close(&self, py: Python<'_>) {
+        for (_, client) in &self.clients {
+            client.borrow(py).close(py);
+        }
+    }
+
+    fn __getitem__(&self, py: Python<'_>, name: &str) -> PyResult<Py<PyClient>> {
+        self.client(py, name)
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.clients.iter().any(|(node, _)| node == name)
+    }
+
+    fn __len__(&self) -> usize {
+        self.clients.len()
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_exc))]
+    fn __exit__(&self, py: Python<'_>, _exc: &Bound<'_, PyTuple>) -> bool {
+        self.close(py);
+        false
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ClientPool(nodes={:?})", self.nodes())
+    }
+}
@@ -0,0 +1,296 @@
+//! Adaptive concurrency limit for requests to a node.
+//!
+//! A fixed concurrency is either too low (the node sits idle) or too high
+//! (requests queue on the node until they time out), and the right number
+//! depends on the node, the network and the workload. The limiter finds it
+//! with AIMD: every completed request reports its latency, the limit grows
+//! by roughly one per round trip while latency stays near the observed
+//! baseline and is cut by [`DECREASE_FACTOR`] when latency climbs past
+//! [`LATENCY_TOLERANCE`] times the baseline or a request fails.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+use crate::rate_limit::Throttle;
+
+/// Default ceiling for the adaptive limit.
+pub const DEFAULT_MAX_CONCURRENCY: usize = 64;
+
+/// Limit the limiter starts from before it has any samples.
+const INITIAL_LIMIT: f64 = 4.0;
+
+/// Latency over `baseline * LATENCY_TOLERANCE` counts as congestion.
+const LATENCY_TOLERANCE: f64 = 2.0;
+
+/// Multiplicative decrease applied on congestion or failure.
+const DECREASE_FACTOR: f64 = 0.9;
+
+/// Weight of a new sample when the baseline drifts upwards, so a node that
+/// got permanently slower is eventually accepted as the new normal.
+const BASELINE_DRIFT: f64 = 0.01;
+
+#[derive(Debug)]
+struct State {
+    limit: f64,
+    max_limit: f64,
+    in_flight: usize,
+    baseline: Option<Duration>,
+    last_rtt: Option<Duration>,
+    decreases: u64,
+}
+
+/// AIMD concurrency limiter shared by every request a client makes.
+#[derive(Debug)]
+pub struct AdaptiveLimiter {
+    state: Mutex<State>,
+    released: Notify,
+    /// Permits shared with other clients of the same node.
+    host_limit: Option<Arc<Semaphore>>,
+    /// Rate limit and `Retry-After` pauses of the node.
+    throttle: Option<Throttle>,
+}
+
+/// Slot for one in-flight request; reports its latency when dropped.
+pub struct Permit {
+    limiter: Arc<AdaptiveLimiter>,
+    started: Instant,
+    failed: bool,
+    _host: Option<OwnedSemaphorePermit>,
+}
+
+impl Permit {
+    /// Report the request as failed, which always counts as congestion.
+    pub fn failed(mut self) {
+        self.failed = true;
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.limiter.release(self.started.elapsed(), !self.failed);
+    }
+}
+
+impl AdaptiveLimiter {
+    /// `max_concurrency` caps the limit; it is clamped to at least 1.
+    pub fn new(max_concurrency: usize) -> Self {
+        let max_limit = max_concurrency.max(1) as f64;
+        Self {
+            state: Mutex::new(State {
+                limit: INITIAL_LIMIT.min(max_limit),
+                max_limit,
+                in_flight: 0,
+                baseline: None,
+                last_rtt: None,
+                decreases: 0,
+            }),
+            released: Notify::new(),
+            host_limit: None,
+            throttle: None,
+        }
+    }
+
+    /// Also take a permit from `host_limit` (see [`crate::pool::host_limit`])
+    /// for every slot.
+    pub fn with_host_limit(mut self, host_limit: Option<Arc<Semaphore>>) -> Self {
+        self.host_limit = host_limit;
+        self
+    }
+
+    /// Wait for `throttle` (see [`crate::rate_limit`]) before every slot.
+    pub fn with_throttle(mut self, throttle: Throttle) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
+
+    /// Wait for a free slot under the current limit (after the throttle
+    /// and a host permit, taken first so waiting for them does not count as
+    /// latency).
+    pub async fn acquire(self: &Arc<Self>) -> Permit {
+        if let Some(throttle) = &self.throttle {
+            throttle.wait().await;
+        }
+        let host = match &self.host_limit {
+            Some(host_limit) => Arc::clone(host_limit).acquire_owned().await.ok(),
+            None => None,
+        };
+        loop {
+            let notified = self.released.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if let Ok(mut state) = self.state.lock() {
+                if state.in_flight < state.limit as usize {
+                    state.in_flight += 1;
+                    return Permit {
+                        limiter: Arc::clone(self),
+                        started: Instant::now(),
+                        failed: false,
+                        _host: host,
+                    };
+                }
+            } else {
+                // A poisoned lock only means a panic elsewhere; don't gate.
+                return Permit {
+                    limiter: Arc::clone(self),
+                    started: Instant::now(),
+                    failed: false,
+                    _host: host,
+                };
+            }
+            notified.await;
+        }
+    }
+
+    fn release(&self, rtt: Duration, ok: bool) {
+        if let Ok(mut state) = self.state.lock() {
+            state.record(rtt, ok);
+            state.in_flight = state.in_flight.saturating_sub(1);
+        }
+        self.released.notify_waiters();
+    }
+
+    /// `Throttle::stats` of the node, `None` without a throttle.
+    pub fn throttle_stats(&self) -> serde_json::Value {
+        self.throttle
+            .as_ref()
+            .map(Throttle::stats)
+            .unwrap_or_default()
+    }
+
+    /// Current integer limit.
+    pub fn limit(&self) -> usize {
+        self.state
+            .lock()
+            .map(|state| state.limit as usize)
+            .unwrap_or(1)
+    }
+
+    /// `{limit, maxLimit, inFlight, baselineMs, lastRttMs, decreases}`.
+    pub fn stats(&self) -> serde_json::Value {
+        let Ok(state) = self.state.lock() else {
+            return serde_json::Value::Null;
+        };
+        let ms = |d: Option<Duration>| d.map(|d| d.as_secs_f64() * 1000.0);
+        serde_json::json!({
+            "limit": state.limit as usize,
+            "maxLimit": state.max_limit as usize,
+            "inFlight": state.in_flight,
+            "baselineMs": ms(state.baseline),
+            "lastRttMs": ms(state.last_rtt),
+            "decreases": state.decreases,
+        })
+    }
+}
+
+impl State {
+    fn record(&mut self, rtt: Duration, ok: bool) {
+        self.last_rtt = Some(rtt);
+        let baseline = match self.baseline {
+            Some(baseline) if rtt >= baseline => {
+                baseline.mul_f64(1.0 - BASELINE_DRIFT) + rtt.mul_f64(BASELINE_DRIFT)
+            }
+            _ => rtt,
+        };
+        self.baseline = Some(baseline);
+
+        if !ok || rtt.as_secs_f64() > baseline.as_secs_f64() * LATENCY_TOLERANCE {
+            self.limit = (self.limit * DECREASE_FACTOR).max(1.0);
+            self.decreases += 1;
+        } else if self.in_flight as f64 >= self.limit / 2.0 {
+            // Only grow when the limit is actually being used; a sequential
+            // caller proves nothing about what the node can take.
+            self.limit = (self.limit + 1.0 / self.limit).min(self.max_limit);
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_state(limit: f64, in_flight: usize) -> State {
+        State {
+            limit,
+            max_limit: 16.0,
+            in_flight,
+            baseline: Some(Duration::from_millis(10)),
+            last_rtt: None,
+            decreases: 0,
+        }
+    }
+
+    /// Fast completions under load grow the limit up to the ceiling.
+    #[test]
+    fn test_limit_grows_under_load() {
+        let mut state = sample_state(4.0, 16);
+        for _ in 0..1000 {
+            state.record(Duration::from_millis(10), true);
+        }
+        assert_eq!(state.limit, 16.0);
+    }
+
+    /// A sequential caller does not inflate the limit.
+    #[test]
+    fn test_limit_steady_when_underused() {
+        let mut state = sample_state(4.0, 1);
+        state.record(Duration::from_millis(10), true);
+        assert_eq!(state.limit, 4.0);
+    }
+
+    /// Latency spikes and failures cut the limit, never below one.
+    #[test]
+    fn test_limit_decreases_on_congestion() {
+        let mut state = sample_state(10.0, 10);
+        state.record(Duration::from_millis(100), true);
+        assert_eq!(state.limit, 9.0);
+        state.record(Duration::from_millis(10), false);
+        assert!((state.limit - 8.1).abs() < 1e-9);
+        assert_eq!(state.decreases, 2);
+
+        let mut state = sample_state(1.0, 1);
+        state.record(Duration::from_secs(5), false);
+        assert_eq!(state.limit, 1.0);
+    }
+
+    /// Acquirers beyond the limit wait until a permit is released.
+    #[tokio::test]
+    async fn test_acquire_waits_for_release() {
+        let limiter = Arc::new(AdaptiveLimiter::new(1));
+        let first = limiter.acquire().await;
+
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move { drop(limiter.acquire().await) })
+        };
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        drop(first);
+        waiter.await.unwrap();
+        assert_eq!(limiter.stats()["inFlight"], 0);
+    }
+
+    /// Limiters sharing a host limit never exceed it together.
+    #[tokio::test]
+    async fn test_host_limit_spans_limiters() {
+        let host = Arc::new(Semaphore::new(1));
+        let a = Arc::new(AdaptiveLimiter::new(4).with_host_limit(Some(host.clone())));
+        let b = Arc::new(AdaptiveLimiter::new(4).with_host_limit(Some(host.clone())));
+        let first = a.acquire().await;
+
+        let waiter = tokio::spawn(async move { drop(b.acquire().await) });
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        drop(first);
+        waiter.await.unwrap();
+        assert_eq!(host.available_permits(), 1);
+    }
+}
@@ -0,0 +1,669 @@
+//! Node endpoints and client defaults from `~/.merobox/config.toml`.
+//!
+//! Scripts that talk to a handful of known nodes should not repeat their URLs
+//! and settings everywhere. The config file names them once:
+//!
+//! ```toml
+//! default_node = "node1"
+//!
+//! [defaults]
+//! request_timeout = 30.0
+//!
+//! [nodes.node1]
+//! url = "http://localhost:2428"
+//! auth_mode = "none"
+//!
+//! [nodes.staging]
+//! url = "https://staging.example.com"
+//! profile = "ci"
+//! connect_timeout = 5.0
+//! ```
+//!
+//! and `connect("node1")` (or `connect()` for the default node) builds the
+//! client. Node settings override `[defaults]`, and keyword arguments to
+//! `connect` override both. `auth_mode = "none"` makes an anonymous
+//! connection, which stores no tokens. With no node given and no default
+//! node, `connect()` uses the first node found by [`crate::discovery`].
+//!
+//! `$CALIMERO_CLIENT_CONFIG` points at another file. Environment variables
+//! override what the file says without changing it: `CALIMERO_NODE` (default
+//! node), `CALIMERO_PROFILE`, `CALIMERO_CONNECT_TIMEOUT`,
+//! `CALIMERO_REQUEST_TIMEOUT` (defaults), and `CALIMERO_NODE_<NAME>_URL`
+//! (URL of a configured node; `<NAME>` upper-cased, other characters than
+//! letters and digits replaced by `_`). [`PyConfig::save`] writes the file
+//! contents only, never the overrides.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::cache;
+use crate::client::{create_client, PyClient};
+use crate::connection::PyConnectionInfo;
+use crate::discovery;
+use crate::entropy;
+use crate::node_address::NodeAddress;
+use crate::utils::json_to_python;
+
+/// Config file name under the legacy state root
+pub const CONFIG_FILE: &str = "config.toml";
+/// Environment variable naming another config file
+pub const CONFIG_PATH_ENV: &str = "CALIMERO_CLIENT_CONFIG";
+/// Environment variable overriding the default node
+pub const DEFAULT_NODE_ENV: &str = "CALIMERO_NODE";
+/// Environment variable overriding the default profile
+pub const PROFILE_ENV: &str = "CALIMERO_PROFILE";
+/// Environment variable overriding the default connect timeout
+pub const CONNECT_TIMEOUT_ENV: &str = "CALIMERO_CONNECT_TIMEOUT";
+/// Environment variable overriding the default request timeout
+pub const REQUEST_TIMEOUT_ENV: &str = "CALIMERO_REQUEST_TIMEOUT";
+
+/// Auth modes a node entry may name.
+const AUTH_MODES: &[&str] = &["none", "required"];
+
+/// Client settings of `[defaults]` or a node entry; unset ones fall through.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout: Option<f64>,
+}
+
+impl Settings {
+    /// `self`, with unset values taken from `fallback`.
+    fn or(&self, fallback: &Settings) -> Settings {
+        Settings {
+            profile: self.profile.clone().or_else(|| fallback.profile.clone()),
+            connect_timeout: self.connect_timeout.or(fallback.connect_timeout),
+            request_timeout: self.request_timeout.or(fallback.request_timeout),
+        }
+    }
+
+    fn validate(&self, section: &str) -> eyre::Result<()> {
+        for (key, value) in [
+            ("connect_timeout", self.connect_timeout),
+            ("request_timeout", self.request_timeout),
+        ] {
+            if value.is_some_and(|secs| !(secs.is_finite() && secs > 0.0)) {
+                eyre::bail!("{}.{} must be a positive number of seconds", section, key);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `[nodes.<name>]` entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeEntry {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_timeout: Option<f64>,
+}
+
+impl NodeEntry {
+    /// Client settings of the entry alone.
+    pub fn settings(&self) -> Settings {
+        Settings {
+            profile: self.profile.clone(),
+            connect_timeout: self.connect_timeout,
+            request_timeout: self.request_timeout,
+        }
+    }
+}
+
+/// Contents of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_node: Option<String>,
+    pub defaults: Settings,
+    pub nodes: BTreeMap<String, NodeEntry>,
+}
+
+impl ConfigFile {
+    pub fn parse(text: &str) -> eyre::Result<Self> {
+        let config: ConfigFile = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn render(&self) -> eyre::Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    pub fn validate(&self) -> eyre::Result<()> {
+        self.defaults.validate("defaults")?;
+        for (name, node) in &self.nodes {
+            if node.url.is_empty() {
+                eyre::bail!("nodes.{}.url must not be empty", name);
+            }
+            if let Some(mode) = node.auth_mode.as_deref() {
+                if !AUTH_MODES.contains(&mode) {
+                    eyre::bail!(
+                        "nodes.{}.auth_mode must be one of {}, not {:?}",
+                        name,
+                        AUTH_MODES.join(", "),
+                        mode
+                    );
+                }
+            }
+            node.settings().validate(&format!("nodes.{}", name))?;
+        }
+        Ok(())
+    }
+
+    /// The file as overridden by `env` (a variable lookup).
+    pub fn with_overrides(&self, env: impl Fn(&str) -> Option<String>) -> eyre::Result<Self> {
+        let timeout = |name: &str| -> eyre::Result<Option<f64>> {
+            env(name)
+                .map(|value| {
+                    value
+                        .trim()
+                        .parse::<f64>()
+                        .wrap_err_with(|| format!("${} is not a number: {:?}", name, value))
+                })
+                .transpose()
+        };
+        let mut config = self.clone();
+        if let Some(node) = env(DEFAULT_NODE_ENV) {
+            config.default_node = Some(node);
+        }
+        if let Some(profile) = env(PROFILE_ENV) {
+            config.defaults.profile = Some(profile);
+        }
+        if let Some(secs) = timeout(CONNECT_TIMEOUT_ENV)? {
+            config.defaults.connect_timeout = Some(secs);
+        }
+        if let Some(secs) = timeout(REQUEST_TIMEOUT_ENV)? {
+            config.defaults.request_timeout = Some(secs);
+        }
+        for (name, node) in config.nodes.iter_mut() {
+            if let Some(url) = env(&node_url_env(name)) {
+                node.url = url;
+            }
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Entry of node `name` with its settings completed from `[defaults]`.
+    pub fn resolve(&self, name: &str) -> Option<NodeEntry> {
+        self.nodes.get(name).map(|node| {
+            let settings = node.settings().or(&self.defaults);
+            NodeEntry {
+                profile: settings.profile,
+                connect_timeout: settings.connect_timeout,
+                request_timeout: settings.request_timeout,
+                ..node.clone()
+            }
+        })
+    }
+}
+
+/// `CALIMERO_NODE_<NAME>_URL` for node `name`.
+pub fn node_url_env(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect();
+    format!("CALIMERO_NODE_{}_URL", name)
+}
+
+/// `$CALIMERO_CLIENT_CONFIG`, else `~/.merobox/config.toml`.
+pub fn default_path() -> PathBuf {
+    std::env::var_os(CONFIG_PATH_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| cache::legacy_root().join(CONFIG_FILE))
+}
+
+/// Non-empty value of the environment variable `name`.
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// The file at `path`; empty when it does not exist.
+fn read(path: &Path) -> eyre::Result<ConfigFile> {
+    match fs::read_to_string(path) {
+        Ok(text) => ConfigFile::parse(&text).wrap_err_with(|| format!("Invalid config {:?}", path)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ConfigFile::default()),
+        Err(e) => Err(e).wrap_err_with(|| format!("Failed to read {:?}", path)),
+    }
+}
+
+/// URL of node `name` in the default config, with environment overrides;
+/// what `calimero://<name>` addresses resolve to.
+pub fn node_url(name: &str) -> eyre::Result<String> {
+    let path = default_path();
+    let config = read(&path)?.with_overrides(env_var)?;
+    match config.nodes.get(name) {
+        Some(node) => Ok(node.url.clone()),
+        None => eyre::bail!(
+            "unknown node {:?} (configured in {:?}: {})",
+            name,
+            path,
+            config.nodes.keys().cloned().collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+/// Write `text` to `path` through a temp file.
+fn write(path: &Path, text: &str) -> eyre::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("Failed to create directory {:?}", parent))?;
+    }
+    let temp_path = path.with_extension(format!("{}.tmp", entropy::temp_suffix()));
+    let written = fs::write(&temp_path, text).and_then(|()| fs::rename(&temp_path, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written.wrap_err_with(|| format!("Failed to write {:?}", path))
+}
+
+fn config_error(e: eyre::Report) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e))
+}
+
+fn io_error(e: eyre::Report) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e))
+}
+
+fn settings_json(settings: &Settings) -> Value {
+    serde_json::to_value(settings).unwrap_or_default()
+}
+
+fn node_json(node: &NodeEntry) -> Value {
+    serde_json::to_value(node).unwrap_or_default()
+}
+
+/// The client config file, with environment overrides applied on read.
+///
+/// `nodes`, `default_node`, `defaults` and `node()` report effective values;
+/// the `set_*`/`remove_node` methods change the file contents, written by
+/// `save()`.
+#[pyclass(name = "Config")]
+pub struct PyConfig {
+    path: PathBuf,
+    file: ConfigFile,
+}
+
+impl PyConfig {
+    fn effective(&self) -> PyResult<ConfigFile> {
+        self.file.with_overrides(env_var).map_err(config_error)
+    }
+}
+
+#[pymethods]
+impl PyConfig {
+    /// Read the config at `path` (default: `$CALIMERO_CLIENT_CONFIG` or
+    /// `~/.merobox/config.toml`). A missing file reads as empty.
+    #[staticmethod]
+    #[pyo3(signature = (path=None))]
+    pub fn load(path: Option<PathBuf>) -> PyResult<Self> {
+        let path = path.unwrap_or_else(default_path);
+        let file = read(&path).map_err(|e| match e.root_cause().is::<std::io::Error>() {
+            true => io_error(e),
+            false => config_error(e),
+        })?;
+        let config = Self { path, file };
+        config.effective()?;
+        Ok(config)
+    }
+
+    /// File this config was read from and `save()` writes to by default
+    #[getter]
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// Names of the configured nodes
+    #[getter]
+    pub fn nodes(&self) -> Vec<String> {
+        self.file.nodes.keys().cloned().collect()
+    }
+
+    /// Node `connect()` uses without a name, if any
+    #[getter]
+    pub fn default_node(&self) -> PyResult<Option<String>> {
+        Ok(self.effective()?.default_node)
+    }
+
+    #[setter]
+    pub fn set_default_node(&mut self, name: Option<String>) -> PyResult<()> {
+        if let Some(name) = name.as_deref() {
+            if !self.file.nodes.contains_key(name) {
+                return Err(PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!(
+                    "unknown node {:?}",
+                    name
+                )));
+            }
+        }
+        self.file.default_node = name;
+        Ok(())
+    }
+
+    /// `{profile, connect_timeout, request_timeout}` applied to every node
+    #[getter]
+    pub fn defaults(&self, py: Python<'_>) -> PyResult<PyObject> {
+        Ok(json_to_python(
+            py,
+            &settings_json(&self.effective()?.defaults),
+        ))
+    }
+
+    /// Replace the defaults; omitted settings are unset.
+    #[pyo3(signature = (profile=None, connect_timeout=None, request_timeout=None))]
+    pub fn set_defaults(
+        &mut self,
+        profile: Option<String>,
+        connect_timeout: Option<f64>,
+        request_timeout: Option<f64>,
+    ) -> PyResult<()> {
+        let defaults = Settings {
+            profile,
+            connect_timeout,
+            request_timeout,
+        };
+        defaults.validate("defaults").map_err(config_error)?;
+        self.file.defaults = defaults;
+        Ok(())
+    }
+
+    /// `{url, auth_mode, profile, connect_timeout, request_timeout}` of node
+    /// `name`, completed from the defaults; `KeyError` for unknown names
+    pub fn node(&self, py: Python<'_>, name: &str) -> PyResult<PyObject> {
+        let node = self.effective()?.resolve(name).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!("unknown node {:?}", name))
+        })?;
+        Ok(json_to_python(py, &node_json(&node)))
+    }
+
+    /// Add node `name`, or replace its entry.
+    #[pyo3(signature = (name, url, auth_mode=None, profile=None, connect_timeout=None, request_timeout=None))]
+    pub fn set_node(
+        &mut self,
+        name: String,
+        url: String,
+        auth_mode: Option<String>,
+        profile: Option<String>,
+        connect_timeout: Option<f64>,
+        request_timeout: Option<f64>,
+    ) -> PyResult<()> {
+        let mut file = self.file.clone();
+        file.nodes.insert(
+            name,
+            NodeEntry {
+                url,
+                auth_mode,
+                profile,
+                connect_timeout,
+                request_timeout,
+            },
+        );
+        file.validate().map_err(config_error)?;
+        self.file = file;
+        Ok(())
+    }
+
+    /// Remove node `name`; returns whether it was configured. Clears the
+    /// default node if it was that one.
+    pub fn remove_node(&mut self, name: &str) -> bool {
+        if self.file.default_node.as_deref() == Some(name) {
+            self.file.default_node = None;
+        }
+        self.file.nodes.remove(name).is_some()
+    }
+
+    /// Write the file contents (not environment overrides) to `path`
+    /// (default: the file it was read from).
+    #[pyo3(signature = (path=None))]
+    pub fn save(&mut self, path: Option<PathBuf>) -> PyResult<()> {
+        let path = path.unwrap_or_else(|| self.path.clone());
+        let text = self.file.render().map_err(config_error)?;
+        write(&path, &text).map_err(io_error)?;
+        self.path = path;
+        Ok(())
+    }
+
+    /// Effective config: `{default_node, defaults, nodes: {name: entry}}`
+    pub fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let config = self.effective()?;
+        let nodes: serde_json::Map<String, Value> = config
+            .nodes
+            .iter()
+            .map(|(name, node)| (name.clone(), node_json(node)))
+            .collect();
+        let value = json!({
+            "default_node": config.default_node,
+            "defaults": settings_json(&config.defaults),
+            "nodes": nodes,
+        });
+        Ok(json_to_python(py, &value))
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.file.nodes.contains_key(name)
+    }
+
+    fn __len__(&self) -> usize {
+        self.file.nodes.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Config(path={:?}, nodes={:?})", self.path, self.nodes())
+    }
+}
+
+/// Read the client config (see `Config.load`).
+#[pyfunction]
+#[pyo3(signature = (path=None))]
+pub fn load_config(path: Option<PathBuf>) -> PyResult<PyConfig> {
+    PyConfig::load(path)
+}
+
+/// First node found by local discovery, for `connect()` without a node or
+/// `default_node`; the config's `[defaults]` apply to it.
+fn discovered_node(
+    py: Python<'_>,
+    effective: &ConfigFile,
+    path: &Path,
+) -> PyResult<(String, NodeEntry)> {
+    let ports: Vec<u16> = discovery::DEFAULT_PORTS.collect();
+    let timeout = Duration::from_secs_f64(discovery::DEFAULT_PROBE_TIMEOUT_SECS);
+    let found = py
+        .allow_threads(|| discovery::discover(discovery::DEFAULT_HOST, &ports, None, timeout))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e)))?;
+    let node = found.into_iter().next().ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "no node given, no default_node in {:?} and no running node discovered",
+            path
+        ))
+    })?;
+    let entry = NodeEntry {
+        url: node.candidate.url.to_string(),
+        auth_mode: None,
+        profile: effective.defaults.profile.clone(),
+        connect_timeout: effective.defaults.connect_timeout,
+        request_timeout: effective.defaults.request_timeout,
+    };
+    Ok((node.node_name(), entry))
+}
+
+/// Client of the configured node `node` (default: the config's
+/// `default_node`), with the connection named after it. With neither, the
+/// first node `discover_nodes()` finds is used, named after its merobox
+/// name or its `host-port`.
+///
+/// `config` is a `Config` (default: `Config.load()`). `storage`,
+/// `cache_dir` and `passphrase` are as for `create_connection`; further
+/// keyword arguments go to `create_client` and override the node's
+/// `profile`, `connect_timeout` and `request_timeout`.
+#[pyfunction]
+#[pyo3(signature = (node=None, config=None, storage=None, cache_dir=None, passphrase=None, **client_options))]
+pub fn connect(
+    py: Python<'_>,
+    node: Option<String>,
+    config: Option<PyRef<'_, PyConfig>>,
+    storage: Option<Bound<'_, PyAny>>,
+    cache_dir: Option<PathBuf>,
+    passphrase: Option<&str>,
+    client_options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyClient>> {
+    let loaded;
+    let config = match config {
+        Some(config) => config,
+        None => {
+            loaded = Bound::new(py, PyConfig::load(None)?)?;
+            loaded.borrow()
+        }
+    };
+    let effective = config.effective()?;
+    let (name, entry) = match node.or(effective.default_node.clone()) {
+        Some(name) => {
+            let entry = effective.resolve(&name).ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!(
+                    "unknown node {:?} (configured in {:?}: {})",
+                    name,
+                    config.path,
+                    effective
+                        .nodes
+                        .keys()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })?;
+            (name, entry)
+        }
+        None => discovered_node(py, &effective, &config.path)?,
+    };
+
+    let address =
+        NodeAddress::parse(&entry.url).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+    let connection = PyConnectionInfo::new(
+        py,
+        address,
+        Some(&name),
+        entry.auth_mode.as_deref() == Some("none"),
+        storage,
+        passphrase,
+        cache_dir,
+    )?;
+    let options = PyDict::new_bound(py);
+    if let Some(profile) = &entry.profile {
+        options.set_item("profile", profile)?;
+    }
+    if let Some(secs) = entry.connect_timeout {
+        options.set_item("connect_timeout", secs)?;
+    }
+    if let Some(secs) = entry.request_timeout {
+        options.set_item("request_timeout", secs)?;
+    }
+    if let Some(client_options) = client_options {
+        options.update(client_options.as_mapping())?;
+    }
+    Ok(wrap_pyfunction!(create_client, py)?
+        .call((connection,), Some(&options))?
+        .downcast_into::<PyClient>()?
+        .unbind())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"
+default_node = "node1"
+
+[defaults]
+request_timeout = 30.0
+
+[nodes.node1]
+url = "http://localhost:2428"
+auth_mode = "none"
+
+[nodes.staging-eu]
+url = "https://staging.example.com"
+profile = "ci"
+connect_timeout = 5.0
+"#;
+
+    #[test]
+    fn test_parse_resolve_and_render() {
+        let config = ConfigFile::parse(EXAMPLE).unwrap();
+        assert_eq!(config.default_node.as_deref(), Some("node1"));
+
+        let staging = config.resolve("staging-eu").unwrap();
+        assert_eq!(staging.profile.as_deref(), Some("ci"));
+        assert_eq!(staging.connect_timeout, Some(5.0));
+        assert_eq!(staging.request_timeout, Some(30.0));
+        assert!(config.resolve("missing").is_none());
+
+        assert_eq!(
+            ConfigFile::parse(&config.render().unwrap()).unwrap(),
+            config
+        );
+        assert_eq!(ConfigFile::parse("").unwrap(), ConfigFile::default());
+    }
+
+    #[test]
+    fn test_invalid_config_rejected() {
+        assert!(ConfigFile::parse("[nodes.a]\nurl = \"http://a\"\nauth_mode = \"maybe\"").is_err());
+        assert!(ConfigFile::parse("[defaults]\nrequest_timeout = -1.0").is_err());
+        assert!(ConfigFile::parse("[nodes.a]\nurl = \"http://a\"\ntimeout = 3").is_err());
+        assert!(ConfigFile::parse("[nodes.a]\nauth_mode = \"none\"").is_err());
+    }
+
+    #[test]
+    fn test_environment_overrides() {
+        let config = ConfigFile::parse(EXAMPLE).unwrap();
+        let env: BTreeMap<&str, &str> = [
+            ("CALIMERO_NODE", "staging-eu"),
+            ("CALIMERO_REQUEST_TIMEOUT", "12.5"),
+            ("CALIMERO_NODE_STAGING_EU_URL", "https://other.example.com"),
+        ]
+        .into_iter()
+        .collect();
+        let overridden = config
+            .with_overrides(|name| env.get(name).map(|v| v.to_string()))
+            .unwrap();
+        assert_eq!(overridden.default_node.as_deref(), Some("staging-eu"));
+        assert_eq!(overridden.defaults.request_timeout, Some(12.5));
+        assert_eq!(
+            overridden.nodes["staging-eu"].url,
+            "https://other.example.com"
+        );
+        assert_eq!(overridden.nodes["node1"].url, "http://localhost:2428");
+
+        let bad =
+            config.with_overrides(|name| (name == CONNECT_TIMEOUT_ENV).then(|| "soon".to_string()));
+        assert!(bad.is_err());
+    }
+}
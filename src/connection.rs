@@ -1,59 +1,261 @@
 //! Python wrapper for ConnectionInfo
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use calimero_client::connection::ConnectionInfo;
 use calimero_client::CliAuthenticator;
 use pyo3::prelude::*;
+use pyo3::types::PyTuple;
 use tokio::runtime::Runtime;
 use url::Url;
 
 use crate::auth::PyAuthMode;
-use crate::storage::MeroboxFileStorage;
+use crate::cache::validate_namespace;
+use crate::error;
+use crate::log_bridge;
+use crate::node_address::NodeAddress;
+use crate::storage::{MeroboxFileStorage, PyMemoryStorage, PythonStorage};
+use crate::timeouts;
+use crate::transport::Transport;
 use crate::utils::json_to_python;
 
 /// Python wrapper for ConnectionInfo
 #[pyclass(name = "ConnectionInfo")]
 pub struct PyConnectionInfo {
     pub(crate) inner: Arc<ConnectionInfo<CliAuthenticator, MeroboxFileStorage>>,
+    pub(crate) storage: MeroboxFileStorage,
+    pub(crate) transport: Transport,
+    /// `ConnectionInfo` arguments recreating this connection in another
+    /// process, or why it cannot be.
+    pub(crate) pickle_args: Result<Py<PyTuple>, &'static str>,
     pub(crate) runtime: Arc<Runtime>,
 }
 
+/// Negotiate the transport of `url`.
+fn negotiate_transport(url: &Url) -> PyResult<Transport> {
+    let transport =
+        Transport::from_url(url).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+    if !transport.is_available() {
+        return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(format!(
+            "The {} transport is not available: Calimero nodes do not expose a gRPC API yet; use an http:// or https:// URL",
+            transport.as_str()
+        )));
+    }
+    Ok(transport)
+}
+
+impl PyConnectionInfo {
+    /// Drive `fut` on the connection runtime, turning panics into
+    /// `InternalError`; Ctrl-C drops it and raises `KeyboardInterrupt`.
+    fn run<F: std::future::Future>(&self, operation: &'static str, fut: F) -> PyResult<F::Output> {
+        error::catch_panic(
+            || {
+                serde_json::json!({
+                    "operation": operation,
+                    "api_url": self.inner.api_url.to_string(),
+                    "node_name": self.inner.node_name,
+                })
+            },
+            || timeouts::block_on(&self.runtime, fut),
+        )
+        .and_then(|outcome| outcome)
+    }
+
+    /// Connection backed by caller-provided storage (e.g. ephemeral).
+    pub(crate) fn with_storage(
+        api_url: &str,
+        node_name: Option<&str>,
+        storage: MeroboxFileStorage,
+    ) -> PyResult<Self> {
+        let runtime = Arc::new(
+            Runtime::new()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        );
+        let address =
+            NodeAddress::parse(api_url).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        let transport = negotiate_transport(address.url())?;
+        let node_name = node_name.or(address.node_name()).map(|s| s.to_string());
+        let connection = ConnectionInfo::new(
+            address.url().clone(),
+            node_name,
+            CliAuthenticator::new(),
+            storage.clone(),
+        );
+
+        Ok(Self {
+            inner: Arc::new(connection),
+            storage,
+            transport,
+            pickle_args: Err("its tokens only live in this process"),
+            runtime,
+        })
+    }
+}
+
 #[pymethods]
 impl PyConnectionInfo {
+    /// `anonymous=True` targets nodes with auth disabled (or public read
+    /// endpoints): no tokens are loaded or saved and no cache files are
+    /// created.
+    ///
+    /// `storage` picks where tokens are kept: `"file"` (the default,
+    /// `get_token_cache_dir()`), `"memory"` (in-process only; pass a
+    /// `MemoryStorage` to share one between connections), `"encrypted"` (the same files encrypted
+    /// with a key derived from `passphrase`, or a machine key in the OS keychain without one;
+    /// existing plaintext files are migrated on first load), `"keyring"` (the OS keychain, falling back
+    /// to files with a logged warning when none is available), or a Python
+    /// object implementing `ClientStorage` (`save_tokens`, `load_tokens`,
+    /// `remove_tokens`), e.g. one backed by Redis or Vault.
+    ///
+    /// `cache_dir` keeps the `"file"` or `"encrypted"` token files in that
+    /// directory instead of `get_token_cache_dir()` (which itself honors
+    /// `MEROBOX_CACHE_DIR`); `migrate_token_cache()` moves existing ones.
     #[new]
-    #[pyo3(signature = (api_url, node_name=None))]
-    pub fn new(api_url: &str, node_name: Option<&str>) -> PyResult<Self> {
+    #[pyo3(signature = (api_url, node_name=None, anonymous=false, storage=None, passphrase=None, cache_dir=None))]
+    pub fn new(
+        py: Python<'_>,
+        api_url: NodeAddress,
+        node_name: Option<&str>,
+        anonymous: bool,
+        storage: Option<Bound<'_, PyAny>>,
+        passphrase: Option<&str>,
+        cache_dir: Option<PathBuf>,
+    ) -> PyResult<Self> {
+        let node_name = node_name.or(api_url.node_name()).map(str::to_string);
+        if passphrase.is_some() && !storage.as_ref().is_some_and(|s| is_named(s, "encrypted")) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "passphrase is only used with storage=\"encrypted\"",
+            ));
+        }
+        if anonymous && storage.is_some() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "anonymous connections store no tokens; do not pass storage",
+            ));
+        }
+        if anonymous && cache_dir.is_some() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "anonymous connections store no tokens; do not pass cache_dir",
+            ));
+        }
+        let pickle_args = match &storage {
+            _ if passphrase.is_some() => Err("its passphrase is not written into pickles"),
+            Some(storage)
+                if is_named(storage, "memory") || storage.is_instance_of::<PyMemoryStorage>() =>
+            {
+                Err("its tokens only live in this process")
+            }
+            storage => Ok(PyTuple::new_bound(
+                py,
+                [
+                    api_url.to_string().into_py(py),
+                    node_name.clone().into_py(py),
+                    anonymous.into_py(py),
+                    storage.clone().into_py(py),
+                    py.None(),
+                    cache_dir.clone().into_py(py),
+                ],
+            )
+            .unbind()),
+        };
+
         let runtime = Arc::new(
             Runtime::new()
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
         );
 
-        let url = Url::parse(api_url).map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid URL: {}", e))
-        })?;
+        let transport = negotiate_transport(api_url.url())?;
+        let url = api_url.into_url();
 
         let authenticator = CliAuthenticator::new();
-        let storage = MeroboxFileStorage::new();
+        let storage = match storage {
+            Some(storage) => select_storage(py, &storage, passphrase, cache_dir.clone())?,
+            None if anonymous => MeroboxFileStorage::anonymous(),
+            None => MeroboxFileStorage::new(),
+        };
+        let storage = match cache_dir {
+            Some(_) if !storage.persists_to_disk() => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "cache_dir only applies to file or encrypted storage, not {}",
+                    storage.backend()
+                )));
+            }
+            Some(dir) => storage.with_cache_dir(dir),
+            None => storage,
+        };
 
-        let connection = ConnectionInfo::new(
-            url,
-            node_name.map(|s| s.to_string()),
-            authenticator,
-            storage,
-        );
+        let connection = ConnectionInfo::new(url, node_name, authenticator, storage.clone());
 
         Ok(Self {
             inner: Arc::new(connection),
+            storage,
+            transport,
+            pickle_args,
             runtime,
         })
     }
 
+    /// Pickle as the arguments that created the connection, so worker
+    /// processes build their own (tokens are read from storage on first
+    /// use). Connections keeping tokens in memory or with a passphrase
+    /// cannot be pickled.
+    pub fn __reduce__(slf: &Bound<'_, Self>) -> PyResult<(PyObject, PyObject)> {
+        let py = slf.py();
+        match &slf.borrow().pickle_args {
+            Ok(args) => Ok((slf.get_type().into_any().unbind(), args.clone_ref(py).into_any())),
+            Err(reason) => Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "cannot pickle this ConnectionInfo: {}; create the connection in the worker instead",
+                reason
+            ))),
+        }
+    }
+
     #[getter]
     pub fn api_url(&self) -> String {
         self.inner.api_url.to_string()
     }
 
+    /// Transport negotiated from the URL scheme (currently always `"http"`)
+    #[getter]
+    pub fn transport(&self) -> &'static str {
+        self.transport.as_str()
+    }
+
+    /// Whether the connection skips the token cache entirely
+    #[getter]
+    pub fn anonymous(&self) -> bool {
+        self.storage.is_anonymous()
+    }
+
+    /// Where tokens are kept: `"file"`, `"encrypted"`, `"memory"`,
+    /// `"keyring"`, `"python"`, `"ephemeral"` or `"anonymous"`
+    #[getter]
+    pub fn storage_backend(&self) -> &'static str {
+        self.storage.backend()
+    }
+
+    /// Directory holding the token files, for file and encrypted storage
+    #[getter]
+    pub fn cache_dir(&self) -> Option<String> {
+        self.storage
+            .persists_to_disk()
+            .then(|| self.storage.cache_dir().to_string_lossy().into_owned())
+    }
+
+    /// Delete the tokens every client created with `namespace` keeps in
+    /// this connection's storage, logging that tenant out of all nodes.
+    /// Returns how many were deleted. A Python `ClientStorage` must
+    /// implement `purge_namespace`; keyring storage cannot purge.
+    pub fn purge_namespace(&self, namespace: &str) -> PyResult<usize> {
+        validate_namespace(namespace).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        self.storage.purge_namespace(namespace).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to purge namespace '{}': {:#}",
+                namespace, e
+            ))
+        })
+    }
+
     #[getter]
     pub fn node_name(&self) -> Option<String> {
         self.inner.node_name.clone()
@@ -65,9 +267,10 @@ impl PyConnectionInfo {
         let path = path.to_string();
 
         Python::with_gil(|py| {
-            let result = self
-                .runtime
-                .block_on(async move { inner.get::<serde_json::Value>(&path).await });
+            let result = self.run(
+                "get",
+                async move { inner.get::<serde_json::Value>(&path).await },
+            )?;
 
             match result {
                 Ok(data) => Ok(json_to_python(py, &data)),
@@ -83,9 +286,10 @@ impl PyConnectionInfo {
     pub fn detect_auth_mode(&self) -> PyResult<PyAuthMode> {
         let inner = self.inner.clone();
 
-        let result = self
-            .runtime
-            .block_on(async move { inner.detect_auth_mode().await });
+        let result = self.run(
+            "detect_auth_mode",
+            async move { inner.detect_auth_mode().await },
+        )?;
 
         match result {
             Ok(mode) => Ok(PyAuthMode { mode }),
@@ -99,7 +303,61 @@ impl PyConnectionInfo {
 
 /// Create a new connection
 #[pyfunction]
-#[pyo3(signature = (api_url, node_name=None))]
-pub fn create_connection(api_url: &str, node_name: Option<&str>) -> PyResult<PyConnectionInfo> {
-    PyConnectionInfo::new(api_url, node_name)
+#[pyo3(signature = (api_url, node_name=None, anonymous=false, storage=None, passphrase=None, cache_dir=None))]
+pub fn create_connection(
+    py: Python<'_>,
+    api_url: NodeAddress,
+    node_name: Option<&str>,
+    anonymous: bool,
+    storage: Option<Bound<'_, PyAny>>,
+    passphrase: Option<&str>,
+    cache_dir: Option<PathBuf>,
+) -> PyResult<PyConnectionInfo> {
+    PyConnectionInfo::new(
+        py, api_url, node_name, anonymous, storage, passphrase, cache_dir,
+    )
+}
+
+/// Token storage for the `storage` argument: a backend name or a Python
+/// `ClientStorage` object.
+fn select_storage(
+    py: Python<'_>,
+    storage: &Bound<'_, PyAny>,
+    passphrase: Option<&str>,
+    cache_dir: Option<PathBuf>,
+) -> PyResult<MeroboxFileStorage> {
+    if let Ok(memory) = storage.downcast::<PyMemoryStorage>() {
+        return Ok(MeroboxFileStorage::memory(&memory.borrow()));
+    }
+    let Ok(name) = storage.extract::<String>() else {
+        return Ok(MeroboxFileStorage::python(PythonStorage::new(storage)?));
+    };
+    match name.as_str() {
+        "file" => Ok(MeroboxFileStorage::new()),
+        "memory" => Ok(MeroboxFileStorage::memory(&PyMemoryStorage::new())),
+        "encrypted" => MeroboxFileStorage::encrypted(passphrase, cache_dir).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to set up encrypted token storage: {:#}",
+                e
+            ))
+        }),
+        "keyring" => Ok(MeroboxFileStorage::keyring().unwrap_or_else(|| {
+            log_bridge::warning(
+                py,
+                "No OS keychain is available; storing tokens in the token cache directory instead",
+            );
+            MeroboxFileStorage::new()
+        })),
+        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid storage '{}'. Expected one of: file, encrypted, keyring, memory, or a ClientStorage object",
+            name
+        ))),
+    }
+}
+
+/// Whether the `storage` argument is the backend name `name`.
+fn is_named(storage: &Bound<'_, PyAny>, name: &str) -> bool {
+    storage
+        .extract::<String>()
+        .is_ok_and(|storage| storage == name)
 }
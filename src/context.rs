@@ -0,0 +1,197 @@
+//! Typed `Context` records returned by the context management calls.
+//!
+//! `create_context`, `get_context` and `list_contexts` return the node's
+//! JSON response by default. With `typed=True` they return [`PyContext`]
+//! objects instead: the record's common fields as attributes, the rest by
+//! key, and `to_dict()` for the record as the node sent it.
+
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+
+use crate::utils::json_to_python;
+
+/// The node's response with its `data` envelope removed.
+fn unwrap_data(response: &Value) -> &Value {
+    match response.get("data") {
+        Some(data) if !data.is_null() => data,
+        _ => response,
+    }
+}
+
+/// A context on the node.
+#[pyclass(name = "Context", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyContext {
+    record: Value,
+}
+
+impl PyContext {
+    /// Context of a `get_context` response.
+    pub fn from_response(response: &Value) -> Self {
+        Self {
+            record: unwrap_data(response).clone(),
+        }
+    }
+
+    /// Contexts of a `list_contexts` response.
+    pub fn list_from_response(response: &Value) -> Vec<Self> {
+        let data = unwrap_data(response);
+        data.get("contexts")
+            .or(Some(data))
+            .and_then(Value::as_array)
+            .map(|contexts| {
+                contexts
+                    .iter()
+                    .map(|record| Self {
+                        record: record.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Context just created by `create_context`: the ids the caller passed
+    /// plus the context id and member key in the response.
+    pub fn created(response: &Value, application_id: &str, group_id: &str) -> Self {
+        let data = unwrap_data(response);
+        Self {
+            record: json!({
+                "id": data.get("contextId").cloned().unwrap_or(Value::Null),
+                "applicationId": application_id,
+                "groupId": group_id,
+                "memberPublicKey": data.get("memberPublicKey").cloned().unwrap_or(Value::Null),
+            }),
+        }
+    }
+
+    /// First string field of the record among `names`.
+    fn field(&self, names: &[&str]) -> Option<String> {
+        names
+            .iter()
+            .find_map(|name| self.record.get(*name).and_then(Value::as_str))
+            .map(str::to_string)
+    }
+}
+
+#[pymethods]
+impl PyContext {
+    /// Context id
+    #[getter]
+    pub fn id(&self) -> String {
+        self.field(&["id", "contextId", "context_id"])
+            .unwrap_or_default()
+    }
+
+    /// Id of the application the context runs
+    #[getter]
+    pub fn application_id(&self) -> Option<String> {
+        self.field(&["applicationId", "application_id"])
+    }
+
+    /// Root hash of the context's state (`None` until known)
+    #[getter]
+    pub fn root_hash(&self) -> Option<String> {
+        self.field(&["rootHash", "root_hash"])
+    }
+
+    /// Id of the group the context belongs to, when reported
+    #[getter]
+    pub fn group_id(&self) -> Option<String> {
+        self.field(&["groupId", "group_id"])
+    }
+
+    /// Public key of this node's member identity (set by `create_context`)
+    #[getter]
+    pub fn member_public_key(&self) -> Option<String> {
+        self.field(&["memberPublicKey", "member_public_key"])
+    }
+
+    /// Field `key` of the record; `KeyError` if absent.
+    fn __getitem__(&self, py: Python<'_>, key: &str) -> PyResult<PyObject> {
+        match self.record.get(key) {
+            Some(value) => Ok(json_to_python(py, value)),
+            None => Err(PyErr::new::<pyo3::exceptions::PyKeyError, _>(
+                key.to_string(),
+            )),
+        }
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        self.record.get(key).is_some()
+    }
+
+    /// Field `key` of the record, or `default`.
+    #[pyo3(signature = (key, default=None))]
+    fn get(&self, py: Python<'_>, key: &str, default: Option<PyObject>) -> PyObject {
+        match self.record.get(key) {
+            Some(value) => json_to_python(py, value),
+            None => default.unwrap_or_else(|| py.None()),
+        }
+    }
+
+    /// The record as the node sent it.
+    fn to_dict(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.record)
+    }
+
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        other
+            .extract::<PyRef<'_, PyContext>>()
+            .is_ok_and(|other| other.record == self.record)
+    }
+
+    fn __hash__(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.id().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn __repr__(&self) -> String {
+        match self.application_id() {
+            Some(application_id) => format!(
+                "Context(id='{}', application_id='{}')",
+                self.id(),
+                application_id
+            ),
+            None => format!("Context(id='{}')", self.id()),
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records are found inside the `data` envelope or at the top level.
+    #[test]
+    fn test_from_responses() {
+        let context = PyContext::from_response(
+            &json!({"data": {"id": "abc", "applicationId": "app", "rootHash": "h"}}),
+        );
+        assert_eq!(context.id(), "abc");
+        assert_eq!(context.application_id().as_deref(), Some("app"));
+        assert_eq!(context.root_hash().as_deref(), Some("h"));
+        assert_eq!(context.group_id(), None);
+
+        let listed = PyContext::list_from_response(
+            &json!({"data": {"contexts": [{"id": "a"}, {"id": "b"}]}}),
+        );
+        let ids: Vec<_> = listed.iter().map(PyContext::id).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert!(PyContext::list_from_response(&json!({"data": {}})).is_empty());
+
+        let created = PyContext::created(
+            &json!({"data": {"contextId": "new", "memberPublicKey": "key"}}),
+            "app",
+            "group",
+        );
+        assert_eq!(created.id(), "new");
+        assert_eq!(created.group_id().as_deref(), Some("group"));
+        assert_eq!(created.member_public_key().as_deref(), Some("key"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Client-side serialization of mutating calls per context.
+//!
+//! Concurrent mutations of one context (from several threads, or a flush
+//! racing a direct call) can conflict on the node. Contexts opted in with
+//! `Client.serialize_context` get a FIFO lock: their mutating calls
+//! (`execute_function`, outbox flushes, application updates) run one at a
+//! time in arrival order. Reads never take the lock, and contexts that are
+//! not opted in are not affected.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::OwnedMutexGuard;
+
+/// Per-context FIFO locks for the contexts that opted in.
+#[derive(Debug, Default)]
+pub struct ContextGuards {
+    locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl ContextGuards {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serialize mutating calls on `context_id` (or stop, when `enabled` is
+    /// false). Calls already waiting keep their place.
+    pub fn set(&self, context_id: &str, enabled: bool) {
+        if let Ok(mut locks) = self.locks.lock() {
+            if enabled {
+                locks.entry(context_id.to_string()).or_default();
+            } else {
+                locks.remove(context_id);
+            }
+        }
+    }
+
+    pub fn is_serialized(&self, context_id: &str) -> bool {
+        self.locks
+            .lock()
+            .map(|locks| locks.contains_key(context_id))
+            .unwrap_or(false)
+    }
+
+    /// Serialized context IDs, sorted.
+    pub fn serialized(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .locks
+            .lock()
+            .map(|locks| locks.keys().cloned().collect())
+            .unwrap_or_default();
+        ids.sort();
+        ids
+    }
+
+    /// Wait for `context_id`'s turn; `None` when it is not serialized. The
+    /// turn lasts until the guard is dropped. Tokio's mutex is fair, so
+    /// waiters are served in the order they arrived.
+    pub async fn acquire(&self, context_id: &str) -> Option<OwnedMutexGuard<()>> {
+        let lock = self
+            .locks
+            .lock()
+            .ok()
+            .and_then(|locks| locks.get(context_id).cloned())?;
+        Some(lock.lock_owned().await)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serialized contexts run mutations one at a time, in arrival order.
+    #[tokio::test]
+    async fn test_serialized_context_is_fifo() {
+        let guards = Arc::new(ContextGuards::new());
+        guards.set("ctx", true);
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let first = guards.acquire("ctx").await;
+        assert!(first.is_some());
+        let mut waiters = Vec::new();
+        for n in 0..3 {
+            let guards = guards.clone();
+            let order = order.clone();
+            waiters.push(tokio::spawn(async move {
+                let _turn = guards.acquire("ctx").await;
+                order.lock().unwrap().push(n);
+            }));
+            tokio::task::yield_now().await;
+        }
+        assert!(order.lock().unwrap().is_empty());
+        drop(first);
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2]);
+    }
+
+    /// Contexts that did not opt in are never held up.
+    #[tokio::test]
+    async fn test_unserialized_context_is_free() {
+        let guards = ContextGuards::new();
+        assert!(guards.acquire("other").await.is_none());
+        guards.set("ctx", true);
+        guards.set("ctx", false);
+        assert!(guards.serialized().is_empty());
+    }
+}
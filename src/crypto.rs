@@ -0,0 +1,224 @@
+//! Symmetric envelope encryption used for client-side secrets at rest.
+//!
+//! Payloads are sealed with a fresh random data key (ChaCha20-Poly1305); the
+//! data key itself is sealed with the caller's 32-byte key encryption key and
+//! stored in the envelope header, so rotating or sharing the outer key never
+//! requires re-encrypting the payload format.
+//!
+//! ## Envelope layout (v1)
+//! ```text
+//! MAGIC (8) | key nonce (12) | sealed data key (32 + 16) | data nonce (12) | sealed payload (n + 16)
+//! ```
+
+use eyre::WrapErr;
+use std::num::NonZeroU32;
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use zeroize::Zeroizing;
+
+use crate::entropy;
+
+/// Marker identifying a v1 envelope.
+pub const ENVELOPE_MAGIC: &[u8; 8] = b"CLMRENC1";
+
+/// Length of key encryption keys and data keys.
+pub const KEY_LEN: usize = 32;
+
+/// Algorithm label recorded next to encrypted payloads.
+pub const ENVELOPE_ALGORITHM: &str = "chacha20poly1305-envelope-v1";
+
+/// PBKDF2-HMAC-SHA256 rounds for passphrase-derived keys.
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+const TAG_LEN: usize = 16;
+const SEALED_KEY_LEN: usize = KEY_LEN + TAG_LEN;
+const HEADER_LEN: usize = ENVELOPE_MAGIC.len() + NONCE_LEN + SEALED_KEY_LEN + NONCE_LEN;
+
+fn aead_key(key: &[u8]) -> eyre::Result<LessSafeKey> {
+    if key.len() != KEY_LEN {
+        eyre::bail!(
+            "Encryption key must be {} bytes, got {}",
+            KEY_LEN,
+            key.len()
+        );
+    }
+    let unbound = UnboundKey::new(&CHACHA20_POLY1305, key)
+        .map_err(|_| eyre::eyre!("Invalid encryption key"))?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+/// Whether `data` starts with the envelope marker.
+pub fn is_envelope(data: &[u8]) -> bool {
+    data.len() >= HEADER_LEN && data.starts_with(ENVELOPE_MAGIC)
+}
+
+/// Seal `plaintext` into a v1 envelope under `key`.
+pub fn seal(key: &[u8], plaintext: &[u8]) -> eyre::Result<Vec<u8>> {
+    let kek = aead_key(key)?;
+
+    let data_key: Zeroizing<[u8; KEY_LEN]> = Zeroizing::new(entropy::random_bytes()?);
+    let key_nonce: [u8; NONCE_LEN] = entropy::random_bytes()?;
+    let data_nonce: [u8; NONCE_LEN] = entropy::random_bytes()?;
+
+    let mut sealed_key = data_key.to_vec();
+    kek.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(key_nonce),
+        Aad::from(ENVELOPE_MAGIC),
+        &mut sealed_key,
+    )
+    .map_err(|_| eyre::eyre!("Failed to seal data key"))?;
+
+    let mut sealed = plaintext.to_vec();
+    aead_key(data_key.as_slice())?
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(data_nonce),
+            Aad::from(ENVELOPE_MAGIC),
+            &mut sealed,
+        )
+        .map_err(|_| eyre::eyre!("Failed to seal payload"))?;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + sealed.len());
+    out.extend_from_slice(ENVELOPE_MAGIC);
+    out.extend_from_slice(&key_nonce);
+    out.extend_from_slice(&sealed_key);
+    out.extend_from_slice(&data_nonce);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+/// Open a v1 envelope produced by [`seal`].
+///
+/// Fails if the data is not an envelope, was sealed under a different key,
+/// or has been tampered with.
+pub fn open(key: &[u8], envelope: &[u8]) -> eyre::Result<Vec<u8>> {
+    if !is_envelope(envelope) {
+        eyre::bail!("Data is not an encrypted envelope");
+    }
+    let kek = aead_key(key)?;
+
+    let (_, rest) = envelope.split_at(ENVELOPE_MAGIC.len());
+    let (key_nonce, rest) = rest.split_at(NONCE_LEN);
+    let (sealed_key, rest) = rest.split_at(SEALED_KEY_LEN);
+    let (data_nonce, sealed) = rest.split_at(NONCE_LEN);
+
+    let mut data_key = Zeroizing::new(sealed_key.to_vec());
+    let data_key = kek
+        .open_in_place(
+            Nonce::try_assume_unique_for_key(key_nonce)
+                .map_err(|_| eyre::eyre!("Malformed envelope"))?,
+            Aad::from(ENVELOPE_MAGIC),
+            &mut data_key,
+        )
+        .map_err(|_| eyre::eyre!("Failed to decrypt: wrong key or corrupted data"))?;
+
+    let mut payload = sealed.to_vec();
+    let plaintext = aead_key(data_key)?
+        .open_in_place(
+            Nonce::try_assume_unique_for_key(data_nonce)
+                .map_err(|_| eyre::eyre!("Malformed envelope"))?,
+            Aad::from(ENVELOPE_MAGIC),
+            &mut payload,
+        )
+        .map_err(|_| eyre::eyre!("Failed to decrypt: wrong key or corrupted data"))?;
+    let len = plaintext.len();
+    payload.truncate(len);
+    Ok(payload)
+}
+
+/// Derive a key encryption key from `passphrase` and `salt`
+/// (PBKDF2-HMAC-SHA256).
+pub fn derive_key(passphrase: &[u8], salt: &[u8]) -> Zeroizing<[u8; KEY_LEN]> {
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).expect("iterations are non-zero");
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase,
+        key.as_mut_slice(),
+    );
+    key
+}
+
+/// Decode a key given as raw bytes or as a 64-character hex string.
+///
+/// The decoded key is wiped from memory when dropped.
+pub fn decode_key(raw: &[u8]) -> eyre::Result<Zeroizing<Vec<u8>>> {
+    if raw.len() == KEY_LEN {
+        return Ok(Zeroizing::new(raw.to_vec()));
+    }
+    let text = std::str::from_utf8(raw).wrap_err("Encryption key must be 32 bytes or hex")?;
+    let key = Zeroizing::new(
+        hex::decode(text.trim()).wrap_err("Encryption key must be 32 bytes or hex")?,
+    );
+    if key.len() != KEY_LEN {
+        eyre::bail!(
+            "Encryption key must be {} bytes, got {}",
+            KEY_LEN,
+            key.len()
+        );
+    }
+    Ok(key)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; KEY_LEN] = [42u8; KEY_LEN];
+
+    /// Sealing then opening returns the original payload.
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let sealed = seal(&KEY, b"secret payload").unwrap();
+        assert!(is_envelope(&sealed));
+        assert_eq!(open(&KEY, &sealed).unwrap(), b"secret payload");
+    }
+
+    /// Each seal uses fresh randomness.
+    #[test]
+    fn test_seal_is_randomized() {
+        assert_ne!(seal(&KEY, b"x").unwrap(), seal(&KEY, b"x").unwrap());
+    }
+
+    /// A different key cannot open the envelope.
+    #[test]
+    fn test_open_wrong_key() {
+        let sealed = seal(&KEY, b"secret").unwrap();
+        assert!(open(&[7u8; KEY_LEN], &sealed).is_err());
+    }
+
+    /// Flipping a payload bit is detected.
+    #[test]
+    fn test_open_tampered() {
+        let mut sealed = seal(&KEY, b"secret").unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(open(&KEY, &sealed).is_err());
+    }
+
+    /// Derived keys depend on both passphrase and salt.
+    #[test]
+    fn test_derive_key() {
+        let key = derive_key(b"passphrase", b"salt");
+        assert_eq!(*key, *derive_key(b"passphrase", b"salt"));
+        assert_ne!(*key, *derive_key(b"passphrase", b"pepper"));
+        assert_ne!(*key, *derive_key(b"other", b"salt"));
+    }
+
+    /// Keys are accepted as raw bytes or hex.
+    #[test]
+    fn test_decode_key() {
+        assert_eq!(*decode_key(&KEY).unwrap(), KEY.to_vec());
+        assert_eq!(
+            *decode_key(hex::encode(KEY).as_bytes()).unwrap(),
+            KEY.to_vec()
+        );
+        assert!(decode_key(b"short").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! Client-side deduplication of repeated mutating calls.
+//!
+//! A UI that double-submits makes its Python backend fire the same
+//! `execute_function` twice within milliseconds, and the node applies both.
+//! With `dedup_window_ms` set, an identical call (same context, method,
+//! arguments and root-hash options) started within the window of the first
+//! one is not sent: it waits for the first call and returns its result, or
+//! its error. Off by default, since a deliberately repeated call inside the
+//! window is folded too.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+use serde_json::Value;
+
+type SharedCall = Shared<BoxFuture<'static, Result<Value, Arc<eyre::Report>>>>;
+
+/// Calls started within the window, by key.
+#[derive(Default)]
+pub struct DedupWindow {
+    window: Option<Duration>,
+    calls: Mutex<HashMap<String, (Instant, SharedCall)>>,
+    suppressed: AtomicU64,
+}
+
+impl DedupWindow {
+    /// Window of `window_ms` milliseconds; `None` or `0` disables it.
+    pub fn new(window_ms: Option<u64>) -> Self {
+        Self {
+            window: window_ms.filter(|&ms| ms > 0).map(Duration::from_millis),
+            ..Self::default()
+        }
+    }
+
+    pub fn window(&self) -> Option<Duration> {
+        self.window
+    }
+
+    /// Calls answered with an earlier call's result.
+    pub fn suppressed(&self) -> u64 {
+        self.suppressed.load(Ordering::Relaxed)
+    }
+
+    /// Key identifying a call by everything that affects what it does.
+    pub fn key(parts: &[&str]) -> String {
+        parts.join("\0")
+    }
+
+    /// Run `call` under `key`, or join the identical call started less than
+    /// the window ago. Without a window, `call` simply runs.
+    pub fn run<F>(
+        &self,
+        key: String,
+        call: F,
+    ) -> impl std::future::Future<Output = eyre::Result<Value>> + Send + 'static
+    where
+        F: std::future::Future<Output = eyre::Result<Value>> + Send + 'static,
+    {
+        let shared = match self.window {
+            None => call.map(|result| result.map_err(Arc::new)).boxed().shared(),
+            Some(window) => self.join_or_start(key, window, call),
+        };
+        shared.map(|result| result.map_err(|e| eyre::eyre!("{:#}", e)))
+    }
+
+    fn join_or_start<F>(&self, key: String, window: Duration, call: F) -> SharedCall
+    where
+        F: std::future::Future<Output = eyre::Result<Value>> + Send + 'static,
+    {
+        let start = || call.map(|result| result.map_err(Arc::new)).boxed().shared();
+        let Ok(mut calls) = self.calls.lock() else {
+            return start();
+        };
+        let now = Instant::now();
+        calls.retain(|_, (started, _)| now.duration_since(*started) < window);
+        if let Some((_, shared)) = calls.get(&key) {
+            self.suppressed.fetch_add(1, Ordering::Relaxed);
+            return shared.clone();
+        }
+        let shared = start();
+        calls.insert(key, (now, shared.clone()));
+        shared
+    }
+
+    /// `{windowMs, suppressed}`
+    pub fn stats(&self) -> Value {
+        serde_json::json!({
+            "windowMs": self.window.map(|window| window.as_millis() as u64),
+            "suppressed": self.suppressed(),
+        })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn counted(sent: &Arc<AtomicUsize>) -> BoxFuture<'static, eyre::Result<Value>> {
+        let sent = sent.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(Value::from(sent.fetch_add(1, Ordering::SeqCst)))
+        }
+        .boxed()
+    }
+
+    /// Identical calls inside the window share the first call's result.
+    #[tokio::test]
+    async fn test_duplicates_share_result() {
+        let dedup = DedupWindow::new(Some(1_000));
+        let sent = Arc::new(AtomicUsize::new(0));
+        let first = dedup.run(DedupWindow::key(&["ctx", "add", "{}"]), counted(&sent));
+        let second = dedup.run(DedupWindow::key(&["ctx", "add", "{}"]), counted(&sent));
+        let other = dedup.run(
+            DedupWindow::key(&["ctx", "add", "{\"n\":1}"]),
+            counted(&sent),
+        );
+        let (first, second, other) = tokio::join!(first, second, other);
+        assert_eq!(first.unwrap(), second.unwrap());
+        assert!(other.is_ok());
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+        assert_eq!(dedup.suppressed(), 1);
+    }
+
+    /// Without a window, or once it has passed, calls go out again.
+    #[tokio::test]
+    async fn test_window_expires() {
+        let sent = Arc::new(AtomicUsize::new(0));
+        let off = DedupWindow::new(None);
+        off.run("k".to_string(), counted(&sent)).await.unwrap();
+        off.run("k".to_string(), counted(&sent)).await.unwrap();
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+
+        let dedup = DedupWindow::new(Some(5));
+        dedup.run("k".to_string(), counted(&sent)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        dedup.run("k".to_string(), counted(&sent)).await.unwrap();
+        assert_eq!(sent.load(Ordering::SeqCst), 4);
+        assert_eq!(dedup.suppressed(), 0);
+    }
+
+    /// A duplicate gets the first call's error, chain included.
+    #[tokio::test]
+    async fn test_duplicates_share_error() {
+        let dedup = DedupWindow::new(Some(1_000));
+        let failing = || async { Err(eyre::eyre!("root").wrap_err("outer")) };
+        let first = dedup.run("k".to_string(), failing());
+        let second = dedup.run("k".to_string(), failing());
+        let (first, second) = tokio::join!(first, second);
+        assert_eq!(format!("{:#}", first.unwrap_err()), "outer: root");
+        assert_eq!(format!("{:#}", second.unwrap_err()), "outer: root");
+    }
+}
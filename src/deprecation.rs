@@ -0,0 +1,98 @@
+//! Deprecation table for renamed Python APIs.
+//!
+//! When a `Client` method is renamed, add a row to [`CLIENT_DEPRECATIONS`]
+//! instead of keeping a hand-written alias. `Client.__getattr__` consults the
+//! table for names that no longer exist, emits a `DeprecatedEndpointWarning`
+//! (a `DeprecationWarning`) spelling out the replacement call and the
+//! release that drops the old name, and returns the new method.
+//!
+//! Remove a row once its `removal` version ships.
+
+/// One renamed API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deprecation {
+    /// Name callers still use.
+    pub old: &'static str,
+    /// Attribute the old name forwards to.
+    pub new: &'static str,
+    /// Replacement call syntax shown in the warning.
+    pub usage: &'static str,
+    /// Release that deprecated the old name.
+    pub since: &'static str,
+    /// Release that will remove the old name.
+    pub removal: &'static str,
+}
+
+impl Deprecation {
+    /// Warning text shown to callers of the old name.
+    pub fn message(&self, class: &str) -> String {
+        format!(
+            "{class}.{} is deprecated since {} and will be removed in {}; use {} instead",
+            self.old, self.since, self.removal, self.usage
+        )
+    }
+}
+
+/// Renamed `Client` methods.
+///
+/// Methods removed outright (e.g. `nest_group`/`unnest_group`, superseded by
+/// `reparent_group` with different semantics) are not listed: there is no
+/// call they could forward to.
+pub const CLIENT_DEPRECATIONS: &[Deprecation] = &[
+    // Older merobox releases still call it (calimero-network/core#2256).
+    Deprecation {
+        old: "set_default_visibility",
+        new: "set_subgroup_visibility",
+        usage: "client.set_subgroup_visibility(group_id, visibility)",
+        since: "0.6.20",
+        removal: "0.8.0",
+    },
+];
+
+/// Row for `name` in `table`, if it is a deprecated alias.
+pub fn lookup(table: &'static [Deprecation], name: &str) -> Option<&'static Deprecation> {
+    table.iter().find(|d| d.old == name)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &[Deprecation] = &[Deprecation {
+        old: "get_contexts",
+        new: "list_contexts",
+        usage: "client.list_contexts()",
+        since: "0.6.20",
+        removal: "0.8.0",
+    }];
+
+    /// Only listed names resolve to a deprecation row.
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup(TABLE, "get_contexts").unwrap().new, "list_contexts");
+        assert!(lookup(TABLE, "list_contexts").is_none());
+    }
+
+    /// The warning names the replacement call and removal version.
+    #[test]
+    fn test_message() {
+        assert_eq!(
+            TABLE[0].message("Client"),
+            "Client.get_contexts is deprecated since 0.6.20 and will be removed in 0.8.0; \
+             use client.list_contexts() instead"
+        );
+    }
+
+    /// Every shipped row forwards to a distinct name.
+    #[test]
+    fn test_client_table_is_well_formed() {
+        for row in CLIENT_DEPRECATIONS {
+            assert_ne!(row.old, row.new);
+            assert!(row.usage.contains(row.new));
+        }
+    }
+}
@@ -0,0 +1,353 @@
+//! Redeploy a WASM application whenever its build output changes.
+//!
+//! `Client.watch_and_deploy(wasm_path, context_id)` returns a
+//! [`PyDevDeployer`] that watches the file (through `notify`, so without
+//! polling) and, once writes to it have settled for `debounce` seconds,
+//! installs it with `install_dev_application` and — given an executor —
+//! moves the context to the new application with
+//! `update_context_application`:
+//!
+//! ```python
+//! with client.watch_and_deploy("target/wasm32-unknown-unknown/app.wasm",
+//!                              ctx, executor_public_key=me) as deployer:
+//!     ...  # cargo build in another terminal; ctx follows every build
+//! ```
+//!
+//! The directory is watched rather than the file, since build tools
+//! usually replace the file instead of writing it in place. A file whose
+//! SHA-256 did not change since the last deploy is not installed again.
+//! Failed deploys (a half-written file, a node that is down) are recorded
+//! and reported to `on_deploy` but do not stop the watch: the next build
+//! gets its turn.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::blob;
+use crate::callbacks::Callback;
+use crate::client::PyClient;
+use crate::utils::{json_to_python, python_to_json};
+
+/// Default time writes must have settled before a deploy (seconds).
+pub const DEFAULT_DEBOUNCE_SECS: f64 = 0.5;
+
+enum Signal {
+    Changed,
+    Deploy { force: bool },
+    Stop,
+}
+
+/// What a deploy installs and where.
+pub struct Target {
+    pub path: PathBuf,
+    pub context_id: String,
+    pub executor_public_key: Option<String>,
+    pub allow_dangerous: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    deploys: AtomicU64,
+    failures: AtomicU64,
+    last: Mutex<Option<Value>>,
+}
+
+/// Background redeploys of a WASM file into a context.
+#[pyclass(name = "DevDeployer")]
+pub struct PyDevDeployer {
+    path: PathBuf,
+    context_id: String,
+    shared: Arc<Shared>,
+    worker: Mutex<Option<(mpsc::Sender<Signal>, JoinHandle<()>)>>,
+}
+
+impl PyDevDeployer {
+    /// Start watching `target.path`; the first deploy runs at once when
+    /// `deploy_on_start`.
+    pub fn start(
+        client: Py<PyClient>,
+        target: Target,
+        debounce: Duration,
+        on_deploy: Option<Callback>,
+        deploy_on_start: bool,
+    ) -> PyResult<Self> {
+        let path = std::fs::canonicalize(&target.path).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyFileNotFoundError, _>(format!(
+                "Cannot watch {:?}: {}",
+                target.path, e
+            ))
+        })?;
+        let (Some(directory), Some(file_name)) = (path.parent(), path.file_name()) else {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Cannot watch {:?}: not a file",
+                path
+            )));
+        };
+        let file_name = file_name.to_os_string();
+
+        let (signals, receiver) = mpsc::channel();
+        let changes = signals.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                let touched = !matches!(event.kind, EventKind::Access(_))
+                    && event
+                        .paths
+                        .iter()
+                        .any(|path| path.file_name() == Some(file_name.as_os_str()));
+                if touched {
+                    let _ = changes.send(Signal::Changed);
+                }
+            })
+            .map_err(|e| watch_error(&path, e))?;
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(|e| watch_error(&path, e))?;
+
+        if deploy_on_start {
+            let _ = signals.send(Signal::Deploy { force: false });
+        }
+        let shared = Arc::new(Shared::default());
+        let context_id = target.context_id.clone();
+        let worker = Worker {
+            client,
+            target: Target {
+                path: path.clone(),
+                ..target
+            },
+            on_deploy,
+            shared: Arc::clone(&shared),
+            last_hash: None,
+        };
+        let handle = std::thread::Builder::new()
+            .name("calimero-dev-deploy".to_string())
+            .spawn(move || worker.run(watcher, receiver, debounce))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        Ok(Self {
+            path,
+            context_id,
+            shared,
+            worker: Mutex::new(Some((signals, handle))),
+        })
+    }
+
+    fn send(&self, signal: Signal) -> bool {
+        self.worker
+            .lock()
+            .ok()
+            .and_then(|worker| {
+                worker
+                    .as_ref()
+                    .map(|(signals, _)| signals.send(signal).is_ok())
+            })
+            .unwrap_or(false)
+    }
+}
+
+fn watch_error(path: &Path, e: notify::Error) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("Cannot watch {:?}: {}", path, e))
+}
+
+struct Worker {
+    client: Py<PyClient>,
+    target: Target,
+    on_deploy: Option<Callback>,
+    shared: Arc<Shared>,
+    /// SHA-256 of the file last deployed successfully.
+    last_hash: Option<String>,
+}
+
+impl Worker {
+    /// Deploy on signals until stopped; `_watcher` lives as long as the
+    /// loop, its events arriving as `Signal::Changed`.
+    fn run(mut self, _watcher: impl Watcher, signals: mpsc::Receiver<Signal>, debounce: Duration) {
+        while let Ok(signal) = signals.recv() {
+            let force = match signal {
+                Signal::Stop => return,
+                Signal::Deploy { force } => force,
+                Signal::Changed => loop {
+                    match signals.recv_timeout(debounce) {
+                        Ok(Signal::Changed) => continue,
+                        Ok(Signal::Deploy { force }) => break force,
+                        Ok(Signal::Stop) | Err(RecvTimeoutError::Disconnected) => return,
+                        Err(RecvTimeoutError::Timeout) => break false,
+                    }
+                },
+            };
+            self.deploy(force);
+        }
+    }
+
+    fn deploy(&mut self, force: bool) {
+        let hash = match std::fs::read(&self.target.path) {
+            Ok(data) => hex::encode(Sha256::digest(&data)),
+            Err(e) => {
+                let error = format!("Failed to read {:?}: {}", self.target.path, e);
+                self.finish(json!({"error": error}));
+                return;
+            }
+        };
+        if !force && self.last_hash.as_deref() == Some(hash.as_str()) {
+            return;
+        }
+        let outcome = Python::with_gil(|py| self.install(py));
+        let mut record = match outcome {
+            Ok((application_id, migrated)) => {
+                self.last_hash = Some(hash.clone());
+                json!({"applicationId": application_id, "migrated": migrated})
+            }
+            Err(e) => json!({"error": e.to_string()}),
+        };
+        record["hash"] = hash.into();
+        self.finish(record);
+    }
+
+    /// Install the file and move the context to it; returns the new
+    /// application id and whether the context was moved.
+    fn install(&self, py: Python<'_>) -> PyResult<(String, bool)> {
+        let client = self.client.borrow(py);
+        let path = self.target.path.to_string_lossy();
+        let installed = client.install_dev_application(&path, None, self.target.allow_dangerous)?;
+        let installed = python_to_json(installed.bind(py))?;
+        let application_id = blob::find_string_field(&installed, "applicationId")
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "install_dev_application returned no application id: {}",
+                    installed
+                ))
+            })?
+            .to_string();
+        let Some(executor) = &self.target.executor_public_key else {
+            return Ok((application_id, false));
+        };
+        client.update_context_application(&self.target.context_id, &application_id, executor)?;
+        Ok((application_id, true))
+    }
+
+    /// Record a deploy attempt and report it to `on_deploy`.
+    fn finish(&self, mut record: Value) {
+        let failed = record.get("error").is_some();
+        record["contextId"] = self.target.context_id.clone().into();
+        record["at"] = chrono::Utc::now().timestamp_millis().into();
+        if let Some(fields) = record.as_object_mut() {
+            fields.entry("error").or_insert(Value::Null);
+        }
+        let counter = match failed {
+            true => &self.shared.failures,
+            false => &self.shared.deploys,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut last) = self.shared.last.lock() {
+            *last = Some(record.clone());
+        }
+        let Some(on_deploy) = &self.on_deploy else {
+            return;
+        };
+        Python::with_gil(|py| {
+            if let Some(callback) = on_deploy.upgrade(py) {
+                if let Err(e) = callback.call1(py, (json_to_python(py, &record),)) {
+                    e.print(py);
+                }
+            }
+        });
+    }
+}
+
+#[pymethods]
+impl PyDevDeployer {
+    /// Deploy now, even if the file did not change since the last deploy.
+    /// Runs on the watcher thread; returns whether it was queued.
+    pub fn deploy_now(&self) -> bool {
+        self.send(Signal::Deploy { force: true })
+    }
+
+    /// Stop watching, after a deploy in progress finished.
+    pub fn stop(&self, py: Python<'_>) {
+        let Some((signals, handle)) = self.worker.lock().ok().and_then(|mut w| w.take()) else {
+            return;
+        };
+        let _ = signals.send(Signal::Stop);
+        py.allow_threads(|| {
+            let _ = handle.join();
+        });
+    }
+
+    #[getter]
+    pub fn running(&self) -> bool {
+        self.worker
+            .lock()
+            .is_ok_and(|worker| worker.as_ref().is_some_and(|(_, h)| !h.is_finished()))
+    }
+
+    /// The watched file, as an absolute path
+    #[getter]
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    #[getter]
+    pub fn context_id(&self) -> String {
+        self.context_id.clone()
+    }
+
+    /// Number of successful deploys
+    #[getter]
+    pub fn deploys(&self) -> u64 {
+        self.shared.deploys.load(Ordering::Relaxed)
+    }
+
+    /// Number of failed deploys
+    #[getter]
+    pub fn failures(&self) -> u64 {
+        self.shared.failures.load(Ordering::Relaxed)
+    }
+
+    /// The last deploy attempt, as passed to `on_deploy`: `{contextId,
+    /// hash, applicationId, migrated, error, at}`; `None` before the first
+    #[getter]
+    pub fn last_deploy(&self, py: Python<'_>) -> Option<PyObject> {
+        let last = self.shared.last.lock().ok()?.clone()?;
+        Some(json_to_python(py, &last))
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, py: Python<'_>, _args: &Bound<'_, PyTuple>) -> bool {
+        self.stop(py);
+        false
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "DevDeployer(path={:?}, context_id='{}', deploys={}, running={})",
+            self.path,
+            self.context_id,
+            self.deploys(),
+            if self.running() { "True" } else { "False" }
+        )
+    }
+}
+
+impl Drop for PyDevDeployer {
+    fn drop(&mut self) {
+        // Without the GIL to release, only signal; the thread ends on its own.
+        if let Some((signals, _)) = self.worker.lock().ok().and_then(|mut w| w.take()) {
+            let _ = signals.send(Signal::Stop);
+        }
+    }
+}
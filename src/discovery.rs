@@ -0,0 +1,340 @@
+//! Discovery of Calimero nodes running on this machine, behind
+//! `discover_nodes()` and a zero-configuration `connect()`.
+//!
+//! Development setups start nodes on well-known local ports, usually through
+//! merobox, and scripts should not need their URLs spelled out. Candidates
+//! come from two places:
+//!
+//! - every port of a range (default [`DEFAULT_PORTS`]) on a local host;
+//! - merobox's workflow state, a JSON file naming the nodes it started
+//!   (`state_file=`, or `$MEROBOX_STATE_FILE`). Nodes are read from a
+//!   `{name: node}` map or a list, optionally under `nodes`; a node is a
+//!   URL string or an object with a URL (`url`, `api_url`, `rpc_url`) or
+//!   an RPC port (`rpc_port`, `port`) and optionally a `name`.
+//!
+//! Each candidate gets one unauthenticated health request (see
+//! [`crate::node_status`]), all at once; the ones that answer healthy are
+//! returned with the version they advertise, local ports first in port
+//! order.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+use tokio::runtime::Runtime;
+use url::Url;
+
+use crate::node_status::{self, PyNodeHealth};
+use crate::utils::json_to_python;
+
+/// Ports scanned by default: the RPC ports merobox gives its first nodes.
+pub const DEFAULT_PORTS: RangeInclusive<u16> = 2428..=2440;
+
+/// Host scanned by default.
+pub const DEFAULT_HOST: &str = "127.0.0.1";
+
+/// Default limit on each health probe, in seconds.
+pub const DEFAULT_PROBE_TIMEOUT_SECS: f64 = 0.5;
+
+/// Environment variable naming merobox's workflow state file.
+pub const STATE_FILE_ENV: &str = "MEROBOX_STATE_FILE";
+
+/// Longest port range scanned in one call.
+const MAX_PORTS: usize = 1024;
+
+/// URL fields of a node in the state file, in order of preference.
+const URL_FIELDS: &[&str] = &["url", "api_url", "apiUrl", "rpc_url", "rpcUrl"];
+
+/// Port fields of a node in the state file, in order of preference.
+const PORT_FIELDS: &[&str] = &["rpc_port", "rpcPort", "port"];
+
+/// Where a candidate endpoint came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Source {
+    Port,
+    Merobox,
+}
+
+impl Source {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Port => "port",
+            Self::Merobox => "merobox",
+        }
+    }
+}
+
+/// An endpoint that may have a node behind it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub url: Url,
+    pub name: Option<String>,
+    pub source: Source,
+}
+
+/// A candidate that answered healthy.
+#[derive(Debug, Clone)]
+pub struct Discovered {
+    pub candidate: Candidate,
+    pub health: PyNodeHealth,
+}
+
+impl Discovered {
+    /// Name for the node's connection: the state file's, else `host-port`.
+    pub fn node_name(&self) -> String {
+        let url = &self.candidate.url;
+        self.candidate.name.clone().unwrap_or_else(|| {
+            format!(
+                "{}-{}",
+                url.host_str().unwrap_or(DEFAULT_HOST),
+                url.port_or_known_default().unwrap_or_default()
+            )
+        })
+    }
+
+    /// `{url, name, source, version, latencyMs}`.
+    pub fn to_json(&self) -> Value {
+        let health = self.health.to_json();
+        json!({
+            "url": self.candidate.url.as_str(),
+            "name": self.node_name(),
+            "source": self.candidate.source.as_str(),
+            "version": health["version"],
+            "latencyMs": health["latencyMs"],
+        })
+    }
+}
+
+/// One candidate per port of `ports` on `host`.
+pub fn port_candidates(host: &str, ports: &[u16]) -> eyre::Result<Vec<Candidate>> {
+    ports
+        .iter()
+        .map(|port| {
+            let url = Url::parse(&format!("http://{}:{}/", host, port))
+                .wrap_err_with(|| format!("Invalid host {:?}", host))?;
+            Ok(Candidate {
+                url,
+                name: None,
+                source: Source::Port,
+            })
+        })
+        .collect()
+}
+
+/// URL of a node entry of the state file.
+fn entry_url(entry: &Value) -> Option<Url> {
+    let text = match entry {
+        Value::String(url) => url.clone(),
+        Value::Object(_) => match URL_FIELDS
+            .iter()
+            .find_map(|field| entry.get(*field).and_then(Value::as_str))
+        {
+            Some(url) => url.to_string(),
+            None => {
+                let port = PORT_FIELDS
+                    .iter()
+                    .find_map(|field| entry.get(*field).and_then(Value::as_u64))?;
+                format!("http://{}:{}/", DEFAULT_HOST, u16::try_from(port).ok()?)
+            }
+        },
+        _ => return None,
+    };
+    Url::parse(&text).ok()
+}
+
+/// Candidates named by merobox workflow `state` (see the module docs).
+pub fn state_candidates(state: &Value) -> Vec<Candidate> {
+    let nodes = state.get("nodes").unwrap_or(state);
+    let entries: Vec<(Option<String>, &Value)> = match nodes {
+        Value::Object(map) => map
+            .iter()
+            .map(|(name, entry)| (Some(name.clone()), entry))
+            .collect(),
+        Value::Array(list) => list.iter().map(|entry| (None, entry)).collect(),
+        _ => Vec::new(),
+    };
+    entries
+        .into_iter()
+        .filter_map(|(key, entry)| {
+            let name = entry
+                .get("name")
+                .or_else(|| entry.get("node_name"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .or(key);
+            Some(Candidate {
+                url: entry_url(entry)?,
+                name,
+                source: Source::Merobox,
+            })
+        })
+        .collect()
+}
+
+/// Candidates of the state file at `path`; none when it does not exist.
+pub fn read_state(path: &Path) -> eyre::Result<Vec<Candidate>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).wrap_err_with(|| format!("Failed to read {:?}", path)),
+    };
+    let state: Value = serde_json::from_str(&text)
+        .wrap_err_with(|| format!("Invalid merobox state file {:?}", path))?;
+    Ok(state_candidates(&state))
+}
+
+/// `candidates` without repeated URLs; a named (state file) entry wins over
+/// a scanned port, and the result is ordered by source, then port.
+pub fn dedup(candidates: Vec<Candidate>) -> Vec<Candidate> {
+    let mut by_url: BTreeMap<String, Candidate> = BTreeMap::new();
+    for candidate in candidates {
+        let key = candidate.url.as_str().trim_end_matches('/').to_string();
+        match by_url.get(&key) {
+            Some(known) if known.name.is_some() || candidate.name.is_none() => {}
+            _ => {
+                by_url.insert(key, candidate);
+            }
+        }
+    }
+    let mut candidates: Vec<Candidate> = by_url.into_values().collect();
+    candidates.sort_by_key(|c| (c.source, c.url.port_or_known_default(), c.url.to_string()));
+    candidates
+}
+
+/// Probe every candidate at once, keeping those that answer healthy.
+pub async fn probe(candidates: Vec<Candidate>, timeout: Duration) -> Vec<Discovered> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(timeout)
+        .build()
+        .unwrap_or_default();
+    let outcomes = futures_util::future::join_all(candidates.iter().map(|candidate| {
+        tokio::time::timeout(timeout, node_status::check(&client, &candidate.url))
+    }))
+    .await;
+    candidates
+        .into_iter()
+        .zip(outcomes)
+        .filter_map(|(candidate, outcome)| {
+            let health = outcome.ok().filter(PyNodeHealth::healthy)?;
+            Some(Discovered { candidate, health })
+        })
+        .collect()
+}
+
+/// Healthy nodes among `ports` on `host` and the nodes of `state_file`
+/// (default: `$MEROBOX_STATE_FILE`), probed for at most `timeout` each.
+pub fn discover(
+    host: &str,
+    ports: &[u16],
+    state_file: Option<PathBuf>,
+    timeout: Duration,
+) -> eyre::Result<Vec<Discovered>> {
+    if ports.len() > MAX_PORTS {
+        eyre::bail!("at most {} ports can be scanned at once", MAX_PORTS);
+    }
+    let mut candidates = port_candidates(host, ports)?;
+    let state_file = state_file.or_else(|| {
+        std::env::var_os(STATE_FILE_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    });
+    if let Some(path) = state_file {
+        candidates.extend(read_state(&path)?);
+    }
+    let runtime = Runtime::new().wrap_err("Failed to start the discovery runtime")?;
+    Ok(runtime.block_on(probe(dedup(candidates), timeout)))
+}
+
+/// Ports of `ports=`: `None` for [`DEFAULT_PORTS`], else a `(first, last)`
+/// tuple or a sequence of ports such as a `range`.
+pub fn ports_arg(ports: Option<&Bound<'_, PyAny>>) -> PyResult<Vec<u16>> {
+    let Some(ports) = ports.filter(|ports| !ports.is_none()) else {
+        return Ok(DEFAULT_PORTS.collect());
+    };
+    if let Ok((first, last)) = ports.extract::<(u16, u16)>() {
+        if first > last {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "port range must be (first, last) with first <= last",
+            ));
+        }
+        return Ok((first..=last).collect());
+    }
+    ports.extract::<Vec<u16>>().map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+            "ports must be a range, a (first, last) tuple or a list of ports",
+        )
+    })
+}
+
+/// Calimero nodes running locally, as `[{url, name, source, version,
+/// latencyMs}]`: healthy nodes on `ports` of `host` (a `range`, a
+/// `(first, last)` tuple or a list; default 2428-2440) and those named in
+/// merobox's workflow state (`state_file`, default `$MEROBOX_STATE_FILE`).
+/// Each endpoint gets one health request of at most `timeout` seconds.
+#[pyfunction]
+#[pyo3(signature = (ports=None, host=DEFAULT_HOST, state_file=None, timeout=DEFAULT_PROBE_TIMEOUT_SECS))]
+pub fn discover_nodes(
+    py: Python<'_>,
+    ports: Option<&Bound<'_, PyAny>>,
+    host: &str,
+    state_file: Option<PathBuf>,
+    timeout: f64,
+) -> PyResult<Vec<PyObject>> {
+    let ports = ports_arg(ports)?;
+    let timeout = crate::timeouts::seconds("timeout", Some(timeout))?.unwrap_or_default();
+    let host = host.to_string();
+    let nodes = py
+        .allow_threads(move || discover(&host, &ports, state_file, timeout))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e)))?;
+    Ok(nodes
+        .iter()
+        .map(|node| json_to_python(py, &node.to_json()))
+        .collect())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// State files name nodes by map key, list entry, URL or port.
+    #[test]
+    fn test_state_candidates() {
+        let state = json!({"nodes": {
+            "calimero-node-1": {"rpc_port": 2428},
+            "calimero-node-2": {"api_url": "http://localhost:2429"},
+            "broken": {"image": "merod"},
+        }});
+        let candidates = state_candidates(&state);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].name.as_deref(), Some("calimero-node-1"));
+        assert_eq!(candidates[0].url.as_str(), "http://127.0.0.1:2428/");
+        assert_eq!(candidates[1].url.as_str(), "http://localhost:2429/");
+
+        let list = json!(["http://127.0.0.1:2430", {"name": "n4", "port": 2431}]);
+        let candidates = state_candidates(&list);
+        assert_eq!(candidates[0].name, None);
+        assert_eq!(candidates[1].name.as_deref(), Some("n4"));
+        assert!(state_candidates(&json!(42)).is_empty());
+    }
+
+    /// A named entry replaces the scanned port at the same URL.
+    #[test]
+    fn test_dedup_prefers_named_entries() {
+        let mut candidates = port_candidates(DEFAULT_HOST, &[2429, 2428]).unwrap();
+        candidates.extend(state_candidates(&json!({"node1": {"port": 2428}})));
+        let candidates = dedup(candidates);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].url.port(), Some(2429));
+        assert_eq!(candidates[1].name.as_deref(), Some("node1"));
+        assert_eq!(candidates[1].source, Source::Merobox);
+    }
+}
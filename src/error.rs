@@ -1,7 +1,174 @@
-//! Python wrapper for ClientError
+//! Python wrapper for ClientError, and the panic safety net.
+//!
+//! Rust panics must never take down the host interpreter. Entry points that
+//! drive the async runtime run under [`catch_panic`], which turns a panic
+//! into an `InternalError` carrying the panic message and a snapshot of the
+//! request being served.
+//!
+//! Ordinary call failures keep their whole story: [`ErrorContext`] gathers
+//! the operation, node, context, HTTP status, the node's own error code and
+//! message, and any retries made along the way, and attaches them to the
+//! raised exception. `run` opens a [`CallRecord`] for the calling thread
+//! (futures are driven on it by `block_on`), so retry sites deep inside a
+//! call can add to it without threading state through every signature.
+//!
+//! Failed calls raise a class of the `CalimeroError` hierarchy picked by
+//! [`ErrorClass`], so Python code can branch on the kind of failure with
+//! `except` instead of matching messages. Every class derives from
+//! `RuntimeError`, which is what client errors raised before.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
 
 use calimero_client::ClientError;
+use pyo3::create_exception;
+use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
+use serde::Serialize;
+
+use crate::auth::AuthFailure;
+use crate::utils::json_to_python;
+
+create_exception!(
+    calimero_client_py,
+    CalimeroError,
+    PyRuntimeError,
+    "Base class of client errors. Has `code` (an `ErrorCode`), `operation`, `status`, `error_context` and the other context attributes."
+);
+create_exception!(
+    calimero_client_py,
+    AuthenticationError,
+    CalimeroError,
+    "The node refused the call's credentials or their permissions."
+);
+create_exception!(
+    calimero_client_py,
+    TokenExpiredError,
+    AuthenticationError,
+    "The access token expired and could not be refreshed."
+);
+create_exception!(
+    calimero_client_py,
+    NodeUnreachableError,
+    CalimeroError,
+    "The node could not be reached, did not answer in time, or is unavailable."
+);
+create_exception!(
+    calimero_client_py,
+    RpcError,
+    CalimeroError,
+    "The node answered a JSON-RPC call with an error. Has `rpc_code`, `rpc_data` and `rpc_error` attributes."
+);
+create_exception!(
+    calimero_client_py,
+    StorageError,
+    CalimeroError,
+    "Reading or writing the local token storage failed."
+);
+create_exception!(
+    calimero_client_py,
+    InternalError,
+    CalimeroError,
+    "A bug in the client bindings (a Rust panic). Has `code`, `panic_message` and `context` attributes; please report it."
+);
+
+/// Prefix of the error a JSON-RPC error response is turned into.
+const RPC_ERROR_PREFIX: &str = "JSON-RPC error: ";
+
+/// Error for a JSON-RPC response whose `error` member is `error`.
+pub fn rpc_error(error: &serde_json::Value) -> eyre::Report {
+    eyre::eyre!("{}{}", RPC_ERROR_PREFIX, error)
+}
+
+/// The JSON-RPC `error` object an error message built by [`rpc_error`]
+/// carries, if any.
+pub fn parse_rpc_error(message: &str) -> Option<serde_json::Value> {
+    let (_, rest) = message.split_once(RPC_ERROR_PREFIX)?;
+    serde_json::Deserializer::from_str(rest)
+        .into_iter::<serde_json::Value>()
+        .next()?
+        .ok()
+}
+
+/// Messages of failures in the local token storage.
+const STORAGE_FAILURES: [&str; 7] = [
+    "storage error",
+    "python storage",
+    "token store",
+    "token file",
+    "key file",
+    "lock file",
+    "cache directory",
+];
+
+/// Which `CalimeroError` class a failed call raises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    Rpc,
+    Storage,
+    TokenExpired,
+    Authentication,
+    NodeUnreachable,
+    Other,
+}
+
+impl ErrorClass {
+    /// Class of a failure with `code` and rendered `message`.
+    pub fn of(code: ErrorCode, message: &str) -> Self {
+        let lower = message.to_ascii_lowercase();
+        if parse_rpc_error(message).is_some() {
+            return Self::Rpc;
+        }
+        if STORAGE_FAILURES.iter().any(|needle| lower.contains(needle)) {
+            return Self::Storage;
+        }
+        match code {
+            ErrorCode::TokenExpired => Self::TokenExpired,
+            ErrorCode::Unauthenticated | ErrorCode::PermissionDenied => Self::Authentication,
+            ErrorCode::Network | ErrorCode::Timeout | ErrorCode::Unavailable => {
+                Self::NodeUnreachable
+            }
+            _ => Self::Other,
+        }
+    }
+
+    /// Exception of this class with `message`.
+    pub fn new_err(self, message: String) -> PyErr {
+        match self {
+            Self::Rpc => RpcError::new_err(message),
+            Self::Storage => StorageError::new_err(message),
+            Self::TokenExpired => TokenExpiredError::new_err(message),
+            Self::Authentication => AuthenticationError::new_err(message),
+            Self::NodeUnreachable => NodeUnreachableError::new_err(message),
+            Self::Other => CalimeroError::new_err(message),
+        }
+    }
+}
+
+/// The exception for a failed call: its [`ErrorClass`], with the context
+/// attached, and for JSON-RPC errors the node's code and data.
+pub fn call_error(context: &ErrorContext, message: &str) -> PyErr {
+    let err = ErrorClass::of(context.code, message).new_err(format!(
+        "Client error: {} [{}]",
+        message,
+        context.summary()
+    ));
+    context.attach(&err);
+    if let Some(rpc) = parse_rpc_error(message) {
+        Python::with_gil(|py| {
+            let value = err.value_bound(py);
+            let code = rpc.get("code").or_else(|| rpc.get("type"));
+            let _ = value.setattr("rpc_code", code.map(|code| json_to_python(py, code)));
+            let _ = value.setattr(
+                "rpc_data",
+                rpc.get("data").map(|data| json_to_python(py, data)),
+            );
+            let _ = value.setattr("rpc_error", json_to_python(py, &rpc));
+        });
+    }
+    err
+}
 
 /// Python wrapper for ClientError
 #[pyclass(name = "ClientError")]
@@ -65,3 +232,577 @@ impl From<ClientError> for PyClientError {
         }
     }
 }
+
+/// Text of a panic payload (`panic!` with a literal or a formatted message).
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Run `f`, converting a panic into `InternalError`.
+///
+/// `context` is only evaluated on panic and should describe the request
+/// (operation, node, ...); it is attached to the exception as `context`.
+///
+/// Only for the FFI boundary: wrap the blocking section of a method Python
+/// calls, never a future, a spawned task or library code, so a panic still
+/// unwinds through everything between it and the interpreter.
+pub(crate) fn catch_panic<T>(
+    context: impl FnOnce() -> serde_json::Value,
+    f: impl FnOnce() -> T,
+) -> PyResult<T> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| internal_error(&panic_message(payload.as_ref()), &context()))
+}
+
+/// Build an `InternalError` with `panic_message` and `context` attributes.
+pub fn internal_error(message: &str, context: &serde_json::Value) -> PyErr {
+    let err = InternalError::new_err(format!(
+        "Internal error (this is a bug, please report it): {}",
+        message
+    ));
+    Python::with_gil(|py| {
+        let value = err.value_bound(py);
+        let _ = value.setattr("code", ErrorCode::Internal.into_py(py));
+        let _ = value.setattr("panic_message", message);
+        let _ = value.setattr("context", json_to_python(py, context));
+    });
+    err
+}
+
+/// Stable, machine-readable cause of a failed call.
+///
+/// Attached to every client exception as `code`, so callers can branch on
+/// `err.code == ErrorCode.CONTEXT_NOT_FOUND` instead of matching messages.
+/// Codes are only ever added, never renamed.
+#[pyclass(name = "ErrorCode", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    #[pyo3(name = "TOKEN_EXPIRED")]
+    TokenExpired,
+    #[pyo3(name = "UNAUTHENTICATED")]
+    Unauthenticated,
+    #[pyo3(name = "PERMISSION_DENIED")]
+    PermissionDenied,
+    #[pyo3(name = "CONTEXT_NOT_FOUND")]
+    ContextNotFound,
+    #[pyo3(name = "APPLICATION_NOT_FOUND")]
+    ApplicationNotFound,
+    #[pyo3(name = "BLOB_NOT_FOUND")]
+    BlobNotFound,
+    #[pyo3(name = "NOT_FOUND")]
+    NotFound,
+    #[pyo3(name = "INVALID_ARGUMENT")]
+    InvalidArgument,
+    #[pyo3(name = "CONFLICT")]
+    Conflict,
+    #[pyo3(name = "RATE_LIMITED")]
+    RateLimited,
+    #[pyo3(name = "RESPONSE_TOO_LARGE")]
+    ResponseTooLarge,
+    #[pyo3(name = "TIMEOUT")]
+    Timeout,
+    #[pyo3(name = "NETWORK")]
+    Network,
+    #[pyo3(name = "UNAVAILABLE")]
+    Unavailable,
+    #[pyo3(name = "NODE_ERROR")]
+    NodeError,
+    #[pyo3(name = "INTERNAL")]
+    Internal,
+    #[default]
+    #[pyo3(name = "UNKNOWN")]
+    Unknown,
+}
+
+const ERROR_CODES: [ErrorCode; 17] = [
+    ErrorCode::TokenExpired,
+    ErrorCode::Unauthenticated,
+    ErrorCode::PermissionDenied,
+    ErrorCode::ContextNotFound,
+    ErrorCode::ApplicationNotFound,
+    ErrorCode::BlobNotFound,
+    ErrorCode::NotFound,
+    ErrorCode::InvalidArgument,
+    ErrorCode::Conflict,
+    ErrorCode::RateLimited,
+    ErrorCode::ResponseTooLarge,
+    ErrorCode::Timeout,
+    ErrorCode::Network,
+    ErrorCode::Unavailable,
+    ErrorCode::NodeError,
+    ErrorCode::Internal,
+    ErrorCode::Unknown,
+];
+
+#[pymethods]
+impl ErrorCode {
+    #[getter]
+    pub fn value(&self) -> &'static str {
+        match self {
+            Self::TokenExpired => "token_expired",
+            Self::Unauthenticated => "unauthenticated",
+            Self::PermissionDenied => "permission_denied",
+            Self::ContextNotFound => "context_not_found",
+            Self::ApplicationNotFound => "application_not_found",
+            Self::BlobNotFound => "blob_not_found",
+            Self::NotFound => "not_found",
+            Self::InvalidArgument => "invalid_argument",
+            Self::Conflict => "conflict",
+            Self::RateLimited => "rate_limited",
+            Self::ResponseTooLarge => "response_too_large",
+            Self::Timeout => "timeout",
+            Self::Network => "network",
+            Self::Unavailable => "unavailable",
+            Self::NodeError => "node_error",
+            Self::Internal => "internal",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    fn __str__(&self) -> &'static str {
+        self.value()
+    }
+}
+
+impl ErrorCode {
+    /// The code named by a node error code (`ContextNotFound`,
+    /// `CONTEXT_NOT_FOUND`, `context-not-found`), if it is one of ours.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mut snake = String::new();
+        for (i, c) in name.chars().enumerate() {
+            if c.is_ascii_uppercase() && i > 0 && !snake.ends_with('_') {
+                let prev = name.as_bytes()[i - 1];
+                if prev.is_ascii_lowercase() || prev.is_ascii_digit() {
+                    snake.push('_');
+                }
+            }
+            match c {
+                '-' | ' ' | '.' => snake.push('_'),
+                c => snake.push(c.to_ascii_lowercase()),
+            }
+        }
+        ERROR_CODES.into_iter().find(|code| code.value() == snake)
+    }
+
+    /// Code for a failed call from its [`ErrorContext`] and rendered message.
+    pub fn classify(context: &ErrorContext, message: &str) -> Self {
+        if let Some(code) = context.node_error_code.as_deref().and_then(Self::from_name) {
+            return code;
+        }
+        let lower = message.to_ascii_lowercase();
+        if crate::limits::is_too_large(message) {
+            return Self::ResponseTooLarge;
+        }
+        let not_found = || {
+            let subject = format!("{} {}", context.operation.unwrap_or_default(), lower);
+            if subject.contains("blob") {
+                Self::BlobNotFound
+            } else if subject.contains("application") || subject.contains("app ") {
+                Self::ApplicationNotFound
+            } else if subject.contains("context") {
+                Self::ContextNotFound
+            } else {
+                Self::NotFound
+            }
+        };
+        let auth = crate::auth::auth_failure(message);
+        match context.status {
+            Some(401) if lower.contains("expired") => Self::TokenExpired,
+            Some(401) => Self::Unauthenticated,
+            Some(403) => Self::PermissionDenied,
+            Some(404) => not_found(),
+            Some(400 | 422) => Self::InvalidArgument,
+            Some(409) => Self::Conflict,
+            Some(429) => Self::RateLimited,
+            Some(408 | 504) => Self::Timeout,
+            Some(503) => Self::Unavailable,
+            Some(500..=599) => Self::NodeError,
+            _ if lower.contains("not found") => not_found(),
+            _ if auth == Some(AuthFailure::Rejected) && lower.contains("expired") => {
+                Self::TokenExpired
+            }
+            _ if auth == Some(AuthFailure::Rejected) => Self::Unauthenticated,
+            _ if auth == Some(AuthFailure::Forbidden) => Self::PermissionDenied,
+            _ if lower.contains("timed out") || lower.contains("timeout") => Self::Timeout,
+            _ if [
+                "connection refused",
+                "error sending request",
+                "dns error",
+                "connect",
+            ]
+            .iter()
+            .any(|needle| lower.contains(needle)) =>
+            {
+                Self::Network
+            }
+            _ if lower.contains("conflict") => Self::Conflict,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// One retried attempt within a call, reported in `retry_history`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetryAttempt {
+    pub attempt: u32,
+    pub reason: String,
+}
+
+/// What the current call on this thread is doing.
+#[derive(Debug, Clone, Default)]
+pub struct CallRecord {
+    pub operation: Option<&'static str>,
+    pub context_id: Option<String>,
+    pub retries: Vec<RetryAttempt>,
+}
+
+thread_local! {
+    static CURRENT_CALL: RefCell<CallRecord> = RefCell::new(CallRecord::default());
+}
+
+/// Start recording a call, replacing whatever the last call left behind.
+pub fn begin_call(operation: &'static str, context_id: Option<String>) {
+    CURRENT_CALL.with(|call| {
+        *call.borrow_mut() = CallRecord {
+            operation: Some(operation),
+            context_id,
+            retries: Vec::new(),
+        }
+    });
+}
+
+/// Note that the current call retried, and why.
+pub fn record_retry(reason: impl Into<String>) {
+    CURRENT_CALL.with(|call| {
+        let mut call = call.borrow_mut();
+        let attempt = call.retries.len() as u32 + 1;
+        call.retries.push(RetryAttempt {
+            attempt,
+            reason: reason.into(),
+        });
+    });
+}
+
+/// Take the current call's record, leaving an empty one.
+pub fn take_call() -> CallRecord {
+    CURRENT_CALL.with(|call| std::mem::take(&mut *call.borrow_mut()))
+}
+
+/// Everything known about a failed call, attached to its exception.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ErrorContext {
+    pub code: ErrorCode,
+    pub operation: Option<&'static str>,
+    pub node_name: Option<String>,
+    pub context_id: Option<String>,
+    pub status: Option<u16>,
+    pub node_error_code: Option<String>,
+    pub node_error_message: Option<String>,
+    pub retry_history: Vec<RetryAttempt>,
+}
+
+impl ErrorContext {
+    /// Combine the call record with what the rendered error `message` says.
+    pub fn new(call: CallRecord, node_name: Option<String>, message: &str) -> Self {
+        let status = parse_status(message);
+        let (node_error_code, node_error_message) = parse_node_error(message);
+        let mut context = Self {
+            code: ErrorCode::Unknown,
+            operation: call.operation,
+            node_name,
+            context_id: call.context_id,
+            status,
+            node_error_code,
+            node_error_message,
+            retry_history: call.retries,
+        };
+        context.code = ErrorCode::classify(&context, message);
+        context
+    }
+
+    /// One-line `key=value` summary appended to the exception message.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("code={}", self.code.value())];
+        if let Some(operation) = self.operation {
+            parts.push(format!("operation={}", operation));
+        }
+        if let Some(node_name) = &self.node_name {
+            parts.push(format!("node={}", node_name));
+        }
+        if let Some(context_id) = &self.context_id {
+            parts.push(format!("context={}", context_id));
+        }
+        if let Some(status) = self.status {
+            parts.push(format!("status={}", status));
+        }
+        if let Some(code) = &self.node_error_code {
+            parts.push(format!("node_error={}", code));
+        }
+        if !self.retry_history.is_empty() {
+            parts.push(format!("retries={}", self.retry_history.len()));
+        }
+        parts.join(", ")
+    }
+
+    /// Set each field as an attribute of `err`'s value, plus the whole
+    /// record as `error_context`.
+    pub fn attach(&self, err: &PyErr) {
+        Python::with_gil(|py| {
+            let value = err.value_bound(py);
+            let _ = value.setattr("code", self.code.into_py(py));
+            let _ = value.setattr("operation", self.operation);
+            let _ = value.setattr("node_name", self.node_name.as_deref());
+            let _ = value.setattr("context_id", self.context_id.as_deref());
+            let _ = value.setattr("status", self.status);
+            let _ = value.setattr("node_error_code", self.node_error_code.as_deref());
+            let _ = value.setattr("node_error_message", self.node_error_message.as_deref());
+            let json = serde_json::to_value(self).unwrap_or_default();
+            let _ = value.setattr("retry_history", json_to_python(py, &json["retry_history"]));
+            let _ = value.setattr("error_context", json_to_python(py, &json));
+        });
+    }
+}
+
+/// Render `e` with its whole cause chain (`outer: inner: root`).
+///
+/// `eyre` reports only show their outermost context under plain `{}`,
+/// which is how transport and node errors used to get lost.
+pub fn render_chain(e: impl std::fmt::Display) -> String {
+    format!("{:#}", e)
+}
+
+/// HTTP status mentioned in an error message (`status 404`, `HTTP 404`).
+pub fn parse_status(message: &str) -> Option<u16> {
+    let lower = message.to_ascii_lowercase();
+    ["status code ", "status: ", "status ", "http "]
+        .iter()
+        .find_map(|marker| {
+            lower.match_indices(marker).find_map(|(at, _)| {
+                let digits: String = lower[at + marker.len()..]
+                    .chars()
+                    .take_while(char::is_ascii_digit)
+                    .collect();
+                digits
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|code| (100..600).contains(code) && digits.len() == 3)
+            })
+        })
+}
+
+/// Node error code and message from a JSON body embedded in `message`.
+///
+/// Nodes reply with `{"error": {"type"|"code": ..., "message"|"data": ...}}`
+/// or `{"code": ..., "message": ...}`; anything else yields `(None, None)`.
+pub fn parse_node_error(message: &str) -> (Option<String>, Option<String>) {
+    let Some(body) = message
+        .find('{')
+        .and_then(|start| serde_json::from_str::<serde_json::Value>(&message[start..]).ok())
+    else {
+        return (None, None);
+    };
+    let error = body.get("error").unwrap_or(&body);
+    let text = |value: &serde_json::Value| match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Null => None,
+        other => Some(other.to_string()),
+    };
+    if let serde_json::Value::String(message) = error {
+        return (None, Some(message.clone()));
+    }
+    let code = ["code", "type", "name"]
+        .iter()
+        .find_map(|key| error.get(key).and_then(text));
+    let message = ["message", "data", "detail"]
+        .iter()
+        .find_map(|key| error.get(key).and_then(text));
+    (code, message)
+}
+
+pub fn register(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("CalimeroError", py.get_type_bound::<CalimeroError>())?;
+    m.add(
+        "AuthenticationError",
+        py.get_type_bound::<AuthenticationError>(),
+    )?;
+    m.add(
+        "TokenExpiredError",
+        py.get_type_bound::<TokenExpiredError>(),
+    )?;
+    m.add(
+        "NodeUnreachableError",
+        py.get_type_bound::<NodeUnreachableError>(),
+    )?;
+    m.add("RpcError", py.get_type_bound::<RpcError>())?;
+    m.add("StorageError", py.get_type_bound::<StorageError>())?;
+    m.add("InternalError", py.get_type_bound::<InternalError>())?;
+    Ok(())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Both literal and formatted panic payloads yield their message.
+    #[test]
+    fn test_panic_message() {
+        let literal = panic::catch_unwind(|| panic!("literal")).unwrap_err();
+        assert_eq!(panic_message(literal.as_ref()), "literal");
+
+        let formatted = panic::catch_unwind(|| panic!("code {}", 7)).unwrap_err();
+        assert_eq!(panic_message(formatted.as_ref()), "code 7");
+    }
+
+    /// Status codes are read from the formats the transport layers use.
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(
+            parse_status("Request to http://n/x failed with status 404 Not Found: {}"),
+            Some(404)
+        );
+        assert_eq!(parse_status("HTTP 503 from node"), Some(503));
+        assert_eq!(parse_status("status 12345"), None);
+        assert_eq!(parse_status("connection refused"), None);
+    }
+
+    /// Node error bodies yield their code and message.
+    #[test]
+    fn test_parse_node_error() {
+        let message = r#"POST http://n/auth failed with status 401: {"error":{"code":"TOKEN_EXPIRED","message":"expired"}}"#;
+        assert_eq!(
+            parse_node_error(message),
+            (
+                Some("TOKEN_EXPIRED".to_string()),
+                Some("expired".to_string())
+            )
+        );
+        assert_eq!(
+            parse_node_error(r#"failed: {"error":"not found"}"#),
+            (None, Some("not found".to_string()))
+        );
+        assert_eq!(parse_node_error("no body"), (None, None));
+    }
+
+    /// Chained `eyre` contexts are all rendered, and retries accumulate on
+    /// the current call until it is taken.
+    #[test]
+    fn test_chain_and_call_record() {
+        let report = eyre::eyre!("connection refused").wrap_err("Request to node failed");
+        assert_eq!(
+            render_chain(&report),
+            "Request to node failed: connection refused"
+        );
+
+        begin_call("execute_function", Some("ctx".to_string()));
+        record_retry("msgpack rejected");
+        let context = ErrorContext::new(take_call(), Some("node".to_string()), "status 500");
+        assert_eq!(context.operation, Some("execute_function"));
+        assert_eq!(context.retry_history.len(), 1);
+        assert_eq!(context.status, Some(500));
+        assert!(take_call().operation.is_none());
+    }
+
+    /// Codes come from node error names first, then status and message.
+    #[test]
+    fn test_classify_error_codes() {
+        let classify = |operation, message: &str| {
+            let call = CallRecord {
+                operation: Some(operation),
+                ..CallRecord::default()
+            };
+            ErrorContext::new(call, None, message).code
+        };
+        assert_eq!(
+            classify(
+                "execute_function",
+                r#"failed with status 404: {"error":{"type":"ContextNotFound"}}"#
+            ),
+            ErrorCode::ContextNotFound
+        );
+        assert_eq!(
+            classify("get_context", "failed with status 404 Not Found"),
+            ErrorCode::ContextNotFound
+        );
+        assert_eq!(
+            classify("get_blob_info", "failed with status 404 Not Found"),
+            ErrorCode::BlobNotFound
+        );
+        assert_eq!(
+            classify(
+                "execute_function",
+                "Root hash conflict: expected a, context is at b"
+            ),
+            ErrorCode::Conflict
+        );
+        assert_eq!(
+            classify("list_contexts", "status 401: token expired"),
+            ErrorCode::TokenExpired
+        );
+        assert_eq!(
+            classify("list_contexts", "error sending request: connection refused"),
+            ErrorCode::Network
+        );
+        assert_eq!(
+            classify("sync_context", "something odd"),
+            ErrorCode::Unknown
+        );
+        assert_eq!(
+            classify("execute_function", "Authentication failed: bad signature"),
+            ErrorCode::Unauthenticated
+        );
+        assert_eq!(
+            classify("sync_context", "Failed to apply delta 0x4013ab9f: bad root"),
+            ErrorCode::Unknown
+        );
+        assert_eq!(
+            ErrorCode::from_name("token-expired"),
+            Some(ErrorCode::TokenExpired)
+        );
+        assert_eq!(ErrorCode::from_name("Teapot"), None);
+    }
+
+    /// JSON-RPC errors survive rendering; other failures map by code.
+    #[test]
+    fn test_error_classes() {
+        let rpc = serde_json::json!({"type": "FunctionCallError", "data": {"panic": "x"}});
+        let message = format!("execute failed: {}", render_chain(rpc_error(&rpc)));
+        assert_eq!(parse_rpc_error(&message), Some(rpc));
+        assert_eq!(
+            ErrorClass::of(ErrorCode::Unknown, &message),
+            ErrorClass::Rpc
+        );
+        assert_eq!(parse_rpc_error("connection refused"), None);
+
+        assert_eq!(
+            ErrorClass::of(ErrorCode::TokenExpired, "status 401: token expired"),
+            ErrorClass::TokenExpired
+        );
+        assert_eq!(
+            ErrorClass::of(ErrorCode::PermissionDenied, "status 403"),
+            ErrorClass::Authentication
+        );
+        assert_eq!(
+            ErrorClass::of(ErrorCode::Timeout, "get_context timed out"),
+            ErrorClass::NodeUnreachable
+        );
+        assert_eq!(
+            ErrorClass::of(ErrorCode::NotFound, "Failed to read key file: \"k\""),
+            ErrorClass::Storage
+        );
+        assert_eq!(
+            ErrorClass::of(ErrorCode::Conflict, "Root hash conflict"),
+            ErrorClass::Other
+        );
+    }
+}
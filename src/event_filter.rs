@@ -0,0 +1,248 @@
+//! Subscription filters, behind `subscribe(context_id, filter=...)`.
+//!
+//! A busy context can emit far more events than a consumer cares about,
+//! and every event handed to Python costs a conversion and a place in the
+//! subscription's buffer. A filter is evaluated on the raw JSON as events
+//! are routed, so events it rejects never leave Rust. Its syntax is a list
+//! of clauses separated by spaces, all of which must hold:
+//!
+//! ```text
+//! type:StateMutation,ExecutionEvent method:transfer key:"balances/" -key:balances/tmp
+//! ```
+//!
+//! - `type:` (or `kind:`) matches the event type exactly;
+//! - `method:` matches the method that emitted the event exactly;
+//! - `key:` matches state keys starting with the value.
+//!
+//! A clause holds when any of its comma-separated values matches; a leading
+//! `-` negates it. Values may be double-quoted to hold spaces or commas.
+//! Fields are read from the event, its `data` and each entry of
+//! `data.events`, so a context event matches when any event it carries
+//! does. An event without the field a clause asks for does not match it.
+
+use std::fmt;
+
+use serde_json::Value;
+
+/// Event field a clause tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Type,
+    Method,
+    Key,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "type" | "kind" => Self::Type,
+            "method" => Self::Method,
+            "key" => Self::Key,
+            _ => return None,
+        })
+    }
+
+    /// Names the field goes by in events.
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            Self::Type => &["type", "kind"],
+            Self::Method => &["method", "functionName", "function_name"],
+            Self::Key => &["key", "keys"],
+        }
+    }
+
+    fn matches(self, value: &str, pattern: &str) -> bool {
+        match self {
+            Self::Key => value.starts_with(pattern),
+            Self::Type | Self::Method => value == pattern,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Clause {
+    field: Field,
+    values: Vec<String>,
+    negated: bool,
+}
+
+/// A parsed subscription filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventFilter {
+    source: String,
+    clauses: Vec<Clause>,
+}
+
+impl fmt::Display for EventFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Split `source` into clauses on unquoted spaces.
+fn tokens(source: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    for c in source.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                token.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quote".to_string());
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Split a clause's values on unquoted commas, unquoting them.
+fn values(raw: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut value = String::new();
+    let mut quoted = false;
+    for c in raw.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => values.push(std::mem::take(&mut value)),
+            c => value.push(c),
+        }
+    }
+    values.push(value);
+    values
+}
+
+impl EventFilter {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let clauses = tokens(source)?
+            .into_iter()
+            .map(|token| {
+                let (negated, clause) = match token.strip_prefix('-') {
+                    Some(clause) => (true, clause),
+                    None => (false, token.as_str()),
+                };
+                let Some((name, raw)) = clause.split_once(':') else {
+                    return Err(format!("expected field:value, got '{}'", token));
+                };
+                let field = Field::parse(name).ok_or_else(|| {
+                    format!("unknown field '{}'; expected type, method or key", name)
+                })?;
+                let values = values(raw);
+                if values.iter().any(String::is_empty) {
+                    return Err(format!("empty value in '{}'", token));
+                }
+                Ok(Clause {
+                    field,
+                    values,
+                    negated,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(|e| format!("Invalid event filter '{}': {}", source, e))?;
+        Ok(Self {
+            source: source.to_string(),
+            clauses,
+        })
+    }
+
+    /// Whether `event` (as received, with or without its JSON-RPC
+    /// envelope) passes every clause.
+    pub fn matches(&self, event: &Value) -> bool {
+        let body = event.get("result").unwrap_or(event);
+        let data = body.get("data");
+        let inner = data
+            .and_then(|data| data.get("events"))
+            .and_then(Value::as_array);
+        let scopes: Vec<&Value> = [Some(body), data]
+            .into_iter()
+            .flatten()
+            .chain(inner.into_iter().flatten())
+            .collect();
+        self.clauses.iter().all(|clause| {
+            let found = scopes.iter().any(|scope| {
+                clause
+                    .field
+                    .names()
+                    .iter()
+                    .filter_map(|name| scope.get(*name))
+                    .flat_map(|value| match value {
+                        Value::Array(items) => items.iter().collect(),
+                        value => vec![value],
+                    })
+                    .filter_map(Value::as_str)
+                    .any(|value| {
+                        clause
+                            .values
+                            .iter()
+                            .any(|pattern| clause.field.matches(value, pattern))
+                    })
+            });
+            found != clause.negated
+        })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Clauses combine with AND, values with OR; keys match by prefix.
+    #[test]
+    fn test_matches() {
+        let filter =
+            EventFilter::parse("type:StateMutation,Other key:votes/ -key:votes/tmp").unwrap();
+        let event = |key: &str| json!({"result": {"type": "StateMutation", "key": key}});
+        assert!(filter.matches(&event("votes/alice")));
+        assert!(!filter.matches(&event("votes/tmp1")));
+        assert!(!filter.matches(&event("profiles/alice")));
+        assert!(!filter.matches(&json!({"type": "Other"})));
+        assert_eq!(
+            filter.to_string(),
+            "type:StateMutation,Other key:votes/ -key:votes/tmp"
+        );
+    }
+
+    /// Fields are found in the event's data and in the events it carries.
+    #[test]
+    fn test_matches_nested_events() {
+        let filter = EventFilter::parse(r#"kind:"Item Added" method:add"#).unwrap();
+        let event = json!({"type": "ExecutionEvent", "data": {
+            "method": "add",
+            "events": [{"kind": "Counted"}, {"kind": "Item Added"}]
+        }});
+        assert!(filter.matches(&event));
+        assert!(!filter.matches(&json!({"data": {"method": "add", "events": []}})));
+    }
+
+    /// Malformed filters are rejected with the offending clause.
+    #[test]
+    fn test_parse_errors() {
+        assert!(EventFilter::parse("").unwrap().matches(&json!({})));
+        assert!(EventFilter::parse("type").unwrap_err().contains("'type'"));
+        assert!(EventFilter::parse("color:red")
+            .unwrap_err()
+            .contains("unknown field"));
+        assert!(EventFilter::parse("key:\"open")
+            .unwrap_err()
+            .contains("unterminated"));
+        assert!(EventFilter::parse("type:a,")
+            .unwrap_err()
+            .contains("empty value"));
+    }
+}
@@ -0,0 +1,165 @@
+//! Delivery offsets of subscribed events, behind `subscribe(replay=True)`.
+//!
+//! A subscription only sees the events the node pushes while the socket is
+//! up, so a consumer that restarts — or whose socket drops — misses
+//! whatever happened in between. With replay, the offset of the last event
+//! the consumer finished with is kept per (node, context) in an
+//! [`OffsetStore`], and every subscribe frame for the context asks the
+//! node to replay from it (`fromOffsets`). Delivery is at-least-once: an
+//! offset is committed only once the event was processed (the callback
+//! returned, or the async iterator was asked for the next event), so a
+//! crash replays the event being handled rather than skipping it, and
+//! replayed events already delivered are dropped by offset.
+//!
+//! Offsets are read from the event itself ([`event_offset`]); events
+//! without one are delivered but not tracked. Nodes that do not support
+//! replay ignore `fromOffsets` and send live events only.
+//!
+//! Offsets of clients whose tokens persist to disk are written to
+//! `<state root>/event_offsets/<node>.json`; other clients keep them in
+//! memory for the life of the client.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use eyre::WrapErr;
+use serde_json::Value;
+
+use crate::cache::{state_root, user_token_filename};
+use crate::entropy;
+
+/// Directory under the state root holding delivery offsets.
+pub const EVENT_OFFSETS_SUBDIR: &str = "event_offsets";
+
+/// Sequence number of an event (`offset`, `sequence` or `eventIndex`, as a
+/// number or a decimal string), at top level or under `result`.
+pub fn event_offset(event: &Value) -> Option<u64> {
+    let body = event.get("result").unwrap_or(event);
+    ["offset", "sequence", "eventIndex"]
+        .iter()
+        .find_map(|field| match body.get(*field)? {
+            Value::Number(number) => number.as_u64(),
+            Value::String(digits) => digits.parse().ok(),
+            _ => None,
+        })
+}
+
+/// Last processed offset of each context of one node.
+#[derive(Debug)]
+pub struct OffsetStore {
+    /// File the offsets persist to; `None` keeps them in memory only.
+    path: Option<PathBuf>,
+    offsets: Mutex<BTreeMap<String, u64>>,
+}
+
+impl OffsetStore {
+    /// Offsets of `node` (and `user`), persisted under the state root.
+    pub fn open(node: &str, user: Option<&str>) -> Self {
+        let path = state_root()
+            .join(EVENT_OFFSETS_SUBDIR)
+            .join(user_token_filename(node, user));
+        Self::at(Some(path))
+    }
+
+    /// Offsets kept in memory only.
+    pub fn memory() -> Self {
+        Self::at(None)
+    }
+
+    fn at(path: Option<PathBuf>) -> Self {
+        // An unreadable file starts over: at worst events are replayed.
+        let offsets = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            offsets: Mutex::new(offsets),
+        }
+    }
+
+    /// Last processed offset of `context_id`.
+    pub fn get(&self, context_id: &str) -> Option<u64> {
+        self.offsets.lock().ok()?.get(context_id).copied()
+    }
+
+    /// Record `offset` as processed for `context_id`. Offsets only move
+    /// forward; an older one is ignored.
+    pub fn commit(&self, context_id: &str, offset: u64) -> eyre::Result<()> {
+        let Ok(mut offsets) = self.offsets.lock() else {
+            eyre::bail!("event offset store is poisoned");
+        };
+        let current = offsets.entry(context_id.to_string()).or_insert(offset);
+        if *current > offset {
+            return Ok(());
+        }
+        *current = offset;
+        self.save(&offsets)
+    }
+
+    /// Forget the offset of `context_id`, so the next subscription starts
+    /// with live events.
+    pub fn reset(&self, context_id: &str) -> eyre::Result<()> {
+        let Ok(mut offsets) = self.offsets.lock() else {
+            eyre::bail!("event offset store is poisoned");
+        };
+        if offsets.remove(context_id).is_none() {
+            return Ok(());
+        }
+        self.save(&offsets)
+    }
+
+    /// Write `offsets` to the store's file, replacing it atomically.
+    fn save(&self, offsets: &BTreeMap<String, u64>) -> eyre::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .wrap_err_with(|| format!("Failed to create event offsets directory: {:?}", dir))?;
+        }
+        let temp_path = path.with_extension(format!("json.{}.tmp", entropy::temp_suffix()));
+        let json =
+            serde_json::to_string_pretty(offsets).wrap_err("Failed to serialize event offsets")?;
+        fs::write(&temp_path, json)
+            .wrap_err_with(|| format!("Failed to write temp file: {:?}", temp_path))?;
+        fs::rename(&temp_path, path)
+            .wrap_err_with(|| format!("Failed to rename {:?} to {:?}", temp_path, path))
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Offsets are found under their usual names, as numbers or strings.
+    #[test]
+    fn test_event_offset() {
+        assert_eq!(event_offset(&json!({"result": {"offset": 7}})), Some(7));
+        assert_eq!(event_offset(&json!({"sequence": "12"})), Some(12));
+        assert_eq!(event_offset(&json!({"contextId": "a"})), None);
+    }
+
+    /// Committed offsets only move forward and survive reopening.
+    #[test]
+    fn test_commit_persists() {
+        let dir = std::env::temp_dir().join(format!("event-offsets-{}", std::process::id()));
+        let path = dir.join("node.json");
+        let store = OffsetStore::at(Some(path.clone()));
+        store.commit("a", 5).unwrap();
+        store.commit("a", 3).unwrap();
+        assert_eq!(store.get("a"), Some(5));
+        assert_eq!(OffsetStore::at(Some(path.clone())).get("a"), Some(5));
+        store.reset("a").unwrap();
+        assert_eq!(OffsetStore::at(Some(path)).get("a"), None);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
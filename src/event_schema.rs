@@ -0,0 +1,542 @@
+//! Application event schemas and typed event objects.
+//!
+//! Applications emit events as a `kind` plus a payload (JSON bytes on the
+//! wire). Without a schema, consumers get that raw JSON and decode it
+//! themselves. An [`EventSchemaRegistry`] holds, per application, the fields
+//! of each event kind:
+//!
+//! ```text
+//! {"ItemAdded": {"key": "string", "value": "string"},
+//!  "Counted": {"total": "int", "note": "string?"}}
+//! ```
+//!
+//! Field types are `string`, `int`, `float`, `bool`, `bytes` (a byte list or
+//! base64 string, decoded to `bytes`) and `json` (any value); a trailing `?`
+//! makes a field optional. Decoding happens in Rust: a matching event becomes
+//! an instance of a per-kind subclass of `ApplicationEvent` with the fields as
+//! attributes, while unknown kinds, events of unregistered applications and
+//! payloads that do not match their schema pass through untyped.
+//!
+//! The schema of an event is found through the context it came from
+//! (`bind_context`), its `applicationId`, or — with a single registered
+//! application — that one.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyBytes, PyDict, PyList, PyTuple};
+use serde_json::{Map, Value};
+
+use crate::utils::{json_to_python, python_to_json};
+
+/// Type of one event field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    String,
+    Int,
+    Float,
+    Bool,
+    Bytes,
+    Json,
+}
+
+impl FieldType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "string" | "str" => Self::String,
+            "int" | "integer" => Self::Int,
+            "float" | "number" => Self::Float,
+            "bool" | "boolean" => Self::Bool,
+            "bytes" => Self::Bytes,
+            "json" | "any" => Self::Json,
+            _ => return None,
+        })
+    }
+
+    /// Whether `value` is a valid (non-null) value of this type.
+    fn accepts(self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Int => value.is_i64() || value.is_u64(),
+            Self::Float => value.is_number(),
+            Self::Bool => value.is_boolean(),
+            Self::Bytes => decode_bytes(value).is_some(),
+            Self::Json => true,
+        }
+    }
+}
+
+/// One field of an event kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSpec {
+    pub name: String,
+    pub kind: FieldType,
+    pub optional: bool,
+}
+
+/// Fields of each event kind of one application.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventSchema {
+    pub kinds: HashMap<String, Vec<FieldSpec>>,
+}
+
+impl EventSchema {
+    /// Parse `{kind: {field: type}}`.
+    pub fn parse(schema: &Value) -> Result<Self, String> {
+        let Value::Object(kinds) = schema else {
+            return Err("event schema must map event kinds to {field: type} dicts".to_string());
+        };
+        let mut parsed = HashMap::new();
+        for (kind, fields) in kinds {
+            let Value::Object(fields) = fields else {
+                return Err(format!("fields of event '{}' must be a dict", kind));
+            };
+            let mut specs = Vec::with_capacity(fields.len());
+            for (name, type_name) in fields {
+                let type_name = type_name.as_str().unwrap_or_default();
+                let (base, optional) = match type_name.strip_suffix('?') {
+                    Some(base) => (base, true),
+                    None => (type_name, false),
+                };
+                let kind_type = FieldType::parse(base).ok_or_else(|| {
+                    format!(
+                        "unknown type '{}' for field '{}' of event '{}'",
+                        type_name, name, kind
+                    )
+                })?;
+                specs.push(FieldSpec {
+                    name: name.clone(),
+                    kind: kind_type,
+                    optional,
+                });
+            }
+            parsed.insert(kind.clone(), specs);
+        }
+        Ok(Self { kinds: parsed })
+    }
+
+    /// Fields of `payload` for an event of `kind`, if the kind is known and
+    /// the payload matches it. Extra payload fields are ignored.
+    pub fn decode(&self, kind: &str, payload: &Value) -> Option<Map<String, Value>> {
+        let specs = self.kinds.get(kind)?;
+        let payload = payload.as_object()?;
+        let mut fields = Map::with_capacity(specs.len());
+        for spec in specs {
+            match payload.get(&spec.name).filter(|value| !value.is_null()) {
+                Some(value) if spec.kind.accepts(value) => {
+                    fields.insert(spec.name.clone(), value.clone());
+                }
+                None if spec.optional => {
+                    fields.insert(spec.name.clone(), Value::Null);
+                }
+                _ => return None,
+            }
+        }
+        Some(fields)
+    }
+
+    /// Type of `field` of `kind`.
+    fn field_type(&self, kind: &str, field: &str) -> Option<FieldType> {
+        self.kinds
+            .get(kind)?
+            .iter()
+            .find(|spec| spec.name == field)
+            .map(|spec| spec.kind)
+    }
+}
+
+/// Bytes of a `bytes` value: a list of byte values or a base64 string.
+fn decode_bytes(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_u64().and_then(|b| u8::try_from(b).ok()))
+            .collect(),
+        Value::String(text) => STANDARD.decode(text).ok(),
+        _ => None,
+    }
+}
+
+/// The JSON payload of an application event's `data`: JSON bytes (as a
+/// byte list or base64 string), a JSON string, or an inline object.
+pub fn event_payload(data: &Value) -> Option<Value> {
+    match data {
+        Value::Object(_) => Some(data.clone()),
+        Value::String(text) => serde_json::from_str(text)
+            .ok()
+            .or_else(|| serde_json::from_slice(&decode_bytes(data)?).ok()),
+        Value::Array(_) => serde_json::from_slice(&decode_bytes(data)?).ok(),
+        _ => None,
+    }
+}
+
+/// A decoded application event.
+///
+/// Instances are created by `EventSchemaRegistry.decode` as per-kind
+/// subclasses (see `EventSchemaRegistry.event_class`); registered fields are
+/// readable as attributes and through `fields`.
+#[pyclass(subclass, name = "ApplicationEvent")]
+pub struct PyApplicationEvent {
+    kind: String,
+    application_id: Option<String>,
+    context_id: Option<String>,
+    fields: Py<PyDict>,
+    raw: PyObject,
+}
+
+#[pymethods]
+impl PyApplicationEvent {
+    #[new]
+    #[pyo3(signature = (kind, fields, application_id=None, context_id=None, raw=None))]
+    pub fn new(
+        py: Python<'_>,
+        kind: String,
+        fields: Py<PyDict>,
+        application_id: Option<String>,
+        context_id: Option<String>,
+        raw: Option<PyObject>,
+    ) -> Self {
+        Self {
+            kind,
+            application_id,
+            context_id,
+            fields,
+            raw: raw.unwrap_or_else(|| py.None()),
+        }
+    }
+
+    #[getter]
+    pub fn kind(&self) -> String {
+        self.kind.clone()
+    }
+
+    #[getter]
+    pub fn application_id(&self) -> Option<String> {
+        self.application_id.clone()
+    }
+
+    #[getter]
+    pub fn context_id(&self) -> Option<String> {
+        self.context_id.clone()
+    }
+
+    /// Registered fields as a dict
+    #[getter]
+    pub fn fields(&self, py: Python<'_>) -> Py<PyDict> {
+        self.fields.clone_ref(py)
+    }
+
+    /// The event as received, before decoding
+    #[getter]
+    pub fn raw(&self, py: Python<'_>) -> PyObject {
+        self.raw.clone_ref(py)
+    }
+
+    fn __getattr__(&self, py: Python<'_>, name: &str) -> PyResult<PyObject> {
+        match self.fields.bind(py).get_item(name)? {
+            Some(value) => Ok(value.unbind()),
+            None => Err(PyErr::new::<pyo3::exceptions::PyAttributeError, _>(
+                format!("event '{}' has no field '{}'", self.kind, name),
+            )),
+        }
+    }
+
+    fn __eq__(&self, py: Python<'_>, other: &Self) -> PyResult<bool> {
+        Ok(self.kind == other.kind
+            && self.application_id == other.application_id
+            && self.fields.bind(py).as_any().eq(other.fields.bind(py))?)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let fields = self
+            .fields
+            .bind(py)
+            .iter()
+            .map(|(name, value)| Ok(format!("{}={}", name, value.repr()?)))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(format!("{}({})", self.kind, fields.join(", ")))
+    }
+}
+
+#[derive(Default)]
+struct Registered {
+    schemas: HashMap<String, EventSchema>,
+    /// Per-kind `ApplicationEvent` subclasses, by application id.
+    classes: HashMap<String, HashMap<String, PyObject>>,
+    contexts: HashMap<String, String>,
+}
+
+/// Event schemas of the applications a consumer cares about.
+#[pyclass(name = "EventSchemaRegistry")]
+#[derive(Default)]
+pub struct EventSchemaRegistry {
+    registered: RwLock<Registered>,
+}
+
+impl EventSchemaRegistry {
+    /// Application whose schema applies to `event`.
+    fn application_for(&self, registered: &Registered, event: &Value) -> Option<String> {
+        let context_id = event.get("contextId").and_then(Value::as_str);
+        if let Some(application_id) = context_id.and_then(|id| registered.contexts.get(id)) {
+            return Some(application_id.clone());
+        }
+        if let Some(application_id) = event.get("applicationId").and_then(Value::as_str) {
+            return Some(application_id.to_string());
+        }
+        match registered.schemas.len() {
+            1 => registered.schemas.keys().next().cloned(),
+            _ => None,
+        }
+    }
+
+    /// `event` as a typed object, or `None` to pass it through untyped.
+    fn decode_one(
+        &self,
+        py: Python<'_>,
+        registered: &Registered,
+        application_id: &str,
+        context_id: Option<&str>,
+        event: &Value,
+    ) -> PyResult<Option<PyObject>> {
+        let (Some(schema), Some(classes)) = (
+            registered.schemas.get(application_id),
+            registered.classes.get(application_id),
+        ) else {
+            return Ok(None);
+        };
+        let Some(kind) = event.get("kind").and_then(Value::as_str) else {
+            return Ok(None);
+        };
+        let Some(payload) = event.get("data").and_then(event_payload) else {
+            return Ok(None);
+        };
+        let (Some(fields), Some(class)) = (schema.decode(kind, &payload), classes.get(kind)) else {
+            return Ok(None);
+        };
+
+        let dict = PyDict::new_bound(py);
+        for (name, value) in &fields {
+            let value = match (schema.field_type(kind, name), decode_bytes(value)) {
+                (Some(FieldType::Bytes), Some(bytes)) => {
+                    PyBytes::new_bound(py, &bytes).into_any().unbind()
+                }
+                _ => json_to_python(py, value),
+            };
+            dict.set_item(name, value)?;
+        }
+        let typed = class.call_bound(
+            py,
+            (kind, dict),
+            Some(
+                &[
+                    ("application_id", application_id.to_object(py)),
+                    ("context_id", context_id.to_object(py)),
+                    ("raw", json_to_python(py, event)),
+                ]
+                .into_py_dict_bound(py),
+            ),
+        )?;
+        Ok(Some(typed))
+    }
+
+    /// Decode one event as received from a stream: a single application
+    /// event (`{kind, data}`), or a context event wrapping several under
+    /// `data.events`, whose entries are decoded in place.
+    pub fn decode_value(&self, py: Python<'_>, event: &Value) -> PyResult<PyObject> {
+        let Ok(registered) = self.registered.read() else {
+            return Ok(json_to_python(py, event));
+        };
+        let Some(application_id) = self.application_for(&registered, event) else {
+            return Ok(json_to_python(py, event));
+        };
+        let context_id = event.get("contextId").and_then(Value::as_str);
+
+        if let Some(inner) = event
+            .get("data")
+            .and_then(|data| data.get("events"))
+            .and_then(Value::as_array)
+        {
+            let decoded = PyList::empty_bound(py);
+            for item in inner {
+                let item_decoded =
+                    self.decode_one(py, &registered, &application_id, context_id, item)?;
+                decoded.append(item_decoded.unwrap_or_else(|| json_to_python(py, item)))?;
+            }
+            let wrapper = json_to_python(py, event);
+            let data = wrapper.bind(py).get_item("data")?;
+            data.set_item("events", decoded)?;
+            return Ok(wrapper);
+        }
+
+        Ok(self
+            .decode_one(py, &registered, &application_id, context_id, event)?
+            .unwrap_or_else(|| json_to_python(py, event)))
+    }
+}
+
+#[pymethods]
+impl EventSchemaRegistry {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) the event schema of `application_id`:
+    /// `{kind: {field: type}}` (see the module docs for types). Raises
+    /// `ValueError` for malformed schemas.
+    pub fn register(
+        &self,
+        py: Python<'_>,
+        application_id: &str,
+        schema: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let parsed = EventSchema::parse(&python_to_json(schema)?)
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+
+        let base = py.get_type_bound::<PyApplicationEvent>();
+        let type_builtin = py.import_bound("builtins")?.getattr("type")?;
+        let mut classes = HashMap::with_capacity(parsed.kinds.len());
+        for kind in parsed.kinds.keys() {
+            let namespace = PyDict::new_bound(py);
+            namespace.set_item("__module__", "calimero_client_py")?;
+            let class = type_builtin.call1((kind, PyTuple::new_bound(py, [&base]), namespace))?;
+            classes.insert(kind.clone(), class.unbind());
+        }
+
+        let mut registered = self.registered.write().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("event schema registry poisoned")
+        })?;
+        registered
+            .schemas
+            .insert(application_id.to_string(), parsed);
+        registered
+            .classes
+            .insert(application_id.to_string(), classes);
+        Ok(())
+    }
+
+    /// Use `application_id`'s schema for events of `context_id`.
+    pub fn bind_context(&self, context_id: &str, application_id: &str) -> PyResult<()> {
+        let mut registered = self.registered.write().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("event schema registry poisoned")
+        })?;
+        registered
+            .contexts
+            .insert(context_id.to_string(), application_id.to_string());
+        Ok(())
+    }
+
+    /// The `ApplicationEvent` subclass for `kind` of `application_id`, for
+    /// `isinstance` checks and `match` statements.
+    pub fn event_class(
+        &self,
+        py: Python<'_>,
+        application_id: &str,
+        kind: &str,
+    ) -> PyResult<PyObject> {
+        self.registered
+            .read()
+            .ok()
+            .and_then(|registered| {
+                registered
+                    .classes
+                    .get(application_id)?
+                    .get(kind)
+                    .map(|class| class.clone_ref(py))
+            })
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!(
+                    "no event '{}' registered for application {}",
+                    kind, application_id
+                ))
+            })
+    }
+
+    /// Decode one event (see the module docs); events without a matching
+    /// schema are returned unchanged as plain dicts.
+    pub fn decode(&self, py: Python<'_>, event: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.decode_value(py, &python_to_json(event)?)
+    }
+
+    /// Decode a list of events, e.g. one delivered batch.
+    pub fn decode_batch(
+        &self,
+        py: Python<'_>,
+        events: Vec<Bound<'_, PyAny>>,
+    ) -> PyResult<Vec<PyObject>> {
+        events.iter().map(|event| self.decode(py, event)).collect()
+    }
+
+    /// Application ids with a registered schema
+    pub fn applications(&self) -> Vec<String> {
+        let mut applications: Vec<String> = self
+            .registered
+            .read()
+            .map(|registered| registered.schemas.keys().cloned().collect())
+            .unwrap_or_default();
+        applications.sort();
+        applications
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> EventSchema {
+        EventSchema::parse(&json!({
+            "ItemAdded": {"key": "string", "value": "string"},
+            "Counted": {"total": "int", "note": "string?", "blob": "bytes?"},
+        }))
+        .unwrap()
+    }
+
+    /// Types are checked; optional fields may be missing or null.
+    #[test]
+    fn test_decode_checks_fields() {
+        let schema = schema();
+        let fields = schema
+            .decode("Counted", &json!({"total": 3, "extra": true}))
+            .unwrap();
+        assert_eq!(fields["total"], 3);
+        assert_eq!(fields["note"], Value::Null);
+        assert!(!fields.contains_key("extra"));
+        assert!(schema.decode("Counted", &json!({"total": "3"})).is_none());
+        assert!(schema.decode("ItemAdded", &json!({"key": "k"})).is_none());
+        assert!(schema.decode("Unknown", &json!({})).is_none());
+    }
+
+    /// Bad type names and shapes are rejected when registering.
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(EventSchema::parse(&json!(["ItemAdded"])).is_err());
+        assert!(EventSchema::parse(&json!({"E": ["key"]})).is_err());
+        assert!(EventSchema::parse(&json!({"E": {"key": "uuid"}})).is_err());
+    }
+
+    /// Payloads arrive as JSON bytes, base64, JSON strings or inline.
+    #[test]
+    fn test_event_payload_forms() {
+        let payload = json!({"key": "k"});
+        let bytes = serde_json::to_vec(&payload).unwrap();
+        assert_eq!(event_payload(&json!(bytes)), Some(payload.clone()));
+        assert_eq!(
+            event_payload(&json!(STANDARD.encode(&bytes))),
+            Some(payload.clone())
+        );
+        assert_eq!(
+            event_payload(&json!(payload.to_string())),
+            Some(payload.clone())
+        );
+        assert_eq!(event_payload(&payload), Some(payload));
+        assert_eq!(event_payload(&json!(42)), None);
+    }
+}
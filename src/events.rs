@@ -0,0 +1,356 @@
+//! Batched delivery of events to Python.
+//!
+//! Crossing into Python costs a GIL acquisition plus a callback frame per
+//! call, which caps per-event delivery far below what the Rust side can
+//! decode. Event producers therefore push into a channel and consumers drain
+//! it with [`next_batch`]: up to `max_batch_size` events, waiting at most
+//! `max_delay` after the first one, handed to Python as one list under a
+//! single GIL acquisition by [`deliver`].
+//!
+//! When the Python handler is slower than the stream, events pile up in the
+//! channel until it fills and the producer has to drop or disconnect. A
+//! [`LagMonitor`] watches the backlog (queue depth and age of the oldest
+//! buffered event) so that is reported through [`report_lag`] — a callback or
+//! a `SlowConsumerWarning` — while there is still time to react.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use tokio::sync::mpsc;
+
+use crate::event_schema::EventSchemaRegistry;
+use crate::utils::json_to_python;
+use crate::warnings::{self, WarningKind};
+
+/// Default upper bound on events per delivered batch.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 256;
+
+/// Default time to wait for a batch to fill after its first event.
+pub const DEFAULT_MAX_BATCH_DELAY: Duration = Duration::from_millis(10);
+
+/// How events are grouped for delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchPolicy {
+    pub max_batch_size: usize,
+    pub max_delay: Duration,
+}
+
+impl Default for BatchPolicy {
+    fn default() -> Self {
+        Self {
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_delay: DEFAULT_MAX_BATCH_DELAY,
+        }
+    }
+}
+
+impl BatchPolicy {
+    /// Build a policy from Python-facing arguments, validating them.
+    pub fn new(max_batch_size: usize, max_delay_secs: f64) -> Result<Self, String> {
+        if max_batch_size == 0 {
+            return Err("max_batch_size must be at least 1".to_string());
+        }
+        let max_delay = Duration::try_from_secs_f64(max_delay_secs)
+            .map_err(|_| "max_batch_delay must be a non-negative number of seconds".to_string())?;
+        Ok(Self {
+            max_batch_size,
+            max_delay,
+        })
+    }
+}
+
+/// Wait for the next batch of events.
+///
+/// Blocks until one event arrives, then keeps collecting until the batch is
+/// full or `max_delay` has passed. Returns `None` once the channel is closed
+/// and drained.
+pub async fn next_batch<T>(rx: &mut mpsc::Receiver<T>, policy: BatchPolicy) -> Option<Vec<T>> {
+    let first = rx.recv().await?;
+    let mut batch = Vec::with_capacity(policy.max_batch_size.min(64));
+    batch.push(first);
+
+    let deadline = tokio::time::Instant::now() + policy.max_delay;
+    while batch.len() < policy.max_batch_size {
+        // Take whatever is already queued without touching the timer.
+        match rx.try_recv() {
+            Ok(event) => {
+                batch.push(event);
+                continue;
+            }
+            Err(mpsc::error::TryRecvError::Disconnected) => break,
+            Err(mpsc::error::TryRecvError::Empty) => {}
+        }
+        match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Some(event)) => batch.push(event),
+            Ok(None) | Err(_) => break,
+        }
+    }
+    Some(batch)
+}
+
+/// Hand `batch` to `callback` as a single Python list, decoding events
+/// into typed objects when `schemas` has a matching event schema.
+pub fn deliver(
+    py: Python<'_>,
+    callback: &PyObject,
+    batch: &[serde_json::Value],
+    schemas: Option<&EventSchemaRegistry>,
+) -> PyResult<()> {
+    let events = match schemas {
+        Some(schemas) => PyList::new_bound(
+            py,
+            batch
+                .iter()
+                .map(|event| schemas.decode_value(py, event))
+                .collect::<PyResult<Vec<_>>>()?,
+        ),
+        None => PyList::new_bound(py, batch.iter().map(|event| json_to_python(py, event))),
+    };
+    callback.call1(py, (events,))?;
+    Ok(())
+}
+
+// ============================================================================
+// Slow-consumer detection
+// ============================================================================
+
+/// Default backlog depth at which the consumer counts as lagging.
+pub const DEFAULT_LAG_MAX_DEPTH: usize = 1_000;
+
+/// Default age of the oldest buffered event at which the consumer lags.
+pub const DEFAULT_LAG_MAX_AGE: Duration = Duration::from_secs(5);
+
+/// Thresholds past which the consumer is reported as slow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LagPolicy {
+    pub max_depth: usize,
+    pub max_age: Duration,
+}
+
+impl Default for LagPolicy {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_LAG_MAX_DEPTH,
+            max_age: DEFAULT_LAG_MAX_AGE,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LagState {
+    /// Arrival time of every buffered event, oldest first.
+    buffered: VecDeque<Instant>,
+    delivered: u64,
+    high_water: usize,
+    lagging: bool,
+    lag_episodes: u64,
+}
+
+/// Backlog tracker shared by an event producer and its consumer.
+///
+/// The producer calls [`LagMonitor::enqueued`] for every event it pushes
+/// and the consumer [`LagMonitor::delivered`] after handing a batch to
+/// Python. [`LagMonitor::check`] fires once when a threshold is crossed and
+/// re-arms only after the backlog has drained below half of both thresholds,
+/// so a consumer hovering at the limit is not reported on every batch.
+#[derive(Debug, Default)]
+pub struct LagMonitor {
+    policy: LagPolicy,
+    state: Mutex<LagState>,
+}
+
+impl LagMonitor {
+    pub fn new(policy: LagPolicy) -> Self {
+        Self {
+            policy,
+            state: Mutex::default(),
+        }
+    }
+
+    pub fn enqueued(&self) {
+        self.enqueued_at(Instant::now());
+    }
+
+    fn enqueued_at(&self, now: Instant) {
+        if let Ok(mut state) = self.state.lock() {
+            state.buffered.push_back(now);
+            state.high_water = state.high_water.max(state.buffered.len());
+        }
+    }
+
+    /// Record that the `count` oldest buffered events reached Python.
+    pub fn delivered(&self, count: usize) {
+        if let Ok(mut state) = self.state.lock() {
+            let count = count.min(state.buffered.len());
+            state.buffered.drain(..count);
+            state.delivered += count as u64;
+        }
+    }
+
+    /// Current stats if the consumer has just started lagging.
+    pub fn check(&self) -> Option<serde_json::Value> {
+        self.check_at(Instant::now())
+    }
+
+    fn check_at(&self, now: Instant) -> Option<serde_json::Value> {
+        let mut state = self.state.lock().ok()?;
+        let depth = state.buffered.len();
+        let age = oldest_age(&state, now);
+
+        if depth >= self.policy.max_depth || age >= self.policy.max_age {
+            if state.lagging {
+                return None;
+            }
+            state.lagging = true;
+            state.lag_episodes += 1;
+            return Some(stats_json(&state, now));
+        }
+        if depth < self.policy.max_depth / 2 && age < self.policy.max_age / 2 {
+            state.lagging = false;
+        }
+        None
+    }
+
+    /// `{queued, oldestAgeMs, delivered, highWater, lagging, lagEpisodes}`.
+    pub fn stats(&self) -> serde_json::Value {
+        match self.state.lock() {
+            Ok(state) => stats_json(&state, Instant::now()),
+            Err(_) => serde_json::Value::Null,
+        }
+    }
+}
+
+fn oldest_age(state: &LagState, now: Instant) -> Duration {
+    state
+        .buffered
+        .front()
+        .map(|oldest| now.saturating_duration_since(*oldest))
+        .unwrap_or_default()
+}
+
+fn stats_json(state: &LagState, now: Instant) -> serde_json::Value {
+    serde_json::json!({
+        "queued": state.buffered.len(),
+        "oldestAgeMs": oldest_age(state, now).as_secs_f64() * 1000.0,
+        "delivered": state.delivered,
+        "highWater": state.high_water,
+        "lagging": state.lagging,
+        "lagEpisodes": state.lag_episodes,
+    })
+}
+
+/// Report a lagging consumer: `on_slow_consumer(stats)` when given,
+/// otherwise a `SlowConsumerWarning`.
+pub fn report_lag(
+    py: Python<'_>,
+    on_slow_consumer: Option<&PyObject>,
+    stats: &serde_json::Value,
+) -> PyResult<()> {
+    match on_slow_consumer {
+        Some(callback) => {
+            callback.call1(py, (json_to_python(py, stats),))?;
+            Ok(())
+        }
+        None => warnings::warn(
+            py,
+            WarningKind::SlowConsumer,
+            &format!(
+                "Event handler is falling behind: {} events buffered, oldest {:.0} ms old",
+                stats["queued"],
+                stats["oldestAgeMs"].as_f64().unwrap_or_default()
+            ),
+        ),
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Queued events are grouped up to the size limit.
+    #[tokio::test]
+    async fn test_batches_respect_max_size() {
+        let (tx, mut rx) = mpsc::channel(16);
+        for i in 0..5 {
+            tx.send(i).await.unwrap();
+        }
+        drop(tx);
+
+        let policy = BatchPolicy::new(2, 1.0).unwrap();
+        assert_eq!(next_batch(&mut rx, policy).await, Some(vec![0, 1]));
+        assert_eq!(next_batch(&mut rx, policy).await, Some(vec![2, 3]));
+        assert_eq!(next_batch(&mut rx, policy).await, Some(vec![4]));
+        assert_eq!(next_batch(&mut rx, policy).await, None);
+    }
+
+    /// A partial batch is released once the delay expires.
+    #[tokio::test(start_paused = true)]
+    async fn test_partial_batch_released_after_delay() {
+        let (tx, mut rx) = mpsc::channel(16);
+        tx.send(1).await.unwrap();
+
+        let policy = BatchPolicy::new(10, 0.05).unwrap();
+        assert_eq!(next_batch(&mut rx, policy).await, Some(vec![1]));
+        drop(tx);
+    }
+
+    /// Zero-sized batches and negative delays are rejected.
+    #[test]
+    fn test_policy_validation() {
+        assert!(BatchPolicy::new(0, 0.01).is_err());
+        assert!(BatchPolicy::new(1, -1.0).is_err());
+        assert_eq!(
+            BatchPolicy::new(DEFAULT_MAX_BATCH_SIZE, 0.01).unwrap(),
+            BatchPolicy::default()
+        );
+    }
+
+    /// Crossing a threshold reports once; draining re-arms the monitor.
+    #[test]
+    fn test_lag_reported_once_per_episode() {
+        let monitor = LagMonitor::new(LagPolicy {
+            max_depth: 4,
+            max_age: Duration::from_secs(60),
+        });
+        let now = Instant::now();
+        for _ in 0..4 {
+            monitor.enqueued_at(now);
+        }
+
+        let stats = monitor.check_at(now).expect("depth threshold reached");
+        assert_eq!(stats["queued"], 4);
+        assert!(monitor.check_at(now).is_none());
+
+        monitor.delivered(3);
+        assert!(monitor.check_at(now).is_none());
+        for _ in 0..3 {
+            monitor.enqueued_at(now);
+        }
+        assert_eq!(monitor.check_at(now).unwrap()["lagEpisodes"], 2);
+        assert_eq!(monitor.stats()["highWater"], 4);
+    }
+
+    /// An old event trips the age threshold even with a shallow queue.
+    #[test]
+    fn test_lag_by_age() {
+        let monitor = LagMonitor::new(LagPolicy {
+            max_depth: 100,
+            max_age: Duration::from_secs(1),
+        });
+        let start = Instant::now();
+        monitor.enqueued_at(start);
+
+        assert!(monitor.check_at(start).is_none());
+        let stats = monitor
+            .check_at(start + Duration::from_secs(2))
+            .expect("age threshold reached");
+        assert_eq!(stats["oldestAgeMs"], 2000.0);
+    }
+}
@@ -0,0 +1,102 @@
+//! Optional Cargo features the extension was built with, behind
+//! `calimero.features()`.
+//!
+//! Heavy integrations (broker sinks, the OS keychain, HTTP/3) are compiled
+//! in only when asked for, so a default build stays small. pip extras of
+//! the same names (`calimero-client-py[kafka,keyring]`) document which ones
+//! a deployment relies on; the native code is selected when the package is
+//! built, e.g. `MATURIN_PEP517_ARGS="--features kafka,keyring"`. Calling
+//! into a feature that was left out raises [`FeatureNotBuiltError`] naming
+//! how to get it.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyNotImplementedError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+create_exception!(
+    calimero_client_py,
+    FeatureNotBuiltError,
+    PyNotImplementedError,
+    "The installed build of calimero-client-py leaves out an optional feature this call needs."
+);
+
+/// Optional features, with what each enables.
+pub const FEATURES: [(&str, bool, &str); 4] = [
+    (
+        "kafka",
+        cfg!(feature = "kafka"),
+        "Kafka sink of CdcExporter",
+    ),
+    (
+        "nats",
+        cfg!(feature = "nats"),
+        "NATS JetStream sink of CdcExporter",
+    ),
+    (
+        "keyring",
+        cfg!(feature = "keyring"),
+        "OS keychain token storage and Signer.from_keychain",
+    ),
+    (
+        "http3",
+        cfg!(feature = "http3"),
+        "experimental HTTP/3 transport (create_client(http3=True))",
+    ),
+];
+
+/// Whether `name` was built in; unknown names were not.
+pub fn is_built(name: &str) -> bool {
+    FEATURES
+        .iter()
+        .any(|(feature, built, _)| *feature == name && *built)
+}
+
+/// Why `name` is unavailable and how to build it in.
+pub fn not_built_message(name: &str) -> String {
+    format!(
+        "calimero-client-py was built without the `{name}` feature; reinstall with \
+         MATURIN_PEP517_ARGS=\"--features {name}\" pip install \"calimero-client-py[{name}]\""
+    )
+}
+
+/// [`FeatureNotBuiltError`] for `name`.
+pub fn not_built(name: &str) -> PyErr {
+    FeatureNotBuiltError::new_err(not_built_message(name))
+}
+
+/// Optional features of the installed build: `{name: built}` for `kafka`,
+/// `nats`, `keyring` and `http3`.
+#[pyfunction]
+pub fn features(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let features = PyDict::new_bound(py);
+    for (name, built, _) in FEATURES {
+        features.set_item(name, built)?;
+    }
+    Ok(features)
+}
+
+pub fn register(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add(
+        "FeatureNotBuiltError",
+        py.get_type_bound::<FeatureNotBuiltError>(),
+    )?;
+    Ok(())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unknown features are never built; messages name the extra.
+    #[test]
+    fn test_is_built() {
+        assert!(!is_built("arrow"));
+        assert_eq!(is_built("kafka"), cfg!(feature = "kafka"));
+        assert!(not_built_message("nats").contains("calimero-client-py[nats]"));
+    }
+}
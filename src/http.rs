@@ -0,0 +1,608 @@
+//! Direct HTTP access to node endpoints not wrapped by `calimero-client`.
+//!
+//! `calimero-client` owns the request pipeline for the typed admin and
+//! JSON-RPC calls, but it does not expose request headers (e.g. `Range`) or
+//! raw status codes. Bindings that need those go through this module, which
+//! attaches the same bearer token the typed client would use and runs the
+//! calling client's request and response hooks (see [`crate::middleware`])
+//! and transcript (see [`crate::transcript`]).
+
+use calimero_client::traits::ClientStorage;
+use calimero_client::JwtToken;
+use eyre::WrapErr;
+use reqwest::header::{
+    HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE, RANGE,
+};
+use reqwest::StatusCode;
+use url::Url;
+use zeroize::Zeroizing;
+
+use crate::limits;
+use crate::maintenance;
+use crate::middleware;
+use crate::quota;
+use crate::rate_limit;
+use crate::storage::WipeOnDrop;
+use crate::transcript::{self, Exchange, Payload, Transcript};
+use crate::wire::MSGPACK_CONTENT_TYPE;
+
+/// HTTP client speaking HTTP/3 (QUIC) only.
+///
+/// Experimental: requires the `http3` feature, which in turn needs reqwest's
+/// unstable cfg (`RUSTFLAGS="--cfg reqwest_unstable"`). The node must serve
+/// HTTP/3 on the same host and port; there is no fallback to TCP.
+#[cfg(feature = "http3")]
+pub fn http3_client() -> eyre::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .http3_prior_knowledge()
+        .build()
+        .wrap_err("Failed to build HTTP/3 client")
+}
+
+/// Open a pooled connection to the node with a cheap unauthenticated `GET`.
+///
+/// Any HTTP status counts: the point is the DNS lookup, TCP connect and TLS
+/// handshake, after which reqwest keeps the connection alive (90 s idle) and
+/// rustls keeps the session ticket for cheap resumption on reconnect.
+pub async fn warm_up(client: &reqwest::Client, api_url: &Url) -> eyre::Result<StatusCode> {
+    let url = api_url
+        .join("admin-api/health")
+        .wrap_err("Invalid node URL")?;
+    let response = client
+        .get(url.clone())
+        .send()
+        .await
+        .wrap_err_with(|| format!("Request to {} failed", url))?;
+    Ok(response.status())
+}
+
+/// Send `request` to `url`, running the hooks of the call it belongs to.
+///
+/// Waits out a `Retry-After` pause of the node first, and pauses it when the
+/// reply is a 429 announcing one (see [`crate::rate_limit`]). A call with a
+/// transcript (see [`crate::transcript`]) records the exchange, or is
+/// answered from the transcript without sending anything.
+async fn send(request: reqwest::RequestBuilder, url: &Url) -> eyre::Result<reqwest::Response> {
+    let transcript = transcript::active();
+    if let Some(transcript) = transcript.as_deref().filter(|t| t.is_replay()) {
+        return replay(transcript, request, url);
+    }
+    rate_limit::wait_for_pause(url).await;
+    let response = match transcript {
+        Some(transcript) => record(&transcript, request, url).await?,
+        None => dispatch(request, url).await?,
+    };
+    rate_limit::observe(url, response.status(), response.headers());
+    Ok(response)
+}
+
+/// Answer `request` with the next matching response of `transcript`.
+fn replay(
+    transcript: &Transcript,
+    request: reqwest::RequestBuilder,
+    url: &Url,
+) -> eyre::Result<reqwest::Response> {
+    let (_, request) = request.build_split();
+    let request = request.wrap_err_with(|| format!("Invalid request to {}", url))?;
+    let exchange = transcript.next(request.method().as_str(), url)?;
+    Transcript::response(&exchange)
+}
+
+/// Send `request` and append it with its response to `transcript`. The
+/// response body is read in full to be written, then handed on as usual.
+async fn record(
+    transcript: &Transcript,
+    request: reqwest::RequestBuilder,
+    url: &Url,
+) -> eyre::Result<reqwest::Response> {
+    let (client, request) = request.build_split();
+    let request = request.wrap_err_with(|| format!("Invalid request to {}", url))?;
+    let method = request.method().to_string();
+    let request_headers = transcript::redacted_headers(request.headers());
+    let request_body = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .map(Payload::new);
+    let request = reqwest::RequestBuilder::from_parts(client, request);
+    let response = dispatch(request, url).await?;
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response
+        .bytes()
+        .await
+        .wrap_err_with(|| format!("Failed to read response from {}", url))?;
+    transcript.append(Exchange {
+        seq: 0,
+        method,
+        url: transcript::request_path(url),
+        request_headers,
+        request_body,
+        status: status.as_u16(),
+        headers: transcript::redacted_headers(&headers),
+        body: Payload::new(&body),
+    })?;
+
+    let mut response = ::http::Response::new(body);
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    Ok(reqwest::Response::from(response))
+}
+
+async fn dispatch(request: reqwest::RequestBuilder, url: &Url) -> eyre::Result<reqwest::Response> {
+    let Some(middleware) = middleware::active() else {
+        return request
+            .send()
+            .await
+            .wrap_err_with(|| format!("Request to {} failed", url));
+    };
+    let (client, request) = request.build_split();
+    let mut request = request.wrap_err_with(|| format!("Invalid request to {}", url))?;
+    let headers = std::mem::take(request.headers_mut());
+    *request.headers_mut() = middleware.before_request(request.method(), url, headers)?;
+    let method = request.method().clone();
+    let started = std::time::Instant::now();
+    let response = client
+        .execute(request)
+        .await
+        .wrap_err_with(|| format!("Request to {} failed", url))?;
+    middleware.after_response(
+        &method,
+        url,
+        response.status(),
+        response.headers(),
+        started.elapsed(),
+    );
+    Ok(response)
+}
+
+/// Build the `Authorization` header for `node_name` from cached tokens.
+///
+/// Returns an empty map for unnamed connections or when no token is cached,
+/// matching how the typed client talks to nodes with auth disabled.
+pub async fn auth_headers<S: ClientStorage>(
+    storage: &S,
+    node_name: Option<&str>,
+) -> eyre::Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    let Some(node_name) = node_name else {
+        return Ok(headers);
+    };
+    if let Some(tokens) = storage.load_tokens(node_name).await? {
+        let tokens = WipeOnDrop::new(tokens);
+        let bearer = Zeroizing::new(format!("Bearer {}", tokens.access_token));
+        let mut value: HeaderValue = bearer
+            .parse()
+            .wrap_err("Access token is not a valid header value")?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+    Ok(headers)
+}
+
+/// A URL plus the headers a third party needs to fetch it directly.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizedRequest {
+    pub url: String,
+    pub headers: std::collections::BTreeMap<String, String>,
+    /// Unix timestamp after which the embedded credentials stop working.
+    pub expires_at: Option<i64>,
+}
+
+/// Describe an authenticated `GET` of `path` without performing it.
+///
+/// Nodes do not mint pre-signed URLs, so the closest equivalent is handing
+/// out the URL together with `token` and its expiry. Callers pass a token
+/// scoped to the resource, never the session's own.
+pub fn authorize_get(
+    api_url: &Url,
+    path: &str,
+    token: Option<&JwtToken>,
+) -> eyre::Result<AuthorizedRequest> {
+    let url = api_url
+        .join(path)
+        .wrap_err_with(|| format!("Invalid request path: {}", path))?;
+
+    let mut headers = std::collections::BTreeMap::new();
+    if let Some(token) = token {
+        headers.insert(
+            AUTHORIZATION.as_str().to_string(),
+            format!("Bearer {}", token.access_token),
+        );
+    }
+
+    Ok(AuthorizedRequest {
+        url: url.to_string(),
+        headers,
+        expires_at: token.and_then(|token| token.expires_at),
+    })
+}
+
+/// A request as it would be sent, for review before sending it.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestPreview {
+    pub method: String,
+    pub url: String,
+    pub headers: std::collections::BTreeMap<String, String>,
+    pub body: serde_json::Value,
+    /// Encoding the body would go out in: `json` or `msgpack`.
+    pub wire: &'static str,
+    /// Unix timestamp after which the attached token stops working.
+    pub expires_at: Option<i64>,
+}
+
+/// Describe a `POST` of `body` to `path` without performing it, with the
+/// bearer token the request would carry.
+///
+/// Request hooks are not run: they belong to the call that sends.
+pub async fn preview_post<S: ClientStorage>(
+    api_url: &Url,
+    path: &str,
+    storage: &S,
+    node_name: Option<&str>,
+    body: serde_json::Value,
+    msgpack: bool,
+) -> eyre::Result<RequestPreview> {
+    let tokens = match node_name {
+        Some(node_name) => storage.load_tokens(node_name).await?.map(WipeOnDrop::new),
+        None => None,
+    };
+    let authorized = authorize_get(api_url, path, tokens.as_deref())?;
+    let mut headers = authorized.headers;
+    let (content_type, wire) = match msgpack {
+        true => (MSGPACK_CONTENT_TYPE, "msgpack"),
+        false => ("application/json", "json"),
+    };
+    headers.insert(CONTENT_TYPE.as_str().to_string(), content_type.to_string());
+    if msgpack {
+        headers.insert(CONTENT_ENCODING.as_str().to_string(), "gzip".to_string());
+        headers.insert(
+            ACCEPT.as_str().to_string(),
+            MSGPACK_CONTENT_TYPE.to_string(),
+        );
+    }
+    Ok(RequestPreview {
+        method: reqwest::Method::POST.to_string(),
+        url: authorized.url,
+        headers,
+        body,
+        wire,
+        expires_at: authorized.expires_at,
+    })
+}
+
+/// Fetch `length` bytes (or everything after `offset` when `None`) of the
+/// resource at `path` using an HTTP range request.
+///
+/// Nodes that honor `Range` answer `206 Partial Content` and only the
+/// requested bytes cross the wire. Nodes that ignore it answer `200` with the
+/// full body, which is then sliced locally so callers always get the same
+/// bytes back.
+pub async fn get_range<S: ClientStorage>(
+    client: &reqwest::Client,
+    api_url: &Url,
+    path: &str,
+    storage: &S,
+    node_name: Option<&str>,
+    offset: u64,
+    length: Option<u64>,
+    limit: Option<usize>,
+) -> eyre::Result<Vec<u8>> {
+    let url = api_url
+        .join(path)
+        .wrap_err_with(|| format!("Invalid request path: {}", path))?;
+    let Some(range) = range_header(offset, length)? else {
+        return Ok(Vec::new());
+    };
+
+    let request = client
+        .get(url.clone())
+        .headers(auth_headers(storage, node_name).await?)
+        .header(RANGE, range);
+    let response = send(request, &url).await?;
+    quota::observe(&url, response.headers());
+    maintenance::check_response(response.status(), response.headers())?;
+
+    match response.status() {
+        StatusCode::PARTIAL_CONTENT => limits::read_body(response, limit).await,
+        StatusCode::OK => {
+            let body = limits::read_body(response, limit).await?;
+            Ok(slice_range(&body, offset, length).to_vec())
+        }
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            eyre::bail!(
+                "Range starting at offset {} is past the end of the blob",
+                offset
+            )
+        }
+        status => eyre::bail!("Request to {} failed with status {}", url, status),
+    }
+}
+
+/// Start streaming the resource at `path` from byte `offset`.
+///
+/// Returns the response with the offset its body actually starts at:
+/// `offset` when the node honored the range request, `0` when it ignored
+/// it and sent everything. `None` means there is nothing past `offset`
+/// (`416 Range Not Satisfiable`).
+pub async fn get_from<S: ClientStorage>(
+    client: &reqwest::Client,
+    api_url: &Url,
+    path: &str,
+    storage: &S,
+    node_name: Option<&str>,
+    offset: u64,
+) -> eyre::Result<Option<(reqwest::Response, u64)>> {
+    let url = api_url
+        .join(path)
+        .wrap_err_with(|| format!("Invalid request path: {}", path))?;
+
+    let mut request = client
+        .get(url.clone())
+        .headers(auth_headers(storage, node_name).await?);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={}-", offset));
+    }
+    let response = send(request, &url).await?;
+    quota::observe(&url, response.headers());
+    maintenance::check_response(response.status(), response.headers())?;
+
+    match response.status() {
+        StatusCode::PARTIAL_CONTENT => Ok(Some((response, offset))),
+        StatusCode::OK => Ok(Some((response, 0))),
+        StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => Ok(None),
+        status => eyre::bail!("Request to {} failed with status {}", url, status),
+    }
+}
+
+/// Upload a streaming body with `PUT` to `path` and decode the JSON reply.
+///
+/// Bodies here and in [`get_range`]/[`post_msgpack`] are read under `limit`
+/// (see [`limits::read_body`]).
+///
+/// Used for blob uploads whose bytes are produced incrementally, so the
+/// payload never has to be buffered in full on either side of the FFI.
+pub async fn put_stream<S: ClientStorage>(
+    client: &reqwest::Client,
+    api_url: &Url,
+    path: &str,
+    storage: &S,
+    node_name: Option<&str>,
+    body: reqwest::Body,
+    limit: Option<usize>,
+) -> eyre::Result<serde_json::Value> {
+    let url = api_url
+        .join(path)
+        .wrap_err_with(|| format!("Invalid request path: {}", path))?;
+
+    let request = client
+        .put(url.clone())
+        .headers(auth_headers(storage, node_name).await?)
+        .body(body);
+    let response = send(request, &url).await?;
+    quota::observe(&url, response.headers());
+    maintenance::check_response(response.status(), response.headers())?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        eyre::bail!("Request to {} failed with status {}: {}", url, status, text);
+    }
+    let body = limits::read_body(response, limit).await?;
+    serde_json::from_slice(&body).wrap_err("Failed to decode JSON response")
+}
+
+/// `POST` a gzip-compressed msgpack body to `path`, expecting msgpack back.
+///
+/// Returns `Ok(None)` when the node rejects the format (any 4xx, or a reply
+/// that isn't msgpack) so the caller can fall back to JSON; the request was
+/// refused before execution in that case.
+pub async fn post_msgpack<S: ClientStorage>(
+    client: &reqwest::Client,
+    api_url: &Url,
+    path: &str,
+    storage: &S,
+    node_name: Option<&str>,
+    body: Vec<u8>,
+    limit: Option<usize>,
+) -> eyre::Result<Option<Vec<u8>>> {
+    let url = api_url
+        .join(path)
+        .wrap_err_with(|| format!("Invalid request path: {}", path))?;
+
+    let request = client
+        .post(url.clone())
+        .headers(auth_headers(storage, node_name).await?)
+        .header(CONTENT_TYPE, MSGPACK_CONTENT_TYPE)
+        .header(CONTENT_ENCODING, "gzip")
+        .header(ACCEPT, MSGPACK_CONTENT_TYPE)
+        .body(body);
+    let response = send(request, &url).await?;
+    quota::observe(&url, response.headers());
+    maintenance::check_response(response.status(), response.headers())?;
+
+    let status = response.status();
+    if status.is_client_error() {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        eyre::bail!("Request to {} failed with status {}", url, status);
+    }
+    let is_msgpack = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with(MSGPACK_CONTENT_TYPE));
+    if !is_msgpack {
+        return Ok(None);
+    }
+    Ok(Some(limits::read_body(response, limit).await?))
+}
+
+/// Send a JSON request to `path` and decode the JSON reply.
+///
+/// For endpoints outside the typed client's API, such as the node's auth
+/// service. An empty success body decodes as `null`; a failure status is an
+/// error carrying the status code, so auth failures are recognized by
+/// [`crate::auth::is_auth_error`].
+#[allow(clippy::too_many_arguments)]
+pub async fn send_json<S: ClientStorage>(
+    client: &reqwest::Client,
+    method: reqwest::Method,
+    api_url: &Url,
+    path: &str,
+    storage: &S,
+    node_name: Option<&str>,
+    body: Option<&serde_json::Value>,
+    limit: Option<usize>,
+) -> eyre::Result<serde_json::Value> {
+    let url = api_url
+        .join(path)
+        .wrap_err_with(|| format!("Invalid request path: {}", path))?;
+
+    let mut request = client
+        .request(method.clone(), url.clone())
+        .headers(auth_headers(storage, node_name).await?);
+    if let Some(body) = body {
+        request = request.json(body);
+    }
+    let response = send(request, &url).await?;
+    quota::observe(&url, response.headers());
+    maintenance::check_response(response.status(), response.headers())?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        eyre::bail!("{} {} failed with status {}: {}", method, url, status, text);
+    }
+    let body = limits::read_body(response, limit).await?;
+    if body.is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    serde_json::from_slice(&body).wrap_err("Failed to decode JSON response")
+}
+
+/// A response handed back as is, whatever its status.
+#[derive(Debug)]
+pub struct RawResponse {
+    pub status: StatusCode,
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+/// Send `body` to `path` with the bearer token plus `headers` (which win
+/// over it) and return the response without interpreting its status.
+///
+/// Backs `calimero.compat.http`, for scripts written against raw status
+/// codes. `path` may be a full URL, but only on the node's own origin, so
+/// the token never leaves for another host.
+#[allow(clippy::too_many_arguments)]
+pub async fn send_raw<S: ClientStorage>(
+    client: &reqwest::Client,
+    method: reqwest::Method,
+    api_url: &Url,
+    path: &str,
+    storage: &S,
+    node_name: Option<&str>,
+    headers: HeaderMap,
+    body: Option<Vec<u8>>,
+    limit: Option<usize>,
+) -> eyre::Result<RawResponse> {
+    let url = api_url
+        .join(path)
+        .wrap_err_with(|| format!("Invalid request path: {}", path))?;
+    if url.origin() != api_url.origin() {
+        eyre::bail!(
+            "{} is not on the node's origin {}; refusing to send its token there",
+            url,
+            api_url.origin().ascii_serialization()
+        );
+    }
+
+    let mut all_headers = auth_headers(storage, node_name).await?;
+    for (name, value) in headers.iter() {
+        all_headers.insert(name, value.clone());
+    }
+    let mut request = client.request(method, url.clone()).headers(all_headers);
+    if let Some(body) = body {
+        request = request.body(body);
+    }
+    let response = send(request, &url).await?;
+    quota::observe(&url, response.headers());
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = limits::read_body(response, limit).await?;
+    Ok(RawResponse {
+        status,
+        url,
+        headers,
+        body,
+    })
+}
+
+/// `Range` header value for `length` bytes from `offset` (to the end when
+/// `None`), or `None` for an empty range that needs no request. Ranges past
+/// `u64::MAX` are rejected.
+pub fn range_header(offset: u64, length: Option<u64>) -> eyre::Result<Option<String>> {
+    let Some(length) = length else {
+        return Ok(Some(format!("bytes={}-", offset)));
+    };
+    let Some(last) = length.checked_sub(1) else {
+        return Ok(None);
+    };
+    let last = last
+        .checked_add(offset)
+        .ok_or_else(|| eyre::eyre!("offset + length is past the largest blob offset"))?;
+    Ok(Some(format!("bytes={}-{}", offset, last)))
+}
+
+/// Slice `[offset, offset + length)` out of `body`, clamped to its bounds.
+pub fn slice_range(body: &[u8], offset: u64, length: Option<u64>) -> &[u8] {
+    let start = usize::try_from(offset)
+        .unwrap_or(usize::MAX)
+        .min(body.len());
+    let end = match length {
+        Some(length) => start
+            .saturating_add(usize::try_from(length).unwrap_or(usize::MAX))
+            .min(body.len()),
+        None => body.len(),
+    };
+    &body[start..end]
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Local slicing mirrors what a `206` response would have returned.
+    #[test]
+    fn test_slice_range() {
+        let body = b"0123456789";
+        assert_eq!(slice_range(body, 2, Some(3)), b"234");
+        assert_eq!(slice_range(body, 8, None), b"89");
+        assert_eq!(slice_range(body, 8, Some(10)), b"89");
+        assert_eq!(slice_range(body, 20, Some(1)), b"");
+    }
+
+    /// Range headers are inclusive; empty ranges need no header and
+    /// overflowing ranges fail.
+    #[test]
+    fn test_range_header() {
+        assert_eq!(range_header(2, Some(3)).unwrap().unwrap(), "bytes=2-4");
+        assert_eq!(range_header(8, None).unwrap().unwrap(), "bytes=8-");
+        assert_eq!(
+            range_header(u64::MAX, Some(1)).unwrap().unwrap(),
+            format!("bytes={0}-{0}", u64::MAX)
+        );
+        assert_eq!(range_header(5, Some(0)).unwrap(), None);
+        assert!(range_header(u64::MAX, Some(2)).is_err());
+    }
+}
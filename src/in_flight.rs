@@ -0,0 +1,148 @@
+//! Executions a client has started and not finished, behind
+//! `Client.pending_executions()`.
+//!
+//! Nodes do not expose their execution queue, so when a pipeline seems stuck
+//! the client reports what it knows itself: the `execute` calls it started
+//! that have not completed (waiting for a concurrency slot or their turn on
+//! a serialized context, or sent and awaiting the node's answer) and the
+//! calls parked in its offline outbox. [`InFlight::track`] lists a call from
+//! the moment its future is created until it completes or is dropped, so
+//! cancelled calls never linger.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde_json::{json, Value};
+
+/// One execution that has not completed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InFlightCall {
+    pub id: u64,
+    pub context_id: String,
+    pub method: String,
+    /// Executor public key the caller passed, if any.
+    pub executor: Option<String>,
+    /// Unix time in milliseconds at which the call started.
+    pub started_at: i64,
+    pub started: Instant,
+}
+
+impl InFlightCall {
+    pub fn to_json(&self, now: Instant) -> Value {
+        json!({
+            "id": self.id,
+            "contextId": self.context_id,
+            "method": self.method,
+            "executor": self.executor,
+            "state": "in_flight",
+            "startedAt": self.started_at,
+            "ageMs": now.duration_since(self.started).as_millis() as u64,
+        })
+    }
+}
+
+/// Calls of one client that have not completed, by start order.
+#[derive(Debug, Default)]
+pub struct InFlight {
+    next_id: AtomicU64,
+    calls: Mutex<BTreeMap<u64, InFlightCall>>,
+}
+
+/// Listing of one call, removed when dropped.
+struct Registration {
+    calls: Arc<InFlight>,
+    id: u64,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Ok(mut calls) = self.calls.calls.lock() {
+            calls.remove(&self.id);
+        }
+    }
+}
+
+impl InFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `call`, listed from now until it completes or is dropped.
+    pub fn track<F: Future>(
+        self: &Arc<Self>,
+        context_id: &str,
+        method: &str,
+        executor: Option<String>,
+        call: F,
+    ) -> impl Future<Output = F::Output> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.insert(
+                id,
+                InFlightCall {
+                    id,
+                    context_id: context_id.to_string(),
+                    method: method.to_string(),
+                    executor,
+                    started_at: chrono::Utc::now().timestamp_millis(),
+                    started: Instant::now(),
+                },
+            );
+        }
+        let registration = Registration {
+            calls: Arc::clone(self),
+            id,
+        };
+        async move {
+            let _registration = registration;
+            call.await
+        }
+    }
+
+    /// Calls not yet completed, of `context_id` or all, oldest first.
+    pub fn calls(&self, context_id: Option<&str>) -> Vec<InFlightCall> {
+        self.calls
+            .lock()
+            .map(|calls| {
+                calls
+                    .values()
+                    .filter(|call| context_id.map_or(true, |id| call.context_id == id))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Calls are listed until they complete, and dropped ones disappear.
+    #[tokio::test]
+    async fn test_tracks_until_done_or_dropped() {
+        let in_flight = Arc::new(InFlight::new());
+        let (tx, rx) = tokio::sync::oneshot::channel::<u8>();
+        let first = in_flight.track("ctx-a", "set", Some("key".into()), rx);
+        let second = in_flight.track("ctx-b", "get", None, async { 2u8 });
+
+        let calls = in_flight.calls(None);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].method, "set");
+        assert_eq!(in_flight.calls(Some("ctx-b"))[0].method, "get");
+        assert_eq!(calls[0].to_json(Instant::now())["state"], "in_flight");
+
+        assert_eq!(second.await, 2);
+        assert_eq!(in_flight.calls(Some("ctx-b")), vec![]);
+        drop(first);
+        drop(tx);
+        assert!(in_flight.calls(None).is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+//! Context invitations passed between processes as strings.
+//!
+//! Contexts are joined through the group they belong to. `Client.invite()`
+//! either adds the invitee to that group directly (when it names one) or
+//! has the node sign an open invitation to it, and returns an
+//! [`Invitation`] encoded as one string (`calimero-invitation:` followed by
+//! base64url JSON) that test harnesses can hand to another process or
+//! node. `Client.join_context(payload)` on the invitee's node joins the
+//! group when the payload carries a signed invitation, then the context.
+//!
+//! `encode_invitation()` / `decode_invitation()` convert between the
+//! string and its dict form.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::utils::{json_to_python, python_to_json};
+
+/// Prefix of encoded invitation payloads.
+pub const INVITATION_PREFIX: &str = "calimero-invitation:";
+
+/// Current payload version.
+pub const INVITATION_VERSION: u32 = 1;
+
+/// What a node needs to join a context it was invited to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Invitation {
+    pub version: u32,
+    pub context_id: String,
+    /// Group the context belongs to, joined with `invitation`.
+    pub group_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inviter: Option<String>,
+    /// Identity added to the group, for invitations to one identity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invitee: Option<String>,
+    /// Open invitation to the group signed by the inviting node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invitation: Option<Value>,
+}
+
+impl Invitation {
+    pub fn encode(&self) -> eyre::Result<String> {
+        Ok(format!(
+            "{}{}",
+            INVITATION_PREFIX,
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(self)?)
+        ))
+    }
+
+    /// Invitation of an encoded payload, or of its JSON form.
+    pub fn decode(payload: &str) -> eyre::Result<Self> {
+        let payload = payload.trim();
+        let invitation: Self = match payload.strip_prefix(INVITATION_PREFIX) {
+            Some(encoded) => {
+                let json = URL_SAFE_NO_PAD
+                    .decode(encoded.trim_end_matches('='))
+                    .wrap_err("Invalid invitation payload (not base64url)")?;
+                serde_json::from_slice(&json).wrap_err("Invalid invitation payload")?
+            }
+            None => serde_json::from_str(payload).wrap_err("Invalid invitation payload")?,
+        };
+        if invitation.version == 0 || invitation.version > INVITATION_VERSION {
+            eyre::bail!(
+                "Unsupported invitation version {} (this release reads up to {})",
+                invitation.version,
+                INVITATION_VERSION
+            );
+        }
+        Ok(invitation)
+    }
+
+    /// Whether `value` looks like an invitation payload rather than a
+    /// context id.
+    pub fn is_payload(value: &str) -> bool {
+        let value = value.trim_start();
+        value.starts_with(INVITATION_PREFIX) || value.starts_with('{')
+    }
+}
+
+/// Group id in a context record, inside its `data` envelope or not.
+pub fn group_of(context: &Value) -> Option<String> {
+    let context = context.get("data").unwrap_or(context);
+    ["groupId", "group_id"]
+        .iter()
+        .find_map(|name| context.get(*name).and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+/// Signed invitation in a `create_namespace_invitation` response.
+pub fn signed_invitation(response: &Value) -> Value {
+    let data = response.get("data").unwrap_or(response);
+    data.get("invitation").unwrap_or(data).clone()
+}
+
+fn payload_error(e: eyre::Report) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e))
+}
+
+/// Encode an invitation dict (`{contextId, groupId, ...}`) as a payload
+/// string for `join_context`.
+#[pyfunction]
+pub fn encode_invitation(invitation: &Bound<'_, PyAny>) -> PyResult<String> {
+    let mut value = python_to_json(invitation)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields
+            .entry("version")
+            .or_insert_with(|| INVITATION_VERSION.into());
+    }
+    let invitation: Invitation = serde_json::from_value(value)
+        .wrap_err("Invalid invitation")
+        .map_err(payload_error)?;
+    invitation.encode().map_err(payload_error)
+}
+
+/// Dict form of an invitation payload returned by `Client.invite()`.
+#[pyfunction]
+pub fn decode_invitation(py: Python<'_>, payload: &str) -> PyResult<PyObject> {
+    let invitation = Invitation::decode(payload).map_err(payload_error)?;
+    let value = serde_json::to_value(&invitation)
+        .wrap_err("Failed to serialize invitation")
+        .map_err(payload_error)?;
+    Ok(json_to_python(py, &value))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn invitation() -> Invitation {
+        Invitation {
+            version: INVITATION_VERSION,
+            context_id: "ctx".to_string(),
+            group_id: "group".to_string(),
+            inviter: None,
+            invitee: Some("key".to_string()),
+            invitation: Some(json!({"signature": "sig"})),
+        }
+    }
+
+    /// Payloads round-trip, encoded or as JSON.
+    #[test]
+    fn test_encode_decode() {
+        let payload = invitation().encode().unwrap();
+        assert!(payload.starts_with(INVITATION_PREFIX));
+        assert!(Invitation::is_payload(&payload));
+        assert_eq!(Invitation::decode(&payload).unwrap(), invitation());
+
+        let json = serde_json::to_string(&invitation()).unwrap();
+        assert!(Invitation::is_payload(&json));
+        assert_eq!(Invitation::decode(&json).unwrap(), invitation());
+        assert!(!Invitation::is_payload("11111111111111111111111111111111"));
+    }
+
+    /// Garbage and payloads from newer releases are rejected.
+    #[test]
+    fn test_decode_rejects_invalid() {
+        assert!(Invitation::decode("calimero-invitation:!!!").is_err());
+        assert!(Invitation::decode("{\"contextId\": \"ctx\"}").is_err());
+        let newer = Invitation {
+            version: INVITATION_VERSION + 1,
+            ..invitation()
+        };
+        assert!(Invitation::decode(&newer.encode().unwrap()).is_err());
+    }
+
+    /// Group ids and signed invitations are found inside `data` envelopes.
+    #[test]
+    fn test_response_fields() {
+        assert_eq!(
+            group_of(&json!({"data": {"id": "ctx", "groupId": "g"}})).as_deref(),
+            Some("g")
+        );
+        assert_eq!(group_of(&json!({"id": "ctx"})), None);
+        assert_eq!(
+            signed_invitation(&json!({"data": {"invitation": {"a": 1}}})),
+            json!({"a": 1})
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! JSON-RPC batch requests behind `Client.execute_batch`.
+//!
+//! Scripts that fan out hundreds of small reads pay a full HTTP round trip
+//! and auth header per `execute_function`. A batch packs up to
+//! `max_batch_size` calls into one JSON-RPC batch (a JSON array of
+//! requests, each tagged with its position as `id`) and matches the
+//! responses back by id, so results come back in call order whatever order
+//! the node answers in.
+//!
+//! Nodes that do not accept batches answer with a 4xx or a single
+//! non-array response; [`BatchSupport`] remembers that, and the calls are
+//! then sent one by one instead.
+//!
+//! `Client.execute_many` takes the other route for calls against one
+//! context: individual requests, [`DEFAULT_FAN_OUT`] of them in flight at
+//! once, driven from Rust with the GIL released, so thousands of small
+//! reads do not pay a Python round trip each.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use calimero_server_primitives::jsonrpc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error;
+
+/// Default number of calls packed into one batch request.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+
+/// Default number of `execute_many` calls in flight at once.
+pub const DEFAULT_FAN_OUT: usize = 32;
+
+/// One call of a batch, as reported back in `failed_inputs`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchCall {
+    pub context_id: String,
+    pub method: String,
+    pub args: String,
+}
+
+impl BatchCall {
+    /// JSON text identifying the call; accepted back by `execute_batch`.
+    pub fn input(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Batch request body for `requests`, each given its index as `id`.
+pub fn body<'a>(
+    requests: impl IntoIterator<Item = (usize, &'a jsonrpc::Request)>,
+) -> eyre::Result<Value> {
+    requests
+        .into_iter()
+        .map(|(index, request)| {
+            let mut request = serde_json::to_value(request)?;
+            request["id"] = Value::String(index.to_string());
+            Ok(request)
+        })
+        .collect::<eyre::Result<Vec<_>>>()
+        .map(Value::Array)
+}
+
+/// Responses of a batch reply by request index; `None` when the reply is
+/// not a batch (the node answered the array as a single request).
+pub fn split(reply: Value) -> Option<HashMap<usize, Value>> {
+    let Value::Array(responses) = reply else {
+        return None;
+    };
+    Some(
+        responses
+            .into_iter()
+            .filter_map(|response| {
+                let id = response.get("id")?;
+                let index = match id {
+                    Value::String(id) => id.parse().ok()?,
+                    id => usize::try_from(id.as_u64()?).ok()?,
+                };
+                Some((index, response))
+            })
+            .collect(),
+    )
+}
+
+/// Error of a JSON-RPC response, rendered, when it failed.
+pub fn response_error(response: &Value) -> Option<String> {
+    response
+        .get("error")
+        .filter(|error| !error.is_null())
+        .map(Value::to_string)
+}
+
+/// Whether a failed batch request means the node does not take batches
+/// (a client error other than an auth failure) rather than a failure the
+/// calls would hit one by one as well.
+pub fn rejected_as_batch(message: &str) -> bool {
+    matches!(
+        error::parse_status(message),
+        Some(status) if (400..500).contains(&status) && status != 401 && status != 403
+    )
+}
+
+const UNKNOWN: u8 = 0;
+const SUPPORTED: u8 = 1;
+const UNSUPPORTED: u8 = 2;
+
+/// Whether the node accepts batch requests, learned from the first batch.
+#[derive(Debug, Default)]
+pub struct BatchSupport(AtomicU8);
+
+impl BatchSupport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `false` once the node has refused a batch.
+    pub fn worth_trying(&self) -> bool {
+        self.0.load(Ordering::Relaxed) != UNSUPPORTED
+    }
+
+    pub fn record(&self, supported: bool) {
+        let state = if supported { SUPPORTED } else { UNSUPPORTED };
+        self.0.store(state, Ordering::Relaxed);
+    }
+
+    /// `"batch"`, `"single"` or `None` before the first batch
+    pub fn mode(&self) -> Option<&'static str> {
+        match self.0.load(Ordering::Relaxed) {
+            SUPPORTED => Some("batch"),
+            UNSUPPORTED => Some("single"),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Responses are matched by id, whatever order they arrive in.
+    #[test]
+    fn test_split_by_id() {
+        let reply = json!([
+            {"jsonrpc": "2.0", "id": "1", "error": {"type": "ServerError"}},
+            {"jsonrpc": "2.0", "id": "0", "result": {"output": 5}},
+            {"jsonrpc": "2.0", "id": 2, "result": null},
+        ]);
+        let responses = split(reply).unwrap();
+        assert_eq!(responses[&0]["result"]["output"], 5);
+        assert_eq!(
+            response_error(&responses[&1]).as_deref(),
+            Some(r#"{"type":"ServerError"}"#)
+        );
+        assert!(response_error(&responses[&2]).is_none());
+        assert!(split(json!({"error": "batch not supported"})).is_none());
+    }
+
+    /// Only non-auth client errors mean batches are not supported.
+    #[test]
+    fn test_rejected_as_batch() {
+        assert!(rejected_as_batch("POST /jsonrpc failed with status 400"));
+        assert!(rejected_as_batch("POST /jsonrpc failed with status 422"));
+        assert!(!rejected_as_batch("POST /jsonrpc failed with status 401"));
+        assert!(!rejected_as_batch("POST /jsonrpc failed with status 503"));
+        assert!(!rejected_as_batch("connection refused"));
+    }
+
+    /// Failed inputs round-trip through their JSON text.
+    #[test]
+    fn test_call_input_round_trip() {
+        let call = BatchCall {
+            context_id: "ctx".to_string(),
+            method: "get".to_string(),
+            args: "{}".to_string(),
+        };
+        let parsed: BatchCall = serde_json::from_str(&call.input()).unwrap();
+        assert_eq!(parsed, call);
+    }
+}
@@ -0,0 +1,154 @@
+//! JWT token storage in the platform keychain.
+//!
+//! Tokens are kept as one keychain item per node (macOS Keychain, Windows
+//! Credential Manager, the Secret Service / libsecret on Linux) rather than
+//! as plaintext JSON in the token cache directory. Requires the `keyring`
+//! feature; without it, or when no keychain is reachable (headless Linux,
+//! containers), [`KeyringStorage::is_available`] is `false` and callers fall
+//! back to the file cache.
+
+use calimero_client::traits::ClientStorage;
+use calimero_client::JwtToken;
+use eyre::WrapErr;
+
+/// Keychain service name token items are stored under.
+pub const KEYRING_SERVICE: &str = "calimero-client-py";
+
+/// Token storage backed by the OS keychain; items are keyed by node name.
+#[derive(Debug, Clone)]
+pub struct KeyringStorage {
+    service: String,
+}
+
+impl KeyringStorage {
+    pub fn new() -> Self {
+        Self {
+            service: KEYRING_SERVICE.to_string(),
+        }
+    }
+
+    /// Whether a keychain can be used from this process.
+    pub fn is_available(&self) -> bool {
+        backend::is_available(&self.service)
+    }
+
+    /// Store a secret other than tokens (e.g. a signing key) as `account`.
+    pub fn save_secret(&self, account: &str, secret: &str) -> eyre::Result<()> {
+        backend::set(&self.service, account, secret)
+            .wrap_err_with(|| format!("Failed to store {} in keychain", account))
+    }
+
+    pub fn load_secret(&self, account: &str) -> eyre::Result<Option<zeroize::Zeroizing<String>>> {
+        backend::get(&self.service, account)
+            .map(|secret| secret.map(zeroize::Zeroizing::new))
+            .wrap_err_with(|| format!("Failed to read {} from keychain", account))
+    }
+
+    pub fn remove_secret(&self, account: &str) -> eyre::Result<()> {
+        backend::delete(&self.service, account)
+            .wrap_err_with(|| format!("Failed to remove {} from keychain", account))
+    }
+}
+
+impl Default for KeyringStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl ClientStorage for KeyringStorage {
+    async fn save_tokens(&self, node_name: &str, tokens: &JwtToken) -> eyre::Result<()> {
+        let json = zeroize::Zeroizing::new(
+            serde_json::to_string(tokens).wrap_err("Failed to serialize JWT tokens to JSON")?,
+        );
+        backend::set(&self.service, node_name, &json)
+            .wrap_err_with(|| format!("Failed to store tokens in keychain for node: {}", node_name))
+    }
+
+    async fn load_tokens(&self, node_name: &str) -> eyre::Result<Option<JwtToken>> {
+        let Some(json) = backend::get(&self.service, node_name).wrap_err_with(|| {
+            format!(
+                "Failed to read tokens from keychain for node: {}",
+                node_name
+            )
+        })?
+        else {
+            return Ok(None);
+        };
+        let json = zeroize::Zeroizing::new(json);
+        let tokens = serde_json::from_str(&json).wrap_err_with(|| {
+            format!(
+                "Failed to parse token JSON from keychain for node: {}",
+                node_name
+            )
+        })?;
+        Ok(Some(tokens))
+    }
+
+    async fn remove_tokens(&self, node_name: &str) -> eyre::Result<()> {
+        backend::delete(&self.service, node_name).wrap_err_with(|| {
+            format!(
+                "Failed to remove tokens from keychain for node: {}",
+                node_name
+            )
+        })
+    }
+}
+
+#[cfg(feature = "keyring")]
+mod backend {
+    use keyring::{Entry, Error};
+
+    /// Account used to probe whether the keychain answers at all.
+    const PROBE_ACCOUNT: &str = "__availability_probe__";
+
+    pub fn is_available(service: &str) -> bool {
+        matches!(
+            Entry::new(service, PROBE_ACCOUNT).map(|entry| entry.get_password()),
+            Ok(Ok(_)) | Ok(Err(Error::NoEntry))
+        )
+    }
+
+    pub fn set(service: &str, account: &str, secret: &str) -> eyre::Result<()> {
+        Entry::new(service, account)?.set_password(secret)?;
+        Ok(())
+    }
+
+    pub fn get(service: &str, account: &str) -> eyre::Result<Option<String>> {
+        match Entry::new(service, account)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn delete(service: &str, account: &str) -> eyre::Result<()> {
+        match Entry::new(service, account)?.delete_credential() {
+            Ok(()) | Err(Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+mod backend {
+    const UNAVAILABLE: &str =
+        "keychain storage requires calimero-client-py built with the `keyring` feature";
+
+    pub fn is_available(_service: &str) -> bool {
+        false
+    }
+
+    pub fn set(_service: &str, _account: &str, _secret: &str) -> eyre::Result<()> {
+        eyre::bail!(UNAVAILABLE)
+    }
+
+    pub fn get(_service: &str, _account: &str) -> eyre::Result<Option<String>> {
+        eyre::bail!(UNAVAILABLE)
+    }
+
+    pub fn delete(_service: &str, _account: &str) -> eyre::Result<()> {
+        eyre::bail!(UNAVAILABLE)
+    }
+}
@@ -5,41 +5,294 @@
 //!
 //! ## Module Structure
 //!
-//! - `error` - PyClientError wrapper
+//! - `deprecation` - Table of renamed APIs forwarded with `DeprecationWarning`
+//! - `dev_deploy` - Redeploy of a WASM file into a context whenever it changes
+//! - `discovery` - Local node discovery (port scan and merobox state) behind `discover_nodes()` and zero-config `connect()`
+//! - `error` - PyClientError, `ErrorCode`, error context and panic conversion
+//! - `abi` - Method discovery from application ABIs behind `Client.discover_methods()`, and ABI-checked calls behind `Client.method()` (`AbiMethod`, `AbiArgumentError`)
+//! - `aliases` - Alias CRUD and transparent alias resolution with a local cache
+//! - `applications` - Application installs from local WASM bundles
+//! - `archive` - Client-side freezing of archived contexts
+//! - `arg_encoding` - JSON, base64 and schema-driven Borsh encodings of call arguments
+//! - `audit` - Hash-chained audit log of mutating calls (`audit_log=`) and `verify_audit_log()`
 //! - `auth` - PyAuthMode wrapper
+//! - `auth_metadata` - On-disk cache of the auth service's JWKS and OIDC discovery documents
+//! - `auth_service` - Token management endpoints of the node's auth service
+//! - `blob` - Blob transfer helpers (bulk download) and blob id hashing (`hash_blob()`, `verify_blob()`)
+//! - `bulk` - `BulkResult` per-item outcomes of bulk calls
 //! - `token` - PyJwtToken wrapper
+//! - `token_lifecycle` - Proactive refresh of tokens nearing expiry
+//! - `token_refresh` - Background token refresher (`start_token_refresh()`) and its callbacks
+//! - `reauth` - Single-flight session recovery and replay after a 401
+//! - `token_bundle` - Portable (optionally encrypted) token export/import bundles
 //! - `cache` - Token cache path utilities
-//! - `storage` - MeroboxFileStorage implementation
+//! - `capabilities` - Typed `Capability` of context members behind `Client.grant()` / `revoke()` / `list_capabilities()`
+//! - `callbacks` - Weakly held hook callbacks and their `CallbackHandle`s
+//! - `cli_credentials` - Import/export of tokens cached by meroctl and merobox
+//! - `cache_admin` - `TokenCache` listing, cleanup and `verify_cache()` of cached tokens
+//! - `cdc` - Change-data-capture export to Kafka/NATS
+//! - `config` - Named node endpoints and client defaults from `~/.merobox/config.toml`, behind `connect()`
+//! - `concurrency` - Adaptive (AIMD) concurrency limit on requests to a node
+//! - `context_guard` - Per-context FIFO serialization of mutating calls
+//! - `crypto` - Envelope encryption for client-side secrets
+//! - `dedup` - Folding of identical `execute` calls fired within a short window
+//! - `entropy` - Injectable randomness source for deterministic tests
+//! - `event_filter` - Subscription filter expressions evaluated in Rust (`subscribe(filter=...)`)
+//! - `event_offsets` - Persistent delivery offsets for replaying subscriptions (`subscribe(replay=True)`)
+//! - `events` - Batched event delivery to Python callbacks
+//! - `event_schema` - Application event schemas decoded into typed `ApplicationEvent` objects
+//! - `features` - Optional Cargo features of the build, `features()` and `FeatureNotBuiltError`
+//! - `keychain` - JWT token storage in the OS keychain (`--features keyring`)
+//! - `lifecycle` - Teardown of a client's runtime, subscriptions and pooled connections behind `Client.close()` and `async with`
+//! - `limits` - Response size caps (`ResponseTooLargeError`)
+//! - `login` - Observable login state (`LoginState`) and change callbacks
+//! - `maintenance` - Wait-and-resume handling of announced node maintenance
+//! - `metadata_cache` - Read-through TTL cache of application/context metadata
+//! - `metadata_store` - Versioned state layout, migrations and `storage_doctor()`
+//! - `method_defaults` - Per-method default arguments merged into `execute` calls
+//! - `metrics` - Request, error, retry and latency metrics behind `Client.metrics()`
+//! - `middleware` - Request and response hooks around the binding's own HTTP requests
+//! - `node_address` - `NodeAddress` parsing and validation of node URLs, `host:port` and `calimero://` names
+//! - `node_status` - `NodeHealth`, `NodeInfo` and `Peers` of `Client.health()` and friends, and `wait_until_ready()`
+//! - `outbox` - Offline queue of `execute` calls, drained per context in order
+//! - `log_bridge` - Forwarding of Rust-side log records and `tracing` events to Python `logging`
+//! - `proptests` - Property-based serialization tests (`--features fuzz`)
+//! - `permissions` - Owner-only access to cache files (Unix modes, Windows DACLs)
+//! - `pool` - Process-wide HTTP connection pools per node, with per-host limits
+//! - `probe` - RTT, TLS handshake and clock offset probe behind `Client.probe()`
+//! - `production` - `production=True` guard on dangerous operations (`DangerousOperationError`)
+//! - `query` - `Query` builder behind `Client.contexts()` / `Client.applications()` / `Client.blobs()`
+//! - `pagination` - Lazy `Paginator` over query results, a page at a time, sync and async
+//! - `quota` - Rate-limit headers behind `Client.quota_status()` and results' `quota`
+//! - `rate_limit` - Client-side token bucket per node (`rate_limit=`) and `Retry-After` pauses
+//! - `read_cache` - LRU cache with a TTL of `Client.view()` results
+//! - `replicas` - `ReplicaSet` routing reads to the lowest-latency healthy replica and writes to the primary
+//! - `storage` - MeroboxFileStorage implementation, `MemoryStorage` and Python `ClientStorage` adapter
+//! - `subscriptions` - WebSocket event subscriptions (`Subscription`), demultiplexed per context over one reconnecting socket
 //! - `connection` - PyConnectionInfo and create_connection()
 //! - `client` - PyClient and create_client()
+//! - `client_pool` - `ClientPool` of named nodes sharing token storage, with fan-out calls
+//! - `context` - Typed `Context` records of the context management calls
+//! - `transcript` - Recording of request/response transcripts and offline replay (`record=` / `replay=`)
+//! - `transport` - Transport negotiation from the API URL scheme
+//! - `http` - Direct HTTP access for requests `calimero-client` doesn't wrap
+//! - `in_flight` - Executions started and not completed, behind `Client.pending_executions()`
+//! - `invitation` - Context invitation payloads of `invite()` / `join_context()`
+//! - `jsonrpc_batch` - JSON-RPC batch requests behind `execute_batch`
+//! - `request_cache` - Prepared `execute` requests reused across repeated calls
+//! - `retention` - Context snapshots written by `delete_context(export_to=...)` before deleting
+//! - `retry` - `RetryConfig` exponential backoff with jitter, guarded for mutations
+//! - `retry_budget` - Cap on the share of requests that may be retries
+//! - `security` - Security posture checks behind `Client.security_check()`
+//! - `session` - `ContextSession`, a client bound to a context and executor
+//! - `signing` - Offline signing of context executions with local Ed25519 keys (`Signer`)
+//! - `membership` - Portable, identity-signed context membership proofs and their verification
+//! - `sso` - Browser login via upstream identity providers (OIDC, PKCE)
+//! - `sqlite_view` - SQLite materialized views of context events
+//! - `state_snapshot` - `snapshot()`/`restore()` archives of the client's local state
+//! - `task_group` - `Client.task_group()` calls run together with all-or-cancel semantics
+//! - `temporary_context` - `Client.temporary_context()` scoped create/delete of a context
+//! - `timeouts` - Connect/request timeouts, `Client.timeout()` blocks and cancellation of in-flight calls
+//! - `wallet_login` - NEAR and Internet Identity challenge-signing logins
+//! - `typed_result` - Execute results converted to dataclasses, pydantic models and typed containers (`ResultTypeError`)
+//! - `usage` - Per-context usage reports behind `Client.usage()`
+//! - `wire` - msgpack/JSON wire format negotiation for execute
+//! - `webhooks` - `WebhookServer` receiving signed node webhook deliveries into event callbacks
+//! - `warnings` - Structured warning categories bridged to `warnings.warn`
 //! - `utils` - JSON to Python conversion helpers
 
+pub mod abi;
+pub mod aliases;
+pub mod applications;
+pub mod archive;
+pub mod arg_encoding;
+pub mod audit;
 pub mod auth;
+pub mod auth_metadata;
+pub mod auth_service;
+pub mod blob;
+pub mod bulk;
 pub mod cache;
+pub mod cache_admin;
+pub mod callbacks;
+pub mod capabilities;
+pub mod cdc;
+pub mod cli_credentials;
 pub mod client;
+pub mod client_pool;
+pub mod concurrency;
+pub mod config;
 pub mod connection;
+pub mod context;
+pub mod context_guard;
+pub mod crypto;
+pub mod dedup;
+pub mod deprecation;
+pub mod dev_deploy;
+pub mod discovery;
+pub mod entropy;
 pub mod error;
+pub mod event_filter;
+pub mod event_offsets;
+pub mod event_schema;
+pub mod events;
+pub mod features;
+pub mod http;
+pub mod in_flight;
+pub mod invitation;
+pub mod jsonrpc_batch;
+pub mod keychain;
+pub mod lifecycle;
+pub mod limits;
+pub mod log_bridge;
+pub mod login;
+pub mod maintenance;
+pub mod membership;
+pub mod metadata_cache;
+pub mod metadata_store;
+pub mod method_defaults;
+pub mod metrics;
+pub mod middleware;
+pub mod node_address;
+pub mod node_status;
+pub mod outbox;
+pub mod pagination;
+pub mod permissions;
+pub mod pool;
+pub mod probe;
+pub mod production;
+#[cfg(all(test, feature = "fuzz"))]
+mod proptests;
+pub mod query;
+pub mod quota;
+pub mod rate_limit;
+pub mod read_cache;
+pub mod reauth;
+pub mod replicas;
+pub mod request_cache;
+pub mod retention;
+pub mod retry;
+pub mod retry_budget;
+pub mod security;
+pub mod session;
+pub mod signing;
+pub mod sqlite_view;
+pub mod sso;
+pub mod state_snapshot;
 pub mod storage;
+pub mod subscriptions;
+pub mod task_group;
+pub mod temporary_context;
+pub mod timeouts;
 pub mod token;
+pub mod token_bundle;
+pub mod token_lifecycle;
+pub mod token_refresh;
+pub mod transcript;
+pub mod transport;
+pub mod typed_result;
+pub mod usage;
 pub mod utils;
+pub mod wallet_login;
+pub mod warnings;
+pub mod webhooks;
+pub mod wire;
 
 use pyo3::prelude::*;
 
 /// Python module for Calimero client
 #[pymodule]
-fn calimero_client_py(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+fn calimero_client_py(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    log_bridge::install();
+
     // Register classes
     m.add_class::<connection::PyConnectionInfo>()?;
     m.add_class::<client::PyClient>()?;
+    m.add_class::<context::PyContext>()?;
     m.add_class::<token::PyJwtToken>()?;
     m.add_class::<error::PyClientError>()?;
+    m.add_class::<error::ErrorCode>()?;
     m.add_class::<auth::PyAuthMode>()?;
+    m.add_class::<login::LoginState>()?;
+    m.add_class::<capabilities::Capability>()?;
+    m.add_class::<callbacks::PyCallbackHandle>()?;
+    m.add_class::<sqlite_view::PySqliteView>()?;
+    m.add_class::<cdc::PyCdcExporter>()?;
+    m.add_class::<bulk::PyBulkResult>()?;
+    m.add_class::<temporary_context::PyTemporaryContext>()?;
+    m.add_class::<task_group::PyTaskGroup>()?;
+    m.add_class::<dev_deploy::PyDevDeployer>()?;
+    m.add_class::<session::PyContextSession>()?;
+    m.add_class::<node_address::PyNodeAddress>()?;
+    m.add_class::<node_status::PyNodeHealth>()?;
+    m.add_class::<node_status::PyNodeInfo>()?;
+    m.add_class::<node_status::PyPeers>()?;
+    m.add_class::<storage::PyClientStorage>()?;
+    m.add_class::<storage::PyMemoryStorage>()?;
+    m.add_class::<event_schema::EventSchemaRegistry>()?;
+    m.add_class::<event_schema::PyApplicationEvent>()?;
+    m.add_class::<cache_admin::PyTokenCache>()?;
+    m.add_class::<subscriptions::PySubscription>()?;
+    m.add_class::<replicas::PyReplicaSet>()?;
+    m.add_class::<client_pool::PyClientPool>()?;
+    m.add_class::<config::PyConfig>()?;
+    m.add_class::<retry::RetryConfig>()?;
+    m.add_class::<arg_encoding::ArgEncoding>()?;
+    m.add_class::<timeouts::PyTimeoutScope>()?;
+    m.add_class::<token_refresh::PyTokenRefresher>()?;
+    m.add_class::<webhooks::PyWebhookServer>()?;
+    m.add_class::<signing::PySigner>()?;
+    m.add_class::<query::PyQuery>()?;
+    m.add_class::<pagination::PyPaginator>()?;
+    m.add_class::<abi::PyAbiMethod>()?;
+
+    // Register exception and warning categories
+    abi::register(py, m)?;
+    error::register(py, m)?;
+    features::register(py, m)?;
+    limits::register(py, m)?;
+    typed_result::register(py, m)?;
+    production::register(py, m)?;
+    warnings::register(py, m)?;
 
     // Register functions
     m.add_function(wrap_pyfunction!(connection::create_connection, m)?)?;
     m.add_function(wrap_pyfunction!(client::create_client, m)?)?;
+    m.add_function(wrap_pyfunction!(config::connect, m)?)?;
+    m.add_function(wrap_pyfunction!(config::load_config, m)?)?;
+    m.add_function(wrap_pyfunction!(discovery::discover_nodes, m)?)?;
     m.add_function(wrap_pyfunction!(cache::get_token_cache_path, m)?)?;
     m.add_function(wrap_pyfunction!(cache::get_token_cache_dir, m)?)?;
+    m.add_function(wrap_pyfunction!(cache::migrate_token_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(metadata_store::storage_doctor, m)?)?;
+    m.add_function(wrap_pyfunction!(cache_admin::verify_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(blob::hash_blob, m)?)?;
+    m.add_function(wrap_pyfunction!(blob::verify_blob, m)?)?;
+    m.add_function(wrap_pyfunction!(audit::verify_audit_log, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        auth_metadata::clear_auth_metadata_cache,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(token_bundle::export_tokens, m)?)?;
+    m.add_function(wrap_pyfunction!(token_bundle::import_tokens, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        cli_credentials::import_cli_credentials,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        cli_credentials::export_cli_credentials,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(state_snapshot::snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(state_snapshot::restore, m)?)?;
+    m.add_function(wrap_pyfunction!(invitation::encode_invitation, m)?)?;
+    m.add_function(wrap_pyfunction!(invitation::decode_invitation, m)?)?;
+    m.add_function(wrap_pyfunction!(membership::verify_membership_proof, m)?)?;
+    m.add_function(wrap_pyfunction!(entropy::set_entropy_source, m)?)?;
+    m.add_function(wrap_pyfunction!(wire::roundtrip, m)?)?;
+    m.add_function(wrap_pyfunction!(log_bridge::set_log_level, m)?)?;
+    m.add_function(wrap_pyfunction!(features::features, m)?)?;
+    m.add_function(wrap_pyfunction!(typed_result::convert_result, m)?)?;
 
     // Add constants
     m.add("VERSION", env!("CARGO_PKG_VERSION"))?;
@@ -0,0 +1,111 @@
+//! Deterministic teardown of a client, behind `Client.close()` and
+//! `with` / `async with` blocks.
+//!
+//! Every client owns a tokio runtime whose worker threads, WebSocket
+//! subscription and pooled keep-alive connections used to live until the
+//! client was garbage collected, which in a long-running notebook often
+//! meant interpreter exit. Closing a client now:
+//!
+//! - ends its subscriptions (iteration stops, callbacks are no longer
+//!   called) and drops the shared socket;
+//! - gives back its share of the process-wide connection pool, so the
+//!   pool is dropped once no open client uses it;
+//! - shuts the runtime down, waiting at most [`SHUTDOWN_GRACE`] for its
+//!   tasks, which closes their sockets and joins the worker threads.
+//!
+//! Calls on a closed client raise `ClientError`; closing twice does
+//! nothing.
+
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+
+use tokio::runtime::{Handle, Runtime};
+
+/// Longest wait for the runtime's tasks when a client is closed.
+pub const SHUTDOWN_GRACE: Duration = Duration::from_secs(1);
+
+/// Message of the error raised by calls on a closed client.
+pub const CLOSED_MESSAGE: &str = "Client is closed";
+
+/// The runtime a client's calls run on, until the client is closed.
+pub struct ClientRuntime {
+    runtime: RwLock<Option<Arc<Runtime>>>,
+    /// Kept past shutdown: tasks spawned through it are cancelled at once.
+    handle: Handle,
+}
+
+impl ClientRuntime {
+    pub fn new(runtime: Arc<Runtime>) -> Self {
+        Self {
+            handle: runtime.handle().clone(),
+            runtime: RwLock::new(Some(runtime)),
+        }
+    }
+
+    /// The runtime, `None` once closed.
+    pub fn get(&self) -> Option<Arc<Runtime>> {
+        self.runtime
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.runtime
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_none()
+    }
+
+    /// Shut the runtime down, giving its tasks `grace` to finish. A call
+    /// still running on another thread keeps the runtime until it returns.
+    /// Returns whether this call closed it.
+    pub fn shutdown(&self, grace: Duration) -> bool {
+        let runtime = self
+            .runtime
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        match runtime {
+            Some(runtime) => {
+                if let Ok(runtime) = Arc::try_unwrap(runtime) {
+                    runtime.shutdown_timeout(grace);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shutdown happens once, cancels pending tasks and leaves the handle
+    /// usable for spawning.
+    #[test]
+    fn test_shutdown() {
+        let runtime = ClientRuntime::new(Arc::new(Runtime::new().unwrap()));
+        let pending = runtime
+            .handle()
+            .spawn(async { tokio::time::sleep(Duration::from_secs(60)).await });
+        assert!(!runtime.is_closed());
+        assert!(runtime.shutdown(Duration::from_millis(100)));
+        assert!(runtime.is_closed() && runtime.get().is_none());
+        assert!(!runtime.shutdown(Duration::from_millis(100)));
+
+        let check = Runtime::new().unwrap();
+        assert!(check.block_on(pending).unwrap_err().is_cancelled());
+        let late = runtime.handle().spawn(async { 1 });
+        assert!(check.block_on(late).unwrap_err().is_cancelled());
+    }
+}
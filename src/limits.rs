@@ -0,0 +1,257 @@
+//! Guards against oversized responses.
+//!
+//! A query that accidentally returns a context's whole state can be hundreds
+//! of megabytes, and expanding that into Python objects multiplies it several
+//! times over — enough to get a notebook kernel OOM-killed. Clients therefore
+//! cap response size (`max_response_bytes`): bodies the binding reads itself
+//! are cut off while streaming, and typed responses are measured before they
+//! are converted to Python. Either way the caller gets a
+//! `ResponseTooLargeError` instead of a dead process.
+//!
+//! Requests get the mirror-image guard. `execute_function` arguments over
+//! `max_request_bytes` (default 2 MiB, the node's default body limit) are
+//! caught before anything is sent: with `oversized_args="error"` the call
+//! raises `RequestTooLargeError` naming the size and the limit, instead of
+//! the node answering an opaque 413 after the upload; with
+//! `oversized_args="blob"` the arguments are uploaded as a blob and the
+//! method receives a reference to it instead, `{"$blob": {"blobId": ...,
+//! "size": ...}}`, for applications that read their input that way.
+
+use std::io::Write;
+
+use eyre::WrapErr;
+use pyo3::create_exception;
+use pyo3::prelude::*;
+
+use crate::error::CalimeroError;
+
+create_exception!(
+    calimero_client_py,
+    ResponseTooLargeError,
+    CalimeroError,
+    "A node response exceeded the client's max_response_bytes limit."
+);
+
+create_exception!(
+    calimero_client_py,
+    RequestTooLargeError,
+    CalimeroError,
+    "A request exceeded the client's max_request_bytes limit and was not sent."
+);
+
+/// Default cap on a single response (64 MiB).
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Default cap on an `execute_function` request (2 MiB).
+pub const DEFAULT_MAX_REQUEST_BYTES: usize = 2 * 1024 * 1024;
+
+/// Prefix of every size-limit error message, used to pick the exception type.
+const TOO_LARGE_PREFIX: &str = "Response too large";
+
+/// Prefix of request size-limit error messages.
+const REQUEST_TOO_LARGE_PREFIX: &str = "Request too large";
+
+/// Key of the blob reference that replaces oversized arguments.
+pub const BLOB_REFERENCE_KEY: &str = "$blob";
+
+/// What `execute_function` does with arguments over `max_request_bytes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversizedArgs {
+    /// Raise `RequestTooLargeError` without sending anything.
+    Error,
+    /// Upload the arguments as a blob and pass a reference to it.
+    Blob,
+}
+
+impl OversizedArgs {
+    pub fn parse(mode: &str) -> eyre::Result<Self> {
+        match mode {
+            "error" => Ok(OversizedArgs::Error),
+            "blob" => Ok(OversizedArgs::Blob),
+            other => eyre::bail!(
+                "Invalid oversized_args '{}'. Expected 'error' or 'blob'",
+                other
+            ),
+        }
+    }
+}
+
+/// Error for a response of (at least) `size` bytes against `limit`.
+pub fn too_large(size: usize, limit: usize) -> eyre::Report {
+    eyre::eyre!(
+        "{}: at least {} bytes, over the max_response_bytes limit of {}; \
+         narrow the query (fields=, pagination) or raise the limit",
+        TOO_LARGE_PREFIX,
+        size,
+        limit
+    )
+}
+
+/// Error for an `execute_function` request of (at least) `size` bytes
+/// against `limit`.
+pub fn request_too_large(method: &str, size: usize, limit: usize) -> eyre::Report {
+    eyre::eyre!(
+        "{}: {} call is at least {} bytes, over the max_request_bytes limit of {}; \
+         send less, pass oversized_args=\"blob\" for applications that read \
+         blob references, or raise the limit if the node accepts more",
+        REQUEST_TOO_LARGE_PREFIX,
+        method,
+        size,
+        limit
+    )
+}
+
+/// Whether an error message came from a size limit, ours or the node's.
+pub fn is_too_large(message: &str) -> bool {
+    message.contains(TOO_LARGE_PREFIX) || is_request_too_large(message)
+}
+
+fn is_request_too_large(message: &str) -> bool {
+    message.contains(REQUEST_TOO_LARGE_PREFIX) || message.contains("413 Payload Too Large")
+}
+
+/// Arguments pointing at blob `blob_id` in place of `size` bytes of JSON.
+pub fn blob_reference(blob_id: &str, size: usize) -> serde_json::Value {
+    serde_json::json!({ BLOB_REFERENCE_KEY: { "blobId": blob_id, "size": size } })
+}
+
+/// Read a response body, failing as soon as it grows past `limit`.
+///
+/// A `Content-Length` over the limit is rejected before any bytes are read.
+pub async fn read_body(
+    mut response: reqwest::Response,
+    limit: Option<usize>,
+) -> eyre::Result<Vec<u8>> {
+    let Some(limit) = limit else {
+        return Ok(response
+            .bytes()
+            .await
+            .wrap_err("Failed to read response body")?
+            .to_vec());
+    };
+    if let Some(length) = response.content_length() {
+        if length > limit as u64 {
+            return Err(too_large(
+                usize::try_from(length).unwrap_or(usize::MAX),
+                limit,
+            ));
+        }
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .wrap_err("Failed to read response body")?
+    {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large(body.len() + chunk.len(), limit));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Writer that only counts bytes and fails once `limit` is passed.
+struct CountingWriter {
+    written: usize,
+    limit: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.written += buf.len();
+        if self.written > self.limit {
+            return Err(std::io::Error::other("limit exceeded"));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `None` if `value`'s JSON encoding fits in `limit` bytes, else how many
+/// bytes were counted before giving up.
+///
+/// Serialization stops at the first byte over the limit, so measuring a huge
+/// value costs no more than the limit itself.
+pub fn json_overflow(value: &impl serde::Serialize, limit: usize) -> Option<usize> {
+    let mut counter = CountingWriter { written: 0, limit };
+    match serde_json::to_writer(&mut counter, value) {
+        Ok(()) => None,
+        Err(_) => Some(counter.written),
+    }
+}
+
+/// Check that `value`'s JSON encoding fits in `limit` bytes.
+pub fn check_json(value: &serde_json::Value, limit: Option<usize>) -> eyre::Result<()> {
+    match limit.and_then(|limit| Some((json_overflow(value, limit)?, limit))) {
+        Some((size, limit)) => Err(too_large(size, limit)),
+        None => Ok(()),
+    }
+}
+
+/// Python exception for a size-limit error.
+pub fn too_large_error(message: impl std::fmt::Display) -> PyErr {
+    let message = message.to_string();
+    match is_request_too_large(&message) {
+        true => RequestTooLargeError::new_err(message),
+        false => ResponseTooLargeError::new_err(message),
+    }
+}
+
+pub fn register(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add(
+        "ResponseTooLargeError",
+        py.get_type_bound::<ResponseTooLargeError>(),
+    )?;
+    m.add(
+        "RequestTooLargeError",
+        py.get_type_bound::<RequestTooLargeError>(),
+    )?;
+    Ok(())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Values at the limit pass; one byte over is rejected as too large.
+    #[test]
+    fn test_check_json_limit() {
+        let value = serde_json::json!({"data": "x".repeat(100)});
+        let size = serde_json::to_vec(&value).unwrap().len();
+
+        assert!(check_json(&value, None).is_ok());
+        assert!(check_json(&value, Some(size)).is_ok());
+        let err = check_json(&value, Some(size - 1)).unwrap_err();
+        assert!(is_too_large(&err.to_string()));
+    }
+
+    /// Request errors name the method and limit; blob references carry
+    /// the id and size.
+    #[test]
+    fn test_request_limit() {
+        let message = request_too_large("set", 3_000_000, DEFAULT_MAX_REQUEST_BYTES).to_string();
+        assert!(is_request_too_large(&message));
+        assert!(message.contains("set call is at least 3000000 bytes"));
+        assert!(message.contains(&DEFAULT_MAX_REQUEST_BYTES.to_string()));
+        assert!(is_request_too_large(
+            "Request failed with status 413 Payload Too Large"
+        ));
+        assert!(!is_request_too_large(&too_large(10, 5).to_string()));
+
+        assert_eq!(
+            blob_reference("abc", 42),
+            serde_json::json!({"$blob": {"blobId": "abc", "size": 42}})
+        );
+        assert_eq!(OversizedArgs::parse("blob").unwrap(), OversizedArgs::Blob);
+        assert!(OversizedArgs::parse("chunk").is_err());
+    }
+}
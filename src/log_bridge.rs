@@ -0,0 +1,405 @@
+//! Bridge from Rust to Python's `logging` module.
+//!
+//! Records go to the `calimero_client_py` logger so applications can route,
+//! filter, or silence them with the standard logging configuration.
+//!
+//! `tracing` spans and events from the binding and the underlying Calimero
+//! client go to `calimero_client` and its children (`calimero_client.http`
+//! for a `calimero_client::http` target). They are emitted on runtime
+//! threads while the calling thread holds the GIL and waits on them, so the
+//! layer queues them and [`flush`] hands them to Python once the call
+//! returns. [`set_log_level`] bounds what is collected at all; the default
+//! is WARNING, matching Python's root logger.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Mutex, Once};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Name of the Python logger the bindings write to.
+pub const LOGGER_NAME: &str = "calimero_client_py";
+
+/// Parent of the Python loggers `tracing` records are forwarded to.
+pub const TRACING_LOGGER_NAME: &str = "calimero_client";
+
+/// Records kept between flushes; the oldest are dropped past this.
+const QUEUE_CAPACITY: usize = 4096;
+
+/// Python `logging` level numbers.
+const TRACE: u8 = 5;
+const DEBUG: u8 = 10;
+const INFO: u8 = 20;
+const WARNING: u8 = 30;
+const ERROR: u8 = 40;
+/// Above every level: nothing is collected.
+const OFF: u8 = 100;
+
+static THRESHOLD: AtomicU8 = AtomicU8::new(WARNING);
+static JSON: AtomicBool = AtomicBool::new(false);
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+static QUEUE: Mutex<VecDeque<LogRecord>> = Mutex::new(VecDeque::new());
+
+fn log(py: Python<'_>, level: &str, message: &str) -> PyResult<()> {
+    py.import_bound("logging")?
+        .call_method1("getLogger", (LOGGER_NAME,))?
+        .call_method1(level, (message,))?;
+    Ok(())
+}
+
+/// Log `message` at WARNING level. Logging failures are swallowed.
+pub fn warning(py: Python<'_>, message: &str) {
+    let _ = log(py, "warning", message);
+}
+
+fn levelno(level: &Level) -> u8 {
+    match *level {
+        Level::TRACE => TRACE,
+        Level::DEBUG => DEBUG,
+        Level::INFO => INFO,
+        Level::WARN => WARNING,
+        Level::ERROR => ERROR,
+    }
+}
+
+/// Python level number for a level name (`"debug"`, `"WARNING"`, `"off"`).
+fn parse_level(name: &str) -> Option<u8> {
+    Some(match name.trim().to_ascii_lowercase().as_str() {
+        "trace" => TRACE,
+        "debug" => DEBUG,
+        "info" => INFO,
+        "warn" | "warning" => WARNING,
+        "error" | "critical" => ERROR,
+        "off" | "none" => OFF,
+        _ => return None,
+    })
+}
+
+/// Python logger for a `tracing` target: `calimero_client::http` and
+/// `calimero_client_py::client` become `calimero_client.http` and
+/// `calimero_client.client`; other crates nest under `calimero_client`.
+fn logger_name(target: &str) -> String {
+    let local = ["calimero_client_py", TRACING_LOGGER_NAME]
+        .iter()
+        .find_map(|prefix| {
+            target
+                .strip_prefix(prefix)
+                .filter(|rest| rest.is_empty() || rest.starts_with("::"))
+        });
+    let rest = local.unwrap_or(target).trim_start_matches("::");
+    match rest.is_empty() {
+        true => TRACING_LOGGER_NAME.to_owned(),
+        false => format!("{TRACING_LOGGER_NAME}.{}", rest.replace("::", ".")),
+    }
+}
+
+/// Field values of a span or event.
+#[derive(Debug, Default, Clone)]
+struct Fields(Map<String, Value>);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_owned(), format!("{value:?}").into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+}
+
+/// One queued `tracing` event, with the spans it was emitted in.
+#[derive(Debug, Clone)]
+struct LogRecord {
+    level: u8,
+    target: String,
+    message: String,
+    fields: Map<String, Value>,
+    spans: Vec<(String, Map<String, Value>)>,
+    timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+fn format_fields(fields: &Map<String, Value>) -> String {
+    fields
+        .iter()
+        .map(|(name, value)| match value {
+            Value::String(text) => format!("{name}={text}"),
+            other => format!("{name}={other}"),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl LogRecord {
+    /// `call{operation=get_context}: request sent status=200`.
+    fn text(&self) -> String {
+        let mut line = String::new();
+        for (name, fields) in &self.spans {
+            line.push_str(name);
+            if !fields.is_empty() {
+                line.push_str(&format!("{{{}}}", format_fields(fields)));
+            }
+            line.push_str(": ");
+        }
+        line.push_str(&self.message);
+        if !self.fields.is_empty() {
+            line.push(' ');
+            line.push_str(&format_fields(&self.fields));
+        }
+        line
+    }
+
+    fn json(&self) -> String {
+        let spans: Vec<Value> = self
+            .spans
+            .iter()
+            .map(|(name, fields)| {
+                let mut span = fields.clone();
+                span.insert("name".to_owned(), name.clone().into());
+                Value::Object(span)
+            })
+            .collect();
+        serde_json::json!({
+            "timestamp": self.timestamp.to_rfc3339(),
+            "level": self.level,
+            "target": self.target,
+            "message": self.message,
+            "fields": self.fields,
+            "spans": spans,
+        })
+        .to_string()
+    }
+}
+
+fn enqueue(record: LogRecord) {
+    let Ok(mut queue) = QUEUE.lock() else {
+        return;
+    };
+    if queue.len() >= QUEUE_CAPACITY {
+        queue.pop_front();
+        DROPPED.fetch_add(1, Ordering::Relaxed);
+    }
+    queue.push_back(record);
+}
+
+/// `tracing` layer that queues events for [`flush`].
+struct PythonLayer;
+
+impl<S> Layer<S> for PythonLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    // The threshold changes at runtime, so callsites are never cached as
+    // always or never interesting.
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        levelno(metadata.level()) >= THRESHOLD.load(Ordering::Relaxed)
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(fields) = span.extensions_mut().get_mut::<Fields>() {
+                values.record(fields);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let message = match fields.0.remove("message") {
+            Some(Value::String(message)) => message,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        let spans = ctx
+            .event_scope(event)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .map(|span| {
+                        let fields = span.extensions().get::<Fields>().cloned();
+                        (span.name().to_owned(), fields.unwrap_or_default().0)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let metadata = event.metadata();
+        enqueue(LogRecord {
+            level: levelno(metadata.level()),
+            target: metadata.target().to_owned(),
+            message,
+            fields: fields.0,
+            spans,
+            timestamp: chrono::Utc::now(),
+        });
+    }
+}
+
+/// Install the forwarding layer as the global `tracing` subscriber, unless
+/// the host process already set one.
+pub fn install() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let subscriber = tracing_subscriber::registry().with(PythonLayer);
+        let _ = tracing::subscriber::set_global_default(subscriber);
+    });
+}
+
+/// Hand queued `tracing` records to Python `logging`. Logging failures are
+/// swallowed.
+pub fn flush(py: Python<'_>) {
+    let records = match QUEUE.lock() {
+        Ok(mut queue) if !queue.is_empty() => std::mem::take(&mut *queue),
+        _ => VecDeque::new(),
+    };
+    let dropped = DROPPED.swap(0, Ordering::Relaxed);
+    if records.is_empty() && dropped == 0 {
+        return;
+    }
+    let Ok(logging) = py.import_bound("logging") else {
+        return;
+    };
+    let json = JSON.load(Ordering::Relaxed);
+    for record in records {
+        let message = match json {
+            true => record.json(),
+            false => record.text(),
+        };
+        let _ = logging
+            .call_method1("getLogger", (logger_name(&record.target),))
+            .and_then(|logger| logger.call_method1("log", (record.level, message)));
+    }
+    if dropped > 0 {
+        let message = format!("{dropped} log records were dropped before delivery");
+        let _ = logging
+            .call_method1("getLogger", (TRACING_LOGGER_NAME,))
+            .and_then(|logger| logger.call_method1("warning", (message,)));
+    }
+}
+
+/// Set the level of `tracing` records forwarded to the `calimero_client`
+/// logger, and set that logger's level to match.
+///
+/// Args:
+///     level: A `logging` level number or name (`"trace"`, `"debug"`,
+///         `"info"`, `"warning"`, `"error"`), or `"off"` to collect
+///         nothing.
+///     json: If given, switch messages to one JSON object per record
+///         (timestamp, level, target, message, fields and spans) or back
+///         to text.
+///
+/// Returns:
+///     The previous level number.
+#[pyfunction]
+#[pyo3(signature = (level, json=None))]
+pub fn set_log_level(py: Python<'_>, level: &Bound<'_, PyAny>, json: Option<bool>) -> PyResult<u8> {
+    let threshold = match level.extract::<u32>() {
+        Ok(number) => number.min(OFF as u32) as u8,
+        Err(_) => {
+            let name: String = level.extract()?;
+            parse_level(&name)
+                .ok_or_else(|| PyValueError::new_err(format!("unknown log level {name:?}")))?
+        }
+    };
+    // Records collected at the old level go out first.
+    flush(py);
+    install();
+    let previous = THRESHOLD.swap(threshold, Ordering::Relaxed);
+    if let Some(json) = json {
+        JSON.store(json, Ordering::Relaxed);
+    }
+    py.import_bound("logging")?
+        .call_method1("getLogger", (TRACING_LOGGER_NAME,))?
+        .call_method1("setLevel", (threshold,))?;
+    Ok(previous)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Targets map onto the `calimero_client` logger hierarchy.
+    #[test]
+    fn test_logger_name() {
+        assert_eq!(logger_name("calimero_client"), "calimero_client");
+        assert_eq!(
+            logger_name("calimero_client::connection"),
+            "calimero_client.connection"
+        );
+        assert_eq!(
+            logger_name("calimero_client_py::client"),
+            "calimero_client.client"
+        );
+        assert_eq!(
+            logger_name("hyper::proto::h1"),
+            "calimero_client.hyper.proto.h1"
+        );
+        assert_eq!(parse_level("Warning"), Some(WARNING));
+        assert_eq!(parse_level("verbose"), None);
+    }
+
+    /// Text and JSON renderings carry spans and fields.
+    #[test]
+    fn test_record_formatting() {
+        let mut span = Map::new();
+        span.insert("operation".to_owned(), "get_context".into());
+        let mut fields = Map::new();
+        fields.insert("status".to_owned(), 200.into());
+        let record = LogRecord {
+            level: DEBUG,
+            target: "calimero_client::http".to_owned(),
+            message: "response".to_owned(),
+            fields,
+            spans: vec![("call".to_owned(), span)],
+            timestamp: chrono::Utc::now(),
+        };
+        assert_eq!(
+            record.text(),
+            "call{operation=get_context}: response status=200"
+        );
+        let json: Value = serde_json::from_str(&record.json()).unwrap();
+        assert_eq!(json["fields"]["status"], 200);
+        assert_eq!(json["spans"][0]["name"], "call");
+        assert_eq!(json["spans"][0]["operation"], "get_context");
+    }
+}
@@ -0,0 +1,259 @@
+//! Observable login state for apps that drive an auth UI.
+//!
+//! `calimero-client` logs in and refreshes tokens on its own, so the binding
+//! infers the state from the token cache around each call: no tokens while a
+//! call is pending means a login is in progress, expired tokens mean a
+//! refresh, and an authentication failure leaves the session expired (or
+//! logged out when no tokens remain). Transitions are reported to callbacks
+//! registered with `Client.on_login_state_change`.
+//!
+//! `Client.login()` drives a login explicitly. Its credentials come from the
+//! call, then from the environment ([`LoginCredentials::with_env`]), so CI
+//! jobs can log in headless without code changes.
+
+use std::sync::{Arc, Mutex};
+
+use calimero_client::JwtToken;
+use pyo3::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::callbacks::{Callback, CallbackList, PyCallbackHandle};
+
+/// Where the client's session stands.
+#[pyclass(name = "LoginState", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginState {
+    #[pyo3(name = "LOGGED_OUT")]
+    LoggedOut,
+    #[pyo3(name = "LOGGING_IN")]
+    LoggingIn,
+    #[pyo3(name = "LOGGED_IN")]
+    LoggedIn,
+    #[pyo3(name = "REFRESHING")]
+    Refreshing,
+    #[pyo3(name = "EXPIRED")]
+    Expired,
+}
+
+#[pymethods]
+impl LoginState {
+    #[getter]
+    pub fn value(&self) -> &'static str {
+        match self {
+            Self::LoggedOut => "logged_out",
+            Self::LoggingIn => "logging_in",
+            Self::LoggedIn => "logged_in",
+            Self::Refreshing => "refreshing",
+            Self::Expired => "expired",
+        }
+    }
+
+    fn __str__(&self) -> &'static str {
+        self.value()
+    }
+}
+
+/// Username for a headless password login.
+pub const USERNAME_ENV: &str = "CALIMERO_USERNAME";
+
+/// Password for a headless password login.
+pub const PASSWORD_ENV: &str = "CALIMERO_PASSWORD";
+
+/// NEAR account for a headless NEAR login.
+pub const NEAR_ACCOUNT_ENV: &str = "CALIMERO_NEAR_ACCOUNT";
+
+/// `ed25519:<base58>` key of the NEAR account for a headless NEAR login.
+pub const NEAR_PRIVATE_KEY_ENV: &str = "CALIMERO_NEAR_PRIVATE_KEY";
+
+/// Credentials for `Client.login()`; any of them may be missing.
+#[derive(Default)]
+pub struct LoginCredentials {
+    pub username: Option<String>,
+    pub password: Option<Zeroizing<String>>,
+    pub account_id: Option<String>,
+    pub private_key: Option<Zeroizing<String>>,
+}
+
+impl LoginCredentials {
+    /// Fill what the call left out from `env`. Variables of the other login
+    /// method are ignored once the call names one, so an explicit username
+    /// is never paired with a NEAR key from the environment.
+    pub fn with_env(mut self, env: impl Fn(&str) -> Option<String>) -> Self {
+        let explicit_password = self.username.is_some() || self.password.is_some();
+        let explicit_near = self.account_id.is_some() || self.private_key.is_some();
+        if !explicit_password {
+            self.account_id = self.account_id.or_else(|| env(NEAR_ACCOUNT_ENV));
+            self.private_key = self
+                .private_key
+                .or_else(|| env(NEAR_PRIVATE_KEY_ENV).map(Zeroizing::new));
+        }
+        if !explicit_near {
+            self.username = self.username.or_else(|| env(USERNAME_ENV));
+            self.password = self
+                .password
+                .or_else(|| env(PASSWORD_ENV).map(Zeroizing::new));
+        }
+        self
+    }
+
+    /// Whether these are for a NEAR login: an account or key is given.
+    pub fn is_near(&self) -> bool {
+        self.account_id.is_some() || self.private_key.is_some()
+    }
+}
+
+/// Settled state for the cached `tokens` at `now`.
+///
+/// Nodes without authentication count as logged in.
+pub fn settled_state(tokens: Option<&JwtToken>, auth_required: bool, now: i64) -> LoginState {
+    match tokens {
+        _ if !auth_required => LoginState::LoggedIn,
+        None => LoginState::LoggedOut,
+        Some(tokens) if tokens.expires_at.is_some_and(|at| at <= now) => LoginState::Expired,
+        Some(_) => LoginState::LoggedIn,
+    }
+}
+
+/// State while a call is in flight, if it differs from the settled one.
+pub fn pending_state(settled: LoginState) -> Option<LoginState> {
+    match settled {
+        LoginState::LoggedOut => Some(LoginState::LoggingIn),
+        LoginState::Expired => Some(LoginState::Refreshing),
+        _ => None,
+    }
+}
+
+/// Current state plus the callbacks watching it.
+pub struct LoginTracker {
+    state: Mutex<LoginState>,
+    callbacks: Arc<CallbackList>,
+}
+
+impl LoginTracker {
+    pub fn new(initial: LoginState) -> Self {
+        Self {
+            state: Mutex::new(initial),
+            callbacks: Arc::new(CallbackList::default()),
+        }
+    }
+
+    pub fn get(&self) -> LoginState {
+        self.state
+            .lock()
+            .map(|state| *state)
+            .unwrap_or(LoginState::LoggedOut)
+    }
+
+    /// Whether anyone listens; tracking around calls is skipped otherwise.
+    pub fn is_observed(&self) -> bool {
+        !self.callbacks.is_empty()
+    }
+
+    pub fn subscribe(&self, callback: Callback) -> PyCallbackHandle {
+        self.callbacks.subscribe("on_login_state_change", callback)
+    }
+
+    pub fn clear(&self) {
+        self.callbacks.clear();
+    }
+
+    /// Move to `next`, calling `callback(old, new)` on each listener when the
+    /// state actually changes. Callback errors are printed, not raised, so a
+    /// broken UI hook cannot fail the request that triggered it.
+    pub fn transition(&self, py: Python<'_>, next: LoginState) {
+        let previous = match self.state.lock() {
+            Ok(mut state) if *state != next => std::mem::replace(&mut *state, next),
+            _ => return,
+        };
+        for callback in self.callbacks.live(py) {
+            if let Err(e) = callback.call1(py, (previous, next)) {
+                e.print(py);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(expires_at: Option<i64>) -> JwtToken {
+        JwtToken {
+            access_token: "a".to_string(),
+            refresh_token: Some("r".to_string()),
+            expires_at,
+        }
+    }
+
+    /// Cached tokens map to settled states; auth-free nodes are logged in.
+    #[test]
+    fn test_settled_state() {
+        assert_eq!(settled_state(None, true, 100), LoginState::LoggedOut);
+        assert_eq!(settled_state(None, false, 100), LoginState::LoggedIn);
+        assert_eq!(
+            settled_state(Some(&tokens(Some(50))), true, 100),
+            LoginState::Expired
+        );
+        assert_eq!(
+            settled_state(Some(&tokens(Some(500))), true, 100),
+            LoginState::LoggedIn
+        );
+        assert_eq!(
+            settled_state(Some(&tokens(None)), true, 100),
+            LoginState::LoggedIn
+        );
+    }
+
+    /// Calls made while logged out or expired log in or refresh first.
+    #[test]
+    fn test_pending_state() {
+        assert_eq!(
+            pending_state(LoginState::LoggedOut),
+            Some(LoginState::LoggingIn)
+        );
+        assert_eq!(
+            pending_state(LoginState::Expired),
+            Some(LoginState::Refreshing)
+        );
+        assert_eq!(pending_state(LoginState::LoggedIn), None);
+    }
+
+    /// The environment fills in missing credentials of the method in use.
+    #[test]
+    fn test_credentials_from_env() {
+        let env = |name: &str| match name {
+            USERNAME_ENV => Some("ci".to_string()),
+            PASSWORD_ENV => Some("secret".to_string()),
+            NEAR_ACCOUNT_ENV => Some("ci.near".to_string()),
+            _ => None,
+        };
+
+        let explicit = LoginCredentials {
+            username: Some("alice".to_string()),
+            ..Default::default()
+        }
+        .with_env(env);
+        assert_eq!(explicit.username.as_deref(), Some("alice"));
+        assert_eq!(
+            explicit.password.as_deref().map(String::as_str),
+            Some("secret")
+        );
+        assert!(!explicit.is_near());
+
+        let from_env = LoginCredentials::default().with_env(env);
+        assert!(from_env.is_near());
+        assert_eq!(from_env.private_key, None);
+
+        let near = LoginCredentials {
+            private_key: Some(Zeroizing::new("ed25519:key".to_string())),
+            ..Default::default()
+        }
+        .with_env(env);
+        assert_eq!(near.account_id.as_deref(), Some("ci.near"));
+        assert_eq!(near.username, None);
+    }
+}
@@ -0,0 +1,234 @@
+//! Handling of planned node maintenance.
+//!
+//! A node being upgraded or drained answers `503 Service Unavailable` with
+//! an `X-Calimero-Maintenance` header (and usually `Retry-After`). Treating
+//! that like any other failure makes every caller retry into a node that
+//! said it is going away, burning the retry budget for nothing. Instead the
+//! client enters a maintenance window when it sees such a reply: the call
+//! that hit it raises, later calls wait for the window to end (up to the
+//! client's `maintenance_wait`) and then resume, and callbacks registered
+//! with `Client.on_maintenance` are told when the window starts and ends.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pyo3::prelude::*;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+use crate::callbacks::{Callback, CallbackList, PyCallbackHandle};
+
+/// Header a node sets on replies sent while it is in maintenance.
+pub const MAINTENANCE_HEADER: &str = "x-calimero-maintenance";
+
+/// Default longest time a call waits for a maintenance window to end.
+pub const DEFAULT_MAINTENANCE_WAIT_SECS: u64 = 300;
+
+/// Window assumed when the node does not say how long it will take.
+const DEFAULT_WINDOW: Duration = Duration::from_secs(5);
+
+/// Marker in error messages for maintenance replies.
+const MAINTENANCE_MARKER: &str = "node is in maintenance";
+
+/// Error for a maintenance reply, when `status` and `headers` are one.
+///
+/// The message carries the status and the announced window, so it is still
+/// recognized (and classified as `UNAVAILABLE`) after crossing the FFI.
+pub fn check_response(status: StatusCode, headers: &HeaderMap) -> eyre::Result<()> {
+    let announced = headers.contains_key(MAINTENANCE_HEADER);
+    if status != StatusCode::SERVICE_UNAVAILABLE || !announced {
+        return Ok(());
+    }
+    match headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+    {
+        Some(secs) => eyre::bail!(
+            "{} (status {}, retry after {}s)",
+            MAINTENANCE_MARKER,
+            status,
+            secs
+        ),
+        None => eyre::bail!("{} (status {})", MAINTENANCE_MARKER, status),
+    }
+}
+
+/// Whether a rendered error message reports node maintenance: our own
+/// marker, or a 503 whose body mentions maintenance or draining.
+pub fn is_maintenance(message: &str) -> bool {
+    let lower = message.to_ascii_lowercase();
+    lower.contains(MAINTENANCE_MARKER)
+        || (crate::error::parse_status(message) == Some(503)
+            && (lower.contains("maintenance") || lower.contains("draining")))
+}
+
+/// Window announced in a maintenance error (`retry after 30s`), if any.
+pub fn announced_window(message: &str) -> Option<Duration> {
+    let lower = message.to_ascii_lowercase();
+    let marker = "retry after ";
+    let at = lower.find(marker)? + marker.len();
+    let digits: String = lower[at..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok().map(Duration::from_secs)
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// End of the current window; `None` outside maintenance.
+    until: Option<Instant>,
+    windows: u64,
+}
+
+/// Maintenance window of one client plus the callbacks watching it.
+pub struct MaintenanceGate {
+    max_wait: Option<Duration>,
+    state: Mutex<State>,
+    callbacks: Arc<CallbackList>,
+}
+
+impl MaintenanceGate {
+    /// `max_wait` bounds how long a call waits; `None` never waits.
+    pub fn new(max_wait: Option<Duration>) -> Self {
+        Self {
+            max_wait,
+            state: Mutex::new(State::default()),
+            callbacks: Arc::new(CallbackList::default()),
+        }
+    }
+
+    pub fn in_maintenance(&self) -> bool {
+        self.state
+            .lock()
+            .map(|state| state.until.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Start (or extend) a window lasting `window`. Returns whether the
+    /// client was not in maintenance before.
+    pub fn enter(&self, window: Option<Duration>) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        let entered = state.until.is_none();
+        if entered {
+            state.windows += 1;
+        }
+        state.until = Some(Instant::now() + window.unwrap_or(DEFAULT_WINDOW));
+        entered
+    }
+
+    /// How long a call starting now should wait, bounded by `max_wait`.
+    pub fn delay(&self) -> Option<Duration> {
+        let max_wait = self.max_wait?;
+        let until = self.state.lock().ok()?.until?;
+        Some(
+            until
+                .saturating_duration_since(Instant::now())
+                .min(max_wait),
+        )
+        .filter(|delay| !delay.is_zero())
+    }
+
+    /// Leave the window once it is over. Returns whether it just ended.
+    pub fn resume(&self) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        match state.until {
+            Some(until) if until <= Instant::now() => {
+                state.until = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn subscribe(&self, callback: Callback) -> PyCallbackHandle {
+        self.callbacks.subscribe("on_maintenance", callback)
+    }
+
+    pub fn clear(&self) {
+        self.callbacks.clear();
+    }
+
+    /// Call `callback(in_maintenance, retry_after)` on each listener, with
+    /// the window in seconds when entering. Callback errors are printed,
+    /// not raised.
+    pub fn notify(&self, py: Python<'_>, in_maintenance: bool, window: Option<Duration>) {
+        let retry_after = window.map(|window| window.as_secs_f64());
+        for callback in self.callbacks.live(py) {
+            if let Err(e) = callback.call1(py, (in_maintenance, retry_after)) {
+                e.print(py);
+            }
+        }
+    }
+
+    pub fn stats(&self) -> serde_json::Value {
+        let Ok(state) = self.state.lock() else {
+            return serde_json::Value::Null;
+        };
+        serde_json::json!({
+            "active": state.until.is_some(),
+            "remainingSecs": state
+                .until
+                .map(|until| until.saturating_duration_since(Instant::now()).as_secs_f64()),
+            "windows": state.windows,
+        })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    /// Only a 503 announcing maintenance is reported, with its window.
+    #[test]
+    fn test_check_response() {
+        let mut headers = HeaderMap::new();
+        assert!(check_response(StatusCode::SERVICE_UNAVAILABLE, &headers).is_ok());
+        headers.insert(MAINTENANCE_HEADER, HeaderValue::from_static("upgrade"));
+        assert!(check_response(StatusCode::BAD_GATEWAY, &headers).is_ok());
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        let message = check_response(StatusCode::SERVICE_UNAVAILABLE, &headers)
+            .unwrap_err()
+            .to_string();
+        assert!(is_maintenance(&message));
+        assert_eq!(announced_window(&message), Some(Duration::from_secs(30)));
+        assert!(is_maintenance("status 503: node is draining"));
+        assert!(!is_maintenance("status 503: overloaded"));
+    }
+
+    /// Calls wait out the window, bounded by `max_wait`, then resume.
+    #[test]
+    fn test_window_delays_then_resumes() {
+        let gate = MaintenanceGate::new(Some(Duration::from_secs(1)));
+        assert!(gate.delay().is_none());
+        assert!(gate.enter(Some(Duration::from_secs(60))));
+        assert!(!gate.enter(Some(Duration::from_secs(60))));
+        assert_eq!(gate.delay(), Some(Duration::from_secs(1)));
+        assert!(!gate.resume());
+
+        gate.enter(Some(Duration::ZERO));
+        assert!(gate.delay().is_none());
+        assert!(gate.resume());
+        assert!(!gate.in_maintenance());
+        assert_eq!(gate.stats()["windows"], 1);
+    }
+
+    /// Without `max_wait` calls never wait.
+    #[test]
+    fn test_no_wait_when_disabled() {
+        let gate = MaintenanceGate::new(None);
+        gate.enter(None);
+        assert!(gate.delay().is_none());
+        assert!(gate.in_maintenance());
+    }
+}
@@ -0,0 +1,260 @@
+//! Portable proofs that an identity belongs to a context, behind
+//! `Client.export_membership_proof()` and `verify_membership_proof()`.
+//!
+//! A service acting as a context identity sometimes has to convince a
+//! system outside the network (a partner API, an auditor) that it really is
+//! that member. Nodes do not sign their responses, so the proof is signed
+//! by the identity itself: the holder of the key ([`crate::signing::PySigner`])
+//! signs a claim naming the context, its public key, the context's root
+//! hash at the time, an optional audience and an expiry. The exporting
+//! client first checks that the key is among the context's identities on
+//! its node, so it never signs a claim its node would contradict.
+//!
+//! Anyone can check the signature offline — the format is plain JSON and
+//! the signed bytes are spelled out in [`MembershipProof::signing_bytes`],
+//! so verifiers need not use this library. That proves possession of the
+//! key; membership itself is confirmed by a node the verifier trusts,
+//! which `verify_membership_proof(proof, client=...)` does by looking the
+//! key up in the context's identities there.
+
+use calimero_primitives::context::ContextId;
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::client::PyClient;
+use crate::utils::{json_to_python, python_to_json};
+use crate::wallet_login::SigningKey;
+
+/// Proof format version.
+pub const MEMBERSHIP_PROOF_VERSION: u32 = 1;
+
+/// Default validity of a proof (one hour).
+pub const DEFAULT_MEMBERSHIP_PROOF_TTL_SECS: u64 = 3600;
+
+/// Domain separator, so these signatures are never valid for anything else.
+const SIGNING_DOMAIN: &str = "calimero-membership-proof-v1";
+
+/// Clock difference tolerated between the issuer and the verifier.
+const CLOCK_SKEW_SECS: i64 = 300;
+
+/// Claim by the holder of `public_key` that it is a member of `context_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipProof {
+    pub version: u32,
+    pub context_id: String,
+    /// Member's public key, base58 like context identities.
+    pub public_key: String,
+    /// Context root hash when the proof was issued, anchoring it to a state.
+    pub root_hash: String,
+    /// Who the proof is meant for; verifiers expecting another refuse it.
+    pub audience: Option<String>,
+    /// Hex; makes each proof unique.
+    pub nonce: String,
+    /// Unix time (seconds) the proof was issued.
+    pub issued_at: i64,
+    /// Unix time (seconds) after which the proof is refused.
+    pub expires_at: i64,
+    /// Base58 Ed25519 signature over [`MembershipProof::signing_bytes`].
+    pub signature: String,
+}
+
+impl MembershipProof {
+    /// Sign a claim of membership with the member's own key.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sign(
+        key: &SigningKey,
+        context_id: &ContextId,
+        root_hash: &str,
+        audience: Option<String>,
+        nonce: [u8; 16],
+        issued_at: i64,
+        expires_at: i64,
+    ) -> Self {
+        let mut proof = Self {
+            version: MEMBERSHIP_PROOF_VERSION,
+            context_id: context_id.to_string(),
+            public_key: bs58::encode(key.public_key()).into_string(),
+            root_hash: root_hash.to_string(),
+            audience,
+            nonce: hex::encode(nonce),
+            issued_at,
+            expires_at,
+            signature: String::new(),
+        };
+        proof.signature = bs58::encode(key.sign(&proof.signing_bytes())).into_string();
+        proof
+    }
+
+    /// The signed message: the domain and every field but the signature,
+    /// one per line, with an empty line for no audience.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            SIGNING_DOMAIN,
+            self.context_id,
+            self.public_key,
+            self.root_hash,
+            self.audience.as_deref().unwrap_or_default(),
+            self.nonce,
+            self.issued_at,
+            self.expires_at
+        )
+        .into_bytes()
+    }
+
+    /// Check the version, validity window, audience and signature at `now`.
+    pub fn verify(&self, now: i64, audience: Option<&str>) -> eyre::Result<ContextId> {
+        if self.version != MEMBERSHIP_PROOF_VERSION {
+            eyre::bail!("Unsupported membership proof version {}", self.version);
+        }
+        if self.expires_at <= now {
+            eyre::bail!("Membership proof expired at {}", self.expires_at);
+        }
+        if self.issued_at > now + CLOCK_SKEW_SECS {
+            eyre::bail!(
+                "Membership proof is issued in the future ({})",
+                self.issued_at
+            );
+        }
+        if let Some(expected) = audience {
+            if self.audience.as_deref() != Some(expected) {
+                eyre::bail!(
+                    "Membership proof is for {}, not '{}'",
+                    self.audience
+                        .as_deref()
+                        .map_or("any audience".to_string(), |a| format!("'{}'", a)),
+                    expected
+                );
+            }
+        }
+        let context_id = self
+            .context_id
+            .parse::<ContextId>()
+            .map_err(|e| eyre::eyre!("Invalid context id in membership proof: {}", e))?;
+        let public_key = bs58::decode(&self.public_key)
+            .into_vec()
+            .wrap_err("Membership proof public key is not base58")?;
+        let signature = bs58::decode(&self.signature)
+            .into_vec()
+            .wrap_err("Membership proof signature is not base58")?;
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(&self.signing_bytes(), &signature)
+            .map_err(|_| eyre::eyre!("Membership proof signature does not match its content"))?;
+        Ok(context_id)
+    }
+}
+
+/// Whether `public_key` is among the identities of a
+/// `get_context_identities` response.
+pub fn is_member(identities: &Value, public_key: &str) -> bool {
+    let data = identities.get("data").unwrap_or(identities);
+    let list = data.get("identities").unwrap_or(data);
+    list.as_array().is_some_and(|identities| {
+        identities.iter().any(|identity| match identity {
+            Value::String(key) => key == public_key,
+            record => ["publicKey", "public_key", "id"]
+                .iter()
+                .any(|field| record.get(*field).and_then(Value::as_str) == Some(public_key)),
+        })
+    })
+}
+
+/// A proof given as a dict or its JSON text.
+pub fn extract_proof(proof: &Bound<'_, PyAny>) -> PyResult<MembershipProof> {
+    let value = match proof.downcast::<pyo3::types::PyString>() {
+        Ok(raw) => serde_json::from_str(raw.to_str()?).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid membership proof JSON: {}",
+                e
+            ))
+        })?,
+        Err(_) => python_to_json(proof)?,
+    };
+    serde_json::from_value(value).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid membership proof: {}", e))
+    })
+}
+
+/// Check a proof from `Client.export_membership_proof()`: its signature,
+/// expiry and, when given, that it was issued for `audience`. With a
+/// `client`, the key must also be among the context's identities on that
+/// client's node. Returns the proof as a dict; raises `ValueError` when it
+/// does not hold.
+#[pyfunction]
+#[pyo3(signature = (proof, audience=None, client=None))]
+pub fn verify_membership_proof(
+    py: Python<'_>,
+    proof: &Bound<'_, PyAny>,
+    audience: Option<&str>,
+    client: Option<&Bound<'_, PyClient>>,
+) -> PyResult<PyObject> {
+    let proof = extract_proof(proof)?;
+    let context_id = proof
+        .verify(chrono::Utc::now().timestamp(), audience)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e)))?;
+    if let Some(client) = client {
+        let identities = client.borrow().identities_response(&context_id)?;
+        if !is_member(&identities, &proof.public_key) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "{} is not an identity of context {}",
+                proof.public_key, context_id
+            )));
+        }
+    }
+    let value = serde_json::to_value(&proof)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    Ok(json_to_python(py, &value))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn proof(audience: Option<&str>) -> MembershipProof {
+        let key = SigningKey::from_bytes(&[9u8; 32]).unwrap();
+        let context_id = bs58::encode([1u8; 32]).into_string().parse().unwrap();
+        MembershipProof::sign(
+            &key,
+            &context_id,
+            "root",
+            audience.map(str::to_string),
+            [4u8; 16],
+            1_000,
+            2_000,
+        )
+    }
+
+    /// Proofs verify in their window, for their audience, and break when
+    /// any field changes.
+    #[test]
+    fn test_sign_and_verify() {
+        let proof = proof(Some("partner"));
+        assert!(proof.verify(1_500, Some("partner")).is_ok());
+        assert!(proof.verify(1_500, None).is_ok());
+        assert!(proof.verify(1_500, Some("other")).is_err());
+        assert!(proof.verify(2_000, None).is_err());
+        assert!(proof.verify(600, None).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.root_hash = "other-root".to_string();
+        assert!(tampered.verify(1_500, None).is_err());
+    }
+
+    /// Identities are matched as strings or records with a public key.
+    #[test]
+    fn test_is_member() {
+        let response = json!({"data": {"identities": ["a", "b"]}});
+        assert!(is_member(&response, "b"));
+        assert!(!is_member(&response, "c"));
+        assert!(is_member(&json!([{"publicKey": "c"}]), "c"));
+    }
+}
@@ -0,0 +1,134 @@
+//! Read-through cache of application and context metadata.
+//!
+//! Dashboards re-render application and context lists every few seconds,
+//! and each render would otherwise cost an admin-API round trip per list.
+//! Responses are kept as JSON for `ttl` and served from memory until then;
+//! callers pass `refresh=True` to bypass the cache, and the client drops the
+//! affected entries itself after installing, uninstalling, creating or
+//! deleting anything.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default time metadata responses are served from the cache (seconds).
+pub const DEFAULT_METADATA_TTL_SECS: u64 = 30;
+
+/// Which metadata a cache entry describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetadataKind {
+    Application,
+    Context,
+}
+
+/// Cache key: the kind plus the ID for single lookups, `None` for lists.
+pub type MetadataKey = (MetadataKind, Option<String>);
+
+/// TTL-bounded responses keyed by [`MetadataKey`]; a zero TTL disables it.
+pub struct MetadataCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<MetadataKey, (Instant, serde_json::Value)>>,
+}
+
+impl MetadataCache {
+    pub fn new(ttl_secs: u64) -> Self {
+        Self {
+            ttl: Duration::from_secs(ttl_secs),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    /// The cached response for `key`, if stored within the TTL.
+    pub fn get(&self, key: &MetadataKey) -> Option<serde_json::Value> {
+        let mut entries = self.entries.lock().ok()?;
+        match entries.get(key) {
+            Some((stored_at, value)) if stored_at.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: MetadataKey, value: serde_json::Value) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, (Instant::now(), value));
+        }
+    }
+
+    /// Drop every entry of `kind` (lists and single lookups alike).
+    pub fn invalidate(&self, kind: MetadataKind) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|(entry_kind, _), _| *entry_kind != kind);
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .map(|entries| entries.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(kind: MetadataKind) -> MetadataKey {
+        (kind, None)
+    }
+
+    /// Stored responses are served until invalidated.
+    #[test]
+    fn test_hit_and_invalidate() {
+        let cache = MetadataCache::new(60);
+        cache.insert(list(MetadataKind::Application), serde_json::json!([1]));
+        cache.insert(
+            (MetadataKind::Application, Some("app".to_string())),
+            serde_json::json!({"id": "app"}),
+        );
+        cache.insert(list(MetadataKind::Context), serde_json::json!([2]));
+
+        assert_eq!(
+            cache.get(&list(MetadataKind::Application)),
+            Some(serde_json::json!([1]))
+        );
+
+        cache.invalidate(MetadataKind::Application);
+        assert!(cache.get(&list(MetadataKind::Application)).is_none());
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(&list(MetadataKind::Context)).is_some());
+    }
+
+    /// A zero TTL stores nothing.
+    #[test]
+    fn test_disabled_cache() {
+        let cache = MetadataCache::new(0);
+        cache.insert(list(MetadataKind::Context), serde_json::json!([]));
+        assert!(cache.is_empty());
+        assert!(cache.get(&list(MetadataKind::Context)).is_none());
+    }
+}
@@ -0,0 +1,370 @@
+//! Versioned layout of the client's on-disk state.
+//!
+//! Everything the client persists lives under the state root (see
+//! [`crate::cache::state_root`]): tokens and auth modes in `auth_cache/`,
+//! blob sidecars in `blob_meta/`, subscription offsets in `event_offsets/`.
+//! The layout version is recorded in `layout_version.json` (a missing file
+//! is version 0, the unversioned layout of earlier releases), and each
+//! change to the layout ships as a forward migration in [`MIGRATIONS`] that
+//! the first client of a process applies, so upgrading never requires
+//! wiping the cache. A layout newer
+//! than this release understands is left untouched.
+//!
+//! `calimero.storage_doctor()` reports (and by default repairs) what the
+//! migrations cannot: unparseable files, which are set aside as
+//! `<name>.corrupt`, stale temp files from interrupted writes, and loose
+//! permissions.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use serde::Serialize;
+
+use crate::blob::BLOB_METADATA_SUBDIR;
+use crate::cache::{state_root, AUTH_CACHE_SUBDIR};
+use crate::event_offsets::EVENT_OFFSETS_SUBDIR;
+use crate::permissions::restrict_to_owner;
+use crate::utils::json_to_python;
+
+/// Layout version this release reads and writes.
+pub const LAYOUT_VERSION: u32 = 1;
+
+/// File under the state root recording the layout version.
+pub const VERSION_FILE: &str = "layout_version.json";
+
+/// Temp files older than this are left over from an interrupted write.
+const STALE_TEMP_AGE: Duration = Duration::from_secs(3600);
+
+/// One forward step of the layout, from `to - 1` to `to`.
+struct Migration {
+    to: u32,
+    description: &'static str,
+    apply: fn(&Path) -> eyre::Result<()>,
+}
+
+/// Every layout migration, in order.
+const MIGRATIONS: &[Migration] = &[Migration {
+    to: 1,
+    description: "remove stale temp files and restrict auth_cache permissions",
+    apply: migrate_v1,
+}];
+
+/// Layout version recorded under `root`; 0 when none is.
+pub fn read_version(root: &Path) -> eyre::Result<u32> {
+    let path = root.join(VERSION_FILE);
+    if !path.exists() {
+        return Ok(0);
+    }
+    let json = fs::read_to_string(&path).wrap_err_with(|| format!("Failed to read {:?}", path))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&json).wrap_err_with(|| format!("Failed to parse {:?}", path))?;
+    value
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .map(|version| version as u32)
+        .ok_or_else(|| eyre::eyre!("{:?} has no layout version", path))
+}
+
+fn write_version(root: &Path, version: u32) -> eyre::Result<()> {
+    fs::create_dir_all(root).wrap_err_with(|| format!("Failed to create {:?}", root))?;
+    let path = root.join(VERSION_FILE);
+    let temp_path = path.with_extension(format!("json.{}.tmp", crate::entropy::temp_suffix()));
+    fs::write(
+        &temp_path,
+        serde_json::to_vec_pretty(&serde_json::json!({ "version": version }))?,
+    )
+    .wrap_err_with(|| format!("Failed to write temp file: {:?}", temp_path))?;
+    fs::rename(&temp_path, &path).wrap_err_with(|| format!("Failed to write {:?}", path))
+}
+
+/// Apply the migrations `root` is missing, recording the version after
+/// each step so an interrupted run resumes where it stopped. Returns the
+/// descriptions of the steps applied.
+pub fn migrate(root: &Path) -> eyre::Result<Vec<String>> {
+    let version = read_version(root)?;
+    if version > LAYOUT_VERSION {
+        eyre::bail!(
+            "{:?} uses layout version {}, newer than the {} this release supports; \
+             upgrade calimero-client-py",
+            root,
+            version,
+            LAYOUT_VERSION
+        );
+    }
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.to > version) {
+        (migration.apply)(root)
+            .wrap_err_with(|| format!("Layout migration to version {} failed", migration.to))?;
+        write_version(root, migration.to)?;
+        applied.push(format!("v{}: {}", migration.to, migration.description));
+    }
+    Ok(applied)
+}
+
+/// Migrate the state root once per process. Only touches the disk when the
+/// state directory exists; failures are left to `storage_doctor()`.
+pub fn ensure_migrated() {
+    static MIGRATED: OnceLock<()> = OnceLock::new();
+    MIGRATED.get_or_init(|| {
+        let root = state_root();
+        if root.exists() {
+            let _ = migrate(&root);
+        }
+    });
+}
+
+fn migrate_v1(root: &Path) -> eyre::Result<()> {
+    for dir in state_dirs(root) {
+        for path in stale_temp_files(&dir) {
+            fs::remove_file(&path).wrap_err_with(|| format!("Failed to remove {:?}", path))?;
+        }
+    }
+    let auth_cache = root.join(AUTH_CACHE_SUBDIR);
+    if auth_cache.exists() {
+        restrict_to_owner(&auth_cache, true)?;
+        for path in json_files(&auth_cache) {
+            restrict_to_owner(&path, false)?;
+        }
+    }
+    Ok(())
+}
+
+fn state_dirs(root: &Path) -> Vec<PathBuf> {
+    [
+        AUTH_CACHE_SUBDIR,
+        BLOB_METADATA_SUBDIR,
+        EVENT_OFFSETS_SUBDIR,
+    ]
+    .iter()
+    .map(|subdir| root.join(subdir))
+    .filter(|dir| dir.is_dir())
+    .collect()
+}
+
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+fn json_files(dir: &Path) -> Vec<PathBuf> {
+    entries(dir)
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect()
+}
+
+fn stale_temp_files(dir: &Path) -> Vec<PathBuf> {
+    entries(dir)
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "tmp"))
+        .filter(|path| {
+            fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age >= STALE_TEMP_AGE)
+        })
+        .collect()
+}
+
+/// One problem `storage_doctor` found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Issue {
+    pub path: String,
+    pub problem: String,
+    pub repaired: bool,
+}
+
+/// Outcome of a `storage_doctor` run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub version: Option<u32>,
+    pub current_version: u32,
+    pub migrated: Vec<String>,
+    pub issues: Vec<Issue>,
+}
+
+/// Check the state under `root`, migrating it and fixing what can be fixed
+/// when `repair` is set.
+pub fn diagnose(root: &Path, repair: bool) -> Report {
+    let mut issues = Vec::new();
+    let mut issue = |path: &Path, problem: String, repaired: bool| {
+        issues.push(Issue {
+            path: path.display().to_string(),
+            problem,
+            repaired,
+        })
+    };
+
+    let version_path = root.join(VERSION_FILE);
+    let mut migrated = Vec::new();
+    let mut version = match read_version(root) {
+        Ok(version) => Some(version),
+        Err(e) => {
+            let repaired = repair && fs::rename(&version_path, corrupt_path(&version_path)).is_ok();
+            issue(&version_path, format!("{:#}", e), repaired);
+            repaired.then_some(0)
+        }
+    };
+    match version {
+        Some(current) if current > LAYOUT_VERSION => issue(
+            &version_path,
+            format!(
+                "layout version {} is newer than this release supports ({})",
+                current, LAYOUT_VERSION
+            ),
+            false,
+        ),
+        Some(current) if current < LAYOUT_VERSION && !repair => issue(
+            &version_path,
+            format!(
+                "layout version {} needs migrating to {}",
+                current, LAYOUT_VERSION
+            ),
+            false,
+        ),
+        Some(_) if repair && root.exists() => match migrate(root) {
+            Ok(applied) => {
+                migrated = applied;
+                version = read_version(root).ok();
+            }
+            Err(e) => issue(&version_path, format!("{:#}", e), false),
+        },
+        _ => {}
+    }
+
+    for dir in state_dirs(root) {
+        for path in stale_temp_files(&dir) {
+            let repaired = repair && fs::remove_file(&path).is_ok();
+            issue(
+                &path,
+                "stale temp file from an interrupted write".into(),
+                repaired,
+            );
+        }
+        for path in json_files(&dir) {
+            let Ok(bytes) = fs::read(&path) else {
+                issue(&path, "file is not readable".into(), false);
+                continue;
+            };
+            if crate::crypto::is_envelope(&bytes)
+                || serde_json::from_slice::<serde_json::Value>(&bytes).is_ok()
+            {
+                continue;
+            }
+            let repaired = repair && fs::rename(&path, corrupt_path(&path)).is_ok();
+            issue(&path, "file is not valid JSON".into(), repaired);
+        }
+    }
+
+    let auth_cache = root.join(AUTH_CACHE_SUBDIR);
+    if auth_cache.exists() {
+        let checks = std::iter::once((auth_cache.clone(), true)).chain(
+            json_files(&auth_cache)
+                .into_iter()
+                .map(|path| (path, false)),
+        );
+        for (path, is_dir) in checks {
+            if let Some(finding) = crate::security::check_permissions(&path, is_dir) {
+                let repaired = repair && restrict_to_owner(&path, is_dir).is_ok();
+                issue(&path, finding.message, repaired);
+            }
+        }
+    }
+
+    Report {
+        version,
+        current_version: LAYOUT_VERSION,
+        migrated,
+        issues,
+    }
+}
+
+fn corrupt_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".corrupt");
+    path.with_file_name(name)
+}
+
+/// Check the state root for problems and, with `repair` (the default), fix
+/// them: apply pending layout migrations, set unparseable files aside as
+/// `<name>.corrupt`, remove stale temp files and restrict permissions.
+///
+/// Returns `{version, currentVersion, migrated, issues: [{path, problem,
+/// repaired}]}`.
+#[pyfunction]
+#[pyo3(signature = (repair=true))]
+pub fn storage_doctor(py: Python<'_>, repair: bool) -> PyResult<PyObject> {
+    let report = diagnose(&state_root(), repair);
+    let json = serde_json::to_value(&report).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+            "Failed to serialize response: {}",
+            e
+        ))
+    })?;
+    Ok(json_to_python(py, &json))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(AUTH_CACHE_SUBDIR)).unwrap();
+        root
+    }
+
+    /// An unversioned layout is migrated once and then left alone.
+    #[test]
+    fn test_migrate_unversioned_layout() {
+        let root = temp_root("layout-migrate");
+        assert_eq!(read_version(&root).unwrap(), 0);
+        assert_eq!(migrate(&root).unwrap().len(), MIGRATIONS.len());
+        assert_eq!(read_version(&root).unwrap(), LAYOUT_VERSION);
+        assert!(migrate(&root).unwrap().is_empty());
+
+        write_version(&root, LAYOUT_VERSION + 1).unwrap();
+        assert!(migrate(&root).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Unparseable files are reported, then set aside on repair.
+    #[test]
+    fn test_doctor_sets_corrupt_files_aside() {
+        let root = temp_root("layout-doctor");
+        let broken = root.join(AUTH_CACHE_SUBDIR).join("node-abc.json");
+        fs::write(&broken, b"{not json").unwrap();
+        fs::write(root.join(AUTH_CACHE_SUBDIR).join("ok.json"), b"{}").unwrap();
+
+        let report = diagnose(&root, false);
+        assert_eq!(report.version, Some(0));
+        assert!(report.migrated.is_empty());
+        assert!(report
+            .issues
+            .iter()
+            .any(|issue| issue.path.ends_with("node-abc.json") && !issue.repaired));
+
+        let report = diagnose(&root, true);
+        assert_eq!(report.version, Some(LAYOUT_VERSION));
+        assert!(!report.migrated.is_empty());
+        assert!(!broken.exists());
+        assert!(corrupt_path(&broken).exists());
+        assert!(diagnose(&root, true).issues.is_empty());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
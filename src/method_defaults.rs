@@ -0,0 +1,193 @@
+//! Client-side default arguments per application method.
+//!
+//! Large codebases tend to repeat the same arguments on every call of a
+//! method (a standard fee, a default visibility). `set_method_defaults()`
+//! registers them once; before an `execute_function` goes out, registered
+//! defaults are merged into its JSON object arguments here in Rust.
+//! Arguments given in the call always win, key by key, with nested objects
+//! merged the same way, so each call site can still override any default.
+//!
+//! Defaults are registered for a method name across all applications or
+//! for one application; both apply, the application's taking precedence.
+//! Application-scoped defaults need the context's application, looked up
+//! through the metadata cache.
+
+use std::collections::BTreeMap;
+use std::sync::{PoisonError, RwLock};
+
+use serde_json::{Map, Value};
+
+/// Registered defaults, keyed by application (`None`: any) and method.
+#[derive(Default)]
+pub struct MethodDefaults {
+    entries: RwLock<BTreeMap<(Option<String>, String), Map<String, Value>>>,
+}
+
+/// Merge `defaults` under `args`: keys missing from `args` are added, and
+/// objects present in both are merged recursively.
+pub fn merge(args: &mut Map<String, Value>, defaults: &Map<String, Value>) {
+    for (key, default) in defaults {
+        match (args.get_mut(key), default) {
+            (None, _) => {
+                args.insert(key.clone(), default.clone());
+            }
+            (Some(Value::Object(given)), Value::Object(default)) => merge(given, default),
+            (Some(_), _) => {}
+        }
+    }
+}
+
+impl MethodDefaults {
+    pub fn set(&self, application_id: Option<&str>, method: &str, defaults: Map<String, Value>) {
+        self.entries
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                (application_id.map(str::to_string), method.to_string()),
+                defaults,
+            );
+    }
+
+    /// Remove the defaults of `method` (every method when `None`) in the
+    /// scope of `application_id`; returns how many registrations went.
+    pub fn clear(&self, application_id: Option<&str>, method: Option<&str>) -> usize {
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        let before = entries.len();
+        entries.retain(|(app, name), _| {
+            app.as_deref() != application_id || method.is_some_and(|method| method != name)
+        });
+        before - entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty()
+    }
+
+    /// Whether `method` has defaults for a specific application, so
+    /// applying them needs the context's application.
+    pub fn is_application_scoped(&self, method: &str) -> bool {
+        self.entries
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .any(|(app, name)| app.is_some() && name == method)
+    }
+
+    /// `args` with the defaults of `method` merged in, or `None` when no
+    /// defaults apply and `args` goes out as given.
+    pub fn apply(
+        &self,
+        application_id: Option<&str>,
+        method: &str,
+        args: &str,
+    ) -> eyre::Result<Option<String>> {
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        let scoped = application_id
+            .and_then(|app| entries.get(&(Some(app.to_string()), method.to_string())));
+        let global = entries.get(&(None, method.to_string()));
+        if scoped.is_none() && global.is_none() {
+            return Ok(None);
+        }
+
+        let mut value: Value = match args.trim() {
+            "" => Value::Object(Map::new()),
+            args => {
+                serde_json::from_str(args).map_err(|e| eyre::eyre!("Invalid JSON args: {}", e))?
+            }
+        };
+        let Value::Object(fields) = &mut value else {
+            eyre::bail!(
+                "{} has default arguments, so its args must be a JSON object",
+                method
+            );
+        };
+        for defaults in [scoped, global].into_iter().flatten() {
+            merge(fields, defaults);
+        }
+        Ok(Some(value.to_string()))
+    }
+
+    /// Registrations as `[{applicationId, method, defaults}]`.
+    pub fn to_json(&self) -> Value {
+        Value::Array(
+            self.entries
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .iter()
+                .map(|((app, method), defaults)| {
+                    serde_json::json!({
+                        "applicationId": app,
+                        "method": method,
+                        "defaults": defaults,
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(fields) => fields,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Call arguments win key by key, nested objects included.
+    #[test]
+    fn test_merge_keeps_call_arguments() {
+        let mut args = object(json!({"fee": 5, "options": {"visibility": "private"}}));
+        merge(
+            &mut args,
+            &object(json!({"fee": 1, "memo": "", "options": {"visibility": "public", "ttl": 60}})),
+        );
+        assert_eq!(
+            Value::Object(args),
+            json!({"fee": 5, "memo": "", "options": {"visibility": "private", "ttl": 60}})
+        );
+    }
+
+    /// Application defaults take precedence over global ones; other
+    /// methods and applications are untouched.
+    #[test]
+    fn test_apply_scopes() {
+        let defaults = MethodDefaults::default();
+        defaults.set(
+            None,
+            "post",
+            object(json!({"fee": 1, "visibility": "public"})),
+        );
+        defaults.set(Some("app"), "post", object(json!({"fee": 2})));
+        assert!(defaults.is_application_scoped("post"));
+
+        let applied = defaults.apply(Some("app"), "post", "{}").unwrap().unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&applied).unwrap(),
+            json!({"fee": 2, "visibility": "public"})
+        );
+        let applied = defaults.apply(Some("other"), "post", "").unwrap().unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&applied).unwrap(),
+            json!({"fee": 1, "visibility": "public"})
+        );
+        assert_eq!(defaults.apply(Some("app"), "get", "[1]").unwrap(), None);
+        assert!(defaults.apply(None, "post", "[1]").is_err());
+
+        assert_eq!(defaults.clear(Some("app"), None), 1);
+        assert!(!defaults.is_application_scoped("post"));
+        assert_eq!(defaults.clear(None, Some("post")), 1);
+        assert!(defaults.is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+//! Request metrics of a client, behind `Client.metrics()`.
+//!
+//! Each client talks to one node, so its counters are that node's: calls
+//! made, calls that raised, proactive token refreshes and their failures,
+//! and the latency of the last [`LATENCY_WINDOW`] calls, from which the
+//! percentiles are taken. Retries come from the client's retry budget,
+//! which already counts them. Snapshots are plain JSON; [`prometheus`]
+//! renders one or more of them in the Prometheus text exposition format,
+//! labelled by node, for services that scrape an endpoint of their own.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+/// Latencies kept for percentiles.
+pub const LATENCY_WINDOW: usize = 1024;
+
+/// Percentiles reported, as quantiles.
+const QUANTILES: [f64; 4] = [0.5, 0.9, 0.95, 0.99];
+
+#[derive(Debug, Default)]
+struct State {
+    requests: u64,
+    errors: u64,
+    token_refreshes: u64,
+    token_refresh_failures: u64,
+    /// Total latency of all calls, for the summary's `_sum`.
+    latency_total: Duration,
+    latencies: VecDeque<Duration>,
+}
+
+/// Counters of one client.
+#[derive(Debug)]
+pub struct Metrics {
+    node: String,
+    started: Instant,
+    state: Mutex<State>,
+}
+
+impl Metrics {
+    /// Metrics labelled with `node` (its name, or its URL's origin).
+    pub fn new(node: String) -> Self {
+        Self {
+            node,
+            started: Instant::now(),
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// A call completed after `elapsed`, whatever its outcome.
+    pub fn record_request(&self, elapsed: Duration) {
+        if let Ok(mut state) = self.state.lock() {
+            state.requests += 1;
+            state.latency_total += elapsed;
+            if state.latencies.len() == LATENCY_WINDOW {
+                state.latencies.pop_front();
+            }
+            state.latencies.push_back(elapsed);
+        }
+    }
+
+    /// A call raised.
+    pub fn record_error(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.errors += 1;
+        }
+    }
+
+    /// A proactive token refresh was attempted.
+    pub fn record_token_refresh(&self, succeeded: bool) {
+        if let Ok(mut state) = self.state.lock() {
+            match succeeded {
+                true => state.token_refreshes += 1,
+                false => state.token_refresh_failures += 1,
+            }
+        }
+    }
+
+    /// `{node, requests, errors, retries, tokenRefreshes,
+    /// tokenRefreshFailures, latencyMs: {count, totalMs, mean, p50, p90,
+    /// p95, p99, max}, uptimeSecs}`; latency statistics beyond `count` and
+    /// `totalMs` cover the latency window and are `null` before any call.
+    pub fn snapshot(&self, retries: u64) -> Value {
+        let Ok(state) = self.state.lock() else {
+            return Value::Null;
+        };
+        let mut window: Vec<f64> = state.latencies.iter().map(millis).collect();
+        window.sort_by(f64::total_cmp);
+        let mean = (!window.is_empty()).then(|| window.iter().sum::<f64>() / window.len() as f64);
+        let mut latency = json!({
+            "count": state.requests,
+            "totalMs": millis(&state.latency_total),
+            "mean": mean,
+            "max": window.last(),
+        });
+        for quantile in QUANTILES {
+            latency[format!("p{}", (quantile * 100.0).round())] =
+                percentile(&window, quantile).into();
+        }
+        json!({
+            "node": self.node,
+            "requests": state.requests,
+            "errors": state.errors,
+            "retries": retries,
+            "tokenRefreshes": state.token_refreshes,
+            "tokenRefreshFailures": state.token_refresh_failures,
+            "latencyMs": latency,
+            "uptimeSecs": self.started.elapsed().as_secs_f64(),
+        })
+    }
+}
+
+fn millis(duration: &Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Nearest-rank percentile of sorted `values`.
+fn percentile(values: &[f64], quantile: f64) -> Option<f64> {
+    let rank = (quantile * values.len() as f64).ceil() as usize;
+    values.get(rank.clamp(1, values.len().max(1)) - 1).copied()
+}
+
+/// `value` as a Prometheus label value.
+fn label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Counters exported, with the snapshot field each comes from.
+const COUNTERS: [(&str, &str, &str); 5] = [
+    ("requests", "requests_total", "Calls made by the client."),
+    ("errors", "errors_total", "Calls that raised."),
+    ("retries", "retries_total", "Retried requests."),
+    (
+        "tokenRefreshes",
+        "token_refreshes_total",
+        "Proactive token refreshes.",
+    ),
+    (
+        "tokenRefreshFailures",
+        "token_refresh_failures_total",
+        "Proactive token refreshes that failed.",
+    ),
+];
+
+/// `snapshots` in the Prometheus text exposition format, one series per
+/// node under the `calimero_client_` prefix.
+pub fn prometheus(snapshots: &[Value]) -> String {
+    let mut text = String::new();
+    let node = |snapshot: &Value| label(snapshot["node"].as_str().unwrap_or_default());
+    for (field, name, help) in COUNTERS {
+        let _ = writeln!(text, "# HELP calimero_client_{name} {help}");
+        let _ = writeln!(text, "# TYPE calimero_client_{name} counter");
+        for snapshot in snapshots {
+            let value = snapshot[field].as_u64().unwrap_or_default();
+            let _ = writeln!(
+                text,
+                "calimero_client_{name}{{node=\"{}\"}} {value}",
+                node(snapshot)
+            );
+        }
+    }
+
+    let name = "calimero_client_request_duration_seconds";
+    let _ = writeln!(text, "# HELP {name} Call latency.");
+    let _ = writeln!(text, "# TYPE {name} summary");
+    for snapshot in snapshots {
+        let node = node(snapshot);
+        let latency = &snapshot["latencyMs"];
+        for quantile in QUANTILES {
+            let key = format!("p{}", (quantile * 100.0).round());
+            let Some(ms) = latency[key.as_str()].as_f64() else {
+                continue;
+            };
+            let _ = writeln!(
+                text,
+                "{name}{{node=\"{node}\",quantile=\"{quantile}\"}} {}",
+                ms / 1000.0
+            );
+        }
+        let total = latency["totalMs"].as_f64().unwrap_or_default() / 1000.0;
+        let count = latency["count"].as_u64().unwrap_or_default();
+        let _ = writeln!(text, "{name}_sum{{node=\"{node}\"}} {total}");
+        let _ = writeln!(text, "{name}_count{{node=\"{node}\"}} {count}");
+    }
+    text
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counters and nearest-rank percentiles over the latency window.
+    #[test]
+    fn test_snapshot() {
+        let metrics = Metrics::new("node1".to_string());
+        assert_eq!(metrics.snapshot(0)["latencyMs"]["p50"], Value::Null);
+        for ms in 1..=100 {
+            metrics.record_request(Duration::from_millis(ms));
+        }
+        metrics.record_error();
+        metrics.record_token_refresh(true);
+        metrics.record_token_refresh(false);
+
+        let snapshot = metrics.snapshot(3);
+        assert_eq!(snapshot["requests"], 100);
+        assert_eq!(snapshot["errors"], 1);
+        assert_eq!(snapshot["retries"], 3);
+        assert_eq!(snapshot["tokenRefreshes"], 1);
+        assert_eq!(snapshot["tokenRefreshFailures"], 1);
+        assert_eq!(snapshot["latencyMs"]["p50"], 50.0);
+        assert_eq!(snapshot["latencyMs"]["p99"], 99.0);
+        assert_eq!(snapshot["latencyMs"]["max"], 100.0);
+    }
+
+    /// Every node gets its own series under one HELP/TYPE header.
+    #[test]
+    fn test_prometheus() {
+        let metrics = Metrics::new("node \"a\"".to_string());
+        metrics.record_request(Duration::from_millis(250));
+        let text = prometheus(&[metrics.snapshot(0), Metrics::new("b".into()).snapshot(0)]);
+        assert_eq!(
+            text.matches("# TYPE calimero_client_requests_total counter")
+                .count(),
+            1
+        );
+        assert!(text.contains("calimero_client_requests_total{node=\"node \\\"a\\\"\"} 1"));
+        assert!(text.contains("calimero_client_requests_total{node=\"b\"} 0"));
+        assert!(text.contains(
+            "calimero_client_request_duration_seconds{node=\"node \\\"a\\\"\",quantile=\"0.5\"} 0.25"
+        ));
+        assert!(text.contains("calimero_client_request_duration_seconds_count{node=\"b\"} 0"));
+    }
+}
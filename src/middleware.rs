@@ -0,0 +1,205 @@
+//! Request and response hooks (`Client.add_request_hook`,
+//! `Client.add_response_hook`).
+//!
+//! Request hooks get a dict `{method, url, headers}` before a request is
+//! sent and may change `headers` in place, to add correlation IDs or the
+//! headers a corporate proxy wants; an exception aborts the request. Response
+//! hooks get `{method, url, status, headers, elapsed_ms}` once the response
+//! headers arrive, for auditing; their exceptions are printed, not raised.
+//!
+//! Hooks see the requests the binding sends itself through [`crate::http`]:
+//! blob streams, auth-service calls and JSON-RPC executions, which go through
+//! it instead of the typed client while any hook is registered. Admin calls
+//! made by `calimero-client` and WebSocket subscriptions are not visible.
+//!
+//! The hooks of the client making a call are found through a task-local set
+//! by [`scope`] around the call, so tasks spawned in the background (which
+//! could not safely wait for the GIL) never run them.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, PROXY_AUTHORIZATION};
+use reqwest::{Method, StatusCode};
+use url::Url;
+
+use crate::callbacks::{Callback, CallbackList, PyCallbackHandle};
+
+tokio::task_local! {
+    static ACTIVE: Arc<Middleware>;
+}
+
+/// Request and response hooks of one client.
+#[derive(Default)]
+pub struct Middleware {
+    request: Arc<CallbackList>,
+    response: Arc<CallbackList>,
+}
+
+impl Middleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe_request(&self, callback: Callback) -> PyCallbackHandle {
+        self.request.subscribe("request_hook", callback)
+    }
+
+    pub fn subscribe_response(&self, callback: Callback) -> PyCallbackHandle {
+        self.response.subscribe("response_hook", callback)
+    }
+
+    pub fn clear(&self) {
+        self.request.clear();
+        self.response.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.request.is_empty() && self.response.is_empty()
+    }
+
+    /// Run the request hooks on a request about to be sent; returns the
+    /// headers to send it with.
+    pub fn before_request(
+        &self,
+        method: &Method,
+        url: &Url,
+        headers: HeaderMap,
+    ) -> eyre::Result<HeaderMap> {
+        if self.request.is_empty() {
+            return Ok(headers);
+        }
+        Python::with_gil(|py| {
+            let hooks = self.request.live(py);
+            if hooks.is_empty() {
+                return Ok(headers);
+            }
+            let run = || -> PyResult<HeaderMap> {
+                let request = PyDict::new_bound(py);
+                request.set_item("method", method.as_str())?;
+                request.set_item("url", url.as_str())?;
+                request.set_item("headers", headers_to_dict(py, &headers)?)?;
+                for hook in &hooks {
+                    hook.call1(py, (&request,))?;
+                }
+                match request.get_item("headers")? {
+                    Some(headers) => headers_from_dict(&headers),
+                    None => Ok(HeaderMap::new()),
+                }
+            };
+            run().map_err(|e| eyre::eyre!("Request hook failed: {}", e))
+        })
+    }
+
+    /// Run the response hooks on a response's status and headers.
+    pub fn after_response(
+        &self,
+        method: &Method,
+        url: &Url,
+        status: StatusCode,
+        headers: &HeaderMap,
+        elapsed: Duration,
+    ) {
+        if self.response.is_empty() {
+            return;
+        }
+        Python::with_gil(|py| {
+            let hooks = self.response.live(py);
+            if hooks.is_empty() {
+                return;
+            }
+            let response = (|| -> PyResult<Bound<'_, PyDict>> {
+                let response = PyDict::new_bound(py);
+                response.set_item("method", method.as_str())?;
+                response.set_item("url", url.as_str())?;
+                response.set_item("status", status.as_u16())?;
+                response.set_item("headers", headers_to_dict(py, headers)?)?;
+                response.set_item("elapsed_ms", elapsed.as_secs_f64() * 1000.0)?;
+                Ok(response)
+            })();
+            let response = match response {
+                Ok(response) => response,
+                Err(e) => return e.print(py),
+            };
+            for hook in hooks {
+                if let Err(e) = hook.call1(py, (&response,)) {
+                    e.print(py);
+                }
+            }
+        })
+    }
+}
+
+/// Run `fut` with `middleware` as the hooks of the requests it sends.
+pub async fn scope<F: std::future::Future>(middleware: Arc<Middleware>, fut: F) -> F::Output {
+    ACTIVE.scope(middleware, fut).await
+}
+
+/// Hooks of the call being made, when it has any.
+pub fn active() -> Option<Arc<Middleware>> {
+    ACTIVE
+        .try_with(Arc::clone)
+        .ok()
+        .filter(|middleware| !middleware.is_empty())
+}
+
+/// Header map as a `{name: value}` dict; repeated headers are joined with
+/// `, ` and values that are not text are left out.
+pub(crate) fn headers_to_dict<'py>(
+    py: Python<'py>,
+    headers: &HeaderMap,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    for name in headers.keys() {
+        let values: Vec<&str> = headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect();
+        if !values.is_empty() {
+            dict.set_item(name.as_str(), values.join(", "))?;
+        }
+    }
+    Ok(dict)
+}
+
+pub(crate) fn headers_from_dict(headers: &Bound<'_, PyAny>) -> PyResult<HeaderMap> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid header: {}", e))
+    };
+    let mut map = HeaderMap::new();
+    for (name, value) in headers.downcast::<PyDict>()?.iter() {
+        let name: String = name.extract()?;
+        let value: String = value.str()?.extract()?;
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| invalid(&e))?;
+        let mut value = HeaderValue::from_str(&value).map_err(|e| invalid(&e))?;
+        value.set_sensitive(name == AUTHORIZATION || name == PROXY_AUTHORIZATION);
+        map.append(name, value);
+    }
+    Ok(map)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hooks are only found inside a scope that has some registered.
+    #[tokio::test]
+    async fn test_active_only_inside_scope() {
+        assert!(active().is_none());
+        let middleware = Arc::new(Middleware::new());
+        let empty = scope(middleware.clone(), async { active().is_some() }).await;
+        assert!(!empty);
+        let spawned = scope(middleware, async {
+            tokio::spawn(async { active().is_some() }).await.unwrap()
+        })
+        .await;
+        assert!(!spawned);
+    }
+}
@@ -0,0 +1,278 @@
+//! Node addresses, as accepted by `create_connection()` and friends.
+//!
+//! Node URLs used to go to `Url::parse` as given: a bare `localhost:2428`
+//! parsed as a URL with scheme `localhost`, and a path prefix without a
+//! trailing slash lost its last segment once request paths were joined
+//! onto it. A [`NodeAddress`] is parsed and checked once, up front, from
+//! one of three forms:
+//!
+//! - `http://host:port` or `https://host:port`, optionally with a path
+//!   prefix (`grpc://` and `grpcs://` are recognized too, see
+//!   [`Transport`]);
+//! - bare `host:port`, taken as `http://host:port`;
+//! - `calimero://node-name`, the URL of `node-name` in the client config
+//!   (see [`crate::config`]), which also names the node.
+//!
+//! The URL is normalized to end in exactly one `/`, so that request paths
+//! join onto the whole prefix. Credentials, queries and fragments are
+//! rejected: none of them would reach the node as intended.
+
+use std::fmt;
+
+use pyo3::prelude::*;
+use url::Url;
+
+use crate::config;
+use crate::transport::Transport;
+
+/// Scheme of addresses naming a node of the client config.
+pub const CONFIG_SCHEME: &str = "calimero";
+
+const FORMS: &str = "expected http(s)://host:port, host:port or calimero://node-name";
+
+/// A validated node URL, and the config node it was looked up from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeAddress {
+    url: Url,
+    node_name: Option<String>,
+}
+
+impl fmt::Display for NodeAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.url.as_str())
+    }
+}
+
+impl NodeAddress {
+    /// Parse `raw`, looking `calimero://` names up in the client config.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        Self::parse_with(raw, |name| {
+            config::node_url(name).map_err(|e| format!("{:#}", e))
+        })
+    }
+
+    fn parse_with(
+        raw: &str,
+        lookup: impl Fn(&str) -> Result<String, String>,
+    ) -> Result<Self, String> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return Err(format!("Invalid node address: empty ({})", FORMS));
+        }
+        let prefix = format!("{}://", CONFIG_SCHEME);
+        let Some(name) = raw.strip_prefix(&prefix) else {
+            return Ok(Self {
+                url: parse_url(raw)?,
+                node_name: None,
+            });
+        };
+        let name = name.trim_end_matches('/');
+        if name.is_empty() || name.contains(['/', '?', '#']) {
+            return Err(format!(
+                "Invalid node address '{}': expected calimero://node-name",
+                raw
+            ));
+        }
+        let url = lookup(name).map_err(|e| format!("Invalid node address '{}': {}", raw, e))?;
+        let url = parse_url(&url).map_err(|e| format!("{} (node {:?} of the config)", e, name))?;
+        Ok(Self {
+            url,
+            node_name: Some(name.to_string()),
+        })
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Config node the address was looked up from (`calimero://` only).
+    pub fn node_name(&self) -> Option<&str> {
+        self.node_name.as_deref()
+    }
+
+    pub fn into_url(self) -> Url {
+        self.url
+    }
+}
+
+/// Parse an `http(s)://` (or other scheme) URL or a bare `host:port`.
+fn parse_url(raw: &str) -> Result<Url, String> {
+    let invalid = |reason: &str| format!("Invalid node address '{}': {}", raw, reason);
+    let url = match raw.contains("://") {
+        true => Url::parse(raw),
+        false => {
+            let authority = raw.split('/').next().unwrap_or_default();
+            let port = match authority.rsplit_once(':') {
+                Some((_, port)) if !authority.ends_with(']') => port,
+                _ => return Err(invalid(&format!("no scheme or port; {}", FORMS))),
+            };
+            if !port.parse::<u16>().is_ok_and(|port| port != 0) {
+                return Err(invalid(&format!("'{}' is not a port number", port)));
+            }
+            Url::parse(&format!("http://{}", raw))
+        }
+    };
+    let mut url = url.map_err(|e| invalid(&e.to_string()))?;
+    Transport::from_url(&url).map_err(|e| invalid(&e))?;
+    if !url.host_str().is_some_and(|host| !host.is_empty()) {
+        return Err(invalid("no host"));
+    }
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(invalid(
+            "credentials do not belong in the URL; log in or pass tokens instead",
+        ));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(invalid("a node URL takes no query or fragment"));
+    }
+    let path = format!("{}/", url.path().trim_end_matches('/'));
+    url.set_path(&path);
+    Ok(url)
+}
+
+impl<'py> FromPyObject<'py> for NodeAddress {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(address) = ob.downcast::<PyNodeAddress>() {
+            return Ok(address.get().inner.clone());
+        }
+        let raw: String = ob.extract().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "node address must be a str or NodeAddress, not {}",
+                ob.get_type()
+                    .name()
+                    .map_or_else(|_| "?".to_string(), |n| n.to_string())
+            ))
+        })?;
+        Self::parse(&raw).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+    }
+}
+
+/// A parsed node address; accepted wherever an `api_url` is.
+#[pyclass(name = "NodeAddress", frozen)]
+pub struct PyNodeAddress {
+    inner: NodeAddress,
+}
+
+#[pymethods]
+impl PyNodeAddress {
+    /// Parse `address`: `http(s)://host:port[/prefix]`, `host:port` or
+    /// `calimero://node-name`. Raises `ValueError` with the reason when it
+    /// is not a usable node address.
+    #[new]
+    pub fn new(address: &str) -> PyResult<Self> {
+        NodeAddress::parse(address)
+            .map(|inner| Self { inner })
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+    }
+
+    /// Normalized URL, ending in `/`
+    #[getter]
+    pub fn url(&self) -> String {
+        self.inner.to_string()
+    }
+
+    #[getter]
+    pub fn scheme(&self) -> &str {
+        self.inner.url.scheme()
+    }
+
+    #[getter]
+    pub fn host(&self) -> String {
+        self.inner.url.host_str().unwrap_or_default().to_string()
+    }
+
+    /// Port, explicit or the scheme's default
+    #[getter]
+    pub fn port(&self) -> Option<u16> {
+        self.inner.url.port_or_known_default()
+    }
+
+    /// Config node the address was looked up from (`calimero://` only)
+    #[getter]
+    pub fn node_name(&self) -> Option<&str> {
+        self.inner.node_name()
+    }
+
+    pub fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        other
+            .downcast::<Self>()
+            .is_ok_and(|other| other.get().inner == self.inner)
+    }
+
+    pub fn __hash__(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.inner.url.as_str().hash(&mut hasher);
+        self.inner.node_name.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    pub fn __repr__(&self) -> String {
+        match self.inner.node_name() {
+            Some(name) => format!("NodeAddress('{}://{}')", CONFIG_SCHEME, name),
+            None => format!("NodeAddress('{}')", self.inner),
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &str) -> Result<String, String> {
+        NodeAddress::parse_with(raw, |name| match name {
+            "staging" => Ok("https://staging.example.com/api//".to_string()),
+            _ => Err(format!("unknown node {:?}", name)),
+        })
+        .map(|address| address.to_string())
+    }
+
+    /// All three forms parse to a URL ending in exactly one slash.
+    #[test]
+    fn test_forms_normalize() {
+        assert_eq!(
+            parse("http://localhost:2428").unwrap(),
+            "http://localhost:2428/"
+        );
+        assert_eq!(
+            parse(" localhost:2428/ ").unwrap(),
+            "http://localhost:2428/"
+        );
+        assert_eq!(parse("[::1]:2428").unwrap(), "http://[::1]:2428/");
+        assert_eq!(
+            parse("https://node.example/prefix///").unwrap(),
+            "https://node.example/prefix/"
+        );
+        assert_eq!(
+            parse("calimero://staging").unwrap(),
+            "https://staging.example.com/api/"
+        );
+        let address =
+            NodeAddress::parse_with("calimero://staging/", |_| Ok("node.example:80".to_string()))
+                .unwrap();
+        assert_eq!(address.node_name(), Some("staging"));
+    }
+
+    /// Unusable addresses are rejected with the reason.
+    #[test]
+    fn test_rejects_with_reason() {
+        let error = |raw: &str| parse(raw).unwrap_err();
+        assert!(error("").contains("empty"));
+        assert!(error("localhost").contains("no scheme or port"));
+        assert!(error("localhost:http").contains("not a port number"));
+        assert!(error("localhost:0").contains("not a port number"));
+        assert!(error("ftp://node.example").contains("Unsupported URL scheme"));
+        assert!(error("http://user:pw@node.example").contains("credentials"));
+        assert!(error("http://node.example/?x=1").contains("query"));
+        assert!(error("calimero://").contains("calimero://node-name"));
+        assert!(error("calimero://prod").contains("unknown node"));
+    }
+}
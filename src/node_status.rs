@@ -0,0 +1,429 @@
+//! Node health, info and peers behind `Client.health()`, `Client.node_info()`
+//! and `Client.peers()`, readiness polling for `wait_until_ready()`, and the
+//! per-context sync status of `Client.get_sync_status()`.
+//!
+//! Health uses the node's unauthenticated `admin-api/health` endpoint, so it
+//! answers before any login and never raises for an unreachable node: the
+//! failure is reported in the returned [`PyNodeHealth`]. Test harnesses that
+//! start nodes in containers poll it with `wait_until_ready(timeout)`, which
+//! backs off from [`READY_POLL_START`] to [`READY_POLL_MAX`] between
+//! attempts until the node reports alive or the timeout runs out.
+
+use std::time::{Duration, Instant};
+
+use pyo3::prelude::*;
+use reqwest::header::SERVER;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::utils::json_to_python;
+
+/// Limit on one health request.
+pub const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// First pause between readiness polls; doubled after every failed poll.
+pub const READY_POLL_START: Duration = Duration::from_millis(100);
+
+/// Longest pause between readiness polls.
+pub const READY_POLL_MAX: Duration = Duration::from_secs(2);
+
+/// The node's response with its `data` envelope removed.
+fn unwrap_data(response: &Value) -> &Value {
+    match response.get("data") {
+        Some(data) if !data.is_null() => data,
+        _ => response,
+    }
+}
+
+/// Outcome of one health request.
+#[pyclass(name = "NodeHealth", frozen)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PyNodeHealth {
+    status: Option<String>,
+    http_status: Option<u16>,
+    latency_ms: Option<f64>,
+    version: Option<String>,
+    error: Option<String>,
+}
+
+impl PyNodeHealth {
+    /// Health of a node that answered with `http_status` and `body`.
+    pub fn from_response(
+        http_status: u16,
+        body: &str,
+        latency_ms: f64,
+        version: Option<String>,
+    ) -> Self {
+        let status = serde_json::from_str::<Value>(body).ok().and_then(|body| {
+            unwrap_data(&body)
+                .get("status")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        });
+        let error = match (200..300).contains(&http_status) {
+            true => None,
+            false => Some(format!("Health request failed with status {}", http_status)),
+        };
+        Self {
+            status,
+            http_status: Some(http_status),
+            latency_ms: Some(latency_ms),
+            version,
+            error,
+        }
+    }
+
+    /// Health of a node that could not be reached.
+    pub fn unreachable(error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::default()
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "healthy": self.healthy(),
+            "status": self.status,
+            "httpStatus": self.http_status,
+            "latencyMs": self.latency_ms,
+            "version": self.version,
+            "error": self.error,
+        })
+    }
+}
+
+#[pymethods]
+impl PyNodeHealth {
+    /// Whether the node answered successfully and, when it reports a
+    /// status, reports itself alive
+    #[getter]
+    pub fn healthy(&self) -> bool {
+        self.error.is_none() && self.status.as_deref().map_or(true, |s| s == "alive")
+    }
+
+    /// Status the node reports (`"alive"`), if any
+    #[getter]
+    pub fn status(&self) -> Option<String> {
+        self.status.clone()
+    }
+
+    /// HTTP status of the health response; `None` when unreachable
+    #[getter]
+    pub fn http_status(&self) -> Option<u16> {
+        self.http_status
+    }
+
+    /// Round trip of the health request in milliseconds
+    #[getter]
+    pub fn latency_ms(&self) -> Option<f64> {
+        self.latency_ms
+    }
+
+    /// Server version the node advertises in its `Server` header, if any
+    #[getter]
+    pub fn version(&self) -> Option<String> {
+        self.version.clone()
+    }
+
+    /// Why the node is not healthy, if it is not
+    #[getter]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.to_json())
+    }
+
+    fn __bool__(&self) -> bool {
+        self.healthy()
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.error {
+            Some(error) => format!("NodeHealth(healthy=False, error='{}')", error),
+            None => format!(
+                "NodeHealth(healthy={}, status={:?}, latency_ms={:.1})",
+                if self.healthy() { "True" } else { "False" },
+                self.status.as_deref().unwrap_or("unknown"),
+                self.latency_ms.unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Peers the node is connected to.
+#[pyclass(name = "Peers", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyPeers {
+    record: Value,
+}
+
+impl PyPeers {
+    pub fn from_response(response: &Value) -> Self {
+        Self {
+            record: unwrap_data(response).clone(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyPeers {
+    /// Number of connected peers
+    #[getter]
+    pub fn count(&self) -> u64 {
+        self.record
+            .get("count")
+            .and_then(Value::as_u64)
+            .or_else(|| self.record.as_u64())
+            .unwrap_or_default()
+    }
+
+    /// The record as the node sent it.
+    fn to_dict(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.record)
+    }
+
+    fn __len__(&self) -> usize {
+        self.count() as usize
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Peers(count={})", self.count())
+    }
+}
+
+/// What `node_info()` gathered about the node and how this client talks
+/// to it.
+#[pyclass(name = "NodeInfo", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyNodeInfo {
+    pub api_url: String,
+    pub node_name: Option<String>,
+    pub health: PyNodeHealth,
+    pub auth_mode: Option<String>,
+    pub peer_count: Option<u64>,
+    pub wire_format: Option<&'static str>,
+    pub batch_mode: Option<&'static str>,
+}
+
+impl PyNodeInfo {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "apiUrl": self.api_url,
+            "nodeName": self.node_name,
+            "version": self.health.version,
+            "health": self.health.to_json(),
+            "authMode": self.auth_mode,
+            "peerCount": self.peer_count,
+            "capabilities": {
+                "wireFormat": self.wire_format,
+                "batchMode": self.batch_mode,
+            },
+        })
+    }
+}
+
+#[pymethods]
+impl PyNodeInfo {
+    /// API URL of the node
+    #[getter]
+    pub fn api_url(&self) -> String {
+        self.api_url.clone()
+    }
+
+    /// Node name keying the client's tokens, if any
+    #[getter]
+    pub fn node_name(&self) -> Option<String> {
+        self.node_name.clone()
+    }
+
+    /// Server version the node advertises, if any
+    #[getter]
+    pub fn version(&self) -> Option<String> {
+        self.health.version.clone()
+    }
+
+    /// Health at the time of the call
+    #[getter]
+    pub fn health(&self) -> PyNodeHealth {
+        self.health.clone()
+    }
+
+    /// Auth mode the node runs (`"none"` or `"required"`), if it answered
+    #[getter]
+    pub fn auth_mode(&self) -> Option<String> {
+        self.auth_mode.clone()
+    }
+
+    /// Connected peers, if the node answered
+    #[getter]
+    pub fn peer_count(&self) -> Option<u64> {
+        self.peer_count
+    }
+
+    /// `{wireFormat, batchMode}` negotiated with the node so far; `None`
+    /// values are not known before the first call that needs them
+    #[getter]
+    pub fn capabilities(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.to_json()["capabilities"])
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.to_json())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NodeInfo(api_url='{}', version={:?}, healthy={})",
+            self.api_url,
+            self.health.version.as_deref().unwrap_or("unknown"),
+            if self.health.healthy() {
+                "True"
+            } else {
+                "False"
+            }
+        )
+    }
+}
+
+/// Send one health request to the node at `api_url`.
+pub async fn check(client: &reqwest::Client, api_url: &Url) -> PyNodeHealth {
+    let url = match api_url.join("admin-api/health") {
+        Ok(url) => url,
+        Err(e) => return PyNodeHealth::unreachable(format!("Invalid node URL: {}", e)),
+    };
+    let started = Instant::now();
+    let response = match client.get(url).timeout(HEALTH_TIMEOUT).send().await {
+        Ok(response) => response,
+        Err(e) => return PyNodeHealth::unreachable(format!("Health request failed: {}", e)),
+    };
+    let status = response.status().as_u16();
+    let version = response
+        .headers()
+        .get(SERVER)
+        .and_then(|server| server.to_str().ok())
+        .map(str::to_string);
+    let body = response.text().await.unwrap_or_default();
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+    PyNodeHealth::from_response(status, &body, latency_ms, version)
+}
+
+/// Pause before the poll after `failed` failed ones.
+pub fn ready_backoff(failed: u32) -> Duration {
+    READY_POLL_START
+        .saturating_mul(2u32.saturating_pow(failed))
+        .min(READY_POLL_MAX)
+}
+
+/// Poll the node's health until it is healthy or `timeout` runs out; `Err`
+/// carries the last unhealthy outcome.
+pub async fn wait_until_ready(
+    client: &reqwest::Client,
+    api_url: &Url,
+    timeout: Duration,
+) -> Result<PyNodeHealth, PyNodeHealth> {
+    let deadline = Instant::now() + timeout;
+    let mut failed = 0;
+    loop {
+        let health = check(client, api_url).await;
+        if health.healthy() {
+            return Ok(health);
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(health);
+        }
+        tokio::time::sleep(ready_backoff(failed).min(remaining)).await;
+        failed += 1;
+    }
+}
+
+/// `{contextId, rootHash, dagHeads, peerCount, syncable}` from a
+/// `get_context` and a `get_peers_count` response. A context is only
+/// `syncable` while the node has peers to sync it with.
+pub fn sync_status(context_id: &str, context: &Value, peers: &Value) -> Value {
+    let context = unwrap_data(context);
+    let context = context.get("context").unwrap_or(context);
+    let peers = unwrap_data(peers);
+    let peer_count = peers
+        .get("count")
+        .and_then(Value::as_u64)
+        .or_else(|| peers.as_u64())
+        .unwrap_or(0);
+    let field = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| context.get(*name))
+            .cloned()
+            .unwrap_or(Value::Null)
+    };
+    json!({
+        "contextId": context_id,
+        "rootHash": field(&["rootHash", "root_hash"]),
+        "dagHeads": field(&["dagHeads", "dag_heads"]),
+        "peerCount": peer_count,
+        "syncable": peer_count > 0,
+    })
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sync status combines the context's root hash and DAG heads with the
+    /// node's peer count.
+    #[test]
+    fn test_sync_status() {
+        let context = json!({"data": {"id": "c", "rootHash": "r", "dagHeads": ["h"]}});
+        let status = sync_status("c", &context, &json!({"count": 2}));
+        assert_eq!(status["rootHash"], "r");
+        assert_eq!(status["dagHeads"], json!(["h"]));
+        assert_eq!(status["peerCount"], 2);
+        assert_eq!(status["syncable"], true);
+        let alone = sync_status("c", &json!({"data": {}}), &json!({"data": {"count": 0}}));
+        assert_eq!(alone["rootHash"], Value::Null);
+        assert_eq!(alone["syncable"], false);
+    }
+
+    /// Health is read from the status code and the reported status.
+    #[test]
+    fn test_health_from_response() {
+        let health = PyNodeHealth::from_response(200, r#"{"data":{"status":"alive"}}"#, 3.0, None);
+        assert!(health.healthy());
+        assert_eq!(health.status().as_deref(), Some("alive"));
+
+        assert!(PyNodeHealth::from_response(200, "", 3.0, None).healthy());
+        assert!(!PyNodeHealth::from_response(200, r#"{"status":"starting"}"#, 3.0, None).healthy());
+        let failed = PyNodeHealth::from_response(503, "", 3.0, None);
+        assert!(!failed.healthy());
+        assert_eq!(failed.http_status(), Some(503));
+        assert!(!PyNodeHealth::unreachable("refused".into()).healthy());
+    }
+
+    #[test]
+    fn test_peers_and_backoff() {
+        let peers = PyPeers::from_response(&json!({"data": {"count": 3}}));
+        assert_eq!(peers.count(), 3);
+        assert_eq!(PyPeers::from_response(&json!({"count": 0})).count(), 0);
+
+        assert_eq!(ready_backoff(0), READY_POLL_START);
+        assert_eq!(ready_backoff(1), READY_POLL_START * 2);
+        assert_eq!(ready_backoff(40), READY_POLL_MAX);
+    }
+
+    /// An unreachable node is reported, not raised, once the time is up.
+    #[tokio::test]
+    async fn test_wait_gives_up() {
+        let url = Url::parse("http://127.0.0.1:9/").unwrap();
+        let outcome =
+            wait_until_ready(&reqwest::Client::new(), &url, Duration::from_millis(50)).await;
+        let health = outcome.unwrap_err();
+        assert!(health.error().is_some());
+    }
+}
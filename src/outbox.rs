@@ -0,0 +1,241 @@
+//! Offline outbox of `execute` calls and the order it drains in.
+//!
+//! Calls queued while a node is unreachable are flushed once it is back.
+//! Calls on the same context may depend on each other, so each context's
+//! entries form a lane sent strictly in queue order, while lanes for
+//! different contexts drain in parallel. A lane stops at the first entry
+//! that fails for a transient reason (network, node error, auth); that
+//! entry and everything behind it stay queued for the next flush. Entries
+//! the node refuses outright — a conflict, or a rejection such as an
+//! unknown context or invalid arguments — are dropped from the queue and
+//! reported so the caller can reconcile them.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Mutex;
+
+use calimero_primitives::context::ContextId;
+
+use crate::error::{CallRecord, ErrorCode, ErrorContext};
+
+/// One queued `execute` call.
+#[derive(Debug, Clone)]
+pub struct OutboxEntry {
+    pub id: u64,
+    pub context_id: ContextId,
+    pub method: String,
+    pub args: String,
+    /// Unix seconds at which the call was queued.
+    pub queued_at: i64,
+}
+
+impl OutboxEntry {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "contextId": self.context_id.to_string(),
+            "method": self.method,
+            "args": self.args,
+            "queuedAt": self.queued_at,
+        })
+    }
+}
+
+/// What flushing an entry came to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrainOutcome {
+    Delivered(serde_json::Value),
+    /// The node refused the call because state moved on (HTTP 409).
+    Conflict(String),
+    /// The node refused the call for good (unknown context, bad args, ...).
+    Rejected(String),
+    /// Not delivered this time; the entry stays queued.
+    Deferred(String),
+}
+
+impl DrainOutcome {
+    /// Outcome of a failed send, by the error's [`ErrorCode`].
+    pub fn from_error(message: &str) -> Self {
+        let code = ErrorContext::new(CallRecord::default(), None, message).code;
+        match code {
+            ErrorCode::Conflict => Self::Conflict(message.to_string()),
+            ErrorCode::ContextNotFound
+            | ErrorCode::ApplicationNotFound
+            | ErrorCode::NotFound
+            | ErrorCode::InvalidArgument
+            | ErrorCode::PermissionDenied
+            | ErrorCode::ResponseTooLarge => Self::Rejected(message.to_string()),
+            _ => Self::Deferred(message.to_string()),
+        }
+    }
+}
+
+/// Queue of pending calls, in the order they were made.
+#[derive(Debug, Default)]
+pub struct Outbox {
+    state: Mutex<(u64, VecDeque<OutboxEntry>)>,
+}
+
+impl Outbox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a call; returns its entry id.
+    pub fn push(&self, context_id: ContextId, method: &str, args: &str, queued_at: i64) -> u64 {
+        let Ok(mut state) = self.state.lock() else {
+            return 0;
+        };
+        state.0 += 1;
+        let id = state.0;
+        state.1.push_back(OutboxEntry {
+            id,
+            context_id,
+            method: method.to_string(),
+            args: args.to_string(),
+            queued_at,
+        });
+        id
+    }
+
+    /// Remove and return everything queued.
+    pub fn take_all(&self) -> Vec<OutboxEntry> {
+        self.state
+            .lock()
+            .map(|mut state| state.1.drain(..).collect())
+            .unwrap_or_default()
+    }
+
+    /// Put `entries` back ahead of anything queued since they were taken,
+    /// keeping their relative order.
+    pub fn requeue(&self, entries: Vec<OutboxEntry>) {
+        if let Ok(mut state) = self.state.lock() {
+            for entry in entries.into_iter().rev() {
+                state.1.push_front(entry);
+            }
+        }
+    }
+
+    pub fn entries(&self) -> Vec<OutboxEntry> {
+        self.state
+            .lock()
+            .map(|state| state.1.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().map(|state| state.1.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Split `entries` into per-context lanes, each in queue order; lanes are
+/// ordered by their first entry.
+pub fn lanes(entries: Vec<OutboxEntry>) -> Vec<Vec<OutboxEntry>> {
+    let mut lanes: Vec<Vec<OutboxEntry>> = Vec::new();
+    for entry in entries {
+        match lanes
+            .iter_mut()
+            .find(|lane| lane[0].context_id == entry.context_id)
+        {
+            Some(lane) => lane.push(entry),
+            None => lanes.push(vec![entry]),
+        }
+    }
+    lanes
+}
+
+/// Send one lane in order, stopping at the first deferred entry. Returns
+/// the outcome of every entry attempted, and the entries left to requeue
+/// (the deferred one and all behind it).
+pub async fn drain_lane<F, Fut>(
+    lane: Vec<OutboxEntry>,
+    send: &F,
+) -> (Vec<(OutboxEntry, DrainOutcome)>, Vec<OutboxEntry>)
+where
+    F: Fn(OutboxEntry) -> Fut,
+    Fut: Future<Output = eyre::Result<serde_json::Value>>,
+{
+    let mut outcomes = Vec::new();
+    let mut pending = lane.into_iter();
+    let mut deferred = None;
+    for entry in pending.by_ref() {
+        let outcome = match send(entry.clone()).await {
+            // Execution errors come back in the JSON-RPC body, not as a
+            // failed request; the call ran and was refused.
+            Ok(value) => match value.get("error").filter(|error| !error.is_null()) {
+                Some(error) => DrainOutcome::Rejected(error.to_string()),
+                None => DrainOutcome::Delivered(value),
+            },
+            Err(e) => DrainOutcome::from_error(&format!("{:#}", e)),
+        };
+        let stop = matches!(outcome, DrainOutcome::Deferred(_));
+        outcomes.push((entry.clone(), outcome));
+        if stop {
+            deferred = Some(entry);
+            break;
+        }
+    }
+    let left = deferred
+        .map(|entry| std::iter::once(entry).chain(pending).collect())
+        .unwrap_or_default();
+    (outcomes, left)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(outbox: &Outbox, context: u8, method: &str) {
+        outbox.push(ContextId::from([context; 32]), method, "{}", 0);
+    }
+
+    /// Lanes keep per-context queue order and first-seen context order.
+    #[test]
+    fn test_lanes_preserve_per_context_order() {
+        let outbox = Outbox::new();
+        queue(&outbox, 1, "a1");
+        queue(&outbox, 2, "b1");
+        queue(&outbox, 1, "a2");
+        let lanes = lanes(outbox.take_all());
+        let methods: Vec<Vec<&str>> = lanes
+            .iter()
+            .map(|lane| lane.iter().map(|e| e.method.as_str()).collect())
+            .collect();
+        assert_eq!(methods, vec![vec!["a1", "a2"], vec!["b1"]]);
+        assert!(outbox.is_empty());
+    }
+
+    /// A transient failure halts the lane; refusals are reported and skipped.
+    #[tokio::test]
+    async fn test_drain_lane_stops_at_deferred() {
+        let outbox = Outbox::new();
+        for method in ["ok", "conflict", "down", "after"] {
+            queue(&outbox, 1, method);
+        }
+        let send = |entry: OutboxEntry| async move {
+            match entry.method.as_str() {
+                "ok" => Ok(serde_json::json!(1)),
+                "conflict" => Err(eyre::eyre!("failed with status 409 Conflict")),
+                _ => Err(eyre::eyre!("error sending request: connection refused")),
+            }
+        };
+
+        let (outcomes, left) = drain_lane(outbox.take_all(), &send).await;
+        assert_eq!(outcomes.len(), 3);
+        assert!(matches!(outcomes[1].1, DrainOutcome::Conflict(_)));
+        assert!(matches!(outcomes[2].1, DrainOutcome::Deferred(_)));
+        let left: Vec<&str> = left.iter().map(|e| e.method.as_str()).collect();
+        assert_eq!(left, ["down", "after"]);
+
+        outbox.requeue(vec![outcomes[0].0.clone()]);
+        assert_eq!(outbox.len(), 1);
+    }
+}
@@ -0,0 +1,276 @@
+//! Lazy paging over list endpoints, behind `Query.pages()` and iteration
+//! of a query (`for record in client.contexts()`, `async for` alike).
+//!
+//! The admin API's list endpoints answer with every record at once, so the
+//! list is fetched (or read from the metadata cache) once and kept as JSON
+//! on the Rust side; only the page being consumed is converted to Python
+//! objects, which is where thousands of records cost memory. Pages follow
+//! the query's filters, sort and `offset`/`limit` window. A paginator's
+//! `cursor` is the offset of its next page within the query's results and
+//! resumes it with `pages(cursor=...)`, as long as the list is unchanged.
+//!
+//! Events have no list endpoint to page through: the node pushes them over
+//! the WebSocket, and a `Subscription` is already an (async) iterator.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use pyo3::prelude::*;
+use serde_json::Value;
+
+use crate::client::PyClient;
+use crate::context::PyContext;
+use crate::query::{self, ListQuery, Resource};
+use crate::utils::json_to_python;
+
+/// Records per page unless the caller picks another size.
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Query results and how far they have been consumed.
+#[derive(Debug)]
+struct State {
+    query: ListQuery,
+    page_size: usize,
+    /// Position of the next page in `records`.
+    cursor: usize,
+    /// Results of the query, once fetched.
+    records: Option<Arc<Vec<Value>>>,
+    /// Rest of the current page, when iterating records.
+    pending: VecDeque<Value>,
+}
+
+impl State {
+    fn load(&mut self, response: &Value) {
+        let records = query::records(response, self.query.resource);
+        self.records = Some(Arc::new(self.query.apply(records)));
+    }
+
+    /// The next page, or `None` past the end.
+    fn next_page(&mut self) -> Option<Vec<Value>> {
+        let records = self.records.as_ref()?;
+        if self.cursor >= records.len() {
+            return None;
+        }
+        let end = (self.cursor + self.page_size).min(records.len());
+        let page = records[self.cursor..end].to_vec();
+        self.cursor = end;
+        Some(page)
+    }
+
+    /// The next record, turning the page over when needed.
+    fn next_record(&mut self) -> Option<Value> {
+        if self.pending.is_empty() {
+            self.pending = self.next_page()?.into();
+        }
+        self.pending.pop_front()
+    }
+
+    /// Offset of the first record not yet handed out, `None` past the end.
+    fn position(&self) -> Option<usize> {
+        let position = self.cursor - self.pending.len();
+        match &self.records {
+            Some(records) if position >= records.len() => None,
+            _ => Some(position),
+        }
+    }
+
+    fn next(&mut self, pages: bool) -> Option<Value> {
+        match pages {
+            true => self.next_page().map(Value::Array),
+            false => self.next_record(),
+        }
+    }
+}
+
+/// Lazy iterator over a query's records (or pages of them), sync or async.
+#[pyclass(name = "Paginator")]
+pub struct PyPaginator {
+    client: Py<PyClient>,
+    state: Arc<Mutex<State>>,
+    /// Yield lists of `page_size` records instead of records.
+    pages: bool,
+    typed: bool,
+}
+
+impl PyPaginator {
+    pub fn new(
+        client: Py<PyClient>,
+        query: ListQuery,
+        page_size: usize,
+        cursor: Option<&str>,
+        pages: bool,
+        typed: bool,
+    ) -> PyResult<Self> {
+        let value_error =
+            |message: String| PyErr::new::<pyo3::exceptions::PyValueError, _>(message);
+        if page_size == 0 {
+            return Err(value_error("page_size must be at least 1".to_string()));
+        }
+        if typed && query.resource != Resource::Contexts {
+            return Err(value_error(
+                "typed=True is only available for contexts".to_string(),
+            ));
+        }
+        let cursor = match cursor {
+            Some(cursor) => cursor
+                .parse()
+                .map_err(|_| value_error(format!("Invalid cursor '{}'", cursor)))?,
+            None => 0,
+        };
+        Ok(Self {
+            client,
+            state: Arc::new(Mutex::new(State {
+                query,
+                page_size,
+                cursor,
+                records: None,
+                pending: VecDeque::new(),
+            })),
+            pages,
+            typed,
+        })
+    }
+
+    fn state(&self) -> PyResult<std::sync::MutexGuard<'_, State>> {
+        self.state.lock().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("paginator state is poisoned")
+        })
+    }
+
+    fn convert(py: Python<'_>, value: &Value, typed: bool) -> PyResult<PyObject> {
+        if !typed {
+            return Ok(json_to_python(py, value));
+        }
+        let records = match value {
+            Value::Array(_) => value.clone(),
+            record => Value::Array(vec![record.clone()]),
+        };
+        let contexts = PyContext::list_from_response(&records)
+            .into_iter()
+            .map(|context| Py::new(py, context).map(|context| context.into_any()))
+            .collect::<PyResult<Vec<_>>>()?;
+        match value {
+            Value::Array(_) => Ok(pyo3::types::PyList::new_bound(py, contexts)
+                .into_any()
+                .unbind()),
+            _ => Ok(contexts.into_iter().next().unwrap_or_else(|| py.None())),
+        }
+    }
+}
+
+#[pymethods]
+impl PyPaginator {
+    /// Offset of the next page within the query's results, to resume with
+    /// `pages(cursor=...)`; `None` once everything was consumed.
+    #[getter]
+    pub fn cursor(&self) -> PyResult<Option<String>> {
+        Ok(self
+            .state()?
+            .position()
+            .map(|position| position.to_string()))
+    }
+
+    #[getter]
+    pub fn page_size(&self) -> PyResult<usize> {
+        Ok(self.state()?.page_size)
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let (loaded, resource) = {
+            let state = self.state()?;
+            (state.records.is_some(), state.query.resource)
+        };
+        if !loaded {
+            let response = self.client.borrow(py).list_response(resource, false)?;
+            self.state()?.load(&response);
+        }
+        let item = self.state()?.next(self.pages);
+        item.map(|item| Self::convert(py, &item, self.typed))
+            .transpose()
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Fetches the list on the client's runtime the first time, without
+    /// blocking the event loop.
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let state = self.state.clone();
+        let (pages, typed) = (self.pages, self.typed);
+        let (loaded, resource) = {
+            let state = self.state()?;
+            (state.records.is_some(), state.query.resource)
+        };
+        let fetch =
+            (!loaded).then(|| PyClient::list_response_async(self.client.bind(py), resource));
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            if let Some(fetch) = fetch {
+                let response = fetch.await?;
+                if let Ok(mut state) = state.lock() {
+                    if state.records.is_none() {
+                        state.load(&response);
+                    }
+                }
+            }
+            let item = state.lock().ok().and_then(|mut state| state.next(pages));
+            match item {
+                Some(item) => Python::with_gil(|py| Self::convert(py, &item, typed)),
+                None => Err(PyErr::new::<pyo3::exceptions::PyStopAsyncIteration, _>(())),
+            }
+        })
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        let state = self.state()?;
+        Ok(format!(
+            "Paginator({}, page_size={}, cursor={})",
+            state.query.resource.name(),
+            state.page_size,
+            state.cursor
+        ))
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Pages and records follow the query window; the position counts
+    /// records handed out, not pages fetched.
+    #[test]
+    fn test_pages_and_records() {
+        let response = json!({"data": {"contexts": [
+            {"id": "c1"}, {"id": "c2"}, {"id": "c3"}, {"id": "c4"}, {"id": "c5"}
+        ]}});
+        let query = ListQuery {
+            offset: 1,
+            ..ListQuery::new(Resource::Contexts)
+        };
+        let mut state = State {
+            query,
+            page_size: 2,
+            cursor: 0,
+            records: None,
+            pending: VecDeque::new(),
+        };
+        assert_eq!(state.next(true), None);
+        state.load(&response);
+        assert_eq!(state.next(true), Some(json!([{"id": "c2"}, {"id": "c3"}])));
+        assert_eq!(state.cursor, 2);
+        assert_eq!(state.next(false), Some(json!({"id": "c4"})));
+        assert_eq!(state.position(), Some(3));
+        assert_eq!(state.next(false), Some(json!({"id": "c5"})));
+        assert_eq!(state.next(false), None);
+        assert_eq!(state.position(), None);
+    }
+}
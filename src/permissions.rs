@@ -0,0 +1,133 @@
+//! Owner-only access for the client's on-disk secrets, on every platform.
+//!
+//! On Unix the cache directory and token files are created 0700/0600. The
+//! Windows equivalent is a protected DACL with a single entry granting the
+//! current user full control: inherited entries (local administrators,
+//! `Users` on some setups) are dropped, and files created inside a
+//! restricted directory inherit the owner-only entry. Other platforms keep
+//! their defaults.
+
+use std::path::Path;
+
+/// Restrict `path` to the current user: mode 0700 (directories) or 0600
+/// (files) on Unix, an owner-only DACL on Windows.
+#[cfg(unix)]
+pub fn restrict_to_owner(path: &Path, is_dir: bool) -> eyre::Result<()> {
+    use eyre::WrapErr;
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = if is_dir { 0o700 } else { 0o600 };
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+        .wrap_err_with(|| format!("Failed to restrict permissions of {:?}", path))
+}
+
+#[cfg(windows)]
+pub fn restrict_to_owner(path: &Path, is_dir: bool) -> eyre::Result<()> {
+    windows::restrict_to_owner(path, is_dir)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn restrict_to_owner(_path: &Path, _is_dir: bool) -> eyre::Result<()> {
+    Ok(())
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use std::ptr;
+
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, LocalFree, ERROR_SUCCESS, GENERIC_ALL, HANDLE,
+    };
+    use windows_sys::Win32::Security::Authorization::{
+        SetEntriesInAclW, SetNamedSecurityInfoW, EXPLICIT_ACCESS_W, NO_MULTIPLE_TRUSTEE,
+        SET_ACCESS, SE_FILE_OBJECT, TRUSTEE_IS_SID, TRUSTEE_IS_USER, TRUSTEE_W,
+    };
+    use windows_sys::Win32::Security::{
+        GetTokenInformation, TokenUser, ACL, CONTAINER_INHERIT_ACE, DACL_SECURITY_INFORMATION,
+        NO_INHERITANCE, OBJECT_INHERIT_ACE, PROTECTED_DACL_SECURITY_INFORMATION, TOKEN_QUERY,
+        TOKEN_USER,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    /// `TOKEN_USER` of the current process, in a buffer that keeps the SID
+    /// it points to alive.
+    fn current_user() -> eyre::Result<Vec<u8>> {
+        // SAFETY: the handle is closed below and every buffer passed to the
+        // API is sized by its own length query.
+        unsafe {
+            let mut token: HANDLE = ptr::null_mut();
+            if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+                eyre::bail!(
+                    "OpenProcessToken failed: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+            let mut len = 0u32;
+            GetTokenInformation(token, TokenUser, ptr::null_mut(), 0, &mut len);
+            let mut buffer = vec![0u8; len as usize];
+            let ok =
+                GetTokenInformation(token, TokenUser, buffer.as_mut_ptr().cast(), len, &mut len);
+            let error = std::io::Error::last_os_error();
+            CloseHandle(token);
+            if ok == 0 {
+                eyre::bail!("GetTokenInformation failed: {}", error);
+            }
+            Ok(buffer)
+        }
+    }
+
+    pub fn restrict_to_owner(path: &Path, is_dir: bool) -> eyre::Result<()> {
+        let user = current_user()?;
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+
+        // SAFETY: `user` outlives every use of the SID inside it, and the
+        // ACL allocated by `SetEntriesInAclW` is freed exactly once.
+        unsafe {
+            let sid = (*user.as_ptr().cast::<TOKEN_USER>()).User.Sid;
+            let access = EXPLICIT_ACCESS_W {
+                grfAccessPermissions: GENERIC_ALL,
+                grfAccessMode: SET_ACCESS,
+                grfInheritance: if is_dir {
+                    OBJECT_INHERIT_ACE | CONTAINER_INHERIT_ACE
+                } else {
+                    NO_INHERITANCE
+                },
+                Trustee: TRUSTEE_W {
+                    pMultipleTrustee: ptr::null_mut(),
+                    MultipleTrusteeOperation: NO_MULTIPLE_TRUSTEE,
+                    TrusteeForm: TRUSTEE_IS_SID,
+                    TrusteeType: TRUSTEE_IS_USER,
+                    ptstrName: sid.cast(),
+                },
+            };
+            let mut acl: *mut ACL = ptr::null_mut();
+            let status = SetEntriesInAclW(1, &access, ptr::null(), &mut acl);
+            if status != ERROR_SUCCESS {
+                eyre::bail!(
+                    "SetEntriesInAclW failed: {}",
+                    std::io::Error::from_raw_os_error(status as i32)
+                );
+            }
+            let status = SetNamedSecurityInfoW(
+                wide.as_ptr(),
+                SE_FILE_OBJECT,
+                DACL_SECURITY_INFORMATION | PROTECTED_DACL_SECURITY_INFORMATION,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                acl,
+                ptr::null(),
+            );
+            LocalFree(acl.cast());
+            if status != ERROR_SUCCESS {
+                eyre::bail!(
+                    "Failed to restrict permissions of {:?}: {}",
+                    path,
+                    std::io::Error::from_raw_os_error(status as i32)
+                );
+            }
+        }
+        Ok(())
+    }
+}
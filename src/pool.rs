@@ -0,0 +1,265 @@
+//! Shared HTTP connection pools per node.
+//!
+//! Every client used to get its own `reqwest::Client` for the requests the
+//! binding sends itself (msgpack execute, batches, blob transfers, auth
+//! service calls), so scripts that create clients in a loop paid a fresh
+//! TCP and TLS setup each time. Clients are now taken from a process-wide
+//! registry keyed by node origin and pool settings: clients for the same
+//! node share one `reqwest::Client`, and with it its keep-alive
+//! connections.
+//!
+//! reqwest has no cap on connections per host, so `max_connections_per_host`
+//! is enforced as a process-wide semaphore per node origin that every
+//! request slot of the clients for that node takes a permit from (see
+//! [`crate::concurrency::AdaptiveLimiter::with_host_limit`]).
+//!
+//! Clients lease their pooled client and give it back when closed; a pooled
+//! client no open client has leased is dropped from the registry, and with
+//! it its idle connections.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use eyre::WrapErr;
+use tokio::sync::Semaphore;
+use url::Url;
+
+/// Default number of idle connections kept per host.
+pub const DEFAULT_POOL_MAX_IDLE: usize = 32;
+
+/// Default time an idle pooled connection is kept open.
+pub const DEFAULT_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+
+/// TCP keep-alive interval for pooled connections.
+const TCP_KEEPALIVE: Duration = Duration::from_secs(30);
+
+/// Pool settings of a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PoolConfig {
+    pub max_idle_per_host: usize,
+    pub idle_timeout_secs: u64,
+    /// Cap on concurrent requests to the node across all clients.
+    pub max_connections_per_host: Option<usize>,
+    /// Limit on establishing a connection; `None` leaves it to the OS.
+    pub connect_timeout: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_per_host: DEFAULT_POOL_MAX_IDLE,
+            idle_timeout_secs: DEFAULT_POOL_IDLE_TIMEOUT_SECS,
+            max_connections_per_host: None,
+            connect_timeout: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PoolKey {
+    origin: String,
+    config: PoolConfig,
+}
+
+/// A pooled client and the number of open leases on it.
+struct Pooled {
+    client: reqwest::Client,
+    leases: usize,
+}
+
+#[derive(Default)]
+struct Registry {
+    clients: HashMap<PoolKey, Pooled>,
+    host_limits: HashMap<(String, usize), Arc<Semaphore>>,
+}
+
+fn registry() -> &'static Mutex<Registry> {
+    static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(Mutex::default)
+}
+
+/// `scheme://host:port` of `url`, the unit connections are pooled by.
+pub fn origin(url: &Url) -> String {
+    match (url.host_str(), url.port_or_known_default()) {
+        (Some(host), Some(port)) => format!("{}://{}:{}", url.scheme(), host, port),
+        _ => url.as_str().to_string(),
+    }
+}
+
+fn build(config: &PoolConfig) -> eyre::Result<reqwest::Client> {
+    let builder = reqwest::Client::builder()
+        .pool_max_idle_per_host(config.max_idle_per_host)
+        .pool_idle_timeout(Duration::from_secs(config.idle_timeout_secs))
+        .tcp_keepalive(TCP_KEEPALIVE);
+    let builder = match config.connect_timeout {
+        Some(timeout) => builder.connect_timeout(timeout),
+        None => builder,
+    };
+    builder.build().wrap_err("Failed to build HTTP client")
+}
+
+/// The shared client for `url`'s node with `config`, built on first use.
+pub fn client_for(url: &Url, config: &PoolConfig) -> eyre::Result<reqwest::Client> {
+    lease(url, config).map(|(client, lease)| {
+        // Unleased clients stay pooled for the life of the process.
+        std::mem::forget(lease);
+        client
+    })
+}
+
+/// [`client_for`], with the lease to give back once the caller is done.
+pub fn lease(url: &Url, config: &PoolConfig) -> eyre::Result<(reqwest::Client, PoolLease)> {
+    let key = PoolKey {
+        origin: origin(url),
+        config: *config,
+    };
+    let mut registry = registry()
+        .lock()
+        .map_err(|_| eyre::eyre!("HTTP client pool is poisoned"))?;
+    let pooled = match registry.clients.entry(key.clone()) {
+        std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+        std::collections::hash_map::Entry::Vacant(entry) => entry.insert(Pooled {
+            client: build(config)?,
+            leases: 0,
+        }),
+    };
+    pooled.leases += 1;
+    let lease = PoolLease {
+        key,
+        released: AtomicBool::new(false),
+    };
+    Ok((pooled.client.clone(), lease))
+}
+
+/// One user's hold on a pooled client, given back by [`PoolLease::release`].
+/// Dropping a lease does not release it, so a client dropped without being
+/// closed leaves its pool to the next client for the node.
+pub struct PoolLease {
+    key: PoolKey,
+    released: AtomicBool,
+}
+
+impl PoolLease {
+    /// Give the lease back, dropping the pooled client when it was the
+    /// last one. Returns whether the pooled client was dropped.
+    pub fn release(&self) -> bool {
+        if self.released.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+        let Ok(mut registry) = registry().lock() else {
+            return false;
+        };
+        let Some(pooled) = registry.clients.get_mut(&self.key) else {
+            return false;
+        };
+        pooled.leases = pooled.leases.saturating_sub(1);
+        if pooled.leases > 0 {
+            return false;
+        }
+        registry.clients.remove(&self.key);
+        true
+    }
+}
+
+/// Process-wide permits for at most `limit` concurrent requests to `url`'s
+/// node; clients passing the same limit for a node share them.
+pub fn host_limit(url: &Url, limit: usize) -> Arc<Semaphore> {
+    let key = (origin(url), limit.max(1));
+    let Ok(mut registry) = registry().lock() else {
+        return Arc::new(Semaphore::new(key.1));
+    };
+    registry
+        .host_limits
+        .entry(key)
+        .or_insert_with_key(|(_, limit)| Arc::new(Semaphore::new(*limit)))
+        .clone()
+}
+
+/// Number of distinct pooled clients in the process.
+pub fn pooled_clients() -> usize {
+    registry()
+        .lock()
+        .map(|registry| registry.clients.len())
+        .unwrap_or_default()
+}
+
+impl PoolConfig {
+    /// `{maxIdlePerHost, idleTimeoutSecs, maxConnectionsPerHost, connectTimeoutSecs}`
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "maxIdlePerHost": self.max_idle_per_host,
+            "idleTimeoutSecs": self.idle_timeout_secs,
+            "maxConnectionsPerHost": self.max_connections_per_host,
+            "connectTimeoutSecs": self.connect_timeout.map(|timeout| timeout.as_secs_f64()),
+        })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    /// Paths and default ports do not split a node's pool.
+    #[test]
+    fn test_origin() {
+        assert_eq!(
+            origin(&url("https://node.example/api")),
+            "https://node.example:443"
+        );
+        assert_eq!(
+            origin(&url("http://localhost:2528/")),
+            origin(&url("http://localhost:2528/jsonrpc"))
+        );
+    }
+
+    /// Clients for one node and setting share a pool; other settings don't.
+    #[test]
+    fn test_clients_shared_per_key() {
+        let config = PoolConfig::default();
+        let before = pooled_clients();
+        client_for(&url("http://pool-a.test:1/"), &config).unwrap();
+        client_for(&url("http://pool-a.test:1/x"), &config).unwrap();
+        assert_eq!(pooled_clients(), before + 1);
+        let small = PoolConfig {
+            max_idle_per_host: 1,
+            ..config
+        };
+        client_for(&url("http://pool-a.test:1/"), &small).unwrap();
+        assert_eq!(pooled_clients(), before + 2);
+    }
+
+    /// A pooled client is dropped when its last lease is given back.
+    #[test]
+    fn test_lease_release() {
+        let config = PoolConfig::default();
+        let node = url("http://pool-c.test:1/");
+        let pooled =
+            |lease: &PoolLease| registry().lock().unwrap().clients.contains_key(&lease.key);
+        let (_, first) = lease(&node, &config).unwrap();
+        let (_, second) = lease(&node, &config).unwrap();
+        assert!(!first.release());
+        assert!(!first.release());
+        assert!(pooled(&second));
+        assert!(second.release());
+        assert!(!pooled(&second));
+    }
+
+    /// Host limits are shared by node and limit.
+    #[test]
+    fn test_host_limit_shared() {
+        let a = host_limit(&url("http://pool-b.test:1/"), 2);
+        let b = host_limit(&url("http://pool-b.test:1/other"), 2);
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(a.available_permits(), 2);
+    }
+}
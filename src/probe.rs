@@ -0,0 +1,295 @@
+//! Network and clock probe behind `Client.probe()`.
+//!
+//! Before filing "the node is slow", it helps to know whether the time goes
+//! to the network or to the node. Each sample opens a fresh TCP connection
+//! (and TLS session for `https://`), timing the connect — about one network
+//! round trip — and the handshake, then sends the unauthenticated health
+//! request over the pooled client. The request's round trip minus the
+//! connect time estimates the node's own processing time.
+//!
+//! The clock offset compares the `Date` header of each reply with the local
+//! clock at the midpoint of the request. `Date` has one-second resolution,
+//! so offsets within about a second mean the clocks agree; larger ones
+//! point at clock skew, which breaks token expiry checks.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Utc};
+use eyre::WrapErr;
+use reqwest::header::DATE;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use url::Url;
+
+/// Default number of samples taken by `probe()`.
+pub const DEFAULT_PROBE_SAMPLES: usize = 5;
+
+/// `host:port` the probe connects to.
+fn node_key(url: &Url) -> eyre::Result<String> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| eyre::eyre!("URL has no host"))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| eyre::eyre!("URL has no port"))?;
+    Ok(format!("{}:{}", host, port))
+}
+
+/// Verifier for the timed handshake: it accepts any certificate (still
+/// checking handshake signatures), since the connection carries no data.
+#[derive(Debug)]
+struct TimingVerifier {
+    provider: Arc<CryptoProvider>,
+}
+
+impl TimingVerifier {
+    fn client_config() -> ClientConfig {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let verifier = Arc::new(Self {
+            provider: Arc::clone(&provider),
+        });
+        ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .expect("ring provider supports the default protocol versions")
+            .dangerous()
+            .with_custom_certificate_verifier(verifier)
+            .with_no_client_auth()
+    }
+}
+
+impl ServerCertVerifier for TimingVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// Timings of one probe sample, in milliseconds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sample {
+    pub connect_ms: Option<f64>,
+    pub tls_ms: Option<f64>,
+    pub rtt_ms: Option<f64>,
+    pub offset_ms: Option<f64>,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Time a fresh TCP connect and, for `https://`, the TLS handshake on it.
+/// The certificate is not checked: this measures timing.
+pub async fn handshake(url: &Url) -> eyre::Result<(f64, Option<f64>)> {
+    let key = node_key(url)?;
+    let started = Instant::now();
+    let stream = TcpStream::connect(&key)
+        .await
+        .wrap_err_with(|| format!("Failed to connect to {}", key))?;
+    let connect_ms = millis(started.elapsed());
+    if url.scheme() != "https" {
+        return Ok((connect_ms, None));
+    }
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| eyre::eyre!("URL has no host"))?
+        .to_string();
+    let server_name = ServerName::try_from(host).wrap_err("Invalid TLS server name")?;
+    let connector = TlsConnector::from(Arc::new(TimingVerifier::client_config()));
+    let started = Instant::now();
+    connector
+        .connect(server_name, stream)
+        .await
+        .wrap_err_with(|| format!("TLS handshake with {} failed", key))?;
+    Ok((connect_ms, Some(millis(started.elapsed()))))
+}
+
+/// Offset of the node's clock from ours (positive: node ahead), given its
+/// `Date` header and the local time halfway through the request.
+pub fn clock_offset_ms(date: &str, local_midpoint: SystemTime) -> Option<f64> {
+    let remote = DateTime::parse_from_rfc2822(date).ok()?.with_timezone(&Utc);
+    let local = DateTime::<Utc>::from(local_midpoint);
+    Some((remote - local).num_milliseconds() as f64)
+}
+
+/// Take one sample: a fresh handshake, then a timed health request.
+pub async fn sample(client: &reqwest::Client, url: &Url) -> Sample {
+    let mut sample = Sample::default();
+    match handshake(url).await {
+        Ok((connect_ms, tls_ms)) => {
+            sample.connect_ms = Some(connect_ms);
+            sample.tls_ms = tls_ms;
+        }
+        Err(e) => sample.error = Some(format!("{:#}", e)),
+    }
+
+    let health = match url.join("admin-api/health") {
+        Ok(health) => health,
+        Err(e) => {
+            sample.error = Some(format!("Invalid node URL: {}", e));
+            return sample;
+        }
+    };
+    let sent_at = SystemTime::now();
+    let started = Instant::now();
+    match client.get(health).send().await {
+        Ok(response) => {
+            let rtt = started.elapsed();
+            sample.rtt_ms = Some(millis(rtt));
+            sample.status = Some(response.status().as_u16());
+            sample.offset_ms = response
+                .headers()
+                .get(DATE)
+                .and_then(|date| date.to_str().ok())
+                .and_then(|date| clock_offset_ms(date, sent_at + rtt / 2));
+        }
+        Err(e) => sample.error = Some(format!("Health request failed: {}", e)),
+    }
+    sample
+}
+
+/// `{min, median, max, mean}` of `values`, or `null` when there are none.
+pub fn summarize(values: impl IntoIterator<Item = f64>) -> Value {
+    let mut values: Vec<f64> = values.into_iter().collect();
+    if values.is_empty() {
+        return Value::Null;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    let median = if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    };
+    json!({
+        "min": values[0],
+        "median": median,
+        "max": values[values.len() - 1],
+        "mean": values.iter().sum::<f64>() / values.len() as f64,
+    })
+}
+
+/// Probe report for `samples` taken against `node`.
+pub fn report(node: &Url, samples: &[Sample]) -> Value {
+    let connect = summarize(samples.iter().filter_map(|s| s.connect_ms));
+    let rtt = summarize(samples.iter().filter_map(|s| s.rtt_ms));
+    let server_ms = match (rtt["median"].as_f64(), connect["median"].as_f64()) {
+        (Some(rtt), Some(connect)) => Value::from((rtt - connect).max(0.0)),
+        _ => Value::Null,
+    };
+    json!({
+        "node": node.to_string(),
+        "samples": samples.len(),
+        "failed": samples.iter().filter(|s| s.error.is_some()).count(),
+        "connectMs": connect,
+        "tlsHandshakeMs": summarize(samples.iter().filter_map(|s| s.tls_ms)),
+        "rttMs": rtt,
+        "serverMs": server_ms,
+        "clockOffsetMs": summarize(samples.iter().filter_map(|s| s.offset_ms)),
+        "status": samples.iter().rev().find_map(|s| s.status),
+        "errors": samples.iter().filter_map(|s| s.error.clone()).collect::<Vec<_>>(),
+    })
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Summaries report the median of odd and even sample counts.
+    #[test]
+    fn test_summarize() {
+        assert_eq!(summarize([]), Value::Null);
+        let odd = summarize([3.0, 1.0, 2.0]);
+        assert_eq!(odd["median"], 2.0);
+        assert_eq!(odd["min"], 1.0);
+        assert_eq!(summarize([1.0, 2.0, 3.0, 4.0])["median"], 2.5);
+    }
+
+    /// The offset is the node's `Date` minus the local midpoint.
+    #[test]
+    fn test_clock_offset() {
+        let midpoint = SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_478);
+        let offset = clock_offset_ms("Wed, 21 Oct 2015 07:28:00 GMT", midpoint);
+        assert_eq!(offset, Some(2_000.0));
+        assert_eq!(clock_offset_ms("yesterday", midpoint), None);
+    }
+
+    /// Node keys always carry an explicit port.
+    #[test]
+    fn test_node_key() {
+        let key = |s: &str| node_key(&Url::parse(s).unwrap()).unwrap();
+        assert_eq!(key("https://node.local"), "node.local:443");
+        assert_eq!(key("https://node.local:8443/api"), "node.local:8443");
+    }
+
+    /// Node time is the request round trip beyond the connect time.
+    #[test]
+    fn test_report_estimates_server_time() {
+        let url = Url::parse("https://node.example").unwrap();
+        let sample = Sample {
+            connect_ms: Some(20.0),
+            tls_ms: Some(30.0),
+            rtt_ms: Some(120.0),
+            offset_ms: None,
+            status: Some(200),
+            error: None,
+        };
+        let report = report(&url, &[sample.clone(), sample]);
+        assert_eq!(report["serverMs"], 100.0);
+        assert_eq!(report["status"], 200);
+        assert_eq!(report["clockOffsetMs"], Value::Null);
+        assert_eq!(report["failed"], 0);
+    }
+}
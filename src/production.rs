@@ -0,0 +1,106 @@
+//! Guarded production mode.
+//!
+//! A client created with `production=True` refuses operations that are
+//! convenient in development but dangerous in a deployed service, raising
+//! `DangerousOperationError` before anything is sent:
+//!
+//! - bulk deletes: `delete_namespace` and `delete_group` (which take every
+//!   context and subgroup below them along) and `remove_group_members`;
+//! - development application installs: `install_dev_application`.
+//!
+//! Each guarded call takes `allow_dangerous=True` to go ahead anyway, so
+//! the foot-gun becomes a deliberate, greppable decision at the call site;
+//! a warning is logged when a production client is told to.
+
+use pyo3::create_exception;
+use pyo3::prelude::*;
+
+use crate::error::CalimeroError;
+use crate::log_bridge;
+
+create_exception!(
+    calimero_client_py,
+    DangerousOperationError,
+    CalimeroError,
+    "A production client refused a dangerous operation; pass allow_dangerous=True to run it."
+);
+
+/// Kinds of operations a production client refuses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dangerous {
+    BulkDelete,
+    DevInstall,
+}
+
+impl Dangerous {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Dangerous::BulkDelete => "a bulk delete",
+            Dangerous::DevInstall => "a development application install",
+        }
+    }
+}
+
+/// Why `operation` is refused, or `None` when it may run.
+pub fn refusal(production: bool, operation: &str, kind: Dangerous, allow: bool) -> Option<String> {
+    (production && !allow).then(|| {
+        format!(
+            "{} is {}, which production clients refuse; pass allow_dangerous=True to run it anyway",
+            operation,
+            kind.describe()
+        )
+    })
+}
+
+/// Raise `DangerousOperationError` if a production client may not run
+/// `operation`; warn when one runs it because the caller allowed it.
+pub fn guard(
+    production: bool,
+    operation: &str,
+    kind: Dangerous,
+    allow_dangerous: bool,
+) -> PyResult<()> {
+    if let Some(message) = refusal(production, operation, kind, allow_dangerous) {
+        return Err(DangerousOperationError::new_err(message));
+    }
+    if production && allow_dangerous {
+        Python::with_gil(|py| {
+            log_bridge::warning(
+                py,
+                &format!(
+                    "production client running {} ({}) with allow_dangerous=True",
+                    operation,
+                    kind.describe()
+                ),
+            )
+        });
+    }
+    Ok(())
+}
+
+pub fn register(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add(
+        "DangerousOperationError",
+        py.get_type_bound::<DangerousOperationError>(),
+    )?;
+    Ok(())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Only production clients refuse, and only without the override.
+    #[test]
+    fn test_refusal() {
+        assert!(refusal(false, "delete_group", Dangerous::BulkDelete, false).is_none());
+        assert!(refusal(true, "delete_group", Dangerous::BulkDelete, true).is_none());
+        let message = refusal(true, "delete_group", Dangerous::BulkDelete, false).unwrap();
+        assert!(message.starts_with("delete_group is a bulk delete"));
+        assert!(message.contains("allow_dangerous=True"));
+    }
+}
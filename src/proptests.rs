@@ -0,0 +1,97 @@
+//! Property-based tests for the serialization layer.
+//!
+//! Enabled with `cargo test --features fuzz`. These exercise the paths that
+//! turn node responses and user input into values (JSON and msgpack wire
+//! encoding, ID parsing, token cache files) with arbitrary input, asserting
+//! that they either round-trip or fail with an error — never panic.
+
+use std::str::FromStr;
+
+use calimero_client::JwtToken;
+use calimero_primitives::context::ContextId;
+use calimero_primitives::hash::Hash;
+use calimero_primitives::identity::PublicKey;
+use proptest::prelude::*;
+use serde_json::Value;
+
+use crate::wire;
+
+/// Arbitrary JSON documents, including integers outside the i64 range.
+fn json_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::from),
+        any::<u64>().prop_map(Value::from),
+        any::<f64>()
+            .prop_filter("JSON numbers are finite", |f| f.is_finite())
+            .prop_map(Value::from),
+        ".*".prop_map(Value::String),
+    ];
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(Value::Array),
+            prop::collection::btree_map(".*", inner, 0..8)
+                .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+proptest! {
+    /// JSON encoding is lossless.
+    #[test]
+    fn json_roundtrip(value in json_value()) {
+        let bytes = serde_json::to_vec(&value).unwrap();
+        prop_assert_eq!(serde_json::from_slice::<Value>(&bytes).unwrap(), value);
+    }
+
+    /// The msgpack request encoding is lossless.
+    #[test]
+    fn msgpack_roundtrip(value in json_value()) {
+        let encoded = wire::encode(&value).unwrap();
+        prop_assert_eq!(wire::decode_gzipped(&encoded).unwrap(), value);
+    }
+
+    /// Malformed msgpack bodies are rejected, not panicked on.
+    #[test]
+    fn msgpack_decode_never_panics(body in prop::collection::vec(any::<u8>(), 0..256)) {
+        let _ = wire::decode(&body);
+        let _ = wire::decode_gzipped(&body);
+    }
+
+    /// ID parsers reject garbage with an error.
+    #[test]
+    fn id_parsing_never_panics(input in ".*") {
+        let _ = ContextId::from_str(&input);
+        let _ = PublicKey::from_str(&input);
+        let _ = Hash::from_str(&input);
+    }
+
+    /// Hashes survive their string form.
+    #[test]
+    fn hash_string_roundtrip(bytes in any::<[u8; 32]>()) {
+        let hash = Hash::from(bytes);
+        prop_assert_eq!(Hash::from_str(&hash.to_string()).unwrap(), hash);
+    }
+
+    /// Token cache files: arbitrary content errors cleanly ...
+    #[test]
+    fn token_file_parsing_never_panics(input in ".*") {
+        let _ = serde_json::from_str::<JwtToken>(&input);
+    }
+
+    /// ... and whatever the storage writes reads back unchanged.
+    #[test]
+    fn token_file_roundtrip(
+        access_token in ".*",
+        refresh_token in proptest::option::of(".*"),
+        expires_at in proptest::option::of(any::<i64>()),
+    ) {
+        let token = JwtToken { access_token, refresh_token, expires_at };
+        let json = serde_json::to_string_pretty(&token).unwrap();
+        let parsed: JwtToken = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(parsed.access_token, token.access_token);
+        prop_assert_eq!(parsed.refresh_token, token.refresh_token);
+        prop_assert_eq!(parsed.expires_at, token.expires_at);
+    }
+}
@@ -0,0 +1,492 @@
+//! Composable queries over list endpoints: `client.contexts()`,
+//! `client.applications()` and `client.blobs()`.
+//!
+//! Parameter dicts built by hand are silently ignored when a name is wrong.
+//! A [`ListQuery`] only accepts the fields its resource has, so a typo in
+//! `filter(aplication=...)` or `sort("created")` raises at once, and it
+//! compiles into one canonical set of query parameters
+//! ([`ListQuery::params`]). The admin API's list endpoints do not take
+//! parameters yet, so the query runs over the (cached) list response:
+//! filters, then a stable sort, then `offset` and `limit`. Iterating a
+//! query, or its `pages()`, converts the results a page at a time
+//! ([`crate::pagination`]).
+
+use std::cmp::Ordering;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::Value;
+
+use crate::client::PyClient;
+use crate::context::PyContext;
+use crate::pagination::{PyPaginator, DEFAULT_PAGE_SIZE};
+use crate::utils::{json_to_python, python_to_json};
+
+/// Listable resource and the fields its records can be queried by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Contexts,
+    Applications,
+    Blobs,
+}
+
+impl Resource {
+    /// Query fields with the record keys they read, first match wins.
+    fn fields(&self) -> &'static [(&'static str, &'static [&'static str])] {
+        match self {
+            Self::Contexts => &[
+                ("id", &["id", "contextId"]),
+                ("application", &["applicationId", "application_id"]),
+                ("root_hash", &["rootHash", "root_hash"]),
+                ("group", &["groupId", "group_id"]),
+            ],
+            Self::Applications => &[
+                ("id", &["id"]),
+                ("size", &["size"]),
+                ("source", &["source"]),
+            ],
+            Self::Blobs => &[("id", &["blobId", "blob_id", "id"]), ("size", &["size"])],
+        }
+    }
+
+    /// Key of the record array in the list response's `data`.
+    pub fn list_key(&self) -> &'static str {
+        match self {
+            Self::Contexts => "contexts",
+            Self::Applications => "apps",
+            Self::Blobs => "blobs",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Contexts => "contexts",
+            Self::Applications => "applications",
+            Self::Blobs => "blobs",
+        }
+    }
+
+    /// Client operation that lists the resource, for errors and traces.
+    pub fn operation(&self) -> &'static str {
+        match self {
+            Self::Contexts => "list_contexts",
+            Self::Applications => "list_applications",
+            Self::Blobs => "list_blobs",
+        }
+    }
+
+    /// Canonical name of `field`; `application_id` is accepted for
+    /// `application` and likewise for `group`.
+    fn field(&self, field: &str) -> Result<&'static str, String> {
+        let base = field.strip_suffix("_id");
+        self.fields()
+            .iter()
+            .map(|(name, _)| *name)
+            .find(|name| *name == field || Some(*name) == base)
+            .ok_or_else(|| {
+                let known: Vec<&str> = self.fields().iter().map(|(name, _)| *name).collect();
+                format!(
+                    "Unknown {} field '{}'; expected one of: {}",
+                    self.name(),
+                    field,
+                    known.join(", ")
+                )
+            })
+    }
+
+    fn value<'a>(&self, record: &'a Value, field: &str) -> Option<&'a Value> {
+        let (_, keys) = self.fields().iter().find(|(name, _)| *name == field)?;
+        keys.iter()
+            .find_map(|key| record.get(*key))
+            .filter(|value| !value.is_null())
+    }
+}
+
+/// Filters, sort and window of a list call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListQuery {
+    pub resource: Resource,
+    /// Field and accepted values; a record must match every filter.
+    pub filters: Vec<(&'static str, Vec<Value>)>,
+    /// Field and whether the order is descending.
+    pub sort: Option<(&'static str, bool)>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+impl ListQuery {
+    pub fn new(resource: Resource) -> Self {
+        Self {
+            resource,
+            filters: Vec::new(),
+            sort: None,
+            limit: None,
+            offset: 0,
+        }
+    }
+
+    /// Add a filter; a list `value` matches any of its items.
+    pub fn filter(mut self, field: &str, value: Value) -> Result<Self, String> {
+        let field = self.resource.field(field)?;
+        let values = match value {
+            Value::Array(values) => values,
+            value => vec![value],
+        };
+        self.filters.push((field, values));
+        Ok(self)
+    }
+
+    /// Sort by `field`, descending with a leading `-`.
+    pub fn sort(mut self, field: &str) -> Result<Self, String> {
+        let (field, descending) = match field.strip_prefix('-') {
+            Some(field) => (field, true),
+            None => (field, false),
+        };
+        self.sort = Some((self.resource.field(field)?, descending));
+        Ok(self)
+    }
+
+    /// Query parameters the query compiles to: `<field>_id` for id filters
+    /// (comma-separated values), `sort`, `order`, `limit`, `offset`.
+    pub fn params(&self) -> Vec<(String, String)> {
+        let mut params: Vec<(String, String)> = self
+            .filters
+            .iter()
+            .map(|(field, values)| {
+                let name = match *field {
+                    "application" | "group" => format!("{}_id", field),
+                    field => field.to_string(),
+                };
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|value| match value {
+                        Value::String(text) => text.clone(),
+                        value => value.to_string(),
+                    })
+                    .collect();
+                (name, values.join(","))
+            })
+            .collect();
+        if let Some((field, descending)) = self.sort {
+            params.push(("sort".to_string(), field.to_string()));
+            let order = if descending { "desc" } else { "asc" };
+            params.push(("order".to_string(), order.to_string()));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit".to_string(), limit.to_string()));
+        }
+        if self.offset > 0 {
+            params.push(("offset".to_string(), self.offset.to_string()));
+        }
+        params
+    }
+
+    /// Run the query over `records`.
+    pub fn apply(&self, mut records: Vec<Value>) -> Vec<Value> {
+        records.retain(|record| {
+            self.filters.iter().all(|(field, values)| {
+                self.resource
+                    .value(record, field)
+                    .is_some_and(|value| values.contains(value))
+            })
+        });
+        if let Some((field, descending)) = self.sort {
+            records.sort_by(|a, b| {
+                match (self.resource.value(a, field), self.resource.value(b, field)) {
+                    (Some(a), Some(b)) if descending => compare(b, a),
+                    (Some(a), Some(b)) => compare(a, b),
+                    // Records without the field go last either way.
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            });
+        }
+        records
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+/// Numbers by value, everything else by its text.
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => match (a.as_str(), b.as_str()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            _ => a.to_string().cmp(&b.to_string()),
+        },
+    }
+}
+
+/// Records of a list response (`{data: {<key>: [...]}}` or a bare array).
+pub fn records(response: &Value, resource: Resource) -> Vec<Value> {
+    let data = response.get("data").unwrap_or(response);
+    data.get(resource.list_key())
+        .or(Some(data))
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn value_error(message: String) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(message)
+}
+
+/// Chainable query over a client's list endpoint; each step returns a new
+/// query and nothing is fetched until the results are asked for.
+#[pyclass(name = "Query", frozen)]
+pub struct PyQuery {
+    client: Py<PyClient>,
+    query: ListQuery,
+}
+
+impl PyQuery {
+    pub fn new(client: Py<PyClient>, resource: Resource) -> Self {
+        Self {
+            client,
+            query: ListQuery::new(resource),
+        }
+    }
+
+    fn with(&self, py: Python<'_>, query: ListQuery) -> Self {
+        Self {
+            client: self.client.clone_ref(py),
+            query,
+        }
+    }
+
+    fn run(&self, py: Python<'_>, refresh: bool) -> PyResult<Vec<Value>> {
+        let response = self
+            .client
+            .borrow(py)
+            .list_response(self.query.resource, refresh)?;
+        Ok(self.query.apply(records(&response, self.query.resource)))
+    }
+}
+
+#[pymethods]
+impl PyQuery {
+    /// Keep records whose fields equal the given values (a list matches any
+    /// of its items), e.g. `filter(application=app_id)`. Unknown fields
+    /// raise `ValueError`.
+    #[pyo3(signature = (**fields))]
+    pub fn filter(&self, py: Python<'_>, fields: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut query = self.query.clone();
+        for (field, value) in fields.into_iter().flat_map(|fields| fields.iter()) {
+            let field: String = field.extract()?;
+            query = query
+                .filter(&field, python_to_json(&value)?)
+                .map_err(value_error)?;
+        }
+        Ok(self.with(py, query))
+    }
+
+    /// Order by `field`; `"-field"` sorts descending. Records without the
+    /// field come last.
+    pub fn sort(&self, py: Python<'_>, field: &str) -> PyResult<Self> {
+        let query = self.query.clone().sort(field).map_err(value_error)?;
+        Ok(self.with(py, query))
+    }
+
+    pub fn limit(&self, py: Python<'_>, limit: usize) -> Self {
+        let mut query = self.query.clone();
+        query.limit = Some(limit);
+        self.with(py, query)
+    }
+
+    pub fn offset(&self, py: Python<'_>, offset: usize) -> Self {
+        let mut query = self.query.clone();
+        query.offset = offset;
+        self.with(py, query)
+    }
+
+    /// Query parameters the query compiles to, as a dict of strings.
+    pub fn params<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let params = PyDict::new_bound(py);
+        for (name, value) in self.query.params() {
+            params.set_item(name, value)?;
+        }
+        Ok(params)
+    }
+
+    /// Run the query: the matching records as dicts, or `Context` objects
+    /// with `typed=True` (contexts only). `refresh=True` skips the
+    /// metadata cache.
+    #[pyo3(signature = (refresh=false, typed=false))]
+    pub fn fetch(&self, py: Python<'_>, refresh: bool, typed: bool) -> PyResult<PyObject> {
+        if typed && self.query.resource != Resource::Contexts {
+            return Err(value_error(
+                "typed=True is only available for contexts".to_string(),
+            ));
+        }
+        let records = self.run(py, refresh)?;
+        if typed {
+            let contexts = PyContext::list_from_response(&Value::Array(records))
+                .into_iter()
+                .map(|context| Py::new(py, context))
+                .collect::<PyResult<Vec<_>>>()?;
+            return Ok(pyo3::types::PyList::new_bound(py, contexts)
+                .into_any()
+                .unbind());
+        }
+        Ok(json_to_python(py, &Value::Array(records)))
+    }
+
+    /// First matching record, or `None`.
+    pub fn first(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let query = ListQuery {
+            limit: Some(1),
+            ..self.query.clone()
+        };
+        let records = self.with(py, query).run(py, false)?;
+        Ok(records
+            .first()
+            .map(|record| json_to_python(py, record))
+            .unwrap_or_else(|| py.None()))
+    }
+
+    /// Number of matching records (within `offset`/`limit`).
+    pub fn count(&self, py: Python<'_>) -> PyResult<usize> {
+        Ok(self.run(py, false)?.len())
+    }
+
+    /// Pages of up to `page_size` matching records (lists of dicts, or of
+    /// `Context` with `typed=True`), converted as they are consumed; works
+    /// with `for` and `async for`. `cursor` resumes where another
+    /// paginator's `cursor` left off.
+    #[pyo3(signature = (page_size=DEFAULT_PAGE_SIZE, cursor=None, typed=false))]
+    pub fn pages(
+        &self,
+        py: Python<'_>,
+        page_size: usize,
+        cursor: Option<&str>,
+        typed: bool,
+    ) -> PyResult<PyPaginator> {
+        PyPaginator::new(
+            self.client.clone_ref(py),
+            self.query.clone(),
+            page_size,
+            cursor,
+            true,
+            typed,
+        )
+    }
+
+    /// Matching records one at a time, fetched in pages of `page_size`;
+    /// `for record in query` is `iter()` with the defaults.
+    #[pyo3(signature = (page_size=DEFAULT_PAGE_SIZE, cursor=None, typed=false))]
+    pub fn iter(
+        &self,
+        py: Python<'_>,
+        page_size: usize,
+        cursor: Option<&str>,
+        typed: bool,
+    ) -> PyResult<PyPaginator> {
+        PyPaginator::new(
+            self.client.clone_ref(py),
+            self.query.clone(),
+            page_size,
+            cursor,
+            false,
+            typed,
+        )
+    }
+
+    fn __iter__(&self, py: Python<'_>) -> PyResult<PyPaginator> {
+        self.iter(py, DEFAULT_PAGE_SIZE, None, false)
+    }
+
+    fn __aiter__(&self, py: Python<'_>) -> PyResult<PyPaginator> {
+        self.iter(py, DEFAULT_PAGE_SIZE, None, false)
+    }
+
+    fn __repr__(&self) -> String {
+        let params: Vec<String> = self
+            .query
+            .params()
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        format!(
+            "Query({}, {})",
+            self.query.resource.name(),
+            params.join("&")
+        )
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn contexts() -> Vec<Value> {
+        vec![
+            json!({"id": "c1", "applicationId": "app-a", "rootHash": "h3"}),
+            json!({"id": "c2", "applicationId": "app-b", "rootHash": "h1"}),
+            json!({"id": "c3", "applicationId": "app-a"}),
+            json!({"id": "c4", "applicationId": "app-a", "rootHash": "h2"}),
+        ]
+    }
+
+    fn ids(records: &[Value]) -> Vec<&str> {
+        records.iter().map(|r| r["id"].as_str().unwrap()).collect()
+    }
+
+    /// Filters, sort (missing values last) and window apply in order.
+    #[test]
+    fn test_apply() {
+        let query = ListQuery::new(Resource::Contexts)
+            .filter("application_id", json!("app-a"))
+            .unwrap()
+            .sort("-root_hash")
+            .unwrap();
+        assert_eq!(ids(&query.apply(contexts())), ["c1", "c4", "c3"]);
+
+        let windowed = ListQuery {
+            limit: Some(1),
+            offset: 1,
+            ..query
+        };
+        assert_eq!(ids(&windowed.apply(contexts())), ["c4"]);
+
+        let any = ListQuery::new(Resource::Contexts)
+            .filter("id", json!(["c2", "c3"]))
+            .unwrap();
+        assert_eq!(ids(&any.apply(contexts())), ["c2", "c3"]);
+    }
+
+    /// Unknown fields are refused; known ones compile to parameters.
+    #[test]
+    fn test_fields_and_params() {
+        let query = ListQuery::new(Resource::Contexts);
+        assert!(query.clone().filter("aplication", json!("x")).is_err());
+        assert!(query.clone().sort("created_at").is_err());
+        assert!(ListQuery::new(Resource::Applications)
+            .filter("application", json!("x"))
+            .is_err());
+
+        let query = ListQuery {
+            limit: Some(50),
+            ..query.filter("application", json!("app-a")).unwrap()
+        }
+        .sort("root_hash")
+        .unwrap();
+        assert_eq!(
+            query.params(),
+            [
+                ("application_id".to_string(), "app-a".to_string()),
+                ("sort".to_string(), "root_hash".to_string()),
+                ("order".to_string(), "asc".to_string()),
+                ("limit".to_string(), "50".to_string()),
+            ]
+        );
+    }
+}
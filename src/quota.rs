@@ -0,0 +1,210 @@
+//! Rate-limit and quota headers reported by nodes, behind
+//! `Client.quota_status()`.
+//!
+//! Gateways in front of nodes announce how much of a quota is left, either
+//! with the IETF `RateLimit-*` fields (or their combined `RateLimit` form)
+//! or the older `X-RateLimit-*` ones. Batch jobs that read them can slow
+//! down before requests start failing with `429`. Every response the binding
+//! reads itself passes through [`observe`], which keeps the latest reading
+//! per node origin process-wide; responses handled inside the typed client
+//! are not visible here.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use url::Url;
+
+use crate::pool::origin;
+
+/// Reset values above this are Unix timestamps rather than delays.
+const EPOCH_THRESHOLD: i64 = 1_000_000_000;
+
+/// One reading of a node's quota.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quota {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Unix time (seconds) at which the quota resets.
+    pub reset_at: Option<i64>,
+    /// Unix time (seconds) of the response that reported it.
+    pub observed_at: i64,
+}
+
+impl Quota {
+    /// Quota from `headers` at `now`, if they report any.
+    pub fn from_headers(headers: &HeaderMap, now: i64) -> Option<Self> {
+        let field = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+        };
+        let number = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| field(*name).and_then(first_number))
+        };
+        let combined = field("ratelimit").map(parse_combined).unwrap_or_default();
+
+        let limit = number(&["ratelimit-limit", "x-ratelimit-limit"])
+            .or(combined.0)
+            .map(|n| n as u64);
+        let remaining = number(&["ratelimit-remaining", "x-ratelimit-remaining"])
+            .or(combined.1)
+            .map(|n| n as u64);
+        let reset = number(&["ratelimit-reset", "x-ratelimit-reset"])
+            .or(combined.2)
+            .or_else(|| match remaining {
+                Some(0) => field(RETRY_AFTER.as_str()).and_then(first_number),
+                _ => None,
+            });
+        if limit.is_none() && remaining.is_none() {
+            return None;
+        }
+        Some(Self {
+            limit,
+            remaining,
+            reset_at: reset.map(|reset| match reset >= EPOCH_THRESHOLD {
+                true => reset,
+                false => now + reset,
+            }),
+            observed_at: now,
+        })
+    }
+
+    /// Share of the quota still available, when the limit is known.
+    pub fn remaining_fraction(&self) -> Option<f64> {
+        match (self.limit, self.remaining) {
+            (Some(0), _) => Some(0.0),
+            (Some(limit), Some(remaining)) => Some(remaining.min(limit) as f64 / limit as f64),
+            _ => None,
+        }
+    }
+
+    /// Whether the reading still applies at `now` (its window has not reset).
+    pub fn is_current(&self, now: i64) -> bool {
+        self.reset_at.map_or(true, |reset_at| reset_at > now)
+    }
+
+    pub fn to_json(&self, now: i64) -> serde_json::Value {
+        serde_json::json!({
+            "limit": self.limit,
+            "remaining": self.remaining,
+            "resetAt": self.reset_at,
+            "resetIn": self.reset_at.map(|at| (at - now).max(0)),
+            "remainingFraction": self.remaining_fraction(),
+            "observedAt": self.observed_at,
+            "current": self.is_current(now),
+        })
+    }
+}
+
+/// Leading integer of a header value (`100`, `100;w=60`).
+fn first_number(value: &str) -> Option<i64> {
+    let digits: String = value
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+/// `limit`, `remaining` and `reset` of a combined `RateLimit` field
+/// (`limit=100, remaining=50, reset=30`).
+fn parse_combined(value: &str) -> (Option<i64>, Option<i64>, Option<i64>) {
+    let mut parsed = (None, None, None);
+    for item in value.split([',', ';']) {
+        let Some((key, number)) = item.split_once('=') else {
+            continue;
+        };
+        let number = first_number(number);
+        match key.trim().to_ascii_lowercase().as_str() {
+            "limit" => parsed.0 = number,
+            "remaining" | "r" => parsed.1 = number,
+            "reset" | "t" => parsed.2 = number,
+            _ => {}
+        }
+    }
+    parsed
+}
+
+fn registry() -> &'static Mutex<HashMap<String, Quota>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, Quota>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Record the quota `headers` of a response from `url` report, if any.
+pub fn observe(url: &Url, headers: &HeaderMap) {
+    let Some(quota) = Quota::from_headers(headers, chrono::Utc::now().timestamp()) else {
+        return;
+    };
+    if let Ok(mut registry) = registry().lock() {
+        registry.insert(origin(url), quota);
+    }
+}
+
+/// Latest quota reported by the node at `url`.
+pub fn latest(url: &Url) -> Option<Quota> {
+    registry().lock().ok()?.get(&origin(url)).cloned()
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    /// Separate, legacy and combined header forms all parse.
+    #[test]
+    fn test_quota_from_headers() {
+        let quota = Quota::from_headers(
+            &headers(&[
+                ("ratelimit-limit", "100"),
+                ("ratelimit-remaining", "25"),
+                ("ratelimit-reset", "30"),
+            ]),
+            1_000,
+        )
+        .unwrap();
+        assert_eq!(quota.limit, Some(100));
+        assert_eq!(quota.reset_at, Some(1_030));
+        assert_eq!(quota.remaining_fraction(), Some(0.25));
+
+        let legacy = Quota::from_headers(
+            &headers(&[
+                ("x-ratelimit-limit", "10;w=60"),
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset", "1700000000"),
+            ]),
+            1_000,
+        )
+        .unwrap();
+        assert_eq!(legacy.remaining, Some(0));
+        assert_eq!(legacy.reset_at, Some(1_700_000_000));
+
+        let combined = Quota::from_headers(
+            &headers(&[("ratelimit", "limit=50, remaining=5, reset=10")]),
+            1_000,
+        )
+        .unwrap();
+        assert_eq!((combined.limit, combined.remaining), (Some(50), Some(5)));
+        assert!(combined.is_current(1_009) && !combined.is_current(1_010));
+
+        assert_eq!(
+            Quota::from_headers(&headers(&[("retry-after", "5")]), 0),
+            None
+        );
+    }
+}
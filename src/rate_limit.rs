@@ -0,0 +1,308 @@
+//! Client-side rate limit per node and `Retry-After` pauses.
+//!
+//! Bulk scripts that fire requests as fast as the node answers them trip
+//! server-side throttling and then fail halfway through a long job. A client
+//! created with `rate_limit=` (requests per second) takes a token from a
+//! bucket holding up to `rate_burst` of them before each request; the bucket
+//! is shared by every client of the same node with the same settings in the
+//! process, like `max_connections_per_host`.
+//!
+//! Independently of any configured rate, a `429 Too Many Requests` carrying
+//! `Retry-After` pauses the node: requests to it wait until the announced
+//! time (at most [`MAX_PAUSE`]) instead of being refused again. Pauses are
+//! learnt from every response the binding reads itself; responses handled
+//! inside the typed client are not visible here.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde_json::json;
+use url::Url;
+
+use crate::pool::origin;
+
+/// Longest pause honored from a single `Retry-After`.
+pub const MAX_PAUSE: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct BucketState {
+    /// Tokens available; negative when waiters have reserved future ones.
+    tokens: f64,
+    refilled: Instant,
+    throttled: u64,
+    waited: Duration,
+}
+
+/// Token bucket refilling at `rate` tokens per second up to `burst`.
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    /// A full bucket; `rate` must be positive and `burst` at least 1.
+    pub fn new(rate: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            rate,
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                refilled: Instant::now(),
+                throttled: 0,
+                waited: Duration::ZERO,
+            }),
+        }
+    }
+
+    /// Take a token at `now`, returning how long the caller must wait for
+    /// it. Tokens are handed out in call order, so waiters do not race.
+    fn reserve(&self, now: Instant) -> Duration {
+        let Ok(mut state) = self.state.lock() else {
+            return Duration::ZERO;
+        };
+        let elapsed = now.saturating_duration_since(state.refilled).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.burst);
+        state.refilled = now;
+        state.tokens -= 1.0;
+        if state.tokens >= 0.0 {
+            return Duration::ZERO;
+        }
+        let wait = Duration::from_secs_f64(-state.tokens / self.rate);
+        state.throttled += 1;
+        state.waited += wait;
+        wait
+    }
+
+    /// Wait for a token.
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// `{rate, burst, available, throttled, waitedSecs}`.
+    pub fn stats(&self) -> serde_json::Value {
+        let Ok(state) = self.state.lock() else {
+            return serde_json::Value::Null;
+        };
+        let elapsed = state.refilled.elapsed().as_secs_f64();
+        json!({
+            "rate": self.rate,
+            "burst": self.burst as u32,
+            "available": (state.tokens + elapsed * self.rate).min(self.burst).max(0.0),
+            "throttled": state.throttled,
+            "waitedSecs": state.waited.as_secs_f64(),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Pause {
+    until: Option<Instant>,
+    pauses: u64,
+}
+
+#[derive(Default)]
+struct Registry {
+    buckets: HashMap<(String, u64, u32), Arc<TokenBucket>>,
+    pauses: HashMap<String, Pause>,
+}
+
+fn registry() -> &'static Mutex<Registry> {
+    static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(Mutex::default)
+}
+
+/// Process-wide bucket for `rate` requests per second (bursts of `burst`)
+/// to `url`'s node; clients passing the same settings for a node share it.
+pub fn bucket(url: &Url, rate: f64, burst: u32) -> Arc<TokenBucket> {
+    let key = (origin(url), rate.to_bits(), burst.max(1));
+    let Ok(mut registry) = registry().lock() else {
+        return Arc::new(TokenBucket::new(rate, burst));
+    };
+    registry
+        .buckets
+        .entry(key)
+        .or_insert_with(|| Arc::new(TokenBucket::new(rate, burst)))
+        .clone()
+}
+
+/// Delay announced by a `Retry-After` header at `now`: delta seconds or an
+/// HTTP date.
+pub fn retry_after(headers: &HeaderMap, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let left = at.with_timezone(&chrono::Utc) - now;
+    Some(left.to_std().unwrap_or_default())
+}
+
+/// Pause `url`'s node when `status` and `headers` are a 429 announcing
+/// when to come back.
+pub fn observe(url: &Url, status: StatusCode, headers: &HeaderMap) {
+    if status != StatusCode::TOO_MANY_REQUESTS {
+        return;
+    }
+    if let Some(delay) = retry_after(headers, chrono::Utc::now()) {
+        pause(url, delay);
+    }
+}
+
+/// Hold requests to `url`'s node for `delay` (capped at [`MAX_PAUSE`]),
+/// extending a pause already in place.
+pub fn pause(url: &Url, delay: Duration) {
+    let until = Instant::now() + delay.min(MAX_PAUSE);
+    if let Ok(mut registry) = registry().lock() {
+        let pause = registry.pauses.entry(origin(url)).or_default();
+        pause.until = Some(pause.until.map_or(until, |current| current.max(until)));
+        pause.pauses += 1;
+    }
+}
+
+/// Time left on `url`'s node pause, if one is in place.
+pub fn paused_for(url: &Url) -> Option<Duration> {
+    let registry = registry().lock().ok()?;
+    let until = registry.pauses.get(&origin(url))?.until?;
+    Some(until.saturating_duration_since(Instant::now())).filter(|left| !left.is_zero())
+}
+
+/// Wait out `url`'s node pause, including extensions made meanwhile.
+pub async fn wait_for_pause(url: &Url) {
+    while let Some(left) = paused_for(url) {
+        tokio::time::sleep(left).await;
+    }
+}
+
+/// What a client waits for before each request to its node.
+#[derive(Debug)]
+pub struct Throttle {
+    url: Url,
+    bucket: Option<Arc<TokenBucket>>,
+}
+
+impl Throttle {
+    /// Pauses of `url`'s node, plus `rate` requests per second in bursts of
+    /// `burst` when given.
+    pub fn new(url: &Url, rate: Option<(f64, u32)>) -> Self {
+        Self {
+            url: url.clone(),
+            bucket: rate.map(|(rate, burst)| bucket(url, rate, burst)),
+        }
+    }
+
+    /// Wait out a pause of the node, then for a token.
+    pub async fn wait(&self) {
+        wait_for_pause(&self.url).await;
+        if let Some(bucket) = &self.bucket {
+            bucket.acquire().await;
+        }
+    }
+
+    /// `{rate, burst, available, throttled, waitedSecs, pausedSecs, pauses}`;
+    /// the bucket fields are `None` without a rate limit.
+    pub fn stats(&self) -> serde_json::Value {
+        let mut stats = match &self.bucket {
+            Some(bucket) => bucket.stats(),
+            None => json!({
+                "rate": null,
+                "burst": null,
+                "available": null,
+                "throttled": 0,
+                "waitedSecs": 0.0,
+            }),
+        };
+        let pauses = registry()
+            .lock()
+            .ok()
+            .and_then(|registry| registry.pauses.get(&origin(&self.url)).map(|p| p.pauses))
+            .unwrap_or_default();
+        stats["pausedSecs"] = paused_for(&self.url)
+            .map(|left| left.as_secs_f64())
+            .unwrap_or_default()
+            .into();
+        stats["pauses"] = pauses.into();
+        stats
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A burst goes out at once; the next tokens arrive at the rate.
+    #[test]
+    fn test_bucket_paces_after_burst() {
+        let bucket = TokenBucket::new(10.0, 2);
+        let now = Instant::now();
+        assert_eq!(bucket.reserve(now), Duration::ZERO);
+        assert_eq!(bucket.reserve(now), Duration::ZERO);
+        let third = bucket.reserve(now);
+        let fourth = bucket.reserve(now);
+        assert!((third.as_secs_f64() - 0.1).abs() < 1e-9);
+        assert!((fourth.as_secs_f64() - 0.2).abs() < 1e-9);
+        assert_eq!(bucket.stats()["throttled"], 2);
+
+        // After a long idle stretch the bucket is full again, no fuller.
+        let later = now + Duration::from_secs(10);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert!(!bucket.reserve(later).is_zero());
+    }
+
+    /// `Retry-After` is read as seconds or as an HTTP date.
+    #[test]
+    fn test_retry_after_forms() {
+        let now = chrono::DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers, now), None);
+
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(retry_after(&headers, now), Some(Duration::from_secs(7)));
+
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:30 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers, now), Some(Duration::from_secs(30)));
+
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:27:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers, now), Some(Duration::ZERO));
+
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers, now), None);
+    }
+
+    /// Only a 429 pauses the node, for at most `MAX_PAUSE`.
+    #[test]
+    fn test_observe_pauses_on_429() {
+        let url = Url::parse("http://rate-limit-test.invalid:2428/jsonrpc").unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "3600".parse().unwrap());
+
+        observe(&url, StatusCode::SERVICE_UNAVAILABLE, &headers);
+        assert_eq!(paused_for(&url), None);
+
+        observe(&url, StatusCode::TOO_MANY_REQUESTS, &headers);
+        let left = paused_for(&url).unwrap();
+        assert!(left <= MAX_PAUSE && left > MAX_PAUSE - Duration::from_secs(5));
+        assert_eq!(Throttle::new(&url, None).stats()["pauses"], 1);
+    }
+}
@@ -0,0 +1,248 @@
+//! Read cache of `Client.view()` results.
+//!
+//! Dashboards poll the same views every second or so, and every poll is a
+//! JSON-RPC round trip the node answers with the result it gave the last
+//! time. With `read_cache_size` set, view results are kept for
+//! `read_cache_ttl` seconds and served from memory; once the cache is full
+//! the least recently used entry makes room. A client talks to one node,
+//! so its cache is keyed by (context, method, args): a [`ReadKey`] holds a
+//! SHA-256 of the arguments with object keys sorted, so argument dicts
+//! that only differ in key order share an entry.
+//!
+//! Entries are dropped by `Client.invalidate()`, and those of a context
+//! when `execute_function` on it succeeds. Writes made elsewhere (other
+//! clients, batches) are seen once the entries expire.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+/// Default time view results are served from the cache (seconds).
+pub const DEFAULT_READ_CACHE_TTL_SECS: f64 = 1.0;
+
+/// Cache key of a view call.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReadKey {
+    context_id: String,
+    method: String,
+    args: [u8; 32],
+}
+
+impl ReadKey {
+    /// Key of `method` on `context_id` with `args` (JSON text; arguments
+    /// that do not parse are hashed as given).
+    pub fn new(context_id: &str, method: &str, args: &str) -> Self {
+        let canonical = match serde_json::from_str::<Value>(args) {
+            Ok(value) => canonical(&value),
+            Err(_) => args.to_string(),
+        };
+        Self {
+            context_id: context_id.to_string(),
+            method: method.to_string(),
+            args: Sha256::digest(canonical.as_bytes()).into(),
+        }
+    }
+}
+
+/// `value` as JSON text with object keys sorted.
+fn canonical(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<&String, String> =
+                map.iter().map(|(k, v)| (k, canonical(v))).collect();
+            let fields: Vec<String> = sorted
+                .into_iter()
+                .map(|(k, v)| format!("{}:{}", Value::String(k.clone()), v))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical).collect();
+            format!("[{}]", items.join(","))
+        }
+        value => value.to_string(),
+    }
+}
+
+struct Entry {
+    stored_at: Instant,
+    /// Tick of the last use, the entry's position in `State::order`.
+    used: u64,
+    value: Value,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<ReadKey, Entry>,
+    /// Keys by last use, least recent first.
+    order: BTreeMap<u64, ReadKey>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl State {
+    fn remove(&mut self, key: &ReadKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.used);
+        }
+    }
+}
+
+/// LRU cache of view results with a TTL; a capacity of zero disables it.
+pub struct ReadCache {
+    capacity: usize,
+    ttl: Duration,
+    state: Mutex<State>,
+}
+
+impl ReadCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// The cached result for `key`, if stored within the TTL.
+    pub fn get(&self, key: &ReadKey) -> Option<Value> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let mut state = self.state.lock().ok()?;
+        let fresh = state
+            .entries
+            .get(key)
+            .map(|entry| entry.stored_at.elapsed() < self.ttl);
+        match fresh {
+            Some(true) => {}
+            Some(false) => {
+                state.remove(key);
+                state.misses += 1;
+                return None;
+            }
+            None => {
+                state.misses += 1;
+                return None;
+            }
+        }
+        state.tick += 1;
+        let tick = state.tick;
+        state.hits += 1;
+        let entry = state.entries.get_mut(key)?;
+        let previous = std::mem::replace(&mut entry.used, tick);
+        let value = entry.value.clone();
+        state.order.remove(&previous);
+        state.order.insert(tick, key.clone());
+        Some(value)
+    }
+
+    pub fn insert(&self, key: ReadKey, value: Value) {
+        if !self.is_enabled() {
+            return;
+        }
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.remove(&key);
+        while state.entries.len() >= self.capacity {
+            let Some((_, oldest)) = state.order.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+        state.tick += 1;
+        let used = state.tick;
+        state.order.insert(used, key.clone());
+        state.entries.insert(
+            key,
+            Entry {
+                stored_at: Instant::now(),
+                used,
+                value,
+            },
+        );
+    }
+
+    /// Drop the entries of `context_id` (all contexts when `None`) for
+    /// `method` (all methods when `None`); returns how many were dropped.
+    pub fn invalidate(&self, context_id: Option<&str>, method: Option<&str>) -> usize {
+        let Ok(mut state) = self.state.lock() else {
+            return 0;
+        };
+        let dropped: Vec<ReadKey> = state
+            .entries
+            .keys()
+            .filter(|key| context_id.is_none() || context_id == Some(key.context_id.as_str()))
+            .filter(|key| method.is_none() || method == Some(key.method.as_str()))
+            .cloned()
+            .collect();
+        for key in &dropped {
+            state.remove(key);
+        }
+        dropped.len()
+    }
+
+    /// `{entries, capacity, ttl, hits, misses}`
+    pub fn stats(&self) -> Value {
+        let (entries, hits, misses) = match self.state.lock() {
+            Ok(state) => (state.entries.len(), state.hits, state.misses),
+            Err(_) => (0, 0, 0),
+        };
+        json!({
+            "entries": entries,
+            "capacity": self.capacity,
+            "ttl": self.ttl.as_secs_f64(),
+            "hits": hits,
+            "misses": misses,
+        })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Argument key order does not matter; values do.
+    #[test]
+    fn test_key_is_canonical() {
+        let key = |args: &str| ReadKey::new("ctx", "get", args);
+        assert_eq!(key(r#"{"a": 1, "b": [2]}"#), key(r#"{"b":[2],"a":1}"#));
+        assert_ne!(key(r#"{"a": 1}"#), key(r#"{"a": 2}"#));
+        assert_ne!(key("{}"), ReadKey::new("ctx", "list", "{}"));
+    }
+
+    /// The least recently used entry goes first; expired ones are misses.
+    #[test]
+    fn test_lru_and_ttl() {
+        let cache = ReadCache::new(2, Duration::from_secs(60));
+        let key = |method: &str| ReadKey::new("ctx", method, "{}");
+        cache.insert(key("a"), json!(1));
+        cache.insert(key("b"), json!(2));
+        assert_eq!(cache.get(&key("a")), Some(json!(1)));
+        cache.insert(key("c"), json!(3));
+        assert_eq!(cache.get(&key("b")), None);
+        assert_eq!(cache.get(&key("a")), Some(json!(1)));
+        assert_eq!(cache.invalidate(Some("ctx"), Some("c")), 1);
+        assert_eq!(cache.stats()["entries"], 1);
+
+        let expired = ReadCache::new(2, Duration::ZERO);
+        expired.insert(key("a"), json!(1));
+        assert_eq!(expired.get(&key("a")), None);
+        assert!(ReadCache::new(0, Duration::from_secs(1))
+            .get(&key("a"))
+            .is_none());
+    }
+}
@@ -0,0 +1,198 @@
+//! Re-authentication after the node rejects a token mid-session.
+//!
+//! Proactive refresh ([`crate::token_lifecycle`]) covers tokens running out
+//! on schedule, not tokens the node stops accepting early: a revoked or
+//! rotated session, an auth service that restarted with new keys, clock
+//! skew. When a call fails with a 401 the session is recovered once, by
+//! exchanging the refresh token or, when that fails, by a username/password
+//! login from `CALIMERO_USERNAME`/`CALIMERO_PASSWORD`, and the call is
+//! replayed. A 403 is a refused permission, which a new token does not fix,
+//! so it is raised as is.
+//!
+//! Recovery is single-flight per client. Each attempt notes the session
+//! generation before it is sent; the first call to take the lock after a 401
+//! recovers and bumps the generation, and calls queued behind it see the new
+//! generation and replay without asking the auth service again, so fifty
+//! concurrent calls holding an expired token cost one refresh. A failed
+//! recovery is likewise not retried by the calls queued behind it.
+//!
+//! Calls are replayed where they are sent through
+//! [`crate::retry::with_retries`], which can rebuild their request; the
+//! session is found through a task-local set around each call, as the
+//! request hooks are.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use calimero_client::traits::ClientStorage;
+use calimero_client::JwtToken;
+use url::Url;
+
+use crate::login::{self, LoginCredentials};
+use crate::metrics::Metrics;
+use crate::storage::{MeroboxFileStorage, WipeOnDrop};
+use crate::token_lifecycle;
+use crate::wallet_login;
+
+tokio::task_local! {
+    static ACTIVE: Arc<Reauth>;
+}
+
+/// How long after a failed recovery 401s are raised without trying again.
+const FAILED_RECOVERY_HOLD: Duration = Duration::from_secs(5);
+
+/// Whether `message` reports the node rejecting the token (401), as opposed
+/// to refusing the caller permission (403).
+pub fn is_rejected_token(message: &str) -> bool {
+    crate::auth::auth_failure(message) == Some(crate::auth::AuthFailure::Rejected)
+}
+
+/// Session recovery of one client's node.
+pub struct Reauth {
+    http: reqwest::Client,
+    api_url: Url,
+    storage: MeroboxFileStorage,
+    node_name: String,
+    max_response_bytes: Option<usize>,
+    metrics: Arc<Metrics>,
+    generation: AtomicU64,
+    /// Generation whose recovery failed, and when.
+    failed: Mutex<Option<(u64, Instant)>>,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl Reauth {
+    pub fn new(
+        http: reqwest::Client,
+        api_url: Url,
+        storage: MeroboxFileStorage,
+        node_name: String,
+        max_response_bytes: Option<usize>,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        Self {
+            http,
+            api_url,
+            storage,
+            node_name,
+            max_response_bytes,
+            metrics,
+            generation: AtomicU64::new(0),
+            failed: Mutex::new(None),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Current session generation, to note before sending a request.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Recover the session after a request sent at generation `seen` was
+    /// rejected. Returns whether the request should be replayed: the session
+    /// was recovered, by this call or by another one in the meantime.
+    pub async fn recover(&self, seen: u64) -> bool {
+        let _recovering = self.lock.lock().await;
+        if self.generation() != seen {
+            return true;
+        }
+        if let Ok(failed) = self.failed.lock() {
+            if matches!(*failed, Some((generation, at))
+                if generation == seen && at.elapsed() < FAILED_RECOVERY_HOLD)
+            {
+                return false;
+            }
+        }
+
+        let recovered = match self.refresh().await {
+            Ok(tokens) => Some(tokens),
+            Err(_) => self.relogin().await.ok(),
+        };
+        self.metrics.record_token_refresh(recovered.is_some());
+        let Some(tokens) = recovered else {
+            if let Ok(mut failed) = self.failed.lock() {
+                *failed = Some((seen, Instant::now()));
+            }
+            return false;
+        };
+        if self
+            .storage
+            .save_tokens(&self.node_name, &tokens)
+            .await
+            .is_err()
+        {
+            return false;
+        }
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        true
+    }
+
+    /// Exchange the stored refresh token for a new pair.
+    async fn refresh(&self) -> eyre::Result<JwtToken> {
+        let Some(tokens) = self.storage.load_tokens(&self.node_name).await? else {
+            eyre::bail!("No tokens are stored for node '{}'", self.node_name);
+        };
+        let tokens = WipeOnDrop::new(tokens);
+        token_lifecycle::refresh(&self.http, &self.api_url, &tokens).await
+    }
+
+    /// Log in again with credentials from the environment.
+    async fn relogin(&self) -> eyre::Result<JwtToken> {
+        let credentials = LoginCredentials::default()
+            .with_env(|name| std::env::var(name).ok().filter(|value| !value.is_empty()));
+        let (Some(username), Some(password)) = (credentials.username, credentials.password) else {
+            eyre::bail!(
+                "Set {} and {} to log in again when a refresh fails",
+                login::USERNAME_ENV,
+                login::PASSWORD_ENV
+            );
+        };
+        let body =
+            wallet_login::password_login_body(&username, &password, chrono::Utc::now().timestamp());
+        wallet_login::exchange(
+            &self.http,
+            &self.api_url,
+            &self.storage,
+            &body,
+            self.max_response_bytes,
+        )
+        .await
+    }
+}
+
+/// Run `fut` with `reauth` recovering the session of the requests it sends.
+pub async fn scope<F: std::future::Future>(reauth: Option<Arc<Reauth>>, fut: F) -> F::Output {
+    match reauth {
+        Some(reauth) => ACTIVE.scope(reauth, fut).await,
+        None => fut.await,
+    }
+}
+
+/// Session recovery of the call being made, with its current generation.
+pub fn active() -> Option<(Arc<Reauth>, u64)> {
+    ACTIVE
+        .try_with(|reauth| (reauth.clone(), reauth.generation()))
+        .ok()
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 401s and expired tokens are recoverable; refused permissions are not.
+    #[test]
+    fn test_is_rejected_token() {
+        assert!(is_rejected_token("HTTP 401 Unauthorized"));
+        assert!(is_rejected_token("Authentication failed: token expired"));
+        assert!(!is_rejected_token(
+            "request failed with status 403 Forbidden"
+        ));
+        assert!(!is_rejected_token("Network error: connection refused"));
+        assert!(!is_rejected_token("Failed to read blob 0x4013ab9f"));
+    }
+}
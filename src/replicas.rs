@@ -0,0 +1,451 @@
+//! Latency-aware routing across replicas of the same contexts.
+//!
+//! Deployments that run the same contexts on nodes in several regions want
+//! reads served by the closest node while every write still lands on one
+//! primary. A [`PyReplicaSet`] wraps a primary `Client` and any number of
+//! replica clients and probes each node's health endpoint in the background.
+//! Reads (`read_only=True`, `read_client()`) go to the healthy node with the
+//! lowest smoothed round trip; writes always go to the primary.
+//!
+//! A node is unhealthy after [`UNHEALTHY_AFTER`] consecutive failed probes
+//! or read calls, and healthy again after one successful probe. The selected
+//! read node only changes when another one is clearly faster (see
+//! [`SWITCH_MARGIN`]), so near-equal nodes do not flap between probes.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+use tokio::runtime::Runtime;
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::client::PyClient;
+use crate::error::ErrorCode;
+use crate::utils::json_to_python;
+
+/// Default seconds between background probe rounds.
+pub const DEFAULT_PROBE_INTERVAL_SECS: f64 = 10.0;
+
+/// Probes slower than this count as failed.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Consecutive failures after which a node stops receiving reads.
+pub const UNHEALTHY_AFTER: u32 = 2;
+
+/// Another node takes over reads only below this share of the current
+/// node's round trip.
+pub const SWITCH_MARGIN: f64 = 0.8;
+
+/// Weight of the newest sample in the smoothed round trip.
+const RTT_SMOOTHING: f64 = 0.3;
+
+/// What the probes know about one node.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeHealth {
+    /// Smoothed health round trip; `None` before the first success.
+    pub rtt_ms: Option<f64>,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+impl NodeHealth {
+    pub fn healthy(&self) -> bool {
+        self.consecutive_failures < UNHEALTHY_AFTER
+    }
+
+    fn record(&mut self, outcome: Result<f64, String>) {
+        match outcome {
+            Ok(rtt_ms) => {
+                self.rtt_ms = Some(match self.rtt_ms {
+                    Some(previous) => previous + RTT_SMOOTHING * (rtt_ms - previous),
+                    None => rtt_ms,
+                });
+                self.consecutive_failures = 0;
+                self.last_error = None;
+            }
+            Err(error) => {
+                self.consecutive_failures += 1;
+                self.last_error = Some(error);
+            }
+        }
+    }
+}
+
+/// Health of every node (index 0 is the primary) and the current read node.
+#[derive(Debug)]
+pub struct Selector {
+    nodes: Mutex<Vec<NodeHealth>>,
+    current: AtomicUsize,
+}
+
+impl Selector {
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            nodes: Mutex::new(vec![NodeHealth::default(); nodes.max(1)]),
+            current: AtomicUsize::new(0),
+        }
+    }
+
+    /// Record a probe or read outcome for node `index`.
+    pub fn record(&self, index: usize, outcome: Result<f64, String>) {
+        if let Ok(mut nodes) = self.nodes.lock() {
+            if let Some(node) = nodes.get_mut(index) {
+                node.record(outcome);
+            }
+        }
+    }
+
+    /// Node reads go to: the current one while it stays healthy and no
+    /// other healthy node is clearly faster, else the fastest healthy one,
+    /// else the primary.
+    pub fn read_node(&self) -> usize {
+        let Ok(nodes) = self.nodes.lock() else {
+            return 0;
+        };
+        let fastest = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.healthy())
+            .filter_map(|(index, node)| Some((index, node.rtt_ms?)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let current = self.current.load(Ordering::Relaxed);
+        let selected = match (fastest, nodes.get(current)) {
+            (None, _) => 0,
+            (Some((_, best)), Some(node)) if node.healthy() => match node.rtt_ms {
+                Some(rtt) if best >= rtt * SWITCH_MARGIN => current,
+                _ => fastest.map_or(0, |(index, _)| index),
+            },
+            (Some((index, _)), _) => index,
+        };
+        self.current.store(selected, Ordering::Relaxed);
+        selected
+    }
+
+    pub fn snapshot(&self) -> Vec<NodeHealth> {
+        self.nodes
+            .lock()
+            .map(|nodes| nodes.to_vec())
+            .unwrap_or_default()
+    }
+}
+
+/// Time one health request against `url`'s node.
+async fn probe_node(client: &reqwest::Client, url: &Url) -> Result<f64, String> {
+    let health = url
+        .join("admin-api/health")
+        .map_err(|e| format!("Invalid node URL: {}", e))?;
+    let started = Instant::now();
+    let response = client
+        .get(health)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Health request failed: {}", e))?;
+    let rtt_ms = started.elapsed().as_secs_f64() * 1000.0;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Health request failed with status {}",
+            response.status().as_u16()
+        ));
+    }
+    Ok(rtt_ms)
+}
+
+/// Probe every node once, concurrently.
+async fn probe_all(selector: &Selector, targets: &[(reqwest::Client, Url)]) {
+    let outcomes =
+        futures_util::future::join_all(targets.iter().map(|(client, url)| probe_node(client, url)))
+            .await;
+    for (index, outcome) in outcomes.into_iter().enumerate() {
+        selector.record(index, outcome);
+    }
+}
+
+/// Whether a failed read means the node itself is unreachable.
+fn is_node_failure(py: Python<'_>, err: &PyErr) -> bool {
+    err.value_bound(py)
+        .getattr("code")
+        .and_then(|code| code.extract::<ErrorCode>())
+        .is_ok_and(|code| {
+            matches!(
+                code,
+                ErrorCode::Network | ErrorCode::Timeout | ErrorCode::Unavailable
+            )
+        })
+}
+
+/// A primary client plus replicas serving the same contexts.
+#[pyclass(name = "ReplicaSet")]
+pub struct PyReplicaSet {
+    /// Primary first, then the replicas in the order given.
+    clients: Vec<Py<PyClient>>,
+    targets: Arc<Vec<(reqwest::Client, Url)>>,
+    selector: Arc<Selector>,
+    probe_interval: Duration,
+    prober: Mutex<Option<JoinHandle<()>>>,
+    runtime: Arc<Runtime>,
+}
+
+impl PyReplicaSet {
+    fn client(&self, py: Python<'_>, index: usize) -> Py<PyClient> {
+        self.clients[index.min(self.clients.len() - 1)].clone_ref(py)
+    }
+}
+
+#[pymethods]
+impl PyReplicaSet {
+    /// Route reads across `primary` and `replicas` by latency, probing every
+    /// `probe_interval` seconds; writes go to `primary`.
+    #[new]
+    #[pyo3(signature = (primary, replicas, probe_interval=DEFAULT_PROBE_INTERVAL_SECS))]
+    pub fn new(
+        py: Python<'_>,
+        primary: Py<PyClient>,
+        replicas: Vec<Py<PyClient>>,
+        probe_interval: f64,
+    ) -> PyResult<Self> {
+        if !(probe_interval.is_finite() && probe_interval > 0.0) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "probe_interval must be a positive number of seconds",
+            ));
+        }
+        let clients: Vec<Py<PyClient>> = std::iter::once(primary).chain(replicas).collect();
+        let targets = Arc::new(
+            clients
+                .iter()
+                .map(|client| client.borrow(py).probe_target())
+                .collect::<Vec<_>>(),
+        );
+        let runtime = Arc::new(
+            Runtime::new()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        );
+        let selector = Arc::new(Selector::new(clients.len()));
+        let probe_interval = Duration::from_secs_f64(probe_interval);
+
+        let prober = {
+            let selector = selector.clone();
+            let targets = targets.clone();
+            runtime.spawn(async move {
+                let mut ticker = tokio::time::interval(probe_interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    ticker.tick().await;
+                    probe_all(&selector, &targets).await;
+                }
+            })
+        };
+
+        Ok(Self {
+            clients,
+            targets,
+            selector,
+            probe_interval,
+            prober: Mutex::new(Some(prober)),
+            runtime,
+        })
+    }
+
+    /// The client all writes go to
+    #[getter]
+    pub fn primary(&self, py: Python<'_>) -> Py<PyClient> {
+        self.client(py, 0)
+    }
+
+    #[getter]
+    pub fn replicas(&self, py: Python<'_>) -> Vec<Py<PyClient>> {
+        self.clients[1..].iter().map(|c| c.clone_ref(py)).collect()
+    }
+
+    #[getter]
+    pub fn probe_interval(&self) -> f64 {
+        self.probe_interval.as_secs_f64()
+    }
+
+    /// Client of the healthy node with the lowest latency (the primary
+    /// until probes have answered)
+    pub fn read_client(&self, py: Python<'_>) -> Py<PyClient> {
+        self.client(py, self.selector.read_node())
+    }
+
+    /// The primary client; same as `primary`
+    pub fn write_client(&self, py: Python<'_>) -> Py<PyClient> {
+        self.client(py, 0)
+    }
+
+    /// Execute a function, on the primary unless `read_only=True`.
+    ///
+    /// Reads go to the selected read node; when that node is unreachable
+    /// it is marked as failing and the read is retried on the primary.
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (context_id, method, args, read_only=false, track_root_hash=false, expected_root_hash=None, result_type=None))]
+    pub fn execute_function(
+        &self,
+        py: Python<'_>,
+        context_id: &str,
+        method: &str,
+        args: &str,
+        read_only: bool,
+        track_root_hash: bool,
+        expected_root_hash: Option<String>,
+        result_type: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<PyObject> {
+        let call = |index: usize| {
+            self.client(py, index).borrow(py).execute_function(
+                context_id,
+                method,
+                args,
+                "",
+                track_root_hash,
+                expected_root_hash.clone(),
+                result_type,
+                false,
+            )
+        };
+        if !read_only {
+            return call(0);
+        }
+        let index = self.selector.read_node();
+        match call(index) {
+            Err(err) if index != 0 && is_node_failure(py, &err) => {
+                self.selector.record(index, Err(err.to_string()));
+                call(0)
+            }
+            result => result,
+        }
+    }
+
+    /// Probe every node now instead of waiting for the next round; returns
+    /// `stats`.
+    pub fn probe(&self, py: Python<'_>) -> PyObject {
+        let selector = self.selector.clone();
+        let targets = self.targets.clone();
+        py.allow_threads(|| {
+            self.runtime
+                .block_on(async move { probe_all(&selector, &targets).await })
+        });
+        self.stats(py)
+    }
+
+    /// Per node: `{url, role, healthy, rttMs, consecutiveFailures,
+    /// lastError, selected}`
+    #[getter]
+    pub fn stats(&self, py: Python<'_>) -> PyObject {
+        let selected = self.selector.read_node();
+        let nodes: Vec<Value> = self
+            .selector
+            .snapshot()
+            .into_iter()
+            .zip(self.targets.iter())
+            .enumerate()
+            .map(|(index, (node, (_, url)))| {
+                json!({
+                    "url": url.to_string(),
+                    "role": if index == 0 { "primary" } else { "replica" },
+                    "healthy": node.healthy(),
+                    "rttMs": node.rtt_ms,
+                    "consecutiveFailures": node.consecutive_failures,
+                    "lastError": node.last_error,
+                    "selected": index == selected,
+                })
+            })
+            .collect();
+        json_to_python(py, &Value::Array(nodes))
+    }
+
+    /// Stop the background probes; routing keeps the last known health.
+    pub fn close(&self) {
+        if let Some(prober) = self.prober.lock().ok().and_then(|mut p| p.take()) {
+            prober.abort();
+        }
+    }
+
+    #[getter]
+    pub fn closed(&self) -> bool {
+        self.prober.lock().map(|p| p.is_none()).unwrap_or(true)
+    }
+
+    pub fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_exc))]
+    pub fn __exit__(&self, _exc: &Bound<'_, pyo3::types::PyTuple>) -> bool {
+        self.close();
+        false
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "ReplicaSet(primary={}, replicas={})",
+            self.targets[0].1,
+            self.targets.len() - 1
+        )
+    }
+}
+
+impl Drop for PyReplicaSet {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads go to the fastest healthy node, the primary before any probe.
+    #[test]
+    fn test_read_node_prefers_fastest_healthy() {
+        let selector = Selector::new(3);
+        assert_eq!(selector.read_node(), 0);
+        selector.record(0, Ok(120.0));
+        selector.record(1, Ok(15.0));
+        selector.record(2, Ok(40.0));
+        assert_eq!(selector.read_node(), 1);
+
+        selector.record(1, Err("connection refused".to_string()));
+        assert_eq!(selector.read_node(), 1, "one failure is tolerated");
+        selector.record(1, Err("connection refused".to_string()));
+        assert_eq!(selector.read_node(), 2);
+    }
+
+    /// A slightly faster node does not take reads over; a clearly faster
+    /// one does.
+    #[test]
+    fn test_read_node_switch_margin() {
+        let selector = Selector::new(2);
+        selector.record(0, Ok(50.0));
+        assert_eq!(selector.read_node(), 0);
+        selector.record(1, Ok(45.0));
+        assert_eq!(selector.read_node(), 0);
+
+        let selector = Selector::new(2);
+        selector.record(0, Ok(50.0));
+        assert_eq!(selector.read_node(), 0);
+        selector.record(1, Ok(20.0));
+        assert_eq!(selector.read_node(), 1);
+    }
+
+    /// Round trips are smoothed and a success clears the failure count.
+    #[test]
+    fn test_node_health_record() {
+        let mut node = NodeHealth::default();
+        node.record(Ok(100.0));
+        node.record(Ok(200.0));
+        assert_eq!(node.rtt_ms, Some(130.0));
+        node.record(Err("timeout".to_string()));
+        node.record(Err("timeout".to_string()));
+        assert!(!node.healthy());
+        node.record(Ok(130.0));
+        assert!(node.healthy());
+        assert_eq!(node.last_error, None);
+    }
+}
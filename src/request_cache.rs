@@ -0,0 +1,186 @@
+//! Cache of prepared `execute` requests for repeated calls.
+//!
+//! Polling loops tend to execute the same `(context, method, args)` over and
+//! over. Parsing the args JSON, building the JSON-RPC request and encoding
+//! it is pure overhead after the first call, so prepared requests are kept
+//! keyed by a hash of their inputs. Entries remember their inputs and are
+//! only reused on an exact match, so a hash collision costs a rebuild, never
+//! a wrong request.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use calimero_primitives::context::ContextId;
+use calimero_server_primitives::jsonrpc;
+
+use crate::wire;
+
+/// Default number of prepared requests kept per client.
+pub const DEFAULT_REQUEST_CACHE_SIZE: usize = 256;
+
+/// A ready-to-send execute request.
+pub struct PreparedRequest {
+    context_id: ContextId,
+    method: String,
+    args: String,
+    pub request: jsonrpc::Request,
+    msgpack: OnceLock<Vec<u8>>,
+}
+
+impl PreparedRequest {
+    /// Parse `args` and build the JSON-RPC request.
+    pub fn build(context_id: ContextId, method: &str, args: &str) -> eyre::Result<Self> {
+        let args_value: serde_json::Value =
+            serde_json::from_str(args).map_err(|e| eyre::eyre!("Invalid JSON args: {}", e))?;
+
+        let execution_request = jsonrpc::ExecutionRequest::new(
+            context_id,
+            method.to_string(),
+            args_value,
+            vec![], // substitute aliases
+        );
+
+        let request = jsonrpc::Request::new(
+            jsonrpc::Version::TwoPointZero,
+            jsonrpc::RequestId::String("1".to_string()),
+            jsonrpc::RequestPayload::Execute(execution_request),
+        );
+
+        Ok(Self {
+            context_id,
+            method: method.to_string(),
+            args: args.to_string(),
+            request,
+            msgpack: OnceLock::new(),
+        })
+    }
+
+    fn matches(&self, context_id: &ContextId, method: &str, args: &str) -> bool {
+        self.context_id == *context_id && self.method == method && self.args == args
+    }
+
+    /// gzip+msgpack body, encoded once on first use.
+    pub fn msgpack(&self) -> eyre::Result<Vec<u8>> {
+        if let Some(body) = self.msgpack.get() {
+            return Ok(body.clone());
+        }
+        let body = wire::encode(&self.request)?;
+        Ok(self.msgpack.get_or_init(|| body).clone())
+    }
+}
+
+/// Bounded map of prepared requests; `capacity == 0` disables caching.
+pub struct RequestCache {
+    entries: Mutex<HashMap<u64, Arc<PreparedRequest>>>,
+    capacity: usize,
+}
+
+impl RequestCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    fn key(context_id: &ContextId, method: &str, args: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        context_id.hash(&mut hasher);
+        method.hash(&mut hasher);
+        args.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Return the prepared request for these inputs, building it on a miss.
+    pub fn get_or_build(
+        &self,
+        context_id: ContextId,
+        method: &str,
+        args: &str,
+    ) -> eyre::Result<Arc<PreparedRequest>> {
+        if self.capacity == 0 {
+            return PreparedRequest::build(context_id, method, args).map(Arc::new);
+        }
+        let key = Self::key(&context_id, method, args);
+        if let Some(hit) = self
+            .entries
+            .lock()
+            .ok()
+            .and_then(|entries| entries.get(&key).cloned())
+            .filter(|entry| entry.matches(&context_id, method, args))
+        {
+            return Ok(hit);
+        }
+
+        let prepared = Arc::new(PreparedRequest::build(context_id, method, args)?);
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() >= self.capacity {
+                entries.clear();
+            }
+            entries.insert(key, Arc::clone(&prepared));
+        }
+        Ok(prepared)
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .map(|entries| entries.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> ContextId {
+        ContextId::from([7u8; 32])
+    }
+
+    /// Identical inputs reuse one prepared request; different args do not.
+    #[test]
+    fn test_cache_reuses_identical_requests() {
+        let cache = RequestCache::new(8);
+        let a = cache.get_or_build(context(), "get", r#"{"k":1}"#).unwrap();
+        let b = cache.get_or_build(context(), "get", r#"{"k":1}"#).unwrap();
+        let c = cache.get_or_build(context(), "get", r#"{"k":2}"#).unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(cache.len(), 2);
+    }
+
+    /// Capacity zero builds every time and stores nothing.
+    #[test]
+    fn test_disabled_cache() {
+        let cache = RequestCache::new(0);
+        let a = cache.get_or_build(context(), "get", "{}").unwrap();
+        let b = cache.get_or_build(context(), "get", "{}").unwrap();
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(cache.len(), 0);
+    }
+
+    /// Invalid args fail without poisoning the cache.
+    #[test]
+    fn test_invalid_args_not_cached() {
+        let cache = RequestCache::new(8);
+        assert!(cache.get_or_build(context(), "get", "{not json").is_err());
+        assert_eq!(cache.len(), 0);
+    }
+}
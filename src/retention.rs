@@ -0,0 +1,124 @@
+//! Export of a context before it is deleted.
+//!
+//! Deleting a context cannot be undone, and cleanup scripts that pick the
+//! wrong IDs lose data for good. `delete_context(export_to=path)` first
+//! writes a snapshot of the context — its record, storage info, member
+//! identities and their aliases, plus the events this client received for
+//! it over `subscribe` — and only deletes once that file is on disk. If the
+//! export fails, nothing is deleted.
+//!
+//! The node has no endpoint returning a context's full key/value state or
+//! its complete event history, so the snapshot holds what the node exposes
+//! and the last [`crate::subscriptions::EVENT_HISTORY_LIMIT`] events seen.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use eyre::WrapErr;
+use serde_json::{json, Value};
+
+/// Version of the snapshot file layout.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// What the node reported about a context, as read before deletion.
+#[derive(Debug, Clone, Default)]
+pub struct ContextRecord {
+    pub context: Value,
+    pub storage: Value,
+    pub identities: Value,
+    pub identity_aliases: Value,
+}
+
+/// Snapshot document for `context_id`.
+pub fn snapshot(context_id: &str, record: ContextRecord, events: Vec<Value>) -> Value {
+    json!({
+        "version": SNAPSHOT_VERSION,
+        "contextId": context_id,
+        "exportedAt": Utc::now().to_rfc3339(),
+        "context": record.context,
+        "storage": record.storage,
+        "identities": record.identities,
+        "identityAliases": record.identity_aliases,
+        "events": events,
+    })
+}
+
+/// File the snapshot goes to: `export_to` itself, or
+/// `context-<id>.json` inside it when it is an existing directory.
+pub fn target_path(export_to: &Path, context_id: &str) -> PathBuf {
+    if export_to.is_dir() {
+        export_to.join(format!("context-{}.json", context_id))
+    } else {
+        export_to.to_path_buf()
+    }
+}
+
+/// Write `snapshot` to `path` and flush it to disk. Written to a temp file
+/// and renamed, so `path` never holds a partial snapshot.
+pub fn write(path: &Path, snapshot: &Value) -> eyre::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).wrap_err_with(|| format!("Failed to create {:?}", parent))?;
+    }
+    let temp_path = path.with_extension(format!("json.{}.tmp", crate::entropy::temp_suffix()));
+    let written = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(snapshot)?)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok::<_, eyre::Report>(())
+    })();
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written.wrap_err_with(|| format!("Failed to write context export to {:?}", path))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Directories get a per-context file; other paths are used as given.
+    #[test]
+    fn test_target_path() {
+        let dir = std::env::temp_dir();
+        assert_eq!(target_path(&dir, "abc"), dir.join("context-abc.json"));
+        let file = dir.join(format!("retention-{}.json", std::process::id()));
+        assert_eq!(target_path(&file, "abc"), file);
+    }
+
+    /// The written snapshot reads back whole, and no temp file is left.
+    #[test]
+    fn test_write_snapshot() {
+        let dir = std::env::temp_dir().join(format!("retention-{}", std::process::id()));
+        let path = dir.join("nested").join("export.json");
+        let record = ContextRecord {
+            context: json!({"id": "abc"}),
+            ..ContextRecord::default()
+        };
+        let events = vec![json!({"contextId": "abc", "type": "StateMutation"})];
+        write(&path, &snapshot("abc", record, events)).unwrap();
+
+        let read: Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(read["version"], SNAPSHOT_VERSION);
+        assert_eq!(read["context"]["id"], "abc");
+        assert_eq!(read["events"].as_array().unwrap().len(), 1);
+        let leftovers = fs::read_dir(path.parent().unwrap()).unwrap().count();
+        assert_eq!(leftovers, 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// A path that cannot be written is an error.
+    #[test]
+    fn test_write_fails_on_unwritable_path() {
+        let file = std::env::temp_dir().join(format!("retention-file-{}", std::process::id()));
+        fs::write(&file, b"not a directory").unwrap();
+        assert!(write(&file.join("export.json"), &json!({})).is_err());
+        fs::remove_file(&file).unwrap();
+    }
+}
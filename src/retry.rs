@@ -0,0 +1,351 @@
+//! Retries of transient failures with exponential backoff (`RetryConfig`).
+//!
+//! A client created with `retry=RetryConfig(...)` retries calls that fail
+//! with one of the `retry_on` error codes (by default rate limiting, node
+//! unavailability, 5xx node errors and connection failures), sleeping
+//! `backoff_base * 2^n` between attempts, capped at `max_backoff` and
+//! shortened by up to `jitter` of itself so retrying clients spread out.
+//! Every retry is also drawn from the client's [`RetryBudget`].
+//!
+//! Reads are retried on any of those failures. Mutations are only retried
+//! when the node cannot have applied them: the connection was never made,
+//! or the node answered 429 or 503 without executing. A mutation that timed
+//! out or hit a 500 may have been applied, so it is raised as is unless
+//! `retry_mutations=True` says the caller's mutations are idempotent.
+//!
+//! Clients created without `retry` do not retry, as before.
+
+use std::time::Duration;
+
+use pyo3::prelude::*;
+use serde_json::json;
+
+use crate::entropy;
+use crate::error::{self, CallRecord, ErrorCode, ErrorContext};
+use crate::reauth;
+use crate::retry_budget::RetryBudget;
+
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+pub const DEFAULT_BACKOFF_BASE_SECS: f64 = 0.2;
+pub const DEFAULT_MAX_BACKOFF_SECS: f64 = 5.0;
+pub const DEFAULT_JITTER: f64 = 0.5;
+
+/// Error codes retried unless `retry_on` says otherwise.
+const DEFAULT_RETRY_ON: [ErrorCode; 4] = [
+    ErrorCode::RateLimited,
+    ErrorCode::Unavailable,
+    ErrorCode::NodeError,
+    ErrorCode::Network,
+];
+
+/// How a client retries failed calls.
+#[pyclass(name = "RetryConfig", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryConfig {
+    max_attempts: u32,
+    backoff_base: Duration,
+    max_backoff: Duration,
+    jitter: f64,
+    retry_on: Vec<ErrorCode>,
+    retry_mutations: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            backoff_base: Duration::from_secs_f64(DEFAULT_BACKOFF_BASE_SECS),
+            max_backoff: Duration::from_secs_f64(DEFAULT_MAX_BACKOFF_SECS),
+            jitter: DEFAULT_JITTER,
+            retry_on: DEFAULT_RETRY_ON.to_vec(),
+            retry_mutations: false,
+        }
+    }
+}
+
+/// Whether a failed mutation provably was not applied: no connection was
+/// made, or the node turned it away before executing it.
+pub fn not_applied(code: ErrorCode, message: &str) -> bool {
+    let lower = message.to_ascii_lowercase();
+    matches!(code, ErrorCode::RateLimited | ErrorCode::Unavailable)
+        || ["connection refused", "dns error", "error trying to connect"]
+            .iter()
+            .any(|needle| lower.contains(needle))
+}
+
+/// Uniform random number in `[0, 1)` from the entropy source.
+fn unit_random() -> f64 {
+    entropy::random_bytes::<8>()
+        .map(|bytes| (u64::from_le_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64)
+        .unwrap_or(0.5)
+}
+
+impl RetryConfig {
+    /// One attempt per call: the behavior of clients without `retry`.
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Sleep before retry number `retry` (1 for the first), with `unit` in
+    /// `[0, 1)` picking how much of the jitter is taken off.
+    pub fn backoff(&self, retry: u32, unit: f64) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31);
+        let delay = self
+            .backoff_base
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_backoff);
+        delay.mul_f64(1.0 - self.jitter * unit.clamp(0.0, 1.0))
+    }
+
+    /// Whether a call that failed with `message` may be sent again.
+    pub fn retryable(&self, message: &str, idempotent: bool) -> bool {
+        let context = ErrorContext::new(CallRecord::default(), None, message);
+        self.retry_on.contains(&context.code)
+            && (idempotent || self.retry_mutations || not_applied(context.code, message))
+    }
+
+    /// `{maxAttempts, backoffBase, maxBackoff, jitter, retryOn, retryMutations}`
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "maxAttempts": self.max_attempts,
+            "backoffBase": self.backoff_base.as_secs_f64(),
+            "maxBackoff": self.max_backoff.as_secs_f64(),
+            "jitter": self.jitter,
+            "retryOn": self.retry_on.iter().map(|code| code.value()).collect::<Vec<_>>(),
+            "retryMutations": self.retry_mutations,
+        })
+    }
+}
+
+/// Run the call `make` builds, building and sending it again while it fails
+/// in a way `config` retries and `budget` allows. A token the node rejects
+/// is recovered (see [`crate::reauth`]) and the call replayed once, outside
+/// the retry policy and budget.
+pub async fn with_retries<T, E, F, Fut>(
+    config: &RetryConfig,
+    budget: &RetryBudget,
+    idempotent: bool,
+    mut make: F,
+) -> Result<T, E>
+where
+    E: std::fmt::Display,
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    let mut reauthenticated = false;
+    loop {
+        let session = reauth::active();
+        let error = match make().await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        let message = error::render_chain(&error);
+        if let Some((session, seen)) = session.filter(|_| !reauthenticated) {
+            if reauth::is_rejected_token(&message) {
+                reauthenticated = true;
+                if session.recover(seen).await {
+                    error::record_retry("token rejected; replayed after re-authenticating");
+                    continue;
+                }
+            }
+        }
+        if attempt >= config.max_attempts {
+            return Err(error);
+        }
+        if !config.retryable(&message, idempotent) || !budget.try_retry() {
+            return Err(error);
+        }
+        error::record_retry(format!("attempt {} failed: {}", attempt, message));
+        tokio::time::sleep(config.backoff(attempt, unit_random())).await;
+        attempt += 1;
+    }
+}
+
+fn error_code(code: &Bound<'_, PyAny>) -> PyResult<ErrorCode> {
+    if let Ok(code) = code.extract::<ErrorCode>() {
+        return Ok(code);
+    }
+    let name: String = code.extract()?;
+    ErrorCode::from_name(&name).ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown error code '{}'", name))
+    })
+}
+
+#[pymethods]
+impl RetryConfig {
+    /// Retry policy for `Client(retry=...)`.
+    ///
+    /// `retry_on` takes `ErrorCode`s or their names (`"rate_limited"`,
+    /// `"node_error"`, ...). Backoff and jitter are in seconds and as a
+    /// fraction of the delay.
+    #[new]
+    #[pyo3(signature = (max_attempts=DEFAULT_MAX_ATTEMPTS, backoff_base=DEFAULT_BACKOFF_BASE_SECS, max_backoff=DEFAULT_MAX_BACKOFF_SECS, jitter=DEFAULT_JITTER, retry_on=None, retry_mutations=false))]
+    pub fn new(
+        max_attempts: u32,
+        backoff_base: f64,
+        max_backoff: f64,
+        jitter: f64,
+        retry_on: Option<Vec<Bound<'_, PyAny>>>,
+        retry_mutations: bool,
+    ) -> PyResult<Self> {
+        let invalid = |message: &str| {
+            Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                message.to_string(),
+            ))
+        };
+        if max_attempts == 0 {
+            return invalid("max_attempts must be at least 1");
+        }
+        if !(backoff_base.is_finite() && backoff_base >= 0.0) {
+            return invalid("backoff_base must be a non-negative number of seconds");
+        }
+        if !(max_backoff.is_finite() && max_backoff >= 0.0) {
+            return invalid("max_backoff must be a non-negative number of seconds");
+        }
+        if !(0.0..=1.0).contains(&jitter) {
+            return invalid("jitter must be between 0 and 1");
+        }
+        let retry_on = match retry_on {
+            Some(codes) => codes.iter().map(error_code).collect::<PyResult<_>>()?,
+            None => DEFAULT_RETRY_ON.to_vec(),
+        };
+        Ok(Self {
+            max_attempts,
+            backoff_base: Duration::from_secs_f64(backoff_base),
+            max_backoff: Duration::from_secs_f64(max_backoff),
+            jitter,
+            retry_on,
+            retry_mutations,
+        })
+    }
+
+    #[getter]
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    #[getter]
+    pub fn backoff_base(&self) -> f64 {
+        self.backoff_base.as_secs_f64()
+    }
+
+    #[getter]
+    pub fn max_backoff(&self) -> f64 {
+        self.max_backoff.as_secs_f64()
+    }
+
+    #[getter]
+    pub fn jitter(&self) -> f64 {
+        self.jitter
+    }
+
+    #[getter]
+    pub fn retry_on(&self) -> Vec<ErrorCode> {
+        self.retry_on.clone()
+    }
+
+    #[getter]
+    pub fn retry_mutations(&self) -> bool {
+        self.retry_mutations
+    }
+
+    /// Pickle as the constructor arguments, error codes by name.
+    pub fn __reduce__(slf: &Bound<'_, Self>) -> PyResult<(PyObject, PyObject)> {
+        let py = slf.py();
+        let config = slf.get();
+        let retry_on: Vec<&str> = config.retry_on.iter().map(|code| code.value()).collect();
+        let args = (
+            config.max_attempts,
+            config.backoff_base.as_secs_f64(),
+            config.max_backoff.as_secs_f64(),
+            config.jitter,
+            retry_on,
+            config.retry_mutations,
+        );
+        Ok((slf.get_type().into_any().unbind(), args.into_py(py)))
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "RetryConfig(max_attempts={}, backoff_base={}, max_backoff={}, jitter={}, retry_on={:?}, retry_mutations={})",
+            self.max_attempts,
+            self.backoff_base.as_secs_f64(),
+            self.max_backoff.as_secs_f64(),
+            self.jitter,
+            self.retry_on.iter().map(|code| code.value()).collect::<Vec<_>>(),
+            if self.retry_mutations { "True" } else { "False" },
+        )
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Delays double per retry up to the cap; jitter only shortens them.
+    #[test]
+    fn test_backoff() {
+        let config = RetryConfig {
+            backoff_base: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+            ..RetryConfig::default()
+        };
+        assert_eq!(config.backoff(1, 0.0), Duration::from_millis(100));
+        assert_eq!(config.backoff(2, 0.0), Duration::from_millis(200));
+        assert_eq!(config.backoff(3, 0.0), Duration::from_millis(350));
+        assert_eq!(config.backoff(40, 0.0), Duration::from_millis(350));
+        assert_eq!(config.backoff(1, 1.0), Duration::from_millis(50));
+    }
+
+    /// Reads retry any listed failure; mutations only those never applied.
+    #[test]
+    fn test_retryable_guards_mutations() {
+        let config = RetryConfig::default();
+        let server_error = "POST /jsonrpc failed with status 500";
+        assert!(config.retryable(server_error, true));
+        assert!(!config.retryable(server_error, false));
+        assert!(config.retryable("failed with status 429", false));
+        assert!(config.retryable("error sending request: connection refused", false));
+        assert!(!config.retryable("failed with status 404", true));
+
+        let mutations = RetryConfig {
+            retry_mutations: true,
+            ..RetryConfig::default()
+        };
+        assert!(mutations.retryable(server_error, false));
+    }
+
+    /// Attempts stop at `max_attempts`, and at the first success.
+    #[tokio::test(start_paused = true)]
+    async fn test_with_retries() {
+        let config = RetryConfig::default();
+        let budget = RetryBudget::new(1.0);
+        let calls = AtomicU32::new(0);
+        let result: Result<(), String> = with_retries(&config, &budget, true, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err("failed with status 503".to_string())
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        calls.store(0, Ordering::SeqCst);
+        let result = with_retries(&config, &budget, false, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err("dns error".to_string()),
+                n => Ok(n),
+            }
+        })
+        .await;
+        assert_eq!(result, Ok(1));
+    }
+}
@@ -0,0 +1,126 @@
+//! Per-client retry budget.
+//!
+//! Retries help with transient failures, but when a node is down every
+//! caller retrying multiplies the load exactly when it can least take it.
+//! The budget caps retries at a fraction of requests: every request
+//! deposits `ratio` into a balance and every retry withdraws one, so over
+//! time at most `ratio` of requests are retries. The balance starts at, and
+//! is capped by, [`RETRY_RESERVE`] so a quiet client can still retry a short
+//! burst of failures. A retry that finds the balance empty is denied and the
+//! original error is raised instead.
+
+use std::sync::Mutex;
+
+/// Default share of requests that may be retries.
+pub const DEFAULT_RETRY_BUDGET_RATIO: f64 = 0.2;
+
+/// Retries available up front, and the most that can be saved up.
+const RETRY_RESERVE: u64 = 10;
+
+/// Balance units per retry; fixed point keeps repeated deposits exact.
+const UNITS_PER_RETRY: u64 = 1000;
+
+#[derive(Debug)]
+struct State {
+    /// Saved-up retries in [`UNITS_PER_RETRY`] units.
+    balance: u64,
+    requests: u64,
+    retries: u64,
+    denied: u64,
+}
+
+/// Token balance shared by every retry a client makes.
+#[derive(Debug)]
+pub struct RetryBudget {
+    ratio: f64,
+    deposit: u64,
+    state: Mutex<State>,
+}
+
+impl RetryBudget {
+    pub fn new(ratio: f64) -> Self {
+        Self {
+            ratio,
+            deposit: (ratio * UNITS_PER_RETRY as f64).round() as u64,
+            state: Mutex::new(State {
+                balance: RETRY_RESERVE * UNITS_PER_RETRY,
+                requests: 0,
+                retries: 0,
+                denied: 0,
+            }),
+        }
+    }
+
+    /// Count an original (non-retry) request towards the budget.
+    pub fn record_request(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.requests += 1;
+            state.balance = (state.balance + self.deposit).min(RETRY_RESERVE * UNITS_PER_RETRY);
+        }
+    }
+
+    /// Withdraw one retry; `false` means the budget is spent and the caller
+    /// must not retry.
+    pub fn try_retry(&self) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        if state.balance >= UNITS_PER_RETRY {
+            state.balance -= UNITS_PER_RETRY;
+            state.retries += 1;
+            true
+        } else {
+            state.denied += 1;
+            false
+        }
+    }
+
+    pub fn stats(&self) -> serde_json::Value {
+        let Ok(state) = self.state.lock() else {
+            return serde_json::Value::Null;
+        };
+        serde_json::json!({
+            "ratio": self.ratio,
+            "balance": state.balance as f64 / UNITS_PER_RETRY as f64,
+            "requests": state.requests,
+            "retries": state.retries,
+            "denied": state.denied,
+        })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The reserve allows a burst; after that retries track the ratio.
+    #[test]
+    fn test_budget_limits_retry_share() {
+        let budget = RetryBudget::new(0.2);
+        let burst = (0..20).filter(|_| budget.try_retry()).count();
+        assert_eq!(burst, RETRY_RESERVE as usize);
+
+        for _ in 0..10 {
+            budget.record_request();
+        }
+        assert!(budget.try_retry());
+        assert!(budget.try_retry());
+        assert!(!budget.try_retry());
+        assert_eq!(budget.stats()["denied"], 11);
+    }
+
+    /// A zero ratio still allows the reserve but never refills it.
+    #[test]
+    fn test_zero_ratio_never_refills() {
+        let budget = RetryBudget::new(0.0);
+        while budget.try_retry() {}
+        for _ in 0..100 {
+            budget.record_request();
+        }
+        assert!(!budget.try_retry());
+    }
+}
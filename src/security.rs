@@ -0,0 +1,220 @@
+//! Security posture checks for a client's configuration.
+//!
+//! Each check inspects local state only (token cache files, cached tokens,
+//! connection settings) and yields machine-readable [`Finding`]s, so CI can
+//! gate on e.g. "no `high` findings".
+
+use std::path::Path;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use calimero_client::JwtToken;
+use serde::Serialize;
+use url::Url;
+
+/// Refresh tokens valid for longer than this are flagged (30 days).
+pub const MAX_REFRESH_TOKEN_LIFETIME_SECS: i64 = 30 * 24 * 3600;
+
+/// Access tokens valid for longer than this are flagged (24 hours).
+pub const MAX_ACCESS_TOKEN_LIFETIME_SECS: i64 = 24 * 3600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+/// One risky setting.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Finding {
+    /// Stable identifier, e.g. `token_file_permissions`.
+    pub id: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub remediation: &'static str,
+}
+
+/// Flag a cache file or directory readable by group/others.
+#[cfg(unix)]
+pub fn check_permissions(path: &Path, is_dir: bool) -> Option<Finding> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path).ok()?.permissions().mode();
+    check_mode(path, mode, is_dir)
+}
+
+#[cfg(not(unix))]
+pub fn check_permissions(_path: &Path, _is_dir: bool) -> Option<Finding> {
+    None
+}
+
+fn check_mode(path: &Path, mode: u32, is_dir: bool) -> Option<Finding> {
+    if mode & 0o077 == 0 {
+        return None;
+    }
+    let (id, remediation) = if is_dir {
+        (
+            "cache_dir_permissions",
+            "chmod 700 the token cache directory",
+        )
+    } else {
+        ("token_file_permissions", "chmod 600 the token cache file")
+    };
+    Some(Finding {
+        id,
+        severity: if mode & 0o004 != 0 {
+            Severity::High
+        } else {
+            Severity::Medium
+        },
+        message: format!(
+            "{} is accessible by other users (mode {:o})",
+            path.display(),
+            mode & 0o777
+        ),
+        remediation,
+    })
+}
+
+/// `exp` claim of a JWT, without verifying the signature.
+pub fn jwt_expiry(token: &str) -> Option<i64> {
+    jwt_claims(token)?.get("exp")?.as_i64()
+}
+
+/// Claims of a JWT, read without verifying its signature.
+pub fn jwt_claims(token: &str) -> Option<serde_json::Value> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Flag tokens whose remaining lifetime is excessive.
+pub fn check_tokens(tokens: &JwtToken, now: i64) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let access_expiry = tokens
+        .expires_at
+        .or_else(|| jwt_expiry(&tokens.access_token));
+    if let Some(expiry) = access_expiry {
+        if expiry - now > MAX_ACCESS_TOKEN_LIFETIME_SECS {
+            findings.push(Finding {
+                id: "long_lived_access_token",
+                severity: Severity::Low,
+                message: format!(
+                    "Cached access token is valid for another {}h",
+                    (expiry - now) / 3600
+                ),
+                remediation: "Configure the node to issue shorter-lived access tokens",
+            });
+        }
+    }
+    if let Some(expiry) = tokens.refresh_token.as_deref().and_then(jwt_expiry) {
+        if expiry - now > MAX_REFRESH_TOKEN_LIFETIME_SECS {
+            findings.push(Finding {
+                id: "long_lived_refresh_token",
+                severity: Severity::Medium,
+                message: format!(
+                    "Cached refresh token is valid for another {} days",
+                    (expiry - now) / 86400
+                ),
+                remediation: "Configure the node to issue refresh tokens valid for 30 days or less",
+            });
+        }
+    }
+    findings
+}
+
+/// Flag connection settings that weaken transport security.
+pub fn check_connection(url: &Url) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let loopback = matches!(
+        url.host_str(),
+        Some("localhost") | Some("127.0.0.1") | Some("[::1]")
+    );
+    if url.scheme() == "http" && !loopback {
+        findings.push(Finding {
+            id: "plaintext_transport",
+            severity: Severity::High,
+            message: format!("{} is reached over unencrypted HTTP", url),
+            remediation: "Serve the node over https://",
+        });
+    }
+    findings
+}
+
+/// Flag plaintext on-disk token storage.
+pub fn unencrypted_storage(path: &Path) -> Finding {
+    Finding {
+        id: "unencrypted_token_storage",
+        severity: Severity::Low,
+        message: format!("Tokens are stored unencrypted in {}", path.display()),
+        remediation: "Restrict access to the cache directory or use anonymous mode where possible",
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwt(exp: i64) -> String {
+        let claims = URL_SAFE_NO_PAD.encode(format!("{{\"exp\":{}}}", exp));
+        format!("header.{}.signature", claims)
+    }
+
+    /// Group/other access is flagged; world-readable is high severity.
+    #[test]
+    fn test_check_mode() {
+        let path = Path::new("/tmp/node.json");
+        assert!(check_mode(path, 0o100600, false).is_none());
+        let finding = check_mode(path, 0o100644, false).unwrap();
+        assert_eq!(finding.id, "token_file_permissions");
+        assert_eq!(finding.severity, Severity::High);
+        let finding = check_mode(path, 0o40750, true).unwrap();
+        assert_eq!(finding.id, "cache_dir_permissions");
+        assert_eq!(finding.severity, Severity::Medium);
+    }
+
+    /// The `exp` claim is read without signature verification.
+    #[test]
+    fn test_jwt_expiry() {
+        assert_eq!(jwt_expiry(&jwt(1234)), Some(1234));
+        assert_eq!(jwt_expiry("not-a-jwt"), None);
+    }
+
+    /// Only excessively long-lived tokens are reported.
+    #[test]
+    fn test_check_tokens() {
+        let now = 1_000_000;
+        let short = JwtToken {
+            access_token: jwt(now + 3600),
+            refresh_token: Some(jwt(now + 7 * 86400)),
+            expires_at: None,
+        };
+        assert!(check_tokens(&short, now).is_empty());
+
+        let long = JwtToken {
+            access_token: jwt(now + 3600),
+            refresh_token: Some(jwt(now + 365 * 86400)),
+            expires_at: Some(now + 7 * 86400),
+        };
+        let ids: Vec<_> = check_tokens(&long, now).iter().map(|f| f.id).collect();
+        assert_eq!(ids, ["long_lived_access_token", "long_lived_refresh_token"]);
+    }
+
+    /// Remote plain HTTP is flagged.
+    #[test]
+    fn test_check_connection() {
+        let local = Url::parse("http://localhost:2528").unwrap();
+        assert!(check_connection(&local).is_empty());
+
+        let remote = Url::parse("http://node.example:2528").unwrap();
+        let ids: Vec<_> = check_connection(&remote).iter().map(|f| f.id).collect();
+        assert_eq!(ids, ["plaintext_transport"]);
+    }
+}
@@ -0,0 +1,163 @@
+//! `ContextSession`: a client bound to one context and executor.
+//!
+//! Application code tends to pass the same context id and executor key to
+//! every call. `Client.session(context_id, executor_public_key)` resolves
+//! both once (aliases included) and returns a [`PyContextSession`] whose
+//! `call()` and `view()` only take the method and its arguments:
+//!
+//! ```python
+//! counter = client.session("counter-ctx", executor_public_key="me")
+//! counter.call("increment", {"by": 2})
+//! value = counter.view("get")
+//! ```
+//!
+//! A session is immutable and holds nothing but the client and the two
+//! resolved ids, so it can be shared between threads like the client.
+//! Arguments are a JSON string or any JSON-serializable value.
+
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+
+use crate::client::PyClient;
+use crate::utils::python_to_json;
+
+/// A client bound to a context and an executor identity.
+#[pyclass(name = "ContextSession", frozen)]
+pub struct PyContextSession {
+    client: Py<PyClient>,
+    context_id: String,
+    executor_public_key: Option<String>,
+}
+
+impl PyContextSession {
+    pub fn new(
+        client: Py<PyClient>,
+        context_id: String,
+        executor_public_key: Option<String>,
+    ) -> Self {
+        Self {
+            client,
+            context_id,
+            executor_public_key,
+        }
+    }
+
+    fn executor(&self) -> &str {
+        self.executor_public_key.as_deref().unwrap_or_default()
+    }
+}
+
+/// `args` as JSON text: strings as given, `None` as `{}`, anything else
+/// serialized.
+fn args_json(args: Option<&Bound<'_, PyAny>>) -> PyResult<String> {
+    match args {
+        None => Ok("{}".to_string()),
+        Some(args) if args.is_none() => Ok("{}".to_string()),
+        Some(args) => match args.downcast::<PyString>() {
+            Ok(text) => Ok(text.to_str()?.to_string()),
+            Err(_) => Ok(python_to_json(args)?.to_string()),
+        },
+    }
+}
+
+#[pymethods]
+impl PyContextSession {
+    /// `execute_function` on the session's context as its executor.
+    #[pyo3(signature = (method, args=None, track_root_hash=false, expected_root_hash=None, result_type=None, dry_run=false))]
+    pub fn call(
+        &self,
+        py: Python<'_>,
+        method: &str,
+        args: Option<&Bound<'_, PyAny>>,
+        track_root_hash: bool,
+        expected_root_hash: Option<String>,
+        result_type: Option<&Bound<'_, PyAny>>,
+        dry_run: bool,
+    ) -> PyResult<PyObject> {
+        let args = args_json(args)?;
+        self.client.borrow(py).execute_function(
+            &self.context_id,
+            method,
+            &args,
+            self.executor(),
+            track_root_hash,
+            expected_root_hash,
+            result_type,
+            dry_run,
+        )
+    }
+
+    /// Asyncio variant of `call`, as `Client.execute_async`.
+    #[pyo3(signature = (method, args=None, track_root_hash=false, expected_root_hash=None))]
+    pub fn call_async<'py>(
+        &self,
+        py: Python<'py>,
+        method: &str,
+        args: Option<&Bound<'py, PyAny>>,
+        track_root_hash: bool,
+        expected_root_hash: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let args = args_json(args)?;
+        PyClient::execute_async(
+            self.client.bind(py),
+            &self.context_id,
+            method,
+            &args,
+            self.executor(),
+            track_root_hash,
+            expected_root_hash,
+        )
+    }
+
+    /// `Client.view` on the session's context.
+    #[pyo3(signature = (method, args=None, refresh=false, result_type=None))]
+    pub fn view(
+        &self,
+        py: Python<'_>,
+        method: &str,
+        args: Option<&Bound<'_, PyAny>>,
+        refresh: bool,
+        result_type: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<PyObject> {
+        let args = args_json(args)?;
+        self.client
+            .borrow(py)
+            .view(py, &self.context_id, method, &args, refresh, result_type)
+    }
+
+    /// Drop cached `view()` results of the session's context (of `method`
+    /// only when given); returns how many were dropped.
+    #[pyo3(signature = (method=None))]
+    pub fn invalidate(&self, py: Python<'_>, method: Option<&str>) -> PyResult<usize> {
+        self.client
+            .borrow(py)
+            .invalidate(Some(&self.context_id), method)
+    }
+
+    /// Context id, resolved from the alias when one was given
+    #[getter]
+    pub fn context_id(&self) -> &str {
+        &self.context_id
+    }
+
+    /// Executor identity, resolved from the alias when one was given
+    #[getter]
+    pub fn executor_public_key(&self) -> Option<&str> {
+        self.executor_public_key.as_deref()
+    }
+
+    #[getter]
+    pub fn client(&self, py: Python<'_>) -> Py<PyClient> {
+        self.client.clone_ref(py)
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.executor_public_key {
+            Some(executor) => format!(
+                "ContextSession(context_id='{}', executor_public_key='{}')",
+                self.context_id, executor
+            ),
+            None => format!("ContextSession(context_id='{}')", self.context_id),
+        }
+    }
+}
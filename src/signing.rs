@@ -0,0 +1,389 @@
+//! Offline signing of context executions with a locally held Ed25519 key.
+//!
+//! Air-gapped and automated signers have no browser and often no network:
+//! a [`PySigner`] loads an identity key from bytes, a key file or the OS
+//! keychain and produces a [`SignedExecution`] — the call plus an expiry, a
+//! nonce and a signature over them — without talking to any node. The
+//! envelope is plain JSON, so it can be carried to an online machine, where
+//! `Client.submit_signed()` verifies it (signature, expiry, one submission
+//! per nonce) before executing the call.
+//!
+//! The node runs the call as the context identity it holds; the signature is
+//! checked by the submitting client, which thereby only forwards calls the
+//! key holder approved.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use calimero_primitives::context::ContextId;
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::blob::find_string_field;
+use crate::entropy;
+use crate::keychain::KeyringStorage;
+use crate::utils::{json_to_python, python_to_json};
+use crate::wallet_login::SigningKey;
+
+/// Envelope format version.
+pub const SIGNED_EXECUTION_VERSION: u32 = 1;
+
+/// Default time a signed execution can be submitted (five minutes).
+pub const DEFAULT_SIGNED_EXECUTION_TTL_SECS: u64 = 300;
+
+/// Prefix of keychain items holding signing keys.
+pub const KEYCHAIN_KEY_PREFIX: &str = "signing-key/";
+
+/// Domain separator, so these signatures are never valid for anything else.
+const SIGNING_DOMAIN: &str = "calimero-signed-execution-v1";
+
+/// A context execution approved by the holder of `public_key`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedExecution {
+    pub version: u32,
+    pub context_id: String,
+    pub method: String,
+    pub args_json: String,
+    /// Signer's public key, base58 like context identities.
+    pub public_key: String,
+    /// Hex; makes each envelope unique.
+    pub nonce: String,
+    /// Unix time (seconds) after which the envelope is refused.
+    pub expires_at: i64,
+    /// Base58 Ed25519 signature over [`SignedExecution::signing_bytes`].
+    pub signature: String,
+}
+
+impl SignedExecution {
+    /// Sign a call; `args_json` is passed on exactly as given.
+    pub fn sign(
+        key: &SigningKey,
+        context_id: &ContextId,
+        method: &str,
+        args_json: &str,
+        nonce: [u8; 16],
+        expires_at: i64,
+    ) -> Self {
+        let mut signed = Self {
+            version: SIGNED_EXECUTION_VERSION,
+            context_id: context_id.to_string(),
+            method: method.to_string(),
+            args_json: args_json.to_string(),
+            public_key: bs58::encode(key.public_key()).into_string(),
+            nonce: hex::encode(nonce),
+            expires_at,
+            signature: String::new(),
+        };
+        signed.signature = bs58::encode(key.sign(&signed.signing_bytes())).into_string();
+        signed
+    }
+
+    /// The signed message: every field but the signature, one per line,
+    /// with the arguments by SHA-256 so their size does not matter.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            SIGNING_DOMAIN,
+            self.context_id,
+            self.method,
+            hex::encode(Sha256::digest(self.args_json.as_bytes())),
+            self.public_key,
+            self.nonce,
+            self.expires_at
+        )
+        .into_bytes()
+    }
+
+    /// Check the version, expiry and signature at `now`.
+    pub fn verify(&self, now: i64) -> eyre::Result<ContextId> {
+        if self.version != SIGNED_EXECUTION_VERSION {
+            eyre::bail!("Unsupported signed execution version {}", self.version);
+        }
+        if self.expires_at <= now {
+            eyre::bail!("Signed execution expired at {}", self.expires_at);
+        }
+        let context_id = self
+            .context_id
+            .parse::<ContextId>()
+            .map_err(|e| eyre::eyre!("Invalid context id in signed execution: {}", e))?;
+        let public_key = bs58::decode(&self.public_key)
+            .into_vec()
+            .wrap_err("Signed execution public key is not base58")?;
+        let signature = bs58::decode(&self.signature)
+            .into_vec()
+            .wrap_err("Signed execution signature is not base58")?;
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(&self.signing_bytes(), &signature)
+            .map_err(|_| eyre::eyre!("Signed execution signature does not match its content"))?;
+        Ok(context_id)
+    }
+}
+
+/// Nonces already submitted through one client, kept until they expire.
+#[derive(Debug, Default)]
+pub struct ReplayGuard {
+    seen: Mutex<HashMap<(String, String), i64>>,
+}
+
+impl ReplayGuard {
+    /// Record `signed`, failing if it was submitted before.
+    pub fn admit(&self, signed: &SignedExecution, now: i64) -> eyre::Result<()> {
+        let mut seen = self
+            .seen
+            .lock()
+            .map_err(|_| eyre::eyre!("Replay guard poisoned"))?;
+        seen.retain(|_, expires_at| *expires_at > now);
+        let key = (signed.public_key.clone(), signed.nonce.clone());
+        if seen.contains_key(&key) {
+            eyre::bail!("Signed execution {} was already submitted", signed.nonce);
+        }
+        seen.insert(key, signed.expires_at);
+        Ok(())
+    }
+}
+
+/// Key material from a key file: raw 32/64 bytes, or text holding a
+/// `ed25519:<base58>` key, hex, base58, or a JSON key file with a
+/// `private_key`/`secret_key` field (NEAR and node key files).
+pub fn parse_key_file(data: &[u8]) -> eyre::Result<Zeroizing<Vec<u8>>> {
+    let text = std::str::from_utf8(data)
+        .ok()
+        .map(str::trim)
+        .filter(|text| {
+            !text.is_empty()
+                && text
+                    .chars()
+                    .all(|c| c.is_ascii_graphic() || c.is_ascii_whitespace())
+        });
+    match text {
+        None if matches!(data.len(), 32 | 64) => Ok(Zeroizing::new(data.to_vec())),
+        None => eyre::bail!("Key file is neither a raw ed25519 key nor text"),
+        Some(text) if text.starts_with('{') => {
+            let value: serde_json::Value =
+                serde_json::from_str(text).wrap_err("Key file is not valid JSON")?;
+            let key = ["private_key", "secret_key", "privateKey", "secretKey"]
+                .iter()
+                .find_map(|field| find_string_field(&value, field))
+                .ok_or_else(|| eyre::eyre!("Key file has no private_key field"))?;
+            parse_key_text(key)
+        }
+        Some(text) => parse_key_text(text),
+    }
+}
+
+fn parse_key_text(text: &str) -> eyre::Result<Zeroizing<Vec<u8>>> {
+    let encoded = text.strip_prefix("ed25519:").unwrap_or(text);
+    if matches!(encoded.len(), 64 | 128) && encoded.chars().all(|c| c.is_ascii_hexdigit()) {
+        return hex::decode(encoded)
+            .map(Zeroizing::new)
+            .wrap_err("Invalid hex key");
+    }
+    bs58::decode(encoded)
+        .into_vec()
+        .map(Zeroizing::new)
+        .map_err(|_| eyre::eyre!("Key is not valid hex or base58"))
+}
+
+fn value_error(e: eyre::Report) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e))
+}
+
+/// Ed25519 key signing context executions offline.
+#[pyclass(name = "Signer")]
+pub struct PySigner {
+    seed: Zeroizing<Vec<u8>>,
+    key: SigningKey,
+}
+
+impl PySigner {
+    fn new(bytes: &[u8]) -> eyre::Result<Self> {
+        let key = SigningKey::from_bytes(bytes)?;
+        Ok(Self {
+            seed: Zeroizing::new(bytes[..32].to_vec()),
+            key,
+        })
+    }
+
+    /// The key, for other claims its holder signs.
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.key
+    }
+
+    pub fn sign(
+        &self,
+        context_id: &ContextId,
+        method: &str,
+        args_json: &str,
+        ttl_secs: u64,
+    ) -> eyre::Result<SignedExecution> {
+        let expires_at = chrono::Utc::now().timestamp() + ttl_secs as i64;
+        Ok(SignedExecution::sign(
+            &self.key,
+            context_id,
+            method,
+            args_json,
+            entropy::random_bytes::<16>()?,
+            expires_at,
+        ))
+    }
+}
+
+#[pymethods]
+impl PySigner {
+    /// Signer for a 32-byte seed or 64-byte seed+public key.
+    #[staticmethod]
+    pub fn from_bytes(key: &[u8]) -> PyResult<Self> {
+        Self::new(key).map_err(value_error)
+    }
+
+    /// Signer for the key in `path` (raw bytes, hex, base58,
+    /// `ed25519:<base58>`, or a JSON key file with `private_key`).
+    #[staticmethod]
+    pub fn from_file(path: &str) -> PyResult<Self> {
+        let data = Zeroizing::new(std::fs::read(Path::new(path)).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyOSError, _>(format!(
+                "Failed to read key file {}: {}",
+                path, e
+            ))
+        })?);
+        let bytes = parse_key_file(&data).map_err(value_error)?;
+        Self::new(&bytes).map_err(value_error)
+    }
+
+    /// Signer for the key saved as `name` with `save_to_keychain`.
+    #[staticmethod]
+    pub fn from_keychain(name: &str) -> PyResult<Self> {
+        let account = format!("{}{}", KEYCHAIN_KEY_PREFIX, name);
+        let encoded = KeyringStorage::new()
+            .load_secret(&account)
+            .map_err(value_error)?
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!(
+                    "No signing key named '{}' in the keychain",
+                    name
+                ))
+            })?;
+        let bytes = parse_key_text(&encoded).map_err(value_error)?;
+        Self::new(&bytes).map_err(value_error)
+    }
+
+    /// Signer for a fresh random key.
+    #[staticmethod]
+    pub fn generate() -> PyResult<Self> {
+        let seed = Zeroizing::new(entropy::random_bytes::<32>().map_err(value_error)?);
+        Self::new(&seed[..]).map_err(value_error)
+    }
+
+    /// Save the key in the OS keychain as `name`, for `from_keychain`.
+    pub fn save_to_keychain(&self, name: &str) -> PyResult<()> {
+        let account = format!("{}{}", KEYCHAIN_KEY_PREFIX, name);
+        let encoded = Zeroizing::new(hex::encode(&self.seed[..]));
+        KeyringStorage::new()
+            .save_secret(&account, &encoded)
+            .map_err(value_error)
+    }
+
+    /// Public key, base58 like context identities.
+    #[getter]
+    pub fn public_key(&self) -> String {
+        bs58::encode(self.key.public_key()).into_string()
+    }
+
+    /// Sign a call of `method` in `context_id` without contacting a node.
+    ///
+    /// `args` is a JSON string or a JSON-compatible value. Returns the
+    /// envelope as a dict `{version, contextId, method, argsJson, publicKey,
+    /// nonce, expiresAt, signature}`, valid for `ttl` seconds; pass it to
+    /// `Client.submit_signed()`.
+    #[pyo3(signature = (context_id, method, args=None, ttl=DEFAULT_SIGNED_EXECUTION_TTL_SECS))]
+    pub fn sign_execution(
+        &self,
+        py: Python<'_>,
+        context_id: &str,
+        method: &str,
+        args: Option<&Bound<'_, PyAny>>,
+        ttl: u64,
+    ) -> PyResult<PyObject> {
+        let context_id = context_id.parse::<ContextId>().map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid context id: {}", e))
+        })?;
+        let args_json = match args {
+            None => "{}".to_string(),
+            Some(args) => match args.downcast::<pyo3::types::PyString>() {
+                Ok(raw) => raw.to_str()?.to_string(),
+                Err(_) => python_to_json(args)?.to_string(),
+            },
+        };
+        let signed = self
+            .sign(&context_id, method, &args_json, ttl)
+            .map_err(value_error)?;
+        let value = serde_json::to_value(&signed).map_err(|e| value_error(e.into()))?;
+        Ok(json_to_python(py, &value))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Signer(public_key='{}')", self.public_key())
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed(expires_at: i64) -> SignedExecution {
+        let key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+        let context_id = bs58::encode([1u8; 32]).into_string().parse().unwrap();
+        SignedExecution::sign(
+            &key,
+            &context_id,
+            "set",
+            r#"{"k":1}"#,
+            [3u8; 16],
+            expires_at,
+        )
+    }
+
+    /// Envelopes verify until expiry and break when any field changes.
+    #[test]
+    fn test_sign_and_verify() {
+        let envelope = signed(1_000);
+        assert!(envelope.verify(999).is_ok());
+        assert!(envelope.verify(1_000).is_err());
+
+        let mut tampered = envelope.clone();
+        tampered.args_json = r#"{"k":2}"#.to_string();
+        assert!(tampered.verify(999).is_err());
+
+        let guard = ReplayGuard::default();
+        assert!(guard.admit(&envelope, 999).is_ok());
+        assert!(guard.admit(&envelope, 999).is_err());
+    }
+
+    /// Key files are read as raw bytes, hex, NEAR strings or JSON.
+    #[test]
+    fn test_parse_key_file() {
+        let seed = [5u8; 32];
+        assert_eq!(*parse_key_file(&seed).unwrap(), seed.to_vec());
+        assert_eq!(
+            *parse_key_file(format!("{}\n", hex::encode(seed)).as_bytes()).unwrap(),
+            seed.to_vec()
+        );
+        let near = format!("ed25519:{}", bs58::encode(seed).into_string());
+        assert_eq!(*parse_key_file(near.as_bytes()).unwrap(), seed.to_vec());
+        let json = serde_json::json!({"account_id": "a.near", "private_key": near});
+        assert_eq!(
+            *parse_key_file(json.to_string().as_bytes()).unwrap(),
+            seed.to_vec()
+        );
+    }
+}
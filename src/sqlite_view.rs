@@ -0,0 +1,248 @@
+//! SQLite materialized views of context events and state.
+//!
+//! `SqliteView` mirrors events (and the key/value updates they carry) into a
+//! local SQLite database so they can be queried with plain SQL. It is
+//! designed to be driven by `calimero.projections.Projection`, which supplies
+//! ordering and checkpointing; this type only owns the database.
+//!
+//! ## Schema
+//! ```sql
+//! events(seq INTEGER PRIMARY KEY, context_id TEXT, kind TEXT, payload TEXT, recorded_at INTEGER)
+//! state(context_id TEXT, key TEXT, value TEXT, updated_at INTEGER, PRIMARY KEY (context_id, key))
+//! ```
+//! `payload` and `value` hold JSON text, queryable with SQLite's JSON1
+//! functions (`json_extract(payload, '$.field')`).
+
+use std::sync::Mutex;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::Connection;
+
+use crate::utils::{python_to_json, str_field};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS events (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        context_id TEXT,
+        kind TEXT,
+        payload TEXT NOT NULL,
+        recorded_at INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS events_context_kind ON events (context_id, kind);
+    CREATE TABLE IF NOT EXISTS state (
+        context_id TEXT NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (context_id, key)
+    );
+";
+
+fn sql_err(e: rusqlite::Error) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("SQLite error: {}", e))
+}
+
+/// Which parts of an event stream a view mirrors.
+#[derive(Debug, Clone, Default)]
+pub struct ViewFilter {
+    /// Only events whose `kind` is listed; `None` keeps every kind.
+    pub event_kinds: Option<Vec<String>>,
+    /// Only state keys starting with one of these; empty keeps every key.
+    pub key_prefixes: Vec<String>,
+}
+
+impl ViewFilter {
+    pub fn accepts_kind(&self, kind: Option<&str>) -> bool {
+        match (&self.event_kinds, kind) {
+            (None, _) => true,
+            (Some(kinds), Some(kind)) => kinds.iter().any(|k| k == kind),
+            (Some(_), None) => false,
+        }
+    }
+
+    pub fn accepts_key(&self, key: &str) -> bool {
+        self.key_prefixes.is_empty() || self.key_prefixes.iter().any(|p| key.starts_with(p))
+    }
+}
+
+/// Python-facing SQLite materialized view.
+#[pyclass(name = "SqliteView")]
+pub struct PySqliteView {
+    conn: Mutex<Connection>,
+    filter: ViewFilter,
+}
+
+impl PySqliteView {
+    fn with_conn<T>(&self, f: impl FnOnce(&mut Connection) -> PyResult<T>) -> PyResult<T> {
+        let mut conn = self.conn.lock().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("SQLite connection lock poisoned")
+        })?;
+        f(&mut conn)
+    }
+}
+
+#[pymethods]
+impl PySqliteView {
+    /// Open (or create) the database at `path`. Use `":memory:"` for a
+    /// throwaway view.
+    #[new]
+    #[pyo3(signature = (path, event_kinds=None, key_prefixes=None))]
+    pub fn new(
+        path: &str,
+        event_kinds: Option<Vec<String>>,
+        key_prefixes: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        let conn = Connection::open(path).map_err(sql_err)?;
+        conn.execute_batch(SCHEMA).map_err(sql_err)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            filter: ViewFilter {
+                event_kinds,
+                key_prefixes: key_prefixes.unwrap_or_default(),
+            },
+        })
+    }
+
+    /// Mirror one event. Returns whether it passed the view's filters.
+    ///
+    /// The event (a dict) is stored in `events`. When it carries a `key`,
+    /// the `state` row for `(contextId, key)` is upserted with its `value`,
+    /// or deleted when `deleted` is true.
+    pub fn apply(&self, event: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let event = python_to_json(event)?;
+        let kind = str_field(&event, &["kind", "type"]).map(str::to_owned);
+        if !self.filter.accepts_kind(kind.as_deref()) {
+            return Ok(false);
+        }
+        let context_id = str_field(&event, &["contextId", "context_id"]).map(str::to_owned);
+        let key = str_field(&event, &["key"]).map(str::to_owned);
+        let now = chrono::Utc::now().timestamp();
+
+        self.with_conn(|conn| {
+            let tx = conn.transaction().map_err(sql_err)?;
+            tx.execute(
+                "INSERT INTO events (context_id, kind, payload, recorded_at) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![context_id, kind, event.to_string(), now],
+            )
+            .map_err(sql_err)?;
+
+            if let Some(key) = key.as_deref().filter(|k| self.filter.accepts_key(k)) {
+                let context_id = context_id.clone().unwrap_or_default();
+                let deleted = event.get("deleted").and_then(|v| v.as_bool()) == Some(true);
+                if deleted {
+                    tx.execute(
+                        "DELETE FROM state WHERE context_id = ?1 AND key = ?2",
+                        rusqlite::params![context_id, key],
+                    )
+                    .map_err(sql_err)?;
+                } else {
+                    let value = event.get("value").map(|v| v.to_string());
+                    tx.execute(
+                        "INSERT INTO state (context_id, key, value, updated_at) VALUES (?1, ?2, ?3, ?4)
+                         ON CONFLICT (context_id, key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+                        rusqlite::params![context_id, key, value, now],
+                    )
+                    .map_err(sql_err)?;
+                }
+            }
+            tx.commit().map_err(sql_err)?;
+            Ok(true)
+        })
+    }
+
+    /// Run a read query and return rows as a list of dicts.
+    ///
+    /// Statements that could modify the database are rejected with
+    /// `ValueError`; the view is only written through `apply` and `clear`.
+    #[pyo3(signature = (sql, params=None))]
+    pub fn query(
+        &self,
+        py: Python<'_>,
+        sql: &str,
+        params: Option<&Bound<'_, PyList>>,
+    ) -> PyResult<PyObject> {
+        let params: Vec<SqlValue> = match params {
+            Some(list) => list
+                .iter()
+                .map(|item| match python_to_json(&item)? {
+                    serde_json::Value::Null => Ok(SqlValue::Null),
+                    serde_json::Value::Bool(b) => Ok(SqlValue::Integer(b as i64)),
+                    serde_json::Value::Number(n) => Ok(n
+                        .as_i64()
+                        .map(SqlValue::Integer)
+                        .unwrap_or_else(|| SqlValue::Real(n.as_f64().unwrap_or_default()))),
+                    serde_json::Value::String(s) => Ok(SqlValue::Text(s)),
+                    other => Ok(SqlValue::Text(other.to_string())),
+                })
+                .collect::<PyResult<_>>()?,
+            None => Vec::new(),
+        };
+
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(sql).map_err(sql_err)?;
+            if !stmt.readonly() {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "SqliteView.query only runs read-only statements",
+                ));
+            }
+            let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+            let mut rows = stmt
+                .query(rusqlite::params_from_iter(params.iter()))
+                .map_err(sql_err)?;
+
+            let out = PyList::empty_bound(py);
+            while let Some(row) = rows.next().map_err(sql_err)? {
+                let dict = PyDict::new_bound(py);
+                for (index, column) in columns.iter().enumerate() {
+                    let value = match row.get_ref(index).map_err(sql_err)? {
+                        ValueRef::Null => py.None(),
+                        ValueRef::Integer(i) => i.into_py(py),
+                        ValueRef::Real(f) => f.into_py(py),
+                        ValueRef::Text(t) => String::from_utf8_lossy(t).into_py(py),
+                        ValueRef::Blob(b) => PyBytes::new_bound(py, b).into_py(py),
+                    };
+                    dict.set_item(column, value)?;
+                }
+                out.append(dict)?;
+            }
+            Ok(out.into_py(py))
+        })
+    }
+
+    /// Remove every mirrored event and state row.
+    pub fn clear(&self) -> PyResult<()> {
+        self.with_conn(|conn| {
+            conn.execute_batch("DELETE FROM events; DELETE FROM state;")
+                .map_err(sql_err)
+        })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Kind and key filters behave as documented.
+    #[test]
+    fn test_view_filter() {
+        let all = ViewFilter::default();
+        assert!(all.accepts_kind(None));
+        assert!(all.accepts_key("anything"));
+
+        let filtered = ViewFilter {
+            event_kinds: Some(vec!["StateMutation".to_string()]),
+            key_prefixes: vec!["votes/".to_string()],
+        };
+        assert!(filtered.accepts_kind(Some("StateMutation")));
+        assert!(!filtered.accepts_kind(Some("ExecutionEvent")));
+        assert!(!filtered.accepts_kind(None));
+        assert!(filtered.accepts_key("votes/alice"));
+        assert!(!filtered.accepts_key("profiles/alice"));
+    }
+}
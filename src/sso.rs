@@ -0,0 +1,382 @@
+//! Browser login through an upstream identity provider (SSO).
+//!
+//! The node's own login page only knows the providers its auth service was
+//! built with. For teams behind enterprise SSO the client runs the OAuth 2.0
+//! authorization-code flow with PKCE against the identity provider itself —
+//! a generic OIDC issuer (via its discovery document) or one of the presets
+//! in [`Provider::preset`] — catches the redirect on a loopback listener, and
+//! exchanges the resulting ID/access token for node JWTs at the auth
+//! service's `auth/token` endpoint. The auth service must have a matching
+//! provider enabled to accept that exchange.
+
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use calimero_client::JwtToken;
+use eyre::WrapErr;
+use reqwest::header::ACCEPT;
+use reqwest::Method;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use url::Url;
+use zeroize::Zeroizing;
+
+use crate::auth_metadata;
+use crate::auth_service;
+use crate::entropy;
+use crate::http;
+use crate::storage::MeroboxFileStorage;
+
+/// Auth-service endpoint exchanging an upstream identity for node tokens.
+pub const TOKEN_EXCHANGE_PATH: &str = "auth/token";
+
+/// Default time allowed for the user to finish logging in (seconds).
+pub const DEFAULT_LOGIN_TIMEOUT_SECS: u64 = 300;
+
+/// Path the identity provider redirects back to on the loopback listener.
+const CALLBACK_PATH: &str = "/callback";
+
+/// Largest callback request read from the browser.
+const MAX_CALLBACK_REQUEST: usize = 16 * 1024;
+
+/// Endpoints and scopes of an identity provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provider {
+    /// Name sent to the auth service as the login method (`oidc`, `github`…).
+    pub name: String,
+    pub authorization_endpoint: Url,
+    pub token_endpoint: Url,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscoveryDocument {
+    authorization_endpoint: Url,
+    token_endpoint: Url,
+}
+
+impl Provider {
+    /// Built-in provider that needs no discovery, if `name` is one.
+    ///
+    /// GitHub is plain OAuth 2.0 (no ID token); the auth service verifies
+    /// its access token instead.
+    pub fn preset(name: &str) -> Option<Self> {
+        let (authorization, token, scopes): (&str, &str, &[&str]) = match name {
+            "github" => (
+                "https://github.com/login/oauth/authorize",
+                "https://github.com/login/oauth/access_token",
+                &["read:user", "user:email"],
+            ),
+            "google" => (
+                "https://accounts.google.com/o/oauth2/v2/auth",
+                "https://oauth2.googleapis.com/token",
+                &["openid", "email", "profile"],
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            name: name.to_string(),
+            authorization_endpoint: Url::parse(authorization).ok()?,
+            token_endpoint: Url::parse(token).ok()?,
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+        })
+    }
+
+    /// Generic OIDC provider from `issuer`'s discovery document, which is
+    /// cached on disk by [`auth_metadata::DocumentCache`].
+    pub async fn discover(client: &reqwest::Client, issuer: &Url) -> eyre::Result<Self> {
+        let url = discovery_url(issuer)?;
+        let document = auth_metadata::DocumentCache::default()
+            .fetch(client, &url, false)
+            .await
+            .wrap_err_with(|| format!("OIDC discovery at {} failed", url))?;
+        let document: DiscoveryDocument =
+            serde_json::from_value(document).wrap_err("Invalid OIDC discovery document")?;
+        Ok(Self {
+            name: "oidc".to_string(),
+            authorization_endpoint: document.authorization_endpoint,
+            token_endpoint: document.token_endpoint,
+            scopes: vec![
+                "openid".to_string(),
+                "email".to_string(),
+                "profile".to_string(),
+            ],
+        })
+    }
+}
+
+/// `{issuer}/.well-known/openid-configuration`, keeping any issuer path.
+fn discovery_url(issuer: &Url) -> eyre::Result<Url> {
+    let base = issuer.as_str().trim_end_matches('/');
+    Url::parse(&format!("{}/.well-known/openid-configuration", base))
+        .wrap_err("Invalid OIDC issuer URL")
+}
+
+/// PKCE verifier/challenge pair (RFC 7636, `S256`) plus the CSRF `state`.
+pub struct Pkce {
+    pub verifier: Zeroizing<String>,
+    pub challenge: String,
+    pub state: String,
+}
+
+impl Pkce {
+    pub fn generate() -> eyre::Result<Self> {
+        let verifier = Zeroizing::new(URL_SAFE_NO_PAD.encode(entropy::random_bytes::<32>()?));
+        let state = URL_SAFE_NO_PAD.encode(entropy::random_bytes::<16>()?);
+        Ok(Self {
+            challenge: challenge_for(&verifier),
+            verifier,
+            state,
+        })
+    }
+}
+
+fn challenge_for(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// URL the user's browser is sent to.
+pub fn authorization_url(
+    provider: &Provider,
+    client_id: &str,
+    redirect_uri: &str,
+    pkce: &Pkce,
+) -> Url {
+    let mut url = provider.authorization_endpoint.clone();
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("scope", &provider.scopes.join(" "))
+        .append_pair("state", &pkce.state)
+        .append_pair("code_challenge", &pkce.challenge)
+        .append_pair("code_challenge_method", "S256");
+    url
+}
+
+/// Loopback listener for the provider's redirect, on an ephemeral port.
+pub async fn bind_callback() -> eyre::Result<(TcpListener, String)> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .wrap_err("Failed to open the login callback listener")?;
+    let port = listener.local_addr()?.port();
+    Ok((
+        listener,
+        format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH),
+    ))
+}
+
+/// Authorization code from a callback request target, checking `state`.
+///
+/// `Ok(None)` for requests that are not the callback (e.g. `/favicon.ico`).
+fn code_from_target(target: &str, expected_state: &str) -> eyre::Result<Option<String>> {
+    let url = Url::parse("http://127.0.0.1")
+        .and_then(|base| base.join(target))
+        .wrap_err("Malformed callback request")?;
+    if url.path() != CALLBACK_PATH {
+        return Ok(None);
+    }
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    if let Some(error) = param("error") {
+        let description = param("error_description").unwrap_or_default();
+        eyre::bail!(
+            "Identity provider refused the login: {} {}",
+            error,
+            description
+        );
+    }
+    if param("state").as_deref() != Some(expected_state) {
+        eyre::bail!("Login callback state mismatch; the login may have been tampered with");
+    }
+    param("code")
+        .map(Some)
+        .ok_or_else(|| eyre::eyre!("Login callback is missing the authorization code"))
+}
+
+/// Wait for the browser to land on the callback and return the code.
+pub async fn wait_for_code(
+    listener: &TcpListener,
+    expected_state: &str,
+    timeout: Duration,
+) -> eyre::Result<String> {
+    tokio::time::timeout(timeout, async {
+        loop {
+            let (mut stream, _) = listener
+                .accept()
+                .await
+                .wrap_err("Login callback listener failed")?;
+            let mut request = vec![0u8; MAX_CALLBACK_REQUEST];
+            let read = stream.read(&mut request).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request[..read]);
+            let target = request
+                .lines()
+                .next()
+                .and_then(|line| line.split_whitespace().nth(1))
+                .unwrap_or("/");
+
+            let outcome = code_from_target(target, expected_state);
+            let (status, body) = match &outcome {
+                Ok(Some(_)) => ("200 OK", "Login complete. You can close this window."),
+                Ok(None) => ("404 Not Found", "Not found."),
+                Err(_) => ("400 Bad Request", "Login failed. Return to the application."),
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+
+            if let Some(code) = outcome? {
+                return Ok(code);
+            }
+        }
+    })
+    .await
+    .map_err(|_| eyre::eyre!("Timed out waiting for the browser login to finish"))?
+}
+
+/// Upstream tokens returned by the provider's token endpoint.
+pub struct UpstreamTokens {
+    pub id_token: Option<Zeroizing<String>>,
+    pub access_token: Zeroizing<String>,
+}
+
+/// Redeem `code` at the provider's token endpoint.
+pub async fn exchange_code(
+    client: &reqwest::Client,
+    provider: &Provider,
+    client_id: &str,
+    code: &str,
+    redirect_uri: &str,
+    pkce: &Pkce,
+) -> eyre::Result<UpstreamTokens> {
+    let response: serde_json::Value = client
+        .post(provider.token_endpoint.clone())
+        .header(ACCEPT, "application/json")
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("client_id", client_id),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", pkce.verifier.as_str()),
+        ])
+        .send()
+        .await
+        .wrap_err_with(|| format!("Request to {} failed", provider.token_endpoint))?
+        .json()
+        .await
+        .wrap_err("Invalid token response from the identity provider")?;
+
+    if let Some(error) = response.get("error").and_then(|e| e.as_str()) {
+        eyre::bail!("Identity provider rejected the code: {}", error);
+    }
+    let field = |name: &str| {
+        response
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(|v| Zeroizing::new(v.to_string()))
+    };
+    Ok(UpstreamTokens {
+        id_token: field("id_token"),
+        access_token: field("access_token")
+            .ok_or_else(|| eyre::eyre!("Identity provider returned no access token"))?,
+    })
+}
+
+/// Trade the upstream identity for node tokens at the auth service.
+pub async fn exchange_for_node_tokens(
+    client: &reqwest::Client,
+    api_url: &Url,
+    storage: &MeroboxFileStorage,
+    provider: &Provider,
+    upstream: &UpstreamTokens,
+    limit: Option<usize>,
+) -> eyre::Result<JwtToken> {
+    let body = serde_json::json!({
+        "auth_method": provider.name,
+        "client_name": "calimero-client-py",
+        "timestamp": chrono::Utc::now().timestamp(),
+        "provider_data": {
+            "id_token": upstream.id_token.as_deref().map(String::as_str),
+            "access_token": upstream.access_token.as_str(),
+        },
+    });
+    let response = http::send_json(
+        client,
+        Method::POST,
+        api_url,
+        TOKEN_EXCHANGE_PATH,
+        storage,
+        None,
+        Some(&body),
+        limit,
+    )
+    .await
+    .wrap_err_with(|| {
+        format!(
+            "The node's auth service did not accept the '{}' login",
+            provider.name
+        )
+    })?;
+    auth_service::parse_tokens(&response, None, chrono::Utc::now().timestamp())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The S256 challenge matches the RFC 7636 appendix B example.
+    #[test]
+    fn test_pkce_challenge() {
+        assert_eq!(
+            challenge_for("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    /// Discovery keeps issuer paths (e.g. Keycloak realms).
+    #[test]
+    fn test_discovery_url() {
+        let issuer = Url::parse("https://sso.example.com/realms/team/").unwrap();
+        assert_eq!(
+            discovery_url(&issuer).unwrap().as_str(),
+            "https://sso.example.com/realms/team/.well-known/openid-configuration"
+        );
+    }
+
+    /// The callback yields the code only with the expected state.
+    #[test]
+    fn test_code_from_target() {
+        assert_eq!(
+            code_from_target("/callback?code=abc&state=s1", "s1").unwrap(),
+            Some("abc".to_string())
+        );
+        assert_eq!(code_from_target("/favicon.ico", "s1").unwrap(), None);
+        assert!(code_from_target("/callback?code=abc&state=other", "s1").is_err());
+        assert!(code_from_target("/callback?error=access_denied&state=s1", "s1").is_err());
+    }
+
+    /// Presets exist for the common providers only.
+    #[test]
+    fn test_presets() {
+        assert_eq!(Provider::preset("github").unwrap().name, "github");
+        assert!(Provider::preset("google")
+            .unwrap()
+            .scopes
+            .contains(&"openid".to_string()));
+        assert!(Provider::preset("myspace").is_none());
+    }
+}
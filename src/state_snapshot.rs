@@ -0,0 +1,459 @@
+//! Snapshots of everything the client keeps locally, for backups and moves.
+//!
+//! `calimero.snapshot(path)` packs the client's local state into one
+//! gzipped JSON archive, and `calimero.restore(path)` unpacks it on another
+//! machine (or the same one after a wipe):
+//!
+//! - the state root: token files, cached auth modes, blob sidecars,
+//!   projection checkpoints and the layout version;
+//! - the token cache directory, when `MEROBOX_CACHE_DIR` moves it out of
+//!   the state root;
+//! - the offline outboxes of the clients passed in, which only live in
+//!   memory.
+//!
+//! Files are stored by their path relative to their root, and restored
+//! under the roots of the machine restoring them. Temp, lock and
+//! `.corrupt` files are left out. The archive holds credentials: it is
+//! written owner-only, and with a passphrase its contents are sealed with
+//! [`crate::crypto`] like a token bundle.
+//!
+//! ```text
+//! {"format": "calimero-state-snapshot", "version": 1, "createdAt": ...,
+//!  "contents": {"sections": {"state"|"tokens": {path: "<base64>"}},
+//!               "outboxes": {api_url: [entry]}}}                  // plaintext
+//!  "encryption": {"algorithm", "kdf", "salt"}, "payload": "<base64>"  // sealed
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use eyre::WrapErr;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use pyo3::prelude::*;
+use serde_json::{json, Map, Value};
+use zeroize::Zeroizing;
+
+use crate::cache::{get_cache_base_dir, state_root};
+use crate::client::PyClient;
+use crate::crypto;
+use crate::entropy;
+use crate::outbox::OutboxEntry;
+use crate::permissions::restrict_to_owner;
+use crate::utils::json_to_python;
+
+/// `format` field identifying a state snapshot.
+pub const SNAPSHOT_FORMAT: &str = "calimero-state-snapshot";
+
+/// Current snapshot version.
+pub const SNAPSHOT_VERSION: u64 = 1;
+
+/// Key derivation recorded for sealed snapshots.
+const SNAPSHOT_KDF: &str = "pbkdf2-hmac-sha256";
+
+/// Files of a section, by path relative to its root (`/`-separated).
+pub type Files = BTreeMap<String, Vec<u8>>;
+
+/// Whether a file is left out of snapshots: in-flight temp files, lock
+/// files and files set aside by `storage_doctor()`.
+fn skipped(name: &str) -> bool {
+    name.ends_with(".tmp") || name.ends_with(".lock") || name.ends_with(".corrupt")
+}
+
+/// Regular files under `root`, recursively. Symlinks are not followed.
+pub fn collect(root: &Path) -> eyre::Result<Files> {
+    fn walk(root: &Path, dir: &Path, files: &mut Files) -> eyre::Result<()> {
+        let entries = fs::read_dir(dir).wrap_err_with(|| format!("Failed to read {:?}", dir))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(kind) = entry.file_type() else {
+                continue;
+            };
+            if kind.is_dir() {
+                walk(root, &path, files)?;
+            } else if kind.is_file() && !skipped(&entry.file_name().to_string_lossy()) {
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                let key = relative
+                    .components()
+                    .map(|part| part.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let data =
+                    fs::read(&path).wrap_err_with(|| format!("Failed to read {:?}", path))?;
+                files.insert(key, data);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Files::new();
+    if root.is_dir() {
+        walk(root, root, &mut files)?;
+    }
+    Ok(files)
+}
+
+/// `relative` under `root`, refusing paths that would leave it.
+fn target(root: &Path, relative: &str) -> eyre::Result<PathBuf> {
+    let path = Path::new(relative);
+    if relative.is_empty()
+        || !path
+            .components()
+            .all(|part| matches!(part, Component::Normal(_)))
+    {
+        eyre::bail!("Snapshot holds an invalid path '{}'", relative);
+    }
+    Ok(root.join(path))
+}
+
+/// Write `files` under `root`, owner-only. Files that exist already are
+/// kept unless `overwrite`. Returns the paths written and skipped.
+pub fn restore_files(
+    root: &Path,
+    files: &Files,
+    overwrite: bool,
+) -> eyre::Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let mut written = Vec::new();
+    let mut kept = Vec::new();
+    for (relative, data) in files {
+        let path = target(root, relative)?;
+        if path.exists() && !overwrite {
+            kept.push(path);
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .wrap_err_with(|| format!("Failed to create directory {:?}", parent))?;
+            restrict_to_owner(parent, true)?;
+        }
+        write_private(&path, data)?;
+        written.push(path);
+    }
+    Ok((written, kept))
+}
+
+/// Write `data` to `path` through a temp file, owner-only.
+fn write_private(path: &Path, data: &[u8]) -> eyre::Result<()> {
+    let temp_path = path.with_extension(format!("{}.tmp", entropy::temp_suffix()));
+    let written = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        restrict_to_owner(&temp_path, false)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok::<_, eyre::Report>(())
+    })();
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written.wrap_err_with(|| format!("Failed to write {:?}", path))
+}
+
+fn encode_files(files: &Files) -> Value {
+    Value::Object(
+        files
+            .iter()
+            .map(|(path, data)| (path.clone(), Value::String(STANDARD.encode(data))))
+            .collect(),
+    )
+}
+
+fn decode_files(files: &Value) -> eyre::Result<Files> {
+    let Some(files) = files.as_object() else {
+        return Ok(Files::new());
+    };
+    files
+        .iter()
+        .map(|(path, data)| {
+            let data = STANDARD
+                .decode(data.as_str().unwrap_or_default())
+                .wrap_err_with(|| format!("Invalid data for '{}' in snapshot", path))?;
+            Ok((path.clone(), data))
+        })
+        .collect()
+}
+
+/// Gzipped archive of `contents`, sealed under `passphrase` when given.
+pub fn build(contents: &Value, passphrase: Option<&str>) -> eyre::Result<Vec<u8>> {
+    let mut document = json!({
+        "format": SNAPSHOT_FORMAT,
+        "version": SNAPSHOT_VERSION,
+        "createdAt": chrono::Utc::now().to_rfc3339(),
+    });
+    match passphrase {
+        Some(passphrase) => {
+            let salt: [u8; 16] = entropy::random_bytes()?;
+            let key = crypto::derive_key(passphrase.as_bytes(), &salt);
+            let plaintext = Zeroizing::new(serde_json::to_vec(contents)?);
+            let sealed = crypto::seal(key.as_slice(), &plaintext)?;
+            document["encryption"] = json!({
+                "algorithm": crypto::ENVELOPE_ALGORITHM,
+                "kdf": SNAPSHOT_KDF,
+                "salt": STANDARD.encode(salt),
+            });
+            document["payload"] = Value::String(STANDARD.encode(sealed));
+        }
+        None => document["contents"] = contents.clone(),
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&serde_json::to_vec(&document)?)?;
+    Ok(encoder.finish()?)
+}
+
+/// Contents of the archive `data`, opening it with `passphrase` when it is
+/// sealed.
+pub fn open(data: &[u8], passphrase: Option<&str>) -> eyre::Result<Value> {
+    let mut json = Zeroizing::new(Vec::new());
+    GzDecoder::new(data)
+        .read_to_end(&mut json)
+        .wrap_err("Not a state snapshot (not gzip data)")?;
+    let document: Value = serde_json::from_slice(&json).wrap_err("Snapshot is not JSON")?;
+    if document["format"] != SNAPSHOT_FORMAT {
+        eyre::bail!(
+            "Not a state snapshot (missing format \"{}\")",
+            SNAPSHOT_FORMAT
+        );
+    }
+    let version = document["version"].as_u64().unwrap_or(0);
+    if version == 0 || version > SNAPSHOT_VERSION {
+        eyre::bail!(
+            "Unsupported snapshot version {} (this release reads up to {})",
+            document["version"],
+            SNAPSHOT_VERSION
+        );
+    }
+    let encryption = &document["encryption"];
+    if encryption.is_null() {
+        return Ok(document["contents"].clone());
+    }
+    if encryption["algorithm"] != crypto::ENVELOPE_ALGORITHM || encryption["kdf"] != SNAPSHOT_KDF {
+        eyre::bail!("Unsupported snapshot encryption: {}", encryption);
+    }
+    let Some(passphrase) = passphrase else {
+        eyre::bail!("Snapshot is encrypted; pass its passphrase");
+    };
+    let salt = STANDARD
+        .decode(encryption["salt"].as_str().unwrap_or_default())
+        .wrap_err("Invalid snapshot salt")?;
+    let sealed = STANDARD
+        .decode(document["payload"].as_str().unwrap_or_default())
+        .wrap_err("Invalid snapshot payload")?;
+    let key = crypto::derive_key(passphrase.as_bytes(), &salt);
+    let plaintext = Zeroizing::new(
+        crypto::open(key.as_slice(), &sealed)
+            .wrap_err("Failed to open snapshot: wrong passphrase or corrupted data")?,
+    );
+    serde_json::from_slice(&plaintext).wrap_err("Snapshot payload is not JSON")
+}
+
+/// Directories snapshotted, by section name. The token cache directory is
+/// its own section only when it lies outside the state root.
+fn sections() -> Vec<(&'static str, PathBuf)> {
+    let state = state_root();
+    let tokens = get_cache_base_dir();
+    let mut sections = vec![("state", state.clone())];
+    if !tokens.starts_with(&state) {
+        sections.push(("tokens", tokens));
+    }
+    sections
+}
+
+fn snapshot_error(e: eyre::Report) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e))
+}
+
+fn io_error(e: eyre::Report) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e))
+}
+
+/// Write a snapshot of the client's local state to `path`.
+///
+/// Captures the state root (tokens, cached auth modes, blob sidecars,
+/// projection checkpoints), a relocated token cache directory, and the
+/// outbox of each client in `clients`. With a `passphrase` the archive's
+/// contents are encrypted. Returns `{path, files, outboxEntries}`.
+#[pyfunction]
+#[pyo3(signature = (path, clients=None, passphrase=None))]
+pub fn snapshot(
+    py: Python<'_>,
+    path: PathBuf,
+    clients: Option<Vec<PyRef<'_, PyClient>>>,
+    passphrase: Option<&str>,
+) -> PyResult<PyObject> {
+    let mut sections_json = Map::new();
+    let mut file_count = 0;
+    for (name, root) in sections() {
+        let files = collect(&root).map_err(io_error)?;
+        file_count += files.len();
+        sections_json.insert(name.to_string(), encode_files(&files));
+    }
+    let mut outboxes = Map::new();
+    let mut entry_count = 0;
+    for client in clients.iter().flatten() {
+        let entries: Vec<Value> = client
+            .outbox_entries()
+            .iter()
+            .map(OutboxEntry::to_json)
+            .collect();
+        entry_count += entries.len();
+        outboxes.insert(client.get_api_url(), Value::Array(entries));
+    }
+    let contents = json!({"sections": sections_json, "outboxes": outboxes});
+    let archive = build(&contents, passphrase).map_err(snapshot_error)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("Failed to create {:?}", parent))
+            .map_err(io_error)?;
+    }
+    write_private(&path, &archive).map_err(io_error)?;
+    Ok(json_to_python(
+        py,
+        &json!({
+            "path": path.to_string_lossy(),
+            "files": file_count,
+            "outboxEntries": entry_count,
+        }),
+    ))
+}
+
+/// Restore a snapshot written by `snapshot()`.
+///
+/// Files are written under this machine's state and token cache
+/// directories; ones that already exist are kept unless
+/// `overwrite=True`. Outbox entries are queued again on the client in
+/// `clients` with the same API URL. Returns `{restored, skipped,
+/// outboxEntries, unmatchedOutboxes}`.
+#[pyfunction]
+#[pyo3(signature = (path, clients=None, passphrase=None, overwrite=false))]
+pub fn restore(
+    py: Python<'_>,
+    path: PathBuf,
+    clients: Option<Vec<PyRef<'_, PyClient>>>,
+    passphrase: Option<&str>,
+    overwrite: bool,
+) -> PyResult<PyObject> {
+    let data = fs::read(&path)
+        .wrap_err_with(|| format!("Failed to read {:?}", path))
+        .map_err(io_error)?;
+    let contents = open(&data, passphrase).map_err(snapshot_error)?;
+
+    let mut restored = Vec::new();
+    let mut skipped = Vec::new();
+    let roots = sections();
+    for (name, files) in contents["sections"].as_object().into_iter().flatten() {
+        // A relocated token section restores into the state root's
+        // token directory when this machine keeps tokens there.
+        let root = match roots.iter().find(|(section, _)| *section == name.as_str()) {
+            Some((_, root)) => root.clone(),
+            None if name == "tokens" => get_cache_base_dir(),
+            None => continue,
+        };
+        let files = decode_files(files).map_err(snapshot_error)?;
+        let (written, kept) = restore_files(&root, &files, overwrite).map_err(io_error)?;
+        restored.extend(written);
+        skipped.extend(kept);
+    }
+
+    let mut entry_count = 0;
+    let mut unmatched = Vec::new();
+    for (api_url, entries) in contents["outboxes"].as_object().into_iter().flatten() {
+        let Some(client) = clients
+            .iter()
+            .flatten()
+            .find(|client| client.get_api_url() == *api_url)
+        else {
+            unmatched.push(api_url.clone());
+            continue;
+        };
+        let entries: Vec<Value> = entries.as_array().cloned().unwrap_or_default();
+        entry_count += entries.len();
+        client.restore_outbox(&entries).map_err(snapshot_error)?;
+    }
+
+    let paths = |paths: Vec<PathBuf>| -> Vec<String> {
+        paths
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect()
+    };
+    Ok(json_to_python(
+        py,
+        &json!({
+            "restored": paths(restored),
+            "skipped": paths(skipped),
+            "outboxEntries": entry_count,
+            "unmatchedOutboxes": unmatched,
+        }),
+    ))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("state-snapshot-{}-{}", name, std::process::id()))
+    }
+
+    /// Files round-trip through collect and restore; temp files stay out.
+    #[test]
+    fn test_collect_and_restore() {
+        let source = temp_dir("source");
+        fs::create_dir_all(source.join("auth_cache")).unwrap();
+        fs::write(source.join("auth_cache").join("node.json"), b"{}").unwrap();
+        fs::write(source.join("auth_cache").join("node.json.ab12.tmp"), b"").unwrap();
+        fs::write(source.join("layout_version.json"), b"{\"version\":1}").unwrap();
+
+        let files = collect(&source).unwrap();
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            ["auth_cache/node.json", "layout_version.json"]
+        );
+
+        let target = temp_dir("target");
+        let (written, kept) = restore_files(&target, &files, false).unwrap();
+        assert_eq!((written.len(), kept.len()), (2, 0));
+        assert_eq!(
+            fs::read(target.join("auth_cache").join("node.json")).unwrap(),
+            b"{}"
+        );
+        let (written, kept) = restore_files(&target, &files, false).unwrap();
+        assert_eq!((written.len(), kept.len()), (0, 2));
+
+        fs::remove_dir_all(&source).unwrap();
+        fs::remove_dir_all(&target).unwrap();
+    }
+
+    /// Paths leaving the restore root are refused.
+    #[test]
+    fn test_restore_refuses_escaping_paths() {
+        let root = temp_dir("escape");
+        for path in ["../evil", "/etc/evil", "a/../../evil", ""] {
+            let files = Files::from([(path.to_string(), b"x".to_vec())]);
+            assert!(restore_files(&root, &files, true).is_err(), "{}", path);
+        }
+    }
+
+    /// Sealed archives open only with their passphrase.
+    #[test]
+    fn test_build_and_open() {
+        let contents = json!({"sections": {"state": {"a": "eA=="}}, "outboxes": {}});
+        let plain = build(&contents, None).unwrap();
+        assert_eq!(open(&plain, None).unwrap(), contents);
+
+        let sealed = build(&contents, Some("secret")).unwrap();
+        assert!(open(&sealed, None).is_err());
+        assert!(open(&sealed, Some("wrong")).is_err());
+        assert_eq!(open(&sealed, Some("secret")).unwrap(), contents);
+        assert!(open(b"not gzip", None).is_err());
+    }
+}
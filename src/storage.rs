@@ -1,17 +1,39 @@
 //! Disk-backed storage implementation for JWT tokens.
 //!
-//! Stores tokens in `~/.merobox/auth_cache/` with atomic writes and secure permissions.
+//! Stores tokens in `<state root>/auth_cache/` with atomic writes and secure permissions.
 //!
 //! ## Features
 //! - Atomic writes using temp file + rename pattern
-//! - Secure permissions (0700 for directory, 0600 for files on Unix)
+//! - Cross-process write locking, keeping the fresher token on a race
+//! - Secure permissions (0700 for directory, 0600 for files on Unix; an
+//!   owner-only DACL on Windows, see [`crate::permissions`])
 //! - Human-readable + collision-resistant filenames
 //! - Proper error handling with context
+//!
+//! With `storage="encrypted"` token files are sealed with ChaCha20-Poly1305
+//! (see [`crate::crypto`]) under a key derived from a passphrase (PBKDF2,
+//! with a salt in the cache directory) or, without one, a random machine key
+//! kept in the OS keychain. The key never sits next to the files it seals,
+//! so the tokens stay sealed when the cache directory alone is copied off the
+//! machine: backups, snapshots, a synced or shared home directory. It does
+//! not protect against code running as the same user, which can read the
+//! keychain (or be handed the passphrase) like the client does. Plaintext
+//! files left from before are read once and rewritten encrypted.
+//!
+//! With `storage="keyring"` tokens go to the OS keychain instead (see
+//! [`KeyringStorage`]). Applications that keep tokens elsewhere (Redis,
+//! Vault, their own database) pass a Python object implementing
+//! `ClientStorage`; the storage then forwards every call to it (see
+//! [`PythonStorage`]).
 
+use std::collections::HashMap;
 use std::fs;
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::PathBuf;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 #[cfg(unix)]
 use std::os::unix::fs::OpenOptionsExt;
@@ -19,8 +41,17 @@ use std::os::unix::fs::OpenOptionsExt;
 use calimero_client::traits::ClientStorage;
 use calimero_client::JwtToken;
 use eyre::WrapErr;
+use pyo3::prelude::*;
+use zeroize::Zeroize;
 
-use crate::cache::{get_cache_base_dir, get_token_cache_path_internal};
+use crate::cache::{get_cache_base_dir, namespace_dir, user_token_filename, validate_namespace};
+use crate::cache_admin;
+use crate::crypto;
+use crate::entropy;
+use crate::keychain::KeyringStorage;
+use crate::token_lifecycle::token_expiry;
+use crate::utils::{json_to_python, python_to_json};
+use crate::warnings::{self, WarningKind};
 
 /// Guard that ensures a temp file is cleaned up if the operation fails.
 /// The file is only removed if `commit()` is not called before drop.
@@ -55,22 +86,658 @@ impl Drop for TempFileGuard {
 }
 
 /// Disk-backed storage implementation for JWT tokens.
+///
+/// In anonymous mode the storage never touches disk: loads report no tokens
+/// and saves/removals are no-ops, so talking to open nodes creates no cache
+/// files. In ephemeral mode tokens live only in memory (see
+/// [`EphemeralTokens`]).
+///
+/// With a `user`, tokens are keyed by (node, user) instead of the node alone,
+/// so one process can hold sessions for several users of the same node.
+/// A `namespace` (a tenant) isolates a whole set of such sessions: token
+/// files go to the namespace's own directory (see [`namespace_dir`]) and
+/// other backends see keys prefixed with `"<namespace>::"`, or the
+/// namespace as a separate argument for a Python storage.
+///
+/// Token files go to [`get_cache_base_dir`] unless a `cache_dir` is set.
 #[derive(Clone)]
-pub struct MeroboxFileStorage;
+pub struct MeroboxFileStorage {
+    mode: StorageMode,
+    user: Option<Arc<str>>,
+    namespace: Option<Arc<str>>,
+    cache_dir: Option<Arc<Path>>,
+}
 
-impl MeroboxFileStorage {
+/// Machine key file older releases kept in the cache directory; moved to
+/// the keychain on first use.
+const LEGACY_MACHINE_KEY_FILE: &str = ".token_key";
+
+/// Keychain account prefix of the machine key sealing a cache directory's
+/// token files when no passphrase is given; the directory follows it.
+const MACHINE_KEY_ACCOUNT_PREFIX: &str = "token-key:";
+
+/// Salt for passphrase-derived token keys.
+const KEY_SALT_FILE: &str = ".token_salt";
+
+/// Separates the namespace from the rest of a key in shared stores.
+pub const NAMESPACE_SEPARATOR: &str = "::";
+
+#[derive(Clone)]
+enum StorageMode {
+    File,
+    /// Token files sealed under this key.
+    Encrypted(Arc<zeroize::Zeroizing<[u8; crypto::KEY_LEN]>>),
+    Anonymous,
+    Ephemeral(Arc<EphemeralTokens>),
+    /// Process-local store shared with a Python `MemoryStorage`.
+    Memory(Arc<EphemeralTokens>),
+    Keyring(KeyringStorage),
+    Python(Arc<PythonStorage>),
+}
+
+/// Base class for token storage implemented in Python.
+///
+/// Subclass it (or pass any object with the same three methods) as
+/// `create_connection(..., storage=...)`. Tokens are dicts with
+/// `access_token`, `refresh_token` and `expires_at`; `key` is the node name,
+/// or `"<node>/<user>"` for clients created with a `user`.
+///
+/// Clients created with a `namespace` pass it first:
+/// `save_tokens(namespace, key, tokens)`, `load_tokens(namespace, key)` and
+/// `remove_tokens(namespace, key)`, so an implementation serving tenants
+/// takes an optional leading `namespace` argument. `purge_namespace` is
+/// only called by `ConnectionInfo.purge_namespace()`.
+#[pyclass(name = "ClientStorage", subclass)]
+#[derive(Debug, Default)]
+pub struct PyClientStorage;
+
+#[pymethods]
+impl PyClientStorage {
+    #[new]
     pub fn new() -> Self {
         Self
     }
 
+    /// Store `tokens` under `key`, replacing any previous ones
+    pub fn save_tokens(&self, key: &str, tokens: PyObject) -> PyResult<()> {
+        let _ = (key, tokens);
+        Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+            "ClientStorage.save_tokens must be implemented",
+        ))
+    }
+
+    /// The tokens stored under `key`, or `None`
+    pub fn load_tokens(&self, key: &str) -> PyResult<Option<PyObject>> {
+        let _ = key;
+        Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+            "ClientStorage.load_tokens must be implemented",
+        ))
+    }
+
+    /// Delete the tokens stored under `key`, if any
+    pub fn remove_tokens(&self, key: &str) -> PyResult<()> {
+        let _ = key;
+        Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+            "ClientStorage.remove_tokens must be implemented",
+        ))
+    }
+
+    /// Delete every token stored for `namespace`, returning how many
+    pub fn purge_namespace(&self, namespace: &str) -> PyResult<usize> {
+        let _ = namespace;
+        Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+            "ClientStorage.purge_namespace must be implemented to purge namespaces",
+        ))
+    }
+}
+
+/// In-process token storage that never touches the filesystem.
+///
+/// Pass it as `create_connection(..., storage=MemoryStorage())` (or use
+/// `storage="memory"` for a private one) in CI jobs and short-lived scripts.
+/// One instance can back several connections, which then see each other's
+/// tokens; keys are the same as for `ClientStorage`, prefixed with
+/// `"<namespace>::"` for clients created with a `namespace`.
+#[pyclass(name = "MemoryStorage")]
+#[derive(Clone)]
+pub struct PyMemoryStorage {
+    tokens: Arc<EphemeralTokens>,
+}
+
+#[pymethods]
+impl PyMemoryStorage {
+    #[new]
+    pub fn new() -> Self {
+        Self {
+            tokens: Arc::new(EphemeralTokens::new(None)),
+        }
+    }
+
+    /// Store `tokens` (a dict like those `load_tokens` returns) under `key`
+    pub fn save_tokens(&self, key: &str, tokens: &Bound<'_, PyAny>) -> PyResult<()> {
+        let tokens: JwtToken = serde_json::from_value(python_to_json(tokens)?).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid tokens: {}", e))
+        })?;
+        if let Ok(mut held) = self.tokens.tokens.lock() {
+            if let Some(mut previous) = held.insert(key.to_string(), tokens) {
+                wipe_token(&mut previous);
+            }
+        }
+        Ok(())
+    }
+
+    /// The tokens stored under `key`, or `None`
+    pub fn load_tokens(&self, py: Python<'_>, key: &str) -> PyResult<Option<PyObject>> {
+        let Some(tokens) = self
+            .tokens
+            .tokens
+            .lock()
+            .ok()
+            .and_then(|held| held.get(key).cloned())
+        else {
+            return Ok(None);
+        };
+        let tokens = WipeOnDrop::new(tokens);
+        let value = serde_json::to_value(&*tokens).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to serialize tokens: {}",
+                e
+            ))
+        })?;
+        Ok(Some(json_to_python(py, &value)))
+    }
+
+    /// Delete the tokens stored under `key`, if any
+    pub fn remove_tokens(&self, key: &str) {
+        if let Ok(mut held) = self.tokens.tokens.lock() {
+            if let Some(mut token) = held.remove(key) {
+                wipe_token(&mut token);
+            }
+        }
+    }
+
+    /// Keys with stored tokens, sorted
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .tokens
+            .tokens
+            .lock()
+            .map(|held| held.keys().cloned().collect())
+            .unwrap_or_default();
+        keys.sort();
+        keys
+    }
+
+    /// Wipe the tokens of clients created with `namespace`, returning how
+    /// many were stored; other namespaces keep theirs.
+    pub fn purge_namespace(&self, namespace: &str) -> usize {
+        self.tokens.purge_namespace(namespace)
+    }
+
+    /// Wipe every stored token
+    pub fn clear(&self) {
+        self.tokens.wipe();
+    }
+
+    fn __len__(&self) -> usize {
+        self.tokens
+            .tokens
+            .lock()
+            .map(|held| held.len())
+            .unwrap_or(0)
+    }
+}
+
+impl Default for PyMemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Adapter forwarding the `ClientStorage` trait to a Python object.
+///
+/// Calls take the GIL; client calls drive their futures on the calling
+/// thread (which already holds it), so this never blocks on another thread.
+pub struct PythonStorage {
+    object: PyObject,
+}
+
+impl PythonStorage {
+    /// Wrap `object`, checking it has the three storage methods.
+    pub fn new(object: &Bound<'_, PyAny>) -> PyResult<Self> {
+        for method in ["save_tokens", "load_tokens", "remove_tokens"] {
+            if !object.hasattr(method)? {
+                return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                    "storage object has no '{}' method",
+                    method
+                )));
+            }
+        }
+        Ok(Self {
+            object: object.clone().unbind(),
+        })
+    }
+
+    /// Positional arguments of a storage call about `key`: the namespace
+    /// comes first when there is one.
+    fn args<'py>(
+        py: Python<'py>,
+        namespace: Option<&str>,
+        key: &str,
+        rest: Option<PyObject>,
+    ) -> Bound<'py, pyo3::types::PyTuple> {
+        let args: Vec<PyObject> = namespace
+            .map(|namespace| namespace.into_py(py))
+            .into_iter()
+            .chain([key.into_py(py)])
+            .chain(rest)
+            .collect();
+        pyo3::types::PyTuple::new_bound(py, args)
+    }
+
+    fn save(&self, namespace: Option<&str>, key: &str, tokens: &JwtToken) -> eyre::Result<()> {
+        let tokens = serde_json::to_value(tokens).wrap_err("Failed to serialize JWT tokens")?;
+        Python::with_gil(|py| {
+            let args = Self::args(py, namespace, key, Some(json_to_python(py, &tokens)));
+            self.object.call_method1(py, "save_tokens", args).map(drop)
+        })
+        .map_err(|e| eyre::eyre!("Python storage save_tokens failed: {}", e))
+    }
+
+    fn load(&self, namespace: Option<&str>, key: &str) -> eyre::Result<Option<JwtToken>> {
+        let value = Python::with_gil(|py| {
+            let args = Self::args(py, namespace, key, None);
+            let tokens = self.object.call_method1(py, "load_tokens", args)?;
+            python_to_json(tokens.bind(py))
+        })
+        .map_err(|e| eyre::eyre!("Python storage load_tokens failed: {}", e))?;
+        if value.is_null() {
+            return Ok(None);
+        }
+        let tokens = serde_json::from_value(value)
+            .wrap_err_with(|| format!("Invalid tokens from Python storage for '{}'", key))?;
+        Ok(Some(tokens))
+    }
+
+    fn remove(&self, namespace: Option<&str>, key: &str) -> eyre::Result<()> {
+        Python::with_gil(|py| {
+            let args = Self::args(py, namespace, key, None);
+            self.object
+                .call_method1(py, "remove_tokens", args)
+                .map(drop)
+        })
+        .map_err(|e| eyre::eyre!("Python storage remove_tokens failed: {}", e))
+    }
+
+    /// Forward to the object's `purge_namespace`, returning what it
+    /// returns as a count (0 for `None`).
+    fn purge(&self, namespace: &str) -> eyre::Result<usize> {
+        Python::with_gil(|py| {
+            let purged = self
+                .object
+                .call_method1(py, "purge_namespace", (namespace,))?;
+            if purged.is_none(py) {
+                Ok(0)
+            } else {
+                purged.extract::<usize>(py)
+            }
+        })
+        .map_err(|e| eyre::eyre!("Python storage purge_namespace failed: {}", e))
+    }
+}
+
+/// Key of `key` in a shared store for clients confined to `namespace`.
+fn namespaced_key(namespace: Option<&str>, key: String) -> String {
+    match namespace {
+        Some(namespace) => format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, key),
+        None => key,
+    }
+}
+
+/// In-memory token store for ephemeral clients.
+///
+/// Tokens are never written to disk. They are wiped (zeroized) on
+/// [`EphemeralTokens::wipe`], once the optional deadline passes, and when the
+/// last handle is dropped.
+pub struct EphemeralTokens {
+    tokens: Mutex<HashMap<String, JwtToken>>,
+    deadline: Option<Instant>,
+}
+
+impl EphemeralTokens {
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            tokens: Mutex::new(HashMap::new()),
+            deadline: ttl.map(|ttl| Instant::now() + ttl),
+        }
+    }
+
+    fn expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Zeroize and drop the tokens of `namespace`, returning how many.
+    pub fn purge_namespace(&self, namespace: &str) -> usize {
+        let prefix = namespaced_key(Some(namespace), String::new());
+        let Ok(mut tokens) = self.tokens.lock() else {
+            return 0;
+        };
+        let keys: Vec<String> = tokens
+            .keys()
+            .filter(|key| key.starts_with(&prefix))
+            .cloned()
+            .collect();
+        for key in &keys {
+            if let Some(mut token) = tokens.remove(key) {
+                wipe_token(&mut token);
+            }
+        }
+        keys.len()
+    }
+
+    /// Zeroize and drop every held token.
+    pub fn wipe(&self) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            for token in tokens.values_mut() {
+                wipe_token(token);
+            }
+            tokens.clear();
+        }
+    }
+}
+
+impl Drop for EphemeralTokens {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
+/// Overwrite a token's secret strings in place.
+pub fn wipe_token(token: &mut JwtToken) {
+    token.access_token.zeroize();
+    if let Some(refresh_token) = token.refresh_token.as_mut() {
+        refresh_token.zeroize();
+    }
+}
+
+/// A loaded token whose secrets are wiped when it goes out of scope.
+pub struct WipeOnDrop(JwtToken);
+
+impl WipeOnDrop {
+    pub fn new(token: JwtToken) -> Self {
+        Self(token)
+    }
+}
+
+impl Deref for WipeOnDrop {
+    type Target = JwtToken;
+
+    fn deref(&self) -> &JwtToken {
+        &self.0
+    }
+}
+
+impl Drop for WipeOnDrop {
+    fn drop(&mut self) {
+        wipe_token(&mut self.0);
+    }
+}
+
+impl MeroboxFileStorage {
+    pub fn new() -> Self {
+        Self {
+            mode: StorageMode::File,
+            user: None,
+            namespace: None,
+            cache_dir: None,
+        }
+    }
+
+    /// File storage with token files in `cache_dir` (default:
+    /// [`get_cache_base_dir`]) encrypted at rest, keyed by `passphrase` or
+    /// (without one) a machine key kept in the OS keychain, which must then
+    /// be available.
+    pub fn encrypted(passphrase: Option<&str>, cache_dir: Option<PathBuf>) -> eyre::Result<Self> {
+        let cache_dir = cache_dir.unwrap_or_else(get_cache_base_dir);
+        Self::ensure_cache_dir_exists(&cache_dir)?;
+        let key = match passphrase {
+            Some(passphrase) => {
+                let salt = load_or_create_secret(&cache_dir.join(KEY_SALT_FILE), 16)?;
+                crypto::derive_key(passphrase.as_bytes(), &salt)
+            }
+            None => machine_key(&cache_dir)?,
+        };
+        Ok(Self {
+            mode: StorageMode::Encrypted(Arc::new(key)),
+            user: None,
+            namespace: None,
+            cache_dir: Some(cache_dir.into()),
+        })
+    }
+
+    /// Storage for token-less connections to nodes with auth disabled.
+    pub fn anonymous() -> Self {
+        Self {
+            mode: StorageMode::Anonymous,
+            user: None,
+            namespace: None,
+            cache_dir: None,
+        }
+    }
+
+    /// Memory-only storage; tokens are wiped after `ttl` (if given).
+    pub fn ephemeral(ttl: Option<Duration>) -> Self {
+        Self {
+            mode: StorageMode::Ephemeral(Arc::new(EphemeralTokens::new(ttl))),
+            user: None,
+            namespace: None,
+            cache_dir: None,
+        }
+    }
+
+    /// Storage in the OS keychain, or `None` when no keychain is available.
+    pub fn keyring() -> Option<Self> {
+        let keyring = KeyringStorage::new();
+        keyring.is_available().then(|| Self {
+            mode: StorageMode::Keyring(keyring),
+            user: None,
+            namespace: None,
+            cache_dir: None,
+        })
+    }
+
+    /// In-process storage backed by `memory`.
+    pub fn memory(memory: &PyMemoryStorage) -> Self {
+        Self {
+            mode: StorageMode::Memory(memory.tokens.clone()),
+            user: None,
+            namespace: None,
+            cache_dir: None,
+        }
+    }
+
+    /// Storage forwarding to a Python `ClientStorage` implementation.
+    pub fn python(storage: PythonStorage) -> Self {
+        Self {
+            mode: StorageMode::Python(Arc::new(storage)),
+            user: None,
+            namespace: None,
+            cache_dir: None,
+        }
+    }
+
+    /// The same storage with tokens keyed by (node, `user`).
+    pub fn with_user(&self, user: &str) -> Self {
+        Self {
+            mode: self.mode.clone(),
+            user: Some(user.into()),
+            namespace: self.namespace.clone(),
+            cache_dir: self.cache_dir.clone(),
+        }
+    }
+
+    /// The same storage confined to `namespace`, which must pass
+    /// [`validate_namespace`].
+    pub fn with_namespace(&self, namespace: &str) -> Self {
+        Self {
+            mode: self.mode.clone(),
+            user: self.user.clone(),
+            namespace: Some(namespace.into()),
+            cache_dir: self.cache_dir.clone(),
+        }
+    }
+
+    /// The same storage with token files kept in `cache_dir`.
+    pub fn with_cache_dir(&self, cache_dir: PathBuf) -> Self {
+        Self {
+            mode: self.mode.clone(),
+            user: self.user.clone(),
+            namespace: self.namespace.clone(),
+            cache_dir: Some(cache_dir.into()),
+        }
+    }
+
+    /// Directory holding this storage's token files.
+    pub fn cache_dir(&self) -> PathBuf {
+        match &self.cache_dir {
+            Some(dir) => dir.to_path_buf(),
+            None => get_cache_base_dir(),
+        }
+    }
+
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Directory holding this namespace's token files: [`Self::cache_dir`]
+    /// itself without a namespace.
+    pub fn token_dir(&self) -> PathBuf {
+        match self.namespace() {
+            Some(namespace) => namespace_dir(&self.cache_dir(), namespace),
+            None => self.cache_dir(),
+        }
+    }
+
+    /// Path of the cache file holding `node_name`'s tokens for this user.
+    pub fn cache_path(&self, node_name: &str) -> PathBuf {
+        self.token_dir()
+            .join(user_token_filename(node_name, self.user()))
+    }
+
+    /// Key of `node_name`'s tokens in the ephemeral store.
+    fn memory_key(&self, node_name: &str) -> String {
+        let key = match self.user() {
+            Some(user) => format!("{}\0{}", node_name, user),
+            None => node_name.to_string(),
+        };
+        namespaced_key(self.namespace(), key)
+    }
+
+    /// Key of `node_name`'s tokens in the keychain or a `MemoryStorage`.
+    fn storage_key(&self, node_name: &str) -> String {
+        namespaced_key(self.namespace(), self.python_key(node_name))
+    }
+
+    /// Key of `node_name`'s tokens in a Python storage, which gets the
+    /// namespace separately.
+    fn python_key(&self, node_name: &str) -> String {
+        match self.user() {
+            Some(user) => format!("{}/{}", node_name, user),
+            None => node_name.to_string(),
+        }
+    }
+
+    pub fn is_anonymous(&self) -> bool {
+        matches!(self.mode, StorageMode::Anonymous)
+    }
+
+    pub fn is_ephemeral(&self) -> bool {
+        matches!(self.mode, StorageMode::Ephemeral(_))
+    }
+
+    /// Name of the backend tokens are kept in.
+    pub fn backend(&self) -> &'static str {
+        match self.mode {
+            StorageMode::File => "file",
+            StorageMode::Encrypted(_) => "encrypted",
+            StorageMode::Anonymous => "anonymous",
+            StorageMode::Ephemeral(_) => "ephemeral",
+            StorageMode::Memory(_) => "memory",
+            StorageMode::Keyring(_) => "keyring",
+            StorageMode::Python(_) => "python",
+        }
+    }
+
+    /// Whether tokens live in a Python-defined storage.
+    pub fn is_python(&self) -> bool {
+        matches!(self.mode, StorageMode::Python(_))
+    }
+
+    /// Whether tokens are read from and written to the on-disk cache.
+    pub fn persists_to_disk(&self) -> bool {
+        matches!(self.mode, StorageMode::File | StorageMode::Encrypted(_))
+    }
+
+    /// Whether token files are encrypted at rest.
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self.mode, StorageMode::Encrypted(_))
+    }
+
+    /// Wipe in-memory tokens (ephemeral mode); a no-op otherwise.
+    pub fn wipe(&self) {
+        if let StorageMode::Ephemeral(tokens) = &self.mode {
+            tokens.wipe();
+        }
+    }
+
+    /// Delete the tokens every client confined to `namespace` keeps in this
+    /// storage (whatever namespace the storage itself is confined to),
+    /// returning how many were deleted. The keychain cannot list its
+    /// entries, so keyring storage refuses.
+    pub fn purge_namespace(&self, namespace: &str) -> eyre::Result<usize> {
+        validate_namespace(namespace).map_err(|e| eyre::eyre!(e))?;
+        match &self.mode {
+            StorageMode::File | StorageMode::Encrypted(_) => {
+                cache_admin::purge_namespace(&self.cache_dir(), namespace)
+            }
+            StorageMode::Anonymous => Ok(0),
+            StorageMode::Ephemeral(tokens) | StorageMode::Memory(tokens) => {
+                Ok(tokens.purge_namespace(namespace))
+            }
+            StorageMode::Keyring(_) => eyre::bail!(
+                "keyring storage cannot list its entries; remove the namespace's tokens \
+                 per node instead"
+            ),
+            StorageMode::Python(storage) => storage.purge(namespace),
+        }
+    }
+
+    /// Tokens currently in the cache file at `path`, if it holds any this
+    /// storage can read.
+    fn read_cached(&self, path: &Path) -> Option<WipeOnDrop> {
+        let contents = zeroize::Zeroizing::new(fs::read(path).ok()?);
+        let json = match (&self.mode, crypto::is_envelope(&contents)) {
+            (StorageMode::Encrypted(key), true) => {
+                zeroize::Zeroizing::new(crypto::open(key.as_slice(), &contents).ok()?)
+            }
+            (_, true) => return None,
+            (_, false) => contents,
+        };
+        serde_json::from_slice(&json).ok().map(WipeOnDrop::new)
+    }
+
     /// Ensure the cache directory exists with secure permissions (0700 on Unix).
     ///
     /// Uses `DirBuilderExt::mode` on Unix to create with correct permissions atomically,
     /// avoiding a TOCTOU race between create and set_permissions.
-    fn ensure_cache_dir_exists(&self) -> eyre::Result<()> {
-        let cache_dir = get_cache_base_dir();
+    fn ensure_cache_dir_exists(cache_dir: &Path) -> eyre::Result<()> {
         if !cache_dir.exists() {
-            Self::create_cache_dir(&cache_dir)?;
+            Self::create_cache_dir(cache_dir)?;
         }
         Ok(())
     }
@@ -85,11 +752,128 @@ impl MeroboxFileStorage {
             .wrap_err_with(|| format!("Failed to create cache directory: {:?}", cache_dir))
     }
 
+    /// Files created inside later inherit the directory's owner-only DACL.
     #[cfg(not(unix))]
     fn create_cache_dir(cache_dir: &std::path::Path) -> eyre::Result<()> {
         fs::create_dir_all(cache_dir)
-            .wrap_err_with(|| format!("Failed to create cache directory: {:?}", cache_dir))
+            .wrap_err_with(|| format!("Failed to create cache directory: {:?}", cache_dir))?;
+        crate::permissions::restrict_to_owner(cache_dir, true)
+    }
+}
+
+/// Open (creating it if needed) the lock file guarding writes of the token
+/// file at `cache_path`. The lock file is never removed, so every process
+/// locks the same file; the lock is advisory (`flock` on Unix,
+/// `LockFileEx` on Windows) and released when the handle is dropped.
+pub(crate) fn token_lock(cache_path: &Path) -> eyre::Result<fd_lock::RwLock<fs::File>> {
+    let lock_path = cache_path.with_extension("json.lock");
+    #[allow(unused_mut)] // mut needed on Unix for mode() call
+    let mut opts = OpenOptions::new();
+    opts.write(true).create(true).truncate(false);
+    #[cfg(unix)]
+    {
+        opts.mode(0o600);
+    }
+    let file = opts
+        .open(&lock_path)
+        .wrap_err_with(|| format!("Failed to open lock file: {:?}", lock_path))?;
+    Ok(fd_lock::RwLock::new(file))
+}
+
+/// Whether `current` outlives `candidate`, i.e. saving `candidate` over it
+/// would replace a fresher token with a staler one. Tokens without a known
+/// expiry never count as fresher.
+fn is_fresher(current: &JwtToken, candidate: &JwtToken) -> bool {
+    match (token_expiry(current), token_expiry(candidate)) {
+        (Some(current), Some(candidate)) => current > candidate,
+        _ => false,
+    }
+}
+
+/// Read the `len`-byte secret at `path`, creating it (random, 0600 on Unix)
+/// if it does not exist yet.
+/// The keychain-held machine key of the token files in `cache_dir`,
+/// adopting the key file older releases left there or creating one.
+fn machine_key(cache_dir: &Path) -> eyre::Result<zeroize::Zeroizing<[u8; crypto::KEY_LEN]>> {
+    let keychain = KeyringStorage::new();
+    if !keychain.is_available() {
+        eyre::bail!(
+            "storage=\"encrypted\" without a passphrase keeps its key in the OS keychain, \
+             and none is available; pass a passphrase"
+        );
+    }
+    let dir = fs::canonicalize(cache_dir).unwrap_or_else(|_| cache_dir.to_path_buf());
+    let account = format!("{}{}", MACHINE_KEY_ACCOUNT_PREFIX, dir.display());
+    let legacy = cache_dir.join(LEGACY_MACHINE_KEY_FILE);
+
+    // Files sealed by older releases need their key; it moves to the keychain
+    if let Ok(secret) = fs::read(&legacy).map(zeroize::Zeroizing::new) {
+        if secret.len() != crypto::KEY_LEN {
+            eyre::bail!(
+                "Key file {:?} is corrupted (expected {} bytes)",
+                legacy,
+                crypto::KEY_LEN
+            );
+        }
+        keychain.save_secret(&account, &zeroize::Zeroizing::new(hex::encode(&secret[..])))?;
+        fs::remove_file(&legacy)
+            .wrap_err_with(|| format!("Failed to remove key file: {:?}", legacy))?;
+    }
+    let encoded = match keychain.load_secret(&account)? {
+        Some(encoded) => Some(encoded),
+        None => {
+            let mut secret = zeroize::Zeroizing::new([0u8; crypto::KEY_LEN]);
+            entropy::fill(&mut secret[..])?;
+            keychain.save_secret(&account, &zeroize::Zeroizing::new(hex::encode(&secret[..])))?;
+            // Read back in case another process stored its key meanwhile
+            keychain.load_secret(&account)?
+        }
+    };
+    let encoded = encoded.ok_or_else(|| eyre::eyre!("Keychain lost the token key just stored"))?;
+
+    let mut key = zeroize::Zeroizing::new([0u8; crypto::KEY_LEN]);
+    hex::decode_to_slice(encoded.as_bytes(), &mut key[..])
+        .wrap_err("Token key in the keychain is corrupted")?;
+    Ok(key)
+}
+
+fn load_or_create_secret(
+    path: &std::path::Path,
+    len: usize,
+) -> eyre::Result<zeroize::Zeroizing<Vec<u8>>> {
+    if !path.exists() {
+        let mut secret = zeroize::Zeroizing::new(vec![0u8; len]);
+        entropy::fill(&mut secret)?;
+        #[allow(unused_mut)] // mut needed on Unix for mode() call
+        let mut opts = OpenOptions::new();
+        opts.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            opts.mode(0o600);
+        }
+        match opts.open(path) {
+            Ok(mut file) => {
+                #[cfg(not(unix))]
+                crate::permissions::restrict_to_owner(path, false)?;
+                file.write_all(&secret)
+                    .and_then(|()| file.sync_all())
+                    .wrap_err_with(|| format!("Failed to write key file: {:?}", path))?;
+                return Ok(secret);
+            }
+            // Another process created it first; use theirs
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => {
+                return Err(e).wrap_err_with(|| format!("Failed to create key file: {:?}", path))
+            }
+        }
     }
+    let secret = zeroize::Zeroizing::new(
+        fs::read(path).wrap_err_with(|| format!("Failed to read key file: {:?}", path))?,
+    );
+    if secret.len() != len {
+        eyre::bail!("Key file {:?} is corrupted (expected {} bytes)", path, len);
+    }
+    Ok(secret)
 }
 
 impl Default for MeroboxFileStorage {
@@ -105,21 +889,86 @@ impl ClientStorage for MeroboxFileStorage {
     /// This method:
     /// 1. Ensures the cache directory exists (creating with 0700 permissions if needed)
     /// 2. Creates a temp file with 0600 permissions (Unix) via OpenOptionsExt::mode to avoid TOCTOU
-    /// 3. Writes tokens and syncs
-    /// 4. Atomically renames temp file to final path
+    /// 3. Takes the token file's cross-process lock, and keeps the cached
+    ///    tokens instead when they expire later than `tokens` (another
+    ///    process refreshed in the meantime)
+    /// 4. Writes tokens and syncs
+    /// 5. Atomically renames temp file to final path
     async fn save_tokens(&self, node_name: &str, tokens: &JwtToken) -> eyre::Result<()> {
+        match &self.mode {
+            StorageMode::File | StorageMode::Encrypted(_) => {}
+            StorageMode::Anonymous => return Ok(()),
+            StorageMode::Ephemeral(memory) => {
+                if memory.expired() {
+                    memory.wipe();
+                    return Ok(());
+                }
+                let mut held = memory
+                    .tokens
+                    .lock()
+                    .map_err(|_| eyre::eyre!("Ephemeral token store lock poisoned"))?;
+                if let Some(mut previous) = held.insert(self.memory_key(node_name), tokens.clone())
+                {
+                    wipe_token(&mut previous);
+                }
+                return Ok(());
+            }
+            StorageMode::Memory(memory) => {
+                let mut held = memory
+                    .tokens
+                    .lock()
+                    .map_err(|_| eyre::eyre!("Memory token store lock poisoned"))?;
+                if let Some(mut previous) = held.insert(self.storage_key(node_name), tokens.clone())
+                {
+                    wipe_token(&mut previous);
+                }
+                return Ok(());
+            }
+            StorageMode::Keyring(keyring) => {
+                return keyring
+                    .save_tokens(&self.storage_key(node_name), tokens)
+                    .await;
+            }
+            StorageMode::Python(storage) => {
+                return storage.save(self.namespace(), &self.python_key(node_name), tokens);
+            }
+        }
+
         // Ensure directory exists with proper permissions
-        self.ensure_cache_dir_exists()?;
+        Self::ensure_cache_dir_exists(&self.token_dir())?;
 
-        let cache_path = get_token_cache_path_internal(node_name);
-        let temp_path = cache_path.with_extension("json.tmp");
+        let cache_path = self.cache_path(node_name);
+
+        // Serialize writers across processes, then compare under the lock so
+        // the fresher token always wins
+        let mut lock = token_lock(&cache_path)?;
+        let _held = lock
+            .write()
+            .wrap_err_with(|| format!("Failed to lock token file: {:?}", cache_path))?;
+        if self
+            .read_cached(&cache_path)
+            .is_some_and(|current| is_fresher(&current, tokens))
+        {
+            return Ok(());
+        }
+
+        let temp_path = cache_path.with_extension(format!("json.{}.tmp", entropy::temp_suffix()));
 
         // Create guard to ensure temp file is cleaned up on error
         let _guard = TempFileGuard::new(temp_path.clone());
 
-        // Serialize tokens to JSON
-        let json = serde_json::to_string_pretty(tokens)
-            .wrap_err("Failed to serialize JWT tokens to JSON")?;
+        // Serialize tokens to JSON (wiped once written), sealed when encrypted
+        let json = zeroize::Zeroizing::new(
+            serde_json::to_string_pretty(tokens)
+                .wrap_err("Failed to serialize JWT tokens to JSON")?,
+        );
+        let contents = match &self.mode {
+            StorageMode::Encrypted(key) => zeroize::Zeroizing::new(
+                crypto::seal(key.as_slice(), json.as_bytes())
+                    .wrap_err("Failed to encrypt JWT tokens")?,
+            ),
+            _ => zeroize::Zeroizing::new(json.as_bytes().to_vec()),
+        };
 
         // Create temp file with correct permissions atomically (avoids TOCTOU).
         // On Unix, use OpenOptionsExt::mode(0o600); on other platforms, use default create.
@@ -134,7 +983,10 @@ impl ClientStorage for MeroboxFileStorage {
             let mut file = opts
                 .open(&temp_path)
                 .wrap_err_with(|| format!("Failed to create temp file: {:?}", temp_path))?;
-            file.write_all(json.as_bytes())
+            // The cache directory may predate the owner-only DACL
+            #[cfg(not(unix))]
+            crate::permissions::restrict_to_owner(&temp_path, false)?;
+            file.write_all(&contents)
                 .wrap_err_with(|| format!("Failed to write to temp file: {:?}", temp_path))?;
             file.sync_all()
                 .wrap_err_with(|| format!("Failed to sync temp file: {:?}", temp_path))?;
@@ -158,30 +1010,107 @@ impl ClientStorage for MeroboxFileStorage {
     ///
     /// Returns:
     /// - `Ok(Some(tokens))` if file exists and is valid JSON
-    /// - `Ok(None)` if file does not exist
-    /// - `Err(...)` if file exists but cannot be read or parsed
+    /// - `Ok(None)` if file does not exist, or held JSON that does not parse;
+    ///   such a file is quarantined (see [`crate::cache_admin`]) with a
+    ///   warning, so the client logs in again
+    /// - `Err(...)` if file exists but cannot be read or decrypted
     async fn load_tokens(&self, node_name: &str) -> eyre::Result<Option<JwtToken>> {
-        let cache_path = get_token_cache_path_internal(node_name);
+        match &self.mode {
+            StorageMode::File | StorageMode::Encrypted(_) => {}
+            StorageMode::Anonymous => return Ok(None),
+            StorageMode::Ephemeral(memory) => {
+                if memory.expired() {
+                    memory.wipe();
+                    return Ok(None);
+                }
+                let held = memory
+                    .tokens
+                    .lock()
+                    .map_err(|_| eyre::eyre!("Ephemeral token store lock poisoned"))?;
+                return Ok(held.get(&self.memory_key(node_name)).cloned());
+            }
+            StorageMode::Memory(memory) => {
+                let held = memory
+                    .tokens
+                    .lock()
+                    .map_err(|_| eyre::eyre!("Memory token store lock poisoned"))?;
+                return Ok(held.get(&self.storage_key(node_name)).cloned());
+            }
+            StorageMode::Keyring(keyring) => {
+                return keyring.load_tokens(&self.storage_key(node_name)).await;
+            }
+            StorageMode::Python(storage) => {
+                return storage.load(self.namespace(), &self.python_key(node_name))
+            }
+        }
+
+        let cache_path = self.cache_path(node_name);
 
         // If file doesn't exist, return None (not an error)
         if !cache_path.exists() {
             return Ok(None);
         }
 
-        // Read and deserialize
-        let json = fs::read_to_string(&cache_path).wrap_err_with(|| {
+        // Read and deserialize (the raw file contents are wiped afterwards)
+        let contents = zeroize::Zeroizing::new(fs::read(&cache_path).wrap_err_with(|| {
             format!(
                 "Failed to read token file: {:?} for node: {}",
                 cache_path, node_name
             )
-        })?;
+        })?);
+        let sealed = crypto::is_envelope(&contents);
+        let json = match (&self.mode, sealed) {
+            (StorageMode::Encrypted(key), true) => zeroize::Zeroizing::new(
+                crypto::open(key.as_slice(), &contents).wrap_err_with(|| {
+                    format!(
+                        "Failed to decrypt token file: {:?} for node: {}",
+                        cache_path, node_name
+                    )
+                })?,
+            ),
+            (_, true) => eyre::bail!(
+                "Token file {:?} for node {} is encrypted; connect with storage=\"encrypted\"",
+                cache_path,
+                node_name
+            ),
+            (_, false) => zeroize::Zeroizing::new(contents.to_vec()),
+        };
 
-        let tokens: JwtToken = serde_json::from_str(&json).wrap_err_with(|| {
-            format!(
-                "Failed to parse token JSON from file: {:?} for node: {}",
-                cache_path, node_name
-            )
-        })?;
+        let tokens: JwtToken = match serde_json::from_slice(&json) {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                // Only if no save replaced the file since it was read
+                let quarantined = cache_admin::quarantine_if(&cache_path, |current| {
+                    current == contents.as_slice()
+                })
+                .wrap_err_with(|| {
+                    format!(
+                        "Failed to parse token JSON from file: {:?} for node: {} ({})",
+                        cache_path, node_name, e
+                    )
+                })?;
+                let moved = match &quarantined {
+                    Some(to) => format!("moved it to {}", to.display()),
+                    None => "a newer save has replaced it".to_string(),
+                };
+                warnings::warn_detached(
+                    WarningKind::CorruptTokenCache,
+                    &format!(
+                        "Token file {} for node '{}' is corrupt ({}); {}, log in again",
+                        cache_path.display(),
+                        node_name,
+                        e,
+                        moved
+                    ),
+                );
+                return Ok(None);
+            }
+        };
+
+        // Plaintext file from before encryption was enabled: rewrite it sealed
+        if self.is_encrypted() && !sealed {
+            self.save_tokens(node_name, &tokens).await?;
+        }
 
         Ok(Some(tokens))
     }
@@ -191,7 +1120,34 @@ impl ClientStorage for MeroboxFileStorage {
     /// This overrides the default trait implementation which would save an "empty token".
     /// Instead, we delete the file entirely.
     async fn remove_tokens(&self, node_name: &str) -> eyre::Result<()> {
-        let cache_path = get_token_cache_path_internal(node_name);
+        match &self.mode {
+            StorageMode::File | StorageMode::Encrypted(_) => {}
+            StorageMode::Anonymous => return Ok(()),
+            StorageMode::Ephemeral(memory) => {
+                if let Ok(mut held) = memory.tokens.lock() {
+                    if let Some(mut token) = held.remove(&self.memory_key(node_name)) {
+                        wipe_token(&mut token);
+                    }
+                }
+                return Ok(());
+            }
+            StorageMode::Memory(memory) => {
+                if let Ok(mut held) = memory.tokens.lock() {
+                    if let Some(mut token) = held.remove(&self.storage_key(node_name)) {
+                        wipe_token(&mut token);
+                    }
+                }
+                return Ok(());
+            }
+            StorageMode::Keyring(keyring) => {
+                return keyring.remove_tokens(&self.storage_key(node_name)).await;
+            }
+            StorageMode::Python(storage) => {
+                return storage.remove(self.namespace(), &self.python_key(node_name))
+            }
+        }
+
+        let cache_path = self.cache_path(node_name);
 
         // Only try to remove if file exists
         if cache_path.exists() {
@@ -206,3 +1162,276 @@ impl ClientStorage for MeroboxFileStorage {
         Ok(())
     }
 }
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Anonymous storage reports no tokens and never writes a cache file.
+    #[tokio::test]
+    async fn test_anonymous_storage_is_inert() {
+        let storage = MeroboxFileStorage::anonymous();
+        let node_name = "anonymous-storage-test-node";
+        let tokens = JwtToken {
+            access_token: "access".to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: None,
+        };
+
+        storage.save_tokens(node_name, &tokens).await.unwrap();
+        assert!(!storage.cache_path(node_name).exists());
+        assert!(storage.load_tokens(node_name).await.unwrap().is_none());
+        storage.remove_tokens(node_name).await.unwrap();
+    }
+
+    /// Ephemeral storage keeps tokens in memory only and wipes them on demand.
+    #[tokio::test]
+    async fn test_ephemeral_storage_is_memory_only() {
+        let storage = MeroboxFileStorage::ephemeral(None);
+        let node_name = "ephemeral-storage-test-node";
+        let tokens = JwtToken {
+            access_token: "access".to_string(),
+            refresh_token: None,
+            expires_at: None,
+        };
+
+        storage.save_tokens(node_name, &tokens).await.unwrap();
+        assert!(!storage.cache_path(node_name).exists());
+        assert_eq!(
+            storage
+                .load_tokens(node_name)
+                .await
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "access"
+        );
+
+        storage.wipe();
+        assert!(storage.load_tokens(node_name).await.unwrap().is_none());
+    }
+
+    /// Tokens are dropped once the ephemeral deadline passes.
+    #[tokio::test]
+    async fn test_ephemeral_storage_ttl() {
+        let storage = MeroboxFileStorage::ephemeral(Some(Duration::ZERO));
+        let tokens = JwtToken {
+            access_token: "access".to_string(),
+            refresh_token: None,
+            expires_at: None,
+        };
+        storage.save_tokens("n", &tokens).await.unwrap();
+        assert!(storage.load_tokens("n").await.unwrap().is_none());
+    }
+
+    /// Wiping overwrites the secret strings before they are freed.
+    #[test]
+    fn test_wipe_token() {
+        let mut token = JwtToken {
+            access_token: "access".to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: None,
+        };
+        wipe_token(&mut token);
+        assert!(token.access_token.is_empty());
+        assert_eq!(token.refresh_token.as_deref(), Some(""));
+    }
+
+    /// Encrypted storage seals files and migrates plaintext ones on load.
+    #[tokio::test]
+    async fn test_encrypted_storage_migrates_plaintext() {
+        let storage = MeroboxFileStorage::encrypted(Some("test passphrase"), None).unwrap();
+        let node_name = "encrypted-storage-test-node";
+        let path = storage.cache_path(node_name);
+        let tokens = JwtToken {
+            access_token: "access".to_string(),
+            refresh_token: None,
+            expires_at: None,
+        };
+        MeroboxFileStorage::new()
+            .save_tokens(node_name, &tokens)
+            .await
+            .unwrap();
+        assert!(!crypto::is_envelope(&fs::read(&path).unwrap()));
+
+        let loaded = storage.load_tokens(node_name).await.unwrap().unwrap();
+        assert_eq!(loaded.access_token, "access");
+        assert!(crypto::is_envelope(&fs::read(&path).unwrap()));
+        assert!(MeroboxFileStorage::new()
+            .load_tokens(node_name)
+            .await
+            .is_err());
+        assert!(MeroboxFileStorage::encrypted(Some("wrong"), None)
+            .unwrap()
+            .load_tokens(node_name)
+            .await
+            .is_err());
+
+        storage.remove_tokens(node_name).await.unwrap();
+        assert!(!path.exists());
+    }
+
+    /// A save racing a fresher token already on disk leaves the fresher one.
+    #[tokio::test]
+    async fn test_save_keeps_fresher_token() {
+        let storage = MeroboxFileStorage::new();
+        let node_name = "fresher-token-test-node";
+        let tokens = |access: &str, expires_at| JwtToken {
+            access_token: access.to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: Some(expires_at),
+        };
+
+        storage
+            .save_tokens(node_name, &tokens("newer", 2_000))
+            .await
+            .unwrap();
+        storage
+            .save_tokens(node_name, &tokens("stale", 1_000))
+            .await
+            .unwrap();
+        let loaded = storage.load_tokens(node_name).await.unwrap().unwrap();
+        assert_eq!(loaded.access_token, "newer");
+
+        storage
+            .save_tokens(node_name, &tokens("newest", 3_000))
+            .await
+            .unwrap();
+        let loaded = storage.load_tokens(node_name).await.unwrap().unwrap();
+        assert_eq!(loaded.access_token, "newest");
+        assert!(storage
+            .cache_path(node_name)
+            .with_extension("json.lock")
+            .exists());
+
+        storage.remove_tokens(node_name).await.unwrap();
+    }
+
+    /// A token file that does not parse is moved aside and loads as `None`.
+    #[tokio::test]
+    async fn test_corrupt_token_file_is_quarantined() {
+        let dir = std::env::temp_dir().join(format!("corrupt-tokens-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let storage = MeroboxFileStorage::new().with_cache_dir(dir.clone());
+        let path = storage.cache_path("node");
+        fs::write(&path, b"{\"access_token\": \"trunc").unwrap();
+
+        assert!(storage.load_tokens("node").await.unwrap().is_none());
+        assert!(!path.exists());
+        let quarantined: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".corrupt-"))
+            .collect();
+        assert_eq!(quarantined.len(), 1);
+        assert!(storage.load_tokens("node").await.unwrap().is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Memory storage is shared by its handles: missing keys load as `None`
+    /// and removal deletes the entry.
+    #[tokio::test]
+    async fn test_memory_storage_semantics() {
+        let memory = PyMemoryStorage::new();
+        let storage = MeroboxFileStorage::memory(&memory);
+        let other = MeroboxFileStorage::memory(&memory).with_user("bob");
+        let tokens = JwtToken {
+            access_token: "access".to_string(),
+            refresh_token: None,
+            expires_at: None,
+        };
+
+        assert!(storage.load_tokens("node").await.unwrap().is_none());
+        storage.save_tokens("node", &tokens).await.unwrap();
+        other.save_tokens("node", &tokens).await.unwrap();
+        assert!(!storage.cache_path("node").exists());
+        assert_eq!(memory.keys(), ["node", "node/bob"]);
+
+        storage.remove_tokens("node").await.unwrap();
+        assert!(storage.load_tokens("node").await.unwrap().is_none());
+        assert_eq!(memory.keys(), ["node/bob"]);
+    }
+
+    /// Users of the same node get separate ephemeral slots and cache files.
+    #[tokio::test]
+    async fn test_user_keyed_storage() {
+        let storage = MeroboxFileStorage::ephemeral(None);
+        let alice = storage.with_user("alice");
+        let bob = storage.with_user("bob");
+        let tokens = JwtToken {
+            access_token: "alice-access".to_string(),
+            refresh_token: None,
+            expires_at: None,
+        };
+
+        alice.save_tokens("node", &tokens).await.unwrap();
+        assert!(bob.load_tokens("node").await.unwrap().is_none());
+        assert!(storage.load_tokens("node").await.unwrap().is_none());
+        assert_eq!(
+            alice
+                .load_tokens("node")
+                .await
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "alice-access"
+        );
+        assert_ne!(alice.cache_path("node"), bob.cache_path("node"));
+    }
+
+    /// Namespaces get their own directory and keys, and purge separately.
+    #[tokio::test]
+    async fn test_namespaced_storage_is_isolated() {
+        let dir = std::env::temp_dir().join(format!("namespaced-storage-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let storage = MeroboxFileStorage::new().with_cache_dir(dir.clone());
+        let acme = storage.with_namespace("acme").with_user("alice");
+        let globex = storage.with_namespace("globex").with_user("alice");
+        let tokens = JwtToken {
+            access_token: "acme-access".to_string(),
+            refresh_token: None,
+            expires_at: None,
+        };
+
+        acme.save_tokens("node", &tokens).await.unwrap();
+        assert!(acme
+            .cache_path("node")
+            .starts_with(dir.join("namespaces/acme")));
+        assert!(globex.load_tokens("node").await.unwrap().is_none());
+        assert!(storage
+            .with_user("alice")
+            .load_tokens("node")
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(cache_admin::namespaces(&dir), ["acme"]);
+        assert_eq!(storage.purge_namespace("globex").unwrap(), 0);
+        assert_eq!(storage.purge_namespace("acme").unwrap(), 1);
+        assert!(acme.load_tokens("node").await.unwrap().is_none());
+        assert!(storage.purge_namespace("../acme").is_err());
+
+        let memory = PyMemoryStorage::new();
+        let shared = MeroboxFileStorage::memory(&memory);
+        shared
+            .with_namespace("acme")
+            .save_tokens("node", &tokens)
+            .await
+            .unwrap();
+        shared
+            .with_namespace("globex")
+            .save_tokens("node", &tokens)
+            .await
+            .unwrap();
+        assert_eq!(memory.keys(), ["acme::node", "globex::node"]);
+        assert_eq!(memory.purge_namespace("acme"), 1);
+        assert_eq!(memory.keys(), ["globex::node"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,978 @@
+//! Demultiplexing of context subscriptions sharing one WebSocket.
+//!
+//! Subscribing to many contexts should not cost a socket each: all
+//! subscriptions of a client share one connection, and events read from it
+//! are routed by their `contextId` into per-context channels, each consumed
+//! as its own async iterator. The [`Multiplexer`] tracks which contexts have
+//! live subscribers, builds the subscribe/unsubscribe frames to send when a
+//! context gains its first or loses its last subscriber, and routes incoming
+//! events. A subscriber that drops its receiver is pruned on the next event
+//! for its context.
+//!
+//! The [`Hub`] owns the socket itself: it connects to the node's `/ws`
+//! endpoint in the background with the cached bearer token, and after a
+//! dropped connection reconnects with exponential backoff and resubscribes
+//! every context that still has subscribers. Python sees each subscription
+//! as a [`PySubscription`], consumed with `async for` or by a callback.
+//!
+//! The last [`EVENT_HISTORY_LIMIT`] events of each context are also kept,
+//! with the time they arrived, so `delete_context(export_to=...)` can
+//! include them in its export and `usage()` can report event volume.
+//!
+//! Contexts subscribed with replay (see [`crate::event_offsets`]) carry the
+//! offset of their latest event in every subscribe frame, and events at or
+//! below it are dropped as replayed duplicates.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use eyre::WrapErr;
+use futures_util::{SinkExt, StreamExt};
+use pyo3::prelude::*;
+use tokio::net::TcpStream;
+use tokio::runtime::Handle;
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+use crate::callbacks::Callback;
+use crate::error;
+use crate::event_filter::EventFilter;
+use crate::event_offsets::{event_offset, OffsetStore};
+use crate::events::{self, BatchPolicy};
+use crate::http;
+use crate::storage::MeroboxFileStorage;
+use crate::timeouts;
+use crate::utils::json_to_python;
+
+/// Default number of events buffered per subscriber.
+pub const DEFAULT_SUBSCRIPTION_CAPACITY: usize = 1024;
+
+/// Events kept per context for exports.
+pub const EVENT_HISTORY_LIMIT: usize = 1000;
+
+#[derive(Debug, Default)]
+struct State {
+    next_request_id: u64,
+    routes: HashMap<String, Vec<Subscriber>>,
+    /// Events per context with their receipt time (Unix milliseconds).
+    history: HashMap<String, VecDeque<(i64, serde_json::Value)>>,
+    /// Contexts subscribed with replay, with the offset of their latest
+    /// event once one is known.
+    replay: HashMap<String, Option<u64>>,
+}
+
+/// Channel of one subscription, with the filter its events must pass.
+#[derive(Debug)]
+struct Subscriber {
+    sender: mpsc::Sender<serde_json::Value>,
+    filter: Option<Arc<EventFilter>>,
+}
+
+/// Routing table from context IDs to subscriber channels.
+#[derive(Debug, Default)]
+pub struct Multiplexer {
+    state: Mutex<State>,
+}
+
+/// Result of routing one event.
+#[derive(Debug, Default, PartialEq)]
+pub struct Routed {
+    /// Subscribers the event was handed to.
+    pub delivered: usize,
+    /// Subscribers whose buffer was full; the event was dropped for them.
+    pub dropped: usize,
+    /// Subscribers whose filter rejected the event.
+    pub filtered: usize,
+    /// The event was a replayed one already routed, and was not delivered.
+    pub duplicate: bool,
+    /// Frame to send because the context's last subscriber went away.
+    pub unsubscribe: Option<serde_json::Value>,
+}
+
+impl Multiplexer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a subscriber to `context_id`, receiving the events that pass
+    /// `filter`. Returns its receiver, and the subscribe frame to send when
+    /// the context had no subscribers yet.
+    pub fn subscribe(
+        &self,
+        context_id: &str,
+        capacity: usize,
+        filter: Option<Arc<EventFilter>>,
+    ) -> (mpsc::Receiver<serde_json::Value>, Option<serde_json::Value>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let Ok(mut state) = self.state.lock() else {
+            return (receiver, None);
+        };
+        let subscribers = state.routes.entry(context_id.to_string()).or_default();
+        let first = subscribers.is_empty();
+        subscribers.push(Subscriber { sender, filter });
+        let frame = first.then(|| frame(&mut state, "subscribe", context_id));
+        (receiver, frame)
+    }
+
+    /// Ask for `context_id`'s events after `offset` to be replayed, or
+    /// only track its offsets when none is known yet. Takes effect with the
+    /// next subscribe frame, so call it before [`Self::subscribe`].
+    pub fn replay_from(&self, context_id: &str, offset: Option<u64>) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let mark = state.replay.entry(context_id.to_string()).or_default();
+        *mark = (*mark).max(offset);
+    }
+
+    /// Route an event read from the socket to its context's subscribers.
+    /// Events without a `contextId` are ignored.
+    pub fn route(&self, event: &serde_json::Value) -> Routed {
+        let Some(context_id) = context_id_of(event) else {
+            return Routed::default();
+        };
+        let Ok(mut state) = self.state.lock() else {
+            return Routed::default();
+        };
+        if let (Some(mark), Some(offset)) = (state.replay.get_mut(context_id), event_offset(event))
+        {
+            if mark.is_some_and(|mark| offset <= mark) {
+                return Routed {
+                    duplicate: true,
+                    ..Routed::default()
+                };
+            }
+            *mark = Some(offset);
+        }
+        let history = state.history.entry(context_id.to_string()).or_default();
+        if history.len() == EVENT_HISTORY_LIMIT {
+            history.pop_front();
+        }
+        history.push_back((chrono::Utc::now().timestamp_millis(), event.clone()));
+        let mut routed = Routed::default();
+        let Some(subscribers) = state.routes.get_mut(context_id) else {
+            return routed;
+        };
+        subscribers.retain(|subscriber| {
+            let rejected = subscriber
+                .filter
+                .as_ref()
+                .is_some_and(|filter| !filter.matches(event));
+            if rejected && !subscriber.sender.is_closed() {
+                routed.filtered += 1;
+                return true;
+            }
+            match subscriber.sender.try_send(event.clone()) {
+                Ok(()) => {
+                    routed.delivered += 1;
+                    true
+                }
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    routed.dropped += 1;
+                    true
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            }
+        });
+        if subscribers.is_empty() {
+            state.routes.remove(context_id);
+            state.replay.remove(context_id);
+            routed.unsubscribe = Some(frame(&mut state, "unsubscribe", context_id));
+        }
+        routed
+    }
+
+    /// Events received for `context_id`, oldest first, up to
+    /// [`EVENT_HISTORY_LIMIT`].
+    pub fn history(&self, context_id: &str) -> Vec<serde_json::Value> {
+        self.timed_history(context_id)
+            .into_iter()
+            .map(|(_, event)| event)
+            .collect()
+    }
+
+    /// [`Self::history`] with the time each event arrived (Unix
+    /// milliseconds).
+    pub fn timed_history(&self, context_id: &str) -> Vec<(i64, serde_json::Value)> {
+        self.state
+            .lock()
+            .ok()
+            .and_then(|state| state.history.get(context_id).cloned())
+            .map(Vec::from)
+            .unwrap_or_default()
+    }
+
+    /// Contexts with at least one subscriber, sorted; resubscribe to these
+    /// after the socket reconnects.
+    pub fn contexts(&self) -> Vec<String> {
+        let mut contexts: Vec<String> = self
+            .state
+            .lock()
+            .map(|state| state.routes.keys().cloned().collect())
+            .unwrap_or_default();
+        contexts.sort();
+        contexts
+    }
+
+    /// One subscribe frame for every context with subscribers, to send on
+    /// a fresh connection; `None` when there are none.
+    pub fn resubscribe(&self) -> Option<serde_json::Value> {
+        let contexts = self.contexts();
+        if contexts.is_empty() {
+            return None;
+        }
+        let mut state = self.state.lock().ok()?;
+        state.next_request_id += 1;
+        let mut params = serde_json::json!({ "contextIds": contexts });
+        add_offsets(&state, &mut params, &contexts);
+        Some(serde_json::json!({
+            "id": state.next_request_id,
+            "method": "subscribe",
+            "params": params,
+        }))
+    }
+
+    /// Drop every subscriber, ending their receivers once they have taken
+    /// the events already buffered.
+    pub fn close_all(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.routes.clear();
+        }
+    }
+}
+
+/// `fromOffsets` of a subscribe frame's `params`: the latest offset of each
+/// of `contexts` subscribed with replay, when there are any.
+fn add_offsets(state: &State, params: &mut serde_json::Value, contexts: &[String]) {
+    let offsets: serde_json::Map<String, serde_json::Value> = contexts
+        .iter()
+        .filter_map(|context_id| {
+            let offset = (*state.replay.get(context_id)?)?;
+            Some((context_id.clone(), offset.into()))
+        })
+        .collect();
+    if !offsets.is_empty() {
+        params["fromOffsets"] = offsets.into();
+    }
+}
+
+/// Context an event belongs to, at top level or under `result`.
+fn context_id_of(event: &serde_json::Value) -> Option<&str> {
+    event
+        .get("result")
+        .unwrap_or(event)
+        .get("contextId")
+        .and_then(serde_json::Value::as_str)
+}
+
+fn frame(state: &mut State, method: &str, context_id: &str) -> serde_json::Value {
+    state.next_request_id += 1;
+    let mut params = serde_json::json!({ "contextIds": [context_id] });
+    if method == "subscribe" {
+        add_offsets(state, &mut params, &[context_id.to_string()]);
+    }
+    serde_json::json!({
+        "id": state.next_request_id,
+        "method": method,
+        "params": params,
+    })
+}
+
+/// The event itself, without the JSON-RPC envelope it arrives in.
+fn event_body(event: &serde_json::Value) -> &serde_json::Value {
+    event.get("result").unwrap_or(event)
+}
+
+// ============================================================================
+// WebSocket connection
+// ============================================================================
+
+/// First delay before reconnecting a dropped socket.
+const RECONNECT_MIN_DELAY: Duration = Duration::from_millis(500);
+
+/// Cap on the delay between reconnection attempts.
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// WebSocket endpoint of the node at `api_url` (`ws://` or `wss://`).
+pub fn ws_url(api_url: &Url) -> eyre::Result<Url> {
+    let mut url = api_url.join("ws").wrap_err("Invalid node URL")?;
+    let scheme = match url.scheme() {
+        "https" => "wss",
+        _ => "ws",
+    };
+    url.set_scheme(scheme)
+        .map_err(|()| eyre::eyre!("Cannot derive a WebSocket URL from {}", api_url))?;
+    Ok(url)
+}
+
+#[derive(Debug, Default)]
+struct HubStatus {
+    connected: AtomicBool,
+    connects: AtomicU64,
+    drops: AtomicU64,
+    dropped_events: AtomicU64,
+    replayed_duplicates: AtomicU64,
+    filtered_events: AtomicU64,
+    /// Set by [`Hub::shutdown`].
+    shut_down: AtomicBool,
+}
+
+/// The shared WebSocket of one client, kept connected in the background
+/// for as long as the hub lives.
+pub struct Hub {
+    mux: Arc<Multiplexer>,
+    outgoing: mpsc::UnboundedSender<serde_json::Value>,
+    status: Arc<HubStatus>,
+    runtime: Handle,
+    /// Delivery offsets of contexts subscribed with replay.
+    offsets: Arc<OffsetStore>,
+}
+
+impl Hub {
+    /// Start connecting to `url` on `runtime`, authenticating with the
+    /// tokens `storage` holds for `node_name`.
+    pub fn start(
+        runtime: &Handle,
+        url: Url,
+        storage: MeroboxFileStorage,
+        node_name: Option<String>,
+    ) -> Self {
+        let mux = Arc::new(Multiplexer::new());
+        let status = Arc::new(HubStatus::default());
+        let offsets = Arc::new(match storage.persists_to_disk() {
+            true => OffsetStore::open(node_name.as_deref().unwrap_or(url.as_str()), storage.user()),
+            false => OffsetStore::memory(),
+        });
+        let (outgoing, frames) = mpsc::unbounded_channel();
+        runtime.spawn(drive(
+            url,
+            storage,
+            node_name,
+            mux.clone(),
+            frames,
+            status.clone(),
+        ));
+        Self {
+            mux,
+            outgoing,
+            status,
+            runtime: runtime.clone(),
+            offsets,
+        }
+    }
+
+    /// Receive the events of `context_id` that pass `filter`, buffering up
+    /// to `capacity`. With `replay`, events after the context's committed
+    /// offset are requested again.
+    pub fn subscribe(
+        &self,
+        context_id: &str,
+        capacity: usize,
+        replay: bool,
+        filter: Option<Arc<EventFilter>>,
+    ) -> mpsc::Receiver<serde_json::Value> {
+        if replay {
+            self.mux
+                .replay_from(context_id, self.offsets.get(context_id));
+        }
+        let (receiver, frame) = self.mux.subscribe(context_id, capacity, filter);
+        if let Some(frame) = frame {
+            // Sent once connected; a fresh connection resubscribes anyway.
+            let _ = self.outgoing.send(frame);
+        }
+        receiver
+    }
+
+    /// Events received for `context_id` so far (see [`Multiplexer::history`])
+    pub fn history(&self, context_id: &str) -> Vec<serde_json::Value> {
+        self.mux.history(context_id)
+    }
+
+    /// Events received for `context_id` with their receipt times
+    pub fn timed_history(&self, context_id: &str) -> Vec<(i64, serde_json::Value)> {
+        self.mux.timed_history(context_id)
+    }
+
+    /// `{connected, connects, drops, droppedEvents, filteredEvents,
+    /// replayedDuplicates, contexts}`
+    pub fn stats(&self) -> serde_json::Value {
+        serde_json::json!({
+            "connected": self.status.connected.load(Ordering::Relaxed),
+            "connects": self.status.connects.load(Ordering::Relaxed),
+            "drops": self.status.drops.load(Ordering::Relaxed),
+            "droppedEvents": self.status.dropped_events.load(Ordering::Relaxed),
+            "filteredEvents": self.status.filtered_events.load(Ordering::Relaxed),
+            "replayedDuplicates": self.status.replayed_duplicates.load(Ordering::Relaxed),
+            "contexts": self.mux.contexts(),
+        })
+    }
+
+    /// End every subscription of the hub, for a client being closed. The
+    /// socket itself goes with the client's runtime.
+    pub fn shutdown(&self) {
+        self.status.shut_down.store(true, Ordering::Relaxed);
+        self.mux.close_all();
+    }
+}
+
+/// How a connection ended.
+enum Ended {
+    /// The socket failed or the node closed it; reconnect.
+    Dropped,
+    /// The hub was dropped; stop.
+    Shutdown,
+}
+
+/// Keep the socket connected until the hub is dropped.
+async fn drive(
+    url: Url,
+    storage: MeroboxFileStorage,
+    node_name: Option<String>,
+    mux: Arc<Multiplexer>,
+    mut frames: mpsc::UnboundedReceiver<serde_json::Value>,
+    status: Arc<HubStatus>,
+) {
+    let mut delay = RECONNECT_MIN_DELAY;
+    loop {
+        if let Ok(socket) = connect(&url, &storage, node_name.as_deref()).await {
+            status.connected.store(true, Ordering::Relaxed);
+            status.connects.fetch_add(1, Ordering::Relaxed);
+            delay = RECONNECT_MIN_DELAY;
+            // Frames queued while disconnected are covered by resubscribing.
+            while frames.try_recv().is_ok() {}
+            let ended = serve(socket, &mux, &mut frames, &status).await;
+            status.connected.store(false, Ordering::Relaxed);
+            match ended {
+                Ended::Shutdown => return,
+                Ended::Dropped => status.drops.fetch_add(1, Ordering::Relaxed),
+            };
+        }
+
+        let wait = tokio::time::sleep(delay);
+        tokio::pin!(wait);
+        loop {
+            tokio::select! {
+                _ = &mut wait => break,
+                frame = frames.recv() => if frame.is_none() {
+                    return;
+                },
+            }
+        }
+        delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+    }
+}
+
+async fn connect(
+    url: &Url,
+    storage: &MeroboxFileStorage,
+    node_name: Option<&str>,
+) -> eyre::Result<Socket> {
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .wrap_err("Invalid WebSocket URL")?;
+    request
+        .headers_mut()
+        .extend(http::auth_headers(storage, node_name).await?);
+    let (socket, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .wrap_err_with(|| format!("WebSocket connection to {} failed", url))?;
+    Ok(socket)
+}
+
+/// Pump frames out and events in until the socket drops or the hub goes.
+async fn serve(
+    socket: Socket,
+    mux: &Multiplexer,
+    frames: &mut mpsc::UnboundedReceiver<serde_json::Value>,
+    status: &HubStatus,
+) -> Ended {
+    let (mut sink, mut stream) = socket.split();
+    if let Some(frame) = mux.resubscribe() {
+        if sink.send(Message::Text(frame.to_string())).await.is_err() {
+            return Ended::Dropped;
+        }
+    }
+    loop {
+        tokio::select! {
+            frame = frames.recv() => {
+                let Some(frame) = frame else {
+                    let _ = sink.close().await;
+                    return Ended::Shutdown;
+                };
+                if sink.send(Message::Text(frame.to_string())).await.is_err() {
+                    return Ended::Dropped;
+                }
+            }
+            message = stream.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let Ok(event) = serde_json::from_str::<serde_json::Value>(&text) else {
+                        continue;
+                    };
+                    let routed = mux.route(&event);
+                    if routed.duplicate {
+                        status.replayed_duplicates.fetch_add(1, Ordering::Relaxed);
+                    }
+                    status
+                        .dropped_events
+                        .fetch_add(routed.dropped as u64, Ordering::Relaxed);
+                    status
+                        .filtered_events
+                        .fetch_add(routed.filtered as u64, Ordering::Relaxed);
+                    if let Some(frame) = routed.unsubscribe {
+                        if sink.send(Message::Text(frame.to_string())).await.is_err() {
+                            return Ended::Dropped;
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return Ended::Dropped,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+// ============================================================================
+// Python API
+// ============================================================================
+
+/// Wait until `closed` is set. A dropped sender never counts as closed:
+/// callback subscriptions outlive their `Subscription` object.
+async fn closed_signal(closed: &mut watch::Receiver<bool>) {
+    if closed.wait_for(|closed| *closed).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Next event of a streamed subscription, `None` once it is closed.
+/// Asking for it means the previous one was processed, so that one is
+/// committed first (best effort: an offset not committed is replayed).
+async fn next_event(
+    receiver: Arc<tokio::sync::Mutex<mpsc::Receiver<serde_json::Value>>>,
+    mut closed: watch::Receiver<bool>,
+    acks: Option<Arc<Acks>>,
+) -> Option<serde_json::Value> {
+    if let Some(acks) = &acks {
+        let _ = acks.commit();
+    }
+    let mut receiver = receiver.lock().await;
+    let event = tokio::select! {
+        event = receiver.recv() => event,
+        _ = closed_signal(&mut closed) => None,
+    };
+    match (&acks, &event) {
+        (Some(acks), Some(event)) => acks.handed_out(event_offset(event)),
+        (_, None) => receiver.close(),
+        (None, Some(_)) => {}
+    }
+    event
+}
+
+/// Offsets of a subscription with replay: the latest event handed to the
+/// consumer, committed to the store once it was processed.
+struct Acks {
+    context_id: String,
+    store: Arc<OffsetStore>,
+    pending: Mutex<Option<u64>>,
+}
+
+impl Acks {
+    fn new(hub: &Hub, context_id: &str) -> Self {
+        Self {
+            context_id: context_id.to_string(),
+            store: hub.offsets.clone(),
+            pending: Mutex::new(None),
+        }
+    }
+
+    /// Note that the event at `offset` was handed to the consumer.
+    fn handed_out(&self, offset: Option<u64>) {
+        if let Ok(mut pending) = self.pending.lock() {
+            *pending = (*pending).max(offset);
+        }
+    }
+
+    /// Commit the events handed out so far as processed.
+    fn commit(&self) -> eyre::Result<()> {
+        let offset = self
+            .pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.take());
+        match offset {
+            Some(offset) => self.store.commit(&self.context_id, offset),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Events of one context, from `Client.subscribe()`.
+///
+/// Without a callback, iterate it with `async for event in subscription`,
+/// or from synchronous code with `for event in subscription` (or `get()`,
+/// which can time out). With one, event batches are passed to `callback(events)` from a
+/// background thread until `close()`. Reconnection after network drops is
+/// automatic; events sent while disconnected are missed unless the
+/// subscription replays (`replay=True`), in which case each event's offset
+/// is committed once the next one is requested or the callback returned.
+#[pyclass(name = "Subscription")]
+pub struct PySubscription {
+    context_id: String,
+    /// `None` when a callback consumes the events.
+    receiver: Option<Arc<tokio::sync::Mutex<mpsc::Receiver<serde_json::Value>>>>,
+    closed: watch::Sender<bool>,
+    /// `None` without replay.
+    acks: Option<Arc<Acks>>,
+    filter: Option<Arc<EventFilter>>,
+    runtime: Handle,
+    /// Status of the hub, shut down with the client.
+    hub: Arc<HubStatus>,
+}
+
+impl PySubscription {
+    /// Subscription consumed as an async iterator.
+    pub fn stream(
+        hub: &Hub,
+        context_id: &str,
+        capacity: usize,
+        replay: bool,
+        filter: Option<Arc<EventFilter>>,
+    ) -> Self {
+        let receiver = hub.subscribe(context_id, capacity, replay, filter.clone());
+        Self {
+            context_id: context_id.to_string(),
+            receiver: Some(Arc::new(tokio::sync::Mutex::new(receiver))),
+            closed: watch::channel(false).0,
+            acks: replay.then(|| Arc::new(Acks::new(hub, context_id))),
+            filter,
+            runtime: hub.runtime.clone(),
+            hub: hub.status.clone(),
+        }
+    }
+
+    /// Subscription delivering batches to `callback`, until it is closed
+    /// or a weakly held callback's owner is collected.
+    pub fn with_callback(
+        hub: &Hub,
+        context_id: &str,
+        capacity: usize,
+        replay: bool,
+        filter: Option<Arc<EventFilter>>,
+        callback: Callback,
+    ) -> Self {
+        let mut receiver = hub.subscribe(context_id, capacity, replay, filter.clone());
+        let acks = replay.then(|| Arc::new(Acks::new(hub, context_id)));
+        let batch_acks = acks.clone();
+        let (closed, mut closed_rx) = watch::channel(false);
+        hub.runtime.spawn(async move {
+            loop {
+                let batch = tokio::select! {
+                    batch = events::next_batch(&mut receiver, BatchPolicy::default()) => batch,
+                    _ = closed_signal(&mut closed_rx) => None,
+                };
+                let Some(batch) = batch else {
+                    break;
+                };
+                let last_offset = batch.iter().filter_map(event_offset).max();
+                let batch: Vec<serde_json::Value> = batch.iter().map(event_body).cloned().collect();
+                let delivered = Python::with_gil(|py| {
+                    // Closed while waiting for the GIL: drop the batch.
+                    if *closed_rx.borrow() {
+                        return false;
+                    }
+                    let Some(callback) = callback.upgrade(py) else {
+                        return false;
+                    };
+                    match events::deliver(py, &callback, &batch, None) {
+                        // Best effort: an offset not committed is replayed.
+                        Ok(()) => {
+                            if let Some(acks) = &batch_acks {
+                                acks.handed_out(last_offset);
+                                let _ = acks.commit();
+                            }
+                        }
+                        Err(e) => e.print(py),
+                    }
+                    true
+                });
+                if !delivered {
+                    break;
+                }
+            }
+            receiver.close();
+        });
+        Self {
+            context_id: context_id.to_string(),
+            receiver: None,
+            closed,
+            acks,
+            filter,
+            runtime: hub.runtime.clone(),
+            hub: hub.status.clone(),
+        }
+    }
+
+    /// The next event, as a future that can be awaited without the GIL.
+    fn receive(&self) -> PyResult<impl std::future::Future<Output = Option<serde_json::Value>>> {
+        let Some(receiver) = self.receiver.clone() else {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "this subscription delivers its events to a callback",
+            ));
+        };
+        Ok(next_event(
+            receiver,
+            self.closed.subscribe(),
+            self.acks.clone(),
+        ))
+    }
+}
+
+#[pymethods]
+impl PySubscription {
+    #[getter]
+    pub fn context_id(&self) -> String {
+        self.context_id.clone()
+    }
+
+    /// Whether the subscription or its client was closed
+    #[getter]
+    pub fn closed(&self) -> bool {
+        *self.closed.borrow() || self.hub.shut_down.load(Ordering::Relaxed)
+    }
+
+    /// The filter events must pass to be delivered, as given.
+    #[getter]
+    pub fn filter(&self) -> Option<String> {
+        self.filter.as_ref().map(ToString::to_string)
+    }
+
+    /// Whether the subscription replays events missed since its last
+    /// committed offset.
+    #[getter]
+    pub fn replay(&self) -> bool {
+        self.acks.is_some()
+    }
+
+    /// Offset of the last event committed as processed for this context,
+    /// `None` without replay or before the first one.
+    #[getter]
+    pub fn offset(&self) -> Option<u64> {
+        let acks = self.acks.as_ref()?;
+        acks.store.get(&acks.context_id)
+    }
+
+    /// Commit the events handed out so far as processed now, rather than
+    /// when the next one is requested. Does nothing without replay.
+    pub fn ack(&self) -> PyResult<()> {
+        match &self.acks {
+            Some(acks) => acks
+                .commit()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{:#}", e))),
+            None => Ok(()),
+        }
+    }
+
+    /// Stop receiving events; iteration ends and the callback is no longer
+    /// called. The last event handed out is not committed, so it is
+    /// replayed by the next subscription with replay.
+    pub fn close(&self) {
+        self.closed.send_replace(true);
+        if let Some(receiver) = &self.receiver {
+            if let Ok(mut receiver) = receiver.try_lock() {
+                receiver.close();
+            }
+        }
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let next = self.receive()?;
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            match next.await {
+                Some(event) => Python::with_gil(|py| Ok(json_to_python(py, event_body(&event)))),
+                None => Err(PyErr::new::<pyo3::exceptions::PyStopAsyncIteration, _>(())),
+            }
+        })
+    }
+
+    /// Block until the next event and return it, or `None` once the
+    /// subscription is closed or `timeout` seconds passed without one.
+    /// Other Python threads run while it waits; Ctrl-C interrupts it.
+    #[pyo3(signature = (timeout=None))]
+    pub fn get(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Option<PyObject>> {
+        let timeout = timeouts::seconds("timeout", timeout)?;
+        let next = self.receive()?;
+        // The client's runtime is gone; nothing more will arrive.
+        if self.hub.shut_down.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let runtime = self.runtime.clone();
+        let context_id = self.context_id.clone();
+        let event = py.allow_threads(move || {
+            error::catch_panic(
+                move || serde_json::json!({"operation": "subscription_get", "context_id": context_id}),
+                || timeouts::block_on_handle(&runtime, timeouts::bounded(timeout, next)),
+            )
+            .and_then(|outcome| outcome)
+        })?;
+        Ok(event
+            .ok()
+            .flatten()
+            .map(|event| json_to_python(py, event_body(&event))))
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        self.get(py, None)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Subscription(context_id='{}', closed={})",
+            self.context_id,
+            if self.closed() { "True" } else { "False" }
+        )
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Events reach only their own context's subscribers.
+    #[tokio::test]
+    async fn test_route_by_context() {
+        let mux = Multiplexer::new();
+        let (mut a, frame) = mux.subscribe("a", 8, None);
+        assert_eq!(frame.unwrap()["method"], "subscribe");
+        let (mut b, frame) = mux.subscribe("b", 8, None);
+        assert!(frame.is_some());
+        let (_a2, frame) = mux.subscribe("a", 8, None);
+        assert!(frame.is_none());
+
+        let routed = mux.route(&json!({"result": {"contextId": "a", "n": 1}}));
+        assert_eq!(routed.delivered, 2);
+        assert_eq!(a.recv().await.unwrap()["result"]["n"], 1);
+        assert!(b.try_recv().is_err());
+        assert_eq!(mux.route(&json!({"n": 2})), Routed::default());
+        assert_eq!(mux.history("a").len(), 1);
+        assert!(mux.history("b").is_empty());
+    }
+
+    /// History keeps the latest events of a context, oldest first.
+    #[test]
+    fn test_history_is_bounded() {
+        let mux = Multiplexer::new();
+        let (_receiver, _) = mux.subscribe("a", EVENT_HISTORY_LIMIT + 5, None);
+        for n in 0..EVENT_HISTORY_LIMIT + 5 {
+            mux.route(&json!({"contextId": "a", "n": n}));
+        }
+        let history = mux.history("a");
+        assert_eq!(history.len(), EVENT_HISTORY_LIMIT);
+        assert_eq!(history[0]["n"], 5);
+        let timed = mux.timed_history("a");
+        assert!(timed.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    }
+
+    /// The last subscriber leaving yields an unsubscribe frame.
+    #[test]
+    fn test_unsubscribe_when_last_receiver_drops() {
+        let mux = Multiplexer::new();
+        let (receiver, _) = mux.subscribe("a", 1, None);
+        drop(receiver);
+        let routed = mux.route(&json!({"contextId": "a"}));
+        assert_eq!(routed.delivered, 0);
+        assert_eq!(routed.unsubscribe.unwrap()["method"], "unsubscribe");
+        assert!(mux.contexts().is_empty());
+    }
+
+    /// Closing the multiplexer ends receivers after their buffered events.
+    #[test]
+    fn test_close_all_ends_receivers() {
+        let mux = Multiplexer::new();
+        let (mut receiver, _) = mux.subscribe("a", 4, None);
+        mux.route(&json!({"contextId": "a", "n": 1}));
+        mux.close_all();
+        assert!(mux.contexts().is_empty());
+        assert_eq!(receiver.try_recv().unwrap()["n"], 1);
+        assert_eq!(
+            receiver.try_recv(),
+            Err(mpsc::error::TryRecvError::Disconnected)
+        );
+    }
+
+    /// A reconnect subscribes every live context in one frame.
+    #[test]
+    fn test_resubscribe_covers_live_contexts() {
+        let mux = Multiplexer::new();
+        assert!(mux.resubscribe().is_none());
+        let (_a, _) = mux.subscribe("a", 1, None);
+        let (_b, _) = mux.subscribe("b", 1, None);
+        let frame = mux.resubscribe().unwrap();
+        assert_eq!(frame["method"], "subscribe");
+        assert_eq!(frame["params"]["contextIds"], json!(["a", "b"]));
+    }
+
+    /// Filtered subscribers only receive matching events; others get all.
+    #[test]
+    fn test_route_through_filters() {
+        let mux = Multiplexer::new();
+        let filter = Arc::new(EventFilter::parse("type:StateMutation").unwrap());
+        let (mut filtered, _) = mux.subscribe("a", 8, Some(filter));
+        let (mut all, _) = mux.subscribe("a", 8, None);
+        let routed = mux.route(&json!({"contextId": "a", "type": "Heartbeat"}));
+        assert_eq!((routed.delivered, routed.filtered), (1, 1));
+        mux.route(&json!({"contextId": "a", "type": "StateMutation"}));
+        assert_eq!(filtered.try_recv().unwrap()["type"], "StateMutation");
+        assert!(filtered.try_recv().is_err());
+        assert_eq!(all.try_recv().unwrap()["type"], "Heartbeat");
+    }
+
+    /// Replaying contexts ask for events after their offset and drop
+    /// events at or below the latest one routed.
+    #[test]
+    fn test_replay_offsets_and_duplicates() {
+        let mux = Multiplexer::new();
+        mux.replay_from("a", Some(4));
+        let (_a, frame) = mux.subscribe("a", 8, None);
+        assert_eq!(frame.unwrap()["params"]["fromOffsets"], json!({"a": 4}));
+        let (_b, frame) = mux.subscribe("b", 8, None);
+        assert!(frame.unwrap()["params"].get("fromOffsets").is_none());
+
+        assert!(mux.route(&json!({"contextId": "a", "offset": 4})).duplicate);
+        assert_eq!(
+            mux.route(&json!({"contextId": "a", "offset": 5})).delivered,
+            1
+        );
+        assert!(mux.route(&json!({"contextId": "a", "offset": 5})).duplicate);
+        assert!(!mux.route(&json!({"contextId": "b", "offset": 1})).duplicate);
+        let frame = mux.resubscribe().unwrap();
+        assert_eq!(frame["params"]["fromOffsets"], json!({"a": 5}));
+    }
+
+    /// The WebSocket endpoint follows the API URL's scheme.
+    #[test]
+    fn test_ws_url() {
+        let url = |api: &str| ws_url(&Url::parse(api).unwrap()).unwrap().to_string();
+        assert_eq!(url("http://localhost:2528"), "ws://localhost:2528/ws");
+        assert_eq!(url("https://node.example/"), "wss://node.example/ws");
+    }
+}
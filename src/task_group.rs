@@ -0,0 +1,233 @@
+//! Structured concurrency for client calls.
+//!
+//! `client.task_group()` collects `execute_function` calls and runs them
+//! together inside Rust with all-or-cancel semantics: the calls go out
+//! concurrently, and the first one to fail drops the others — their
+//! requests are aborted rather than left running — before its error is
+//! raised. On success the results come back in the order the calls were
+//! added.
+//!
+//! ```python
+//! with client.task_group() as group:
+//!     group.execute(ctx, "get", '{"key": "a"}')
+//!     group.execute(ctx, "get", '{"key": "b"}')
+//! a, b = group.results
+//!
+//! results = await group.run_async()   # from asyncio
+//! ```
+//!
+//! Each call takes its own concurrency slot, so a group never exceeds the
+//! client's limit.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+
+use calimero_primitives::context::ContextId;
+use futures_util::future::{try_join_all, TryFutureExt};
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
+use serde_json::Value;
+
+use crate::client::PyClient;
+use crate::utils::json_to_python;
+
+/// A call of a group, turned into a future when the group runs.
+pub type GroupFuture = Pin<Box<dyn Future<Output = eyre::Result<Value>> + Send>>;
+
+/// One `execute_function` call added to a group.
+#[derive(Debug, Clone)]
+pub struct GroupCall {
+    pub context_id: ContextId,
+    pub method: String,
+    pub args: String,
+    pub executor_public_key: String,
+}
+
+/// Run `calls` concurrently. The first failure drops the calls still
+/// running and comes back with the index of the call that failed.
+pub async fn join_all_or_cancel(
+    calls: Vec<GroupFuture>,
+) -> Result<Vec<Value>, (usize, eyre::Report)> {
+    try_join_all(
+        calls
+            .into_iter()
+            .enumerate()
+            .map(|(index, call)| call.map_err(move |e| (index, e))),
+    )
+    .await
+}
+
+/// Calls run together with all-or-cancel semantics; see the module docs.
+#[pyclass(name = "TaskGroup")]
+pub struct PyTaskGroup {
+    client: Py<PyClient>,
+    calls: Vec<GroupCall>,
+    results: Option<Vec<Value>>,
+    /// Set by the failing call, from whichever thread runs the group.
+    failed: Arc<OnceLock<usize>>,
+    started: bool,
+}
+
+impl PyTaskGroup {
+    pub fn new(client: Py<PyClient>) -> Self {
+        Self {
+            client,
+            calls: Vec::new(),
+            results: None,
+            failed: Arc::new(OnceLock::new()),
+            started: false,
+        }
+    }
+
+    /// The calls to run; a group runs once.
+    fn take_calls(&mut self) -> PyResult<Vec<GroupCall>> {
+        if self.started {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "task group already ran; create a new one with task_group()",
+            ));
+        }
+        self.started = true;
+        Ok(std::mem::take(&mut self.calls))
+    }
+}
+
+#[pymethods]
+impl PyTaskGroup {
+    /// Add an `execute_function` call; returns its index in the results.
+    #[pyo3(signature = (context_id, method, args, executor_public_key=""))]
+    pub fn execute(
+        &mut self,
+        py: Python<'_>,
+        context_id: &str,
+        method: &str,
+        args: &str,
+        executor_public_key: &str,
+    ) -> PyResult<usize> {
+        if self.started {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "task group already ran; create a new one with task_group()",
+            ));
+        }
+        let context_id = self.client.borrow(py).context_id(context_id)?;
+        self.calls.push(GroupCall {
+            context_id,
+            method: method.to_string(),
+            args: args.to_string(),
+            executor_public_key: executor_public_key.to_string(),
+        });
+        Ok(self.calls.len() - 1)
+    }
+
+    /// Run the calls and return their results in order. The first failure
+    /// cancels the other calls and is raised; `failed_index` names it.
+    pub fn run(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        let calls = self.take_calls()?;
+        let results = self
+            .client
+            .borrow(py)
+            .run_group(&calls, self.failed.clone())?;
+        let list = PyList::new_bound(py, results.iter().map(|r| json_to_python(py, r)));
+        self.results = Some(results);
+        Ok(list.into_any().unbind())
+    }
+
+    /// Asyncio variant of `run`: an awaitable resolving to the results.
+    /// Cancelling the awaiting task cancels every call of the group.
+    pub fn run_async<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let calls = self.take_calls()?;
+        PyClient::run_group_async(self.client.bind(py), &calls, self.failed.clone())
+    }
+
+    /// Results of the calls once `run()` or the `with` block succeeded,
+    /// else `None`
+    #[getter]
+    pub fn results(&self, py: Python<'_>) -> Option<PyObject> {
+        self.results.as_ref().map(|results| {
+            PyList::new_bound(py, results.iter().map(|r| json_to_python(py, r)))
+                .into_any()
+                .unbind()
+        })
+    }
+
+    /// Index of the call whose failure cancelled the group, if one did
+    #[getter]
+    pub fn failed_index(&self) -> Option<usize> {
+        self.failed.get().copied()
+    }
+
+    fn __len__(&self) -> usize {
+        self.calls.len()
+    }
+
+    fn __enter__(slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf
+    }
+
+    /// Run the calls when the block completes; when it raised, they are
+    /// dropped unsent.
+    #[pyo3(signature = (*args))]
+    fn __exit__(&mut self, py: Python<'_>, args: &Bound<'_, PyTuple>) -> PyResult<bool> {
+        let raised = args.get_item(0).is_ok_and(|exc_type| !exc_type.is_none());
+        if raised {
+            self.started = true;
+            self.calls.clear();
+        } else if !self.started {
+            self.run(py)?;
+        }
+        Ok(false)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TaskGroup(calls={}, ran={})",
+            self.calls
+                .len()
+                .max(self.results.as_ref().map_or(0, Vec::len)),
+            self.started
+        )
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Results keep call order.
+    #[tokio::test]
+    async fn test_results_in_order() {
+        let calls: Vec<GroupFuture> = vec![
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok(Value::from(0))
+            }),
+            Box::pin(async { Ok(Value::from(1)) }),
+        ];
+        let results = join_all_or_cancel(calls).await.unwrap();
+        assert_eq!(results, [Value::from(0), Value::from(1)]);
+    }
+
+    /// A failure drops the calls still running and reports its index.
+    #[tokio::test]
+    async fn test_failure_cancels_siblings() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let calls: Vec<GroupFuture> = vec![
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+                let _ = tx.send(());
+                Ok(Value::Null)
+            }),
+            Box::pin(async { Err(eyre::eyre!("boom")) }),
+        ];
+        let (index, error) = join_all_or_cancel(calls).await.unwrap_err();
+        assert_eq!(index, 1);
+        assert_eq!(error.to_string(), "boom");
+        // The sleeping call was dropped, closing its sender unsent.
+        assert!(rx.await.is_err());
+    }
+}
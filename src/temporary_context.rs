@@ -0,0 +1,155 @@
+//! Scoped contexts for examples and integration tests.
+//!
+//! `Client.temporary_context(...)` returns a [`PyTemporaryContext`] whose
+//! `with` block creates a context on entry and deletes it on exit, even when
+//! the block raises, so scripts and tests stop leaking contexts on the node.
+
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+
+use crate::client::PyClient;
+use crate::log_bridge;
+
+/// A context that lives for the duration of a `with` block.
+#[pyclass(name = "TemporaryContext")]
+pub struct PyTemporaryContext {
+    client: Py<PyClient>,
+    application_id: String,
+    group_id: String,
+    params: Option<String>,
+    service_name: Option<String>,
+    context_id: Option<String>,
+    member_public_key: Option<String>,
+    deleted: bool,
+}
+
+impl PyTemporaryContext {
+    pub fn new(
+        client: Py<PyClient>,
+        application_id: &str,
+        group_id: &str,
+        params: Option<&str>,
+        service_name: Option<&str>,
+    ) -> Self {
+        Self {
+            client,
+            application_id: application_id.to_string(),
+            group_id: group_id.to_string(),
+            params: params.map(str::to_string),
+            service_name: service_name.map(str::to_string),
+            context_id: None,
+            member_public_key: None,
+            deleted: false,
+        }
+    }
+}
+
+/// String field `name` of a `create_context` response, inside its `data`
+/// envelope or at the top level.
+fn response_field(response: &Bound<'_, PyAny>, name: &str) -> Option<String> {
+    let data = response
+        .get_item("data")
+        .ok()
+        .filter(|data| !data.is_none())
+        .unwrap_or_else(|| response.clone());
+    data.get_item(name).ok()?.extract().ok()
+}
+
+#[pymethods]
+impl PyTemporaryContext {
+    /// Id of the created context (`None` before the `with` block)
+    #[getter]
+    pub fn context_id(&self) -> Option<String> {
+        self.context_id.clone()
+    }
+
+    /// Public key of the identity that created the context
+    #[getter]
+    pub fn member_public_key(&self) -> Option<String> {
+        self.member_public_key.clone()
+    }
+
+    #[getter]
+    pub fn application_id(&self) -> String {
+        self.application_id.clone()
+    }
+
+    /// Whether the context has been deleted again
+    #[getter]
+    pub fn deleted(&self) -> bool {
+        self.deleted
+    }
+
+    /// The client the context was created with
+    #[getter]
+    pub fn client(&self, py: Python<'_>) -> Py<PyClient> {
+        self.client.clone_ref(py)
+    }
+
+    fn __enter__(mut slf: PyRefMut<'_, Self>) -> PyResult<PyRefMut<'_, Self>> {
+        if slf.context_id.is_some() {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "temporary context is already in use; call temporary_context() again",
+            ));
+        }
+        let py = slf.py();
+        let response = slf.client.borrow(py).create_context(
+            &slf.application_id,
+            &slf.group_id,
+            slf.params.as_deref(),
+            slf.service_name.as_deref(),
+            false,
+        )?;
+        let response = response.bind(py);
+        let Some(context_id) = response_field(response, "contextId") else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "create_context returned no context id: {}",
+                response
+            )));
+        };
+        slf.member_public_key = response_field(response, "memberPublicKey");
+        slf.context_id = Some(context_id);
+        Ok(slf)
+    }
+
+    /// Delete the context. A failed delete raises when the block succeeded;
+    /// when the block raised, the delete error is logged and the block's
+    /// exception propagates.
+    #[pyo3(signature = (exc_type, *_rest))]
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        exc_type: &Bound<'_, PyAny>,
+        _rest: &Bound<'_, PyTuple>,
+    ) -> PyResult<bool> {
+        let Some(context_id) = self.context_id.clone().filter(|_| !self.deleted) else {
+            return Ok(false);
+        };
+        match self
+            .client
+            .borrow(py)
+            .delete_context(&context_id, None, None)
+        {
+            Ok(_) => self.deleted = true,
+            Err(e) if exc_type.is_none() => return Err(e),
+            Err(e) => log_bridge::warning(
+                py,
+                &format!("Failed to delete temporary context {}: {}", context_id, e),
+            ),
+        }
+        Ok(false)
+    }
+
+    fn __repr__(&self) -> String {
+        let context_id = match &self.context_id {
+            Some(context_id) => format!("'{}'", context_id),
+            None => "None".to_string(),
+        };
+        format!(
+            "TemporaryContext(application_id='{}', context_id={}, deleted={})",
+            self.application_id,
+            context_id,
+            if self.deleted { "True" } else { "False" }
+        )
+    }
+}
@@ -0,0 +1,232 @@
+//! Request timeouts and cancellation of in-flight calls.
+//!
+//! `Client(connect_timeout=..., request_timeout=...)` bounds how long
+//! connecting to the node, and a whole call including its retries, may
+//! take; a call running past `request_timeout` fails with
+//! `ErrorCode.TIMEOUT` instead of waiting for the socket to give up.
+//! `with client.timeout(seconds):` overrides the request timeout for the
+//! calls made on that thread inside the block.
+//!
+//! Blocking calls check for pending signals while they wait, so Ctrl-C
+//! raises `KeyboardInterrupt` at once and drops the in-flight request.
+//! That covers every wait on a runtime, not only requests: maintenance
+//! holds, readiness polls, browser logins and blob uploads all go through
+//! [`block_on`]. Awaitables abort their request when the asyncio task
+//! awaiting them is cancelled.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use tokio::runtime::{Handle, Runtime};
+use tokio::task::{JoinError, JoinHandle};
+
+/// How often a blocking call checks for Ctrl-C while it waits.
+pub const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+thread_local! {
+    /// Request timeouts of the `client.timeout()` blocks entered on this
+    /// thread, innermost last.
+    static OVERRIDES: RefCell<Vec<Option<Duration>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// `value` seconds as a timeout, rejecting zero, negative and non-finite
+/// values; `None` means no timeout.
+pub fn seconds(name: &str, value: Option<f64>) -> PyResult<Option<Duration>> {
+    match value {
+        None => Ok(None),
+        Some(secs) if secs.is_finite() && secs > 0.0 => Ok(Some(Duration::from_secs_f64(secs))),
+        Some(_) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "{} must be a positive number of seconds or None",
+            name
+        ))),
+    }
+}
+
+/// Request timeout for a call made now on this thread: the innermost
+/// `client.timeout()` block's, else the client's `default`.
+pub fn effective(default: Option<Duration>) -> Option<Duration> {
+    OVERRIDES.with(|overrides| overrides.borrow().last().copied().unwrap_or(default))
+}
+
+/// Error message of a call cut off by its timeout. Phrased so the error
+/// classifies as `ErrorCode.TIMEOUT`.
+pub fn timed_out_message(operation: &str, timeout: Duration) -> String {
+    format!(
+        "{} timed out after {}s (request_timeout)",
+        operation,
+        timeout.as_secs_f64()
+    )
+}
+
+/// Run `fut` for at most `timeout`; `Err` carries the timeout it ran past.
+pub async fn bounded<F: Future>(timeout: Option<Duration>, fut: F) -> Result<F::Output, Duration> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, fut)
+            .await
+            .map_err(|_| timeout),
+        None => Ok(fut.await),
+    }
+}
+
+/// Resolves with the exception of the first signal handler that raises
+/// (`KeyboardInterrupt` for Ctrl-C). Signals are only handled on the main
+/// thread; elsewhere this never resolves.
+pub async fn until_signal() -> PyErr {
+    let mut interval = tokio::time::interval(SIGNAL_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = Python::with_gil(|py| py.check_signals()) {
+            return e;
+        }
+    }
+}
+
+/// Drive `fut` on `runtime` until it completes or a signal handler raises;
+/// then `fut` is dropped, cancelling whatever it had in flight, and the
+/// handler's exception (`KeyboardInterrupt`) is returned.
+pub fn block_on<F: Future>(runtime: &Runtime, fut: F) -> PyResult<F::Output> {
+    runtime.block_on(async move {
+        tokio::select! {
+            output = fut => Ok(output),
+            interrupt = until_signal() => Err(interrupt),
+        }
+    })
+}
+
+/// [`block_on`] through a runtime handle.
+pub fn block_on_handle<F: Future>(handle: &Handle, fut: F) -> PyResult<F::Output> {
+    handle.block_on(async move {
+        tokio::select! {
+            output = fut => Ok(output),
+            interrupt = until_signal() => Err(interrupt),
+        }
+    })
+}
+
+/// A spawned task that is aborted when its handle is dropped, so a
+/// cancelled awaitable does not leave its request running.
+pub struct AbortOnDrop<T>(pub JoinHandle<T>);
+
+impl<T> Future for AbortOnDrop<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// `with client.timeout(seconds):` block; see [`PyTimeoutScope::__enter__`].
+#[pyclass(name = "TimeoutScope")]
+pub struct PyTimeoutScope {
+    timeout: Option<Duration>,
+    depth: Option<usize>,
+}
+
+impl PyTimeoutScope {
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            depth: None,
+        }
+    }
+}
+
+#[pymethods]
+impl PyTimeoutScope {
+    /// Request timeout in seconds inside the block (`None`: no timeout)
+    #[getter]
+    pub fn timeout(&self) -> Option<f64> {
+        self.timeout.map(|timeout| timeout.as_secs_f64())
+    }
+
+    /// Apply the timeout to calls made on this thread until the block ends.
+    fn __enter__(mut slf: PyRefMut<'_, Self>) -> PyResult<PyRefMut<'_, Self>> {
+        if slf.depth.is_some() {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "timeout block is already in use; call timeout() again",
+            ));
+        }
+        let timeout = slf.timeout;
+        slf.depth = Some(OVERRIDES.with(|overrides| {
+            let mut overrides = overrides.borrow_mut();
+            overrides.push(timeout);
+            overrides.len() - 1
+        }));
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&mut self, _args: &Bound<'_, PyTuple>) -> bool {
+        if let Some(depth) = self.depth.take() {
+            OVERRIDES.with(|overrides| overrides.borrow_mut().truncate(depth));
+        }
+        false
+    }
+
+    fn __repr__(&self) -> String {
+        match self.timeout {
+            Some(timeout) => format!("TimeoutScope(timeout={})", timeout.as_secs_f64()),
+            None => "TimeoutScope(timeout=None)".to_string(),
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The innermost override wins; without one the default applies.
+    #[test]
+    fn test_effective_timeout() {
+        let default = Some(Duration::from_secs(30));
+        assert_eq!(effective(default), default);
+        OVERRIDES.with(|overrides| overrides.borrow_mut().push(Some(Duration::from_secs(2))));
+        assert_eq!(effective(default), Some(Duration::from_secs(2)));
+        OVERRIDES.with(|overrides| overrides.borrow_mut().push(None));
+        assert_eq!(effective(default), None);
+        OVERRIDES.with(|overrides| overrides.borrow_mut().clear());
+        assert_eq!(effective(None), None);
+    }
+
+    /// A slow future is cut off at the timeout; a fast one completes.
+    #[tokio::test(start_paused = true)]
+    async fn test_bounded() {
+        let slow = tokio::time::sleep(Duration::from_secs(10));
+        assert_eq!(
+            bounded(Some(Duration::from_secs(1)), slow).await,
+            Err(Duration::from_secs(1))
+        );
+        assert_eq!(
+            bounded(Some(Duration::from_secs(1)), async { 7 }).await,
+            Ok(7)
+        );
+        assert_eq!(bounded(None, async { 7 }).await, Ok(7));
+    }
+
+    /// Dropping the handle aborts the task.
+    #[tokio::test]
+    async fn test_abort_on_drop() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let task = AbortOnDrop(tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+            let _ = tx.send(());
+        }));
+        drop(task);
+        assert!(rx.await.is_err());
+    }
+}
@@ -1,67 +1,208 @@
 //! Python wrapper for JwtToken
+//!
+//! Token strings are held in zeroizing buffers and wiped when the wrapper is
+//! dropped. They are never handed to Python implicitly: the `access_token`
+//! and `refresh_token` properties are redacted, and the raw values are only
+//! returned by an explicit `reveal()` call.
+//!
+//! Lifetimes are exposed as `datetime`/`timedelta` values computed from the
+//! stored `expires_at` or, for tokens without one, the JWT's `exp` claim.
+//! Tokens compare equal when all fields match and order by expiry (tokens
+//! that never expire sort last), so `max(tokens)` is the freshest.
 
 use calimero_client::JwtToken;
+use chrono::{DateTime, TimeDelta, Utc};
+use pyo3::basic::CompareOp;
 use pyo3::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::security::jwt_expiry;
+
+/// Placeholder returned in place of secret token strings.
+const REDACTED: &str = "<redacted>";
+
+/// Characters of a JWT shown by `repr()`: part of the (public) header.
+const PREVIEW_LEN: usize = 8;
+
+/// Truncated form of `token` for display: the start of a JWT's header, or
+/// nothing for opaque tokens, whose every character is secret.
+fn preview(token: &str) -> String {
+    let header = token.split('.').next().unwrap_or_default();
+    if token.starts_with("eyJ")
+        && token.split('.').count() == 3
+        && header.is_ascii()
+        && header.len() > PREVIEW_LEN
+    {
+        format!("{}…", &header[..PREVIEW_LEN])
+    } else {
+        REDACTED.to_string()
+    }
+}
 
 /// Python wrapper for JwtToken
 #[pyclass(name = "JwtToken")]
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct PyJwtToken {
-    access_token: String,
-    refresh_token: Option<String>,
+    access_token: Zeroizing<String>,
+    refresh_token: Option<Zeroizing<String>>,
     expires_at: Option<i64>,
 }
 
 #[pymethods]
 impl PyJwtToken {
+    /// `expires_at` is a Unix timestamp or a timezone-aware `datetime`.
     #[new]
     #[pyo3(signature = (access_token, refresh_token=None, expires_at=None))]
-    pub fn new(access_token: &str, refresh_token: Option<&str>, expires_at: Option<i64>) -> Self {
-        Self {
-            access_token: access_token.to_string(),
-            refresh_token: refresh_token.map(|s| s.to_string()),
+    pub fn new(
+        access_token: &str,
+        refresh_token: Option<&str>,
+        expires_at: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let expires_at = match expires_at {
+            None => None,
+            Some(value) => match value.extract::<i64>() {
+                Ok(timestamp) => Some(timestamp),
+                Err(_) => Some(
+                    value
+                        .extract::<DateTime<Utc>>()
+                        .map_err(|_| {
+                            PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                                "expires_at must be a Unix timestamp or a timezone-aware datetime",
+                            )
+                        })?
+                        .timestamp(),
+                ),
+            },
+        };
+        Ok(Self {
+            access_token: Zeroizing::new(access_token.to_string()),
+            refresh_token: refresh_token.map(|s| Zeroizing::new(s.to_string())),
             expires_at,
-        }
+        })
     }
 
+    /// Always `"<redacted>"`; use `reveal()` for the raw value
     #[getter]
-    pub fn access_token(&self) -> &str {
-        &self.access_token
+    pub fn access_token(&self) -> &'static str {
+        REDACTED
     }
 
+    /// `"<redacted>"` when a refresh token is held; use `reveal(refresh=True)`
     #[getter]
-    pub fn refresh_token(&self) -> Option<&str> {
-        self.refresh_token.as_deref()
+    pub fn refresh_token(&self) -> Option<&'static str> {
+        self.refresh_token.as_ref().map(|_| REDACTED)
     }
 
+    /// Expiry as a UTC `datetime`, or `None` when unknown
     #[getter]
-    pub fn expires_at(&self) -> Option<i64> {
-        self.expires_at
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expiry()
+            .and_then(|expiry| DateTime::from_timestamp(expiry, 0))
     }
 
+    /// Expiry as a Unix timestamp, or `None` when unknown
+    #[getter]
+    pub fn expires_at_timestamp(&self) -> Option<i64> {
+        self.expiry()
+    }
+
+    /// Whether the expiry has passed; tokens without one never expire
+    #[getter]
     pub fn is_expired(&self) -> bool {
-        if let Some(expires_at) = self.expires_at {
-            let now = chrono::Utc::now().timestamp();
-            now >= expires_at
+        self.remaining().is_some_and(|remaining| remaining <= 0)
+    }
+
+    /// Remaining lifetime as a `timedelta` (zero once expired), or `None`
+    /// when the expiry is unknown
+    #[getter]
+    pub fn ttl(&self) -> Option<TimeDelta> {
+        self.remaining()
+            .and_then(|remaining| TimeDelta::try_seconds(remaining.max(0)))
+    }
+
+    /// Return the raw access token (or refresh token with `refresh=True`).
+    ///
+    /// The returned Python string is outside the wrapper's control and will
+    /// not be wiped; keep it short-lived and out of logs.
+    #[pyo3(signature = (refresh=false))]
+    pub fn reveal(&self, refresh: bool) -> Option<String> {
+        if refresh {
+            self.refresh_token.as_ref().map(|t| t.to_string())
         } else {
-            false
+            Some(self.access_token.to_string())
+        }
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp) -> bool {
+        match op {
+            CompareOp::Eq => self == other,
+            CompareOp::Ne => self != other,
+            _ => op.matches(self.sort_key().cmp(&other.sort_key())),
         }
     }
 
     fn __str__(&self) -> String {
+        let expires_at = match self.expires_at() {
+            Some(expires_at) => expires_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            None => "None".to_string(),
+        };
         format!(
-            "JwtToken(access_token='{}...', expires_at={:?})",
-            &self.access_token[..self.access_token.len().min(10)],
-            self.expires_at
+            "JwtToken(access_token='{}', expires_at={}, is_expired={})",
+            preview(&self.access_token),
+            expires_at,
+            if self.is_expired() { "True" } else { "False" }
         )
     }
+
+    fn __repr__(&self) -> String {
+        self.__str__()
+    }
+}
+
+impl PyJwtToken {
+    /// Stored expiry, else the access token's `exp` claim.
+    fn expiry(&self) -> Option<i64> {
+        self.expires_at.or_else(|| jwt_expiry(&self.access_token))
+    }
+
+    /// Seconds until expiry (negative once expired).
+    fn remaining(&self) -> Option<i64> {
+        self.expiry()
+            .map(|expiry| expiry.saturating_sub(Utc::now().timestamp()))
+    }
+
+    /// Ordering key: expiry, with tokens that never expire last.
+    fn sort_key(&self) -> i64 {
+        self.expiry().unwrap_or(i64::MAX)
+    }
+}
+
+impl PartialEq for PyJwtToken {
+    fn eq(&self, other: &Self) -> bool {
+        *self.access_token == *other.access_token
+            && self.refresh_token.as_deref() == other.refresh_token.as_deref()
+            && self.expires_at == other.expires_at
+    }
+}
+
+impl std::fmt::Debug for PyJwtToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PyJwtToken")
+            .field("access_token", &REDACTED)
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| REDACTED),
+            )
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
 }
 
 impl From<JwtToken> for PyJwtToken {
     fn from(token: JwtToken) -> Self {
         Self {
-            access_token: token.access_token,
-            refresh_token: token.refresh_token,
+            access_token: Zeroizing::new(token.access_token),
+            refresh_token: token.refresh_token.map(Zeroizing::new),
             expires_at: token.expires_at,
         }
     }
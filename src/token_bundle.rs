@@ -0,0 +1,359 @@
+//! Portable token bundles for moving credentials between machines.
+//!
+//! `export_tokens` packs the cached tokens of one or more nodes — or of
+//! every node in the token cache — into a JSON bundle that can be stored as
+//! a CI secret or written to a file and copied to another machine, and
+//! `import_tokens` saves them into any token storage there, instead of
+//! copying raw cache files (whose names, encryption and layout depend on
+//! the machine).
+//!
+//! Refresh tokens are left out unless asked for, so an exported bundle only
+//! grants access until the access tokens expire. With a passphrase the
+//! tokens are sealed with [`crate::crypto`] under a PBKDF2 key; the node
+//! names stay readable so a bundle can be identified without opening it.
+//!
+//! ```text
+//! {"format": "calimero-token-bundle", "version": 1, "createdAt": ...,
+//!  "refreshIncluded": false, "nodes": [...],
+//!  "tokens": {node: {access_token, expires_at}}}        // plaintext
+//!  "encryption": {"algorithm", "kdf", "salt"}, "payload": "<base64>"  // sealed
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use calimero_client::traits::ClientStorage;
+use calimero_client::JwtToken;
+use eyre::WrapErr;
+use pyo3::prelude::*;
+use serde_json::{json, Map, Value};
+use zeroize::Zeroizing;
+
+use crate::cache_admin;
+use crate::connection::PyConnectionInfo;
+use crate::crypto;
+use crate::entropy;
+use crate::permissions::restrict_to_owner;
+use crate::storage::{MeroboxFileStorage, WipeOnDrop};
+
+/// `format` field identifying a token bundle.
+pub const BUNDLE_FORMAT: &str = "calimero-token-bundle";
+
+/// Current bundle version.
+pub const BUNDLE_VERSION: u64 = 1;
+
+/// Key derivation recorded for sealed bundles.
+const BUNDLE_KDF: &str = "pbkdf2-hmac-sha256";
+
+/// Build a bundle holding `tokens`, dropping refresh tokens unless
+/// `include_refresh`, sealed under `passphrase` when given.
+pub fn build(
+    tokens: &[(String, WipeOnDrop)],
+    include_refresh: bool,
+    passphrase: Option<&str>,
+) -> eyre::Result<Value> {
+    let mut entries = Map::new();
+    for (node, token) in tokens {
+        let mut token = serde_json::to_value(&**token).wrap_err("Failed to serialize tokens")?;
+        if !include_refresh {
+            if let Some(token) = token.as_object_mut() {
+                token.remove("refresh_token");
+            }
+        }
+        entries.insert(node.clone(), token);
+    }
+
+    let mut bundle = json!({
+        "format": BUNDLE_FORMAT,
+        "version": BUNDLE_VERSION,
+        "createdAt": chrono::Utc::now().to_rfc3339(),
+        "refreshIncluded": include_refresh,
+        "nodes": tokens.iter().map(|(node, _)| node.as_str()).collect::<Vec<_>>(),
+    });
+    match passphrase {
+        Some(passphrase) => {
+            let salt: [u8; 16] = entropy::random_bytes()?;
+            let key = crypto::derive_key(passphrase.as_bytes(), &salt);
+            let plaintext = Zeroizing::new(serde_json::to_vec(&Value::Object(entries))?);
+            let sealed = crypto::seal(key.as_slice(), &plaintext)?;
+            bundle["encryption"] = json!({
+                "algorithm": crypto::ENVELOPE_ALGORITHM,
+                "kdf": BUNDLE_KDF,
+                "salt": STANDARD.encode(salt),
+            });
+            bundle["payload"] = Value::String(STANDARD.encode(sealed));
+        }
+        None => bundle["tokens"] = Value::Object(entries),
+    }
+    Ok(bundle)
+}
+
+/// Tokens in `bundle`, opening it with `passphrase` when it is sealed.
+pub fn open(bundle: &Value, passphrase: Option<&str>) -> eyre::Result<Vec<(String, WipeOnDrop)>> {
+    if bundle["format"] != BUNDLE_FORMAT {
+        eyre::bail!("Not a token bundle (missing format \"{}\")", BUNDLE_FORMAT);
+    }
+    let version = bundle["version"].as_u64().unwrap_or(0);
+    if version == 0 || version > BUNDLE_VERSION {
+        eyre::bail!(
+            "Unsupported token bundle version {} (this release reads up to {})",
+            bundle["version"],
+            BUNDLE_VERSION
+        );
+    }
+
+    let entries = match &bundle["encryption"] {
+        Value::Null => bundle["tokens"].clone(),
+        encryption => {
+            if encryption["algorithm"] != crypto::ENVELOPE_ALGORITHM
+                || encryption["kdf"] != BUNDLE_KDF
+            {
+                eyre::bail!("Unsupported token bundle encryption: {}", encryption);
+            }
+            let Some(passphrase) = passphrase else {
+                eyre::bail!("Token bundle is encrypted; pass its passphrase");
+            };
+            let salt = STANDARD
+                .decode(encryption["salt"].as_str().unwrap_or_default())
+                .wrap_err("Invalid token bundle salt")?;
+            let sealed = STANDARD
+                .decode(bundle["payload"].as_str().unwrap_or_default())
+                .wrap_err("Invalid token bundle payload")?;
+            let key = crypto::derive_key(passphrase.as_bytes(), &salt);
+            let plaintext = Zeroizing::new(
+                crypto::open(key.as_slice(), &sealed)
+                    .wrap_err("Failed to open token bundle: wrong passphrase or corrupted data")?,
+            );
+            serde_json::from_slice(&plaintext).wrap_err("Token bundle payload is not JSON")?
+        }
+    };
+
+    let Value::Object(entries) = entries else {
+        eyre::bail!("Token bundle holds no tokens");
+    };
+    entries
+        .into_iter()
+        .map(|(node, token)| {
+            let token: JwtToken = serde_json::from_value(token)
+                .wrap_err_with(|| format!("Invalid tokens for node '{}'", node))?;
+            Ok((node, WipeOnDrop::new(token)))
+        })
+        .collect()
+}
+
+/// Storage of `connection`, or the default token files.
+pub(crate) fn target_storage(connection: Option<&PyConnectionInfo>) -> MeroboxFileStorage {
+    connection.map_or_else(MeroboxFileStorage::new, |connection| {
+        connection.storage.clone()
+    })
+}
+
+/// Drive a storage future to completion outside any client runtime.
+pub(crate) fn block_on<F: std::future::Future>(fut: F) -> PyResult<F::Output> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    Ok(runtime.block_on(fut))
+}
+
+/// Nodes with usable tokens in `storage`'s cache directory, for exporting
+/// everything. Per-user sessions are left out: bundles hold one session
+/// per node.
+fn cached_nodes(storage: &MeroboxFileStorage) -> PyResult<Vec<String>> {
+    if !storage.persists_to_disk() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Cannot list the nodes of {} token storage; name the nodes to export",
+            storage.backend()
+        )));
+    }
+    let mut nodes: Vec<String> =
+        cache_admin::scan(&storage.cache_dir(), chrono::Utc::now().timestamp())
+            .into_iter()
+            .filter(|entry| entry.user.is_none() && !entry.expired)
+            .map(|entry| entry.node)
+            .collect();
+    nodes.sort();
+    nodes.dedup();
+    Ok(nodes)
+}
+
+/// Write `bundle` to `path`, readable by the owner only.
+fn write_bundle(path: &Path, bundle: &str) -> eyre::Result<()> {
+    let temp_path = path.with_extension(format!("{}.tmp", entropy::temp_suffix()));
+    fs::write(&temp_path, "").wrap_err_with(|| format!("Failed to create {:?}", temp_path))?;
+    restrict_to_owner(&temp_path, false)?;
+    fs::write(&temp_path, bundle)
+        .wrap_err_with(|| format!("Failed to write temp file: {:?}", temp_path))?;
+    fs::rename(&temp_path, path)
+        .wrap_err_with(|| format!("Failed to rename {:?} to {:?}", temp_path, path))
+}
+
+fn bundle_error(e: eyre::Report) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e))
+}
+
+/// Export the cached tokens of `node` (a name or a list of names) as a
+/// JSON token bundle.
+///
+/// Without `node`, every node with usable tokens in the cache directory is
+/// exported (per-user sessions excepted). Refresh tokens are only included
+/// with `include_refresh=True`. With a `passphrase` the tokens are
+/// encrypted (import needs the same passphrase). Tokens are read from
+/// `connection`'s storage when given, else from the token cache directory.
+/// With `path`, the bundle is also written there, readable by the owner
+/// only. Raises `ValueError` when a node has no cached tokens.
+#[pyfunction]
+#[pyo3(signature = (node=None, include_refresh=false, passphrase=None, connection=None, path=None))]
+pub fn export_tokens(
+    node: Option<&Bound<'_, PyAny>>,
+    include_refresh: bool,
+    passphrase: Option<&str>,
+    connection: Option<PyRef<'_, PyConnectionInfo>>,
+    path: Option<PathBuf>,
+) -> PyResult<String> {
+    let storage = target_storage(connection.as_deref());
+    let nodes: Vec<String> = match node {
+        None => cached_nodes(&storage)?,
+        Some(node) => match node.extract::<String>() {
+            Ok(node) => vec![node],
+            Err(_) => node.extract()?,
+        },
+    };
+    if nodes.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "No cached tokens to export",
+        ));
+    }
+
+    let mut tokens = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let loaded = block_on(storage.load_tokens(&node))?.map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to load tokens for node '{}': {:#}",
+                node, e
+            ))
+        })?;
+        let Some(loaded) = loaded else {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "No cached tokens for node '{}'",
+                node
+            )));
+        };
+        tokens.push((node, WipeOnDrop::new(loaded)));
+    }
+
+    let bundle = build(&tokens, include_refresh, passphrase).map_err(bundle_error)?;
+    let bundle = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    if let Some(path) = path {
+        write_bundle(&path, &bundle)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("{:#}", e)))?;
+    }
+    Ok(bundle)
+}
+
+/// Save the tokens of a bundle made by `export_tokens`, given as its text
+/// or read from `path`.
+///
+/// Tokens go to `connection`'s storage when given, else to the token cache
+/// directory; `passphrase` opens encrypted bundles. Returns the imported
+/// node names. Raises `ValueError` for malformed bundles or a wrong
+/// passphrase.
+#[pyfunction]
+#[pyo3(signature = (bundle=None, passphrase=None, connection=None, path=None))]
+pub fn import_tokens(
+    bundle: Option<&str>,
+    passphrase: Option<&str>,
+    connection: Option<PyRef<'_, PyConnectionInfo>>,
+    path: Option<PathBuf>,
+) -> PyResult<Vec<String>> {
+    let text = match (bundle, &path) {
+        (Some(bundle), None) => Zeroizing::new(bundle.to_string()),
+        (None, Some(path)) => Zeroizing::new(fs::read_to_string(path).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyOSError, _>(format!(
+                "Failed to read token bundle {:?}: {}",
+                path, e
+            ))
+        })?),
+        _ => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Pass either a bundle or a path",
+            ))
+        }
+    };
+    let bundle: Value = serde_json::from_str(&text).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Token bundle is not JSON: {}", e))
+    })?;
+    let tokens = open(&bundle, passphrase).map_err(bundle_error)?;
+    let storage = target_storage(connection.as_deref());
+
+    let mut imported = Vec::with_capacity(tokens.len());
+    for (node, token) in &tokens {
+        block_on(storage.save_tokens(node, token))?.map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to save tokens for node '{}': {:#}",
+                node, e
+            ))
+        })?;
+        imported.push(node.clone());
+    }
+    Ok(imported)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens() -> Vec<(String, WipeOnDrop)> {
+        vec![(
+            "node".to_string(),
+            WipeOnDrop::new(JwtToken {
+                access_token: "access".to_string(),
+                refresh_token: Some("refresh".to_string()),
+                expires_at: Some(1_700_000_000),
+            }),
+        )]
+    }
+
+    /// Refresh tokens are only exported on request.
+    #[test]
+    fn test_plain_bundle_redacts_refresh() {
+        let bundle = build(&tokens(), false, None).unwrap();
+        assert_eq!(bundle["nodes"], json!(["node"]));
+        let opened = open(&bundle, None).unwrap();
+        assert_eq!(opened[0].1.access_token, "access");
+        assert!(opened[0].1.refresh_token.is_none());
+
+        let bundle = build(&tokens(), true, None).unwrap();
+        let opened = open(&bundle, None).unwrap();
+        assert_eq!(opened[0].1.refresh_token.as_deref(), Some("refresh"));
+    }
+
+    /// Sealed bundles hide the tokens and need the passphrase.
+    #[test]
+    fn test_sealed_bundle_needs_passphrase() {
+        let bundle = build(&tokens(), true, Some("secret")).unwrap();
+        assert!(bundle.get("tokens").is_none());
+        assert!(!bundle.to_string().contains("access"));
+        assert!(open(&bundle, None).is_err());
+        assert!(open(&bundle, Some("wrong")).is_err());
+        let opened = open(&bundle, Some("secret")).unwrap();
+        assert_eq!(opened[0].1.refresh_token.as_deref(), Some("refresh"));
+    }
+
+    /// Other JSON and newer versions are refused.
+    #[test]
+    fn test_rejects_foreign_bundles() {
+        assert!(open(&json!({"tokens": {}}), None).is_err());
+        let mut bundle = build(&tokens(), false, None).unwrap();
+        bundle["version"] = json!(BUNDLE_VERSION + 1);
+        assert!(open(&bundle, None).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! Proactive refresh of cached tokens before they expire.
+//!
+//! `calimero-client` refreshes a session only after the node rejects the
+//! access token with a 401, which costs a failed round trip and races when
+//! several calls hit the expiry at once. Instead, before each call the
+//! client checks the cached token's expiry (its JWT `exp` claim, or the
+//! stored `expires_at` for opaque tokens) and, once it is within the refresh
+//! margin, exchanges the refresh token for a new pair and saves it through
+//! the storage (atomically, for the file cache).
+//!
+//! Expiry is tracked per node in memory and re-read from storage at most
+//! every [`RECHECK_INTERVAL`], so calls do not pay a storage read each.
+//! Refreshes of one node are serialized by a per-node lock, and the tokens
+//! are re-read under it, so concurrent calls refresh once.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use calimero_client::traits::ClientStorage;
+use calimero_client::JwtToken;
+use eyre::WrapErr;
+use url::Url;
+
+use crate::auth_service::parse_tokens;
+use crate::security::jwt_expiry;
+
+/// Default time before expiry at which tokens are refreshed (seconds).
+pub const DEFAULT_REFRESH_MARGIN_SECS: u64 = 60;
+
+/// Auth-service endpoint exchanging a refresh token for a new pair.
+pub const REFRESH_PATH: &str = "auth/refresh";
+
+/// How long a node's tracked expiry is trusted before storage is re-read.
+const RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Expiry of `tokens`: the access token's `exp` claim, else `expires_at`.
+pub fn token_expiry(tokens: &JwtToken) -> Option<i64> {
+    jwt_expiry(&tokens.access_token).or(tokens.expires_at)
+}
+
+/// Whether `tokens` should be refreshed at `now` with `margin` seconds of
+/// headroom. Tokens without a refresh token or a known expiry never are.
+pub fn needs_refresh(tokens: &JwtToken, now: i64, margin: u64) -> bool {
+    tokens.refresh_token.is_some()
+        && token_expiry(tokens).is_some_and(|expiry| expiry.saturating_sub(now) <= margin as i64)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Tracked {
+    checked_at: Instant,
+    expiry: Option<i64>,
+}
+
+/// Per-node expiry tracking and refresh serialization for one client.
+#[derive(Debug)]
+pub struct TokenLifecycle {
+    margin: u64,
+    tracked: Mutex<HashMap<String, Tracked>>,
+    locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl TokenLifecycle {
+    pub fn new(margin: u64) -> Self {
+        Self {
+            margin,
+            tracked: Mutex::new(HashMap::new()),
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether the tracked state of `node_name` may be stale or due.
+    fn should_check(&self, node_name: &str, now: i64) -> bool {
+        let Ok(tracked) = self.tracked.lock() else {
+            return true;
+        };
+        match tracked.get(node_name) {
+            Some(entry) if entry.checked_at.elapsed() < RECHECK_INTERVAL => entry
+                .expiry
+                .is_some_and(|expiry| expiry.saturating_sub(now) <= self.margin as i64),
+            _ => true,
+        }
+    }
+
+    fn track(&self, node_name: &str, tokens: Option<&JwtToken>) {
+        if let Ok(mut tracked) = self.tracked.lock() {
+            tracked.insert(
+                node_name.to_string(),
+                Tracked {
+                    checked_at: Instant::now(),
+                    expiry: tokens.and_then(token_expiry),
+                },
+            );
+        }
+    }
+
+    fn node_lock(&self, node_name: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.locks
+            .lock()
+            .map(|mut locks| locks.entry(node_name.to_string()).or_default().clone())
+            .unwrap_or_default()
+    }
+
+    /// Refresh `node_name`'s tokens if they are about to expire. Returns
+    /// whether a refresh happened.
+    pub async fn ensure_fresh<S: ClientStorage>(
+        &self,
+        client: &reqwest::Client,
+        api_url: &Url,
+        storage: &S,
+        node_name: &str,
+    ) -> eyre::Result<bool> {
+        let now = chrono::Utc::now().timestamp();
+        if !self.should_check(node_name, now) {
+            return Ok(false);
+        }
+        let refreshed = self
+            .refresh_within(client, api_url, storage, node_name, self.margin)
+            .await?;
+        Ok(refreshed.is_some())
+    }
+
+    /// Refresh `node_name`'s stored tokens if they expire within `window`
+    /// seconds, serialized with [`Self::ensure_fresh`]. Returns the new
+    /// tokens when a refresh happened.
+    pub async fn refresh_within<S: ClientStorage>(
+        &self,
+        client: &reqwest::Client,
+        api_url: &Url,
+        storage: &S,
+        node_name: &str,
+        window: u64,
+    ) -> eyre::Result<Option<JwtToken>> {
+        let lock = self.node_lock(node_name);
+        let _refreshing = lock.lock().await;
+        let now = chrono::Utc::now().timestamp();
+        let tokens = storage.load_tokens(node_name).await?;
+        let Some(tokens) = tokens.filter(|tokens| needs_refresh(tokens, now, window)) else {
+            self.track(node_name, tokens.as_ref());
+            return Ok(None);
+        };
+
+        let refreshed = refresh(client, api_url, &tokens).await?;
+        storage.save_tokens(node_name, &refreshed).await?;
+        self.track(node_name, Some(&refreshed));
+        Ok(Some(refreshed))
+    }
+}
+
+/// Exchange `tokens`' refresh token for a new pair. The old refresh token
+/// is kept when the service does not rotate it.
+pub async fn refresh(
+    client: &reqwest::Client,
+    api_url: &Url,
+    tokens: &JwtToken,
+) -> eyre::Result<JwtToken> {
+    let url = api_url
+        .join(REFRESH_PATH)
+        .wrap_err("Invalid token refresh URL")?;
+    let response = client
+        .post(url.clone())
+        .json(&serde_json::json!({
+            "access_token": tokens.access_token,
+            "refresh_token": tokens.refresh_token,
+        }))
+        .send()
+        .await
+        .wrap_err_with(|| format!("Request to {} failed", url))?;
+    let status = response.status();
+    if !status.is_success() {
+        eyre::bail!("Token refresh failed with status {}", status);
+    }
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .wrap_err("Token refresh response is not JSON")?;
+    let mut refreshed = parse_tokens(&body, None, chrono::Utc::now().timestamp())?;
+    if refreshed.refresh_token.is_none() {
+        refreshed.refresh_token = tokens.refresh_token.clone();
+    }
+    Ok(refreshed)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(expires_at: Option<i64>, refresh: bool) -> JwtToken {
+        JwtToken {
+            access_token: "opaque".to_string(),
+            refresh_token: refresh.then(|| "refresh".to_string()),
+            expires_at,
+        }
+    }
+
+    /// Refresh is due within the margin, and only with a refresh token.
+    #[test]
+    fn test_needs_refresh() {
+        assert!(needs_refresh(&tokens(Some(1_030), true), 1_000, 60));
+        assert!(needs_refresh(&tokens(Some(900), true), 1_000, 60));
+        assert!(!needs_refresh(&tokens(Some(2_000), true), 1_000, 60));
+        assert!(!needs_refresh(&tokens(Some(1_030), false), 1_000, 60));
+        assert!(!needs_refresh(&tokens(None, true), 1_000, 60));
+    }
+
+    /// Tracked far-off expiries skip the storage read until rechecked.
+    #[test]
+    fn test_tracked_expiry_skips_checks() {
+        let lifecycle = TokenLifecycle::new(60);
+        let now = chrono::Utc::now().timestamp();
+        assert!(lifecycle.should_check("node", now));
+        lifecycle.track("node", Some(&tokens(Some(now + 3_600), true)));
+        assert!(!lifecycle.should_check("node", now));
+        lifecycle.track("node", Some(&tokens(Some(now + 30), true)));
+        assert!(lifecycle.should_check("node", now));
+    }
+}
@@ -0,0 +1,308 @@
+//! Background refresh of cached tokens (`Client.start_token_refresh()`).
+//!
+//! The per-call refresh of [`crate::token_lifecycle`] runs just before a
+//! request, so the call that finds its token expiring pays for the round
+//! trip to the auth service, and a service idle for longer than its token
+//! lifetime wakes up logged out. The refresher instead scans the cached
+//! tokens of the client's node (and of any other nodes it is given, e.g.
+//! the nodes of a `ClientPool` sharing the storage) every `interval`
+//! seconds on the client's runtime, and refreshes those expiring within
+//! `window` seconds. It shares the per-node lock of the per-call refresh,
+//! so the two never refresh the same tokens twice.
+//!
+//! `on_refresh(node, expires_at)` and `on_error(node, message)` are called
+//! from a runtime thread as refreshes succeed or fail; their exceptions are
+//! printed rather than raised. The refresher stops with `stop()`, at the
+//! end of its `with` block, when another one is started for the client, or
+//! when the client is closed.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use pyo3::prelude::*;
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::callbacks::Callback;
+use crate::metrics::Metrics;
+use crate::storage::MeroboxFileStorage;
+use crate::token_lifecycle::{token_expiry, TokenLifecycle};
+use crate::utils::json_to_python;
+
+/// Default time between scans (seconds).
+pub const DEFAULT_REFRESH_INTERVAL_SECS: f64 = 30.0;
+
+/// Default time before expiry at which the refresher renews tokens
+/// (seconds); well ahead of the per-call margin, so calls never have to.
+pub const DEFAULT_REFRESH_WINDOW_SECS: f64 = 300.0;
+
+/// Outcome of the last scan of one node.
+#[derive(Debug, Clone, Default)]
+struct NodeStatus {
+    expires_at: Option<i64>,
+    last_refresh: Option<i64>,
+    last_error: Option<String>,
+}
+
+/// Counters shared by the refresh task and its handle.
+#[derive(Debug, Default)]
+pub struct RefreshStats {
+    scans: AtomicU64,
+    refreshes: AtomicU64,
+    failures: AtomicU64,
+    nodes: Mutex<BTreeMap<String, NodeStatus>>,
+}
+
+impl RefreshStats {
+    fn update(&self, node_name: &str, update: impl FnOnce(&mut NodeStatus)) {
+        if let Ok(mut nodes) = self.nodes.lock() {
+            update(nodes.entry(node_name.to_string()).or_default());
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let nodes = self
+            .nodes
+            .lock()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .map(|(name, status)| {
+                        let status = serde_json::json!({
+                            "expiresAt": status.expires_at,
+                            "lastRefresh": status.last_refresh,
+                            "lastError": status.last_error,
+                        });
+                        (name.clone(), status)
+                    })
+                    .collect::<serde_json::Map<_, _>>()
+            })
+            .unwrap_or_default();
+        serde_json::json!({
+            "scans": self.scans.load(Ordering::Relaxed),
+            "refreshes": self.refreshes.load(Ordering::Relaxed),
+            "failures": self.failures.load(Ordering::Relaxed),
+            "nodes": nodes,
+        })
+    }
+}
+
+/// What the refresh task needs of its client.
+pub struct Refresher {
+    pub lifecycle: Arc<TokenLifecycle>,
+    pub storage: MeroboxFileStorage,
+    pub http: reqwest::Client,
+    pub metrics: Arc<Metrics>,
+    /// `(node name, API URL)` of each node whose tokens are scanned.
+    pub nodes: Vec<(String, Url)>,
+    pub interval: Duration,
+    pub window: u64,
+    pub on_refresh: Option<Callback>,
+    pub on_error: Option<Callback>,
+}
+
+impl Refresher {
+    /// Scan the nodes once, refreshing and reporting as needed.
+    async fn scan(&self, stats: &RefreshStats) {
+        stats.scans.fetch_add(1, Ordering::Relaxed);
+        for (node_name, api_url) in &self.nodes {
+            let outcome = self
+                .lifecycle
+                .refresh_within(&self.http, api_url, &self.storage, node_name, self.window)
+                .await;
+            match outcome {
+                Ok(None) => {}
+                Ok(Some(tokens)) => {
+                    let expires_at = token_expiry(&tokens);
+                    stats.refreshes.fetch_add(1, Ordering::Relaxed);
+                    self.metrics.record_token_refresh(true);
+                    stats.update(node_name, |status| {
+                        status.expires_at = expires_at;
+                        status.last_refresh = Some(chrono::Utc::now().timestamp());
+                        status.last_error = None;
+                    });
+                    notify(self.on_refresh.as_ref(), node_name, expires_at);
+                }
+                Err(e) => {
+                    let message = format!("{:#}", e);
+                    stats.failures.fetch_add(1, Ordering::Relaxed);
+                    self.metrics.record_token_refresh(false);
+                    stats.update(node_name, |status| {
+                        status.last_error = Some(message.clone());
+                    });
+                    notify(self.on_error.as_ref(), node_name, message);
+                }
+            }
+        }
+    }
+
+    /// Start scanning on `runtime`, the first scan right away.
+    pub fn spawn(self, runtime: &tokio::runtime::Handle) -> Arc<RefreshTask> {
+        let stats = Arc::new(RefreshStats::default());
+        let interval = self.interval;
+        let window = self.window;
+        let nodes = self.nodes.iter().map(|(name, _)| name.clone()).collect();
+        let handle = {
+            let stats = stats.clone();
+            runtime.spawn(async move {
+                let mut ticker = tokio::time::interval(self.interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    ticker.tick().await;
+                    self.scan(&stats).await;
+                }
+            })
+        };
+        Arc::new(RefreshTask {
+            handle: Mutex::new(Some(handle)),
+            stats,
+            interval,
+            window,
+            nodes,
+        })
+    }
+}
+
+/// Call `callback(node_name, value)`, if it is still alive.
+fn notify(callback: Option<&Callback>, node_name: &str, value: impl IntoPy<PyObject>) {
+    let Some(callback) = callback else {
+        return;
+    };
+    Python::with_gil(|py| {
+        if let Some(callback) = callback.upgrade(py) {
+            if let Err(e) = callback.call1(py, (node_name, value)) {
+                e.print(py);
+            }
+        }
+    });
+}
+
+/// A running refresher, shared by the client and its Python handle.
+pub struct RefreshTask {
+    handle: Mutex<Option<JoinHandle<()>>>,
+    stats: Arc<RefreshStats>,
+    interval: Duration,
+    window: u64,
+    nodes: Vec<String>,
+}
+
+impl RefreshTask {
+    /// Stop scanning; returns whether it was running.
+    pub fn stop(&self) -> bool {
+        match self.handle.lock().ok().and_then(|mut handle| handle.take()) {
+            Some(handle) => {
+                let running = !handle.is_finished();
+                handle.abort();
+                running
+            }
+            None => false,
+        }
+    }
+
+    /// Whether the task still runs: neither stopped nor ended with its
+    /// client's runtime.
+    pub fn is_running(&self) -> bool {
+        self.handle
+            .lock()
+            .map(|handle| handle.as_ref().is_some_and(|handle| !handle.is_finished()))
+            .unwrap_or(false)
+    }
+}
+
+/// Handle of a background token refresher, returned by
+/// `Client.start_token_refresh()`.
+#[pyclass(name = "TokenRefresher", frozen)]
+pub struct PyTokenRefresher {
+    task: Arc<RefreshTask>,
+}
+
+impl PyTokenRefresher {
+    pub fn new(task: Arc<RefreshTask>) -> Self {
+        Self { task }
+    }
+}
+
+#[pymethods]
+impl PyTokenRefresher {
+    /// Stop refreshing; returns whether the refresher was running
+    pub fn stop(&self) -> bool {
+        self.task.stop()
+    }
+
+    /// Whether the refresher is still scanning
+    #[getter]
+    pub fn running(&self) -> bool {
+        self.task.is_running()
+    }
+
+    /// Seconds between scans
+    #[getter]
+    pub fn interval(&self) -> f64 {
+        self.task.interval.as_secs_f64()
+    }
+
+    /// Seconds before expiry at which tokens are refreshed
+    #[getter]
+    pub fn window(&self) -> u64 {
+        self.task.window
+    }
+
+    /// Names of the nodes whose tokens are scanned
+    #[getter]
+    pub fn nodes(&self) -> Vec<String> {
+        self.task.nodes.clone()
+    }
+
+    /// `{scans, refreshes, failures, nodes}`, `nodes` mapping each node
+    /// refreshed or failed so far to `{expiresAt, lastRefresh, lastError}`
+    #[getter]
+    pub fn stats(&self, py: Python<'_>) -> PyObject {
+        json_to_python(py, &self.task.stats.to_json())
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_exc))]
+    fn __exit__(&self, _exc: &Bound<'_, pyo3::types::PyTuple>) -> bool {
+        self.task.stop();
+        false
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TokenRefresher(nodes={:?}, interval={}, window={}, running={})",
+            self.task.nodes,
+            self.task.interval.as_secs_f64(),
+            self.task.window,
+            self.task.is_running()
+        )
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Per-node outcomes and counters show in the stats.
+    #[test]
+    fn test_stats_json() {
+        let stats = RefreshStats::default();
+        stats.refreshes.fetch_add(1, Ordering::Relaxed);
+        stats.update("node1", |status| status.expires_at = Some(1_000));
+        stats.update("node2", |status| status.last_error = Some("denied".into()));
+        let json = stats.to_json();
+        assert_eq!(json["refreshes"], 1);
+        assert_eq!(json["nodes"]["node1"]["expiresAt"], 1_000);
+        assert_eq!(json["nodes"]["node2"]["lastError"], "denied");
+        assert!(json["nodes"]["node2"]["expiresAt"].is_null());
+    }
+}
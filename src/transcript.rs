@@ -0,0 +1,363 @@
+//! Recording of request/response transcripts and their replay
+//! (`record=` / `replay=` on `create_client`).
+//!
+//! A client created with `record="run.jsonl"` appends every exchange it
+//! sends through [`crate::http`] to that file as one JSON object per line:
+//! `{seq, method, url, requestHeaders, requestBody, status, headers, body}`,
+//! where `url` is the path and query (so a transcript replays against any
+//! address of the node) and bodies are `{"text": ...}` or `{"base64": ...}`.
+//! A client created with `replay="run.jsonl"` sends nothing: each request is
+//! answered with the next recorded response for the same method and URL, in
+//! recorded order, and a request the transcript has no answer for fails.
+//! Integration tests then run deterministically against behavior captured
+//! from a live node, and a bug report can carry the exchange that shows it.
+//!
+//! As with request hooks, JSON-RPC executions go through [`crate::http`]
+//! while a transcript is active, so they are covered along with blobs, the
+//! auth service and raw requests. Admin calls made by `calimero-client` and
+//! WebSocket subscriptions are neither recorded nor replayed.
+//!
+//! `Authorization`, `Proxy-Authorization`, `Cookie` and `Set-Cookie` values
+//! are written as `[redacted]`; bodies are written as sent and received, so
+//! a transcript of a login holds its credentials and tokens. The file is
+//! created readable by its owner only.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use eyre::WrapErr;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use url::Url;
+
+use crate::permissions;
+
+tokio::task_local! {
+    static ACTIVE: Option<Arc<Transcript>>;
+}
+
+/// Headers whose values never reach a transcript.
+const REDACTED_HEADERS: [&str; 4] = [
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+];
+
+const REDACTED: &str = "[redacted]";
+
+/// A request or response body as written to a transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Payload {
+    Text(String),
+    Base64(String),
+}
+
+impl Payload {
+    pub fn new(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::Text(text.to_string()),
+            Err(_) => Self::Base64(BASE64.encode(bytes)),
+        }
+    }
+
+    pub fn to_bytes(&self) -> eyre::Result<Vec<u8>> {
+        match self {
+            Self::Text(text) => Ok(text.as_bytes().to_vec()),
+            Self::Base64(encoded) => BASE64
+                .decode(encoded)
+                .wrap_err("Transcript body is not valid base64"),
+        }
+    }
+}
+
+/// One request and the response it got.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Exchange {
+    pub seq: u64,
+    pub method: String,
+    /// Path and query of the request.
+    pub url: String,
+    #[serde(default)]
+    pub request_headers: BTreeMap<String, String>,
+    /// `None` for streamed request bodies.
+    #[serde(default)]
+    pub request_body: Option<Payload>,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: Payload,
+}
+
+/// Path and query of `url`, the part a transcript matches on.
+pub fn request_path(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+/// `headers` as `{name: value}`, sensitive values redacted; repeated
+/// headers are joined with `, `.
+pub fn redacted_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    let mut out = BTreeMap::new();
+    for name in headers.keys() {
+        let value = match REDACTED_HEADERS.contains(&name.as_str()) {
+            true => REDACTED.to_string(),
+            false => headers
+                .get_all(name)
+                .iter()
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        out.insert(name.as_str().to_string(), value);
+    }
+    out
+}
+
+enum Mode {
+    Record(File),
+    Replay(HashMap<(String, String), VecDeque<Exchange>>),
+}
+
+struct State {
+    mode: Mode,
+    exchanges: u64,
+}
+
+/// The transcript a client records to or replays from.
+pub struct Transcript {
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+impl Transcript {
+    /// Start a new transcript at `path`, replacing an existing file.
+    pub fn record(path: &Path) -> eyre::Result<Self> {
+        let file = File::create(path)
+            .wrap_err_with(|| format!("Failed to create transcript {:?}", path))?;
+        permissions::restrict_to_owner(path, false)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(State {
+                mode: Mode::Record(file),
+                exchanges: 0,
+            }),
+        })
+    }
+
+    /// Load the transcript at `path` for replay.
+    pub fn replay(path: &Path) -> eyre::Result<Self> {
+        let file =
+            File::open(path).wrap_err_with(|| format!("Failed to open transcript {:?}", path))?;
+        let mut queues: HashMap<_, VecDeque<Exchange>> = HashMap::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.wrap_err_with(|| format!("Failed to read transcript {:?}", path))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let exchange: Exchange = serde_json::from_str(&line).wrap_err_with(|| {
+                format!("Invalid exchange on line {} of {:?}", number + 1, path)
+            })?;
+            queues
+                .entry((exchange.method.clone(), exchange.url.clone()))
+                .or_default()
+                .push_back(exchange);
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(State {
+                mode: Mode::Replay(queues),
+                exchanges: 0,
+            }),
+        })
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.state
+            .lock()
+            .is_ok_and(|state| matches!(state.mode, Mode::Replay(_)))
+    }
+
+    /// Append `exchange` (its `seq` assigned here) to a recording.
+    pub fn append(&self, mut exchange: Exchange) -> eyre::Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| eyre::eyre!("Transcript lock is poisoned"))?;
+        let seq = state.exchanges + 1;
+        let Mode::Record(file) = &mut state.mode else {
+            eyre::bail!("Transcript {:?} is being replayed", self.path);
+        };
+        exchange.seq = seq;
+        let mut line = serde_json::to_vec(&exchange)?;
+        line.push(b'\n');
+        file.write_all(&line)
+            .and_then(|()| file.flush())
+            .wrap_err_with(|| format!("Failed to write transcript {:?}", self.path))?;
+        state.exchanges = seq;
+        Ok(())
+    }
+
+    /// Next recorded response to `method` on `url`.
+    pub fn next(&self, method: &str, url: &Url) -> eyre::Result<Exchange> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| eyre::eyre!("Transcript lock is poisoned"))?;
+        let Mode::Replay(queues) = &mut state.mode else {
+            eyre::bail!("Transcript {:?} is being recorded", self.path);
+        };
+        let path = request_path(url);
+        let exchange = queues
+            .get_mut(&(method.to_string(), path.clone()))
+            .and_then(VecDeque::pop_front)
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "Transcript {:?} has no (more) responses to {} {}",
+                    self.path,
+                    method,
+                    path
+                )
+            })?;
+        state.exchanges += 1;
+        Ok(exchange)
+    }
+
+    /// `exchange`'s response as if it came from the node.
+    pub fn response(exchange: &Exchange) -> eyre::Result<reqwest::Response> {
+        let mut builder = ::http::Response::builder().status(exchange.status);
+        for (name, value) in &exchange.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                builder = builder.header(name, value);
+            }
+        }
+        let response = builder
+            .body(exchange.body.to_bytes()?)
+            .wrap_err("Invalid response in transcript")?;
+        Ok(reqwest::Response::from(response))
+    }
+
+    /// `{path, mode, exchanges, remaining}`; `remaining` counts responses
+    /// not yet replayed (`None` when recording).
+    pub fn stats(&self) -> serde_json::Value {
+        let Ok(state) = self.state.lock() else {
+            return serde_json::Value::Null;
+        };
+        let (mode, remaining) = match &state.mode {
+            Mode::Record(_) => ("record", None),
+            Mode::Replay(queues) => ("replay", Some(queues.values().map(VecDeque::len).sum())),
+        };
+        json!({
+            "path": self.path.to_string_lossy(),
+            "mode": mode,
+            "exchanges": state.exchanges,
+            "remaining": remaining,
+        })
+    }
+}
+
+/// Run `fut` with `transcript` as the one [`crate::http`] records to or
+/// replays from.
+pub async fn scope<F: std::future::Future>(
+    transcript: Option<Arc<Transcript>>,
+    fut: F,
+) -> F::Output {
+    ACTIVE.scope(transcript, fut).await
+}
+
+/// Transcript of the call being made, when its client has one.
+pub fn active() -> Option<Arc<Transcript>> {
+    ACTIVE.try_with(Clone::clone).ok().flatten()
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(url: &str, status: u16, body: &[u8]) -> Exchange {
+        Exchange {
+            seq: 0,
+            method: "POST".to_string(),
+            url: url.to_string(),
+            request_headers: BTreeMap::new(),
+            request_body: Some(Payload::new(b"{}")),
+            status,
+            headers: BTreeMap::from([("content-type".to_string(), "text/plain".to_string())]),
+            body: Payload::new(body),
+        }
+    }
+
+    /// A recording replays in order per method and URL, then runs dry.
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = std::env::temp_dir().join(format!("transcript-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.jsonl");
+
+        let recording = Transcript::record(&path).unwrap();
+        recording
+            .append(exchange("/jsonrpc", 200, b"first"))
+            .unwrap();
+        recording
+            .append(exchange("/other", 500, &[0xff, 0x00]))
+            .unwrap();
+        recording
+            .append(exchange("/jsonrpc", 429, b"second"))
+            .unwrap();
+        assert_eq!(recording.stats()["exchanges"], 3);
+        drop(recording);
+
+        let replay = Transcript::replay(&path).unwrap();
+        assert!(replay.is_replay());
+        assert_eq!(replay.stats()["remaining"], 3);
+        let url = Url::parse("http://elsewhere:1/jsonrpc").unwrap();
+        let first = replay.next("POST", &url).unwrap();
+        assert_eq!(first.seq, 1);
+        let response = Transcript::response(&first).unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], "text/plain");
+        assert_eq!(response.bytes().await.unwrap().as_ref(), b"first");
+
+        let second = replay.next("POST", &url).unwrap();
+        assert_eq!((second.seq, second.status), (3, 429));
+        assert!(replay.next("POST", &url).is_err());
+        assert!(replay.next("GET", &url.join("/other").unwrap()).is_err());
+
+        let binary = replay.next("POST", &url.join("/other").unwrap()).unwrap();
+        assert_eq!(binary.body, Payload::Base64("/wA=".to_string()));
+        assert_eq!(binary.body.to_bytes().unwrap(), vec![0xff, 0x00]);
+        assert_eq!(replay.stats()["remaining"], 0);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    /// Credentials in headers are redacted; the rest is kept.
+    #[test]
+    fn test_sensitive_headers_are_redacted() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer secret".parse().unwrap());
+        headers.append("set-cookie", "a=1".parse().unwrap());
+        headers.insert("x-request-id", "abc".parse().unwrap());
+        let redacted = redacted_headers(&headers);
+        assert_eq!(redacted["authorization"], REDACTED);
+        assert_eq!(redacted["set-cookie"], REDACTED);
+        assert_eq!(redacted["x-request-id"], "abc");
+    }
+}
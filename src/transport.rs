@@ -0,0 +1,71 @@
+//! Transport selection for node connections.
+//!
+//! The transport is negotiated from the API URL scheme: `http://` and
+//! `https://` use JSON over HTTP (the only API nodes currently serve), while
+//! `grpc://` and `grpcs://` are reserved for a gRPC API. Nodes do not expose
+//! gRPC yet, so those schemes are recognized and rejected with a clear
+//! message instead of failing later with an opaque connection error.
+
+use url::Url;
+
+/// Wire transport used to talk to a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// JSON-RPC and admin API over HTTP/1.1 or HTTP/2.
+    Http,
+    /// gRPC over HTTP/2 (`grpc://`, `grpcs://`).
+    Grpc,
+}
+
+impl Transport {
+    /// Transport implied by `url`'s scheme.
+    pub fn from_url(url: &Url) -> Result<Self, String> {
+        match url.scheme() {
+            "http" | "https" => Ok(Self::Http),
+            "grpc" | "grpcs" => Ok(Self::Grpc),
+            other => Err(format!(
+                "Unsupported URL scheme '{}' (expected http, https, grpc or grpcs)",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Http => "http",
+            Self::Grpc => "grpc",
+        }
+    }
+
+    /// Whether this build can open connections over the transport.
+    pub fn is_available(&self) -> bool {
+        matches!(self, Self::Http)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Schemes map onto transports; unknown schemes are rejected.
+    #[test]
+    fn test_transport_from_url() {
+        let parse = |s: &str| Transport::from_url(&Url::parse(s).unwrap());
+        assert_eq!(parse("http://localhost:2528"), Ok(Transport::Http));
+        assert_eq!(parse("https://node.example"), Ok(Transport::Http));
+        assert_eq!(parse("grpc://localhost:2529"), Ok(Transport::Grpc));
+        assert_eq!(parse("grpcs://node.example"), Ok(Transport::Grpc));
+        assert!(parse("ftp://node.example").is_err());
+    }
+
+    /// Only HTTP is usable until nodes ship a gRPC API.
+    #[test]
+    fn test_transport_availability() {
+        assert!(Transport::Http.is_available());
+        assert!(!Transport::Grpc.is_available());
+    }
+}
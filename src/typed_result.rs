@@ -0,0 +1,351 @@
+//! Conversion of execute results into caller-provided Python types, behind
+//! `execute_function(..., result_type=...)` and `convert_result()`.
+//!
+//! Application methods return arbitrary JSON, which callers otherwise pick
+//! apart by key and only notice a changed schema when a lookup fails deep in
+//! their code. Given a `result_type`, the method's `output` is checked and
+//! converted in Rust against it:
+//!
+//! - `int`, `float`, `str`, `bool`, `None`, `dict`, `list` and `typing.Any`
+//!   check the JSON kind (`float` accepts integers);
+//! - `list[T]`, `dict[str, T]`, `Optional[T]` and unions (first match wins)
+//!   check their items;
+//! - dataclasses get their fields from the JSON object, by name or its
+//!   camelCase spelling, converted by their annotations; missing required
+//!   fields and unknown keys are mismatches;
+//! - classes with `model_validate` (pydantic models) validate themselves;
+//! - other classes are called with the object's keys as keyword arguments,
+//!   or with the value itself.
+//!
+//! A mismatch raises [`ResultTypeError`] naming where in the output it is,
+//! e.g. `output.items[2].price: expected float, got string`. Admin calls
+//! have typed wrappers of their own (`list_contexts(typed=True)`).
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyType};
+use serde_json::Value;
+
+use crate::utils::{json_to_python, python_to_json};
+
+create_exception!(
+    calimero_client_py,
+    ResultTypeError,
+    PyValueError,
+    "An execute result did not match the result_type it was converted to."
+);
+
+/// JSON kind of `value`, for mismatch messages.
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(number) if number.is_f64() => "float",
+        Value::Number(_) => "int",
+        Value::String(_) => "string",
+        Value::Array(_) => "list",
+        Value::Object(_) => "object",
+    }
+}
+
+fn mismatch(path: &str, expected: &str, value: &Value) -> PyErr {
+    ResultTypeError::new_err(format!(
+        "{}: expected {}, got {}",
+        path,
+        expected,
+        kind(value)
+    ))
+}
+
+/// `snake_case` as `camelCase`.
+fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        match c {
+            '_' if !camel.is_empty() => upper = true,
+            c if upper => {
+                camel.extend(c.to_uppercase());
+                upper = false;
+            }
+            c => camel.push(c),
+        }
+    }
+    camel
+}
+
+/// Method output of an execute response (`result.output`), or the value
+/// itself when it is not a JSON-RPC response.
+pub fn output(response: &Value) -> &Value {
+    response
+        .get("result")
+        .and_then(|result| result.get("output"))
+        .unwrap_or(response)
+}
+
+/// `value` converted to `ty`; `path` names it in mismatch errors.
+pub fn convert(
+    py: Python<'_>,
+    value: &Value,
+    ty: &Bound<'_, PyAny>,
+    path: &str,
+) -> PyResult<PyObject> {
+    let typing = py.import_bound("typing")?;
+    if ty.is(&typing.getattr("Any")?) {
+        return Ok(json_to_python(py, value));
+    }
+    if ty.is_none() || ty.is(&py.None().bind(py).get_type()) {
+        return match value {
+            Value::Null => Ok(py.None()),
+            value => Err(mismatch(path, "None", value)),
+        };
+    }
+
+    let origin = typing.call_method1("get_origin", (ty,))?;
+    if !origin.is_none() {
+        let args = typing.call_method1("get_args", (ty,))?;
+        return convert_generic(py, value, ty, &origin, &args, path);
+    }
+
+    let builtins = py.import_bound("builtins")?;
+    let is = |name: &str| -> PyResult<bool> { Ok(ty.is(&builtins.getattr(name)?)) };
+    if is("bool")? {
+        return match value {
+            Value::Bool(_) => Ok(json_to_python(py, value)),
+            value => Err(mismatch(path, "bool", value)),
+        };
+    }
+    if is("int")? {
+        return match value {
+            Value::Number(number) if !number.is_f64() => Ok(json_to_python(py, value)),
+            value => Err(mismatch(path, "int", value)),
+        };
+    }
+    if is("float")? {
+        return match value.as_f64() {
+            Some(number) => Ok(number.into_py(py)),
+            None => Err(mismatch(path, "float", value)),
+        };
+    }
+    if is("str")? {
+        return match value {
+            Value::String(_) => Ok(json_to_python(py, value)),
+            value => Err(mismatch(path, "str", value)),
+        };
+    }
+    if is("dict")? {
+        return match value {
+            Value::Object(_) => Ok(json_to_python(py, value)),
+            value => Err(mismatch(path, "dict", value)),
+        };
+    }
+    if is("list")? {
+        return match value {
+            Value::Array(_) => Ok(json_to_python(py, value)),
+            value => Err(mismatch(path, "list", value)),
+        };
+    }
+
+    let dataclasses = py.import_bound("dataclasses")?;
+    if ty.is_instance_of::<PyType>()
+        && dataclasses
+            .call_method1("is_dataclass", (ty,))?
+            .is_truthy()?
+    {
+        return convert_dataclass(py, value, ty, &dataclasses, path);
+    }
+    if ty.hasattr("model_validate")? {
+        return Ok(ty
+            .call_method1("model_validate", (json_to_python(py, value),))?
+            .unbind());
+    }
+    if !ty.is_callable() {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "result_type must be a type, not {}",
+            ty.repr()?
+        )));
+    }
+    let converted = match value {
+        Value::Object(fields) => {
+            let kwargs = PyDict::new_bound(py);
+            for (key, field) in fields {
+                kwargs.set_item(key, json_to_python(py, field))?;
+            }
+            ty.call((), Some(&kwargs))
+        }
+        value => ty.call1((json_to_python(py, value),)),
+    };
+    converted.map(Bound::unbind).map_err(|e| {
+        ResultTypeError::new_err(format!("{}: {} rejected the value: {}", path, ty, e))
+    })
+}
+
+/// `list[T]`, `dict[K, V]` and unions, by their `typing` origin.
+fn convert_generic<'py>(
+    py: Python<'py>,
+    value: &Value,
+    ty: &Bound<'py, PyAny>,
+    origin: &Bound<'py, PyAny>,
+    args: &Bound<'py, PyAny>,
+    path: &str,
+) -> PyResult<PyObject> {
+    let builtins = py.import_bound("builtins")?;
+    let typing = py.import_bound("typing")?;
+    let arg = |index: usize| -> PyResult<Bound<'py, PyAny>> {
+        match args.len()? > index {
+            true => args.get_item(index),
+            false => typing.getattr("Any"),
+        }
+    };
+    let is_union = origin.is(&typing.getattr("Union")?)
+        || py
+            .import_bound("types")?
+            .getattr("UnionType")
+            .is_ok_and(|union| origin.is(&union));
+    if is_union {
+        let mut first_error = None;
+        for candidate in args.iter()? {
+            match convert(py, value, &candidate?, path) {
+                Ok(converted) => return Ok(converted),
+                Err(e) if e.is_instance_of::<ResultTypeError>(py) => {
+                    first_error.get_or_insert(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        return Err(first_error.unwrap_or_else(|| mismatch(path, &ty.to_string(), value)));
+    }
+    if origin.is(&builtins.getattr("list")?) {
+        let Value::Array(items) = value else {
+            return Err(mismatch(path, "list", value));
+        };
+        let item_type = arg(0)?;
+        let converted = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| convert(py, item, &item_type, &format!("{}[{}]", path, index)))
+            .collect::<PyResult<Vec<_>>>()?;
+        return Ok(PyList::new_bound(py, converted).into_any().unbind());
+    }
+    if origin.is(&builtins.getattr("dict")?) {
+        let Value::Object(fields) = value else {
+            return Err(mismatch(path, "dict", value));
+        };
+        let value_type = arg(1)?;
+        let converted = PyDict::new_bound(py);
+        for (key, field) in fields {
+            converted.set_item(
+                key,
+                convert(py, field, &value_type, &format!("{}.{}", path, key))?,
+            )?;
+        }
+        return Ok(converted.into_any().unbind());
+    }
+    // Other generics (tuples, literals, ...) are taken as they come.
+    Ok(json_to_python(py, value))
+}
+
+/// A dataclass from a JSON object, field by field.
+fn convert_dataclass(
+    py: Python<'_>,
+    value: &Value,
+    ty: &Bound<'_, PyAny>,
+    dataclasses: &Bound<'_, PyModule>,
+    path: &str,
+) -> PyResult<PyObject> {
+    let name: String = ty.getattr("__name__")?.extract()?;
+    let Value::Object(object) = value else {
+        return Err(mismatch(path, &name, value));
+    };
+    let hints = py
+        .import_bound("typing")?
+        .call_method1("get_type_hints", (ty,))?;
+    let missing = dataclasses.getattr("MISSING")?;
+    let kwargs = PyDict::new_bound(py);
+    let mut used = Vec::new();
+    for field in dataclasses.call_method1("fields", (ty,))?.iter()? {
+        let field = field?;
+        if !field.getattr("init")?.is_truthy()? {
+            continue;
+        }
+        let field_name: String = field.getattr("name")?.extract()?;
+        let key = [field_name.clone(), camel_case(&field_name)]
+            .into_iter()
+            .find(|key| object.contains_key(key));
+        let Some(key) = key else {
+            let has_default = !field.getattr("default")?.is(&missing)
+                || !field.getattr("default_factory")?.is(&missing);
+            if has_default {
+                continue;
+            }
+            return Err(ResultTypeError::new_err(format!(
+                "{}: missing field '{}' of {}",
+                path, field_name, name
+            )));
+        };
+        let field_type = hints.get_item(&field_name)?;
+        let converted = convert(
+            py,
+            &object[&key],
+            &field_type,
+            &format!("{}.{}", path, field_name),
+        )?;
+        kwargs.set_item(&field_name, converted)?;
+        used.push(key);
+    }
+    if let Some(unknown) = object.keys().find(|key| !used.contains(key)) {
+        return Err(ResultTypeError::new_err(format!(
+            "{}: unknown field '{}' for {}",
+            path, unknown, name
+        )));
+    }
+    Ok(ty.call((), Some(&kwargs))?.unbind())
+}
+
+/// Convert a JSON-compatible `value` (for instance the result of
+/// `execute_async`) to `result_type` as `execute_function(...,
+/// result_type=...)` does. An execute response is converted from its
+/// `result.output`.
+#[pyfunction]
+pub fn convert_result(
+    py: Python<'_>,
+    value: &Bound<'_, PyAny>,
+    result_type: &Bound<'_, PyAny>,
+) -> PyResult<PyObject> {
+    let value = python_to_json(value)?;
+    convert(py, output(&value), result_type, "output")
+}
+
+pub fn register(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("ResultTypeError", py.get_type_bound::<ResultTypeError>())?;
+    Ok(())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Field names are looked up in their camelCase spelling too.
+    #[test]
+    fn test_camel_case() {
+        assert_eq!(camel_case("root_hash"), "rootHash");
+        assert_eq!(camel_case("id"), "id");
+        assert_eq!(camel_case("_private"), "_private");
+    }
+
+    /// The output of an execute response is found under `result.output`.
+    #[test]
+    fn test_output() {
+        let response = json!({"jsonrpc": "2.0", "result": {"output": [1, 2]}});
+        assert_eq!(output(&response), &json!([1, 2]));
+        assert_eq!(output(&json!({"a": 1})), &json!({"a": 1}));
+        assert_eq!(kind(&json!(1.5)), "float");
+        assert_eq!(kind(&json!(1)), "int");
+    }
+}
@@ -0,0 +1,270 @@
+//! Per-context usage reports behind `Client.usage()`.
+//!
+//! The node keeps no per-context call statistics, so the client records the
+//! executions it sends itself (`execute_function`, `execute_async` and the
+//! calls of `execute_batch`): context, method, the executor passed by the
+//! caller and whether the call succeeded. Together with the events received
+//! over `subscribe` this gives, for a time window, execution counts, the
+//! busiest methods, active identities and event volume.
+//!
+//! Both sources are bounded ([`USAGE_LOG_LIMIT`] executions per client,
+//! [`crate::subscriptions::EVENT_HISTORY_LIMIT`] events per context) and
+//! only cover this client, so reports say from when their data starts.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+/// Executions kept per client for usage reports.
+pub const USAGE_LOG_LIMIT: usize = 10_000;
+
+/// Window of `usage()` reports when none is given.
+pub const DEFAULT_USAGE_WINDOW_SECS: f64 = 3600.0;
+
+/// Methods listed under `topMethods`.
+pub const TOP_METHODS: usize = 10;
+
+/// One execution sent by this client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Execution {
+    pub context_id: String,
+    pub method: String,
+    /// Executor public key the caller passed, if any.
+    pub identity: Option<String>,
+    /// Unix time in milliseconds.
+    pub at: i64,
+    pub ok: bool,
+}
+
+/// Bounded log of the executions a client sent, oldest first.
+#[derive(Debug, Default)]
+pub struct UsageLog {
+    entries: Mutex<VecDeque<Execution>>,
+}
+
+impl UsageLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, execution: Execution) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        if entries.len() == USAGE_LOG_LIMIT {
+            entries.pop_front();
+        }
+        entries.push_back(execution);
+    }
+
+    /// Executions on `context_id`, and when the log's data starts (the
+    /// oldest entry kept, across all contexts).
+    pub fn for_context(&self, context_id: &str) -> (Vec<Execution>, Option<i64>) {
+        let Ok(entries) = self.entries.lock() else {
+            return (Vec::new(), None);
+        };
+        let executions = entries
+            .iter()
+            .filter(|execution| execution.context_id == context_id)
+            .cloned()
+            .collect();
+        (executions, entries.front().map(|execution| execution.at))
+    }
+}
+
+/// The event itself, inside the `result` of a subscription notification.
+fn payload(event: &Value) -> &Value {
+    event.get("result").unwrap_or(event)
+}
+
+/// String field named like an identity at the top of `event` or in its
+/// `data`.
+fn identity_of(event: &Value) -> Option<&str> {
+    const NAMES: [&str; 5] = [
+        "executorPublicKey",
+        "executor_public_key",
+        "executor",
+        "author",
+        "publicKey",
+    ];
+    let event = payload(event);
+    [Some(event), event.get("data")]
+        .into_iter()
+        .flatten()
+        .flat_map(|scope| NAMES.iter().map(move |name| scope.get(*name)))
+        .find_map(|value| value.and_then(Value::as_str))
+}
+
+fn event_type(event: &Value) -> &str {
+    let event = payload(event);
+    event
+        .get("type")
+        .or_else(|| event.get("kind"))
+        .and_then(Value::as_str)
+        .unwrap_or("unknown")
+}
+
+fn rfc3339(ms: i64) -> Value {
+    chrono::DateTime::from_timestamp_millis(ms)
+        .map(|at| Value::from(at.to_rfc3339()))
+        .unwrap_or(Value::Null)
+}
+
+/// Usage report of `context_id` over `[from, to]` (Unix milliseconds) from
+/// the client's `executions` and the `events` (with their receipt times)
+/// it received for the context. `executions_since` and `events_since` are
+/// when each source's data starts.
+pub fn report(
+    context_id: &str,
+    from: i64,
+    to: i64,
+    executions: &[Execution],
+    executions_since: Option<i64>,
+    events: &[(i64, Value)],
+    events_since: Option<i64>,
+) -> Value {
+    let in_window = |at: i64| (from..=to).contains(&at);
+
+    let mut total = 0;
+    let mut failed = 0;
+    let mut methods: HashMap<&str, (u64, u64)> = HashMap::new();
+    let mut identities: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+    for execution in executions.iter().filter(|e| in_window(e.at)) {
+        total += 1;
+        let counts = methods.entry(&execution.method).or_default();
+        counts.0 += 1;
+        if !execution.ok {
+            failed += 1;
+            counts.1 += 1;
+        }
+        if let Some(identity) = execution.identity.as_deref() {
+            identities.entry(identity).or_default().0 += 1;
+        }
+    }
+
+    let mut event_total = 0;
+    let mut by_type: BTreeMap<&str, u64> = BTreeMap::new();
+    for (_, event) in events.iter().filter(|(at, _)| in_window(*at)) {
+        event_total += 1;
+        *by_type.entry(event_type(event)).or_default() += 1;
+        if let Some(identity) = identity_of(event) {
+            identities.entry(identity).or_default().1 += 1;
+        }
+    }
+
+    let mut top: Vec<_> = methods.into_iter().collect();
+    top.sort_by(|(a, (a_count, _)), (b, (b_count, _))| b_count.cmp(a_count).then(a.cmp(b)));
+    let top_methods: Vec<Value> = top
+        .into_iter()
+        .take(TOP_METHODS)
+        .map(
+            |(method, (count, failed))| json!({"method": method, "count": count, "failed": failed}),
+        )
+        .collect();
+    let active_identities: Vec<Value> = identities
+        .into_iter()
+        .map(|(identity, (executions, events))| {
+            json!({"identity": identity, "executions": executions, "events": events})
+        })
+        .collect();
+    let minutes = ((to - from) as f64 / 60_000.0).max(f64::MIN_POSITIVE);
+
+    json!({
+        "contextId": context_id,
+        "from": rfc3339(from),
+        "to": rfc3339(to),
+        "windowSecs": (to - from) as f64 / 1000.0,
+        "executions": {
+            "total": total,
+            "failed": failed,
+            "perMinute": total as f64 / minutes,
+        },
+        "topMethods": top_methods,
+        "activeIdentities": active_identities,
+        "events": {
+            "total": event_total,
+            "byType": by_type,
+            "perMinute": event_total as f64 / minutes,
+        },
+        "coverage": {
+            "executionsSince": executions_since.map(rfc3339),
+            "eventsSince": events_since.map(rfc3339),
+        },
+    })
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution(method: &str, identity: Option<&str>, at: i64, ok: bool) -> Execution {
+        Execution {
+            context_id: "ctx".to_string(),
+            method: method.to_string(),
+            identity: identity.map(str::to_string),
+            at,
+            ok,
+        }
+    }
+
+    /// Only entries inside the window count; methods rank by calls.
+    #[test]
+    fn test_report_aggregates_window() {
+        let executions = vec![
+            execution("set", Some("alice"), 1_000, true),
+            execution("set", Some("bob"), 2_000, false),
+            execution("get", Some("alice"), 3_000, true),
+            execution("set", Some("alice"), 500_000, true),
+        ];
+        let events = vec![
+            (1_500, json!({"type": "StateMutation"})),
+            (
+                2_500,
+                json!({"result": {"type": "ExecutionEvent", "data": {"author": "carol"}}}),
+            ),
+            (900_000, json!({"type": "StateMutation"})),
+        ];
+        let report = report("ctx", 0, 60_000, &executions, Some(1_000), &events, None);
+
+        assert_eq!(report["executions"]["total"], 3);
+        assert_eq!(report["executions"]["failed"], 1);
+        assert_eq!(report["executions"]["perMinute"], 3.0);
+        assert_eq!(
+            report["topMethods"][0],
+            json!({"method": "set", "count": 2, "failed": 1})
+        );
+        assert_eq!(report["topMethods"][1]["method"], "get");
+        let identities: Vec<_> = report["activeIdentities"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["identity"].as_str().unwrap())
+            .collect();
+        assert_eq!(identities, ["alice", "bob", "carol"]);
+        assert_eq!(report["events"]["total"], 2);
+        assert_eq!(report["events"]["byType"]["ExecutionEvent"], 1);
+        assert!(report["coverage"]["eventsSince"].is_null());
+    }
+
+    /// The log keeps the newest entries and filters by context.
+    #[test]
+    fn test_usage_log_is_bounded() {
+        let log = UsageLog::new();
+        for at in 0..USAGE_LOG_LIMIT as i64 + 3 {
+            log.record(execution("set", None, at, true));
+        }
+        log.record(Execution {
+            context_id: "other".to_string(),
+            ..execution("get", None, 0, true)
+        });
+        let (executions, since) = log.for_context("ctx");
+        assert_eq!(executions.len(), USAGE_LOG_LIMIT - 1);
+        assert_eq!(since, Some(4));
+        assert_eq!(log.for_context("other").0.len(), 1);
+    }
+}
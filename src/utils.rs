@@ -1,7 +1,50 @@
 //! Utility functions for JSON to Python conversion
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList};
+use pyo3::sync::GILProtected;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString, PyTuple};
+
+/// Entries kept by the string interner before it starts over.
+const INTERN_CAPACITY: usize = 65_536;
+
+/// Shared Python strings for IDs and object keys.
+///
+/// Event-heavy workloads see the same context/identity IDs and field names
+/// millions of times; handing out one immutable `str` object per distinct
+/// value avoids allocating a fresh copy for every occurrence. Bounded so a
+/// stream of unique IDs cannot grow it without limit.
+static INTERNED: GILProtected<RefCell<Option<HashMap<Box<str>, Py<PyString>>>>> =
+    GILProtected::new(RefCell::new(None));
+
+/// Whether `s` is worth interning: object keys and ID-shaped values
+/// (base58/hex, 32–64 alphanumeric characters).
+fn should_intern(s: &str, is_key: bool) -> bool {
+    if is_key {
+        return s.len() <= 64;
+    }
+    (32..=64).contains(&s.len()) && s.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+/// Python string for `s`, reusing a cached object when `s` is internable.
+fn py_string(py: Python<'_>, s: &str, is_key: bool) -> PyObject {
+    if !should_intern(s, is_key) {
+        return s.into_py(py);
+    }
+    let mut cache = INTERNED.get(py).borrow_mut();
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some(cached) = cache.get(s) {
+        return cached.clone_ref(py).into_any();
+    }
+    if cache.len() >= INTERN_CAPACITY {
+        cache.clear();
+    }
+    let value = PyString::new_bound(py, s).unbind();
+    cache.insert(s.into(), value.clone_ref(py));
+    value.into_any()
+}
 
 /// Convert serde_json::Value to Python object
 pub fn json_to_python(py: Python, value: &serde_json::Value) -> PyObject {
@@ -11,13 +54,15 @@ pub fn json_to_python(py: Python, value: &serde_json::Value) -> PyObject {
         serde_json::Value::Number(n) => {
             if let Some(i) = n.as_i64() {
                 i.into_py(py)
+            } else if let Some(u) = n.as_u64() {
+                u.into_py(py)
             } else if let Some(f) = n.as_f64() {
                 f.into_py(py)
             } else {
                 n.to_string().into_py(py)
             }
         }
-        serde_json::Value::String(s) => s.into_py(py),
+        serde_json::Value::String(s) => py_string(py, s, false),
         serde_json::Value::Array(arr) => {
             let list = PyList::new_bound(py, Vec::<PyObject>::new());
             for item in arr {
@@ -28,9 +73,177 @@ pub fn json_to_python(py: Python, value: &serde_json::Value) -> PyObject {
         serde_json::Value::Object(obj) => {
             let dict = PyDict::new_bound(py);
             for (k, v) in obj {
-                dict.set_item(k, json_to_python(py, v)).unwrap();
+                dict.set_item(py_string(py, k, true), json_to_python(py, v))
+                    .unwrap();
             }
             dict.into_py(py)
         }
     }
 }
+
+/// Convert a Python object (dict/list/str/int/float/bool/None) to serde_json::Value
+pub fn python_to_json(obj: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    if obj.is_none() {
+        return Ok(serde_json::Value::Null);
+    }
+    // bool must be checked before int: Python bools are ints.
+    if let Ok(b) = obj.downcast::<PyBool>() {
+        return Ok(serde_json::Value::Bool(b.is_true()));
+    }
+    if obj.is_instance_of::<PyLong>() {
+        if let Ok(i) = obj.extract::<i64>() {
+            return Ok(i.into());
+        }
+        if let Ok(u) = obj.extract::<u64>() {
+            return Ok(u.into());
+        }
+        return Err(PyErr::new::<pyo3::exceptions::PyOverflowError, _>(
+            "Integer is too large to encode as JSON",
+        ));
+    }
+    if let Ok(f) = obj.downcast::<PyFloat>() {
+        return serde_json::Number::from_f64(f.value())
+            .map(serde_json::Value::Number)
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "NaN and infinity cannot be encoded as JSON",
+                )
+            });
+    }
+    if let Ok(s) = obj.downcast::<PyString>() {
+        return Ok(serde_json::Value::String(s.to_str()?.to_owned()));
+    }
+    if let Ok(dict) = obj.downcast::<PyDict>() {
+        let mut map = serde_json::Map::with_capacity(dict.len());
+        for (k, v) in dict.iter() {
+            let key = k.downcast::<PyString>().map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyTypeError, _>("JSON object keys must be strings")
+            })?;
+            map.insert(key.to_str()?.to_owned(), python_to_json(&v)?);
+        }
+        return Ok(serde_json::Value::Object(map));
+    }
+    if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() {
+        let items = obj
+            .iter()?
+            .map(|item| python_to_json(&item?))
+            .collect::<PyResult<Vec<_>>>()?;
+        return Ok(serde_json::Value::Array(items));
+    }
+    Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+        "Object of type '{}' is not JSON serializable",
+        obj.get_type().name()?
+    )))
+}
+
+/// Keep only the requested `fields` on every record of a list response.
+///
+/// Admin list endpoints wrap their rows in envelopes of varying depth
+/// (`{data: [...]}`, `{data: {contexts: [...]}}`), so this walks objects
+/// until it reaches an array and then strips every record in that array down
+/// to the requested top-level keys. Non-object array items are left as-is.
+/// Applied in Rust before the response crosses into Python so wide records
+/// are never materialized as Python dicts.
+pub fn project_fields(value: &mut serde_json::Value, fields: &[String]) {
+    match value {
+        serde_json::Value::Array(items) => {
+            for item in items {
+                if let serde_json::Value::Object(record) = item {
+                    record.retain(|key, _| fields.iter().any(|f| f == key));
+                }
+            }
+        }
+        serde_json::Value::Object(obj) => {
+            for (_, nested) in obj.iter_mut() {
+                project_fields(nested, fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// First of `names` present on `event` as a string, for payloads that spell
+/// a field more than one way (`contextId` / `context_id`).
+pub fn str_field<'a>(event: &'a serde_json::Value, names: &[&str]) -> Option<&'a str> {
+    names
+        .iter()
+        .find_map(|name| event.get(*name).and_then(|v| v.as_str()))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Records nested under `data.<list>` are projected.
+    #[test]
+    fn test_project_fields_nested_envelope() {
+        let mut value = json!({
+            "data": {
+                "contexts": [
+                    {"id": "a", "applicationId": "x", "rootHash": "h1"},
+                    {"id": "b", "applicationId": "y", "rootHash": "h2"},
+                ]
+            }
+        });
+
+        project_fields(&mut value, &fields(&["id"]));
+
+        assert_eq!(
+            value,
+            json!({"data": {"contexts": [{"id": "a"}, {"id": "b"}]}})
+        );
+    }
+
+    /// Records directly under `data` are projected.
+    #[test]
+    fn test_project_fields_flat_envelope() {
+        let mut value = json!({"data": [{"version": "1", "blobId": "b", "size": 3}]});
+
+        project_fields(&mut value, &fields(&["version", "size"]));
+
+        assert_eq!(value, json!({"data": [{"version": "1", "size": 3}]}));
+    }
+
+    /// Unknown field names simply yield empty records.
+    #[test]
+    fn test_project_fields_unknown_field() {
+        let mut value = json!({"data": [{"id": "a"}]});
+
+        project_fields(&mut value, &fields(&["missing"]));
+
+        assert_eq!(value, json!({"data": [{}]}));
+    }
+
+    /// Scalars inside arrays are untouched.
+    #[test]
+    fn test_project_fields_scalar_items() {
+        let mut value = json!({"data": ["a", "b"]});
+
+        project_fields(&mut value, &fields(&["id"]));
+
+        assert_eq!(value, json!({"data": ["a", "b"]}));
+    }
+
+    /// Keys and ID-shaped values are interned; free text is not.
+    #[test]
+    fn test_should_intern() {
+        let context_id = "7Xz1bR9kUq3N8mTfYc2WvLp4HsJd6AeGo5Ki1ZnMxQr";
+        assert!(should_intern(context_id, false));
+        assert!(should_intern("contextId", true));
+        assert!(!should_intern("short", false));
+        assert!(!should_intern(
+            "a sentence of free text, long enough to qualify",
+            false
+        ));
+        assert!(!should_intern(&"k".repeat(65), true));
+    }
+}
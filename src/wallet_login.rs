@@ -0,0 +1,321 @@
+//! Wallet-based logins: NEAR account keys and Internet Identity, plus the
+//! plain username/password login that shares their token exchange.
+//!
+//! Some auth service setups gate access on a blockchain identity instead of
+//! a password. Both wallet flows here fetch a challenge from the auth service
+//! (`auth/challenge`), sign it locally and exchange the proof for node JWTs
+//! at `auth/token`:
+//!
+//! - NEAR: the challenge is signed as a NEP-413 message with the account's
+//!   full-access or function-call ed25519 key.
+//! - Internet Identity: II only issues delegations to a browser dapp, so the
+//!   caller supplies the delegation chain and the session key it delegates
+//!   to; the session key signs the challenge.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use calimero_client::JwtToken;
+use eyre::WrapErr;
+use reqwest::Method;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use sha2::{Digest, Sha256};
+use url::Url;
+use zeroize::Zeroizing;
+
+use crate::auth_service;
+use crate::blob::find_string_field;
+use crate::http;
+use crate::storage::MeroboxFileStorage;
+
+/// Auth-service endpoint issuing login challenges.
+pub const CHALLENGE_PATH: &str = "auth/challenge";
+
+/// Auth-service endpoint exchanging a signed challenge for node tokens.
+pub const TOKEN_PATH: &str = "auth/token";
+
+/// Default NEP-413 recipient, matching the auth service's NEAR provider.
+pub const DEFAULT_NEAR_RECIPIENT: &str = "calimero";
+
+/// NEP-413 prefix tag: `2^31 + 413`.
+const NEP413_TAG: u32 = (1 << 31) + 413;
+
+/// DER prefix of an ed25519 `SubjectPublicKeyInfo`, as used by the IC.
+const ED25519_DER_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// Login challenge issued by the auth service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub message: String,
+    pub nonce: [u8; 32],
+}
+
+impl Challenge {
+    fn from_response(value: &serde_json::Value) -> eyre::Result<Self> {
+        let message = find_string_field(value, "challenge")
+            .or_else(|| find_string_field(value, "message"))
+            .ok_or_else(|| eyre::eyre!("Auth service returned no challenge"))?
+            .to_string();
+        let nonce = find_string_field(value, "nonce")
+            .ok_or_else(|| eyre::eyre!("Auth service returned no challenge nonce"))?;
+        let nonce: [u8; 32] = STANDARD
+            .decode(nonce)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| eyre::eyre!("Challenge nonce must be 32 base64-encoded bytes"))?;
+        Ok(Self { message, nonce })
+    }
+}
+
+/// Ask the auth service for a fresh challenge.
+pub async fn fetch_challenge(
+    client: &reqwest::Client,
+    api_url: &Url,
+    storage: &MeroboxFileStorage,
+    limit: Option<usize>,
+) -> eyre::Result<Challenge> {
+    let response = http::send_json(
+        client,
+        Method::GET,
+        api_url,
+        CHALLENGE_PATH,
+        storage,
+        None,
+        None,
+        limit,
+    )
+    .await
+    .wrap_err("The node's auth service did not issue a login challenge")?;
+    Challenge::from_response(&response)
+}
+
+/// Ed25519 signing key, from a 32-byte seed or a 64-byte seed+public key.
+pub struct SigningKey(Ed25519KeyPair);
+
+impl SigningKey {
+    pub fn from_bytes(bytes: &[u8]) -> eyre::Result<Self> {
+        let seed = match bytes.len() {
+            32 | 64 => &bytes[..32],
+            len => eyre::bail!("ed25519 keys are 32 or 64 bytes, got {}", len),
+        };
+        let pair = Ed25519KeyPair::from_seed_unchecked(seed)
+            .map_err(|_| eyre::eyre!("Invalid ed25519 key"))?;
+        if bytes.len() == 64 && pair.public_key().as_ref()[..] != bytes[32..] {
+            eyre::bail!("ed25519 key does not match its embedded public key");
+        }
+        Ok(Self(pair))
+    }
+
+    /// Parse a NEAR key string (`ed25519:<base58>`).
+    pub fn from_near(key: &str) -> eyre::Result<Self> {
+        let encoded = key
+            .strip_prefix("ed25519:")
+            .ok_or_else(|| eyre::eyre!("Only ed25519 NEAR keys are supported"))?;
+        let bytes = Zeroizing::new(
+            bs58::decode(encoded)
+                .into_vec()
+                .map_err(|_| eyre::eyre!("NEAR key is not valid base58"))?,
+        );
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn public_key(&self) -> &[u8] {
+        self.0.public_key().as_ref()
+    }
+
+    /// Public key in NEAR notation.
+    pub fn near_public_key(&self) -> String {
+        format!("ed25519:{}", bs58::encode(self.public_key()).into_string())
+    }
+
+    /// DER-encoded public key, as Internet Identity delegations reference it.
+    pub fn der_public_key(&self) -> Vec<u8> {
+        [&ED25519_DER_PREFIX[..], self.public_key()].concat()
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        self.0.sign(message).as_ref().to_vec()
+    }
+}
+
+fn borsh_string(out: &mut Vec<u8>, value: &str) {
+    out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    out.extend_from_slice(value.as_bytes());
+}
+
+/// SHA-256 of the NEP-413 payload `{message, nonce, recipient}` with no
+/// callback URL, prefixed by the NEP-413 tag — the bytes a wallet signs.
+pub fn nep413_hash(message: &str, nonce: &[u8; 32], recipient: &str) -> [u8; 32] {
+    let mut payload = Vec::with_capacity(4 + message.len() + 32 + recipient.len() + 9);
+    payload.extend_from_slice(&NEP413_TAG.to_le_bytes());
+    borsh_string(&mut payload, message);
+    payload.extend_from_slice(nonce);
+    borsh_string(&mut payload, recipient);
+    payload.push(0); // callback_url: None
+    Sha256::digest(payload).into()
+}
+
+/// Request body proving control of `account_id` for `challenge`.
+pub fn near_login_body(
+    account_id: &str,
+    key: &SigningKey,
+    challenge: &Challenge,
+    recipient: &str,
+    now: i64,
+) -> serde_json::Value {
+    let signature = key.sign(&nep413_hash(
+        &challenge.message,
+        &challenge.nonce,
+        recipient,
+    ));
+    serde_json::json!({
+        "auth_method": "near_wallet",
+        "public_key": key.near_public_key(),
+        "client_name": "calimero-client-py",
+        "timestamp": now,
+        "provider_data": {
+            "wallet_address": account_id,
+            "message": challenge.message,
+            "nonce": STANDARD.encode(challenge.nonce),
+            "recipient": recipient,
+            "signature": STANDARD.encode(signature),
+        },
+    })
+}
+
+/// Request body of a username/password login; no challenge is involved.
+pub fn password_login_body(username: &str, password: &str, now: i64) -> serde_json::Value {
+    serde_json::json!({
+        "auth_method": "user_password",
+        "public_key": username,
+        "client_name": "calimero-client-py",
+        "timestamp": now,
+        "provider_data": {
+            "username": username,
+            "password": password,
+        },
+    })
+}
+
+/// Request body proving an Internet Identity delegation to `session_key`.
+pub fn internet_identity_body(
+    delegation: &serde_json::Value,
+    session_key: &SigningKey,
+    challenge: &Challenge,
+    now: i64,
+) -> serde_json::Value {
+    let signature = session_key.sign(challenge.message.as_bytes());
+    serde_json::json!({
+        "auth_method": "internet_identity",
+        "public_key": hex::encode(session_key.der_public_key()),
+        "client_name": "calimero-client-py",
+        "timestamp": now,
+        "provider_data": {
+            "delegation": delegation,
+            "session_public_key": hex::encode(session_key.der_public_key()),
+            "message": challenge.message,
+            "nonce": STANDARD.encode(challenge.nonce),
+            "signature": STANDARD.encode(signature),
+        },
+    })
+}
+
+/// Exchange a signed login body for node tokens.
+pub async fn exchange(
+    client: &reqwest::Client,
+    api_url: &Url,
+    storage: &MeroboxFileStorage,
+    body: &serde_json::Value,
+    limit: Option<usize>,
+) -> eyre::Result<JwtToken> {
+    let method = body["auth_method"].as_str().unwrap_or_default().to_string();
+    let response = http::send_json(
+        client,
+        Method::POST,
+        api_url,
+        TOKEN_PATH,
+        storage,
+        None,
+        Some(body),
+        limit,
+    )
+    .await
+    .wrap_err_with(|| {
+        format!(
+            "The node's auth service did not accept the '{}' login",
+            method
+        )
+    })?;
+    auth_service::parse_tokens(&response, None, chrono::Utc::now().timestamp())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{UnparsedPublicKey, ED25519};
+
+    fn key() -> SigningKey {
+        SigningKey::from_bytes(&[9u8; 32]).unwrap()
+    }
+
+    fn challenge() -> Challenge {
+        Challenge {
+            message: "login to node".to_string(),
+            nonce: [1u8; 32],
+        }
+    }
+
+    /// NEAR keys round-trip through their string form; bad keys fail.
+    #[test]
+    fn test_near_key_parsing() {
+        let seed = [9u8; 32];
+        let full = [&seed[..], key().public_key()].concat();
+        let encoded = format!("ed25519:{}", bs58::encode(&full).into_string());
+        let parsed = SigningKey::from_near(&encoded).unwrap();
+        assert_eq!(parsed.near_public_key(), key().near_public_key());
+
+        assert!(SigningKey::from_near("secp256k1:abc").is_err());
+        let mut tampered = full.clone();
+        tampered[40] ^= 1;
+        let tampered = format!("ed25519:{}", bs58::encode(&tampered).into_string());
+        assert!(SigningKey::from_near(&tampered).is_err());
+    }
+
+    /// The NEAR proof verifies against the NEP-413 hash.
+    #[test]
+    fn test_near_signature_verifies() {
+        let body = near_login_body("alice.near", &key(), &challenge(), "calimero", 0);
+        let signature = STANDARD
+            .decode(body["provider_data"]["signature"].as_str().unwrap())
+            .unwrap();
+        let hash = nep413_hash("login to node", &[1u8; 32], "calimero");
+        UnparsedPublicKey::new(&ED25519, key().public_key())
+            .verify(&hash, &signature)
+            .unwrap();
+        assert_eq!(body["provider_data"]["wallet_address"], "alice.near");
+    }
+
+    /// Challenges need a 32-byte nonce.
+    #[test]
+    fn test_challenge_parsing() {
+        let value = serde_json::json!({
+            "data": {"challenge": "c", "nonce": STANDARD.encode([2u8; 32])}
+        });
+        assert_eq!(Challenge::from_response(&value).unwrap().nonce, [2u8; 32]);
+        let short = serde_json::json!({"data": {"challenge": "c", "nonce": "AAAA"}});
+        assert!(Challenge::from_response(&short).is_err());
+    }
+
+    /// DER keys carry the ed25519 SPKI prefix.
+    #[test]
+    fn test_der_public_key() {
+        let der = key().der_public_key();
+        assert_eq!(der.len(), 44);
+        assert_eq!(&der[..12], &ED25519_DER_PREFIX);
+    }
+}
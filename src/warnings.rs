@@ -0,0 +1,169 @@
+//! Structured warnings bridged to Python's `warnings` module.
+//!
+//! Soft problems — insecure configuration, tokens about to expire, corrupt
+//! token files, slow event handlers, deprecated endpoints — are reported with
+//! `warnings.warn` under dedicated categories, so they are visible by default
+//! but can be filtered or escalated with the standard `warnings` filters:
+//!
+//! ```python
+//! import warnings
+//! from calimero_client_py import InsecureConfigWarning
+//! warnings.simplefilter("error", InsecureConfigWarning)
+//! ```
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyDeprecationWarning, PyUserWarning};
+use pyo3::prelude::*;
+
+create_exception!(
+    calimero_client_py,
+    CalimeroWarning,
+    PyUserWarning,
+    "Base category for soft issues reported by the client."
+);
+create_exception!(
+    calimero_client_py,
+    InsecureConfigWarning,
+    CalimeroWarning,
+    "The connection is configured in a way that weakens security."
+);
+create_exception!(
+    calimero_client_py,
+    TokenExpiryWarning,
+    CalimeroWarning,
+    "A cached access token is expired or about to expire."
+);
+create_exception!(
+    calimero_client_py,
+    CorruptTokenCacheWarning,
+    CalimeroWarning,
+    "A cached token file did not parse and was moved aside."
+);
+create_exception!(
+    calimero_client_py,
+    SlowConsumerWarning,
+    CalimeroWarning,
+    "A Python event handler is falling behind the event stream."
+);
+create_exception!(
+    calimero_client_py,
+    DeprecatedEndpointWarning,
+    PyDeprecationWarning,
+    "A deprecated node endpoint or client API was used."
+);
+
+/// Tokens expiring within this many seconds trigger a `TokenExpiryWarning`.
+pub const TOKEN_EXPIRY_WARNING_SECS: i64 = 300;
+
+/// Categories of soft issues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    InsecureConfig,
+    TokenExpiry,
+    CorruptTokenCache,
+    SlowConsumer,
+    DeprecatedEndpoint,
+}
+
+impl WarningKind {
+    fn category<'py>(&self, py: Python<'py>) -> Bound<'py, pyo3::types::PyType> {
+        match self {
+            Self::InsecureConfig => py.get_type_bound::<InsecureConfigWarning>(),
+            Self::TokenExpiry => py.get_type_bound::<TokenExpiryWarning>(),
+            Self::CorruptTokenCache => py.get_type_bound::<CorruptTokenCacheWarning>(),
+            Self::SlowConsumer => py.get_type_bound::<SlowConsumerWarning>(),
+            Self::DeprecatedEndpoint => py.get_type_bound::<DeprecatedEndpointWarning>(),
+        }
+    }
+}
+
+/// Emit a warning attributed to the caller's Python frame.
+///
+/// Returns an error when a filter escalates the warning to an exception,
+/// which callers should propagate.
+pub fn warn(py: Python<'_>, kind: WarningKind, message: &str) -> PyResult<()> {
+    PyErr::warn_bound(py, &kind.category(py), message, 1)
+}
+
+/// Emit a warning from code that cannot hand a Python error back (storage
+/// backends, background tasks). A filter escalating it to an exception gets
+/// it printed; without an interpreter (Rust tests) it is only logged.
+pub fn warn_detached(kind: WarningKind, message: &str) {
+    // SAFETY: Py_IsInitialized may be called at any time.
+    if unsafe { pyo3::ffi::Py_IsInitialized() } == 0 {
+        tracing::warn!("{}", message);
+        return;
+    }
+    Python::with_gil(|py| {
+        if let Err(e) = warn(py, kind, message) {
+            e.print(py);
+        }
+    });
+}
+
+/// Message for a token expiring at `expires_at` (unix seconds), or `None`
+/// when it is comfortably valid.
+pub fn token_expiry_message(node_name: &str, expires_at: i64, now: i64) -> Option<String> {
+    let remaining = expires_at - now;
+    if remaining <= 0 {
+        Some(format!(
+            "Cached access token for '{}' has expired; it will be refreshed on the next call",
+            node_name
+        ))
+    } else if remaining <= TOKEN_EXPIRY_WARNING_SECS {
+        Some(format!(
+            "Cached access token for '{}' expires in {}s",
+            node_name, remaining
+        ))
+    } else {
+        None
+    }
+}
+
+/// Register the warning categories on the Python module.
+pub fn register(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("CalimeroWarning", py.get_type_bound::<CalimeroWarning>())?;
+    m.add(
+        "InsecureConfigWarning",
+        py.get_type_bound::<InsecureConfigWarning>(),
+    )?;
+    m.add(
+        "TokenExpiryWarning",
+        py.get_type_bound::<TokenExpiryWarning>(),
+    )?;
+    m.add(
+        "CorruptTokenCacheWarning",
+        py.get_type_bound::<CorruptTokenCacheWarning>(),
+    )?;
+    m.add(
+        "SlowConsumerWarning",
+        py.get_type_bound::<SlowConsumerWarning>(),
+    )?;
+    m.add(
+        "DeprecatedEndpointWarning",
+        py.get_type_bound::<DeprecatedEndpointWarning>(),
+    )?;
+    Ok(())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Only expired or soon-to-expire tokens produce a warning.
+    #[test]
+    fn test_token_expiry_message() {
+        let now = 1_000_000;
+        assert!(token_expiry_message("n", now + 3600, now).is_none());
+        assert!(token_expiry_message("n", now + 60, now)
+            .unwrap()
+            .contains("expires in 60s"));
+        assert!(token_expiry_message("n", now - 1, now)
+            .unwrap()
+            .contains("has expired"));
+    }
+}
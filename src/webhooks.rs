@@ -0,0 +1,511 @@
+//! Embedded listener for node webhook deliveries.
+//!
+//! Nodes can push context events to an HTTP endpoint instead of (or besides)
+//! the WebSocket stream behind `Client.subscribe()`. A [`PyWebhookServer`]
+//! is that endpoint: it accepts `POST`s on one path, checks each delivery's
+//! signature, and hands the events to callbacks registered with `on_event`
+//! the same way callback subscriptions do — as a list per delivery, decoded
+//! into typed `ApplicationEvent` objects when an `EventSchemaRegistry` is
+//! given — so a consumer does not care which path an event took.
+//!
+//! ```python
+//! server = WebhookServer(secret, port=8900, schemas=registry)
+//! server.on_event(handle_events, context_id=ctx)
+//! with server:
+//!     ...  # node delivers to server.url
+//! ```
+//!
+//! Deliveries are signed with HMAC-SHA256 of the raw body under the shared
+//! secret, sent hex-encoded (optionally prefixed `sha256=`) in the
+//! [`SIGNATURE_HEADER`] header. Unsigned or mis-signed requests get `401`
+//! and reach no callback. The sender gets `204` before the callbacks run, so
+//! a slow callback never times out the node; callback exceptions are printed,
+//! as for subscriptions.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use ring::hmac;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::watch;
+
+use crate::callbacks::{Callback, CallbackList, PyCallbackHandle};
+use crate::event_schema::EventSchemaRegistry;
+use crate::events;
+use crate::utils::json_to_python;
+
+/// Header carrying a delivery's signature.
+pub const SIGNATURE_HEADER: &str = "x-calimero-signature";
+
+/// Default path deliveries are accepted on.
+pub const DEFAULT_WEBHOOK_PATH: &str = "/webhook";
+
+/// Default cap on a delivery's body.
+pub const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Cap on the request line and headers together.
+const MAX_HEAD_BYTES: u64 = 16 * 1024;
+
+/// Limit on receiving one request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `sha256=<hex>` signature of `body` under `secret`.
+pub fn sign_body(secret: &[u8], body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+    format!("sha256={}", hex::encode(hmac::sign(&key, body).as_ref()))
+}
+
+/// Whether `signature` (hex, optionally `sha256=`-prefixed) signs `body`
+/// under `secret`. Compared in constant time.
+pub fn verify_signature(secret: &[u8], body: &[u8], signature: &str) -> bool {
+    let signature = signature.trim();
+    let hex_tag = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let Ok(tag) = hex::decode(hex_tag) else {
+        return false;
+    };
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+    hmac::verify(&key, body, &tag).is_ok()
+}
+
+/// A received HTTP request.
+#[derive(Debug, Default, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// Request target without its query string.
+    pub path: String,
+    /// Header names lower-cased.
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/// Status and reason phrase of a refused request.
+type Refusal = (u16, &'static str);
+
+/// Read one HTTP/1.1 request, refusing heads over [`MAX_HEAD_BYTES`] and
+/// bodies over `max_body_bytes`.
+pub async fn read_request<R: AsyncRead + Unpin>(
+    reader: &mut BufReader<R>,
+    max_body_bytes: usize,
+) -> Result<Request, Refusal> {
+    const BAD_REQUEST: Refusal = (400, "Bad Request");
+    let mut head = (&mut *reader).take(MAX_HEAD_BYTES);
+    let mut line = String::new();
+    head.read_line(&mut line).await.map_err(|_| BAD_REQUEST)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(BAD_REQUEST);
+    };
+    let mut request = Request {
+        method: method.to_string(),
+        path: target.split('?').next().unwrap_or_default().to_string(),
+        ..Request::default()
+    };
+    loop {
+        line.clear();
+        if head.read_line(&mut line).await.map_err(|_| BAD_REQUEST)? == 0 {
+            // Head cut off by the size cap or the peer.
+            return Err((431, "Request Header Fields Too Large"));
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':').ok_or(BAD_REQUEST)?;
+        request
+            .headers
+            .insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+    let length = match request.headers.get("content-length") {
+        Some(length) => length.parse::<usize>().map_err(|_| BAD_REQUEST)?,
+        None => return Err((411, "Length Required")),
+    };
+    if length > max_body_bytes {
+        return Err((413, "Payload Too Large"));
+    }
+    request.body = vec![0; length];
+    reader
+        .read_exact(&mut request.body)
+        .await
+        .map_err(|_| BAD_REQUEST)?;
+    Ok(request)
+}
+
+/// Events of a delivery body: one event or a list of them, each without
+/// the JSON-RPC envelope it may arrive in.
+pub fn delivery_events(body: &[u8]) -> Option<Vec<Value>> {
+    let unwrap = |event: Value| match event {
+        Value::Object(mut object) if object.contains_key("result") => {
+            object.remove("result").unwrap_or_default()
+        }
+        event => event,
+    };
+    match serde_json::from_slice::<Value>(body).ok()? {
+        Value::Array(events) => Some(events.into_iter().map(unwrap).collect()),
+        event @ Value::Object(_) => Some(vec![unwrap(event)]),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    received: AtomicU64,
+    delivered: AtomicU64,
+    rejected: AtomicU64,
+}
+
+/// What the accept loop and connection tasks share with the Python object.
+struct Shared {
+    secret: Vec<u8>,
+    path: String,
+    max_body_bytes: usize,
+    schemas: Option<Py<EventSchemaRegistry>>,
+    /// Callbacks by the context they filter on; `None` takes every event.
+    listeners: Mutex<HashMap<Option<String>, Arc<CallbackList>>>,
+    stats: Stats,
+}
+
+impl Shared {
+    /// Events of `request`, or why it is refused.
+    fn accept(&self, request: &Request) -> Result<Vec<Value>, Refusal> {
+        if request.path != self.path {
+            return Err((404, "Not Found"));
+        }
+        if request.method != "POST" {
+            return Err((405, "Method Not Allowed"));
+        }
+        let signed = request
+            .headers
+            .get(SIGNATURE_HEADER)
+            .is_some_and(|signature| verify_signature(&self.secret, &request.body, signature));
+        if !signed {
+            return Err((401, "Unauthorized"));
+        }
+        delivery_events(&request.body).ok_or((400, "Bad Request"))
+    }
+
+    fn dispatch(&self, py: Python<'_>, delivered: &[Value]) {
+        let listeners: Vec<(Option<String>, Arc<CallbackList>)> = match self.listeners.lock() {
+            Ok(listeners) => listeners
+                .iter()
+                .map(|(context, list)| (context.clone(), Arc::clone(list)))
+                .collect(),
+            Err(_) => return,
+        };
+        let schemas = self.schemas.as_ref().map(|schemas| schemas.borrow(py));
+        for (context, list) in listeners {
+            let batch: Vec<Value> = delivered
+                .iter()
+                .filter(|event| {
+                    context.as_deref().map_or(true, |context| {
+                        event.get("contextId").and_then(Value::as_str) == Some(context)
+                    })
+                })
+                .cloned()
+                .collect();
+            if batch.is_empty() {
+                continue;
+            }
+            for callback in list.live(py) {
+                match events::deliver(py, &callback, &batch, schemas.as_deref()) {
+                    Ok(()) => {
+                        self.stats
+                            .delivered
+                            .fetch_add(batch.len() as u64, Ordering::Relaxed);
+                    }
+                    Err(e) => e.print(py),
+                }
+            }
+        }
+    }
+
+    async fn handle(&self, stream: TcpStream) {
+        let (read, mut write) = stream.into_split();
+        let mut reader = BufReader::new(read);
+        let outcome =
+            tokio::time::timeout(READ_TIMEOUT, read_request(&mut reader, self.max_body_bytes))
+                .await
+                .unwrap_or(Err((408, "Request Timeout")))
+                .and_then(|request| self.accept(&request));
+
+        let (status, reason) = match &outcome {
+            Ok(_) => (204, "No Content"),
+            Err(refusal) => *refusal,
+        };
+        let response = format!(
+            "HTTP/1.1 {} {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            status, reason
+        );
+        let _ = write.write_all(response.as_bytes()).await;
+        let _ = write.shutdown().await;
+
+        match outcome {
+            Ok(delivered) => {
+                self.stats
+                    .received
+                    .fetch_add(delivered.len() as u64, Ordering::Relaxed);
+                Python::with_gil(|py| self.dispatch(py, &delivered));
+            }
+            Err(_) => {
+                self.stats.rejected.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+async fn serve(listener: TcpListener, shared: Arc<Shared>, mut stop: watch::Receiver<bool>) {
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = stop.wait_for(|stop| *stop) => break,
+        };
+        if let Ok((stream, _)) = accepted {
+            let shared = Arc::clone(&shared);
+            tokio::spawn(async move { shared.handle(stream).await });
+        }
+    }
+}
+
+/// HTTP endpoint receiving signed webhook deliveries from nodes.
+///
+/// `start()` (or entering a `with` block) begins listening on `host:port`
+/// (`port=0` picks a free one, see `url`); `stop()` (or leaving the block)
+/// stops accepting deliveries.
+#[pyclass(name = "WebhookServer")]
+pub struct PyWebhookServer {
+    host: String,
+    port: u16,
+    shared: Arc<Shared>,
+    runtime: Arc<Runtime>,
+    running: Mutex<Option<(SocketAddr, watch::Sender<bool>)>>,
+}
+
+impl PyWebhookServer {
+    fn address(&self) -> Option<SocketAddr> {
+        self.running
+            .lock()
+            .ok()
+            .and_then(|running| running.as_ref().map(|(address, _)| *address))
+    }
+}
+
+#[pymethods]
+impl PyWebhookServer {
+    /// Endpoint for deliveries signed with `secret`, on `path`. Events are
+    /// decoded with `schemas` (an `EventSchemaRegistry`) when given.
+    #[new]
+    #[pyo3(signature = (secret, host="127.0.0.1", port=0, path=DEFAULT_WEBHOOK_PATH, schemas=None, max_body_bytes=DEFAULT_MAX_BODY_BYTES))]
+    pub fn new(
+        secret: &str,
+        host: &str,
+        port: u16,
+        path: &str,
+        schemas: Option<Py<EventSchemaRegistry>>,
+        max_body_bytes: usize,
+    ) -> PyResult<Self> {
+        if secret.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "secret must not be empty",
+            ));
+        }
+        if !path.starts_with('/') {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "path must start with '/'",
+            ));
+        }
+        let runtime = Arc::new(
+            Runtime::new()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?,
+        );
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            shared: Arc::new(Shared {
+                secret: secret.as_bytes().to_vec(),
+                path: path.to_string(),
+                max_body_bytes,
+                schemas,
+                listeners: Mutex::new(HashMap::new()),
+                stats: Stats::default(),
+            }),
+            runtime,
+            running: Mutex::new(None),
+        })
+    }
+
+    /// Call `callback(events)` with the events of every accepted delivery
+    /// (only those of `context_id` when given). Bound methods are held
+    /// weakly unless `weak=False`, as for `Client.on_maintenance`.
+    #[pyo3(signature = (callback, context_id=None, weak=None))]
+    pub fn on_event(
+        &self,
+        py: Python<'_>,
+        callback: PyObject,
+        context_id: Option<String>,
+        weak: Option<bool>,
+    ) -> PyResult<PyCallbackHandle> {
+        let callback = Callback::new(py, callback, weak)?;
+        let mut listeners = self.shared.listeners.lock().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("webhook listeners poisoned")
+        })?;
+        Ok(listeners
+            .entry(context_id)
+            .or_default()
+            .subscribe("on_event", callback))
+    }
+
+    /// Start listening; returns `url`. Does nothing if already listening.
+    pub fn start(&self) -> PyResult<String> {
+        let mut running = self.running.lock().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("webhook server state poisoned")
+        })?;
+        if running.is_none() {
+            let listener = std::net::TcpListener::bind((self.host.as_str(), self.port))
+                .and_then(|listener| {
+                    listener.set_nonblocking(true)?;
+                    Ok(listener)
+                })
+                .map_err(|e| {
+                    PyErr::new::<pyo3::exceptions::PyOSError, _>(format!(
+                        "Failed to listen on {}:{}: {}",
+                        self.host, self.port, e
+                    ))
+                })?;
+            let address = listener.local_addr()?;
+            let _entered = self.runtime.enter();
+            let listener = TcpListener::from_std(listener)?;
+            let (stop, stop_rx) = watch::channel(false);
+            self.runtime
+                .spawn(serve(listener, Arc::clone(&self.shared), stop_rx));
+            *running = Some((address, stop));
+        }
+        drop(running);
+        Ok(self.url().unwrap_or_default())
+    }
+
+    /// Stop accepting deliveries. Callbacks stay registered for a later
+    /// `start()`.
+    pub fn stop(&self) {
+        if let Some((_, stop)) = self.running.lock().ok().and_then(|mut r| r.take()) {
+            stop.send_replace(true);
+        }
+    }
+
+    #[getter]
+    pub fn running(&self) -> bool {
+        self.address().is_some()
+    }
+
+    /// URL to configure on the node, e.g. `http://127.0.0.1:8900/webhook`;
+    /// `None` while stopped
+    #[getter]
+    pub fn url(&self) -> Option<String> {
+        self.address()
+            .map(|address| format!("http://{}{}", address, self.shared.path))
+    }
+
+    /// `{received, delivered, rejected}`: events accepted, events handed to
+    /// callbacks (once per callback), and requests refused
+    #[getter]
+    pub fn stats(&self, py: Python<'_>) -> PyObject {
+        let stats = &self.shared.stats;
+        json_to_python(
+            py,
+            &json!({
+                "received": stats.received.load(Ordering::Relaxed),
+                "delivered": stats.delivered.load(Ordering::Relaxed),
+                "rejected": stats.rejected.load(Ordering::Relaxed),
+            }),
+        )
+    }
+
+    /// Signature header value for `body` under `secret`, for senders and
+    /// tests.
+    #[staticmethod]
+    pub fn sign(secret: &str, body: &[u8]) -> String {
+        sign_body(secret.as_bytes(), body)
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        slf.start()?;
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, _args: &Bound<'_, PyTuple>) -> bool {
+        self.stop();
+        false
+    }
+
+    fn __repr__(&self) -> String {
+        match self.url() {
+            Some(url) => format!("WebhookServer(url='{}')", url),
+            None => "WebhookServer(stopped)".to_string(),
+        }
+    }
+}
+
+impl Drop for PyWebhookServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signatures() {
+        let signature = sign_body(b"secret", b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert!(verify_signature(b"secret", b"{}", &signature));
+        assert!(verify_signature(
+            b"secret",
+            b"{}",
+            signature.trim_start_matches("sha256=")
+        ));
+        assert!(!verify_signature(b"other", b"{}", &signature));
+        assert!(!verify_signature(b"secret", b"[]", &signature));
+        assert!(!verify_signature(b"secret", b"{}", "sha256=zz"));
+    }
+
+    #[test]
+    fn test_delivery_events() {
+        let one = delivery_events(br#"{"result": {"contextId": "c"}}"#).unwrap();
+        assert_eq!(one, vec![json!({"contextId": "c"})]);
+        let many = delivery_events(br#"[{"kind": "A"}, {"kind": "B"}]"#).unwrap();
+        assert_eq!(many.len(), 2);
+        assert!(delivery_events(b"42").is_none());
+        assert!(delivery_events(b"not json").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_request() {
+        let raw: &[u8] =
+            b"POST /webhook?x=1 HTTP/1.1\r\nContent-Length: 2\r\nX-Calimero-Signature: s\r\n\r\n{}";
+        let request = read_request(&mut BufReader::new(raw), 1024).await.unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/webhook");
+        assert_eq!(request.headers[SIGNATURE_HEADER], "s");
+        assert_eq!(request.body, b"{}");
+
+        let raw: &[u8] = b"POST / HTTP/1.1\r\nContent-Length: 5000\r\n\r\n";
+        let refused = read_request(&mut BufReader::new(raw), 1024).await;
+        assert_eq!(refused.unwrap_err().0, 413);
+        let raw: &[u8] = b"POST / HTTP/1.1\r\n\r\n";
+        let refused = read_request(&mut BufReader::new(raw), 1024).await;
+        assert_eq!(refused.unwrap_err().0, 411);
+    }
+}
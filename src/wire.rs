@@ -0,0 +1,204 @@
+//! Wire format negotiation for `execute` payloads.
+//!
+//! Nodes that understand MessagePack accept a gzip-compressed msgpack
+//! JSON-RPC request (`Content-Type: application/msgpack`) and answer in kind,
+//! which is noticeably cheaper than JSON for chatty workloads. Nodes that
+//! don't reject the request with a 4xx; the client then falls back to JSON
+//! and remembers the outcome so the probe is paid at most once per client.
+
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use eyre::WrapErr;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use pyo3::prelude::*;
+use serde::Serialize;
+
+use crate::utils::{json_to_python, python_to_json};
+
+/// Media type for msgpack request and response bodies.
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Wire format requested when constructing a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireMode {
+    /// Try msgpack first, fall back to JSON if the node doesn't support it.
+    Auto,
+    /// Always JSON (the typed `calimero-client` path).
+    Json,
+    /// Always msgpack; unsupported nodes are an error.
+    Msgpack,
+}
+
+impl WireMode {
+    pub fn parse(mode: &str) -> Result<Self, String> {
+        match mode.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "json" => Ok(Self::Json),
+            "msgpack" => Ok(Self::Msgpack),
+            _ => Err(format!(
+                "Invalid wire mode '{}'. Expected one of: auto, json, msgpack",
+                mode
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Json => "json",
+            Self::Msgpack => "msgpack",
+        }
+    }
+}
+
+const UNKNOWN: u8 = 0;
+const JSON: u8 = 1;
+const MSGPACK: u8 = 2;
+
+/// Per-client negotiation state.
+#[derive(Debug)]
+pub struct WireNegotiation {
+    mode: WireMode,
+    detected: AtomicU8,
+}
+
+impl WireNegotiation {
+    pub fn new(mode: WireMode) -> Self {
+        Self {
+            mode,
+            detected: AtomicU8::new(UNKNOWN),
+        }
+    }
+
+    pub fn mode(&self) -> WireMode {
+        self.mode
+    }
+
+    /// Whether the next execute should be sent as msgpack.
+    pub fn use_msgpack(&self) -> bool {
+        match self.mode {
+            WireMode::Auto => self.detected.load(Ordering::Relaxed) != JSON,
+            WireMode::Json => false,
+            WireMode::Msgpack => true,
+        }
+    }
+
+    /// Record whether the node accepted msgpack.
+    pub fn record(&self, msgpack_supported: bool) {
+        let value = if msgpack_supported { MSGPACK } else { JSON };
+        self.detected.store(value, Ordering::Relaxed);
+    }
+
+    /// Format currently in use: `"msgpack"`, `"json"`, or `None` before the
+    /// first execute in auto mode.
+    pub fn active(&self) -> Option<&'static str> {
+        match self.mode {
+            WireMode::Json => Some("json"),
+            WireMode::Msgpack => Some("msgpack"),
+            WireMode::Auto => match self.detected.load(Ordering::Relaxed) {
+                JSON => Some("json"),
+                MSGPACK => Some("msgpack"),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Serialize `value` as msgpack (with field names) and gzip it.
+pub fn encode<T: Serialize>(value: &T) -> eyre::Result<Vec<u8>> {
+    let packed = rmp_serde::to_vec_named(value).wrap_err("Failed to encode msgpack request")?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(&packed)?;
+    Ok(encoder.finish()?)
+}
+
+/// Decode a msgpack response body into JSON.
+pub fn decode(body: &[u8]) -> eyre::Result<serde_json::Value> {
+    rmp_serde::from_slice(body).wrap_err("Failed to decode msgpack response")
+}
+
+/// Inverse of [`encode`]: gunzip then decode msgpack into JSON.
+pub fn decode_gzipped(body: &[u8]) -> eyre::Result<serde_json::Value> {
+    let mut packed = Vec::new();
+    GzDecoder::new(body)
+        .read_to_end(&mut packed)
+        .wrap_err("Failed to gunzip msgpack body")?;
+    decode(&packed)
+}
+
+/// Push `obj` through a wire encoder and back, returning the decoded value.
+///
+/// `wire` is `"json"` or `"msgpack"`. Handy for checking that a payload
+/// survives the binding's conversions before sending it to a node.
+#[pyfunction]
+#[pyo3(signature = (obj, wire="json"))]
+pub fn roundtrip(py: Python<'_>, obj: &Bound<'_, PyAny>, wire: &str) -> PyResult<PyObject> {
+    let value = python_to_json(obj)?;
+    let decoded = match WireMode::parse(wire) {
+        Ok(WireMode::Json) => serde_json::to_vec(&value)
+            .and_then(|bytes| serde_json::from_slice(&bytes))
+            .map_err(eyre::Report::from),
+        Ok(WireMode::Msgpack) => encode(&value).and_then(|body| decode_gzipped(&body)),
+        _ => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid wire format '{}': expected 'json' or 'msgpack'",
+                wire
+            )))
+        }
+    }
+    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:#}", e)))?;
+    Ok(json_to_python(py, &decoded))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mode strings parse case-insensitively; unknown modes are rejected.
+    #[test]
+    fn test_wire_mode_parse() {
+        assert_eq!(WireMode::parse("AUTO"), Ok(WireMode::Auto));
+        assert_eq!(WireMode::parse("json"), Ok(WireMode::Json));
+        assert_eq!(WireMode::parse("msgpack"), Ok(WireMode::Msgpack));
+        assert!(WireMode::parse("cbor").is_err());
+    }
+
+    /// Auto mode probes msgpack until the node is known not to support it.
+    #[test]
+    fn test_auto_negotiation_falls_back_once() {
+        let wire = WireNegotiation::new(WireMode::Auto);
+        assert!(wire.use_msgpack());
+        assert_eq!(wire.active(), None);
+
+        wire.record(false);
+        assert!(!wire.use_msgpack());
+        assert_eq!(wire.active(), Some("json"));
+    }
+
+    /// Forced modes ignore detection results.
+    #[test]
+    fn test_forced_modes() {
+        let json = WireNegotiation::new(WireMode::Json);
+        json.record(true);
+        assert!(!json.use_msgpack());
+
+        let msgpack = WireNegotiation::new(WireMode::Msgpack);
+        msgpack.record(false);
+        assert!(msgpack.use_msgpack());
+    }
+
+    /// Encoded requests are gzip-wrapped msgpack that round-trips to JSON.
+    #[test]
+    fn test_encode_round_trip() {
+        let value = serde_json::json!({"jsonrpc": "2.0", "id": "1", "params": {"n": 5}});
+        let encoded = encode(&value).unwrap();
+        assert_eq!(decode_gzipped(&encoded).unwrap(), value);
+    }
+}
@@ -0,0 +1,59 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.audit (no node required).
+"""
+
+from calimero.audit import audit_permissions
+
+
+class _FakeClient:
+    def get_context(self, context_id):
+        return {"data": {"id": context_id, "groupId": "group-1"}}
+
+    def get_context_identities(self, context_id):
+        return {"data": {"identities": ["alice", "carol"]}}
+
+    def get_context_client_keys(self, context_id):
+        return {"data": {"clientKeys": [{"identity": "mallory", "key": "k1"}]}}
+
+    def list_group_members(self, group_id):
+        return {
+            "data": [
+                {"identity": "alice", "role": "Admin"},
+                {"identity": "bob", "role": "Admin"},
+            ]
+        }
+
+    def get_member_capabilities(self, group_id, member_id):
+        if member_id == "bob":
+            raise RuntimeError("Client error: not found")
+        return {"data": {"capabilities": 7}}
+
+
+def _kinds(report):
+    return sorted((a["kind"], a["subject"]) for a in report["anomalies"])
+
+
+def test_report_collects_members_and_identities():
+    report = audit_permissions(_FakeClient(), "ctx-1")
+    assert report["groupId"] == "group-1"
+    assert report["contextIdentities"] == ["alice", "carol"]
+    assert [m["publicKey"] for m in report["members"]] == ["alice", "bob"]
+    assert report["members"][0]["capabilities"] == {"capabilities": 7}
+    assert report["pendingInvitations"] is None
+
+
+def test_report_flags_anomalies():
+    report = audit_permissions(_FakeClient(), "ctx-1")
+    assert _kinds(report) == [
+        ("identity_outside_group", "carol"),
+        ("orphaned_admin", "bob"),
+        ("orphaned_client_key", "mallory"),
+    ]
+
+
+def test_failed_lookups_are_recorded_not_raised():
+    report = audit_permissions(_FakeClient(), "ctx-1")
+    assert report["errors"] == [
+        {"lookup": "capabilities:bob", "error": "Client error: not found"}
+    ]
@@ -3,6 +3,9 @@
 Basic tests for Calimero Client Python Library.
 """
 
+import json
+import os
+
 import pytest
 from calimero_client_py import create_connection, create_client, ClientError, AuthMode
 
@@ -33,6 +36,13 @@ def test_connection_creation():
     )
     assert connection is not None
     assert hasattr(connection, "api_url")
+    assert connection.transport == "http"
+
+
+def test_grpc_transport_not_yet_available():
+    """grpc:// URLs are recognized but nodes don't serve gRPC yet."""
+    with pytest.raises(NotImplementedError, match="gRPC"):
+        create_connection(api_url="grpc://localhost:2529")
 
 
 def test_client_creation():
@@ -44,6 +54,1990 @@ def test_client_creation():
     client = create_client(connection)
     assert client is not None
     assert hasattr(client, "get_api_url")
+    assert client.http_version == "auto"
+    assert client.wire_format is None
+
+
+def test_client_rejects_unknown_wire_mode():
+    """Only auto, json and msgpack wire modes exist."""
+    connection = create_connection(
+        api_url="https://test.merod.dev.p2p.aws.calimero.network",
+        node_name="test-dev-node",
+    )
+    with pytest.raises(ValueError, match="Invalid wire mode"):
+        create_client(connection, wire="cbor")
+
+
+def test_anonymous_connection():
+    """Anonymous connections are flagged on both connection and client."""
+    connection = create_connection(
+        api_url="https://test.merod.dev.p2p.aws.calimero.network",
+        node_name="test-dev-node",
+        anonymous=True,
+    )
+    assert connection.anonymous is True
+    assert create_client(connection).anonymous is True
+
+
+def test_client_user_keys_token_cache():
+    """Clients for different users of one node report their user."""
+    connection = create_connection(
+        api_url="https://test.merod.dev.p2p.aws.calimero.network",
+        node_name="test-dev-node",
+    )
+    assert create_client(connection).user is None
+    assert create_client(connection, user="alice").user == "alice"
+
+
+def test_client_profile_is_user():
+    """profile= keys tokens like user=; conflicting values are refused."""
+    connection = create_connection(
+        api_url="https://test.merod.dev.p2p.aws.calimero.network",
+        node_name="test-dev-node",
+    )
+    admin = create_client(connection, profile="admin")
+    assert admin.profile == "admin" and admin.user == "admin"
+    assert create_client(connection).profile is None
+    assert create_client(connection, user="admin", profile="admin").user == "admin"
+    with pytest.raises(ValueError):
+        create_client(connection, user="admin", profile="member")
+
+
+def test_temporary_context_creates_on_enter():
+    """Nothing is created until the with block; failed creation leaks nothing."""
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    ctx = create_client(connection).temporary_context("not-an-id", "group")
+    assert ctx.context_id is None and ctx.deleted is False
+    with pytest.raises(ValueError):
+        with ctx:
+            pass
+    assert ctx.context_id is None
+
+
+def test_execute_async_is_awaitable():
+    """execute_async validates eagerly and raises call failures on await."""
+    import asyncio
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    with pytest.raises(ValueError):
+        client.execute_async("not-an-id", "get", "{}")
+
+    async def call():
+        return await client.execute_async("1" * 32, "get", "{}")
+
+    with pytest.raises(RuntimeError):
+        asyncio.run(call())
+
+
+def test_subscription_close_ends_iteration():
+    """Subscribing needs no live node; close() ends async iteration."""
+    import asyncio
+
+    from calimero_client_py import Subscription
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    assert client.subscription_stats is None
+    with pytest.raises(ValueError):
+        client.subscribe("not-an-id")
+    subscription = client.subscribe("11111111111111111111111111111111")
+    assert isinstance(subscription, Subscription)
+    assert client.subscription_stats["contexts"] == [subscription.context_id]
+
+    async def drain():
+        subscription.close()
+        return [event async for event in subscription]
+
+    assert asyncio.run(drain()) == []
+    assert subscription.closed
+
+
+def test_close_tears_the_client_down():
+    """close() ends subscriptions and refuses later calls; async with closes."""
+    import asyncio
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    subscription = client.subscribe("11111111111111111111111111111111")
+    assert not client.closed and not subscription.closed
+    client.close()
+    assert client.closed and subscription.closed
+    assert subscription.get(timeout=0.1) is None
+    with pytest.raises(ClientError, match="closed"):
+        client.get_peers_count()
+    client.close()
+
+    async def scoped():
+        connection = create_connection(api_url="http://127.0.0.1:9")
+        async with create_client(connection) as c:
+            assert not c.closed
+        return c
+
+    assert asyncio.run(scoped()).closed
+
+
+def test_audit_log_chains_mutating_calls(tmp_path):
+    """Failed mutating calls are recorded with a verifiable chain."""
+    from calimero_client_py import verify_audit_log
+
+    records = []
+    client = create_client(
+        create_connection(api_url="http://127.0.0.1:9"), audit_log=records.append
+    )
+    with pytest.raises(ClientError):
+        client.leave_namespace("ns-1")
+    assert [r["operation"] for r in records] == ["leave_namespace"]
+    assert records[0]["outcome"] == "error" and records[0]["seq"] == 1
+    assert records[0]["prev"] == "0" * 64
+
+    path = tmp_path / "audit.jsonl"
+    for _ in range(2):
+        client = create_client(
+            create_connection(api_url="http://127.0.0.1:9"), audit_log=str(path)
+        )
+        with pytest.raises(ClientError):
+            client.leave_namespace("ns-1")
+    report = verify_audit_log(str(path))
+    assert report["valid"] and report["records"] == 2
+
+    lines = path.read_text().splitlines()
+    path.write_text(lines[1] + "\n")
+    assert verify_audit_log(str(path))["firstInvalid"] == 1
+
+
+def test_audit_log_key_and_head(tmp_path):
+    """A keyed chain verifies only under its key and ends at audit_head."""
+    from calimero_client_py import verify_audit_log
+
+    path = tmp_path / "audit.jsonl"
+    client = create_client(
+        create_connection(api_url="http://127.0.0.1:9"),
+        audit_log=str(path),
+        audit_key="s3cret",
+    )
+    assert client.audit_head == {"seq": 0, "hash": "0" * 64}
+    with pytest.raises(ClientError):
+        client.leave_namespace("ns-1")
+    head = client.audit_head
+    assert head["seq"] == 1
+
+    report = verify_audit_log(str(path), key="s3cret")
+    assert report["valid"] and report["head"] == head["hash"]
+    assert verify_audit_log(str(path))["firstInvalid"] == 1
+    assert verify_audit_log(str(path), key="guess")["firstInvalid"] == 1
+
+    with pytest.raises(ValueError, match="audit_key needs audit_log"):
+        create_client(
+            create_connection(api_url="http://127.0.0.1:9"), audit_key="s3cret"
+        )
+
+
+def test_event_schema_registry_types_events():
+    """Registered events decode to typed objects; others pass through."""
+    import json
+
+    from calimero_client_py import ApplicationEvent, EventSchemaRegistry
+
+    registry = EventSchemaRegistry()
+    registry.register("app-1", {"ItemAdded": {"key": "string", "count": "int?"}})
+    payload = list(json.dumps({"key": "k", "count": 2}).encode())
+    event = registry.decode({"contextId": "ctx", "kind": "ItemAdded", "data": payload})
+
+    ItemAdded = registry.event_class("app-1", "ItemAdded")
+    assert isinstance(event, ItemAdded) and isinstance(event, ApplicationEvent)
+    assert (event.key, event.count, event.kind) == ("k", 2, "ItemAdded")
+    assert event.context_id == "ctx" and event.application_id == "app-1"
+    assert repr(event).startswith("ItemAdded(") and "key='k'" in repr(event)
+
+    unknown = {"kind": "Other", "data": payload}
+    assert registry.decode(unknown) == unknown
+    mismatched = {"kind": "ItemAdded", "data": {"key": 5}}
+    assert registry.decode(mismatched) == mismatched
+
+    wrapped = registry.decode(
+        {"contextId": "ctx", "type": "ExecutionEvent", "data": {"events": [{"kind": "ItemAdded", "data": payload}]}}
+    )
+    assert isinstance(wrapped["data"]["events"][0], ItemAdded)
+    with pytest.raises(ValueError):
+        registry.register("app-2", {"ItemAdded": {"key": "uuid"}})
+
+
+def test_warning_categories_are_filterable():
+    """Soft-issue categories subclass the standard warning types."""
+    from calimero_client_py import (
+        CalimeroWarning,
+        DeprecatedEndpointWarning,
+        InsecureConfigWarning,
+        TokenExpiryWarning,
+    )
+
+    for category in (InsecureConfigWarning, TokenExpiryWarning):
+        assert issubclass(category, CalimeroWarning)
+    assert issubclass(CalimeroWarning, UserWarning)
+    assert issubclass(DeprecatedEndpointWarning, DeprecationWarning)
+
+
+def test_renamed_method_forwards_with_deprecation_warning():
+    """Old names in the deprecation table forward to the new method."""
+    from calimero_client_py import DeprecatedEndpointWarning
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    with pytest.warns(
+        DeprecatedEndpointWarning, match=r"client\.set_subgroup_visibility\("
+    ):
+        method = client.set_default_visibility
+    assert method == client.set_subgroup_visibility
+    with pytest.raises(AttributeError):
+        client.no_such_method
+
+
+def test_plaintext_remote_node_warns_insecure_config():
+    """Plain HTTP to a remote node warns at creation; loopback does not."""
+    import warnings
+
+    from calimero_client_py import InsecureConfigWarning
+
+    with pytest.warns(InsecureConfigWarning, match="unencrypted HTTP"):
+        create_client(create_connection(api_url="http://node.example:2528"))
+    with warnings.catch_warnings():
+        warnings.simplefilter("error", InsecureConfigWarning)
+        create_client(create_connection(api_url="http://127.0.0.1:2528"))
+        with pytest.raises(InsecureConfigWarning):
+            create_client(create_connection(api_url="http://node.example:2528"))
+
+
+def test_security_check_returns_findings():
+    """security_check reports local findings without contacting the node."""
+    connection = create_connection(api_url="http://node.example:2528")
+    findings = create_client(connection).security_check()
+    ids = {finding["id"] for finding in findings}
+    assert "plaintext_transport" in ids
+    assert all(f["severity"] in ("low", "medium", "high") for f in findings)
+
+
+def test_ephemeral_client_never_touches_token_cache():
+    """Ephemeral tokens stay in memory; nothing lands in the cache dir."""
+    from calimero_client_py import Client, get_token_cache_path
+
+    node_name = "ephemeral-test-node"
+    with Client.ephemeral(
+        "https://test.merod.dev.p2p.aws.calimero.network",
+        node_name=node_name,
+        access_token="secret-access-token",
+        ttl=60,
+    ) as client:
+        assert client.ephemeral_tokens is True
+    assert not os.path.exists(get_token_cache_path(node_name))
+
+
+def test_jwt_token_redacted_until_revealed():
+    """Token strings only leave the wrapper through reveal()."""
+    from calimero_client_py import JwtToken
+
+    token = JwtToken("secret-access", refresh_token="secret-refresh")
+    assert token.access_token == "<redacted>"
+    assert token.refresh_token == "<redacted>"
+    assert "secret" not in str(token) and "secret" not in repr(token)
+    assert token.reveal() == "secret-access"
+    assert token.reveal(refresh=True) == "secret-refresh"
+
+
+def test_jwt_token_lifetimes_and_ordering():
+    """Expiry is exposed as datetime/timedelta; tokens order by expiry."""
+    import base64
+    import json
+    from datetime import datetime, timedelta, timezone
+
+    from calimero_client_py import JwtToken
+
+    soon = datetime.now(timezone.utc) + timedelta(hours=1)
+    token = JwtToken("secret-access", expires_at=soon)
+    assert token.expires_at == soon.replace(microsecond=0)
+    assert token.expires_at_timestamp == int(soon.timestamp())
+    assert token.is_expired is False
+    assert timedelta(minutes=59) < token.ttl <= timedelta(hours=1)
+
+    expired = JwtToken("secret-access", expires_at=1)
+    assert expired.is_expired is True
+    assert expired.ttl == timedelta(0)
+    forever = JwtToken("secret-access")
+    assert forever.expires_at is None and forever.ttl is None
+    assert expired < token < forever
+    assert max([token, forever, expired]) is forever
+    assert JwtToken("secret-access", expires_at=1) == expired
+    assert JwtToken("other", expires_at=1) != expired
+
+    # Without expires_at, the JWT's exp claim is used; repr shows only the header
+    claims = base64.urlsafe_b64encode(json.dumps({"exp": 4102444800}).encode())
+    jwt = "eyJhbGciOiJIUzI1NiJ9." + claims.decode().rstrip("=") + ".c2lnbmF0dXJl"
+    token = JwtToken(jwt)
+    assert token.expires_at_timestamp == 4102444800
+    assert "eyJhbGci…" in repr(token) and claims.decode()[:10] not in repr(token)
+
+
+def test_internal_error_is_runtime_error():
+    """Panics surface as InternalError, catchable as a RuntimeError."""
+    from calimero_client_py import InternalError
+
+    assert issubclass(InternalError, RuntimeError)
+
+
+def test_max_response_bytes_configurable():
+    """Responses are capped by default; the cap can be changed or disabled."""
+    from calimero_client_py import ResponseTooLargeError
+
+    connection = create_connection(api_url="http://localhost:2528")
+    assert create_client(connection).max_response_bytes == 64 * 1024 * 1024
+    assert create_client(connection, max_response_bytes=1024).max_response_bytes == 1024
+    assert create_client(connection, max_response_bytes=None).max_response_bytes is None
+    assert issubclass(ResponseTooLargeError, RuntimeError)
+
+
+def test_oversized_args_fail_before_sending():
+    """Arguments over max_request_bytes raise a precise error client-side."""
+    from calimero_client_py import RequestTooLargeError
+
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    assert create_client(connection).max_request_bytes == 2 * 1024 * 1024
+    client = create_client(connection, max_request_bytes=1024)
+    args = json.dumps({"value": "x" * 4096})
+    with pytest.raises(RequestTooLargeError, match="max_request_bytes limit of 1024"):
+        client.execute_function("11111111111111111111111111111111", "set", args)
+    with pytest.raises(ValueError):
+        create_client(connection, oversized_args="chunk")
+
+
+def test_client_errors_carry_call_context():
+    """Failed calls report the operation, node and context they were serving."""
+    connection = create_connection(
+        api_url="http://127.0.0.1:9", node_name="error-context-test-node"
+    )
+    client = create_client(connection)
+    context_id = "11111111111111111111111111111111"
+    with pytest.raises(RuntimeError) as excinfo:
+        client.get_context_storage(context_id)
+
+    err = excinfo.value
+    assert err.operation == "get_context_storage"
+    assert err.node_name == "error-context-test-node"
+    assert err.context_id == context_id
+    assert err.retry_history == []
+    assert err.error_context["operation"] == "get_context_storage"
+
+
+def test_client_errors_carry_error_code():
+    """Every client error has a stable ErrorCode to branch on."""
+    from calimero_client_py import ErrorCode
+
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    with pytest.raises(RuntimeError) as excinfo:
+        create_client(connection).list_contexts()
+
+    assert isinstance(excinfo.value.code, ErrorCode)
+    assert excinfo.value.error_context["code"] == excinfo.value.code.value
+    assert ErrorCode.CONTEXT_NOT_FOUND.value == "context_not_found"
+
+
+def test_client_errors_are_typed():
+    """Failures raise a CalimeroError subclass that is still a RuntimeError."""
+    from calimero_client_py import (
+        AuthenticationError,
+        CalimeroError,
+        NodeUnreachableError,
+        ResponseTooLargeError,
+        RpcError,
+        TokenExpiredError,
+    )
+
+    assert issubclass(TokenExpiredError, AuthenticationError)
+    assert issubclass(RpcError, CalimeroError)
+    assert issubclass(ResponseTooLargeError, CalimeroError)
+    assert issubclass(CalimeroError, RuntimeError)
+
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    with pytest.raises(NodeUnreachableError) as excinfo:
+        create_client(connection).list_contexts()
+    assert excinfo.value.code.value == "network"
+
+
+def test_retry_budget_reported_in_stats():
+    """The retry budget is configurable and visible in stats()."""
+    connection = create_connection(api_url="http://localhost:2528")
+    stats = create_client(connection, retry_budget=0.1).stats()
+    assert stats["retryBudget"]["ratio"] == 0.1
+    assert stats["retryBudget"]["retries"] == 0
+    assert "limit" in stats["concurrency"]
+    with pytest.raises(ValueError, match="retry_budget"):
+        create_client(connection, retry_budget=1.5)
+
+
+def test_dedup_window_folds_identical_calls():
+    """A duplicate inside the window shares the first call's failure."""
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    assert create_client(connection).stats()["dedup"]["windowMs"] is None
+    client = create_client(connection, dedup_window_ms=60_000)
+    context_id = "11111111111111111111111111111111"
+    for _ in range(2):
+        with pytest.raises(RuntimeError):
+            client.execute_function(context_id, "add", "{}")
+    assert client.stats()["dedup"] == {"windowMs": 60_000, "suppressed": 1}
+
+
+def test_execute_batch_reports_invalid_calls_per_item():
+    """Invalid calls fail their own record without sending anything."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    result = client.execute_batch(
+        [
+            ("not-an-id", "get", "{}"),
+            {"context_id": "11111111111111111111111111111111", "method": "get", "args": "not json"},
+        ]
+    )
+    assert result.operation == "execute_batch"
+    assert len(result) == 2 and not result.ok
+    assert "Invalid context ID" in result[0]["error"]
+    assert "Invalid JSON args" in result[1]["error"]
+    retried = client.execute_batch(result.failed_inputs)
+    assert [r["method"] for r in retried] == ["get", "get"]
+    with pytest.raises(ValueError):
+        client.execute_batch([], max_batch_size=0)
+
+
+def test_probe_reports_unreachable_node():
+    """Failed samples are reported in the probe result, not raised."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    report = client.probe(samples=2)
+    assert report["node"] == "http://127.0.0.1:9/"
+    assert report["samples"] == 2 and report["failed"] == 2
+    assert report["rttMs"] is None and report["tlsHandshakeMs"] is None
+    assert len(report["errors"]) == 2
+    with pytest.raises(ValueError):
+        client.probe(samples=0)
+
+
+def test_clients_share_connection_pool():
+    """Clients of one node share a pool; settings are validated."""
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    first = create_client(connection, max_connections_per_host=4)
+    pooled = first.stats()["pool"]["pooledClients"]
+    second = create_client(connection)
+    assert second.stats()["pool"]["pooledClients"] == pooled
+    assert first.stats()["pool"]["maxConnectionsPerHost"] == 4
+    with pytest.raises(ValueError):
+        create_client(connection, max_connections_per_host=0)
+
+
+def test_replica_set_routes_to_primary_until_probed():
+    """Reads fall back to the primary while no node answers probes."""
+    from calimero_client_py import ReplicaSet
+
+    primary = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    replica = create_client(create_connection(api_url="http://127.0.0.1:10"))
+    with ReplicaSet(primary, [replica], probe_interval=60.0) as replicas:
+        stats = replicas.probe()
+        assert [node["role"] for node in stats] == ["primary", "replica"]
+        assert all(node["rttMs"] is None for node in stats)
+        assert stats[0]["selected"]
+        assert replicas.read_client().get_api_url() == primary.get_api_url()
+        assert replicas.write_client().get_api_url() == primary.get_api_url()
+    assert replicas.closed
+    with pytest.raises(ValueError):
+        ReplicaSet(primary, [replica], probe_interval=0)
+
+
+def test_retry_config():
+    """Retry settings are validated and reported in stats."""
+    from calimero_client_py import ErrorCode, RetryConfig
+
+    config = RetryConfig(max_attempts=4, retry_on=["rate_limited", ErrorCode.NETWORK])
+    assert config.max_attempts == 4
+    assert config.retry_on == [ErrorCode.RATE_LIMITED, ErrorCode.NETWORK]
+    assert not config.retry_mutations
+    for bad in ({"max_attempts": 0}, {"jitter": 1.5}, {"retry_on": ["bogus"]}):
+        with pytest.raises(ValueError):
+            RetryConfig(**bad)
+
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    client = create_client(connection, retry=config)
+    assert client.stats()["retry"]["maxAttempts"] == 4
+    assert create_client(connection).stats()["retry"]["maxAttempts"] == 1
+
+
+def test_request_timeout():
+    """A call to a node that never answers gives up at the timeout."""
+    import socket
+    import time
+
+    from calimero_client_py import ErrorCode
+
+    server = socket.socket()
+    server.bind(("127.0.0.1", 0))
+    server.listen()
+    port = server.getsockname()[1]
+    connection = create_connection(api_url=f"http://127.0.0.1:{port}")
+    client = create_client(connection, connect_timeout=1.0, request_timeout=30.0)
+    assert client.request_timeout == 30.0
+    assert client.stats()["pool"]["connectTimeoutSecs"] == 1.0
+
+    started = time.monotonic()
+    with client.timeout(0.2):
+        with pytest.raises(RuntimeError) as excinfo:
+            client.get_context("11111111111111111111111111111111")
+    assert time.monotonic() - started < 10
+    assert excinfo.value.code == ErrorCode.TIMEOUT
+    server.close()
+    with pytest.raises(ValueError):
+        create_client(connection, request_timeout=0)
+
+
+def test_delete_context_export_failure_keeps_context(tmp_path):
+    """A failed export raises before anything is deleted."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    context_id = "11111111111111111111111111111111"
+    with pytest.raises(RuntimeError, match="Export before deletion failed"):
+        client.delete_context(context_id, export_to=str(tmp_path))
+    assert list(tmp_path.iterdir()) == []
+
+
+def test_outbox_keeps_unsent_entries_queued():
+    """Entries that cannot reach the node stay queued in order."""
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    client = create_client(connection)
+    context_id = "11111111111111111111111111111111"
+    first = client.enqueue_execute(context_id, "set", '{"k": 1}')
+    second = client.enqueue_execute(context_id, "set", '{"k": 2}')
+    with pytest.raises(ValueError):
+        client.enqueue_execute(context_id, "set", "{not json")
+
+    summary = client.flush_outbox()
+    assert summary["pending"] == 2
+    assert summary["delivered"] == []
+    assert [entry["id"] for entry in client.outbox] == [first, second]
+
+
+def test_frozen_context_refuses_execution():
+    """Frozen contexts are listed as archived and cannot be executed on."""
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    client = create_client(connection)
+    context_id = "11111111111111111111111111111111"
+    assert client.freeze_context(context_id)
+    assert not client.freeze_context(context_id)
+    assert client.frozen_contexts == [context_id]
+    with pytest.raises(RuntimeError, match="frozen"):
+        client.execute_function(context_id, "get", "{}")
+    with pytest.raises(RuntimeError, match="frozen"):
+        client.enqueue_execute(context_id, "set", "{}")
+
+    assert client.unfreeze_context(context_id)
+    assert client.frozen_contexts == []
+
+
+def test_serialize_context_is_configurable():
+    """Contexts opt in and out of client-side write serialization."""
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    client = create_client(connection)
+    context_id = "11111111111111111111111111111111"
+    client.serialize_context(context_id)
+    assert client.serialized_contexts == [context_id]
+    client.serialize_context(context_id, enabled=False)
+    assert client.serialized_contexts == []
+    with pytest.raises(ValueError):
+        client.serialize_context("not-a-context")
+
+
+def test_execute_function_accepts_root_hash_options():
+    """Root hash tracking is opt-in via keywords; failures still raise."""
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    client = create_client(connection)
+    with pytest.raises(RuntimeError):
+        client.execute_function(
+            "11111111111111111111111111111111",
+            "set",
+            "{}",
+            expected_root_hash="abc",
+        )
+
+
+def test_usage_counts_executions():
+    """Executions this client sent show up in the context's usage."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    context_id = "11111111111111111111111111111111"
+    assert client.usage(context_id)["executions"]["total"] == 0
+    with pytest.raises(RuntimeError):
+        client.execute_function(context_id, "set", "{}", "alice")
+
+    report = client.usage(context_id, window=60)
+    assert report["executions"] == {"total": 1, "failed": 1, "perMinute": 1.0}
+    assert report["topMethods"] == [{"method": "set", "count": 1, "failed": 1}]
+    assert report["activeIdentities"][0]["identity"] == "alice"
+    assert report["events"]["total"] == 0
+    with pytest.raises(ValueError):
+        client.usage(context_id, window=0)
+
+
+def test_state_snapshot_roundtrip(tmp_path, monkeypatch):
+    """Local state and outboxes survive a snapshot/restore cycle."""
+    from calimero_client_py import snapshot, restore
+
+    monkeypatch.setenv("CALIMERO_CACHE_DIR", str(tmp_path / "state"))
+    monkeypatch.delenv("MEROBOX_CACHE_DIR", raising=False)
+    token = tmp_path / "state" / "auth_cache" / "node.json"
+    token.parent.mkdir(parents=True)
+    token.write_text("{}")
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    context_id = "11111111111111111111111111111111"
+    client.enqueue_execute(context_id, "set", "{}")
+
+    archive = tmp_path / "backup.gz"
+    written = snapshot(str(archive), clients=[client], passphrase="s3cret")
+    assert written["files"] >= 1 and written["outboxEntries"] == 1
+    token.unlink()
+    with pytest.raises(ValueError):
+        restore(str(archive), passphrase="wrong")
+
+    fresh = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    result = restore(str(archive), clients=[fresh], passphrase="s3cret")
+    assert result["restored"] == [str(token)]  # the rest is still on disk
+    assert token.read_text() == "{}"
+    assert [entry["method"] for entry in fresh.outbox] == ["set"]
+    assert str(token) in restore(str(archive), passphrase="s3cret")["skipped"]
+
+
+def test_context_calls_accept_typed():
+    """The context management calls take `typed` without changing their defaults."""
+    from calimero_client_py import Context
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    context_id = "11111111111111111111111111111111"
+    with pytest.raises(RuntimeError):
+        client.get_context(context_id, typed=True)
+    with pytest.raises(RuntimeError):
+        client.list_contexts(typed=True)
+    assert Context.__name__ == "Context"
+
+
+def test_invitation_payloads():
+    """Invitation payloads survive the string round trip join_context takes."""
+    from calimero_client_py import encode_invitation, decode_invitation
+
+    invitation = {"contextId": "11111111111111111111111111111111", "groupId": "g"}
+    payload = encode_invitation(invitation)
+    assert payload.startswith("calimero-invitation:")
+    assert decode_invitation(payload) == {**invitation, "version": 1}
+    with pytest.raises(ValueError):
+        decode_invitation("calimero-invitation:not-json")
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    with pytest.raises(ValueError):
+        client.join_context("calimero-invitation:not-json")
+    with pytest.raises(ValueError):
+        client.invite("11111111111111111111111111111111", invitee_id="not-a-key")
+
+
+def test_production_client_refuses_dangerous_operations():
+    """Production clients need allow_dangerous=True for foot-gun operations."""
+    from calimero_client_py import DangerousOperationError
+
+    client = create_client(
+        create_connection(api_url="http://127.0.0.1:9"), production=True
+    )
+    assert client.production
+    with pytest.raises(DangerousOperationError, match="allow_dangerous=True"):
+        client.delete_group("some-group-id")
+    with pytest.raises(DangerousOperationError):
+        client.install_dev_application("/tmp/app.wasm")
+    # Allowed calls go out (and fail here for want of a node)
+    with pytest.raises(Exception) as exc_info:
+        client.delete_group("some-group-id", allow_dangerous=True)
+    assert not isinstance(exc_info.value, DangerousOperationError)
+
+
+
+def test_install_application_rejects_missing_bundle(tmp_path):
+    """Local bundles are read client-side, so a missing file fails fast."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    with pytest.raises(ValueError, match="not found"):
+        client.install_application(str(tmp_path / "missing.wasm"))
+    with pytest.raises(ValueError, match="scheme"):
+        client.install_application("ftp://example.com/app.wasm")
+
+
+def test_task_group_cancels_on_failure():
+    """A failing call fails the whole group and names the call."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    group = client.task_group()
+    assert group.execute("11111111111111111111111111111111", "get", "{}") == 0
+    assert group.execute("11111111111111111111111111111111", "set", "{}") == 1
+    assert len(group) == 2
+    with pytest.raises(ValueError):
+        group.execute("not-a-context-id", "get", "{}")
+    with pytest.raises(Exception, match="Task group call"):
+        group.run()
+    assert group.failed_index in (0, 1)
+    assert group.results is None
+    with pytest.raises(RuntimeError, match="already ran"):
+        group.run()
+
+    # A block that raises drops its calls unsent
+    with pytest.raises(KeyError):
+        with client.task_group() as group:
+            group.execute("11111111111111111111111111111111", "get", "{}")
+            raise KeyError("boom")
+    assert group.failed_index is None
+
+
+def test_alias_arguments_are_validated():
+    """Alias kinds are checked, and names that are neither ids nor known
+    aliases fail as invalid context ids."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    with pytest.raises(ValueError, match="alias kind"):
+        client.list_aliases(kind="group")
+    with pytest.raises(ValueError, match="context_id"):
+        client.resolve_alias("alice", kind="identity")
+    with pytest.raises(ValueError, match="Invalid context ID 'staging'"):
+        client.get_context_storage("staging")
+
+
+def test_method_defaults_merge_under_call_arguments():
+    """Registered defaults fill in missing keys; call arguments win."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    client.set_method_defaults("post", {"fee": 1, "options": {"visibility": "public"}})
+    client.enqueue_execute(
+        "11111111111111111111111111111111", "post", '{"fee": 5, "options": {"ttl": 60}}'
+    )
+    assert json.loads(client.outbox[0]["args"]) == {
+        "fee": 5,
+        "options": {"visibility": "public", "ttl": 60},
+    }
+    assert client.method_defaults == [
+        {
+            "applicationId": None,
+            "method": "post",
+            "defaults": {"fee": 1, "options": {"visibility": "public"}},
+        }
+    ]
+    with pytest.raises(ValueError, match="JSON object"):
+        client.enqueue_execute("11111111111111111111111111111111", "post", "[1]")
+    with pytest.raises(ValueError):
+        client.set_method_defaults("post", "[1]")
+    assert client.clear_method_defaults("post") == 1
+    assert client.method_defaults == []
+
+
+
+def test_arg_encodings_convert_call_arguments():
+    """Borsh and base64 encodings turn arguments into byte arrays."""
+    from calimero_client_py import ArgEncoding
+
+    encoding = ArgEncoding("borsh", schema=[["owner", "string"], ["amount", "u64"]])
+    assert encoding.kind == "borsh"
+    assert encoding.encode_args({"owner": "al", "amount": 258}) == (
+        b"\x02\x00\x00\x00al\x02\x01\x00\x00\x00\x00\x00\x00"
+    )
+    with pytest.raises(ValueError, match=r"args\.amount"):
+        encoding.encode_args({"owner": "al", "amount": -1})
+    with pytest.raises(ValueError, match="Invalid Borsh schema"):
+        ArgEncoding("borsh", schema=[["owner", "strng"]])
+    with pytest.raises(ValueError, match="needs a schema"):
+        ArgEncoding("borsh")
+
+    context = "11111111111111111111111111111111"
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    client.set_arg_encoding(context, "base64")
+    client.set_arg_encoding(context, encoding, method="transfer")
+    assert client.arg_encodings == {context: {"*": "base64", "transfer": "borsh"}}
+    client.enqueue_execute(context, "upload", '"AAEC"')
+    client.enqueue_execute(context, "transfer", '{"owner": "a", "amount": 1}')
+    assert json.loads(client.outbox[0]["args"]) == [0, 1, 2]
+    assert json.loads(client.outbox[1]["args"]) == [1, 0, 0, 0, 97, 1, 0, 0, 0, 0, 0, 0, 0]
+    assert client.clear_arg_encoding(context) == 2
+    assert client.arg_encodings == {}
+
+def test_node_status_of_unreachable_node():
+    """Health reports an unreachable node; waiting for it times out."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    health = client.health()
+    assert not health
+    assert health.healthy is False
+    assert health.http_status is None
+    assert health.error
+    assert health.to_dict()["healthy"] is False
+
+    info = client.node_info()
+    assert info.api_url.startswith("http://127.0.0.1:9")
+    assert info.peer_count is None
+    assert set(info.capabilities) == {"wireFormat", "batchMode"}
+
+    with pytest.raises(Exception, match="wait_until_ready timed out"):
+        client.wait_until_ready(timeout=0.2)
+    with pytest.raises(ValueError):
+        client.wait_until_ready(timeout=0)
+
+
+def test_callback_handles_and_weak_methods():
+    """Hooks unsubscribe through their handle; bound methods don't leak."""
+    import gc
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    handle = client.on_maintenance(lambda active, retry_after: None)
+    assert handle.active and handle.hook == "on_maintenance"
+    assert handle.unsubscribe() is True
+    assert handle.unsubscribe() is False
+    with client.on_maintenance(lambda active, retry_after: None) as scoped:
+        assert scoped.active
+    assert not scoped.active
+
+    class Widget:
+        def on_change(self, old, new):
+            pass
+
+    widget = Widget()
+    handle = client.on_login_state_change(widget.on_change)
+    assert handle.active
+    del widget
+    gc.collect()
+    assert not handle.active
+
+    kept = client.on_login_state_change(Widget().on_change, weak=False)
+    gc.collect()
+    assert kept.active
+    client.close()
+    assert not kept.active
+
+
+def test_client_pool_fans_out_per_node():
+    """Pools key clients by node name and report failures per node."""
+    from calimero_client_py import ClientPool, MemoryStorage
+
+    nodes = {"node1": "http://127.0.0.1:9", "node2": "http://127.0.0.1:9"}
+    with ClientPool(nodes, storage=MemoryStorage(), max_concurrency=1) as pool:
+        assert pool.nodes == ["node1", "node2"]
+        assert len(pool) == 2 and "node2" in pool
+        with pytest.raises(KeyError):
+            pool["node3"]
+
+        result = pool.execute_on_all("11111111111111111111111111111111", "get", "{}")
+        assert not result.ok
+        assert result.failed_inputs == ["node1", "node2"]
+        assert [record["node"] for record in result] == ["node1", "node2"]
+
+        names = pool.map(lambda name, client: name.upper(), nodes=["node2"])
+        assert names == {"node2": "NODE2"}
+        with pytest.raises(ZeroDivisionError):
+            pool.map(lambda name, client: 1 / 0)
+        errors = pool.map(lambda name, client: 1 / 0, return_exceptions=True)
+        assert isinstance(errors["node1"], ZeroDivisionError)
+    with pytest.raises(ValueError):
+        ClientPool(nodes, max_concurrency=0)
+
+
+def test_config_names_nodes(tmp_path, monkeypatch):
+    """Nodes saved to the config file are connected to by name."""
+    from calimero_client_py import connect, load_config, MemoryStorage
+
+    path = tmp_path / "config.toml"
+    config = load_config(path)
+    assert len(config) == 0 and config.default_node is None
+    config.set_node("node1", "http://127.0.0.1:9", request_timeout=3.0)
+    config.set_node("open-node", "http://127.0.0.1:9", auth_mode="none")
+    config.set_defaults(connect_timeout=2.0)
+    config.default_node = "node1"
+    with pytest.raises(ValueError):
+        config.set_node("bad", "http://127.0.0.1:9", auth_mode="maybe")
+    config.save()
+
+    config = load_config(path)
+    assert config.nodes == ["node1", "open-node"]
+    assert config.node("node1") == {
+        "url": "http://127.0.0.1:9",
+        "request_timeout": 3.0,
+        "connect_timeout": 2.0,
+    }
+    with pytest.raises(KeyError):
+        config.node("node3")
+
+    monkeypatch.setenv("CALIMERO_NODE_NODE1_URL", "http://127.0.0.1:10")
+    assert config.node("node1")["url"] == "http://127.0.0.1:10"
+    assert "127.0.0.1:10" not in path.read_text()
+
+    client = connect(config=config, storage=MemoryStorage())
+    assert client.get_api_url().startswith("http://127.0.0.1:10")
+    assert connect("open-node", config=config).get_api_url()
+    with pytest.raises(KeyError):
+        connect("node3", config=config)
+
+
+def test_webhook_server_verifies_and_dispatches():
+    """Signed deliveries reach callbacks as typed events; others are refused."""
+    import time
+    import urllib.error
+    import urllib.request
+
+    from calimero_client_py import EventSchemaRegistry, WebhookServer
+
+    schemas = EventSchemaRegistry()
+    schemas.register("app", {"Counted": {"total": "int"}})
+    received = []
+
+    def post(url, body, signature=None):
+        request = urllib.request.Request(url, data=body, method="POST")
+        if signature:
+            request.add_header("X-Calimero-Signature", signature)
+        try:
+            return urllib.request.urlopen(request, timeout=5).status
+        except urllib.error.HTTPError as e:
+            return e.code
+
+    server = WebhookServer("s3cret", schemas=schemas)
+    handle = server.on_event(received.extend, context_id="ctx")
+    with server:
+        assert server.running and server.url.endswith("/webhook")
+        event = {"contextId": "ctx", "kind": "Counted", "data": {"total": 3}}
+        body = json.dumps(event).encode()
+        assert post(server.url, body) == 401
+        assert post(server.url, body, WebhookServer.sign("wrong", body)) == 401
+        assert post(server.url + "x", body, WebhookServer.sign("s3cret", body)) == 404
+        other = json.dumps(dict(event, contextId="other")).encode()
+        assert post(server.url, other, WebhookServer.sign("s3cret", other)) == 204
+        assert post(server.url, body, WebhookServer.sign("s3cret", body)) == 204
+
+        deadline = time.monotonic() + 5
+        while not received and time.monotonic() < deadline:
+            time.sleep(0.05)
+        assert len(received) == 1
+        assert received[0].kind == "Counted" and received[0].total == 3
+        assert server.stats["rejected"] == 3
+    assert not server.running and server.url is None
+    assert handle.unsubscribe()
+
+
+def test_pending_executions_lists_unfinished_calls():
+    """Outbox entries are listed as queued until flushed; none are in flight."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    ctx = "11111111111111111111111111111111"
+    assert client.pending_executions() == []
+    client.enqueue_execute(ctx, "set", '{"key": "a"}')
+    (queued,) = client.pending_executions(ctx)
+    assert queued["state"] == "queued" and queued["method"] == "set"
+    assert queued["ageMs"] >= 0 and queued["serialized"] is False
+    assert client.pending_executions("2" * 44) == []
+    with pytest.raises(RuntimeError):
+        client.execute_function(ctx, "get", "{}")
+    assert [p["state"] for p in client.pending_executions()] == ["queued"]
+
+
+def test_auth_jwks_is_cached_and_revalidated(tmp_path, monkeypatch):
+    """The JWKS is fetched once, then revalidated by ETag on refresh."""
+    import threading
+    from http.server import BaseHTTPRequestHandler, HTTPServer
+
+    from calimero_client_py import clear_auth_metadata_cache
+
+    monkeypatch.setenv("CALIMERO_CACHE_DIR", str(tmp_path))
+    requests = []
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_GET(self):
+            requests.append((self.path, self.headers.get("If-None-Match")))
+            if self.headers.get("If-None-Match") == '"v1"':
+                self.send_response(304)
+                self.end_headers()
+                return
+            body = json.dumps({"keys": [{"kid": "k1"}]}).encode()
+            self.send_response(200)
+            self.send_header("ETag", '"v1"')
+            self.send_header("Content-Type", "application/json")
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+
+        def log_message(self, *args):
+            pass
+
+    server = HTTPServer(("127.0.0.1", 0), Handler)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    try:
+        url = f"http://127.0.0.1:{server.server_port}"
+        client = create_client(create_connection(api_url=url))
+        assert client.auth_jwks() == {"keys": [{"kid": "k1"}]}
+        assert client.auth_jwks() == {"keys": [{"kid": "k1"}]}
+        assert requests == [("/auth/.well-known/jwks.json", None)]
+        assert client.auth_jwks(refresh=True)["keys"][0]["kid"] == "k1"
+        assert requests[1] == ("/auth/.well-known/jwks.json", '"v1"')
+    finally:
+        server.shutdown()
+    assert clear_auth_metadata_cache() == 1
+    assert clear_auth_metadata_cache() == 0
+
+
+def test_install_application_resumes_chunked_upload(tmp_path):
+    """A bundle upload cut off halfway continues from the node's offset."""
+    import threading
+    from http.server import BaseHTTPRequestHandler, HTTPServer
+    from urllib.parse import parse_qs, urlparse
+
+    from calimero_client_py import hash_blob
+
+    bundle = tmp_path / "app.wasm"
+    bundle.write_bytes(bytes(range(256)) * 40)
+    blob_id = hash_blob(str(bundle))["blobId"]
+    held = bytearray()
+    chunks = []
+    fail_once = [True]
+
+    class Handler(BaseHTTPRequestHandler):
+        def reply(self, status, payload):
+            body = json.dumps(payload).encode()
+            self.send_response(status)
+            self.send_header("Content-Type", "application/json")
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+
+        def do_GET(self):
+            if self.path == f"/admin-api/blobs/uploads/{blob_id}":
+                return self.reply(200, {"offset": len(held)})
+            self.reply(404, {})
+
+        def do_PUT(self):
+            url = urlparse(self.path)
+            query = parse_qs(url.query)
+            offset, total = int(query["offset"][0]), int(query["total"][0])
+            data = self.rfile.read(int(self.headers["Content-Length"]))
+            if offset > 0 and fail_once[0]:
+                fail_once[0] = False
+                return self.reply(400, {"error": "link dropped"})
+            chunks.append(offset)
+            held[offset:] = data
+            if len(held) == total:
+                return self.reply(200, {"data": {"blobId": blob_id}})
+            self.reply(200, {"offset": len(held)})
+
+        def do_POST(self):
+            self.reply(404, {})
+
+        def log_message(self, *args):
+            pass
+
+    server = HTTPServer(("127.0.0.1", 0), Handler)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    try:
+        url = f"http://127.0.0.1:{server.server_port}"
+        client = create_client(create_connection(api_url=url), production=True)
+        progress = []
+        with pytest.raises(ClientError, match="status 400"):
+            client.install_application(str(bundle), chunk_size=4096)
+        assert chunks == [0] and len(held) == 4096
+        with pytest.raises(ClientError):
+            client.install_application(
+                str(bundle),
+                chunk_size=4096,
+                on_progress=lambda sent, total: progress.append((sent, total)),
+            )
+        assert chunks == [0, 4096, 8192] and bytes(held) == bundle.read_bytes()
+        assert progress[0] == (4096, 10240) and progress[-1] == (10240, 10240)
+    finally:
+        server.shutdown()
+
+
+def test_signer_signs_offline_and_submission_verifies(tmp_path):
+    """Envelopes are signed without a node; tampered or reused ones fail."""
+    from calimero_client_py import Signer
+
+    key_file = tmp_path / "key"
+    key_file.write_text(bytes(range(32)).hex() + "\n")
+    signer = Signer.from_file(str(key_file))
+    assert signer.public_key == Signer.from_bytes(bytes(range(32))).public_key
+
+    ctx = "1" * 32
+    envelope = signer.sign_execution(ctx, "set", {"key": "a"})
+    assert envelope["publicKey"] == signer.public_key
+    assert json.loads(envelope["argsJson"]) == {"key": "a"}
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    with pytest.raises(ValueError):
+        client.submit_signed(dict(envelope, method="delete"))
+    with pytest.raises(RuntimeError):
+        client.submit_signed(json.dumps(envelope))
+    with pytest.raises(ValueError):
+        client.submit_signed(envelope)
+    with pytest.raises(ValueError):
+        Signer.from_bytes(b"short")
+
+
+def test_quota_status_is_none_until_reported():
+    """Nodes that send no rate-limit headers have no quota reading."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    assert client.quota_status() is None
+
+
+def test_query_builder_validates_fields():
+    """Queries compile to parameters without a request; typos raise."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    query = client.contexts().filter(application_id="app").sort("-id").limit(50)
+    assert query.params() == {
+        "application_id": "app",
+        "sort": "id",
+        "order": "desc",
+        "limit": "50",
+    }
+    assert client.contexts().params() == {}
+    with pytest.raises(ValueError):
+        client.contexts().filter(aplication="app")
+    with pytest.raises(ValueError):
+        client.applications().sort("created_at")
+    with pytest.raises(ValueError):
+        client.applications().fetch(typed=True)
+
+
+def test_paginators_validate_before_fetching():
+    """Page sizes, cursors and typed pages are checked without a request."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    pages = client.contexts().limit(10).pages(page_size=25, cursor="5")
+    assert pages.page_size == 25
+    assert pages.cursor == "5"
+    assert client.blobs().params() == {}
+    with pytest.raises(ValueError):
+        client.contexts().pages(page_size=0)
+    with pytest.raises(ValueError):
+        client.contexts().iter(cursor="next")
+    with pytest.raises(ValueError):
+        client.blobs().pages(typed=True)
+
+
+def test_tracing_records_reach_python_logging():
+    """Call spans arrive on the calimero_client logger, optionally as JSON."""
+    import logging
+
+    from calimero_client_py import set_log_level
+
+    records = []
+    handler = logging.Handler()
+    handler.emit = records.append
+    logger = logging.getLogger("calimero_client")
+    logger.addHandler(handler)
+    previous = set_log_level("debug", json=True)
+    try:
+        client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+        with pytest.raises(ClientError):
+            client.get_peers_count()
+    finally:
+        set_log_level(previous, json=False)
+        logger.removeHandler(handler)
+    finished = [json.loads(r.getMessage()) for r in records]
+    assert any(record["message"] == "call finished" for record in finished)
+    with pytest.raises(ValueError):
+        set_log_level("verbose")
+
+
+def test_clone_context_checks_members_before_requests():
+    """Invalid member keys fail before the node is contacted."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    with pytest.raises(ValueError):
+        client.clone_context("ctx", new_members=["not-a-key"])
+
+
+def test_request_hooks_see_and_edit_headers():
+    """Request hooks add headers; response hooks see the reply."""
+    from http.server import BaseHTTPRequestHandler, HTTPServer
+    import threading
+
+    seen = {}
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_POST(self):
+            seen["correlation"] = self.headers.get("X-Correlation-Id")
+            body = b'{"jsonrpc": "2.0", "id": "1", "result": {"output": 3}}'
+            self.send_response(200)
+            self.send_header("Content-Type", "application/json")
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+
+        def log_message(self, *args):
+            pass
+
+    server = HTTPServer(("127.0.0.1", 0), Handler)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    responses = []
+
+    def tag(request):
+        request["headers"]["X-Correlation-Id"] = "abc-123"
+
+    try:
+        url = f"http://127.0.0.1:{server.server_port}"
+        client = create_client(create_connection(api_url=url))
+        with client.add_request_hook(tag):
+            client.add_response_hook(responses.append)
+            context_id = "11111111111111111111111111111111"
+            client.execute_function(context_id, "get", "{}")
+    finally:
+        server.shutdown()
+    assert seen["correlation"] == "abc-123"
+    assert responses[0]["status"] == 200
+    with pytest.raises(TypeError):
+        client.add_request_hook("not callable")
+
+
+def test_metrics_count_requests_and_errors():
+    """Failed calls show up as requests and errors, in both formats."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    assert client.metrics()["requests"] == 0
+    with pytest.raises(ClientError):
+        client.get_peers_count()
+    metrics = client.metrics()
+    assert metrics["requests"] == 1
+    assert metrics["errors"] == 1
+    assert metrics["latencyMs"]["p50"] is not None
+    text = client.metrics(prometheus=True)
+    assert "# TYPE calimero_client_requests_total counter" in text
+    assert "calimero_client_errors_total{" in text
+
+
+def test_features_report_the_build():
+    """features() lists every optional feature; missing ones raise cleanly."""
+    from calimero_client_py import FeatureNotBuiltError, features
+
+    built = features()
+    assert set(built) == {"kafka", "nats", "keyring", "http3"}
+    assert issubclass(FeatureNotBuiltError, NotImplementedError)
+    if not built["http3"]:
+        connection = create_connection(api_url="http://127.0.0.1:9")
+        with pytest.raises(FeatureNotBuiltError, match="http3"):
+            create_client(connection, http3=True)
+
+
+def test_rejected_token_is_refreshed_once_for_concurrent_calls():
+    """Calls holding a token the node rejects share one refresh, then replay."""
+    from concurrent.futures import ThreadPoolExecutor
+    from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+    import json
+    import threading
+
+    from calimero_client_py import MemoryStorage
+
+    refreshes = []
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_POST(self):
+            self.rfile.read(int(self.headers.get("Content-Length") or 0))
+            if self.path.startswith("/auth/refresh"):
+                refreshes.append(self.path)
+                self._reply(200, {"access_token": "new", "refresh_token": "r2"})
+            elif self.headers.get("Authorization") != "Bearer new":
+                self._reply(401, {"error": "token expired"})
+            else:
+                self._reply(200, {"jsonrpc": "2.0", "id": "1", "result": {"output": 1}})
+
+        def _reply(self, status, body):
+            data = json.dumps(body).encode()
+            self.send_response(status)
+            self.send_header("Content-Type", "application/json")
+            self.send_header("Content-Length", str(len(data)))
+            self.end_headers()
+            self.wfile.write(data)
+
+        def log_message(self, *args):
+            pass
+
+    server = ThreadingHTTPServer(("127.0.0.1", 0), Handler)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    storage = MemoryStorage()
+    storage.save_tokens("node1", {"access_token": "old", "refresh_token": "r1"})
+    try:
+        url = f"http://127.0.0.1:{server.server_port}"
+        connection = create_connection(api_url=url, node_name="node1", storage=storage)
+        client = create_client(connection, wire="json", max_concurrency=16)
+        # Hooks route JSON-RPC through the binding's own requests.
+        client.add_request_hook(lambda request: None)
+        context_id = "11111111111111111111111111111111"
+        with ThreadPoolExecutor(8) as pool:
+            calls = [
+                pool.submit(client.execute_function, context_id, "get", "{}")
+                for _ in range(16)
+            ]
+            results = [call.result() for call in calls]
+    finally:
+        server.shutdown()
+    assert all(result["result"]["output"] == 1 for result in results)
+    assert len(refreshes) == 1
+    assert storage.load_tokens("node1")["access_token"] == "new"
+
+
+
+def test_background_token_refresh_reports_outcomes():
+    """The refresher renews expiring tokens of each node and reports them."""
+    from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+    import threading
+    import time
+
+    from calimero_client_py import MemoryStorage
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_POST(self):
+            body = json.loads(self.rfile.read(int(self.headers.get("Content-Length") or 0)))
+            if body.get("refresh_token") == "revoked":
+                self._reply(403, {"error": "revoked"})
+            else:
+                self._reply(200, {"access_token": "new", "refresh_token": "r2"})
+
+        def _reply(self, status, body):
+            data = json.dumps(body).encode()
+            self.send_response(status)
+            self.send_header("Content-Type", "application/json")
+            self.send_header("Content-Length", str(len(data)))
+            self.end_headers()
+            self.wfile.write(data)
+
+        def log_message(self, *args):
+            pass
+
+    server = ThreadingHTTPServer(("127.0.0.1", 0), Handler)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    expiring = int(time.time()) + 60
+    storage = MemoryStorage()
+    storage.save_tokens(
+        "node1", {"access_token": "old", "refresh_token": "r1", "expires_at": expiring}
+    )
+    storage.save_tokens(
+        "node2", {"access_token": "old", "refresh_token": "revoked", "expires_at": expiring}
+    )
+    refreshed, failed = [], []
+    try:
+        url = f"http://127.0.0.1:{server.server_port}"
+        connection = create_connection(api_url=url, node_name="node1", storage=storage)
+        client = create_client(connection)
+        with pytest.raises(ValueError, match="interval"):
+            client.start_token_refresh(interval=0)
+        with client.start_token_refresh(
+            interval=0.1,
+            on_refresh=lambda node, expires_at: refreshed.append(node),
+            on_error=lambda node, message: failed.append((node, message)),
+            nodes={"node2": url},
+        ) as refresher:
+            assert refresher.running and refresher.nodes == ["node1", "node2"]
+            deadline = time.time() + 5
+            while not (refreshed and failed) and time.time() < deadline:
+                time.sleep(0.05)
+            stats = refresher.stats
+        assert not refresher.running
+    finally:
+        server.shutdown()
+    assert refreshed == ["node1"]
+    assert failed and failed[0][0] == "node2" and "403" in failed[0][1]
+    assert storage.load_tokens("node1")["access_token"] == "new"
+    assert stats["refreshes"] == 1 and stats["nodes"]["node2"]["lastError"]
+
+def test_clients_pickle_as_their_configuration():
+    """Pickles carry settings, not connections; in-memory tokens refuse."""
+    import pickle
+
+    from calimero_client_py import MemoryStorage, RetryConfig
+
+    retry = RetryConfig(max_attempts=5, retry_on=["rate_limited"])
+    connection = create_connection(api_url="http://127.0.0.1:9", node_name="node1")
+    client = create_client(connection, max_concurrency=4, retry=retry)
+    copy = pickle.loads(pickle.dumps(client))
+    assert copy is not client
+    assert copy.get_api_url() == client.get_api_url()
+    assert copy.concurrency_stats()["maxLimit"] == 4
+    assert repr(pickle.loads(pickle.dumps(retry))) == repr(retry)
+
+    memory = create_connection(api_url="http://127.0.0.1:9", storage=MemoryStorage())
+    with pytest.raises(TypeError, match="create the connection in the worker"):
+        pickle.dumps(create_client(memory))
+
+
+@pytest.mark.skipif(os.name != "posix", reason="needs SIGINT delivery")
+def test_ctrl_c_interrupts_a_blocking_wait():
+    """SIGINT raises KeyboardInterrupt at once instead of after the wait."""
+    import signal
+    import threading
+    import time
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    timer = threading.Timer(0.3, os.kill, (os.getpid(), signal.SIGINT))
+    started = time.monotonic()
+    timer.start()
+    with pytest.raises(KeyboardInterrupt):
+        client.wait_until_ready(timeout=30.0)
+    timer.join()
+    assert time.monotonic() - started < 5
+
+
+def test_membership_proofs_are_checked_offline():
+    """Malformed, expired or unsigned proofs raise ValueError."""
+    from calimero_client_py import verify_membership_proof
+
+    proof = {
+        "version": 1,
+        "contextId": "11111111111111111111111111111111",
+        "publicKey": "11111111111111111111111111111111",
+        "rootHash": "root",
+        "audience": "partner",
+        "nonce": "00" * 16,
+        "issuedAt": 0,
+        "expiresAt": 4102444800,
+        "signature": "1111",
+    }
+    with pytest.raises(ValueError, match="signature"):
+        verify_membership_proof(proof)
+    with pytest.raises(ValueError, match="not 'auditor'"):
+        verify_membership_proof(proof, audience="auditor")
+    with pytest.raises(ValueError, match="expired"):
+        verify_membership_proof(json.dumps({**proof, "expiresAt": 1}))
+    with pytest.raises(ValueError):
+        verify_membership_proof("not json")
+
+
+def test_results_convert_to_dataclasses():
+    """Outputs become dataclasses by their annotations; mismatches name a path."""
+    from dataclasses import dataclass, field
+    from typing import List, Optional
+
+    from calimero_client_py import ResultTypeError, convert_result
+
+    @dataclass
+    class Item:
+        name: str
+        price: float
+        tags: List[str] = field(default_factory=list)
+
+    @dataclass
+    class Cart:
+        owner_id: str
+        items: List[Item]
+        note: Optional[str] = None
+
+    response = {
+        "result": {
+            "output": {"ownerId": "alice", "items": [{"name": "tea", "price": 3}]}
+        }
+    }
+    cart = convert_result(response, Cart)
+    assert cart == Cart("alice", [Item("tea", 3.0)])
+    assert convert_result([1, 2], List[int]) == [1, 2]
+
+    bad = {"owner_id": "a", "items": [{"name": "x", "price": "?"}]}
+    with pytest.raises(ResultTypeError, match=r"output\.items\[0\]\.price"):
+        convert_result(bad, Cart)
+    with pytest.raises(ResultTypeError, match="missing field 'items'"):
+        convert_result({"owner_id": "a"}, Cart)
+    with pytest.raises(ResultTypeError, match="unknown field 'extra'"):
+        convert_result({"owner_id": "a", "items": [], "extra": 1}, Cart)
+
+
+def test_proposal_methods_validate_ids_before_calling():
+    """Sync status and proposal calls check their ids locally first."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    for name in ("get_sync_status", "list_proposals", "approve_proposal"):
+        assert hasattr(client, name)
+    with pytest.raises(ValueError):
+        client.get_sync_status("not-a-context-id")
+    with pytest.raises(ValueError):
+        client.list_proposals("not-a-context-id")
+    with pytest.raises(ValueError):
+        client.approve_proposal("not-a-context-id", "1" * 32, "1" * 32)
+
+
+def test_subscription_filters_are_parsed_up_front():
+    """Filters are validated when subscribing and kept on the subscription."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    context_id = "33333333333333333333333333333333"
+    with pytest.raises(ValueError, match="unknown field"):
+        client.subscribe(context_id, filter="color:red")
+    subscription = client.subscribe(
+        context_id, filter='type:StateMutation key:"votes/" -method:ping'
+    )
+    assert subscription.filter == 'type:StateMutation key:"votes/" -method:ping'
+    assert client.subscribe(context_id).filter is None
+    assert client.subscription_stats["filteredEvents"] == 0
+    subscription.close()
+
+
+def test_replaying_subscription_tracks_offsets():
+    """replay=True subscriptions start without a committed offset."""
+    import asyncio
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    live = client.subscribe("11111111111111111111111111111111")
+    assert not live.replay and live.offset is None
+    subscription = client.subscribe("22222222222222222222222222222222", replay=True)
+    assert subscription.replay
+    assert subscription.offset is None
+    subscription.ack()
+    assert client.subscription_stats["replayedDuplicates"] == 0
+
+    async def drain():
+        subscription.close()
+        return [event async for event in subscription]
+
+    assert asyncio.run(drain()) == []
+    assert subscription.offset is None
+
+
+def test_abi_method_checks_arguments_before_calling():
+    """Client.method() returns an ABI-checked callable; without a node the
+    context lookup fails before any argument is checked."""
+    from calimero_client_py import AbiArgumentError, AbiMethod
+
+    assert issubclass(AbiArgumentError, ValueError)
+    for name in ("validate", "signature", "name", "context_id"):
+        assert hasattr(AbiMethod, name)
+
+    client = create_client(create_connection("http://127.0.0.1:9"))
+    assert hasattr(client, "method")
+    with pytest.raises(ValueError):
+        client.method("not-a-context-id", "transfer")
+
+
+def test_node_address_forms(tmp_path, monkeypatch):
+    """Node addresses are normalized, validated, and resolved from config."""
+    from calimero_client_py import NodeAddress
+
+    config = tmp_path / "config.toml"
+    config.write_text('[nodes.staging]\nurl = "https://staging.example.com/api"\n')
+    monkeypatch.setenv("CALIMERO_CLIENT_CONFIG", str(config))
+
+    assert NodeAddress("localhost:2428").url == "http://localhost:2428/"
+    staging = NodeAddress("calimero://staging")
+    assert staging.url == "https://staging.example.com/api/"
+    assert (staging.node_name, staging.port) == ("staging", 443)
+    connection = create_connection(staging)
+    assert connection.node_name == "staging"
+    assert connection.api_url == "https://staging.example.com/api/"
+    for bad in ("localhost", "http://user:pw@node:2428", "calimero://prod"):
+        with pytest.raises(ValueError):
+            create_connection(bad)
+
+
+def test_subscription_blocking_iteration():
+    """Subscriptions are read without an event loop, with a timeout."""
+    import time
+
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    subscription = client.subscribe("11111111111111111111111111111111")
+    started = time.monotonic()
+    assert subscription.get(timeout=0.2) is None
+    assert time.monotonic() - started >= 0.2
+    with pytest.raises(ValueError):
+        subscription.get(timeout=0)
+    subscription.close()
+    assert list(subscription) == []
+    callback = client.subscribe("22222222222222222222222222222222", callback=print)
+    with pytest.raises(TypeError):
+        callback.get(timeout=0.1)
+    callback.close()
+
+
+def test_execute_many_fails_per_call():
+    """execute_many records each call's failure and takes its failed inputs."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    context_id = "11111111111111111111111111111111"
+    result = client.execute_many(
+        context_id,
+        [("get", "not json"), {"method": "get", "args": {"key": "a"}}],
+        concurrency=2,
+    )
+    assert result.operation == "execute_many"
+    assert len(result) == 2 and not result.ok
+    assert "Invalid JSON args" in result[0]["error"]
+    assert result[1]["error"] and result[1]["contextId"] == context_id
+    retried = client.execute_many(context_id, result.failed_inputs[:1])
+    assert retried[0]["method"] == "get"
+    with pytest.raises(ValueError):
+        client.execute_many("22222222222222222222222222222222", result.failed_inputs)
+    with pytest.raises(ValueError):
+        client.execute_many(context_id, [], concurrency=0)
+
+
+
+def test_query_contexts_maps_each_context_to_its_outcome():
+    """Contexts answer, fail or run past the deadline independently."""
+    from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+    import threading
+    import time
+
+    fast, slow = "11111111111111111111111111111111", "22222222222222222222222222222222"
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_POST(self):
+            body = self.rfile.read(int(self.headers.get("Content-Length") or 0))
+            if slow.encode() in body:
+                time.sleep(2)
+            reply = {"jsonrpc": "2.0", "id": json.loads(body).get("id"), "result": {"output": 7}}
+            data = json.dumps(reply).encode()
+            self.send_response(200)
+            self.send_header("Content-Type", "application/json")
+            self.send_header("Content-Length", str(len(data)))
+            self.end_headers()
+            self.wfile.write(data)
+
+        def log_message(self, *args):
+            pass
+
+    server = ThreadingHTTPServer(("127.0.0.1", 0), Handler)
+    server.daemon_threads = True
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    try:
+        url = f"http://127.0.0.1:{server.server_port}"
+        client = create_client(create_connection(api_url=url), wire="json")
+        with pytest.raises(ValueError):
+            client.query_contexts([fast], "get", concurrency=0)
+        with pytest.raises(ValueError):
+            client.query_contexts([fast], "get", deadline=0)
+        started = time.time()
+        results = client.query_contexts(
+            [fast, "not-a-context", slow, fast], "get", '{"key": "a"}', deadline=0.5
+        )
+        assert time.time() - started < 1.5
+    finally:
+        server.shutdown()
+    assert list(results) == [fast, "not-a-context", slow]
+    assert results[fast]["error"] is None
+    assert results[fast]["result"]["result"] == {"output": 7}
+    assert results["not-a-context"]["result"] is None and results["not-a-context"]["error"]
+    assert "timed out" in results[slow]["error"]
+
+
+def test_read_blob_empty_range_is_empty():
+    """A zero-length range returns empty bytes without contacting the node."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    assert client.read_blob("11111111111111111111111111111111", offset=5, length=0) == b""
+
+
+def test_read_blob_rejects_overflowing_range():
+    """A range past the largest offset raises instead of wrapping."""
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    with pytest.raises(ValueError, match="largest"):
+        client.read_blob("11111111111111111111111111111111", offset=2**64 - 1, length=2)
+
+
+def test_read_cache_options_and_invalidate():
+    """The read cache is off by default and configured per client."""
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    assert create_client(connection).read_cache_stats["capacity"] == 0
+    client = create_client(connection, read_cache_size=8, read_cache_ttl=5.0)
+    stats = client.read_cache_stats
+    assert (stats["capacity"], stats["ttl"], stats["entries"]) == (8, 5.0, 0)
+    assert client.invalidate() == 0
+    assert client.invalidate("11111111111111111111111111111111", "get") == 0
+    with pytest.raises(ValueError):
+        create_client(connection, read_cache_size=8, read_cache_ttl=0)
+    with pytest.raises(Exception):
+        client.view("11111111111111111111111111111111", "get", '{"key": "a"}')
+    assert client.read_cache_stats["entries"] == 0
+
+
+def test_execute_dry_run_returns_request():
+    """A dry run builds the execute request without contacting the node."""
+    from calimero_client_py import RequestTooLargeError
+
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    client = create_client(connection, wire="json", max_request_bytes=4096)
+    context_id = "11111111111111111111111111111111"
+    preview = client.execute_function(context_id, "set", '{"key": "a"}', dry_run=True)
+    assert preview["method"] == "POST"
+    assert preview["url"] == "http://127.0.0.1:9/jsonrpc"
+    assert preview["headers"]["content-type"] == "application/json"
+    assert preview["wire"] == "json"
+    assert preview["body"]["jsonrpc"] == "2.0"
+    assert '"set"' in json.dumps(preview["body"])
+    assert client.pending_executions() == []
+    with pytest.raises(Exception):
+        client.execute_function(context_id, "set", "not json", dry_run=True)
+    with pytest.raises(RequestTooLargeError):
+        client.execute_function(
+            context_id, "set", '{"key": "%s"}' % ("a" * 5000), dry_run=True
+        )
+
+
+def test_watch_and_deploy_reports_failures(tmp_path):
+    """Deploys run on a watcher thread; failures are reported, not raised."""
+    import time
+
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    client = create_client(connection)
+    context_id = "11111111111111111111111111111111"
+    with pytest.raises(FileNotFoundError):
+        client.watch_and_deploy(str(tmp_path / "missing.wasm"), context_id)
+    with pytest.raises(ValueError):
+        client.watch_and_deploy(str(tmp_path), context_id, debounce=0)
+
+    wasm = tmp_path / "app.wasm"
+    wasm.write_bytes(b"\0asm")
+    records = []
+    with client.watch_and_deploy(
+        str(wasm), context_id, debounce=0.05, on_deploy=records.append
+    ) as deployer:
+        assert deployer.running
+        deadline = time.monotonic() + 30
+        while not records and time.monotonic() < deadline:
+            time.sleep(0.05)
+    assert not deployer.running
+    assert deployer.failures == 1 and deployer.deploys == 0
+    assert records[0]["error"] and records[0]["contextId"] == context_id
+    assert deployer.last_deploy == records[0]
+
+
+def test_context_session_binds_context():
+    """A session resolves its context once and fills it into every call."""
+    from calimero_client_py import ContextSession
+
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    client = create_client(connection, wire="json")
+    context_id = "11111111111111111111111111111111"
+    session = client.session(context_id)
+    assert isinstance(session, ContextSession)
+    assert session.context_id == context_id
+    assert session.executor_public_key is None
+    preview = session.call("set", {"key": "a"}, dry_run=True)
+    assert preview == client.execute_function(
+        context_id, "set", '{"key":"a"}', dry_run=True
+    )
+    assert session.invalidate() == 0
+    with pytest.raises(Exception):
+        session.view("get")
+    with pytest.raises(Exception):
+        client.session("no-such-alias")
+
+
+def test_verify_cache_quarantines_damaged_tokens(tmp_path):
+    """Damaged token files are reported, then moved aside on request."""
+    from calimero_client_py import TokenCache, get_token_cache_path, verify_cache
+
+    cache = TokenCache(cache_dir=str(tmp_path))
+    assert verify_cache(cache_dir=str(tmp_path)) == []
+    name = os.path.basename(get_token_cache_path("broken-node"))
+    (tmp_path / name).write_text('{"access_token": ')
+    (damaged,) = cache.verify()
+    assert damaged["node"] == "broken-node" and damaged["error"]
+    assert damaged["quarantinedTo"] is None
+    (damaged,) = verify_cache(cache_dir=str(tmp_path), quarantine=True)
+    assert damaged["quarantinedTo"].startswith(name + ".corrupt-")
+    assert not (tmp_path / name).exists()
+    assert cache.verify() == []
+
+
+def test_corrupt_token_file_warns_with_quarantine_path(tmp_path):
+    """Loading a damaged token file warns where it was moved to."""
+    from calimero_client_py import CorruptTokenCacheWarning, get_token_cache_path
+
+    name = os.path.basename(get_token_cache_path("broken-node"))
+    (tmp_path / name).write_text('{"access_token": ')
+    with pytest.warns(CorruptTokenCacheWarning) as caught:
+        create_client(
+            create_connection(
+                api_url="http://127.0.0.1:9",
+                node_name="broken-node",
+                cache_dir=str(tmp_path),
+            )
+        )
+    message = str(caught[0].message)
+    assert str(tmp_path / name) in message
+    (moved,) = [p for p in tmp_path.iterdir() if ".corrupt-" in p.name]
+    assert str(moved) in message
+    assert not (tmp_path / name).exists()
+
+
+def test_rate_limit_paces_requests():
+    """Requests beyond the burst wait for the rate limit's tokens."""
+    import time
+
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    with pytest.raises(ValueError):
+        create_client(connection, rate_limit=0)
+    with pytest.raises(ValueError):
+        create_client(connection, rate_burst=2)
+    client = create_client(connection, rate_limit=5.0, rate_burst=1)
+    started = time.monotonic()
+    for _ in range(3):
+        with pytest.raises(Exception):
+            client.get_peers_count()
+    assert time.monotonic() - started >= 0.35
+    stats = client.rate_limit_stats
+    assert stats["rate"] == 5.0 and stats["burst"] == 1
+    assert stats["throttled"] >= 2
+    assert client.stats()["rateLimit"]["rate"] == 5.0
+    assert create_client(connection).rate_limit_stats["rate"] is None
+
+
+def test_transcript_replays_without_network(tmp_path):
+    """A replaying client answers executions from the transcript in order."""
+    import json
+
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    context_id = "11111111111111111111111111111111"
+    recorded = tmp_path / "recorded.jsonl"
+    client = create_client(connection, wire="json", record=str(recorded))
+    with pytest.raises(Exception):
+        client.execute_function(context_id, "get", "{}")
+    assert client.transcript_stats["mode"] == "record"
+    assert recorded.exists()
+
+    response = {"jsonrpc": "2.0", "id": 1, "result": {"output": 42}}
+    exchange = {
+        "seq": 1,
+        "method": "POST",
+        "url": "/jsonrpc",
+        "status": 200,
+        "headers": {"content-type": "application/json"},
+        "body": {"text": json.dumps(response)},
+    }
+    transcript = tmp_path / "replay.jsonl"
+    transcript.write_text(json.dumps(exchange) + "\n")
+    client = create_client(connection, wire="json", replay=str(transcript))
+    assert client.transcript_stats["remaining"] == 1
+    result = client.execute_function(context_id, "get", "{}")
+    assert result["result"]["output"] == 42
+    with pytest.raises(Exception, match=r"no \(more\) responses"):
+        client.execute_function(context_id, "get", "{}")
+    assert client.transcript_stats["exchanges"] == 1
+    with pytest.raises(ValueError):
+        create_client(connection, record=str(recorded), replay=str(transcript))
+
+
+def test_hash_blob_matches_node_ids(tmp_path):
+    """Blob ids are the digest of the 1 MiB chunks' SHA-256 digests."""
+    import hashlib
+
+    from calimero_client_py import hash_blob, verify_blob
+
+    def b58decode(text):
+        alphabet = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz"
+        number = 0
+        for char in text:
+            number = number * 58 + alphabet.index(char)
+        return number.to_bytes(32, "big")
+
+    data = bytes(range(256)) * 8193
+    path = tmp_path / "blob.bin"
+    path.write_bytes(data)
+    chunks = hashlib.sha256()
+    for start in range(0, len(data), 1 << 20):
+        chunks.update(hashlib.sha256(data[start : start + (1 << 20)]).digest())
+    digest = hash_blob(str(path))
+    assert digest["size"] == len(data)
+    assert b58decode(digest["blobId"]) == chunks.digest()
+    assert b58decode(digest["hash"]) == hashlib.sha256(data).digest()
+    assert verify_blob(str(path), digest["blobId"])
+    path.write_bytes(data[:-1])
+    assert not verify_blob(str(path), digest["blobId"])
+    with pytest.raises(ValueError):
+        verify_blob(str(path), "not-a-blob-id")
+
+
+def test_capability_arguments_are_checked_before_any_request():
+    """Unknown capabilities are refused before the node is contacted."""
+    from calimero_client_py import Capability, create_client, create_connection
+
+    assert Capability.INVITE_MEMBERS.bit == 2
+    assert str(Capability.JOIN_OPEN_CONTEXTS) == "join_open_contexts"
+    client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    context = "11111111111111111111111111111111"
+    with pytest.raises(ValueError):
+        client.grant(context, "member", "admin")
+    with pytest.raises(ValueError):
+        client.revoke(context, "member", 0)
+    with pytest.raises(TypeError):
+        client.grant(context, "member", True)
+
+
+def test_discover_nodes_finds_healthy_local_nodes(tmp_path, monkeypatch):
+    """Scanned ports and merobox state entries are kept only when healthy."""
+    import threading
+    from http.server import BaseHTTPRequestHandler, HTTPServer
+
+    from calimero_client_py import discover_nodes
+
+    monkeypatch.delenv("MEROBOX_STATE_FILE", raising=False)
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_GET(self):
+            body = json.dumps({"data": {"status": "alive"}}).encode()
+            self.send_response(200 if self.path == "/admin-api/health" else 404)
+            self.send_header("Server", "merod/0.8.0")
+            self.send_header("Content-Type", "application/json")
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+
+        def log_message(self, *args):
+            pass
+
+    server = HTTPServer(("127.0.0.1", 0), Handler)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    try:
+        port = server.server_port
+        nodes = discover_nodes(ports=[9, port])
+        assert [n["url"] for n in nodes] == [f"http://127.0.0.1:{port}/"]
+        assert nodes[0]["name"] == f"127.0.0.1-{port}"
+        assert nodes[0]["source"] == "port"
+
+        state = tmp_path / "state.json"
+        state.write_text(json.dumps({"nodes": {"node1": {"rpc_port": port}}}))
+        nodes = discover_nodes(ports=[], state_file=str(state))
+        assert [(n["name"], n["source"]) for n in nodes] == [("node1", "merobox")]
+    finally:
+        server.shutdown()
+    assert discover_nodes(ports=(9, 9)) == []
+    with pytest.raises(TypeError):
+        discover_nodes(ports="2428")
+
+
+def test_refresh_margin_is_configurable():
+    """Proactive token refresh can be tuned or disabled per client."""
+    connection = create_connection(api_url="http://127.0.0.1:9", node_name="n")
+    assert create_client(connection, refresh_margin=300) is not None
+    assert create_client(connection, refresh_margin=None) is not None
+    with pytest.raises(OverflowError):
+        create_client(connection, refresh_margin=-1)
+
+
+def test_maintenance_state_and_callbacks():
+    """Clients start outside maintenance and reject non-callable hooks."""
+    connection = create_connection(api_url="http://127.0.0.1:9")
+    client = create_client(connection, maintenance_wait=None)
+    assert client.in_maintenance is False
+    assert client.stats()["maintenance"]["active"] is False
+    with pytest.raises(TypeError):
+        client.on_maintenance("not callable")
+    client.on_maintenance(lambda active, retry_after: None)
+    client.clear_maintenance_callbacks()
+
+
+def test_login_state_callbacks():
+    """Login state starts logged out and rejects non-callable hooks."""
+    from calimero_client_py import LoginState
+
+    connection = create_connection(
+        api_url="http://localhost:2528", node_name="login-state-test-node"
+    )
+    client = create_client(connection)
+    assert client.login_state == LoginState.LOGGED_OUT
+    assert LoginState.LOGGED_OUT.value == "logged_out"
+
+    client.on_login_state_change(lambda old, new: None)
+    with pytest.raises(TypeError):
+        client.on_login_state_change("not callable")
+    client.clear_login_state_callbacks()
+
+
+def test_login_and_logout_need_a_node_name(tmp_path):
+    """Tokens are stored per node name, so both refuse unnamed connections."""
+    from calimero_client_py import LoginState
+
+    unnamed = create_client(create_connection(api_url="http://127.0.0.1:9"))
+    with pytest.raises(ValueError):
+        unnamed.login(username="ci", password="secret", headless=True)
+    with pytest.raises(ValueError):
+        unnamed.logout()
+
+    connection = create_connection(
+        api_url="http://127.0.0.1:9",
+        node_name="logout-test-node",
+        cache_dir=str(tmp_path),
+    )
+    client = create_client(connection)
+    assert client.logout() is False
+    assert client.login_state == LoginState.LOGGED_OUT
 
 
 def test_auth_mode():
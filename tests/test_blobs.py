@@ -0,0 +1,243 @@
+#!/usr/bin/env python3
+"""
+Tests for the blob transfer bindings.
+
+These pin argument validation that happens before any network round trip,
+so they run without a live node.
+"""
+
+import pytest
+from calimero_client_py import ClientStorage, create_connection, create_client
+
+
+def _client():
+    connection = create_connection(
+        api_url="https://test.merod.dev.p2p.aws.calimero.network",
+        node_name="test-dev-node",
+    )
+    return create_client(connection)
+
+
+class TestDownloadBlobs:
+    """Tests for Client.download_blobs."""
+
+    def test_rejects_zero_concurrency(self, tmp_path):
+        """concurrency=0 would never make progress and is rejected up front."""
+        client = _client()
+        with pytest.raises(ValueError, match="concurrency"):
+            client.download_blobs([], str(tmp_path), concurrency=0)
+
+    def test_invalid_ids_are_reported_per_item(self, tmp_path):
+        """Malformed ids fail individually instead of aborting the batch."""
+        client = _client()
+        results = client.download_blobs(["not-a-blob-id"], str(tmp_path))
+        assert len(results) == 1
+        assert results[0]["blobId"] == "not-a-blob-id"
+        assert results[0]["path"] is None
+        assert "Invalid blob ID" in results[0]["error"]
+        assert not results.ok
+        assert results.failed_inputs == ["not-a-blob-id"]
+        assert "not-a-blob-id" in results.errors
+
+    def test_merge_replaces_retried_failures(self, tmp_path):
+        """Retrying the failed subset merges back into the original slots."""
+        client = _client()
+        first = client.download_blobs(["bad-1", "bad-2"], str(tmp_path))
+        retried = client.download_blobs(first.failed_inputs[1:], str(tmp_path))
+        merged = first.merge(retried)
+        assert [r["blobId"] for r in merged] == ["bad-1", "bad-2"]
+        assert merged.operation == "download_blobs"
+
+    def test_empty_batch_returns_empty_list(self, tmp_path):
+        """An empty id list is a no-op."""
+        client = _client()
+        assert client.download_blobs([], str(tmp_path)) == []
+
+    def test_repeated_ids_share_one_download(self, tmp_path):
+        """An id listed twice is fetched once and reported at both positions."""
+        client = create_client(create_connection(api_url="http://127.0.0.1:9"))
+        blob_id = "11111111111111111111111111111111"
+        calls = []
+        results = client.download_blobs(
+            [blob_id, blob_id],
+            str(tmp_path),
+            on_progress=lambda *args: calls.append(args),
+        )
+        assert [r["blobId"] for r in results] == [blob_id, blob_id]
+        assert results[0]["error"] and results[0]["error"] == results[1]["error"]
+        assert calls == [(2, 2, 0)]
+        assert list(tmp_path.iterdir()) == []
+
+    def test_python_storage_is_read_without_deadlock(self, tmp_path):
+        """Downloads read tokens from a Python storage while the GIL is free."""
+
+        class RecordingStorage(ClientStorage):
+            def __init__(self):
+                super().__init__()
+                self.loads = []
+
+            def save_tokens(self, key, tokens):
+                pass
+
+            def load_tokens(self, key):
+                self.loads.append(key)
+                return {"access_token": "access", "refresh_token": None, "expires_at": None}
+
+            def remove_tokens(self, key):
+                pass
+
+        storage = RecordingStorage()
+        connection = create_connection(
+            "http://127.0.0.1:9", node_name="blob-storage-node", storage=storage
+        )
+        client = create_client(connection)
+        before = len(storage.loads)
+        results = client.download_blobs(
+            ["11111111111111111111111111111111", "1111111111111111111111111111111A"],
+            str(tmp_path),
+            concurrency=2,
+        )
+        assert all(r["error"] for r in results)
+        assert len(storage.loads) >= before + 2
+
+
+class TestBlobMetadata:
+    """Tests for content-type / metadata arguments on blob bindings."""
+
+    def test_upload_blob_accepts_metadata_kwargs(self):
+        """upload_blob takes content_type and metadata keywords."""
+        client = _client()
+        with pytest.raises(Exception) as exc_info:
+            client.upload_blob(
+                b"hello",
+                context_id="not-a-context",
+                content_type="text/plain",
+                metadata={"name": "hello.txt"},
+            )
+        assert not isinstance(exc_info.value, TypeError)
+
+    def test_download_blob_accepts_with_metadata(self):
+        """download_blob takes a with_metadata keyword."""
+        client = _client()
+        with pytest.raises(ValueError, match="Invalid blob ID"):
+            client.download_blob("not-a-blob-id", with_metadata=True)
+
+    def test_download_blob_accepts_verify(self):
+        """download_blob takes a verify keyword."""
+        client = _client()
+        with pytest.raises(ValueError, match="Invalid blob ID"):
+            client.download_blob("not-a-blob-id", verify=True)
+
+
+class TestReadBlob:
+    """Tests for Client.read_blob."""
+
+    def test_rejects_invalid_blob_id(self):
+        """Blob ids are parsed before any request is made."""
+        client = _client()
+        with pytest.raises(ValueError, match="Invalid blob ID"):
+            client.read_blob("not-a-blob-id", offset=0, length=16)
+
+    def test_rejects_negative_offset(self):
+        """Offsets are unsigned; negative values fail at the binding layer."""
+        client = _client()
+        with pytest.raises(OverflowError):
+            client.read_blob("not-a-blob-id", offset=-1)
+
+
+class TestUploadBlobStream:
+    """Tests for Client.upload_blob_stream source handling."""
+
+    def test_rejects_non_iterable_source(self):
+        """Objects that are neither readable nor iterable are a TypeError."""
+        client = _client()
+        with pytest.raises(TypeError, match="Upload source"):
+            client.upload_blob_stream(42)
+
+    def test_rejects_zero_chunk_size(self):
+        """chunk_size=0 would never make progress."""
+        client = _client()
+        with pytest.raises(ValueError, match="chunk_size"):
+            client.upload_blob_stream(iter([b"abc"]), chunk_size=0)
+
+    def test_rejects_invalid_context_id(self):
+        """The context id is validated before any bytes are pulled."""
+        client = _client()
+        with pytest.raises(ValueError, match="Invalid context ID"):
+            client.upload_blob_stream(iter([b"abc"]), context_id="not-a-context")
+
+    def test_accepts_paths_bytes_and_progress(self, tmp_path):
+        """Paths and bytes are valid sources; on_progress is a keyword."""
+        client = _client()
+        path = tmp_path / "blob.bin"
+        path.write_bytes(b"abc")
+        for source in (path, str(path), b"abc"):
+            with pytest.raises(ValueError, match="Invalid context ID"):
+                client.upload_blob_stream(
+                    source, context_id="not-a-context", on_progress=print
+                )
+
+
+class TestDownloadBlobToFile:
+    """Tests for download_blob(..., dest_path=...)."""
+
+    def test_rejects_in_memory_options(self, tmp_path):
+        """Decryption and metadata need the whole payload in memory."""
+        client = _client()
+        blob_id = "1" * 44
+        with pytest.raises(ValueError, match="dest_path"):
+            client.download_blob(
+                blob_id, dest_path=tmp_path / "blob.bin", encryption_key=b"k" * 32
+            )
+        with pytest.raises(ValueError, match="dest_path"):
+            client.download_blob(blob_id, dest_path=str(tmp_path / "b"), with_metadata=True)
+
+    def test_rejects_invalid_blob_id(self, tmp_path):
+        """Blob ids are parsed before anything is written."""
+        client = _client()
+        with pytest.raises(ValueError, match="Invalid blob ID"):
+            client.download_blob("not-a-blob-id", dest_path=tmp_path / "blob.bin")
+        assert not (tmp_path / "blob.bin.part").exists()
+
+
+class TestBlobEncryption:
+    """Tests for client-side blob encryption arguments."""
+
+    def test_upload_rejects_malformed_key(self):
+        """Keys must be 32 raw bytes or 64 hex characters."""
+        client = _client()
+        with pytest.raises(ValueError, match="Failed to encrypt blob"):
+            client.upload_blob(b"secret", encryption_key=b"too-short")
+
+    def test_download_rejects_malformed_key(self):
+        """The key is validated before any request is made."""
+        client = _client()
+        blob_id = "1" * 44
+        with pytest.raises(ValueError):
+            client.download_blob(blob_id, encryption_key=b"too-short")
+
+
+class TestBlobAccess:
+    """Tests for Client.blob_access."""
+
+    def test_rejects_invalid_blob_id(self):
+        """Blob ids are validated before building the URL."""
+        client = _client()
+        with pytest.raises(ValueError, match="Invalid blob ID"):
+            client.blob_access("not-a-blob-id", "11111111111111111111111111111111")
+
+    def test_requires_a_context(self):
+        """The token handed out is scoped to one context, so one is required."""
+        client = _client()
+        with pytest.raises(TypeError):
+            client.blob_access("11111111111111111111111111111111")
+
+    def test_rejects_zero_ttl(self):
+        """A token that expires immediately is rejected up front."""
+        client = _client()
+        with pytest.raises(ValueError, match="ttl"):
+            client.blob_access(
+                "11111111111111111111111111111111",
+                "11111111111111111111111111111111",
+                ttl=0,
+            )
@@ -0,0 +1,55 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.blocking (no node required).
+"""
+
+import asyncio
+
+import pytest
+
+from calimero import BlockingClient, Client, create_connection
+
+
+def _client():
+    return BlockingClient(create_connection(api_url="http://127.0.0.1:9"))
+
+
+def test_owns_a_client_and_forwards_blocking_calls():
+    with _client() as client:
+        assert isinstance(client.client, Client)
+        assert client.closed is False
+        with pytest.raises(ValueError):
+            client.execute_function("not-a-context-id", "get", "{}")
+        with pytest.raises(RuntimeError):
+            client.execute_function("1" * 32, "get", "{}")
+    assert client.closed is True
+
+
+def test_awaitable_methods_point_at_their_blocking_twin():
+    client = _client()
+    with pytest.raises(AttributeError, match="execute_function"):
+        client.execute_async
+    assert "execute_async" not in dir(client)
+    assert "execute_function" in dir(client)
+    client.close()
+
+
+def test_calls_inside_an_event_loop_raise():
+    client = _client()
+
+    async def call():
+        return client.list_contexts()
+
+    with pytest.raises(RuntimeError, match="event loop"):
+        asyncio.run(call())
+    client.close()
+
+
+def test_client_options_are_passed_through():
+    client = BlockingClient(
+        create_connection(api_url="http://127.0.0.1:9"), max_concurrency=2
+    )
+    assert client.client.closed is False
+    with pytest.raises(ValueError):
+        BlockingClient(create_connection(api_url="http://127.0.0.1:9"), wire="bogus")
+    client.close()
@@ -0,0 +1,24 @@
+#!/usr/bin/env python3
+"""
+Tests for the CDC exporter.
+
+These pin URL validation that happens before any broker connection, so they
+run without Kafka or NATS.
+"""
+
+import pytest
+from calimero_client_py import CdcExporter
+
+
+class TestCdcExporter:
+    """Tests for CdcExporter construction."""
+
+    def test_rejects_unknown_scheme(self):
+        """Only kafka:// and nats:// brokers are supported."""
+        with pytest.raises(ValueError, match="Unsupported broker scheme"):
+            CdcExporter("amqp://localhost:5672")
+
+    def test_rejects_missing_scheme(self):
+        """A bare host:port is ambiguous and rejected."""
+        with pytest.raises(ValueError, match="missing scheme"):
+            CdcExporter("localhost:9092")
@@ -0,0 +1,40 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.compat.http against a mock node (no node required).
+"""
+
+import pytest
+from calimero_client_py import ClientError
+
+from calimero.compat import http
+from calimero.testing import MockConnection
+
+
+def test_get_and_post_return_requests_style_responses():
+    """Bodies, statuses and headers come back whatever the status."""
+    with MockConnection() as node:
+        node.route("GET", "/admin-api/contexts", {"data": {"contexts": []}})
+        node.route("POST", "/admin-api/contexts", {"error": "bad"}, status=400)
+
+        response = http.get(f"{node.api_url}/admin-api/contexts", params={"a": 1})
+        assert response.ok and response.status_code == 200
+        assert response.json() == {"data": {"contexts": []}}
+        assert response.headers["content-type"] == "application/json"
+        assert response.headers["Content-Type"] == "application/json"
+
+        session = http.Session(node.client())
+        response = session.post("admin-api/contexts", json={"applicationId": "x"})
+        assert response.status_code == 400
+        with pytest.raises(http.HTTPError) as raised:
+            response.raise_for_status()
+        assert raised.value.response is response
+
+
+def test_tokens_never_leave_the_node_origin():
+    """Full URLs on another origin are refused instead of sent."""
+    with MockConnection() as node:
+        session = http.Session(node.client())
+        with pytest.raises(ClientError, match="origin"):
+            session.get("http://127.0.0.1:9/admin-api/contexts")
+    with pytest.raises(ValueError):
+        http.get("admin-api/contexts")
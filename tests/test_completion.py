@@ -0,0 +1,48 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.completion (no node required).
+"""
+
+import pytest
+
+from calimero.completion import (
+    cached_node_names,
+    context_candidates,
+    generate,
+)
+
+OPTIONS = {"--base-url": [], "--node-name": [], "--output": ["json", "table"]}
+
+
+def test_cached_node_names(tmp_path):
+    for name in [
+        "node-a-0123456789ab.json",
+        "node-b-ba9876543210.json",
+        "auth_modes.json",
+        "node-a-0123456789ab.json.tmp",
+    ]:
+        (tmp_path / name).write_text("{}")
+    assert cached_node_names(str(tmp_path)) == ["node-a", "node-b"]
+
+
+def test_cached_node_names_missing_dir(tmp_path):
+    assert cached_node_names(str(tmp_path / "missing")) == []
+
+
+def test_context_candidates_merges_ids_and_aliases():
+    contexts = {"data": {"contexts": [{"id": "ctx1"}, {"id": "ctx2"}]}}
+    aliases = {"data": {"staging": "ctx1"}}
+    assert context_candidates(contexts, aliases) == ["ctx1", "ctx2", "staging"]
+
+
+@pytest.mark.parametrize("shell", ["bash", "zsh", "fish"])
+def test_scripts_reference_commands_and_dynamic_values(shell):
+    script = generate(shell, "calimero-client-py", ["list-contexts"], OPTIONS)
+    assert "list-contexts" in script
+    assert "__complete node-names" in script
+    assert "__complete contexts" in script
+
+
+def test_unknown_shell():
+    with pytest.raises(ValueError, match="Unsupported shell"):
+        generate("tcsh", "calimero-client-py", [], OPTIONS)
@@ -0,0 +1,58 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.daemon (no node required).
+"""
+
+import os
+import sys
+import tempfile
+import threading
+import time
+
+import pytest
+
+from calimero import daemon
+
+pytestmark = pytest.mark.skipif(
+    sys.platform == "win32", reason="the daemon listens on a Unix socket"
+)
+
+
+@pytest.fixture
+def socket_path():
+    directory = tempfile.mkdtemp()
+    path = os.path.join(directory, "daemon.sock")
+    thread = threading.Thread(target=daemon.serve, args=(path, 60.0), daemon=True)
+    thread.start()
+    deadline = time.monotonic() + 5
+    while not daemon._ping(path) and time.monotonic() < deadline:
+        time.sleep(0.02)
+    yield path
+    daemon.stop(path)
+    thread.join(timeout=5)
+
+
+def test_calls_are_forwarded_to_a_shared_client(socket_path):
+    """Proxies reuse the daemon's client of a node."""
+    client = daemon.connect("http://127.0.0.1:9", socket_path=socket_path, spawn=False)
+    assert client.get_api_url().startswith("http://127.0.0.1:9")
+    assert client.metrics()["requests"] == 0
+    assert daemon._request(socket_path, {"method": "__ping__"}, 1.0)["clients"] == 1
+    assert oct(os.stat(socket_path).st_mode & 0o777) == "0o600"
+
+
+def test_errors_cross_the_socket(socket_path):
+    """Built-in exceptions keep their type; private methods are refused."""
+    client = daemon.connect("http://127.0.0.1:9", socket_path=socket_path, spawn=False)
+    with pytest.raises(AttributeError):
+        client.no_such_method()
+    with pytest.raises(daemon.DaemonError):
+        client.get_peers_count()
+
+
+def test_connect_without_spawn_needs_a_daemon():
+    """spawn=False reports a missing daemon instead of starting one."""
+    path = os.path.join(tempfile.mkdtemp(), "missing.sock")
+    with pytest.raises(daemon.DaemonError):
+        daemon.connect("http://127.0.0.1:9", socket_path=path, spawn=False)
+    assert daemon.stop(path) is False
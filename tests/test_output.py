@@ -0,0 +1,59 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.output CLI renderers.
+"""
+
+import json
+
+import pytest
+
+from calimero.output import render
+
+DATA = {
+    "contexts": [
+        {"id": "abc", "applicationId": "app", "rootHash": None},
+        {"id": "def", "applicationId": "app", "tags": ["x", "y"]},
+    ]
+}
+
+
+def test_json_is_lossless():
+    assert json.loads(render(DATA, "json")) == DATA
+
+
+def test_yaml_layout():
+    assert render(DATA, "yaml") == "\n".join(
+        [
+            "contexts:",
+            "  - id: abc",
+            "    applicationId: app",
+            "    rootHash: null",
+            "  - id: def",
+            "    applicationId: app",
+            "    tags:",
+            "      - x",
+            "      - y",
+        ]
+    )
+
+
+def test_yaml_quotes_ambiguous_scalars():
+    assert render({"a": "true", "b": "1.5", "c": "x: y"}, "yaml") == "\n".join(
+        ['a: "true"', 'b: "1.5"', 'c: "x: y"']
+    )
+
+
+def test_table_from_records():
+    lines = render(DATA, "table").splitlines()
+    assert lines[0].split() == ["ID", "APPLICATIONID", "ROOTHASH", "TAGS"]
+    assert lines[2].split() == ["abc", "app"]
+    assert lines[3].split() == ["def", "app", '["x","y"]']
+
+
+def test_table_empty_listing():
+    assert render({"contexts": []}, "table") == "(no results)"
+
+
+def test_unknown_format():
+    with pytest.raises(ValueError, match="Unknown output format"):
+        render(DATA, "xml")
@@ -0,0 +1,88 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.policy (no node required).
+"""
+
+import json
+
+import pytest
+
+from calimero.policy import PolicyError, apply_policy, plan
+
+POLICY = {
+    "capabilities": {"create_context": 1, "invite": 2, "manage": 4},
+    "roles": {
+        "admin": {"capabilities": ["invite", "manage"], "members": ["alice"]},
+        "writer": {"capabilities": ["create_context"], "members": ["alice", "bob"]},
+    },
+}
+
+
+class _FakeClient:
+    def __init__(self, capabilities):
+        self.capabilities = dict(capabilities)
+        self.calls = []
+
+    def get_context(self, context_id):
+        return {"data": {"id": context_id, "groupId": "group-1"}}
+
+    def list_group_members(self, group_id):
+        return {"data": [{"identity": key} for key in self.capabilities]}
+
+    def get_member_capabilities(self, group_id, member_id):
+        return {"data": {"capabilities": self.capabilities[member_id]}}
+
+    def set_member_capabilities(self, group_id, member_id, capabilities):
+        if member_id == "mallory":
+            raise RuntimeError("Client error: forbidden")
+        self.calls.append((group_id, member_id, capabilities))
+        self.capabilities[member_id] = capabilities
+
+
+def test_plan_unions_roles_and_reports_delta():
+    report = plan(POLICY, {"alice": 1, "bob": 3, "carol": 4})
+    assert report["changes"] == [
+        {"member": "alice", "from": 1, "to": 7, "granted": 6, "revoked": 0},
+        {"member": "bob", "from": 3, "to": 1, "granted": 0, "revoked": 2},
+    ]
+    assert report["unchanged"] == []
+    assert report["notMembers"] == []
+
+
+def test_default_applies_to_unlisted_members():
+    report = plan({**POLICY, "default": 0}, {"alice": 7, "carol": 4, "dave": 0})
+    assert [c["member"] for c in report["changes"]] == ["carol"]
+    assert report["unchanged"] == ["alice", "dave"]
+    assert report["notMembers"] == ["bob"]
+
+
+def test_apply_sets_only_the_delta():
+    client = _FakeClient({"alice": 7, "bob": 0})
+    report = apply_policy(client, "ctx-1", POLICY)
+    assert report["groupId"] == "group-1"
+    assert client.calls == [("group-1", "bob", 1)]
+    assert apply_policy(client, "ctx-1", POLICY)["changes"] == []
+
+
+def test_dry_run_and_failures():
+    client = _FakeClient({"alice": 0, "mallory": 4})
+    policy = {**POLICY, "default": 0}
+    assert apply_policy(client, "ctx-1", policy, dry_run=True)["dryRun"]
+    assert client.calls == []
+    report = apply_policy(client, "ctx-1", policy)
+    assert client.calls == [("group-1", "alice", 7)]
+    assert report["errors"] == [
+        {"member": "mallory", "error": "Client error: forbidden"}
+    ]
+
+
+def test_policy_file_and_validation(tmp_path):
+    path = tmp_path / "policy.json"
+    path.write_text(json.dumps(POLICY))
+    client = _FakeClient({"alice": 7, "bob": 1})
+    assert apply_policy(client, "ctx-1", str(path))["unchanged"] == ["alice", "bob"]
+    bad = {"roles": {"admin": {"capabilities": ["root"], "members": ["alice"]}}}
+    with pytest.raises(PolicyError):
+        apply_policy(client, "ctx-1", bad)
+    with pytest.raises(PolicyError):
+        plan({"roles": {}}, {})
@@ -0,0 +1,86 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.projections.
+"""
+
+import pytest
+
+from calimero.projections import Projection, sqlite_projection
+
+
+def _count(state, event):
+    state = dict(state)
+    state[event["kind"]] = state.get(event["kind"], 0) + 1
+    return state
+
+
+EVENTS = [{"kind": "a"}, {"kind": "b"}, {"kind": "a"}]
+
+
+def test_replay_folds_events(tmp_path):
+    projection = Projection("p", _count, {}, checkpoint_dir=str(tmp_path))
+    assert projection.replay(EVENTS) == {"a": 2, "b": 1}
+    assert projection.position == 3
+
+
+def test_replay_resumes_from_checkpoint(tmp_path):
+    first = Projection("p", _count, {}, checkpoint_dir=str(tmp_path))
+    first.replay(EVENTS[:2])
+
+    resumed = Projection("p", _count, {}, checkpoint_dir=str(tmp_path))
+    assert resumed.position == 2
+    # The full history is replayed; only the unseen tail is applied.
+    assert resumed.replay(EVENTS) == {"a": 2, "b": 1}
+
+
+def test_follow_applies_live_events(tmp_path):
+    projection = Projection("p", _count, {}, checkpoint_dir=str(tmp_path))
+    projection.replay(EVENTS)
+    projection.follow([{"kind": "c"}])
+    assert projection.state == {"a": 2, "b": 1, "c": 1}
+
+
+def test_in_memory_projection_writes_nothing(tmp_path):
+    projection = Projection("p", _count, {}, checkpoint_dir=None)
+    projection.replay(EVENTS)
+    assert projection.checkpoint_path is None
+    assert list(tmp_path.iterdir()) == []
+
+
+def test_reset_removes_checkpoint(tmp_path):
+    projection = Projection("p", _count, {}, checkpoint_dir=str(tmp_path))
+    projection.replay(EVENTS)
+    projection.reset({})
+    assert projection.position == 0
+    assert not (tmp_path / "p.json").exists()
+
+
+def test_rejects_zero_checkpoint_interval():
+    with pytest.raises(ValueError, match="checkpoint_every"):
+        Projection("p", _count, {}, checkpoint_dir=None, checkpoint_every=0)
+
+
+def test_sqlite_projection_mirrors_state(tmp_path):
+    projection = sqlite_projection(
+        "view",
+        str(tmp_path / "view.db"),
+        key_prefixes=["votes/"],
+        checkpoint_dir=str(tmp_path),
+    )
+    projection.replay(
+        [
+            {"kind": "StateMutation", "contextId": "c1", "key": "votes/a", "value": 1},
+            {"kind": "StateMutation", "contextId": "c1", "key": "other", "value": 2},
+            {"kind": "StateMutation", "contextId": "c1", "key": "votes/a", "value": 3},
+        ]
+    )
+    rows = projection.view.query("SELECT key, value FROM state ORDER BY key")
+    assert rows == [{"key": "votes/a", "value": "3"}]
+    assert projection.state == 3
+
+
+def test_sqlite_view_query_rejects_writes(tmp_path):
+    projection = sqlite_projection("view", str(tmp_path / "view.db"), checkpoint_dir=str(tmp_path))
+    with pytest.raises(ValueError, match="read-only"):
+        projection.view.query("DELETE FROM state")
+    assert projection.view.query("SELECT count(*) AS n FROM events") == [{"n": 0}]
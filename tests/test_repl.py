@@ -0,0 +1,68 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.repl helpers (no node required).
+"""
+
+import json
+
+from calimero.repl import ContextProxy, abi_methods, build_namespace, context_ids
+
+
+class _FakeClient:
+    def __init__(self):
+        self.calls = []
+
+    def execute_function(self, context_id, method, args):
+        self.calls.append((context_id, method, json.loads(args)))
+        return {"output": "ok"}
+
+    def list_contexts(self):
+        return {"data": {"contexts": [{"id": "ctx-1"}, {"id": "ctx-2"}]}}
+
+    def get_context(self, context_id):
+        return {"data": {"id": context_id, "applicationId": "app-1"}}
+
+    def get_application(self, app_id):
+        abi = {"methods": [{"name": "set"}, {"name": "get"}]}
+        return {"data": {"application": {"metadata": {"abi": json.dumps(abi)}}}}
+
+
+def test_context_ids_from_listing():
+    assert context_ids(_FakeClient().list_contexts()) == ["ctx-1", "ctx-2"]
+
+
+def test_abi_methods_from_json_string():
+    assert abi_methods(_FakeClient().get_application("app-1")) == ["get", "set"]
+
+
+def test_abi_methods_missing_abi():
+    assert abi_methods({"data": {"application": {}}}) == []
+
+
+def test_context_proxy_calls_execute_function():
+    client = _FakeClient()
+    ctx = ContextProxy(client, "ctx-1")
+    assert ctx.set(key="a", value="b") == {"output": "ok"}
+    assert client.calls == [("ctx-1", "set", {"key": "a", "value": "b"})]
+
+
+def test_context_proxy_completes_abi_methods():
+    ctx = ContextProxy(_FakeClient(), "ctx-1")
+    assert {"get", "set"} <= set(dir(ctx))
+
+
+def test_namespace_contents():
+    namespace = build_namespace(_FakeClient())
+    assert {"client", "connection", "contexts", "pp"} <= set(namespace)
+    assert namespace["contexts"].ids() == ["ctx-1", "ctx-2"]
+    assert "ctx-2" in namespace["contexts"]._ipython_key_completions_()
+
+
+def test_context_proxy_prefers_discover_methods():
+    class _DiscoveringClient(_FakeClient):
+        def discover_methods(self, context_id):
+            return [{"name": "entries", "params": [], "returns": None}]
+
+    ctx = ContextProxy(_DiscoveringClient(), "ctx-1")
+    assert "entries" in dir(ctx)
+    assert "set" not in dir(ctx)
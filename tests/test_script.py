@@ -0,0 +1,101 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.script (no node required).
+"""
+
+import json
+
+import pytest
+
+from calimero.script import ScriptError, ScriptRunner, load_script, substitute
+
+
+class _FakeClient:
+    def __init__(self):
+        self.store = {}
+
+    def create_context(self, application_id, group_id, params=None, service_name=None):
+        return {"data": {"contextId": f"ctx-{application_id}"}}
+
+    def execute_function(self, context_id, method, args):
+        args = json.loads(args)
+        if method == "set":
+            self.store[args["key"]] = args["value"]
+            return {"result": {"output": None}}
+        return {"result": {"output": self.store.get(args["key"])}}
+
+    def list_contexts(self):
+        return {"data": {"contexts": []}}
+
+
+def _runner():
+    client = _FakeClient()
+    return ScriptRunner(connect=lambda spec: client)
+
+
+SCRIPT = {
+    "vars": {"app": "app1"},
+    "steps": [
+        {"login": {"url": "http://localhost:2528", "node_name": "node1"}},
+        {
+            "create_context": {"application_id": "${app}", "group_id": "g"},
+            "save_as": "created",
+        },
+        {
+            "execute": {
+                "context_id": "${created.data.contextId}",
+                "method": "set",
+                "args": {"key": "greeting", "value": "hello"},
+            }
+        },
+        {
+            "execute": {
+                "context_id": "${created.data.contextId}",
+                "method": "get",
+                "args": {"key": "greeting"},
+            },
+            "save_as": "greeting",
+        },
+        {"assert": {"that": "${greeting.result.output}", "equals": "hello"}},
+        {"echo": "context ${created.data.contextId} ready"},
+    ],
+}
+
+
+def test_script_runs_end_to_end():
+    results = _runner().run(SCRIPT)
+    assert [r["kind"] for r in results] == [
+        "login",
+        "create_context",
+        "execute",
+        "execute",
+        "assert",
+        "echo",
+    ]
+    assert results[-1]["result"] == "context ctx-app1 ready"
+
+
+def test_failed_assert_reports_step():
+    script = {"steps": [{"set": {"x": 1}}, {"assert": {"that": "${x}", "equals": 2}}]}
+    with pytest.raises(ScriptError) as exc_info:
+        _runner().run(script)
+    assert exc_info.value.step == 2
+    assert exc_info.value.kind == "assert"
+
+
+def test_steps_need_a_client():
+    with pytest.raises(ScriptError, match="login"):
+        ScriptRunner().run({"steps": [{"call": {"method": "list_contexts"}}]})
+
+
+def test_substitute_keeps_types_for_whole_references():
+    variables = {"n": 3, "items": [{"id": "a"}]}
+    assert substitute("${n}", variables) == 3
+    assert substitute("n=${n} id=${items.0.id}", variables) == "n=3 id=a"
+
+
+def test_load_script_requires_steps(tmp_path):
+    path = tmp_path / "bad.json"
+    path.write_text(json.dumps({"vars": {}}))
+    with pytest.raises(ValueError, match="steps"):
+        load_script(str(path))
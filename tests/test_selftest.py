@@ -0,0 +1,112 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.selftest (no node required).
+"""
+
+import pytest
+
+from calimero.selftest import STAGES, self_test
+
+
+class _Health:
+    def to_dict(self):
+        return {"healthy": True, "status": "alive"}
+
+
+class _Mode:
+    value = "none"
+
+
+class _Subscription:
+    closed = False
+
+    def close(self):
+        self.closed = True
+
+
+class _FakeClient:
+    auth_mode = _Mode()
+
+    def __init__(self, fail=None):
+        self.fail = fail
+        self.deleted = []
+        self.subscription = _Subscription()
+        self.subscription_stats = None
+
+    def _check(self, stage):
+        if self.fail == stage:
+            raise RuntimeError(f"{stage} broke")
+
+    def get_api_url(self):
+        return "http://node.test/"
+
+    def wait_until_ready(self, timeout):
+        self._check("connect")
+        return _Health()
+
+    def list_contexts(self):
+        self._check("auth")
+        return {"data": {"contexts": [{"id": "a"}]}}
+
+    def create_context(self, application_id, group_id):
+        self._check("create_context")
+        return {"data": {"contextId": "ctx-1", "memberPublicKey": "key"}}
+
+    def execute_function(self, context_id, method, args):
+        self._check("execute")
+        return {"output": args}
+
+    def subscribe(self, context_id):
+        self._check("subscribe")
+        self.subscription_stats = {"connected": True, "contexts": 1}
+        return self.subscription
+
+    def delete_context(self, context_id):
+        self.deleted.append(context_id)
+        return {"data": {"isDeleted": True}}
+
+
+def _statuses(report):
+    return {stage["stage"]: stage["status"] for stage in report["stages"]}
+
+
+def test_all_stages_pass():
+    client = _FakeClient()
+    report = self_test(
+        client=client, application_id="app", group_id="group", method="get"
+    )
+    assert report["ok"]
+    assert report["node"] == "http://node.test/"
+    assert [stage["stage"] for stage in report["stages"]] == list(STAGES)
+    assert set(_statuses(report).values()) == {"passed"}
+    assert client.deleted == ["ctx-1"]
+    assert client.subscription.closed
+
+
+def test_failure_skips_dependent_stages():
+    report = self_test(client=_FakeClient(fail="auth"))
+    assert not report["ok"]
+    statuses = _statuses(report)
+    assert statuses["auth"] == "failed"
+    assert statuses["create_context"] == statuses["cleanup"] == "skipped"
+    failed = next(s for s in report["stages"] if s["stage"] == "auth")
+    assert failed["error"] == "RuntimeError: auth broke"
+
+
+def test_context_is_deleted_after_failed_execute():
+    client = _FakeClient(fail="execute")
+    report = self_test(
+        client=client, application_id="app", group_id="group", method="get"
+    )
+    statuses = _statuses(report)
+    assert statuses["execute"] == "failed"
+    assert statuses["subscribe"] == statuses["cleanup"] == "passed"
+    assert client.deleted == ["ctx-1"]
+
+
+def test_missing_inputs_skip_context_stages():
+    report = self_test(client=_FakeClient())
+    assert report["ok"]
+    assert _statuses(report)["create_context"] == "skipped"
+    with pytest.raises(ValueError):
+        self_test()
@@ -0,0 +1,102 @@
+#!/usr/bin/env python3
+"""
+Tests for calimero.testing helpers (no node required).
+"""
+
+import pytest
+from calimero_client_py import set_entropy_source
+
+from calimero.testing import (
+    MockConnection,
+    MockError,
+    deterministic_entropy,
+    roundtrip,
+)
+
+CONTEXT_ID = "11111111111111111111111111111111"
+
+
+def test_entropy_source_rejects_unsupported_types():
+    """Only None, int/bytes seeds and callables are accepted."""
+    with pytest.raises(TypeError):
+        set_entropy_source("not-a-seed")
+
+
+def test_deterministic_entropy_validates_before_entering():
+    """An invalid source fails at the with-statement, not on first use."""
+    entered = False
+    with pytest.raises(TypeError):
+        with deterministic_entropy(object()):
+            entered = True
+    assert not entered
+
+
+def test_deterministic_entropy_accepts_seeds_and_callables():
+    """Int, bytes and callable sources all install and restore cleanly."""
+    for seed in (42, b"seed", lambda n: bytes(n)):
+        with deterministic_entropy(seed):
+            pass
+
+
+@pytest.mark.parametrize("wire", ["json", "msgpack"])
+def test_roundtrip_preserves_values(wire):
+    """Payloads survive both wire formats, including u64-range integers."""
+    payload = {
+        "name": "ünïcode",
+        "count": 2**63,
+        "neg": -(2**63),
+        "ratio": 0.5,
+        "flags": [True, False, None],
+        "nested": {"empty": [], "tuple": [1, 2]},
+    }
+    assert roundtrip(payload, wire=wire) == payload
+
+
+def test_roundtrip_rejects_unencodable_values():
+    """Values outside the JSON data model fail with Python exceptions."""
+    with pytest.raises(ValueError):
+        roundtrip(float("nan"))
+    with pytest.raises(OverflowError):
+        roundtrip(2**64)
+    with pytest.raises(TypeError):
+        roundtrip({1: "non-string key"})
+    with pytest.raises(ValueError, match="wire format"):
+        roundtrip({}, wire="cbor")
+
+
+def test_repeated_ids_share_one_python_string():
+    """ID-shaped strings coming out of the binding are interned."""
+    context_id = "7Xz1bR9kUq3N8mTfYc2WvLp4HsJd6AeGo5Ki1ZnMxQr"
+    first = roundtrip({"contextId": context_id})
+    second = roundtrip({"contextId": context_id})
+    assert first["contextId"] is second["contextId"]
+
+
+def test_mock_connection_serves_canned_responses():
+    """The production client gets canned outputs and the calls are recorded."""
+    with MockConnection(responses={"get": {"value": 1}}) as node:
+        client = node.client()
+        result = client.execute_function(CONTEXT_ID, "get", '{"key": "a"}')
+        assert result["result"]["output"] == {"value": 1}
+        assert node.calls[-1].context_id == CONTEXT_ID
+        assert node.calls[-1].method == "get"
+        assert node.calls[-1].args == {"key": "a"}
+
+
+def test_mock_connection_handler_and_errors():
+    """A handler answers unlisted methods and MockError becomes an RPC error."""
+
+    def handler(call):
+        if call.method == "fail":
+            raise MockError({"type": "FunctionCallError", "data": "boom"})
+        return call.args["n"] * 2
+
+    with MockConnection(handler=handler) as node:
+        client = node.client()
+        result = client.execute_function(CONTEXT_ID, "double", '{"n": 21}')
+        assert result["result"]["output"] == 42
+        with pytest.raises(Exception, match="boom"):
+            client.execute_function(CONTEXT_ID, "fail", "{}")
+        node.respond("double", 0)
+        result = client.execute_function(CONTEXT_ID, "double", '{"n": 21}')
+        assert result["result"]["output"] == 0
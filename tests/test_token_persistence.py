@@ -7,15 +7,33 @@ These tests verify:
 2. Filename derivation is stable (same input → same output)
 3. Different node names produce different cache paths
 4. Cache paths are valid filesystem paths
+5. Python-defined ClientStorage backends replace the cache files
+6. storage_doctor reports on the versioned state layout
+7. Token bundles move credentials between storages
+8. TokenCache lists and cleans up cached tokens
+9. CLI (meroctl/merobox) credentials are imported and written back
 """
 
 import os
 import json
 import tempfile
 
+import pytest
+
 from calimero_client_py import (
+    ClientStorage,
+    MemoryStorage,
+    TokenCache,
+    create_client,
+    create_connection,
+    export_cli_credentials,
+    export_tokens,
+    import_cli_credentials,
+    import_tokens,
     get_token_cache_path,
     get_token_cache_dir,
+    migrate_token_cache,
+    storage_doctor,
 )
 
 
@@ -28,11 +46,58 @@ class TestTokenCachePathUtilities:
         assert isinstance(cache_dir, str)
         assert len(cache_dir) > 0
 
-    def test_get_token_cache_dir_contains_merobox(self):
-        """Test that cache dir is under .merobox/auth_cache."""
+    def test_get_token_cache_dir_is_auth_cache(self):
+        """Test that cache dir is auth_cache under the state root."""
         cache_dir = get_token_cache_dir()
-        assert ".merobox" in cache_dir
-        assert "auth_cache" in cache_dir
+        assert os.path.basename(cache_dir.rstrip(os.sep)) == "auth_cache"
+
+    def test_cache_dir_env_override(self, tmp_path, monkeypatch):
+        """CALIMERO_CACHE_DIR moves the token cache."""
+        monkeypatch.setenv("CALIMERO_CACHE_DIR", str(tmp_path))
+        assert get_token_cache_dir() == str(tmp_path / "auth_cache")
+        assert get_token_cache_path("node").startswith(str(tmp_path))
+
+    def test_token_cache_dir_env_override(self, tmp_path, monkeypatch):
+        """MEROBOX_CACHE_DIR names the token directory itself."""
+        monkeypatch.setenv("MEROBOX_CACHE_DIR", str(tmp_path))
+        assert get_token_cache_dir() == str(tmp_path)
+
+    def test_cache_dir_env_precedence(self, tmp_path, monkeypatch):
+        """cache_dir beats MEROBOX_CACHE_DIR, which beats CALIMERO_CACHE_DIR."""
+        monkeypatch.setenv("CALIMERO_CACHE_DIR", str(tmp_path / "state"))
+        monkeypatch.setenv("MEROBOX_CACHE_DIR", str(tmp_path / "tokens"))
+        assert get_token_cache_dir() == str(tmp_path / "tokens")
+        conn = create_connection(
+            api_url="http://127.0.0.1:9", node_name="n", cache_dir=str(tmp_path / "own")
+        )
+        assert conn.cache_dir == str(tmp_path / "own")
+        monkeypatch.delenv("MEROBOX_CACHE_DIR")
+        assert get_token_cache_dir() == str(tmp_path / "state" / "auth_cache")
+
+    def test_connection_cache_dir(self, tmp_path):
+        """cache_dir keeps one connection's token files elsewhere."""
+        conn = create_connection(
+            api_url="http://127.0.0.1:9", node_name="n", cache_dir=str(tmp_path)
+        )
+        assert conn.cache_dir == str(tmp_path)
+        with pytest.raises(ValueError):
+            create_connection(
+                api_url="http://127.0.0.1:9", anonymous=True, cache_dir=str(tmp_path)
+            )
+        with pytest.raises(ValueError):
+            create_connection(
+                api_url="http://127.0.0.1:9", storage="memory", cache_dir=str(tmp_path)
+            )
+
+    def test_migrate_token_cache(self, tmp_path):
+        """Existing token files move once; names already present stay."""
+        source, destination = tmp_path / "old", tmp_path / "new"
+        source.mkdir()
+        (source / "node-abc.json").write_text("{}")
+        moved = migrate_token_cache(source=str(source), destination=str(destination))
+        assert moved == ["node-abc.json"]
+        assert (destination / "node-abc.json").exists()
+        assert migrate_token_cache(source=str(source), destination=str(destination)) == []
 
     def test_get_token_cache_path_returns_string(self):
         """Test that get_token_cache_path returns a string path."""
@@ -73,6 +138,15 @@ class TestFilenameDerivationStability:
         paths = {path1, path2, path3}
         assert len(paths) == 3, "Different inputs should produce different outputs"
 
+    def test_users_of_same_node_different_outputs(self):
+        """Test that each user of a node gets its own token cache path."""
+        alice = get_token_cache_path("shared-node", user="alice")
+        bob = get_token_cache_path("shared-node", user="bob")
+        node_only = get_token_cache_path("shared-node")
+
+        assert len({alice, bob, node_only}) == 3
+        assert get_token_cache_path("shared-node", user=None) == node_only
+
     def test_url_like_node_names(self):
         """Test that URL-like node names work and are stable."""
         node_name = "https://my-node.example.com:8080/api"
@@ -241,6 +315,348 @@ class TestTokenCacheIntegration:
             assert not os.path.exists(test_path)
 
 
+class DictStorage(ClientStorage):
+    """In-memory ClientStorage recording the keys it is asked for."""
+
+    def __init__(self):
+        super().__init__()
+        self.tokens = {}
+        self.loads = []
+
+    def save_tokens(self, key, tokens):
+        self.tokens[key] = tokens
+
+    def load_tokens(self, key):
+        self.loads.append(key)
+        return self.tokens.get(key)
+
+    def remove_tokens(self, key):
+        self.tokens.pop(key, None)
+
+
+class TestPythonStorage:
+    """Tests for Python-defined token storage backends."""
+
+    def test_client_reads_tokens_from_python_storage(self):
+        """Token lookups go to the Python object instead of the cache dir."""
+        storage = DictStorage()
+        storage.tokens["py-storage-node/alice"] = {
+            "access_token": "access",
+            "refresh_token": None,
+            "expires_at": None,
+        }
+        connection = create_connection(
+            "http://127.0.0.1:9", node_name="py-storage-node", storage=storage
+        )
+        create_client(connection, user="alice")
+        assert connection.storage_backend == "python"
+        assert storage.loads == ["py-storage-node/alice"]
+        assert not os.path.exists(get_token_cache_path("py-storage-node"))
+
+    def test_keyring_storage_selectable(self):
+        """storage="keyring" uses the keychain, or files when there is none."""
+        connection = create_connection("http://127.0.0.1:9", storage="keyring")
+        assert connection.storage_backend in ("keyring", "file")
+        assert create_connection("http://127.0.0.1:9").storage_backend == "file"
+        with pytest.raises(ValueError):
+            create_connection("http://127.0.0.1:9", storage="vault")
+
+    def test_encrypted_storage_selectable(self):
+        """storage="encrypted" takes an optional passphrase."""
+        connection = create_connection(
+            "http://127.0.0.1:9", storage="encrypted", passphrase="s3cret"
+        )
+        assert connection.storage_backend == "encrypted"
+        with pytest.raises(ValueError):
+            create_connection("http://127.0.0.1:9", passphrase="s3cret")
+
+    def test_encrypted_machine_key_stays_out_of_cache_dir(self, tmp_path):
+        """Without a passphrase the key goes to the keychain, never the cache."""
+        try:
+            connection = create_connection(
+                "http://127.0.0.1:9", storage="encrypted", cache_dir=str(tmp_path)
+            )
+        except RuntimeError as e:
+            assert "pass a passphrase" in str(e)
+        else:
+            assert connection.storage_backend == "encrypted"
+        assert not (tmp_path / ".token_key").exists()
+
+    def test_memory_storage_shared_between_connections(self):
+        """A MemoryStorage backs connections without touching the disk."""
+        memory = MemoryStorage()
+        memory.save_tokens(
+            "memory-node",
+            {"access_token": "access", "refresh_token": None, "expires_at": None},
+        )
+        connection = create_connection(
+            "http://127.0.0.1:9", node_name="memory-node", storage=memory
+        )
+        create_client(connection)
+        assert connection.storage_backend == "memory"
+        assert memory.keys() == ["memory-node"]
+        assert memory.load_tokens("missing") is None
+
+        memory.remove_tokens("memory-node")
+        assert len(memory) == 0
+        assert not os.path.exists(get_token_cache_path("memory-node"))
+        assert (
+            create_connection("http://127.0.0.1:9", storage="memory").storage_backend
+            == "memory"
+        )
+
+    def test_namespaces_isolate_tenants(self):
+        """Namespaced clients pass their tenant to storage and purge alone."""
+
+        class TenantStorage(DictStorage):
+            def save_tokens(self, namespace, key, tokens):
+                self.tokens[(namespace, key)] = tokens
+
+            def remove_tokens(self, namespace, key):
+                self.tokens.pop((namespace, key), None)
+
+            def load_tokens(self, namespace, key):
+                self.loads.append((namespace, key))
+                return self.tokens.get((namespace, key))
+
+            def purge_namespace(self, namespace):
+                keys = [key for key in self.tokens if key[0] == namespace]
+                for key in keys:
+                    del self.tokens[key]
+                return len(keys)
+
+        storage = TenantStorage()
+        storage.tokens[("acme", "tenant-node/alice")] = {
+            "access_token": "access",
+            "refresh_token": None,
+            "expires_at": None,
+        }
+        connection = create_connection(
+            "http://127.0.0.1:9", node_name="tenant-node", storage=storage
+        )
+        client = create_client(connection, namespace="acme", user="alice")
+        assert client.namespace == "acme"
+        assert storage.loads == [("acme", "tenant-node/alice")]
+        assert connection.purge_namespace("globex") == 0
+        assert connection.purge_namespace("acme") == 1
+        with pytest.raises(ValueError):
+            create_client(connection, namespace="../acme")
+
+        memory = MemoryStorage()
+        memory.save_tokens(
+            "acme::node",
+            {"access_token": "access", "refresh_token": None, "expires_at": None},
+        )
+        assert memory.purge_namespace("acme") == 1
+        assert len(memory) == 0
+        path = get_token_cache_path("node", "alice", namespace="acme")
+        assert os.path.join("namespaces", "acme") in path
+
+    def test_storage_must_implement_methods(self):
+        """Objects without the storage methods are rejected up front."""
+        with pytest.raises(TypeError):
+            create_connection("http://127.0.0.1:9", storage=object())
+
+    def test_base_class_methods_are_abstract(self):
+        """The base class raises until its methods are overridden."""
+        with pytest.raises(NotImplementedError):
+            ClientStorage().load_tokens("node")
+
+
+class TestStorageDoctor:
+    """Tests for the storage_doctor() layout check."""
+
+    def test_report_without_repair(self):
+        """A dry run reports the layout version and issues without changes."""
+        report = storage_doctor(repair=False)
+        assert report["currentVersion"] >= 1
+        assert report["migrated"] == []
+        for issue in report["issues"]:
+            assert issue["repaired"] is False
+
+
+class TestTokenBundles:
+    """Tests for export_tokens/import_tokens and the tokens CLI."""
+
+    TOKENS = {"access_token": "access", "refresh_token": "refresh", "expires_at": None}
+
+    def _connection(self, memory, node="bundle-node"):
+        return create_connection("http://127.0.0.1:9", node_name=node, storage=memory)
+
+    def test_roundtrip_redacts_refresh_by_default(self):
+        """Bundles carry access tokens; refresh tokens only on request."""
+        source = MemoryStorage()
+        source.save_tokens("bundle-node", self.TOKENS)
+        bundle = export_tokens("bundle-node", connection=self._connection(source))
+        assert "refresh_token" not in json.loads(bundle)["tokens"]["bundle-node"]
+
+        target = MemoryStorage()
+        assert import_tokens(bundle, connection=self._connection(target)) == ["bundle-node"]
+        assert target.load_tokens("bundle-node")["access_token"] == "access"
+
+        bundle = export_tokens(
+            ["bundle-node"], include_refresh=True, connection=self._connection(source)
+        )
+        assert json.loads(bundle)["tokens"]["bundle-node"]["refresh_token"] == "refresh"
+
+    def test_encrypted_bundle(self):
+        """A passphrase seals the tokens and is needed to import them."""
+        source = MemoryStorage()
+        source.save_tokens("bundle-node", self.TOKENS)
+        bundle = export_tokens(
+            "bundle-node", passphrase="s3cret", connection=self._connection(source)
+        )
+        assert "access" not in json.loads(bundle).get("tokens", {})
+        target = self._connection(MemoryStorage())
+        with pytest.raises(ValueError):
+            import_tokens(bundle, connection=target)
+        with pytest.raises(ValueError):
+            import_tokens(bundle, passphrase="wrong", connection=target)
+        assert import_tokens(bundle, passphrase="s3cret", connection=target) == [
+            "bundle-node"
+        ]
+
+    def test_missing_node_raises(self):
+        with pytest.raises(ValueError):
+            export_tokens("missing", connection=self._connection(MemoryStorage()))
+
+    def test_export_every_cached_node_to_file(self, tmp_path, monkeypatch):
+        """Without node names, every cached node goes into one sealed file."""
+        monkeypatch.setenv("MEROBOX_CACHE_DIR", str(tmp_path / "cache"))
+        for node in ("node-a", "node-b"):
+            source = MemoryStorage()
+            source.save_tokens(node, self.TOKENS)
+            bundle = export_tokens(node, connection=self._connection(source, node))
+            import_tokens(bundle)
+
+        bundle_file = tmp_path / "all.json"
+        export_tokens(passphrase="s3cret", path=bundle_file)
+        assert json.loads(bundle_file.read_text())["nodes"] == ["node-a", "node-b"]
+        if os.name == "posix":
+            assert bundle_file.stat().st_mode & 0o777 == 0o600
+
+        target = MemoryStorage()
+        imported = import_tokens(
+            path=bundle_file, passphrase="s3cret", connection=self._connection(target)
+        )
+        assert imported == ["node-a", "node-b"]
+        assert target.load_tokens("node-b")["access_token"] == "access"
+        with pytest.raises(ValueError):
+            export_tokens(connection=self._connection(MemoryStorage()))
+
+    def test_cli_import_into_token_cache(self, tmp_path, monkeypatch):
+        """`tokens import` saves a bundle file into the token cache."""
+        from calimero.cli import main
+
+        monkeypatch.setenv("MEROBOX_CACHE_DIR", str(tmp_path / "cache"))
+        source = MemoryStorage()
+        source.save_tokens("bundle-node", self.TOKENS)
+        bundle_file = tmp_path / "bundle.json"
+        bundle_file.write_text(
+            export_tokens("bundle-node", connection=self._connection(source))
+        )
+        main(["--output", "json", "tokens", "import", str(bundle_file)])
+        assert os.path.exists(get_token_cache_path("bundle-node"))
+
+
+class TestCliCredentials:
+    """Tests for import_cli_credentials/export_cli_credentials."""
+
+    MEROCTL = """active_node = "cli-node"
+
+[nodes.cli-node.Remote]
+url = "http://localhost:2428"
+
+[nodes.cli-node.Remote.jwt_tokens]
+access_token = "cli-access"
+refresh_token = "cli-refresh"
+"""
+
+    def _connection(self, memory):
+        return create_connection(
+            "http://127.0.0.1:9", node_name="cli-node", storage=memory
+        )
+
+    def test_import_from_meroctl_config(self, tmp_path):
+        """Tokens in the meroctl config land in storage, once."""
+        config = tmp_path / "config.toml"
+        config.write_text(self.MEROCTL)
+        storage = MemoryStorage()
+        connection = self._connection(storage)
+
+        result = import_cli_credentials(
+            "cli-node", source=config, connection=connection
+        )
+        assert result["imported"] and result["refreshToken"]
+        assert result["source"] == str(config)
+        assert storage.load_tokens("cli-node")["access_token"] == "cli-access"
+
+        storage.save_tokens("cli-node", {"access_token": "newer"})
+        again = import_cli_credentials("cli-node", source=config, connection=connection)
+        assert again["imported"] is False
+        assert storage.load_tokens("cli-node")["access_token"] == "newer"
+        with pytest.raises(ValueError):
+            import_cli_credentials("other-node", source=config, connection=connection)
+
+    def test_export_updates_record_in_place(self, tmp_path):
+        """Write-back replaces the node's tokens and keeps its settings."""
+        config = tmp_path / "config.toml"
+        config.write_text(self.MEROCTL)
+        storage = MemoryStorage()
+        storage.save_tokens("cli-node", {"access_token": "refreshed"})
+
+        written = export_cli_credentials(
+            "cli-node", destination=config, connection=self._connection(storage)
+        )
+        assert str(written) == str(config)
+        text = config.read_text()
+        assert "refreshed" in text and "cli-refresh" not in text
+        assert "http://localhost:2428" in text
+
+        bare = tmp_path / "bare.toml"
+        bare.write_text('[nodes.cli-node.Remote]\nurl = "http://localhost:2428"\n')
+        with pytest.raises(ValueError):
+            export_cli_credentials(
+                "cli-node", destination=bare, connection=self._connection(storage)
+            )
+
+
+class TestTokenCache:
+    """Tests for the TokenCache inspection API."""
+
+    def _write(self, cache_dir, node, expires_at, refresh_token=None):
+        path = cache_dir / os.path.basename(get_token_cache_path(node))
+        path.write_text(
+            json.dumps(
+                {
+                    "access_token": "access",
+                    "refresh_token": refresh_token,
+                    "expires_at": expires_at,
+                }
+            )
+        )
+
+    def test_list_expiry_purge_and_clear(self, tmp_path):
+        """Expired, unrefreshable tokens are purged; clear_all removes all."""
+        self._write(tmp_path, "stale-node", 10)
+        self._write(tmp_path, "refreshable-node", 10, refresh_token="refresh")
+        self._write(tmp_path, "live-node", 4102444800)
+        cache = TokenCache(cache_dir=str(tmp_path))
+
+        assert cache.list_nodes() == ["live-node", "refreshable-node", "stale-node"]
+        assert cache.get_expiry("live-node").year == 2100
+        assert cache.get_expiry("missing-node") is None
+
+        purged = cache.purge_expired()
+        assert len(purged) == 1 and purged[0].startswith("stale-node-")
+        assert cache.list_nodes() == ["live-node", "refreshable-node"]
+        assert cache.clear_all() == 2
+        assert cache.list_nodes() == []
+
+    def test_defaults_to_token_cache_dir(self):
+        assert TokenCache().cache_dir == get_token_cache_dir()
+
+
 class TestNodeNameBestPractices:
     """Tests demonstrating node_name best practices."""
 